@@ -0,0 +1,160 @@
+//! 命令行无界面操作
+//!
+//! 支持 `--export-config <path>` 与 `--import-config <path>`（也可写作 `--export-config=<path>`），
+//! 在不显示窗口的情况下导出或导入设置（config/ 与 profiles/，格式与设置备份相同），
+//! 便于用户在自己的备份工具中脚本化备份 MXU 配置。导入前会先自动备份当前设置
+
+use std::path::PathBuf;
+
+use crate::commands;
+
+/// 无界面命令
+enum HeadlessCommand {
+    ExportConfig(PathBuf),
+    ImportConfig(PathBuf),
+}
+
+/// 从命令行参数中解析无界面命令
+fn parse_args() -> Result<Option<HeadlessCommand>, String> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+            None => (arg.clone(), None),
+        };
+        let make: fn(PathBuf) -> HeadlessCommand = match flag.as_str() {
+            "--export-config" => HeadlessCommand::ExportConfig,
+            "--import-config" => HeadlessCommand::ImportConfig,
+            _ => continue,
+        };
+        let value = inline_value
+            .or_else(|| args.next())
+            .filter(|v| !v.is_empty())
+            .ok_or_else(|| format!("{} 需要指定文件路径", flag))?;
+        return Ok(Some(make(PathBuf::from(value))));
+    }
+    Ok(None)
+}
+
+/// 是否为无界面调用（main 中用于跳过 WebView 检查与提权）
+pub fn is_headless_invocation() -> bool {
+    std::env::args()
+        .skip(1)
+        .any(|arg| arg.starts_with("--export-config") || arg.starts_with("--import-config"))
+}
+
+/// 通过 mxu:// 链接启动且开启单实例时，把链接转发给已运行的实例
+/// （main 中在提权前调用，避免每次点击链接都弹出 UAC）。
+/// 与单实例插件使用相同的窗口与 WM_COPYDATA 格式；返回 true 表示已运行的实例确认收到，调用方应直接退出。
+/// 没有运行中的实例（冷启动）或转发失败时返回 false，本进程就是处理链接的主实例，仍需照常提权
+#[cfg(windows)]
+pub fn forward_deep_link_to_running_instance() -> bool {
+    use windows::core::PCWSTR;
+    use windows::Win32::Foundation::{LPARAM, WPARAM};
+    use windows::Win32::System::DataExchange::COPYDATASTRUCT;
+    use windows::Win32::UI::WindowsAndMessaging::{FindWindowW, SendMessageW, WM_COPYDATA};
+
+    /// 与 tauri.conf.json 中的 identifier 保持一致
+    const APP_IDENTIFIER: &str = "com.misteo.mxu";
+    /// 单实例插件识别转发数据的标记
+    const SINGLE_INSTANCE_DATA: usize = 1542;
+
+    let is_deep_link = std::env::args()
+        .skip(1)
+        .any(|arg| arg.to_ascii_lowercase().starts_with("mxu:"));
+    if !is_deep_link || !crate::commands::deep_link::single_instance_enabled() {
+        return false;
+    }
+
+    fn to_wide(s: &str) -> Vec<u16> {
+        s.encode_utf16().chain(Some(0)).collect()
+    }
+    let class_name = to_wide(&format!("{}-sic", APP_IDENTIFIER));
+    let window_name = to_wide(&format!("{}-siw", APP_IDENTIFIER));
+
+    unsafe {
+        let Ok(hwnd) = FindWindowW(
+            PCWSTR::from_raw(class_name.as_ptr()),
+            PCWSTR::from_raw(window_name.as_ptr()),
+        ) else {
+            return false;
+        };
+
+        let cwd = std::env::current_dir().unwrap_or_default();
+        let args = std::env::args().collect::<Vec<String>>().join("|");
+        let data = format!("{}|{}\0", cwd.to_string_lossy(), args);
+        let cds = COPYDATASTRUCT {
+            dwData: SINGLE_INSTANCE_DATA,
+            cbData: data.len() as u32,
+            lpData: data.as_ptr() as *mut _,
+        };
+        // 插件处理后返回 1；实例正在退出或消息被拦截（如权限级别不同）时返回 0
+        SendMessageW(
+            hwnd,
+            WM_COPYDATA,
+            WPARAM(0),
+            LPARAM(&cds as *const _ as isize),
+        )
+        .0 != 0
+    }
+}
+
+/// Windows release 版本为 GUI 子系统，附加到父进程控制台以便输出结果
+#[cfg(windows)]
+fn attach_parent_console() {
+    use windows::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+    unsafe {
+        let _ = AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+fn execute(command: HeadlessCommand) -> Result<String, String> {
+    match command {
+        HeadlessCommand::ExportConfig(path) => {
+            if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                std::fs::create_dir_all(parent)
+                    .map_err(|e| format!("无法创建目录 [{}]: {}", parent.display(), e))?;
+            }
+            let count = commands::backup::write_backup_zip(&path)?;
+            Ok(format!("已导出 {} 个文件到 {}", count, path.display()))
+        }
+        HeadlessCommand::ImportConfig(path) => {
+            commands::guest_mode::init_guest_mode();
+            commands::guest_mode::ensure_not_guest()?;
+            if !path.is_file() {
+                return Err(format!("文件不存在: {}", path.display()));
+            }
+            commands::backup::create_backup(Some("pre-import"), commands::backup::DEFAULT_KEEP)?;
+            let count = commands::backup::restore_backup_zip(&path)?;
+            Ok(format!("已从 {} 导入 {} 个文件", path.display(), count))
+        }
+    }
+}
+
+/// 处理无界面命令；返回 Some(退出码) 表示已处理，调用方应直接退出
+pub fn run_headless_command() -> Option<i32> {
+    let command = match parse_args() {
+        Ok(Some(command)) => command,
+        Ok(None) => return None,
+        Err(e) => {
+            #[cfg(windows)]
+            attach_parent_console();
+            eprintln!("{}", e);
+            return Some(2);
+        }
+    };
+
+    #[cfg(windows)]
+    attach_parent_console();
+
+    match execute(command) {
+        Ok(message) => {
+            println!("{}", message);
+            Some(0)
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            Some(1)
+        }
+    }
+}
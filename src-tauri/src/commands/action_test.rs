@@ -0,0 +1,26 @@
+//! MXU 内置动作测试调用
+//!
+//! 在 pipeline 之外调用 MXU_WEBHOOK / MXU_NOTIFY / MXU_LAUNCH 等内置动作，
+//! 用于在长时间运行前验证参数；有副作用的动作默认只做 dry-run（见 mxu_actions::test_action）
+
+use super::error::MxuError;
+use super::i18n::ErrorCode;
+use super::types::ActionTestResult;
+
+/// 测试调用内置动作
+#[tauri::command]
+pub async fn mxu_action_test(
+    name: String,
+    param_json: String,
+    dry_run: Option<bool>,
+) -> Result<ActionTestResult, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    if serde_json::from_str::<serde_json::Value>(&param_json).is_err() {
+        return Err(MxuError::new(ErrorCode::InvalidJson));
+    }
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        crate::mxu_actions::test_action(&name, &param_json, dry_run)
+    })
+    .await
+    .map_err(|e| e.to_string())??)
+}
@@ -0,0 +1,191 @@
+//! 自适应阈值重试
+//!
+//! 按节点统计识别得分分布（config/recognition_stats.json）。开启后，若任务失败时
+//! 某节点的得分仅略低于其历史最低命中分，且此类勉强失败已累计多次，
+//! 则以小幅放宽的阈值重试一次该任务（放宽幅度有上限，写入日志并发送 threshold-relaxed 事件）
+
+use log::{info, warn};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use maa_framework::tasker::Tasker;
+use tauri::{AppHandle, Emitter};
+
+use super::error::MxuError;
+use super::i18n::ErrorCode;
+use super::recognition::{fetch_recognition_detail, records_since};
+use super::types::{AdaptiveThresholdConfig, NodeScoreStats, ThresholdRelaxation};
+use super::utils::{get_app_data_dir, save_json_config};
+
+/// 支持 threshold 字段的识别算法
+const SUPPORTED_ALGORITHMS: &[&str] = &["TemplateMatch", "OCR", "NeuralNetworkDetect"];
+
+/// 每个节点保留的失败得分数量
+const MAX_MISS_SCORES: usize = 20;
+
+/// 得分统计读写锁
+static STATS_LOCK: Mutex<()> = Mutex::new(());
+
+fn config_path() -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?
+        .join("config")
+        .join("adaptive_threshold.json"))
+}
+
+fn stats_path() -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?
+        .join("config")
+        .join("recognition_stats.json"))
+}
+
+fn read_json<T: serde::de::DeserializeOwned + Default>(path: Result<PathBuf, String>) -> T {
+    path.ok()
+        .and_then(|p| std::fs::read_to_string(p).ok())
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn load_config() -> AdaptiveThresholdConfig {
+    read_json(config_path())
+}
+
+fn load_stats() -> BTreeMap<String, NodeScoreStats> {
+    read_json(stats_path())
+}
+
+/// 统计任务执行期间的识别得分；任务失败且满足条件时返回放宽阈值的 pipeline 覆盖
+///
+/// `since`: 本次尝试开始的时间（Unix 毫秒）
+pub fn record_and_plan(
+    app: &AppHandle,
+    tasker: &Tasker,
+    since: i64,
+    succeeded: bool,
+) -> Option<serde_json::Value> {
+    let config = load_config();
+    if !config.enabled {
+        return None;
+    }
+    let _guard = STATS_LOCK.lock().ok()?;
+    let mut stats = load_stats();
+
+    // 每个失败节点只保留最高得分
+    let mut misses: BTreeMap<String, (f64, String)> = BTreeMap::new();
+    for record in records_since(since) {
+        let Ok(detail) = fetch_recognition_detail(tasker, record.reco_id, false) else {
+            continue;
+        };
+        if !SUPPORTED_ALGORITHMS.contains(&detail.algorithm.as_str()) {
+            continue;
+        }
+        let Some(score) = detail.score else {
+            continue;
+        };
+        let entry = stats.entry(detail.node_name.clone()).or_default();
+        if detail.hit {
+            entry.hits += 1;
+            entry.min_hit_score = Some(entry.min_hit_score.map_or(score, |s| s.min(score)));
+        } else {
+            entry.misses += 1;
+            entry.recent_miss_scores.push(score);
+            let overflow = entry
+                .recent_miss_scores
+                .len()
+                .saturating_sub(MAX_MISS_SCORES);
+            entry.recent_miss_scores.drain(..overflow);
+            let best = misses
+                .entry(detail.node_name)
+                .or_insert((score, detail.algorithm));
+            best.0 = best.0.max(score);
+        }
+    }
+
+    let mut relaxations = Vec::new();
+    if !succeeded {
+        for (node_name, (score, algorithm)) in misses {
+            let Some(entry) = stats.get_mut(&node_name) else {
+                continue;
+            };
+            let Some(baseline) = entry.min_hit_score else {
+                continue;
+            };
+            // 仅处理略低于历史最低命中分的失败
+            if score >= baseline || baseline - score > config.margin {
+                continue;
+            }
+            entry.near_misses += 1;
+            if entry.near_misses < config.min_near_misses {
+                continue;
+            }
+            let threshold = score.max(baseline - config.max_relax);
+            entry.relaxed += 1;
+            relaxations.push(ThresholdRelaxation {
+                node_name,
+                algorithm,
+                score,
+                baseline,
+                threshold,
+            });
+        }
+    }
+
+    if let Err(e) = stats_path().and_then(|p| Ok(save_json_config(&p, &stats)?)) {
+        warn!("[adaptive_threshold] Failed to save stats: {}", e);
+    }
+    if relaxations.is_empty() {
+        return None;
+    }
+
+    let mut patch = serde_json::Map::new();
+    for relax in &relaxations {
+        warn!(
+            "[adaptive_threshold] Relaxing {} ({}) threshold to {:.3} (score {:.3}, baseline {:.3})",
+            relax.node_name, relax.algorithm, relax.threshold, relax.score, relax.baseline
+        );
+        patch.insert(
+            relax.node_name.clone(),
+            serde_json::json!({ "threshold": relax.threshold }),
+        );
+    }
+    if let Err(e) = app.emit("threshold-relaxed", &relaxations) {
+        log::error!("Failed to emit threshold-relaxed: {}", e);
+    }
+    Some(serde_json::Value::Object(patch))
+}
+
+/// 获取自适应阈值配置
+#[tauri::command]
+pub fn adaptive_threshold_get_config() -> AdaptiveThresholdConfig {
+    load_config()
+}
+
+/// 设置自适应阈值配置
+#[tauri::command]
+pub fn adaptive_threshold_set_config(config: AdaptiveThresholdConfig) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    if !(0.0..=0.2).contains(&config.max_relax) || !(0.0..=0.2).contains(&config.margin) {
+        return Err(MxuError::with_args(ErrorCode::ValueOutOfRange, [0.0, 0.2]));
+    }
+    save_json_config(&config_path()?, &config)?;
+    info!("[adaptive_threshold] Config updated: {:?}", config);
+    Ok(())
+}
+
+/// 获取各节点的识别得分统计
+#[tauri::command]
+pub fn adaptive_threshold_stats() -> BTreeMap<String, NodeScoreStats> {
+    load_stats()
+}
+
+/// 清空识别得分统计
+#[tauri::command]
+pub fn adaptive_threshold_reset_stats() -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    let _guard = STATS_LOCK.lock().map_err(|e| e.to_string())?;
+    let path = stats_path()?;
+    if path.exists() {
+        std::fs::remove_file(&path).map_err(|e| format!("无法删除得分统计: {}", e))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,345 @@
+//! ADB 可执行文件管理
+//!
+//! 查找并校验可用的 adb（MXU 自带的 platform-tools、PATH、设备搜索结果中模拟器自带的 adb），
+//! 可从 Google 下载 platform-tools，重启 adb server，并检测多个不同版本的 adb 互相抢占 server
+//! （表现为 “adb server version doesn't match this client” 后反复重启、设备时有时无）
+
+use log::{info, warn};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+use tauri::{AppHandle, State};
+
+use super::error::MxuError;
+use super::i18n::ErrorCode;
+use super::types::{AdbBinaryInfo, AdbConflictReport, AdbServerProcess, MaaState};
+use super::update::extract_zip;
+use super::utils::{get_app_data_dir, get_exe_directory};
+
+/// MXU 自带 platform-tools 的目录（相对于 exe 目录）
+const PLATFORM_TOOLS_DIR: &str = "platform-tools";
+
+fn adb_file_name() -> &'static str {
+    if cfg!(windows) {
+        "adb.exe"
+    } else {
+        "adb"
+    }
+}
+
+/// 当前平台的 platform-tools 下载地址
+fn platform_tools_url() -> &'static str {
+    if cfg!(windows) {
+        "https://dl.google.com/android/repository/platform-tools-latest-windows.zip"
+    } else if cfg!(target_os = "macos") {
+        "https://dl.google.com/android/repository/platform-tools-latest-darwin.zip"
+    } else {
+        "https://dl.google.com/android/repository/platform-tools-latest-linux.zip"
+    }
+}
+
+fn adb_command(adb_path: &Path) -> Command {
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        let mut cmd = Command::new(adb_path);
+        cmd.creation_flags(CREATE_NO_WINDOW);
+        cmd
+    }
+
+    #[cfg(not(windows))]
+    {
+        Command::new(adb_path)
+    }
+}
+
+/// 执行 adb 命令，返回标准输出
+fn run_adb(adb_path: &Path, args: &[&str]) -> Result<String, String> {
+    let output = adb_command(adb_path)
+        .args(args)
+        .output()
+        .map_err(|e| format!("执行 adb 失败 [{}]: {}", adb_path.display(), e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "adb {} 失败: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// 解析 `adb version` 输出中的版本号（如 "1.0.41"）
+fn parse_version(output: &str) -> Option<String> {
+    output
+        .lines()
+        .find_map(|l| l.trim().strip_prefix("Android Debug Bridge version "))
+        .map(|v| v.trim().to_string())
+}
+
+/// 运行 `adb version` 校验 adb 是否可用
+fn inspect(path: &Path, source: &str) -> AdbBinaryInfo {
+    let (version, error) = match run_adb(path, &["version"]) {
+        Ok(output) => match parse_version(&output) {
+            Some(version) => (Some(version), None),
+            None => (None, Some("无法识别 adb 版本输出".to_string())),
+        },
+        Err(e) => (None, Some(e)),
+    };
+    AdbBinaryInfo {
+        path: path.to_string_lossy().to_string(),
+        source: source.to_string(),
+        valid: version.is_some(),
+        version,
+        error,
+    }
+}
+
+/// 比较路径时忽略大小写差异（Windows）
+fn path_key(path: &Path) -> String {
+    let key = path.to_string_lossy().to_string();
+    if cfg!(windows) {
+        key.to_lowercase()
+    } else {
+        key
+    }
+}
+
+/// 查找所有候选 adb 并校验，顺序为 MXU 自带、PATH、设备搜索结果
+#[tauri::command]
+pub async fn adb_locate(state: State<'_, Arc<MaaState>>) -> Result<Vec<AdbBinaryInfo>, MxuError> {
+    info!("adb_locate called");
+
+    let mut candidates: Vec<(PathBuf, &str)> = Vec::new();
+    let bundled = get_exe_directory()?
+        .join(PLATFORM_TOOLS_DIR)
+        .join(adb_file_name());
+    if bundled.is_file() {
+        candidates.push((bundled, "bundled"));
+    }
+    if let Some(paths) = std::env::var_os("PATH") {
+        candidates.extend(
+            std::env::split_paths(&paths)
+                .map(|dir| dir.join(adb_file_name()))
+                .filter(|p| p.is_file())
+                .map(|p| (p, "path")),
+        );
+    }
+    if let Ok(devices) = state.cached_adb_devices.lock() {
+        candidates.extend(
+            devices
+                .iter()
+                .map(|d| PathBuf::from(&d.adb_path))
+                .filter(|p| p.is_file())
+                .map(|p| (p, "device")),
+        );
+    }
+
+    let mut seen = BTreeSet::new();
+    candidates.retain(|(path, _)| seen.insert(path_key(path)));
+
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        candidates
+            .iter()
+            .map(|(path, source)| inspect(path, source))
+            .collect::<Vec<_>>()
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    info!("[adb_manager] Located {} adb binaries", result.len());
+    Ok(result)
+}
+
+/// 下载 platform-tools 到 exe 目录下的 platform-tools，下载进度通过 download-progress 事件发送
+#[tauri::command]
+pub async fn adb_download_platform_tools(
+    app: AppHandle,
+    proxy_url: Option<String>,
+) -> Result<AdbBinaryInfo, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    info!("adb_download_platform_tools called");
+
+    let cache_dir = get_app_data_dir()?.join("cache").join("platform-tools");
+    if cache_dir.exists() {
+        let _ = std::fs::remove_dir_all(&cache_dir);
+    }
+    let download = super::download::download_file(
+        app,
+        platform_tools_url().to_string(),
+        cache_dir
+            .join("platform-tools.zip")
+            .to_string_lossy()
+            .to_string(),
+        None,
+        proxy_url,
+    )
+    .await?;
+
+    let extract_dir = cache_dir.join("extract");
+    extract_zip(
+        download.actual_save_path,
+        extract_dir.to_string_lossy().to_string(),
+    )?;
+    // 压缩包内为 platform-tools/ 目录
+    let extracted = extract_dir.join(PLATFORM_TOOLS_DIR);
+    let extracted_adb = extracted.join(adb_file_name());
+    if !extracted_adb.is_file() {
+        return Err(MxuError::with_args(
+            ErrorCode::FileNotInArchive,
+            [adb_file_name()],
+        ));
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = std::fs::set_permissions(&extracted_adb, std::fs::Permissions::from_mode(0o755));
+    }
+    let checked = tauri::async_runtime::spawn_blocking(move || inspect(&extracted_adb, "bundled"))
+        .await
+        .map_err(|e| e.to_string())?;
+    if !checked.valid {
+        return Err(MxuError::with_args(
+            ErrorCode::AdbNotRunnable,
+            [checked.error.unwrap_or_default()],
+        ));
+    }
+
+    // 替换旧版本；旧 adb 可能正作为 server 运行，先结束它
+    let target = get_exe_directory()?.join(PLATFORM_TOOLS_DIR);
+    let target_adb = target.join(adb_file_name());
+    if target_adb.is_file() {
+        let _ = run_adb(&target_adb, &["kill-server"]);
+    }
+    if target.exists() {
+        std::fs::remove_dir_all(&target).map_err(|e| {
+            format!(
+                "无法删除旧的 platform-tools（可能仍在使用）[{}]: {}",
+                target.display(),
+                e
+            )
+        })?;
+    }
+    std::fs::rename(&extracted, &target).or_else(|_| {
+        super::update::copy_dir_recursive(&extracted, &target)
+            .map_err(|e| format!("无法安装 platform-tools: {}", e))
+    })?;
+    let _ = std::fs::remove_dir_all(&cache_dir);
+
+    info!(
+        "[adb_manager] Installed platform-tools {} to {}",
+        checked.version.as_deref().unwrap_or_default(),
+        target.display()
+    );
+    Ok(AdbBinaryInfo {
+        path: target_adb.to_string_lossy().to_string(),
+        ..checked
+    })
+}
+
+/// 结束 adb server
+#[tauri::command]
+pub async fn adb_kill_server(adb_path: String) -> Result<(), MxuError> {
+    info!("adb_kill_server called, adb_path: {}", adb_path);
+    tauri::async_runtime::spawn_blocking(move || run_adb(Path::new(&adb_path), &["kill-server"]))
+        .await
+        .map_err(|e| e.to_string())??;
+    Ok(())
+}
+
+/// 重启 adb server（先结束再启动），返回 server 使用的 adb 版本
+#[tauri::command]
+pub async fn adb_restart_server(adb_path: String) -> Result<Option<String>, MxuError> {
+    info!("adb_restart_server called, adb_path: {}", adb_path);
+    let version = tauri::async_runtime::spawn_blocking(move || -> Result<_, String> {
+        let path = Path::new(&adb_path);
+        if let Err(e) = run_adb(path, &["kill-server"]) {
+            // server 未运行时 kill-server 也可能失败，不影响启动
+            warn!("[adb_manager] kill-server failed: {}", e);
+        }
+        run_adb(path, &["start-server"])?;
+        Ok(inspect(path, "server").version)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    Ok(version)
+}
+
+/// 检测 adb server 冲突：列出正在运行的 adb 进程及其版本，
+/// 存在多个不同版本（或与 adb_path 的版本不同）时标记冲突
+#[tauri::command]
+pub async fn adb_detect_conflicts(adb_path: Option<String>) -> Result<AdbConflictReport, MxuError> {
+    info!("adb_detect_conflicts called");
+
+    let report = tauri::async_runtime::spawn_blocking(move || {
+        let mut system = System::new();
+        system.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::nothing().with_exe(UpdateKind::OnlyIfNotSet),
+        );
+
+        let mut versions_by_path: Vec<(String, Option<String>)> = Vec::new();
+        let mut processes = Vec::new();
+        for (pid, process) in system.processes() {
+            let name = process.name().to_string_lossy().to_lowercase();
+            if name != "adb" && name != "adb.exe" {
+                continue;
+            }
+            let path = process.exe().map(Path::to_path_buf);
+            let version = path.as_ref().and_then(|p| {
+                let key = path_key(p);
+                if let Some((_, v)) = versions_by_path.iter().find(|(k, _)| *k == key) {
+                    return v.clone();
+                }
+                let v = inspect(p, "server").version;
+                versions_by_path.push((key, v.clone()));
+                v
+            });
+            processes.push(AdbServerProcess {
+                pid: pid.as_u32(),
+                path: path.map(|p| p.to_string_lossy().to_string()),
+                version,
+            });
+        }
+
+        let client_version = adb_path.and_then(|p| inspect(Path::new(&p), "client").version);
+        let versions: BTreeSet<&String> = processes
+            .iter()
+            .filter_map(|p| p.version.as_ref())
+            .chain(client_version.as_ref())
+            .collect();
+        let conflict = versions.len() > 1;
+        let message = conflict.then(|| {
+            format!(
+                "检测到多个版本的 adb（{}），它们会互相结束对方的 server，导致设备断开或无法找到。\
+                 请关闭其他使用 adb 的工具，或统一使用同一个 adb",
+                versions
+                    .iter()
+                    .map(|v| v.as_str())
+                    .collect::<Vec<_>>()
+                    .join("、")
+            )
+        });
+        AdbConflictReport {
+            processes,
+            client_version,
+            conflict,
+            message,
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    if report.conflict {
+        warn!(
+            "[adb_manager] adb version conflict: {} process(es)",
+            report.processes.len()
+        );
+    }
+    Ok(report)
+}
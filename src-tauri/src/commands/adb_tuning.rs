@@ -0,0 +1,311 @@
+//! ADB 截图与输入方式调优
+//!
+//! 列出 MaaFramework 支持的 ADB 截图方式（encode、raw、minicap、模拟器扩展等）与输入方式
+//! （adb shell、minitouch、maatouch、模拟器扩展），ADB 配置中的方式可直接使用名称（如 "Encode|RawWithGzip"）。
+//! benchmark_adb_methods 使用实例最近连接的设备，为每种方式单独创建控制器并测量延迟，推荐最快的方式
+
+use log::{info, warn};
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use maa_framework::controller::{AdbControllerBuilder, Controller};
+use tauri::State;
+
+use super::error::MxuError;
+use super::i18n::ErrorCode;
+use super::types::{
+    AdbMethodBenchmark, AdbMethodBenchmarkReport, AdbMethodList, ControllerConfig,
+    ControllerMethodOption, MaaState,
+};
+use super::utils::get_maafw_dir;
+use super::win32_capture::select_methods;
+
+/// 与 MaaFramework AdbScreencapMethod 对应的名称与位标志
+pub const SCREENCAP_METHODS: &[(&str, u64)] = &[
+    ("EncodeToFileAndPull", 1),
+    ("Encode", 1 << 1),
+    ("RawWithGzip", 1 << 2),
+    ("RawByNetcat", 1 << 3),
+    ("MinicapDirect", 1 << 4),
+    ("MinicapStream", 1 << 5),
+    ("EmulatorExtras", 1 << 6),
+];
+
+/// 与 MaaFramework AdbInputMethod 对应的名称与位标志
+pub const INPUT_METHODS: &[(&str, u64)] = &[
+    ("AdbShell", 1),
+    ("MinitouchAndAdbKey", 1 << 1),
+    ("Maatouch", 1 << 2),
+    ("EmulatorExtras", 1 << 3),
+];
+
+/// 测量截图时使用的输入方式（不发送输入，选最通用的即可）
+const BENCHMARK_INPUT_METHOD: u64 = 1;
+/// 测量输入时使用的截图方式
+const BENCHMARK_SCREENCAP_METHOD: u64 = 1 << 1;
+/// 测量输入时发送的键码（KEYCODE_UNKNOWN，设备上无任何效果）
+const NOOP_KEYCODE: i32 = 0;
+/// 默认测量轮数
+const DEFAULT_ROUNDS: u32 = 3;
+/// 连接超时
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+/// 单次操作超时
+const ACTION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// ADB 设备参数（不含截图与输入方式）
+#[derive(Clone)]
+struct AdbTarget {
+    adb_path: String,
+    address: String,
+    config: String,
+}
+
+/// 各实例最近连接的 ADB 设备
+static TARGETS: LazyLock<Mutex<HashMap<String, AdbTarget>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 记录实例连接的 ADB 设备（maa_connect_controller 时调用）
+pub fn note_controller(instance_id: &str, config: &ControllerConfig) {
+    let Ok(mut targets) = TARGETS.lock() else {
+        return;
+    };
+    match config {
+        ControllerConfig::Adb {
+            adb_path,
+            address,
+            config,
+            ..
+        } => {
+            targets.insert(
+                instance_id.to_string(),
+                AdbTarget {
+                    adb_path: adb_path.clone(),
+                    address: address.clone(),
+                    config: config.clone(),
+                },
+            );
+        }
+        _ => {
+            targets.remove(instance_id);
+        }
+    }
+}
+
+/// 将 ADB 配置中的方式解析为位标志：数字字符串，或以 | / , 分隔的方式名称
+pub fn parse_methods(table: &[(&str, u64)], value: &str) -> Result<u64, MxuError> {
+    if let Ok(bits) = value.trim().parse::<u64>() {
+        return Ok(bits);
+    }
+    value
+        .split(['|', ','])
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .try_fold(0, |bits, name| {
+            table
+                .iter()
+                .find(|(n, _)| n.eq_ignore_ascii_case(name))
+                .map(|(_, b)| bits | b)
+                .ok_or_else(|| MxuError::with_args(ErrorCode::UnknownControllerMethod, [name]))
+        })
+}
+
+/// 列出 MaaFramework 支持的 ADB 截图与输入方式
+#[tauri::command]
+pub fn list_adb_methods() -> AdbMethodList {
+    let options = |table: &[(&str, u64)]| {
+        table
+            .iter()
+            .map(|(name, value)| ControllerMethodOption {
+                name: name.to_string(),
+                value: *value,
+            })
+            .collect()
+    };
+    AdbMethodList {
+        screencap: options(SCREENCAP_METHODS),
+        input: options(INPUT_METHODS),
+    }
+}
+
+/// 等待控制器操作结束（通过回调中的 ctrl_id 匹配）
+fn wait_action(
+    events: &Receiver<(String, String)>,
+    id: i64,
+    timeout: Duration,
+) -> Result<(), String> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let (message, details) = events
+            .recv_timeout(remaining)
+            .map_err(|_| "操作超时".to_string())?;
+        let matches = serde_json::from_str::<serde_json::Value>(&details)
+            .ok()
+            .and_then(|d| d.get("ctrl_id").and_then(|v| v.as_i64()))
+            == Some(id);
+        if !matches {
+            continue;
+        }
+        match message.as_str() {
+            "Controller.Action.Succeeded" => return Ok(()),
+            "Controller.Action.Failed" => return Err("操作失败".to_string()),
+            _ => {}
+        }
+    }
+}
+
+/// 按指定方式创建控制器并连接，返回控制器、回调接收端与连接耗时
+fn connect(
+    target: &AdbTarget,
+    screencap: u64,
+    input: u64,
+) -> Result<(Controller, Receiver<(String, String)>, u64), String> {
+    let agent_path = get_maafw_dir()
+        .map(|p| p.join("MaaAgentBinary").to_string_lossy().to_string())
+        .unwrap_or_else(|_| "./MaaAgentBinary".to_string());
+    let controller = AdbControllerBuilder::new(&target.adb_path, &target.address)
+        .screencap_methods(
+            maa_framework::common::AdbScreencapMethod::from_bits_truncate(screencap).bits(),
+        )
+        .input_methods(maa_framework::common::AdbInputMethod::from_bits_truncate(input).bits())
+        .config(&target.config)
+        .agent_path(&agent_path)
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let (tx, rx) = mpsc::channel();
+    controller
+        .add_sink(move |msg, detail| {
+            let _ = tx.send((msg.to_string(), detail.to_string()));
+        })
+        .map_err(|e| e.to_string())?;
+    // 与正式连接保持一致的截图尺寸
+    let _ = controller.set_screenshot_target_short_side(720);
+
+    let start = Instant::now();
+    let id = controller.post_connection().map_err(|e| e.to_string())?;
+    wait_action(&rx, id, CONNECT_TIMEOUT).map_err(|e| format!("连接失败: {}", e))?;
+    Ok((controller, rx, start.elapsed().as_millis() as u64))
+}
+
+/// 重复执行操作并返回平均耗时（毫秒）
+fn measure(
+    events: &Receiver<(String, String)>,
+    rounds: u32,
+    post: impl Fn() -> Result<i64, String>,
+) -> Result<u64, String> {
+    let mut total = 0u64;
+    for _ in 0..rounds {
+        let start = Instant::now();
+        let id = post()?;
+        wait_action(events, id, ACTION_TIMEOUT)?;
+        total += start.elapsed().as_millis() as u64;
+    }
+    Ok(total / rounds as u64)
+}
+
+fn benchmark(
+    kind: &str,
+    name: &str,
+    value: u64,
+    result: Result<(u64, u64), String>,
+) -> AdbMethodBenchmark {
+    info!("[adb_tuning] {} {} -> {:?}", kind, name, result);
+    AdbMethodBenchmark {
+        kind: kind.to_string(),
+        method: name.to_string(),
+        value,
+        supported: result.is_ok(),
+        connect_ms: result.as_ref().ok().map(|r| r.0),
+        average_ms: result.as_ref().ok().map(|r| r.1),
+        error: result.err(),
+    }
+}
+
+/// 测量实例所连接 ADB 设备上各截图与输入方式的延迟，并推荐最快的方式
+///
+/// 每种方式单独创建控制器：截图方式测量 rounds 次截图的平均耗时；输入方式测量发送无效果按键
+/// （KEYCODE_UNKNOWN）的平均耗时，不会点击屏幕。`screencap_methods` / `input_methods` 为空时测量全部方式
+#[tauri::command]
+pub async fn benchmark_adb_methods(
+    state: State<'_, Arc<MaaState>>,
+    instance_id: String,
+    screencap_methods: Option<Vec<String>>,
+    input_methods: Option<Vec<String>>,
+    rounds: Option<u32>,
+) -> Result<AdbMethodBenchmarkReport, MxuError> {
+    info!("benchmark_adb_methods called, instance_id: {}", instance_id);
+    // 测量期间会占用设备，避免干扰正在运行的任务
+    super::update::ensure_idle(&state)?;
+
+    let target = TARGETS
+        .lock()
+        .map_err(|e| e.to_string())?
+        .get(&instance_id)
+        .cloned()
+        .ok_or_else(|| MxuError::new(ErrorCode::ControllerNotConnected))?;
+    let rounds = rounds.unwrap_or(DEFAULT_ROUNDS).clamp(1, 20);
+    let screencap_candidates = select_methods(SCREENCAP_METHODS, screencap_methods.as_ref());
+    let input_candidates = select_methods(INPUT_METHODS, input_methods.as_ref());
+
+    let report = tauri::async_runtime::spawn_blocking(move || {
+        let screencap: Vec<AdbMethodBenchmark> = screencap_candidates
+            .into_iter()
+            .map(|(name, value)| {
+                let result = connect(&target, value, BENCHMARK_INPUT_METHOD).and_then(
+                    |(controller, events, connect_ms)| {
+                        let average = measure(&events, rounds, || {
+                            controller.post_screencap().map_err(|e| e.to_string())
+                        })?;
+                        Ok((connect_ms, average))
+                    },
+                );
+                benchmark("screencap", name, value, result)
+            })
+            .collect();
+
+        let input: Vec<AdbMethodBenchmark> = input_candidates
+            .into_iter()
+            .map(|(name, value)| {
+                let result = connect(&target, BENCHMARK_SCREENCAP_METHOD, value).and_then(
+                    |(controller, events, connect_ms)| {
+                        let average = measure(&events, rounds, || {
+                            controller
+                                .post_click_key(NOOP_KEYCODE)
+                                .map_err(|e| e.to_string())
+                        })?;
+                        Ok((connect_ms, average))
+                    },
+                );
+                benchmark("input", name, value, result)
+            })
+            .collect();
+
+        let fastest = |items: &[AdbMethodBenchmark]| {
+            items
+                .iter()
+                .filter(|b| b.supported)
+                .min_by_key(|b| b.average_ms)
+                .map(|b| b.method.clone())
+        };
+        AdbMethodBenchmarkReport {
+            recommended_screencap: fastest(&screencap),
+            recommended_input: fastest(&input),
+            screencap,
+            input,
+            rounds,
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    if report.recommended_screencap.is_none() {
+        warn!(
+            "[adb_tuning] No screencap method worked for {}",
+            instance_id
+        );
+    }
+    Ok(report)
+}
@@ -0,0 +1,260 @@
+//! Agent 子进程隔离
+//!
+//! - 可选在独立工作目录（<数据目录>/agents/<实例>/<序号>）中运行 Agent，
+//!   参数中指向项目目录内文件的相对路径会转换为绝对路径，项目目录通过 MXU_PROJECT_DIR 传入
+//! - 可选清理继承的环境变量，只保留系统必需变量与白名单，并注入自定义变量
+//! - Windows 上每个 Agent 放入带 KILL_ON_JOB_CLOSE 的作业对象，其他平台放入独立进程组，
+//!   结束 Agent 时连同其派生的子进程一起结束（MXU 退出时作业对象关闭，子进程同样会被结束）
+
+use log::info;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+use std::sync::Mutex;
+
+use super::error::MxuError;
+use super::i18n::ErrorCode;
+use super::types::AgentSandboxSettings;
+use super::utils::{get_app_data_dir, save_json_config};
+
+/// 清理环境变量时始终保留的变量（运行时与解释器正常工作所需）
+const ESSENTIAL_ENV: &[&str] = &[
+    "PATH",
+    "PATHEXT",
+    "SYSTEMROOT",
+    "SYSTEMDRIVE",
+    "WINDIR",
+    "COMSPEC",
+    "TEMP",
+    "TMP",
+    "TMPDIR",
+    "HOME",
+    "USERPROFILE",
+    "APPDATA",
+    "LOCALAPPDATA",
+    "PROGRAMDATA",
+    "PROGRAMFILES",
+    "PROGRAMFILES(X86)",
+    "NUMBER_OF_PROCESSORS",
+    "PROCESSOR_ARCHITECTURE",
+    "OS",
+    "USER",
+    "USERNAME",
+    "LOGNAME",
+    "LANG",
+    "LC_ALL",
+    "LD_LIBRARY_PATH",
+    "DYLD_LIBRARY_PATH",
+    "XDG_RUNTIME_DIR",
+    "DISPLAY",
+    "WAYLAND_DISPLAY",
+];
+
+/// 各 Agent 进程（按 PID）对应的作业对象句柄
+#[cfg(windows)]
+static JOBS: std::sync::LazyLock<Mutex<std::collections::HashMap<u32, isize>>> =
+    std::sync::LazyLock::new(|| Mutex::new(std::collections::HashMap::new()));
+
+/// 配置读写锁
+static CONFIG_LOCK: Mutex<()> = Mutex::new(());
+
+fn config_path() -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?
+        .join("config")
+        .join("agent_sandbox.json"))
+}
+
+/// 读取隔离设置，文件不存在或无法解析时使用默认值（不隔离）
+pub fn load_settings() -> AgentSandboxSettings {
+    config_path()
+        .ok()
+        .and_then(|p| std::fs::read_to_string(p).ok())
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn save_settings(settings: &AgentSandboxSettings) -> Result<(), String> {
+    let path = config_path()?;
+    Ok(save_json_config(&path, settings)?)
+}
+
+fn is_kept(name: &str, settings: &AgentSandboxSettings) -> bool {
+    ESSENTIAL_ENV
+        .iter()
+        .copied()
+        .chain(settings.env_passthrough.iter().map(String::as_str))
+        .any(|kept| kept.eq_ignore_ascii_case(name))
+}
+
+/// 按隔离设置配置 Agent 命令：工作目录、参数与环境变量，返回实际工作目录。
+/// 须在设置其他环境变量之前调用（清理环境变量会移除之前设置的变量）
+pub fn prepare(
+    cmd: &mut Command,
+    settings: &AgentSandboxSettings,
+    instance_id: &str,
+    agent_index: usize,
+    project_dir: &Path,
+    args: &mut [String],
+) -> Result<PathBuf, String> {
+    if settings.scrub_env {
+        cmd.env_clear();
+        cmd.envs(
+            std::env::vars_os().filter(|(name, _)| is_kept(&name.to_string_lossy(), settings)),
+        );
+    }
+    cmd.envs(&settings.env);
+    cmd.env("MXU_PROJECT_DIR", project_dir);
+
+    // 独立进程组，结束时连同子进程一起结束
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+
+    if !settings.isolate_working_dir {
+        return Ok(project_dir.to_path_buf());
+    }
+
+    let safe_instance: String = instance_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let work_dir = get_app_data_dir()?
+        .join("agents")
+        .join(safe_instance)
+        .join(agent_index.to_string());
+    std::fs::create_dir_all(&work_dir)
+        .map_err(|e| format!("无法创建 Agent 工作目录 [{}]: {}", work_dir.display(), e))?;
+
+    // 相对路径参数原本相对于项目目录解析
+    for arg in args.iter_mut() {
+        let candidate = project_dir.join(arg.as_str());
+        if Path::new(arg.as_str()).is_relative() && candidate.exists() {
+            *arg = candidate.to_string_lossy().to_string();
+        }
+    }
+    Ok(work_dir)
+}
+
+/// 将已启动的 Agent 放入作业对象（Windows）
+pub fn attach(child: &Child) {
+    #[cfg(windows)]
+    {
+        use std::os::windows::io::AsRawHandle;
+        use windows::Win32::Foundation::HANDLE;
+
+        match job::create_for(HANDLE(child.as_raw_handle())) {
+            Ok(handle) => {
+                if let Ok(mut jobs) = JOBS.lock() {
+                    jobs.insert(child.id(), handle);
+                }
+            }
+            Err(e) => log::warn!(
+                "[agent_sandbox] Failed to assign agent {} to job object: {}",
+                child.id(),
+                e
+            ),
+        }
+    }
+    #[cfg(not(windows))]
+    let _ = child;
+}
+
+/// 结束 Agent 及其派生的全部子进程并回收（进程已退出时只清理残留的子进程）
+pub fn kill_tree(child: &mut Child) {
+    let pid = child.id();
+
+    #[cfg(windows)]
+    if let Some(handle) = JOBS.lock().ok().and_then(|mut jobs| jobs.remove(&pid)) {
+        job::terminate(handle);
+    }
+
+    #[cfg(unix)]
+    // SAFETY: 向以 Agent PID 为组 ID 的进程组发送信号，进程组不存在时返回 ESRCH
+    unsafe {
+        libc::kill(-(pid as libc::pid_t), libc::SIGKILL);
+    }
+
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+#[cfg(windows)]
+mod job {
+    use std::ffi::c_void;
+
+    use windows::core::PCWSTR;
+    use windows::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows::Win32::System::JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation,
+        SetInformationJobObject, TerminateJobObject, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+        JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+    };
+
+    /// 创建作业对象并加入进程，返回作业句柄
+    pub fn create_for(process: HANDLE) -> Result<isize, String> {
+        unsafe {
+            let job = CreateJobObjectW(None, PCWSTR::null()).map_err(|e| e.to_string())?;
+            let mut info = JOBOBJECT_EXTENDED_LIMIT_INFORMATION::default();
+            info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+            let result = SetInformationJobObject(
+                job,
+                JobObjectExtendedLimitInformation,
+                &info as *const _ as *const c_void,
+                std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+            )
+            .and_then(|_| AssignProcessToJobObject(job, process));
+            if let Err(e) = result {
+                let _ = CloseHandle(job);
+                return Err(e.to_string());
+            }
+            Ok(job.0 as isize)
+        }
+    }
+
+    /// 结束作业内全部进程并关闭句柄
+    pub fn terminate(handle: isize) {
+        unsafe {
+            let job = HANDLE(handle as *mut c_void);
+            let _ = TerminateJobObject(job, 1);
+            let _ = CloseHandle(job);
+        }
+    }
+}
+
+/// 获取 Agent 隔离设置
+#[tauri::command]
+pub fn agent_sandbox_get_settings() -> AgentSandboxSettings {
+    load_settings()
+}
+
+/// 保存 Agent 隔离设置（下次启动 Agent 时生效）
+#[tauri::command]
+pub fn agent_sandbox_set_settings(settings: AgentSandboxSettings) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    if let Some(name) = settings
+        .env
+        .keys()
+        .find(|k| k.is_empty() || k.contains(['=', '\0']))
+    {
+        return Err(MxuError::with_args(
+            ErrorCode::InvalidEnvVarName,
+            [format!("{:?}", name)],
+        ));
+    }
+    let _guard = CONFIG_LOCK.lock().map_err(|e| e.to_string())?;
+    save_settings(&settings)?;
+    info!(
+        "[agent_sandbox] Settings updated: isolate_working_dir={}, scrub_env={}, {} injected variable(s)",
+        settings.isolate_working_dir,
+        settings.scrub_env,
+        settings.env.len()
+    );
+    Ok(())
+}
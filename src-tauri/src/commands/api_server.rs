@@ -0,0 +1,520 @@
+//! 远程 API 服务
+//!
+//! 开启后在局域网端口（默认 18080）提供 HTTP 接口，供配对的移动端调用。
+//! 请求通过 `Authorization: Bearer <令牌>` 携带 remote_auth 签发的令牌，
+//! 每个请求都经 remote_auth::authorize 按白名单与权限范围鉴权并写入审计日志：
+//! - `POST /invoke/<命令>`：以 JSON 对象为参数调用白名单中的命令，返回命令结果
+//! - `GET /status`：节点状态（白名单命令名 status），供集群主实例查询可用性
+//! - `POST /dispatch`：接收集群主实例分发的配置档案并运行（白名单命令名 dispatch）
+//! - `POST /cluster/report`：集群节点回报运行结果，使用本轮分发时下发的回报密钥鉴权
+//!
+//! 服务只处理短连接的 JSON 请求，每个连接在独立线程中处理
+
+use log::{error, info, warn};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::error::MxuError;
+use super::i18n::ErrorCode;
+use super::types::{ApiServerSettings, ApiServerStatus, MaaState, StopMode};
+use super::utils::{get_app_data_dir, save_json_config};
+
+/// 请求体大小上限
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+/// 单个连接的读写超时
+const CONNECTION_TIMEOUT: Duration = Duration::from_secs(10);
+/// 监听线程检查停止标记的间隔
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// 同时处理的连接数上限，超出时直接关闭新连接，避免局域网主机在鉴权前耗尽线程
+const MAX_CONNECTIONS: usize = 32;
+
+/// 应用句柄（请求在服务线程中处理，需要访问实例状态与发送事件）
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+/// 运行中的服务
+struct Server {
+    port: u16,
+    stop_flag: Arc<AtomicBool>,
+    /// 监听线程，停止时等待其释放端口
+    thread: thread::JoinHandle<()>,
+}
+
+static SERVER: Mutex<Option<Server>> = Mutex::new(None);
+
+/// 最近一次启动失败的原因
+static LAST_ERROR: Mutex<Option<String>> = Mutex::new(None);
+
+/// 正在处理的连接数
+static ACTIVE_CONNECTIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// 占用一个连接名额，连接处理结束（线程退出）时释放
+struct ConnectionSlot;
+
+impl ConnectionSlot {
+    fn acquire() -> Option<Self> {
+        ACTIVE_CONNECTIONS
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+                (n < MAX_CONNECTIONS).then_some(n + 1)
+            })
+            .ok()
+            .map(|_| Self)
+    }
+}
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        ACTIVE_CONNECTIONS.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+fn config_path() -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?.join("config").join("api_server.json"))
+}
+
+fn load_settings() -> ApiServerSettings {
+    config_path()
+        .ok()
+        .and_then(|p| std::fs::read_to_string(p).ok())
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+/// 保存应用句柄，设置中已开启时启动服务（setup 中调用）
+pub fn init(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+    let settings = load_settings();
+    if settings.enabled {
+        if let Err(e) = start(settings.port) {
+            error!("[api_server] Failed to start: {}", e);
+        }
+    }
+}
+
+/// 服务是否正在运行
+pub fn is_running() -> bool {
+    running_port().is_some()
+}
+
+/// 正在运行的服务端口
+pub fn running_port() -> Option<u16> {
+    SERVER.lock().ok()?.as_ref().map(|server| server.port)
+}
+
+fn set_last_error(message: Option<String>) {
+    if let Ok(mut last) = LAST_ERROR.lock() {
+        *last = message;
+    }
+}
+
+/// 启动服务（已在同一端口运行时保持不变，否则先停止旧服务）
+fn start(port: u16) -> Result<(), String> {
+    if running_port() == Some(port) {
+        return Ok(());
+    }
+    stop();
+    let listener = TcpListener::bind(("0.0.0.0", port))
+        .and_then(|l| l.set_nonblocking(true).map(|_| l))
+        .map_err(|e| {
+            let message = format!("无法监听端口 {}: {}", port, e);
+            set_last_error(Some(message.clone()));
+            message
+        })?;
+    set_last_error(None);
+
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let thread_stop_flag = stop_flag.clone();
+    let thread = thread::spawn(move || {
+        let stop_flag = thread_stop_flag;
+        info!("[api_server] Listening on 0.0.0.0:{}", port);
+        while !stop_flag.load(Ordering::SeqCst) {
+            match listener.accept() {
+                Ok((stream, addr)) => {
+                    let Some(slot) = ConnectionSlot::acquire() else {
+                        warn!("[api_server] Too many connections, rejecting {}", addr);
+                        continue;
+                    };
+                    // 派发线程前设置读写超时，空闲连接不会一直占用名额
+                    if let Err(e) = stream
+                        .set_nonblocking(false)
+                        .and_then(|_| stream.set_read_timeout(Some(CONNECTION_TIMEOUT)))
+                        .and_then(|_| stream.set_write_timeout(Some(CONNECTION_TIMEOUT)))
+                    {
+                        warn!("[api_server] Failed to configure {}: {}", addr, e);
+                        continue;
+                    }
+                    thread::spawn(move || {
+                        let _slot = slot;
+                        if let Err(e) = handle_connection(stream) {
+                            warn!("[api_server] Connection from {} failed: {}", addr, e);
+                        }
+                    });
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                    thread::sleep(ACCEPT_POLL_INTERVAL);
+                }
+                Err(e) => {
+                    warn!("[api_server] Accept failed: {}", e);
+                    thread::sleep(ACCEPT_POLL_INTERVAL);
+                }
+            }
+        }
+        info!("[api_server] Stopped listening on port {}", port);
+    });
+    *SERVER.lock().map_err(|e| e.to_string())? = Some(Server {
+        port,
+        stop_flag,
+        thread,
+    });
+    Ok(())
+}
+
+/// 停止服务，等待监听线程退出以释放端口（之后可立即重新绑定）
+fn stop() {
+    if let Some(server) = SERVER.lock().ok().and_then(|mut s| s.take()) {
+        server.stop_flag.store(true, Ordering::SeqCst);
+        if server.thread.join().is_err() {
+            warn!("[api_server] Listener thread panicked");
+        }
+    }
+}
+
+// ============================================================================
+// HTTP 解析与响应
+// ============================================================================
+
+/// 解析后的请求
+pub(crate) struct Request {
+    pub method: String,
+    pub path: String,
+    /// 请求头（名称为小写）
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    /// Authorization 头中的 Bearer 令牌
+    pub fn bearer_token(&self) -> Option<&str> {
+        self.headers
+            .get("authorization")
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+    }
+
+    /// 将请求体解析为 JSON，空请求体视为空对象
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T, String> {
+        if self.body.iter().all(|b| b.is_ascii_whitespace()) {
+            return serde_json::from_value(json!({})).map_err(|e| format!("缺少请求参数: {}", e));
+        }
+        serde_json::from_slice(&self.body).map_err(|e| format!("无效的请求参数: {}", e))
+    }
+}
+
+/// 响应：(HTTP 状态码, JSON 内容)
+pub(crate) type Response = (u16, Value);
+
+fn read_request(stream: &TcpStream) -> Result<Request, String> {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader
+        .read_line(&mut line)
+        .map_err(|e| format!("读取请求失败: {}", e))?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next().ok_or("无效的请求行")?.to_uppercase();
+    let path = parts.next().ok_or("无效的请求行")?.to_string();
+
+    let mut headers = HashMap::new();
+    loop {
+        line.clear();
+        reader
+            .read_line(&mut line)
+            .map_err(|e| format!("读取请求头失败: {}", e))?;
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+        }
+    }
+
+    let length = headers
+        .get("content-length")
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(0);
+    if length > MAX_BODY_BYTES {
+        return Err(format!("请求体过大: {} 字节", length));
+    }
+    let mut body = vec![0u8; length];
+    reader
+        .read_exact(&mut body)
+        .map_err(|e| format!("读取请求体失败: {}", e))?;
+
+    Ok(Request {
+        method,
+        path,
+        headers,
+        body,
+    })
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        _ => "Internal Server Error",
+    }
+}
+
+fn write_response(mut stream: &TcpStream, (status, body): Response) -> Result<(), String> {
+    let body = body.to_string();
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason_phrase(status),
+        body.len(),
+        body
+    )
+    .and_then(|_| stream.flush())
+    .map_err(|e| format!("发送响应失败: {}", e))
+}
+
+fn handle_connection(stream: TcpStream) -> Result<(), String> {
+    let response = match read_request(&stream) {
+        Ok(request) => route(&request),
+        Err(e) => error_response(400, e),
+    };
+    write_response(&stream, response)
+}
+
+/// 错误响应
+pub(crate) fn error_response(status: u16, message: impl Into<MxuError>) -> Response {
+    let error: MxuError = message.into();
+    (status, json!({ "error": error }))
+}
+
+/// 鉴权失败时返回对应的错误响应
+pub(crate) fn authorize(request: &Request, command: &str) -> Result<(), Response> {
+    let token = request
+        .bearer_token()
+        .ok_or_else(|| error_response(401, "缺少访问令牌"))?;
+    super::remote_auth::authorize(token, command)
+        .map(|_| ())
+        .map_err(|e| error_response(403, e))
+}
+
+fn route(request: &Request) -> Response {
+    let Some(app) = APP.get() else {
+        return error_response(500, "应用尚未初始化");
+    };
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/status") => super::cluster::handle_status(request),
+        ("POST", "/dispatch") => super::cluster::handle_dispatch(app, request),
+        ("POST", "/cluster/report") => super::cluster::handle_report(app, request),
+        ("POST", path) if path.starts_with("/invoke/") => {
+            let command = &path["/invoke/".len()..];
+            if let Err(response) = authorize(request, command) {
+                return response;
+            }
+            let result = request
+                .json::<Value>()
+                .map_err(MxuError::internal)
+                .and_then(|args| invoke(app, command, args));
+            match result {
+                Ok(value) => (200, json!({ "result": value })),
+                Err(e) => error_response(400, e),
+            }
+        }
+        _ => error_response(
+            404,
+            format!("不支持的请求: {} {}", request.method, request.path),
+        ),
+    }
+}
+
+// ============================================================================
+// 远程命令
+// ============================================================================
+
+fn parse<T: DeserializeOwned>(args: Value) -> Result<T, MxuError> {
+    Ok(serde_json::from_value(args).map_err(|e| format!("无效的命令参数: {}", e))?)
+}
+
+fn reply<T: Serialize>(value: T) -> Result<Value, MxuError> {
+    Ok(serde_json::to_value(value).map_err(|e| e.to_string())?)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InstanceArgs {
+    instance_id: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StopArgs {
+    instance_id: String,
+    #[serde(default)]
+    mode: Option<StopMode>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StartProfileArgs {
+    profile: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SubscribeArgs {
+    patterns: Vec<String>,
+    #[serde(default)]
+    instance_id: Option<String>,
+    #[serde(default)]
+    hits_only: Option<bool>,
+    #[serde(default)]
+    include_image: Option<bool>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SubscriptionArgs {
+    subscription_id: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PollArgs {
+    subscription_id: String,
+    #[serde(default)]
+    since: Option<i64>,
+    #[serde(default)]
+    consume: Option<bool>,
+}
+
+/// 调用远程命令，参数与前端 invoke 相同（camelCase）
+fn invoke(app: &AppHandle, command: &str, args: Value) -> Result<Value, MxuError> {
+    use super::{maa_core, recognition_feed, task_queue, variables};
+
+    let state = app.state::<Arc<MaaState>>();
+    match command {
+        "queue_state" => {
+            let args: InstanceArgs = parse(args)?;
+            reply(task_queue::queue_state(state, args.instance_id)?)
+        }
+        "queue_pause" => {
+            let args: InstanceArgs = parse(args)?;
+            reply(task_queue::queue_pause(state, args.instance_id)?)
+        }
+        "queue_resume" => {
+            let args: InstanceArgs = parse(args)?;
+            reply(task_queue::queue_resume(state, args.instance_id)?)
+        }
+        "maa_is_running" => {
+            let args: InstanceArgs = parse(args)?;
+            reply(maa_core::maa_is_running(state, args.instance_id)?)
+        }
+        "maa_stop_task" => {
+            let args: StopArgs = parse(args)?;
+            reply(maa_core::maa_stop_task(
+                app.clone(),
+                state,
+                args.instance_id,
+                args.mode,
+            )?)
+        }
+        "get_task_variables" => {
+            let args: InstanceArgs = parse(args)?;
+            reply(variables::get_task_variables(args.instance_id)?)
+        }
+        "start_profile" => {
+            let args: StartProfileArgs = parse(args)?;
+            super::guest_mode::ensure_not_guest()?;
+            let summary = super::profiles::find_summary(&args.profile)?;
+            info!("[api_server] Remote start of profile {}", summary.name);
+            // 与托盘快速启动相同，由前端切换实例并开始任务
+            app.emit("tray-start-profile", &summary.id)
+                .map_err(|e| e.to_string())?;
+            reply(summary)
+        }
+        "recognition_subscribe" => {
+            let args: SubscribeArgs = parse(args)?;
+            reply(recognition_feed::recognition_subscribe(
+                args.patterns,
+                args.instance_id,
+                args.hits_only,
+                args.include_image,
+            )?)
+        }
+        "recognition_unsubscribe" => {
+            let args: SubscriptionArgs = parse(args)?;
+            reply(recognition_feed::recognition_unsubscribe(
+                args.subscription_id,
+            )?)
+        }
+        "recognition_poll" => {
+            let args: PollArgs = parse(args)?;
+            reply(recognition_feed::recognition_poll(
+                args.subscription_id,
+                args.since,
+                args.consume,
+            )?)
+        }
+        _ => Err(MxuError::with_args(
+            ErrorCode::CommandNotRemotable,
+            [command],
+        )),
+    }
+}
+
+// ============================================================================
+// 设置命令
+// ============================================================================
+
+/// 获取远程 API 服务设置
+#[tauri::command]
+pub fn api_server_get_settings() -> ApiServerSettings {
+    load_settings()
+}
+
+/// 保存远程 API 服务设置，立即启动、重启或停止服务
+#[tauri::command]
+pub fn api_server_set_settings(settings: ApiServerSettings) -> Result<ApiServerStatus, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    if settings.port == 0 {
+        return Err(MxuError::new(ErrorCode::InvalidPort));
+    }
+    save_json_config(&config_path()?, &settings)?;
+    if settings.enabled {
+        start(settings.port)?;
+    } else {
+        stop();
+        info!("[api_server] Disabled");
+    }
+    Ok(api_server_status())
+}
+
+/// 获取远程 API 服务运行状态
+#[tauri::command]
+pub fn api_server_status() -> ApiServerStatus {
+    ApiServerStatus {
+        running: is_running(),
+        port: running_port(),
+        error: LAST_ERROR.lock().ok().and_then(|e| e.clone()),
+    }
+}
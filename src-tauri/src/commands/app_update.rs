@@ -0,0 +1,289 @@
+//! MXU 程序自身更新
+//!
+//! 与 `update` 模块（资源 / MaaFramework 更新包）分开：下载新版 MXU 可执行文件（或包含它的压缩包）到
+//! cache/app_update，校验 SHA-256 与文件格式后替换正在运行的 exe 并重启。
+//! 替换时先将当前 exe 重命名到 cache/old（Windows 允许重命名运行中的 exe），再复制新文件到原位置；
+//! 新进程携带 `--replaced-pid` 参数启动，等待旧进程退出后再继续，避免单实例插件将其当作重复启动
+
+use log::{info, warn};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, State};
+
+use super::error::MxuError;
+use super::i18n::ErrorCode;
+use super::types::{MaaState, StagedAppUpdate};
+use super::update::{ensure_idle, extract_zip, move_to_old_folder};
+use super::utils::get_app_data_dir;
+
+/// 新进程等待旧进程退出的参数
+const REPLACED_PID_ARG: &str = "--replaced-pid=";
+/// 等待旧进程退出的最长时间
+const WAIT_PREVIOUS_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// 程序更新暂存目录
+fn staging_dir() -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?.join("cache").join("app_update"))
+}
+
+/// 按文件头判断下载文件是否为压缩包，返回解压时使用的扩展名
+/// （下载文件统一保存为 mxu_update，无法依赖 URL 中的扩展名）
+fn archive_extension(path: &Path) -> Option<&'static str> {
+    use std::io::Read;
+
+    let mut header = [0u8; 4];
+    std::fs::File::open(path)
+        .and_then(|mut f| f.read_exact(&mut header))
+        .ok()?;
+    if header == *b"PK\x03\x04" {
+        Some("zip")
+    } else if header.starts_with(&[0x1f, 0x8b]) {
+        Some("tar.gz")
+    } else {
+        None
+    }
+}
+
+/// 当前 exe 的文件名（压缩包中按此名称查找新版程序）
+fn exe_file_name() -> Result<std::ffi::OsString, MxuError> {
+    std::env::current_exe()
+        .map_err(|e| MxuError::with_args(ErrorCode::ExePathUnavailable, [e]))?
+        .file_name()
+        .map(|n| n.to_os_string())
+        .ok_or_else(|| MxuError::internal("无法获取 exe 文件名"))
+}
+
+/// 在解压目录中查找与当前 exe 同名的文件（最多两层）
+fn find_exe(dir: &Path, name: &std::ffi::OsStr, depth: u32) -> Option<PathBuf> {
+    let candidate = dir.join(name);
+    if candidate.is_file() {
+        return Some(candidate);
+    }
+    if depth == 0 {
+        return None;
+    }
+    std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_dir())
+        .find_map(|e| find_exe(&e.path(), name, depth - 1))
+}
+
+/// 检查文件头是否为当前平台的可执行格式，避免把下载到的错误页面或其他平台的程序替换上去
+fn check_executable_format(path: &Path) -> Result<(), MxuError> {
+    use std::io::Read;
+
+    let mut header = [0u8; 4];
+    std::fs::File::open(path)
+        .and_then(|mut f| f.read_exact(&mut header))
+        .map_err(|e| {
+            MxuError::with_args(
+                ErrorCode::FileReadFailed,
+                [path.display().to_string(), e.to_string()],
+            )
+        })?;
+
+    let valid = if cfg!(windows) {
+        header.starts_with(b"MZ")
+    } else if cfg!(target_os = "linux") {
+        header == *b"\x7fELF"
+    } else {
+        true
+    };
+    if !valid {
+        return Err(MxuError::with_args(
+            ErrorCode::InvalidExecutable,
+            [path.display()],
+        ));
+    }
+    Ok(())
+}
+
+/// 下载新版 MXU 并校验
+/// url: 可执行文件或包含它的压缩包（zip / tar.gz），sha256: 下载文件的 SHA-256（十六进制）
+/// 下载进度通过 download-progress 事件发送，可用 cancel_download 取消
+#[tauri::command]
+pub async fn app_update_download(
+    app: AppHandle,
+    url: String,
+    sha256: String,
+    proxy_url: Option<String>,
+) -> Result<StagedAppUpdate, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    info!("[app_update] Downloading {}", url);
+
+    if cfg!(target_os = "macos") {
+        return Err(MxuError::new(ErrorCode::AppUpdateUnsupported));
+    }
+
+    let dir = staging_dir()?;
+    // 清理上次残留的暂存文件
+    if dir.exists() {
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+    let save_path = dir.join("mxu_update");
+    let download = super::download::download_file(
+        app.clone(),
+        url,
+        save_path.to_string_lossy().to_string(),
+        None,
+        proxy_url,
+    )
+    .await?;
+    let downloaded = PathBuf::from(&download.actual_save_path);
+
+    let expected = sha256.trim().to_lowercase();
+    let hash_app = app.clone();
+    let hash_path = download.actual_save_path.clone();
+    let actual = tauri::async_runtime::spawn_blocking(move || {
+        super::file_ops::compute_file_hash(&hash_app, &hash_path, "sha256")
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    if actual != expected {
+        let _ = std::fs::remove_file(&downloaded);
+        return Err(MxuError::with_args(
+            ErrorCode::ChecksumMismatch,
+            [expected, actual],
+        ));
+    }
+
+    let exe_path = if let Some(extension) = archive_extension(&downloaded) {
+        // extract_zip 按扩展名选择解压格式
+        let archive = downloaded.with_extension(extension);
+        std::fs::rename(&downloaded, &archive).map_err(|e| {
+            MxuError::with_args(
+                ErrorCode::FileWriteFailed,
+                [archive.display().to_string(), e.to_string()],
+            )
+        })?;
+        let extract_dir = dir.join("extract");
+        extract_zip(
+            archive.to_string_lossy().to_string(),
+            extract_dir.to_string_lossy().to_string(),
+        )?;
+        let name = exe_file_name()?;
+        find_exe(&extract_dir, &name, 2).ok_or_else(|| {
+            MxuError::with_args(ErrorCode::FileNotInArchive, [name.to_string_lossy()])
+        })?
+    } else {
+        downloaded
+    };
+    check_executable_format(&exe_path)?;
+
+    let size = std::fs::metadata(&exe_path).map(|m| m.len()).unwrap_or(0);
+    info!(
+        "[app_update] Staged {} ({} bytes)",
+        exe_path.display(),
+        size
+    );
+    Ok(StagedAppUpdate {
+        exe_path: exe_path.to_string_lossy().to_string(),
+        sha256: actual,
+        size,
+    })
+}
+
+/// 用新文件替换当前 exe，复制失败时还原
+fn swap_exe(current: &Path, new_exe: &Path) -> Result<(), MxuError> {
+    let old = move_to_old_folder(current)?;
+    if let Err(e) = std::fs::copy(new_exe, current) {
+        // 按实际移动到的路径还原（重名时会带 .bakNNN 后缀）
+        if let Some(Err(restore_err)) = old.map(|old| std::fs::rename(&old, current)) {
+            warn!(
+                "[app_update] Failed to restore previous exe: {}",
+                restore_err
+            );
+        }
+        return Err(MxuError::with_args(
+            ErrorCode::FileWriteFailed,
+            [current.display().to_string(), e.to_string()],
+        ));
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = std::fs::set_permissions(current, std::fs::Permissions::from_mode(0o755));
+    }
+    Ok(())
+}
+
+/// 应用已下载的 MXU 更新：替换 exe 后以相同参数重新启动
+/// 有任务运行时返回 UpdateConflict
+#[tauri::command]
+pub fn app_update_apply(
+    app: AppHandle,
+    state: State<Arc<MaaState>>,
+    exe_path: String,
+) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    info!("[app_update] Applying {}", exe_path);
+    ensure_idle(&state)?;
+
+    // 规范化后再比较，避免 .. 或符号链接绕过暂存目录限制
+    let staging = staging_dir()?;
+    let new_exe = std::fs::canonicalize(&exe_path).map_err(|e| {
+        MxuError::with_args(ErrorCode::FileReadFailed, [exe_path.clone(), e.to_string()])
+    })?;
+    let canonical_staging = std::fs::canonicalize(&staging).map_err(|e| {
+        MxuError::with_args(
+            ErrorCode::DirectoryReadFailed,
+            [staging.display().to_string(), e.to_string()],
+        )
+    })?;
+    if !new_exe.starts_with(&canonical_staging) || !new_exe.is_file() {
+        return Err(MxuError::new(ErrorCode::AppUpdateNotStaged));
+    }
+    check_executable_format(&new_exe)?;
+
+    let current = std::env::current_exe()
+        .map_err(|e| MxuError::with_args(ErrorCode::ExePathUnavailable, [e]))?;
+    swap_exe(&current, &new_exe)?;
+    let _ = std::fs::remove_dir_all(&staging);
+
+    // 保留原启动参数（去掉上次更新留下的等待参数）
+    let args: Vec<std::ffi::OsString> = std::env::args_os()
+        .skip(1)
+        .filter(|a| !a.to_string_lossy().starts_with(REPLACED_PID_ARG))
+        .collect();
+    std::process::Command::new(&current)
+        .args(&args)
+        .arg(format!("{}{}", REPLACED_PID_ARG, std::process::id()))
+        .spawn()
+        .map_err(|e| MxuError::with_args(ErrorCode::ProcessLaunchFailed, [e]))?;
+
+    info!("[app_update] New version launched, exiting");
+    app.exit(0);
+    Ok(())
+}
+
+/// 由更新后重启的新进程在启动时调用：等待旧进程退出
+pub fn wait_for_replaced_process() {
+    use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+    let Some(pid) = std::env::args()
+        .find_map(|a| a.strip_prefix(REPLACED_PID_ARG).map(String::from))
+        .and_then(|p| p.parse::<u32>().ok())
+    else {
+        return;
+    };
+
+    let pid = Pid::from_u32(pid);
+    let mut system = System::new();
+    let started = Instant::now();
+    while started.elapsed() < WAIT_PREVIOUS_TIMEOUT {
+        system.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&[pid]),
+            true,
+            ProcessRefreshKind::nothing(),
+        );
+        if system.process(pid).is_none() {
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    warn!("[app_update] Previous process {} did not exit in time", pid);
+}
@@ -0,0 +1,201 @@
+//! 低功耗后台模式
+//!
+//! 主窗口隐藏到托盘后，高频的 maa-callback 与 task-progress 事件不再逐条发送，
+//! 而是缓存后每隔 batch_interval_secs 秒合并为一条 mxu://event-batch 事件，减少隐藏页面的唤醒次数；
+//! 资源监控等纯展示事件暂停发送。开启 suspend_webview 时，Windows 下额外挂起 WebView2 进程，
+//! 窗口重新显示时先恢复 WebView 再补发缓存的事件
+
+use log::{info, warn};
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, WebviewWindow};
+
+use super::error::MxuError;
+use super::i18n::ErrorCode;
+use super::types::{BackgroundModeSettings, BatchedEvent};
+use super::utils::{get_app_data_dir, save_json_config};
+
+/// 窗口状态检查间隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+/// 隐藏后等待多久再挂起 WebView（避免短暂隐藏时反复挂起）
+const SUSPEND_DELAY: Duration = Duration::from_secs(10);
+/// 缓存事件上限，超出时丢弃最早的事件
+const MAX_BUFFERED: usize = 20_000;
+
+/// 是否处于后台模式
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// 待发送的事件
+static BUFFER: LazyLock<Mutex<VecDeque<BatchedEvent>>> =
+    LazyLock::new(|| Mutex::new(VecDeque::new()));
+
+fn config_path() -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?
+        .join("config")
+        .join("background_mode.json"))
+}
+
+fn load_settings() -> Result<BackgroundModeSettings, String> {
+    let path = config_path()?;
+    if !path.exists() {
+        return Ok(BackgroundModeSettings::default());
+    }
+    let content =
+        std::fs::read_to_string(&path).map_err(|e| format!("无法读取后台模式设置: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("无法解析后台模式设置: {}", e))
+}
+
+/// 是否处于后台模式（纯展示类事件可据此跳过发送）
+pub fn is_active() -> bool {
+    ACTIVE.load(Ordering::SeqCst)
+}
+
+/// 发送高频事件：后台模式下缓存合并，否则直接发送
+pub fn emit<S: Serialize + Clone>(app: &AppHandle, event: &str, payload: S) {
+    if is_active() {
+        let Ok(payload) = serde_json::to_value(&payload) else {
+            return;
+        };
+        if let Ok(mut buffer) = BUFFER.lock() {
+            if buffer.len() >= MAX_BUFFERED {
+                buffer.pop_front();
+            }
+            buffer.push_back(BatchedEvent {
+                event: event.to_string(),
+                payload,
+            });
+        }
+        return;
+    }
+    if let Err(e) = app.emit(event, payload) {
+        log::error!("Failed to emit {}: {}", event, e);
+    }
+}
+
+/// 发送缓存的事件
+fn flush(app: &AppHandle) {
+    let events: Vec<BatchedEvent> = match BUFFER.lock() {
+        Ok(mut buffer) => buffer.drain(..).collect(),
+        Err(_) => return,
+    };
+    if events.is_empty() {
+        return;
+    }
+    if let Err(e) = app.emit("mxu://event-batch", events) {
+        log::error!("Failed to emit event batch: {}", e);
+    }
+}
+
+/// 挂起或恢复 WebView2（设为不可见后 TrySuspend，重新可见时自动恢复）
+#[cfg(windows)]
+fn set_webview_suspended(window: &WebviewWindow, suspended: bool) {
+    let result = window.with_webview(move |webview| unsafe {
+        use webview2_com::Microsoft::Web::WebView2::Win32::ICoreWebView2_3;
+        use webview2_com::TrySuspendCompletedHandler;
+        use windows_core::Interface;
+
+        let controller = webview.controller();
+        if let Err(e) = controller.SetIsVisible(!suspended) {
+            warn!("[background_mode] Failed to set webview visibility: {}", e);
+            return;
+        }
+        if !suspended {
+            return;
+        }
+        let webview3 = controller
+            .CoreWebView2()
+            .and_then(|core| core.cast::<ICoreWebView2_3>());
+        match webview3 {
+            Ok(webview3) => {
+                let handler = TrySuspendCompletedHandler::create(Box::new(|_, _| Ok(())));
+                if let Err(e) = webview3.TrySuspend(&handler) {
+                    warn!("[background_mode] TrySuspend failed: {}", e);
+                }
+            }
+            Err(e) => warn!("[background_mode] WebView2 does not support suspend: {}", e),
+        }
+    });
+    if let Err(e) = result {
+        warn!("[background_mode] Failed to access webview: {}", e);
+    }
+}
+
+#[cfg(not(windows))]
+fn set_webview_suspended(_window: &WebviewWindow, _suspended: bool) {}
+
+/// 启动后台模式线程
+pub fn spawn_background_mode(app: AppHandle) {
+    std::thread::spawn(move || {
+        let mut hidden_since: Option<Instant> = None;
+        let mut last_flush = Instant::now();
+        let mut suspended = false;
+        loop {
+            std::thread::sleep(CHECK_INTERVAL);
+            let Some(window) = app.get_webview_window("main") else {
+                continue;
+            };
+            let settings = load_settings().unwrap_or_default();
+            let hidden = !window.is_visible().unwrap_or(true);
+
+            if settings.enabled && hidden {
+                let since = *hidden_since.get_or_insert_with(Instant::now);
+                if !ACTIVE.swap(true, Ordering::SeqCst) {
+                    info!("[background_mode] Main window hidden, entering background mode");
+                    last_flush = Instant::now();
+                }
+                if settings.suspend_webview && !suspended && since.elapsed() >= SUSPEND_DELAY {
+                    info!("[background_mode] Suspending webview");
+                    set_webview_suspended(&window, true);
+                    suspended = true;
+                }
+                // 挂起期间页面无法处理事件，保留缓存等待恢复
+                if !suspended
+                    && last_flush.elapsed() >= Duration::from_secs(settings.batch_interval_secs)
+                {
+                    flush(&app);
+                    last_flush = Instant::now();
+                }
+                continue;
+            }
+
+            hidden_since = None;
+            if suspended {
+                info!("[background_mode] Resuming webview");
+                set_webview_suspended(&window, false);
+                suspended = false;
+            }
+            if ACTIVE.swap(false, Ordering::SeqCst) {
+                info!("[background_mode] Leaving background mode");
+                flush(&app);
+            }
+        }
+    });
+}
+
+/// 获取后台模式设置
+#[tauri::command]
+pub fn background_mode_get_settings() -> Result<BackgroundModeSettings, MxuError> {
+    Ok(load_settings()?)
+}
+
+/// 保存后台模式设置（数秒内生效）
+#[tauri::command]
+pub fn background_mode_set_settings(settings: BackgroundModeSettings) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    if settings.batch_interval_secs == 0 {
+        return Err(MxuError::new(ErrorCode::IntervalNotPositive));
+    }
+    let path = config_path()?;
+    save_json_config(&path, &settings)
+}
+
+/// 查询当前是否处于后台模式
+#[tauri::command]
+pub fn background_mode_is_active() -> bool {
+    is_active()
+}
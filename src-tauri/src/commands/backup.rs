@@ -0,0 +1,421 @@
+//! 设置备份与恢复
+//!
+//! 将数据目录下的 config/ 与 profiles/ 打包为带时间戳的 zip，保存在 backups/ 中，
+//! 仅保留最近 N 份；应用更新前会自动创建一次备份。
+//! 通过命令创建/恢复时在后台线程执行，逐文件发送 backup-progress 事件，可通过 backup_cancel 取消
+
+use log::{info, warn};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use tauri::{AppHandle, Emitter};
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+use super::error::MxuError;
+use super::i18n::ErrorCode;
+use super::types::{BackupInfo, BackupProgressEvent};
+use super::utils::get_app_data_dir;
+
+/// 参与备份的数据目录子目录
+const BACKUP_SOURCES: &[&str] = &["config", "profiles"];
+
+/// 备份时跳过的子目录（config/backup 为迁移备份）
+const BACKUP_SKIP_DIRS: &[&str] = &["backup"];
+
+/// 默认保留的备份数量
+pub const DEFAULT_KEEP: usize = 10;
+
+/// 备份文件名前缀
+const BACKUP_PREFIX: &str = "mxu-backup-";
+
+/// 备份操作代数，取消时递增，使进行中的备份/恢复失效
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// 进度回调：参数为当前文件、已处理数与总数，返回 false 表示取消
+pub type ProgressFn<'a> = &'a mut dyn FnMut(&str, usize, usize) -> bool;
+
+fn get_backups_dir() -> Result<PathBuf, MxuError> {
+    let dir = get_app_data_dir()?.join("backups");
+    std::fs::create_dir_all(&dir).map_err(|e| {
+        MxuError::with_args(
+            ErrorCode::DirectoryCreateFailed,
+            [dir.display().to_string(), e.to_string()],
+        )
+    })?;
+    Ok(dir)
+}
+
+fn backup_info(path: &Path) -> BackupInfo {
+    let metadata = path.metadata().ok();
+    BackupInfo {
+        path: path.to_string_lossy().to_string(),
+        file_name: path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        size: metadata.as_ref().map(|m| m.len()).unwrap_or(0),
+        created: metadata
+            .and_then(|m| m.modified().ok())
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_millis() as u64),
+    }
+}
+
+/// 递归收集目录下需要备份的文件（路径与压缩包内名称）
+fn collect_files(
+    dir: &Path,
+    prefix: &str,
+    files: &mut Vec<(PathBuf, String)>,
+) -> Result<(), MxuError> {
+    let entries = std::fs::read_dir(dir).map_err(|e| {
+        MxuError::with_args(
+            ErrorCode::DirectoryReadFailed,
+            [dir.display().to_string(), e.to_string()],
+        )
+    })?;
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        let archive_name = format!("{}/{}", prefix, name);
+
+        if path.is_dir() {
+            if BACKUP_SKIP_DIRS.contains(&name.as_str()) {
+                continue;
+            }
+            collect_files(&path, &archive_name, files)?;
+        } else if path.is_file() {
+            files.push((path, archive_name));
+        }
+    }
+    Ok(())
+}
+
+/// 列出备份文件（最新在前）
+fn list_backup_paths() -> Result<Vec<PathBuf>, MxuError> {
+    let dir = get_backups_dir()?;
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(&dir)
+        .map_err(|e| {
+            MxuError::with_args(
+                ErrorCode::DirectoryReadFailed,
+                [dir.display().to_string(), e.to_string()],
+            )
+        })?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| {
+            p.is_file()
+                && p.file_name().is_some_and(|n| {
+                    let n = n.to_string_lossy();
+                    n.starts_with(BACKUP_PREFIX) && n.ends_with(".zip")
+                })
+        })
+        .collect();
+    // 文件名包含时间戳，按名称倒序即为时间倒序
+    paths.sort_by(|a, b| b.file_name().cmp(&a.file_name()));
+    Ok(paths)
+}
+
+/// 将 config/ 与 profiles/ 打包到指定 zip 文件，返回文件数
+pub fn write_backup_zip(zip_path: &Path) -> Result<usize, MxuError> {
+    write_backup_zip_with_progress(zip_path, &mut |_, _, _| true)
+}
+
+/// 打包备份并报告进度，取消时删除未完成的文件
+pub fn write_backup_zip_with_progress(
+    zip_path: &Path,
+    on_progress: ProgressFn,
+) -> Result<usize, MxuError> {
+    let data_dir = get_app_data_dir()?;
+    let mut files = Vec::new();
+    for source in BACKUP_SOURCES {
+        let dir = data_dir.join(source);
+        if dir.is_dir() {
+            collect_files(&dir, source, &mut files)?;
+        }
+    }
+
+    let write_failed = |e: &dyn std::fmt::Display| {
+        MxuError::with_args(ErrorCode::BackupWriteFailed, [e.to_string()])
+    };
+    let file = File::create(zip_path).map_err(|e| write_failed(&e))?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    let total = files.len();
+    for (index, (path, archive_name)) in files.iter().enumerate() {
+        if !on_progress(archive_name, index, total) {
+            drop(zip);
+            let _ = std::fs::remove_file(zip_path);
+            return Err(MxuError::new(ErrorCode::Cancelled));
+        }
+        let mut content = Vec::new();
+        File::open(path)
+            .and_then(|mut f| f.read_to_end(&mut content))
+            .map_err(|e| {
+                MxuError::with_args(
+                    ErrorCode::FileReadFailed,
+                    [path.display().to_string(), e.to_string()],
+                )
+            })?;
+        zip.start_file(archive_name.as_str(), options)
+            .map_err(|e| write_failed(&e))?;
+        zip.write_all(&content).map_err(|e| write_failed(&e))?;
+    }
+    zip.finish().map_err(|e| write_failed(&e))?;
+    on_progress("", total, total);
+    Ok(total)
+}
+
+/// 删除备份范围内不在 keep 中的文件，使数据目录与备份内容一致
+fn remove_files_not_in(data_dir: &Path, keep: &HashSet<PathBuf>) -> Result<(), MxuError> {
+    let mut files = Vec::new();
+    for source in BACKUP_SOURCES {
+        let dir = data_dir.join(source);
+        if dir.is_dir() {
+            collect_files(&dir, source, &mut files)?;
+        }
+    }
+    for (path, _) in files {
+        if keep.contains(&path) {
+            continue;
+        }
+        std::fs::remove_file(&path).map_err(|e| {
+            MxuError::with_args(
+                ErrorCode::FileDeleteFailed,
+                [path.display().to_string(), e.to_string()],
+            )
+        })?;
+        info!("[backup] Removed {} (not in backup)", path.display());
+    }
+    Ok(())
+}
+
+/// 从指定 zip 文件恢复 config/ 与 profiles/（仅恢复备份范围内的条目），返回文件数
+pub fn restore_backup_zip(zip_path: &Path) -> Result<usize, MxuError> {
+    restore_backup_zip_with_progress(zip_path, &mut |_, _, _| true)
+}
+
+/// 恢复备份并报告进度，完成后删除备份之后新建的文件；取消时已写入的文件不会回滚，由调用方处理
+pub fn restore_backup_zip_with_progress(
+    zip_path: &Path,
+    on_progress: ProgressFn,
+) -> Result<usize, MxuError> {
+    let data_dir = get_app_data_dir()?;
+    let read_failed = |e: &dyn std::fmt::Display| {
+        MxuError::with_args(ErrorCode::BackupReadFailed, [e.to_string()])
+    };
+    let zip_file = File::open(zip_path).map_err(|e| read_failed(&e))?;
+    let mut archive = zip::ZipArchive::new(zip_file).map_err(|e| read_failed(&e))?;
+
+    let total = archive.len();
+    let mut restored = HashSet::new();
+    for i in 0..total {
+        let mut entry = archive.by_index(i).map_err(|e| read_failed(&e))?;
+        if !on_progress(entry.name(), i, total) {
+            return Err(MxuError::new(ErrorCode::Cancelled));
+        }
+        let Some(relative) = entry.enclosed_name() else {
+            continue;
+        };
+        // 只恢复备份范围内的目录
+        let in_scope = relative
+            .components()
+            .next()
+            .is_some_and(|c| BACKUP_SOURCES.contains(&c.as_os_str().to_string_lossy().as_ref()));
+        if !in_scope || entry.is_dir() {
+            continue;
+        }
+
+        let dest = data_dir.join(&relative);
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| {
+                MxuError::with_args(
+                    ErrorCode::DirectoryCreateFailed,
+                    [parent.display().to_string(), e.to_string()],
+                )
+            })?;
+        }
+        let write_failed = |e: std::io::Error| {
+            MxuError::with_args(
+                ErrorCode::FileWriteFailed,
+                [dest.display().to_string(), e.to_string()],
+            )
+        };
+        let mut out = File::create(&dest).map_err(write_failed)?;
+        std::io::copy(&mut entry, &mut out).map_err(write_failed)?;
+        restored.insert(dest);
+    }
+    remove_files_not_in(&data_dir, &restored)?;
+    on_progress("", total, total);
+    Ok(restored.len())
+}
+
+/// 创建备份并清理超出保留数量的旧备份
+/// label: 可选标签，附加在文件名中（如 pre-update）
+pub fn create_backup(label: Option<&str>, keep: usize) -> Result<BackupInfo, MxuError> {
+    create_backup_with_progress(label, keep, None, &mut |_, _, _| true)
+}
+
+/// protect: 清理旧备份时需要保留的文件（如正在恢复的备份）
+fn create_backup_with_progress(
+    label: Option<&str>,
+    keep: usize,
+    protect: Option<&Path>,
+    on_progress: ProgressFn,
+) -> Result<BackupInfo, MxuError> {
+    let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S%3f");
+    let file_name = match label.filter(|l| !l.is_empty()) {
+        Some(label) => {
+            let label: String = label
+                .chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() || c == '-' {
+                        c
+                    } else {
+                        '_'
+                    }
+                })
+                .collect();
+            format!("{}{}-{}.zip", BACKUP_PREFIX, timestamp, label)
+        }
+        None => format!("{}{}.zip", BACKUP_PREFIX, timestamp),
+    };
+    let zip_path = get_backups_dir()?.join(&file_name);
+    let count = write_backup_zip_with_progress(&zip_path, on_progress)?;
+    info!(
+        "[backup] Created {} with {} file(s)",
+        zip_path.display(),
+        count
+    );
+
+    // 清理旧备份
+    for old in list_backup_paths()?
+        .into_iter()
+        .skip(keep.max(1))
+        .filter(|p| Some(p.as_path()) != protect)
+    {
+        if let Err(e) = std::fs::remove_file(&old) {
+            warn!(
+                "[backup] Failed to remove old backup {}: {}",
+                old.display(),
+                e
+            );
+        }
+    }
+
+    Ok(backup_info(&zip_path))
+}
+
+/// 生成发送 backup-progress 事件并检查取消状态的进度回调
+fn progress_emitter(
+    app: AppHandle,
+    operation: &'static str,
+) -> impl FnMut(&str, usize, usize) -> bool {
+    let generation = GENERATION.load(Ordering::SeqCst);
+    move |current, processed, total| {
+        let _ = app.emit(
+            "backup-progress",
+            BackupProgressEvent {
+                operation: operation.to_string(),
+                current: current.to_string(),
+                processed,
+                total,
+                progress: processed as f64 / total.max(1) as f64 * 100.0,
+            },
+        );
+        GENERATION.load(Ordering::SeqCst) == generation
+    }
+}
+
+/// 创建设置备份（后台执行，发送 backup-progress 事件）
+#[tauri::command]
+pub async fn backup_create(
+    app: AppHandle,
+    label: Option<String>,
+    keep: Option<usize>,
+) -> Result<BackupInfo, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    let mut on_progress = progress_emitter(app, "create");
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        create_backup_with_progress(
+            label.as_deref(),
+            keep.unwrap_or(DEFAULT_KEEP),
+            None,
+            &mut on_progress,
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())??)
+}
+
+/// 列出所有设置备份
+#[tauri::command]
+pub fn backup_list() -> Result<Vec<BackupInfo>, MxuError> {
+    Ok(list_backup_paths()?
+        .iter()
+        .map(|p| backup_info(p))
+        .collect())
+}
+
+/// 从备份恢复设置，恢复前会先备份当前设置；取消或失败时回滚到恢复前的设置
+/// file: 备份目录中的文件名
+#[tauri::command]
+pub async fn backup_restore(app: AppHandle, file: String) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    if file.contains(['/', '\\']) || file.contains("..") {
+        return Err(MxuError::with_args(ErrorCode::BackupNameInvalid, [&file]));
+    }
+    let zip_path = get_backups_dir()?.join(&file);
+    if !zip_path.is_file() {
+        return Err(MxuError::with_args(ErrorCode::BackupNotFound, [&file]));
+    }
+
+    let mut on_progress = progress_emitter(app, "restore");
+    tauri::async_runtime::spawn_blocking(move || {
+        // 先备份当前状态，便于撤销恢复操作；清理旧备份时保留正在恢复的文件
+        let pre_restore = create_backup_with_progress(
+            Some("pre-restore"),
+            DEFAULT_KEEP,
+            Some(&zip_path),
+            &mut |_, _, _| true,
+        )?;
+
+        match restore_backup_zip_with_progress(&zip_path, &mut on_progress) {
+            Ok(restored) => {
+                info!("[backup] Restored {} file(s) from {}", restored, file);
+                Ok(())
+            }
+            Err(e) => {
+                warn!("[backup] Restore from {} failed, rolling back: {}", file, e);
+                restore_backup_zip(Path::new(&pre_restore.path)).map_err(|rollback| {
+                    MxuError::with_args(ErrorCode::BackupRollbackFailed, [&e, &rollback])
+                })?;
+                if e.code == ErrorCode::Cancelled {
+                    Err(MxuError::new(ErrorCode::BackupRestoreCancelled))
+                } else {
+                    Err(MxuError::with_args(ErrorCode::BackupRestoreFailed, [e]))
+                }
+            }
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 取消进行中的备份或恢复
+#[tauri::command]
+pub fn backup_cancel() {
+    GENERATION.fetch_add(1, Ordering::SeqCst);
+}
+
+/// 应用更新前自动备份，失败只记录警告
+pub fn backup_before_update() {
+    if let Err(e) = create_backup(Some("pre-update"), DEFAULT_KEEP) {
+        warn!("[backup] Automatic backup before update failed: {}", e);
+    }
+}
@@ -0,0 +1,74 @@
+//! 功能清单
+//!
+//! 以机器可读的形式描述当前构建支持的功能（内置动作及其参数、控制器、通知渠道、平台功能），
+//! 资源与前端据此按功能检测，而不必根据版本号推测
+
+use std::collections::BTreeMap;
+
+use super::types::Capabilities;
+
+/// 清单格式版本，字段有不兼容变化时递增
+const SCHEMA_VERSION: u32 = 1;
+
+/// 当前平台可用的控制器类型
+fn controllers() -> Vec<String> {
+    let mut controllers = vec!["Adb"];
+    if cfg!(windows) {
+        controllers.extend(["Win32", "Gamepad"]);
+    }
+    if cfg!(target_os = "macos") {
+        controllers.push("PlayCover");
+    }
+    // 回放截图的模拟控制器，各平台均可用
+    controllers.push("Mock");
+    controllers.into_iter().map(String::from).collect()
+}
+
+/// 平台相关功能
+fn features() -> BTreeMap<String, bool> {
+    [
+        // Windows 专有
+        ("win32_window_preview", cfg!(windows)),
+        ("wake_timer", cfg!(windows)),
+        ("agent_job_objects", cfg!(windows)),
+        ("taskbar_progress", cfg!(windows)),
+        // 跨平台
+        ("scrcpy_preview", super::scrcpy::scrcpy_is_available()),
+        ("tts", true),
+        ("pause_resume", true),
+        ("stop_modes", true),
+        ("task_hooks", true),
+        ("run_digest", true),
+        ("remote_auth", true),
+        ("action_test", true),
+        ("scripting", cfg!(feature = "scripting")),
+        ("recognition_subscriptions", true),
+    ]
+    .into_iter()
+    .map(|(name, enabled)| (name.to_string(), enabled))
+    .collect()
+}
+
+/// 获取当前构建的功能清单
+#[tauri::command]
+pub fn get_capabilities() -> Capabilities {
+    let maafw_version = std::panic::catch_unwind(|| maa_framework::maa_version().to_string())
+        .ok()
+        .filter(|v| !v.is_empty() && v != "unknown");
+    Capabilities {
+        schema_version: SCHEMA_VERSION,
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        maafw_version,
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        custom_actions: crate::mxu_actions::action_manifest(),
+        custom_recognitions: crate::mxu_recognizers::recognizer_names(),
+        controllers: controllers(),
+        notification_channels: ["system", "webhook", "tts"]
+            .into_iter()
+            .map(String::from)
+            .collect(),
+        api_server: super::api_server::is_running(),
+        features: features(),
+    }
+}
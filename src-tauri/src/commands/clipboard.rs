@@ -0,0 +1,64 @@
+//! 剪贴板命令
+//!
+//! 使用 arboard 原生访问系统剪贴板，避免部分 WebView2 版本中剪贴板权限失效的问题
+
+use log::{debug, info};
+
+use super::error::MxuError;
+use super::i18n::ErrorCode;
+use super::utils::encode_png_data_url;
+
+/// 单次读写的文本长度上限（字符数），避免误读超大内容阻塞 IPC
+const MAX_TEXT_CHARS: usize = 1024 * 1024;
+
+fn open_clipboard() -> Result<arboard::Clipboard, String> {
+    arboard::Clipboard::new().map_err(|e| format!("无法访问剪贴板: {}", e))
+}
+
+/// 读取剪贴板文本，剪贴板为空或不是文本时返回 None
+#[tauri::command]
+pub fn clipboard_read_text() -> Result<Option<String>, MxuError> {
+    debug!("clipboard_read_text called");
+    match open_clipboard()?.get_text() {
+        Ok(text) => {
+            if text.chars().count() > MAX_TEXT_CHARS {
+                return Err(MxuError::new(ErrorCode::ClipboardTextTooLong));
+            }
+            Ok(Some(text))
+        }
+        Err(arboard::Error::ContentNotAvailable) => Ok(None),
+        Err(e) => Err(MxuError::with_args(ErrorCode::ClipboardReadFailed, [e])),
+    }
+}
+
+/// 写入文本到剪贴板
+#[tauri::command]
+pub fn clipboard_write_text(text: String) -> Result<(), MxuError> {
+    if text.chars().count() > MAX_TEXT_CHARS {
+        return Err(MxuError::new(ErrorCode::ClipboardTextTooLong));
+    }
+    open_clipboard()?
+        .set_text(text)
+        .map_err(|e| format!("写入剪贴板失败: {}", e))?;
+    info!("clipboard_write_text success");
+    Ok(())
+}
+
+/// 读取剪贴板图片，返回 PNG data URL；剪贴板中没有图片时返回 None
+#[tauri::command]
+pub fn clipboard_read_image() -> Result<Option<String>, MxuError> {
+    debug!("clipboard_read_image called");
+    let data = match open_clipboard()?.get_image() {
+        Ok(data) => data,
+        Err(arboard::Error::ContentNotAvailable) => return Ok(None),
+        Err(e) => return Err(MxuError::with_args(ErrorCode::ClipboardReadFailed, [e])),
+    };
+
+    let image = image::RgbaImage::from_raw(
+        data.width as u32,
+        data.height as u32,
+        data.bytes.into_owned(),
+    )
+    .ok_or_else(|| MxuError::new(ErrorCode::NoImageData))?;
+    Ok(encode_png_data_url(&image).map(Some)?)
+}
@@ -0,0 +1,765 @@
+//! 集群模式
+//!
+//! 主实例将配置档案分发到局域网内的其他 MXU 节点执行：
+//! 通过节点的远程 API 服务查询可用性（GET /status）并提交档案（POST /dispatch），
+//! 节点运行结束后向主实例的 POST /cluster/report 回报结果，结果写入主实例的运行历史，
+//! 全部完成后汇总通知。
+//! 主实例与节点都需要开启远程 API 服务（api_server），节点一次只执行一个分发任务，
+//! 档案多于空闲节点时其余档案排队等待节点空闲；节点与主实例两侧均有超时，
+//! 节点离线或无法启动时不会无限等待
+
+use log::{info, warn};
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Emitter};
+
+use super::api_server::{authorize, error_response, Request, Response};
+use super::error::MxuError;
+use super::file_ops::to_hex;
+use super::i18n::ErrorCode;
+use super::profiles::profile_get;
+use super::types::{
+    ClusterAssignment, ClusterDispatchRequest, ClusterNode, ClusterNodeState, ClusterNodeStatus,
+    ClusterRunResult, ClusterSummary, Profile, RunHistoryEntry,
+};
+use super::utils::{get_app_data_dir, save_json_config};
+
+/// 节点请求超时
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+/// 节点执行分发任务的默认最长时间（档案未设置运行时长上限时使用），超时后回报失败
+const JOB_TIMEOUT: Duration = Duration::from_secs(6 * 60 * 60);
+/// 档案设置了运行时长上限时，在上限之外额外留给连接与启动的时间
+const JOB_START_GRACE: Duration = Duration::from_secs(10 * 60);
+/// 主实例等待单个分配回报的最长时间（略长于节点超时，覆盖节点离线的情况）
+const ASSIGNMENT_TIMEOUT: Duration = Duration::from_secs(6 * 60 * 60 + 10 * 60);
+/// 主实例检查回报超时的间隔
+const ROUND_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 已提交到节点、等待回报的分配
+struct PendingAssignment {
+    profile_id: String,
+    node_id: String,
+    /// 档案中的任务数量（写入运行历史）
+    task_count: usize,
+    dispatched_at: String,
+    deadline: Instant,
+}
+
+/// 主实例当前一轮分发的状态
+#[derive(Default)]
+struct Round {
+    id: u64,
+    /// 待回报的分配
+    pending: Vec<PendingAssignment>,
+    /// 等待空闲节点的档案（节点一次只执行一个分发任务）
+    queued: VecDeque<String>,
+    /// 正在进行首次分配，此时不判断本轮是否结束
+    filling: bool,
+    /// 已回报的结果
+    results: Vec<ClusterRunResult>,
+    /// 回报运行结果的地址
+    report_url: String,
+    /// 节点回报结果时使用的密钥
+    secret: String,
+}
+
+impl Round {
+    fn is_active(&self) -> bool {
+        self.filling || !self.pending.is_empty() || !self.queued.is_empty()
+    }
+}
+
+static ROUND: LazyLock<Mutex<Round>> = LazyLock::new(|| Mutex::new(Round::default()));
+
+/// 本节点正在执行的分发任务
+struct NodeJob {
+    id: u64,
+    request: ClusterDispatchRequest,
+    /// 对应的 run_id（运行开始后关联）
+    run_id: Option<String>,
+}
+
+static NODE_JOB: Mutex<Option<NodeJob>> = Mutex::new(None);
+static NEXT_JOB_ID: AtomicU64 = AtomicU64::new(1);
+
+fn nodes_path() -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?
+        .join("config")
+        .join("cluster_nodes.json"))
+}
+
+fn load_nodes() -> Result<Vec<ClusterNode>, MxuError> {
+    let path = nodes_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(&path).map_err(|e| {
+        MxuError::with_args(
+            ErrorCode::FileReadFailed,
+            [path.display().to_string(), e.to_string()],
+        )
+    })?;
+    serde_json::from_str(&content).map_err(|e| {
+        MxuError::with_args(
+            ErrorCode::FileParseFailed,
+            [path.display().to_string(), e.to_string()],
+        )
+    })
+}
+
+fn save_nodes(nodes: &[ClusterNode]) -> Result<(), MxuError> {
+    let path = nodes_path()?;
+    save_json_config(&path, nodes)
+}
+
+fn build_client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))
+}
+
+/// 列出集群节点
+#[tauri::command]
+pub fn cluster_list_nodes() -> Result<Vec<ClusterNode>, MxuError> {
+    load_nodes()
+}
+
+/// 添加集群节点
+#[tauri::command]
+pub fn cluster_add_node(name: String, url: String, token: String) -> Result<ClusterNode, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    let url = url.trim().trim_end_matches('/').to_string();
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err(MxuError::with_args(ErrorCode::InvalidUrl, [&url]));
+    }
+
+    let mut nodes = load_nodes()?;
+    let node = ClusterNode {
+        id: chrono::Local::now().format("%Y%m%d%H%M%S%3f").to_string(),
+        name: name.trim().to_string(),
+        url,
+        token,
+    };
+    nodes.push(node.clone());
+    save_nodes(&nodes)?;
+    info!("[cluster] Node added: {} ({})", node.name, node.url);
+    Ok(node)
+}
+
+/// 移除集群节点
+#[tauri::command]
+pub fn cluster_remove_node(id: String) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    let mut nodes = load_nodes()?;
+    let before = nodes.len();
+    nodes.retain(|n| n.id != id);
+    if nodes.len() == before {
+        return Err(MxuError::with_args(ErrorCode::ClusterNodeNotFound, [&id]));
+    }
+    save_nodes(&nodes)
+}
+
+/// 查询单个节点状态
+async fn probe_node(client: &reqwest::Client, node: &ClusterNode) -> ClusterNodeStatus {
+    let result = async {
+        let response = client
+            .get(format!("{}/status", node.url))
+            .bearer_auth(&node.token)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!("HTTP {}", response.status()));
+        }
+        let state: ClusterNodeState = response.json().await.map_err(|e| e.to_string())?;
+        Ok(state.busy)
+    }
+    .await;
+
+    match result {
+        Ok(busy) => ClusterNodeStatus {
+            node_id: node.id.clone(),
+            online: true,
+            busy,
+            error: None,
+        },
+        Err(e) => ClusterNodeStatus {
+            node_id: node.id.clone(),
+            online: false,
+            busy: false,
+            error: Some(e),
+        },
+    }
+}
+
+/// 查询所有节点的可用性
+#[tauri::command]
+pub async fn cluster_probe_nodes() -> Result<Vec<ClusterNodeStatus>, MxuError> {
+    let client = build_client()?;
+    let mut statuses = Vec::new();
+    for node in load_nodes()? {
+        statuses.push(probe_node(&client, &node).await);
+    }
+    Ok(statuses)
+}
+
+/// 将配置档案分配给空闲节点并提交，档案多于空闲节点时其余档案排队，
+/// 待节点回报结果后依次提交给该节点
+#[tauri::command]
+pub async fn cluster_dispatch(
+    app: AppHandle,
+    profile_ids: Vec<String>,
+) -> Result<Vec<ClusterAssignment>, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    // 节点通过主实例的远程 API 服务回报结果
+    let port = super::api_server::running_port()
+        .ok_or_else(|| MxuError::new(ErrorCode::ClusterApiServerRequired))?;
+    let ip = super::remote_auth::local_lan_ip()
+        .ok_or_else(|| MxuError::new(ErrorCode::LanAddressUnavailable))?;
+    let report_url = format!("http://{}:{}/cluster/report", ip, port);
+    let secret = generate_secret()?;
+    let client = build_client()?;
+    let nodes = load_nodes()?;
+
+    let round_id = {
+        let mut round = ROUND.lock().map_err(|e| e.to_string())?;
+        if round.is_active() {
+            return Err(MxuError::new(ErrorCode::ClusterRoundActive));
+        }
+        let id = round.id + 1;
+        *round = Round {
+            id,
+            queued: profile_ids.iter().cloned().collect(),
+            filling: true,
+            report_url,
+            secret,
+            ..Default::default()
+        };
+        id
+    };
+
+    let mut available = Vec::new();
+    for node in &nodes {
+        let status = probe_node(&client, node).await;
+        if status.online && !status.busy {
+            available.push(node);
+        }
+    }
+    if available.is_empty() {
+        if let Ok(mut round) = ROUND.lock() {
+            *round = Round {
+                id: round_id,
+                ..Default::default()
+            };
+        }
+        return Err(MxuError::new(ErrorCode::ClusterNoNodes));
+    }
+
+    let mut assignments = Vec::new();
+    for node in available {
+        assignments.extend(dispatch_next(&client, round_id, node).await);
+    }
+
+    let queued = {
+        let mut round = ROUND.lock().map_err(|e| e.to_string())?;
+        round.filling = false;
+        round.queued.iter().cloned().collect::<Vec<_>>()
+    };
+    assignments.extend(queued.into_iter().map(|profile_id| ClusterAssignment {
+        profile_id,
+        node_id: String::new(),
+        queued: true,
+        error: None,
+    }));
+    info!(
+        "[cluster] Round {} started: {} profile(s)",
+        round_id,
+        profile_ids.len()
+    );
+
+    spawn_round_watchdog(app.clone(), round_id);
+    finish_round_if_done(&app, round_id);
+    Ok(assignments)
+}
+
+/// 向节点提交分发请求
+async fn send_dispatch(
+    client: &reqwest::Client,
+    node: &ClusterNode,
+    request: &ClusterDispatchRequest,
+) -> Result<(), String> {
+    let response = client
+        .post(format!("{}/dispatch", node.url))
+        .bearer_auth(&node.token)
+        .json(request)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("HTTP {}", response.status()))
+    }
+}
+
+/// 从本轮队列中取出下一个档案提交给节点；
+/// 档案无法读取时记为失败并继续取下一个，节点拒绝或无法访问时停止向该节点分配
+async fn dispatch_next(
+    client: &reqwest::Client,
+    round_id: u64,
+    node: &ClusterNode,
+) -> Vec<ClusterAssignment> {
+    let mut assignments = Vec::new();
+    loop {
+        let next = ROUND.lock().ok().and_then(|mut round| {
+            if round.id != round_id {
+                return None;
+            }
+            let profile_id = round.queued.pop_front()?;
+            Some((profile_id, round.report_url.clone(), round.secret.clone()))
+        });
+        let Some((profile_id, report_url, report_secret)) = next else {
+            return assignments;
+        };
+
+        let profile = match profile_get(profile_id.clone()) {
+            Ok(profile) => profile,
+            Err(e) => {
+                warn!("[cluster] Failed to read profile {}: {}", profile_id, e);
+                push_result(
+                    round_id,
+                    failed_result(node, &profile_id, &e.message),
+                    0,
+                    None,
+                );
+                assignments.push(ClusterAssignment {
+                    profile_id,
+                    node_id: node.id.clone(),
+                    queued: false,
+                    error: Some(e.message),
+                });
+                continue;
+            }
+        };
+        let task_count = profile.content.tasks.as_array().map_or(0, Vec::len);
+        let request = ClusterDispatchRequest {
+            node_id: node.id.clone(),
+            profile,
+            report_url,
+            report_secret,
+        };
+        // 先登记再提交，避免节点在提交返回前就回报结果
+        if let Ok(mut round) = ROUND.lock() {
+            if round.id == round_id {
+                round.pending.push(PendingAssignment {
+                    profile_id: profile_id.clone(),
+                    node_id: node.id.clone(),
+                    task_count,
+                    dispatched_at: chrono::Local::now().to_rfc3339(),
+                    deadline: Instant::now() + ASSIGNMENT_TIMEOUT,
+                });
+            }
+        }
+        let error = send_dispatch(client, node, &request).await.err();
+        match &error {
+            None => info!("[cluster] Dispatched {} to {}", profile_id, node.name),
+            Some(e) => {
+                warn!(
+                    "[cluster] Failed to dispatch {} to {}: {}",
+                    profile_id, node.name, e
+                );
+                if let Ok(mut round) = ROUND.lock() {
+                    round
+                        .pending
+                        .retain(|a| !(a.node_id == node.id && a.profile_id == profile_id));
+                }
+                push_result(
+                    round_id,
+                    failed_result(node, &profile_id, e),
+                    task_count,
+                    None,
+                );
+            }
+        }
+        assignments.push(ClusterAssignment {
+            profile_id,
+            node_id: node.id.clone(),
+            queued: false,
+            error,
+        });
+        return assignments;
+    }
+}
+
+fn failed_result(node: &ClusterNode, profile_id: &str, message: &str) -> ClusterRunResult {
+    ClusterRunResult {
+        node_id: node.id.clone(),
+        profile_id: profile_id.to_string(),
+        success: false,
+        message: Some(message.to_string()),
+        finished_at: Some(chrono::Local::now().to_rfc3339()),
+    }
+}
+
+/// 保存一条结果并写入运行历史
+fn push_result(
+    round_id: u64,
+    result: ClusterRunResult,
+    task_count: usize,
+    started_at: Option<String>,
+) {
+    record_history(&result, task_count, started_at);
+    if let Ok(mut round) = ROUND.lock() {
+        if round.id == round_id {
+            round.results.push(result);
+        }
+    }
+}
+
+/// 将节点的运行结果写入主实例的运行历史（实例 ID 为 cluster:<节点 ID>）
+fn record_history(result: &ClusterRunResult, task_count: usize, started_at: Option<String>) {
+    let now = chrono::Local::now();
+    let finished_at = result
+        .finished_at
+        .clone()
+        .unwrap_or_else(|| now.to_rfc3339());
+    super::digest::record_run(RunHistoryEntry {
+        run_id: format!(
+            "cluster-{}-{}",
+            now.format("%Y%m%d-%H%M%S%3f"),
+            result.profile_id
+        ),
+        instance_id: format!("cluster:{}", result.node_id),
+        started_at: started_at.unwrap_or_else(|| finished_at.clone()),
+        finished_at,
+        status: super::runs::status_name(result.success).to_string(),
+        reason: result.message.clone(),
+        task_count,
+        failed_tasks: Vec::new(),
+        counters: Default::default(),
+    });
+}
+
+/// 生成本轮分发的回报密钥
+fn generate_secret() -> Result<String, String> {
+    let mut buf = [0u8; 32];
+    getrandom::getrandom(&mut buf).map_err(|e| format!("无法生成回报密钥: {}", e))?;
+    Ok(to_hex(&buf))
+}
+
+/// 记录节点回报的运行结果，本轮全部完成后发送汇总事件与系统通知
+/// 仅由经回报密钥鉴权的 POST /cluster/report 调用，不暴露为前端命令
+fn record_result(app: &AppHandle, result: ClusterRunResult) -> Result<(), String> {
+    let round_id = {
+        let mut round = ROUND.lock().map_err(|e| e.to_string())?;
+        let Some(pos) = round
+            .pending
+            .iter()
+            .position(|a| a.node_id == result.node_id && a.profile_id == result.profile_id)
+        else {
+            return Err(format!(
+                "未找到对应的分配: {} @ {}",
+                result.profile_id, result.node_id
+            ));
+        };
+        let assignment = round.pending.remove(pos);
+        record_history(
+            &result,
+            assignment.task_count,
+            Some(assignment.dispatched_at),
+        );
+        round.results.push(result.clone());
+        round.id
+    };
+
+    // 节点已空闲：提交下一个排队的档案后检查本轮是否结束
+    let node = load_nodes()
+        .ok()
+        .and_then(|nodes| nodes.into_iter().find(|n| n.id == result.node_id));
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let (Some(node), Ok(client)) = (node, build_client()) {
+            dispatch_next(&client, round_id, &node).await;
+        }
+        finish_round_if_done(&app, round_id);
+    });
+    Ok(())
+}
+
+/// 定期将超时未回报的分配记为失败（节点离线或崩溃时不再无限等待）
+fn spawn_round_watchdog(app: AppHandle, round_id: u64) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(ROUND_CHECK_INTERVAL);
+        let expired = {
+            let Ok(mut round) = ROUND.lock() else {
+                return;
+            };
+            if round.id != round_id || !round.is_active() {
+                return;
+            }
+            let now = Instant::now();
+            let (expired, pending): (Vec<_>, Vec<_>) = std::mem::take(&mut round.pending)
+                .into_iter()
+                .partition(|a| a.deadline <= now);
+            round.pending = pending;
+            expired
+        };
+        for assignment in expired {
+            warn!(
+                "[cluster] No report for {} from node {} before timeout",
+                assignment.profile_id, assignment.node_id
+            );
+            push_result(
+                round_id,
+                ClusterRunResult {
+                    node_id: assignment.node_id,
+                    profile_id: assignment.profile_id,
+                    success: false,
+                    message: Some("timeout".to_string()),
+                    finished_at: Some(chrono::Local::now().to_rfc3339()),
+                },
+                assignment.task_count,
+                Some(assignment.dispatched_at),
+            );
+        }
+        finish_round_if_done(&app, round_id);
+    });
+}
+
+/// 没有待回报的分配时结束本轮：仍在排队的档案已无节点可用，记为失败；
+/// 随后发送汇总事件与系统通知
+fn finish_round_if_done(app: &AppHandle, round_id: u64) {
+    let summary = {
+        let Ok(mut round) = ROUND.lock() else {
+            return;
+        };
+        if round.id != round_id || round.filling || !round.pending.is_empty() {
+            return;
+        }
+        if round.results.is_empty() && round.queued.is_empty() {
+            return;
+        }
+        let now = chrono::Local::now().to_rfc3339();
+        let unassigned: Vec<ClusterRunResult> = round
+            .queued
+            .drain(..)
+            .map(|profile_id| ClusterRunResult {
+                node_id: String::new(),
+                profile_id,
+                success: false,
+                message: Some("没有可用的集群节点".to_string()),
+                finished_at: Some(now.clone()),
+            })
+            .collect();
+        for result in &unassigned {
+            record_history(result, 0, None);
+        }
+        round.results.extend(unassigned);
+
+        let results = std::mem::take(&mut round.results);
+        let succeeded = results.iter().filter(|r| r.success).count();
+        ClusterSummary {
+            total: results.len(),
+            succeeded,
+            failed: results.len() - succeeded,
+            results,
+        }
+    };
+
+    info!(
+        "[cluster] Round {} finished: {}/{} succeeded",
+        round_id, summary.succeeded, summary.total
+    );
+    if let Err(e) = super::notifications::show(
+        "MXU",
+        &format!(
+            "集群任务完成：成功 {}，失败 {}",
+            summary.succeeded, summary.failed
+        ),
+        &[],
+        None,
+    ) {
+        warn!("[cluster] Failed to send notification: {}", e);
+    }
+    let _ = app.emit("cluster-summary", summary);
+}
+
+// ============================================================================
+// 远程 API 服务路由（见 api_server）
+// ============================================================================
+
+fn node_state() -> ClusterNodeState {
+    let dispatched_profile = NODE_JOB
+        .lock()
+        .ok()
+        .and_then(|job| job.as_ref().map(|job| job.request.profile.id.clone()));
+    ClusterNodeState {
+        busy: dispatched_profile.is_some() || super::runs::active_run_count() > 0,
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        dispatched_profile,
+    }
+}
+
+/// GET /status：本节点状态
+pub(crate) fn handle_status(request: &Request) -> Response {
+    if let Err(response) = authorize(request, "status") {
+        return response;
+    }
+    (200, serde_json::json!(node_state()))
+}
+
+/// POST /dispatch：保存主实例分发的配置档案，并交由前端按档案内容（设备、资源、任务）运行
+pub(crate) fn handle_dispatch(app: &AppHandle, request: &Request) -> Response {
+    if let Err(response) = authorize(request, "dispatch") {
+        return response;
+    }
+    let dispatch: ClusterDispatchRequest = match request.json() {
+        Ok(dispatch) => dispatch,
+        Err(e) => return error_response(400, e),
+    };
+    let result = (|| -> Result<(u64, Profile), String> {
+        let mut job = NODE_JOB.lock().map_err(|e| e.to_string())?;
+        if job.is_some() || super::runs::active_run_count() > 0 {
+            return Err("节点正忙".to_string());
+        }
+        let profile = super::profiles::save_dispatched(dispatch.profile.clone())?;
+        let id = NEXT_JOB_ID.fetch_add(1, Ordering::SeqCst);
+        *job = Some(NodeJob {
+            id,
+            request: dispatch,
+            run_id: None,
+        });
+        Ok((id, profile))
+    })();
+    let (job_id, profile) = match result {
+        Ok(accepted) => accepted,
+        Err(e) => {
+            warn!("[cluster] Rejected dispatch: {}", e);
+            return error_response(400, e);
+        }
+    };
+
+    if let Err(e) = app.emit("cluster-run-profile", &profile) {
+        warn!("[cluster] Failed to start dispatched profile: {}", e);
+        fail_job(Some(job_id), &e.to_string());
+        return error_response(500, e.to_string());
+    }
+    let timeout = profile
+        .content
+        .max_duration_secs
+        .filter(|&secs| secs > 0)
+        .map(|secs| Duration::from_secs(secs) + JOB_START_GRACE)
+        .unwrap_or(JOB_TIMEOUT);
+    std::thread::spawn(move || {
+        std::thread::sleep(timeout);
+        if fail_job(Some(job_id), "timeout") {
+            warn!("[cluster] Dispatched job {} timed out", job_id);
+        }
+    });
+
+    info!("[cluster] Accepted dispatched profile as {}", profile.id);
+    (200, serde_json::json!({ "profile_id": profile.id }))
+}
+
+/// 前端无法启动分发的档案时调用：结束本节点的分发任务并向主实例回报失败
+#[tauri::command]
+pub fn cluster_node_job_failed(message: String) -> Result<(), MxuError> {
+    if fail_job(None, &message) {
+        warn!("[cluster] Dispatched job failed to start: {}", message);
+    }
+    Ok(())
+}
+
+/// 结束分发任务并回报失败；job_id 为空时只结束尚未开始运行的任务。返回是否有任务被结束
+fn fail_job(job_id: Option<u64>, message: &str) -> bool {
+    let failed = NODE_JOB.lock().ok().and_then(|mut job| {
+        job.as_ref()
+            .is_some_and(|job| match job_id {
+                Some(id) => job.id == id,
+                None => job.run_id.is_none(),
+            })
+            .then(|| job.take())
+            .flatten()
+    });
+    match failed {
+        Some(job) => {
+            send_report(job.request, false, message.to_string());
+            true
+        }
+        None => false,
+    }
+}
+
+/// POST /cluster/report：节点回报运行结果（使用本轮分发的回报密钥鉴权）
+pub(crate) fn handle_report(app: &AppHandle, request: &Request) -> Response {
+    let authorized = ROUND.lock().ok().is_some_and(|round| {
+        !round.secret.is_empty() && request.bearer_token() == Some(round.secret.as_str())
+    });
+    if !authorized {
+        return error_response(403, "无效的回报密钥");
+    }
+    let result = request
+        .json::<ClusterRunResult>()
+        .and_then(|result| record_result(app, result));
+    match result {
+        Ok(()) => (200, serde_json::json!({})),
+        Err(e) => error_response(400, e),
+    }
+}
+
+/// 运行开始时关联本节点等待中的分发任务（runs 模块调用）
+pub fn on_run_started(run_id: &str) {
+    if let Ok(mut job) = NODE_JOB.lock() {
+        if let Some(job) = job.as_mut().filter(|job| job.run_id.is_none()) {
+            job.run_id = Some(run_id.to_string());
+        }
+    }
+}
+
+/// 运行结束时向主实例回报分发任务的结果（runs 模块调用）
+pub fn on_run_finished(run_id: &str, succeeded: bool, reason: Option<&str>) {
+    let finished = NODE_JOB.lock().ok().and_then(|mut job| {
+        job.as_ref()
+            .is_some_and(|job| job.run_id.as_deref() == Some(run_id))
+            .then(|| job.take())
+            .flatten()
+    });
+    let Some(job) = finished else {
+        return;
+    };
+    let message = reason.unwrap_or(super::runs::status_name(succeeded));
+    send_report(job.request, succeeded, message.to_string());
+}
+
+/// 在后台线程中向主实例回报分发任务的结果
+fn send_report(request: ClusterDispatchRequest, success: bool, message: String) {
+    let result = ClusterRunResult {
+        node_id: request.node_id,
+        profile_id: request.profile.id,
+        success,
+        message: Some(message),
+        finished_at: Some(chrono::Local::now().to_rfc3339()),
+    };
+    let (url, secret) = (request.report_url, request.report_secret);
+    std::thread::spawn(move || {
+        let response = reqwest::blocking::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .and_then(|client| client.post(&url).bearer_auth(&secret).json(&result).send());
+        match response {
+            Ok(r) if r.status().is_success() => {
+                info!(
+                    "[cluster] Reported result of {} to {}",
+                    result.profile_id, url
+                )
+            }
+            Ok(r) => warn!("[cluster] Report to {} returned HTTP {}", url, r.status()),
+            Err(e) => warn!("[cluster] Failed to report to {}: {}", url, e),
+        }
+    });
+}
@@ -0,0 +1,386 @@
+//! 设备颜色校准
+//!
+//! 部分模拟器的伽马/饱和度与参考设备不同，导致基于颜色的识别失效。
+//! 校准时将当前截图与参考图逐像素比对，最小二乘拟合颜色校正矩阵，
+//! 保存到配置档案的 device.color_calibration 中。
+//!
+//! 截图由 MaaFramework 内部获取，无法在识别前直接改写像素，因此启用校准后：
+//! - 任务启动时将资源中 ColorMatch 节点的 lower/upper 反向映射到设备颜色空间
+//! - sample_pixels 取色结果经校正后返回参考颜色，便于编写 ColorMatch 节点
+
+use log::{info, warn};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use maa_framework::resource::Resource;
+use tauri::State;
+
+use super::error::MxuError;
+use super::i18n::ErrorCode;
+use super::screencap_tools::cached_screencap_image;
+use super::types::{ColorCalibration, MaaState};
+
+/// 拟合时最多采样的像素数
+const MAX_SAMPLES: u32 = 20_000;
+
+/// ColorMatch 的 RGB 匹配方式（cv::COLOR_BGR2RGB），其他方式不做映射
+const COLOR_MATCH_METHOD_RGB: i64 = 4;
+
+/// 各实例当前启用的校准
+static ACTIVE: Mutex<Option<HashMap<String, ColorCalibration>>> = Mutex::new(None);
+
+/// 对单个颜色应用仿射矩阵（输入输出均为 0~255）
+fn transform(matrix: &[[f64; 4]; 3], rgb: [f64; 3]) -> [f64; 3] {
+    let [r, g, b] = rgb.map(|c| c / 255.0);
+    matrix.map(|row| (row[0] * r + row[1] * g + row[2] * b + row[3]) * 255.0)
+}
+
+/// 解 4x4 线性方程组（高斯消元，部分主元），奇异时返回 None
+#[allow(clippy::needless_range_loop)]
+fn solve4(mut a: [[f64; 4]; 4], mut y: [f64; 4]) -> Option<[f64; 4]> {
+    for col in 0..4 {
+        let pivot = (col..4).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col].abs() < 1e-9 {
+            return None;
+        }
+        a.swap(col, pivot);
+        y.swap(col, pivot);
+        for row in (col + 1)..4 {
+            let factor = a[row][col] / a[col][col];
+            for k in col..4 {
+                a[row][k] -= factor * a[col][k];
+            }
+            y[row] -= factor * y[col];
+        }
+    }
+    let mut x = [0.0; 4];
+    for row in (0..4).rev() {
+        let sum: f64 = ((row + 1)..4).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (y[row] - sum) / a[row][row];
+    }
+    Some(x)
+}
+
+/// 求 3x3 部分的逆映射：参考颜色 -> 设备颜色
+fn invert(matrix: &[[f64; 4]; 3]) -> Option<[[f64; 4]; 3]> {
+    let m = |r: usize, c: usize| matrix[r][c];
+    let det = m(0, 0) * (m(1, 1) * m(2, 2) - m(1, 2) * m(2, 1))
+        - m(0, 1) * (m(1, 0) * m(2, 2) - m(1, 2) * m(2, 0))
+        + m(0, 2) * (m(1, 0) * m(2, 1) - m(1, 1) * m(2, 0));
+    if det.abs() < 1e-9 {
+        return None;
+    }
+    let inv = [
+        [
+            (m(1, 1) * m(2, 2) - m(1, 2) * m(2, 1)) / det,
+            (m(0, 2) * m(2, 1) - m(0, 1) * m(2, 2)) / det,
+            (m(0, 1) * m(1, 2) - m(0, 2) * m(1, 1)) / det,
+        ],
+        [
+            (m(1, 2) * m(2, 0) - m(1, 0) * m(2, 2)) / det,
+            (m(0, 0) * m(2, 2) - m(0, 2) * m(2, 0)) / det,
+            (m(0, 2) * m(1, 0) - m(0, 0) * m(1, 2)) / det,
+        ],
+        [
+            (m(1, 0) * m(2, 1) - m(1, 1) * m(2, 0)) / det,
+            (m(0, 1) * m(2, 0) - m(0, 0) * m(2, 1)) / det,
+            (m(0, 0) * m(1, 1) - m(0, 1) * m(1, 0)) / det,
+        ],
+    ];
+    // x = inv * (y - t)
+    let t = [m(0, 3), m(1, 3), m(2, 3)];
+    Some(inv.map(|row| {
+        let offset = -(row[0] * t[0] + row[1] * t[1] + row[2] * t[2]);
+        [row[0], row[1], row[2], offset]
+    }))
+}
+
+/// 拟合颜色校正矩阵
+#[allow(clippy::needless_range_loop)]
+fn fit(
+    device: &image::RgbaImage,
+    reference: &image::RgbaImage,
+) -> Result<ColorCalibration, String> {
+    let (width, height) = device.dimensions();
+    let step = ((width as u64 * height as u64 / MAX_SAMPLES as u64) as f64)
+        .sqrt()
+        .ceil()
+        .max(1.0) as u32;
+
+    let mut samples = Vec::new();
+    for y in (0..height).step_by(step as usize) {
+        for x in (0..width).step_by(step as usize) {
+            let [dr, dg, db, _] = device.get_pixel(x, y).0;
+            let [rr, rg, rb, _] = reference.get_pixel(x, y).0;
+            samples.push((
+                [dr, dg, db].map(|c| c as f64 / 255.0),
+                [rr, rg, rb].map(|c| c as f64 / 255.0),
+            ));
+        }
+    }
+
+    // 正规方程 (XᵀX) w = Xᵀy，X 的每行为 [r, g, b, 1]
+    let mut xtx = [[0.0; 4]; 4];
+    let mut xty = [[0.0; 4]; 3];
+    for (d, r) in &samples {
+        let row = [d[0], d[1], d[2], 1.0];
+        for i in 0..4 {
+            for j in 0..4 {
+                xtx[i][j] += row[i] * row[j];
+            }
+            for c in 0..3 {
+                xty[c][i] += row[i] * r[c];
+            }
+        }
+    }
+    let mut matrix = [[0.0; 4]; 3];
+    for c in 0..3 {
+        matrix[c] = solve4(xtx, xty[c])
+            .ok_or("参考区域颜色过于单一，无法计算校正矩阵，请选择色彩丰富的画面")?;
+    }
+
+    let mean_error = |f: &dyn Fn([f64; 3]) -> [f64; 3]| {
+        let total: f64 = samples
+            .iter()
+            .map(|(d, r)| {
+                let out = f(d.map(|c| c * 255.0));
+                (0..3).map(|c| (out[c] - r[c] * 255.0).abs()).sum::<f64>() / 3.0
+            })
+            .sum();
+        total / samples.len().max(1) as f64
+    };
+
+    Ok(ColorCalibration {
+        matrix,
+        samples: samples.len(),
+        error_before: mean_error(&|rgb| rgb),
+        error_after: mean_error(&|rgb| transform(&matrix, rgb)),
+        created_at: chrono::Local::now().to_rfc3339(),
+    })
+}
+
+/// 获取实例当前启用的校准
+fn active_calibration(instance_id: &str) -> Option<ColorCalibration> {
+    ACTIVE.lock().ok()?.as_ref()?.get(instance_id).cloned()
+}
+
+/// 将校正应用到颜色（无校准时原样返回）
+pub fn correct_color(instance_id: &str, rgb: [u8; 3]) -> [u8; 3] {
+    match active_calibration(instance_id) {
+        Some(calibration) => transform(&calibration.matrix, rgb.map(|c| c as f64))
+            .map(|c| c.round().clamp(0.0, 255.0) as u8),
+        None => rgb,
+    }
+}
+
+/// 将 ColorMatch 的 [lower, upper] 范围映射到设备颜色空间（取变换后包围盒）
+#[allow(clippy::needless_range_loop)]
+fn map_bounds(inverse: &[[f64; 4]; 3], lower: &[f64], upper: &[f64]) -> ([i64; 3], [i64; 3]) {
+    let mut min = [f64::MAX; 3];
+    let mut max = [f64::MIN; 3];
+    for corner in 0..8 {
+        let rgb = [0, 1, 2].map(|c| {
+            if corner >> c & 1 == 0 {
+                lower[c]
+            } else {
+                upper[c]
+            }
+        });
+        let mapped = transform(inverse, rgb);
+        for c in 0..3 {
+            min[c] = min[c].min(mapped[c]);
+            max[c] = max[c].max(mapped[c]);
+        }
+    }
+    (
+        min.map(|v| v.floor().clamp(0.0, 255.0) as i64),
+        max.map(|v| v.ceil().clamp(0.0, 255.0) as i64),
+    )
+}
+
+/// 映射 lower/upper 字段（支持单个颜色或多组颜色），格式不符时返回 None
+fn map_color_params(
+    inverse: &[[f64; 4]; 3],
+    lower: &serde_json::Value,
+    upper: &serde_json::Value,
+) -> Option<(serde_json::Value, serde_json::Value)> {
+    let as_color = |v: &serde_json::Value| -> Option<Vec<f64>> {
+        let items: Vec<f64> = v.as_array()?.iter().filter_map(|c| c.as_f64()).collect();
+        (items.len() == 3).then_some(items)
+    };
+    if let (Some(lo), Some(hi)) = (as_color(lower), as_color(upper)) {
+        let (lo, hi) = map_bounds(inverse, &lo, &hi);
+        return Some((serde_json::json!(lo), serde_json::json!(hi)));
+    }
+    let (lowers, uppers) = (lower.as_array()?, upper.as_array()?);
+    let mut mapped_lower = Vec::new();
+    let mut mapped_upper = Vec::new();
+    for (lo, hi) in lowers.iter().zip(uppers) {
+        let (lo, hi) = map_bounds(inverse, &as_color(lo)?, &as_color(hi)?);
+        mapped_lower.push(serde_json::json!(lo));
+        mapped_upper.push(serde_json::json!(hi));
+    }
+    Some((mapped_lower.into(), mapped_upper.into()))
+}
+
+/// 为资源中的 RGB ColorMatch 节点生成颜色范围映射覆盖（兼容新旧两种 pipeline 格式）
+fn color_match_override(resource: &Resource, inverse: &[[f64; 4]; 3]) -> serde_json::Value {
+    let mut patch = serde_json::Map::new();
+    let nodes = resource.node_list().unwrap_or_default();
+    for name in nodes {
+        let Some(data) = resource
+            .get_node_data(&name)
+            .ok()
+            .flatten()
+            .and_then(|d| serde_json::from_str::<serde_json::Value>(&d).ok())
+        else {
+            continue;
+        };
+        // 新格式：{"recognition": {"type": "ColorMatch", "param": {...}}}
+        let (params, nested) = match data.get("recognition") {
+            Some(reco) if reco.get("type").and_then(|t| t.as_str()) == Some("ColorMatch") => {
+                (reco.get("param").cloned().unwrap_or_default(), true)
+            }
+            Some(reco) if reco.as_str() == Some("ColorMatch") => (data.clone(), false),
+            _ => continue,
+        };
+        let method = params
+            .get("method")
+            .and_then(|m| m.as_i64())
+            .unwrap_or(COLOR_MATCH_METHOD_RGB);
+        if method != COLOR_MATCH_METHOD_RGB {
+            continue;
+        }
+        let (Some(lower), Some(upper)) = (params.get("lower"), params.get("upper")) else {
+            continue;
+        };
+        let Some((lower, upper)) = map_color_params(inverse, lower, upper) else {
+            continue;
+        };
+        let bounds = serde_json::json!({ "lower": lower, "upper": upper });
+        let node_patch = if nested {
+            serde_json::json!({ "recognition": { "type": "ColorMatch", "param": bounds } })
+        } else {
+            bounds
+        };
+        patch.insert(name, node_patch);
+    }
+    serde_json::Value::Object(patch)
+}
+
+/// 生成实例的颜色校准覆盖（任务启动时追加到每个任务），未启用校准时返回 None
+pub fn calibration_override(state: &MaaState, instance_id: &str) -> Option<serde_json::Value> {
+    let calibration = active_calibration(instance_id)?;
+    let Some(inverse) = invert(&calibration.matrix) else {
+        warn!("[color_calibration] Calibration matrix is not invertible, skipped");
+        return None;
+    };
+    let instances = state.instances.lock().ok()?;
+    let resource = instances.get(instance_id)?.resource.as_ref()?;
+    let patch = color_match_override(resource, &inverse);
+    let count = patch.as_object().map_or(0, |p| p.len());
+    if count == 0 {
+        return None;
+    }
+    info!(
+        "[color_calibration] Remapped {} ColorMatch node(s) for instance {}",
+        count, instance_id
+    );
+    Some(patch)
+}
+
+/// 对当前截图执行颜色校准
+///
+/// - `reference_path`: 参考图（在标准设备上截取的同一画面）
+/// - `rect`: 参与比对的区域 [x, y, width, height]，为空时使用整张截图；参考图会缩放到该区域大小
+/// - `profile_id`: 指定时将结果保存到该配置档案的 device.color_calibration
+#[tauri::command]
+pub fn color_calibration_run(
+    state: State<Arc<MaaState>>,
+    instance_id: String,
+    reference_path: String,
+    rect: Option<[i32; 4]>,
+    profile_id: Option<String>,
+) -> Result<ColorCalibration, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    let frame = cached_screencap_image(&state, &instance_id)?;
+    let [x, y, width, height] = rect.unwrap_or([0, 0, frame.width() as i32, frame.height() as i32]);
+    if x < 0
+        || y < 0
+        || width <= 0
+        || height <= 0
+        || (x + width) as u32 > frame.width()
+        || (y + height) as u32 > frame.height()
+    {
+        return Err(MxuError::with_args(
+            ErrorCode::InvalidRegion,
+            [format!("[{}, {}, {}, {}]", x, y, width, height)],
+        ));
+    }
+    let (x, y, width, height) = (x as u32, y as u32, width as u32, height as u32);
+    let device = image::imageops::crop_imm(&frame, x, y, width, height).to_image();
+
+    let reference = image::open(Path::new(&reference_path))
+        .map_err(|e| format!("无法读取参考图 [{}]: {}", reference_path, e))?
+        .to_rgba8();
+    let reference = if reference.dimensions() == (width, height) {
+        reference
+    } else {
+        image::imageops::resize(
+            &reference,
+            width,
+            height,
+            image::imageops::FilterType::Triangle,
+        )
+    };
+
+    let calibration = fit(&device, &reference)?;
+    info!(
+        "[color_calibration] Calibrated instance {}: error {:.2} -> {:.2} ({} samples)",
+        instance_id, calibration.error_before, calibration.error_after, calibration.samples
+    );
+
+    if let Some(profile_id) = profile_id {
+        let mut profile = super::profiles::profile_get(profile_id.clone())?;
+        let value = serde_json::to_value(&calibration).map_err(|e| e.to_string())?;
+        match profile.content.device.as_object_mut() {
+            Some(device) => {
+                device.insert("color_calibration".to_string(), value);
+            }
+            None => {
+                profile.content.device = serde_json::json!({ "color_calibration": value });
+            }
+        }
+        super::profiles::profile_save(profile_id, profile.content)?;
+    }
+
+    color_calibration_set_active(instance_id, Some(calibration.clone()))?;
+    Ok(calibration)
+}
+
+/// 为实例启用（或传 None 关闭）颜色校准，通常在按配置档案连接设备后调用
+#[tauri::command]
+pub fn color_calibration_set_active(
+    instance_id: String,
+    calibration: Option<ColorCalibration>,
+) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    let mut active = ACTIVE.lock().map_err(|e| e.to_string())?;
+    let map = active.get_or_insert_with(HashMap::new);
+    match calibration {
+        Some(calibration) => {
+            map.insert(instance_id, calibration);
+        }
+        None => {
+            map.remove(&instance_id);
+        }
+    }
+    Ok(())
+}
+
+/// 获取实例当前启用的颜色校准
+#[tauri::command]
+pub fn color_calibration_get_active(instance_id: String) -> Option<ColorCalibration> {
+    active_calibration(&instance_id)
+}
@@ -0,0 +1,361 @@
+//! 从其他 MaaFramework GUI 导入配置
+//!
+//! 读取相邻工具的配置文件，将任务选择、选项与设备信息转换为 MXU 配置档案：
+//! - MaaPiCli：config/maa_pi_config.json（task 数组，选项值为 case 名称）
+//! - MFAAvalonia：config/config.json 中的 TaskItems（选项值为 case 下标）
+//!
+//! 选项类型与 case 名称按当前项目的 interface.json 解析，找不到对应定义的任务或选项会跳过并给出提示
+
+use log::info;
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Map, Value};
+
+use super::error::MxuError;
+use super::i18n::ErrorCode;
+use super::types::{ImportPreview, ImportSource, ImportedTask, Profile, ProfileContent};
+use super::utils::get_exe_directory;
+
+/// 转换结果
+struct Converted {
+    source: ImportSource,
+    path: PathBuf,
+    resource: Option<String>,
+    controller: Option<String>,
+    device: Value,
+    tasks: Vec<ImportedTask>,
+    /// MXU 任务列表（SavedTask 格式）
+    saved_tasks: Vec<Value>,
+    warnings: Vec<String>,
+}
+
+/// 按工具目录或配置文件路径定位配置文件
+fn locate(path: &Path) -> Result<(ImportSource, PathBuf), String> {
+    let candidates: Vec<PathBuf> = if path.is_dir() {
+        vec![
+            path.join("config").join("maa_pi_config.json"),
+            path.join("config").join("config.json"),
+            path.join("maa_pi_config.json"),
+        ]
+    } else {
+        vec![path.to_path_buf()]
+    };
+    for file in candidates {
+        if !file.is_file() {
+            continue;
+        }
+        let json = read_json(&file)?;
+        if json.get("task").is_some_and(Value::is_array) {
+            return Ok((ImportSource::MaaPiCli, file));
+        }
+        if get_ci(&json, "TaskItems").is_some_and(Value::is_array) {
+            return Ok((ImportSource::MfaAvalonia, file));
+        }
+    }
+    Err(format!("未识别的配置格式: {}", path.display()))
+}
+
+fn read_json(path: &Path) -> Result<Value, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("无法读取配置文件 [{}]: {}", path.display(), e))?;
+    serde_json::from_str(content.trim_start_matches('\u{feff}'))
+        .map_err(|e| format!("无法解析配置文件 [{}]: {}", path.display(), e))
+}
+
+/// 忽略大小写读取字段（MFAAvalonia 的字段为 PascalCase）
+fn get_ci<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    value
+        .as_object()?
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(key))
+        .map(|(_, v)| v)
+}
+
+fn get_str<'a>(value: &'a Value, key: &str) -> Option<&'a str> {
+    get_ci(value, key).and_then(Value::as_str)
+}
+
+/// 当前项目的 interface.json（不存在时为空对象）
+fn load_interface() -> Value {
+    get_exe_directory()
+        .ok()
+        .map(|dir| dir.join("interface.json"))
+        .and_then(|path| read_json(&path).ok())
+        .unwrap_or_else(|| json!({}))
+}
+
+/// 开关选项的值是否表示开启
+fn is_switch_on(case_name: &str) -> bool {
+    matches!(
+        case_name.to_ascii_lowercase().as_str(),
+        "yes" | "true" | "on" | "1"
+    )
+}
+
+/// 将单个选项值转换为 MXU 的 OptionValue；raw 为 case 名称、case 下标或输入值对象
+fn convert_option(definition: &Value, raw: &Value) -> Option<Value> {
+    let cases: Vec<&str> = definition
+        .get("cases")
+        .and_then(Value::as_array)
+        .map(|cases| {
+            cases
+                .iter()
+                .filter_map(|c| c.get("name").and_then(Value::as_str))
+                .collect()
+        })
+        .unwrap_or_default();
+    let case_name = match raw {
+        Value::String(name) => Some(name.as_str()),
+        Value::Number(n) => n.as_u64().and_then(|i| cases.get(i as usize).copied()),
+        _ => None,
+    };
+
+    match definition
+        .get("type")
+        .and_then(Value::as_str)
+        .unwrap_or("select")
+    {
+        "switch" => {
+            let on = match raw {
+                Value::Bool(b) => *b,
+                _ => is_switch_on(case_name?),
+            };
+            Some(json!({ "type": "switch", "value": on }))
+        }
+        "input" => {
+            let values: Map<String, Value> = match raw {
+                Value::Object(map) => map
+                    .iter()
+                    .map(|(k, v)| {
+                        let text = v.as_str().map_or_else(|| v.to_string(), str::to_string);
+                        (k.clone(), Value::String(text))
+                    })
+                    .collect(),
+                _ => return None,
+            };
+            Some(json!({ "type": "input", "values": values }))
+        }
+        _ => {
+            let case_name = case_name.filter(|name| cases.contains(name))?;
+            Some(json!({ "type": "select", "caseName": case_name }))
+        }
+    }
+}
+
+/// 转换一个任务：name 为 interface 中的任务名，options 为 (选项名, 原始值)
+fn convert_task(
+    interface: &Value,
+    index: usize,
+    name: &str,
+    enabled: bool,
+    options: Vec<(String, Value)>,
+    warnings: &mut Vec<String>,
+) -> Option<(ImportedTask, Value)> {
+    let known_task = interface
+        .get("task")
+        .and_then(Value::as_array)
+        .is_none_or(|tasks| {
+            tasks
+                .iter()
+                .any(|t| t.get("name").and_then(Value::as_str) == Some(name))
+        });
+    if !known_task {
+        warnings.push(format!("当前项目中没有任务「{}」，已跳过", name));
+        return None;
+    }
+
+    let mut option_values = Map::new();
+    for (option_name, raw) in options {
+        let definition = interface.get("option").and_then(|o| o.get(&option_name));
+        let converted = match definition {
+            Some(definition) => convert_option(definition, &raw),
+            // 无 interface 定义时按值的形态推断
+            None => match &raw {
+                Value::String(case) => Some(json!({ "type": "select", "caseName": case })),
+                Value::Bool(on) => Some(json!({ "type": "switch", "value": on })),
+                _ => None,
+            },
+        };
+        match converted {
+            Some(value) => {
+                option_values.insert(option_name, value);
+            }
+            None => warnings.push(format!(
+                "任务「{}」的选项「{}」的值 {} 无法转换，已使用默认值",
+                name, option_name, raw
+            )),
+        }
+    }
+
+    let imported = ImportedTask {
+        name: name.to_string(),
+        enabled,
+        option_count: option_values.len(),
+    };
+    let saved = json!({
+        "id": format!("imported-{}", index),
+        "taskName": name,
+        "enabled": enabled,
+        "optionValues": option_values,
+    });
+    Some((imported, saved))
+}
+
+fn convert(path: &Path) -> Result<Converted, String> {
+    let (source, file) = locate(path)?;
+    let json = read_json(&file)?;
+    let interface = load_interface();
+    let mut warnings = Vec::new();
+    if interface.get("task").is_none() {
+        warnings.push("未找到当前项目的 interface.json，选项类型按值推断".to_string());
+    }
+
+    // (任务名, 是否启用, 选项列表)
+    let raw_tasks: Vec<(String, bool, Vec<(String, Value)>)> = match source {
+        ImportSource::MaaPiCli => json
+            .get("task")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|task| {
+                let name = task.get("name")?.as_str()?.to_string();
+                let options = task
+                    .get("option")
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|o| {
+                        Some((
+                            o.get("name")?.as_str()?.to_string(),
+                            o.get("value")?.clone(),
+                        ))
+                    })
+                    .collect();
+                Some((name, true, options))
+            })
+            .collect(),
+        ImportSource::MfaAvalonia => get_ci(&json, "TaskItems")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|item| {
+                let interface_item = get_ci(item, "InterfaceItem").unwrap_or(item);
+                let name = get_str(interface_item, "name")?.to_string();
+                let enabled = get_ci(item, "IsChecked")
+                    .or_else(|| get_ci(item, "IsCheckedWithNull"))
+                    .and_then(Value::as_bool)
+                    .unwrap_or(true);
+                let options = get_ci(interface_item, "option")
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|o| {
+                        let value = get_ci(o, "index")
+                            .or_else(|| get_ci(o, "value"))
+                            .or_else(|| get_ci(o, "data"))?;
+                        Some((get_str(o, "name")?.to_string(), value.clone()))
+                    })
+                    .collect();
+                Some((name, enabled, options))
+            })
+            .collect(),
+    };
+
+    let mut tasks = Vec::new();
+    let mut saved_tasks = Vec::new();
+    for (index, (name, enabled, options)) in raw_tasks.into_iter().enumerate() {
+        if let Some((imported, saved)) =
+            convert_task(&interface, index, &name, enabled, options, &mut warnings)
+        {
+            tasks.push(imported);
+            saved_tasks.push(saved);
+        }
+    }
+
+    let (resource, controller, device) = match source {
+        ImportSource::MaaPiCli => {
+            let controller = json
+                .get("controller")
+                .and_then(|c| c.get("name"))
+                .and_then(Value::as_str)
+                .map(str::to_string);
+            let adb = json.get("adb");
+            let window = json.get("win32").or_else(|| json.get("desktop"));
+            let device = json!({
+                "controllerName": controller,
+                "adbPath": adb.and_then(|a| a.get("adb_path")),
+                "address": adb.and_then(|a| a.get("address")),
+                "windowName": window.and_then(|w| w.get("window_name")),
+            });
+            let resource = json
+                .get("resource")
+                .and_then(Value::as_str)
+                .map(str::to_string);
+            (resource, controller, device)
+        }
+        ImportSource::MfaAvalonia => {
+            let controller = get_str(&json, "CurrentController").map(str::to_string);
+            let device = json!({
+                "controllerName": controller,
+                "adbPath": get_ci(&json, "AdbPath"),
+                "address": get_ci(&json, "AdbAddress"),
+            });
+            let resource = get_str(&json, "Resource")
+                .or_else(|| get_str(&json, "CurrentResource"))
+                .map(str::to_string);
+            (resource, controller, device)
+        }
+    };
+
+    Ok(Converted {
+        source,
+        path: file,
+        resource,
+        controller,
+        device,
+        tasks,
+        saved_tasks,
+        warnings,
+    })
+}
+
+/// 预览导入结果（不写入）
+#[tauri::command]
+pub fn import_preview(path: String) -> Result<ImportPreview, MxuError> {
+    let converted = convert(Path::new(&path))?;
+    Ok(ImportPreview {
+        source: converted.source,
+        path: converted.path.to_string_lossy().to_string(),
+        resource: converted.resource,
+        controller: converted.controller,
+        tasks: converted.tasks,
+        warnings: converted.warnings,
+    })
+}
+
+/// 导入为新的配置档案
+#[tauri::command]
+pub fn import_as_profile(path: String, name: String) -> Result<Profile, MxuError> {
+    let converted = convert(Path::new(&path))?;
+    if converted.saved_tasks.is_empty() {
+        return Err(MxuError::new(ErrorCode::NothingToImport));
+    }
+    let task_count = converted.saved_tasks.len();
+    let profile = super::profiles::profile_create(
+        name,
+        ProfileContent {
+            device: converted.device,
+            resource: converted.resource,
+            tasks: Value::Array(converted.saved_tasks),
+            ..Default::default()
+        },
+    )?;
+    info!(
+        "[config_import] Imported {} task(s) from {:?} ({}) as profile {}",
+        task_count,
+        converted.source,
+        converted.path.display(),
+        profile.id
+    );
+    Ok(profile)
+}
@@ -0,0 +1,225 @@
+//! 用户配置加载与迁移
+//!
+//! 读取 config/mxu-<project>.json，按 schema_version 逐步执行迁移函数，
+//! 迁移前备份原文件，并返回变更说明；未知字段原样保留
+
+use log::{info, warn};
+use serde_json::{Map, Value};
+use std::path::PathBuf;
+
+use super::error::MxuError;
+use super::i18n::ErrorCode;
+use super::types::ConfigLoadResult;
+use super::utils::{get_app_data_dir, merge_json, save_json_config};
+
+/// 当前配置 schema 版本
+pub const CONFIG_SCHEMA_VERSION: u32 = 1;
+
+/// 迁移函数：将配置从 N 版本升级到 N+1，返回变更说明
+type Migrator = fn(&mut Map<String, Value>) -> Vec<String>;
+
+/// 按顺序排列的迁移函数，下标 i 对应 v{i} -> v{i+1}
+const MIGRATIONS: &[Migrator] = &[migrate_v0_to_v1];
+
+/// v0 -> v1：引入 schema_version，规范 instances/settings 的类型
+fn migrate_v0_to_v1(config: &mut Map<String, Value>) -> Vec<String> {
+    let mut changes = Vec::new();
+
+    if !config.get("instances").is_some_and(Value::is_array) {
+        config.insert("instances".to_string(), Value::Array(Vec::new()));
+        changes.push("instances 字段缺失或类型错误，已重置为空列表".to_string());
+    }
+    if !config.get("settings").is_some_and(Value::is_object) {
+        config.insert("settings".to_string(), Value::Object(Map::new()));
+        changes.push("settings 字段缺失或类型错误，已重置为默认设置".to_string());
+    }
+
+    changes
+}
+
+/// 获取配置文件路径
+fn config_file_path(project_name: Option<&str>) -> Result<PathBuf, String> {
+    let file_name = match project_name {
+        Some(name) if !name.is_empty() => {
+            if name.contains(['/', '\\']) || name.contains("..") {
+                return Err(format!("非法的项目名称: {}", name));
+            }
+            format!("mxu-{}.json", name)
+        }
+        _ => "mxu.json".to_string(),
+    };
+    Ok(get_app_data_dir()?.join("config").join(file_name))
+}
+
+/// 读取配置的 schema 版本，缺失视为 0
+fn schema_version_of(config: &Map<String, Value>) -> u32 {
+    config
+        .get("schema_version")
+        .and_then(Value::as_u64)
+        .map(|v| v as u32)
+        .unwrap_or(0)
+}
+
+/// 对配置执行迁移，返回 (原版本, 变更说明)
+pub fn migrate_config(config: &mut Map<String, Value>) -> (u32, Vec<String>) {
+    let from_version = schema_version_of(config);
+    let mut changes = Vec::new();
+
+    for (version, migrator) in MIGRATIONS.iter().enumerate().skip(from_version as usize) {
+        let step_changes = migrator(config);
+        info!(
+            "[config_migration] Migrated v{} -> v{} ({} change(s))",
+            version,
+            version + 1,
+            step_changes.len()
+        );
+        changes.extend(step_changes);
+    }
+
+    if from_version < CONFIG_SCHEMA_VERSION {
+        config.insert(
+            "schema_version".to_string(),
+            Value::from(CONFIG_SCHEMA_VERSION),
+        );
+        changes.push(format!(
+            "schema_version: {} -> {}",
+            from_version, CONFIG_SCHEMA_VERSION
+        ));
+    }
+
+    (from_version, changes)
+}
+
+/// 加载用户配置，必要时迁移到当前 schema 版本并写回（迁移前备份）
+#[tauri::command]
+pub fn load_user_config(project_name: Option<String>) -> Result<ConfigLoadResult, MxuError> {
+    let path = config_file_path(project_name.as_deref())?;
+    if !path.exists() {
+        return Ok(ConfigLoadResult {
+            config: None,
+            migrated: false,
+            from_version: CONFIG_SCHEMA_VERSION,
+            to_version: CONFIG_SCHEMA_VERSION,
+            backup_path: None,
+            changes: Vec::new(),
+        });
+    }
+
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("无法读取配置文件 [{}]: {}", path.display(), e))?;
+    let value: Value = serde_json::from_str(&content)
+        .map_err(|e| format!("无法解析配置文件 [{}]: {}", path.display(), e))?;
+    let Value::Object(mut config) = value else {
+        return Err(MxuError::with_args(
+            ErrorCode::ConfigNotObject,
+            [path.display()],
+        ));
+    };
+
+    let from_version = schema_version_of(&config);
+    if from_version > CONFIG_SCHEMA_VERSION {
+        // 新版本写入的配置：原样返回，不做任何修改
+        warn!(
+            "[config_migration] Config schema v{} is newer than supported v{}",
+            from_version, CONFIG_SCHEMA_VERSION
+        );
+        return Ok(ConfigLoadResult {
+            config: Some(Value::Object(config)),
+            migrated: false,
+            from_version,
+            to_version: from_version,
+            backup_path: None,
+            changes: vec![format!(
+                "配置版本 v{} 高于当前支持的 v{}，未做迁移",
+                from_version, CONFIG_SCHEMA_VERSION
+            )],
+        });
+    }
+    if from_version == CONFIG_SCHEMA_VERSION {
+        return Ok(ConfigLoadResult {
+            config: Some(Value::Object(config)),
+            migrated: false,
+            from_version,
+            to_version: CONFIG_SCHEMA_VERSION,
+            backup_path: None,
+            changes: Vec::new(),
+        });
+    }
+
+    // 迁移前备份原文件
+    let backup_dir = path
+        .parent()
+        .map(|p| p.join("backup"))
+        .ok_or_else(|| MxuError::internal("无法获取配置目录"))?;
+    std::fs::create_dir_all(&backup_dir)
+        .map_err(|e| format!("无法创建备份目录 [{}]: {}", backup_dir.display(), e))?;
+    let backup_path = backup_dir.join(format!(
+        "{}.v{}.{}.bak",
+        path.file_name().unwrap_or_default().to_string_lossy(),
+        from_version,
+        chrono::Local::now().format("%Y%m%d%H%M%S")
+    ));
+    std::fs::copy(&path, &backup_path).map_err(|e| format!("无法备份配置文件: {}", e))?;
+
+    let (_, changes) = migrate_config(&mut config);
+    let config = Value::Object(config);
+
+    let content =
+        serde_json::to_string_pretty(&config).map_err(|e| format!("无法序列化配置: {}", e))?;
+    std::fs::write(&path, content)
+        .map_err(|e| format!("无法写入配置文件 [{}]: {}", path.display(), e))?;
+
+    info!(
+        "[config_migration] Config migrated v{} -> v{}, backup: {}",
+        from_version,
+        CONFIG_SCHEMA_VERSION,
+        backup_path.display()
+    );
+
+    Ok(ConfigLoadResult {
+        config: Some(config),
+        migrated: true,
+        from_version,
+        to_version: CONFIG_SCHEMA_VERSION,
+        backup_path: Some(backup_path.to_string_lossy().to_string()),
+        changes,
+    })
+}
+
+/// 读取磁盘上的配置对象，文件不存在或无法解析时返回 None
+fn read_stored_config(path: &std::path::Path) -> Option<Map<String, Value>> {
+    let content = std::fs::read_to_string(path).ok()?;
+    match serde_json::from_str(&content) {
+        Ok(Value::Object(config)) => Some(config),
+        _ => None,
+    }
+}
+
+/// 保存用户配置：合并到磁盘上的配置之上，前端未知的字段得以保留；
+/// 写入当前 schema 版本（磁盘上已是更高版本时保留该版本号）；访客模式下拒绝
+#[tauri::command]
+pub fn save_user_config(
+    project_name: Option<String>,
+    config: Map<String, Value>,
+) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    let path = config_file_path(project_name.as_deref())?;
+    let stored = read_stored_config(&path).unwrap_or_default();
+    let stored_version = schema_version_of(&stored);
+    if stored_version > CONFIG_SCHEMA_VERSION {
+        // 新版本写入的配置：不降低版本号，避免新版本再次打开时重复迁移
+        warn!(
+            "[config_migration] Keeping stored schema v{} (supported v{})",
+            stored_version, CONFIG_SCHEMA_VERSION
+        );
+    }
+    let mut merged = Value::Object(stored);
+    merge_json(&mut merged, &Value::Object(config));
+    if let Value::Object(config) = &mut merged {
+        config.insert(
+            "schema_version".to_string(),
+            stored_version.max(CONFIG_SCHEMA_VERSION).into(),
+        );
+    }
+    save_json_config(&path, &merged)
+}
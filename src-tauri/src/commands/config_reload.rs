@@ -0,0 +1,264 @@
+//! 配置热重载
+//!
+//! 监听 config/ 与 profiles/ 目录，配置文件被外部编辑或从备份恢复后，
+//! 校验并重新加载后端缓存的配置（快捷键、日志过滤、调用超时、推理选项），
+//! 然后发送 config-reloaded 事件，由前端重新读取用户设置（含定时任务）与配置档案。
+//! 内容未变化的写入不触发重载；无法解析的文件保持当前配置并在事件中报告。
+//! 访客模式状态不热重载，需重启后生效（避免绕过 PIN 退出访客模式）
+
+use log::{info, warn};
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeSet, HashMap};
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{LazyLock, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter};
+
+use super::error::MxuError;
+use super::types::{ConfigReloadFailure, ConfigReloadedEvent};
+use super::utils::get_app_data_dir;
+
+/// 防抖时间：最后一次变化后等待的时长
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// 后端运行中频繁写入的状态文件，不触发重载
+const IGNORED: &[&str] = &["recognition_stats.json"];
+
+/// 需要重启才能生效的配置文件
+const RESTART_REQUIRED: &[&str] = &["guest_mode.json"];
+
+/// 各配置文件最近一次的内容哈希（文件不存在时无记录）
+static HASHES: LazyLock<Mutex<HashMap<PathBuf, u64>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn watched_dirs() -> Result<[PathBuf; 2], String> {
+    let data_dir = get_app_data_dir()?;
+    Ok([data_dir.join("config"), data_dir.join("profiles")])
+}
+
+fn is_json(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+        && !path
+            .file_name()
+            .is_some_and(|n| IGNORED.contains(&n.to_string_lossy().as_ref()))
+}
+
+fn content_hash(path: &Path) -> Option<u64> {
+    let content = std::fs::read(path).ok()?;
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    Some(hasher.finish())
+}
+
+/// 更新内容哈希，返回内容是否变化（新增与删除都视为变化）
+fn update_hash(path: &Path) -> bool {
+    let hash = content_hash(path);
+    let Ok(mut hashes) = HASHES.lock() else {
+        return true;
+    };
+    let previous = match hash {
+        Some(hash) => hashes.insert(path.to_path_buf(), hash),
+        None => hashes.remove(path),
+    };
+    previous != hash
+}
+
+/// 目录下的全部 JSON 文件
+fn json_files(dir: &Path) -> Vec<PathBuf> {
+    std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.is_file() && is_json(p))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn parse_json(path: &Path) -> Result<serde_json::Value, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| format!("无法读取文件: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("无法解析文件: {}", e))
+}
+
+/// 校验并重新加载单个文件，返回 Ok(true) 表示需要重启后生效
+fn reload_file(
+    app: &AppHandle,
+    path: &Path,
+    event: &mut ConfigReloadedEvent,
+) -> Result<bool, String> {
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let in_profiles = path
+        .parent()
+        .and_then(Path::file_name)
+        .is_some_and(|n| n == "profiles");
+
+    if in_profiles {
+        event.profiles_changed = true;
+        if path.exists() {
+            let id = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default();
+            super::profiles::profile_get(id)?;
+        }
+        return Ok(false);
+    }
+    if RESTART_REQUIRED.contains(&file_name.as_str()) {
+        return Ok(true);
+    }
+    if !path.exists() {
+        // 删除的文件在下次读取时按默认值处理
+        match file_name.as_str() {
+            "hotkeys.json" => super::hotkeys::reload_hotkeys(app)?,
+            "log_config.json" => super::log_config::reload_log_config()?,
+            "ffi_timeouts.json" => super::ffi_guard::reload_ffi_timeouts()?,
+            "inference.json" => super::inference::reload_inference_options()?,
+            _ => {}
+        }
+        if file_name.starts_with("mxu") {
+            event.settings_changed = true;
+        }
+        return Ok(false);
+    }
+
+    let json = parse_json(path)?;
+    match file_name.as_str() {
+        "hotkeys.json" => super::hotkeys::reload_hotkeys(app)?,
+        "log_config.json" => super::log_config::reload_log_config()?,
+        "ffi_timeouts.json" => super::ffi_guard::reload_ffi_timeouts()?,
+        "inference.json" => super::inference::reload_inference_options()?,
+        // 前端用户设置（mxu.json / mxu-<project>.json），定时任务也保存在其中
+        name if name.starts_with("mxu") => {
+            if !json.get("settings").is_none_or(|s| s.is_object()) {
+                return Err("settings 字段类型错误".to_string());
+            }
+            event.settings_changed = true;
+        }
+        // 其余配置在每次使用时读取，校验格式即可
+        _ => {}
+    }
+    Ok(false)
+}
+
+/// 重新加载给定文件并发送 config-reloaded 事件
+fn reload(app: &AppHandle, paths: BTreeSet<PathBuf>) -> ConfigReloadedEvent {
+    let mut event = ConfigReloadedEvent {
+        reloaded: Vec::new(),
+        failed: Vec::new(),
+        restart_required: Vec::new(),
+        settings_changed: false,
+        profiles_changed: false,
+    };
+    for path in paths {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        match reload_file(app, &path, &mut event) {
+            Ok(false) => event.reloaded.push(name),
+            Ok(true) => event.restart_required.push(name),
+            Err(error) => {
+                warn!("[config_reload] Failed to reload {}: {}", name, error);
+                event.failed.push(ConfigReloadFailure { file: name, error });
+            }
+        }
+    }
+    info!(
+        "[config_reload] Reloaded {} file(s), {} failed, {} require restart",
+        event.reloaded.len(),
+        event.failed.len(),
+        event.restart_required.len()
+    );
+    let _ = app.emit("config-reloaded", event.clone());
+    event
+}
+
+/// 启动配置目录监听（setup 中调用）
+pub fn spawn_config_watcher(app: AppHandle) {
+    let dirs = match watched_dirs() {
+        Ok(dirs) => dirs,
+        Err(e) => {
+            warn!(
+                "[config_reload] Failed to resolve config directories: {}",
+                e
+            );
+            return;
+        }
+    };
+    // 记录启动时的内容，之后只有内容变化才触发重载
+    for path in dirs.iter().flat_map(|dir| json_files(dir)) {
+        update_hash(&path);
+    }
+
+    thread::spawn(move || {
+        let (tx, rx) = mpsc::channel::<PathBuf>();
+        let mut watcher =
+            match notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+                let Ok(event) = res else {
+                    return;
+                };
+                if matches!(
+                    event.kind,
+                    EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+                ) {
+                    for path in event.paths.into_iter().filter(|p| is_json(p)) {
+                        let _ = tx.send(path);
+                    }
+                }
+            }) {
+                Ok(watcher) => watcher,
+                Err(e) => {
+                    warn!("[config_reload] Failed to create watcher: {}", e);
+                    return;
+                }
+            };
+        for dir in &dirs {
+            let _ = std::fs::create_dir_all(dir);
+            if let Err(e) = watcher.watch(dir, RecursiveMode::NonRecursive) {
+                warn!("[config_reload] Failed to watch {}: {}", dir.display(), e);
+            }
+        }
+        info!("[config_reload] Watching configuration directories");
+
+        while let Ok(first) = rx.recv() {
+            let mut changed = BTreeSet::from([first]);
+            // 防抖：持续收集直到一段时间内没有新变化
+            loop {
+                match rx.recv_timeout(DEBOUNCE) {
+                    Ok(path) => {
+                        changed.insert(path);
+                    }
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+            }
+            changed.retain(|p| update_hash(p));
+            if !changed.is_empty() {
+                reload(&app, changed);
+            }
+        }
+    });
+}
+
+/// 立即重新加载全部配置（不论内容是否变化）
+#[tauri::command]
+pub fn config_reload_now(app: AppHandle) -> Result<ConfigReloadedEvent, MxuError> {
+    let paths: BTreeSet<PathBuf> = watched_dirs()?
+        .iter()
+        .flat_map(|dir| json_files(dir))
+        .collect();
+    for path in &paths {
+        update_hash(path);
+    }
+    Ok(reload(&app, paths))
+}
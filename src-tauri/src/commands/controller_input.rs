@@ -0,0 +1,128 @@
+//! 控制器输入透传
+//!
+//! 任务暂停等待人工处理时，前端可通过遥控面板直接向已连接的控制器发送点击、滑动、按键与文本输入。
+//! 与 maa_post_screencap 相同，命令返回操作 ID，完成状态通过 maa-callback 事件通知
+
+use log::info;
+use std::sync::Arc;
+
+use tauri::State;
+
+use super::error::MxuError;
+use super::i18n::ErrorCode;
+use super::maa_core::connected_controller;
+use super::types::MaaState;
+
+/// 未指定时的滑动时长（毫秒）
+const DEFAULT_SWIPE_DURATION_MS: i32 = 200;
+
+fn ensure_point(x: i32, y: i32) -> Result<(), MxuError> {
+    if x < 0 || y < 0 {
+        return Err(MxuError::with_args(ErrorCode::InvalidCoordinates, [x, y]));
+    }
+    Ok(())
+}
+
+/// 点击
+#[tauri::command]
+pub fn controller_click(
+    state: State<Arc<MaaState>>,
+    instance_id: String,
+    x: i32,
+    y: i32,
+) -> Result<i64, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    ensure_point(x, y)?;
+    info!("controller_click: {} ({}, {})", instance_id, x, y);
+    let controller = connected_controller(&state, &instance_id)?;
+
+    Ok(super::ffi_guard::call(
+        &instance_id,
+        "post_click",
+        move || controller.post_click(x, y).map_err(|e| e.to_string()),
+    )??)
+}
+
+/// 滑动
+/// duration_ms: 滑动时长，默认 200 毫秒
+#[tauri::command]
+pub fn controller_swipe(
+    state: State<Arc<MaaState>>,
+    instance_id: String,
+    x1: i32,
+    y1: i32,
+    x2: i32,
+    y2: i32,
+    duration_ms: Option<i32>,
+) -> Result<i64, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    ensure_point(x1, y1)?;
+    ensure_point(x2, y2)?;
+    let duration = duration_ms
+        .filter(|d| *d > 0)
+        .unwrap_or(DEFAULT_SWIPE_DURATION_MS);
+    info!(
+        "controller_swipe: {} ({}, {}) -> ({}, {}) in {}ms",
+        instance_id, x1, y1, x2, y2, duration
+    );
+    let controller = connected_controller(&state, &instance_id)?;
+
+    Ok(super::ffi_guard::call(
+        &instance_id,
+        "post_swipe",
+        move || {
+            controller
+                .post_swipe(x1, y1, x2, y2, duration)
+                .map_err(|e| e.to_string())
+        },
+    )??)
+}
+
+/// 按键
+/// keycode: ADB 控制器为 Android KeyEvent 键码，Win32 控制器为虚拟键码
+#[tauri::command]
+pub fn controller_key(
+    state: State<Arc<MaaState>>,
+    instance_id: String,
+    keycode: i32,
+) -> Result<i64, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    info!("controller_key: {} keycode {}", instance_id, keycode);
+    let controller = connected_controller(&state, &instance_id)?;
+
+    Ok(super::ffi_guard::call(
+        &instance_id,
+        "post_click_key",
+        move || {
+            controller
+                .post_click_key(keycode)
+                .map_err(|e| e.to_string())
+        },
+    )??)
+}
+
+/// 输入文本
+#[tauri::command]
+pub fn controller_input_text(
+    state: State<Arc<MaaState>>,
+    instance_id: String,
+    text: String,
+) -> Result<i64, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    if text.is_empty() {
+        return Err(MxuError::new(ErrorCode::EmptyInputText));
+    }
+    // 不记录文本内容，可能包含密码等敏感信息
+    info!(
+        "controller_input_text: {} ({} chars)",
+        instance_id,
+        text.chars().count()
+    );
+    let controller = connected_controller(&state, &instance_id)?;
+
+    Ok(super::ffi_guard::call(
+        &instance_id,
+        "post_input_text",
+        move || controller.post_input_text(&text).map_err(|e| e.to_string()),
+    )??)
+}
@@ -0,0 +1,307 @@
+//! 崩溃报告
+//!
+//! 安装 panic hook，在后端 panic 时将信息、调用栈与最近的日志写入 debug/crashes，
+//! 下次启动时由前端通过 crash_reports_list 查询未查看的报告并提示用户。
+//! 用户在 config/crash_reporter.json 中开启上传并配置地址后，未上传的报告会在启动时自动上传
+
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use super::error::MxuError;
+use super::i18n::ErrorCode;
+use super::types::{CrashReportInfo, CrashReporterConfig};
+use super::utils::{
+    build_user_agent, get_app_data_dir, get_logs_dir, read_backend_log_tail, save_json_config,
+};
+
+/// 崩溃报告目录（debug 下）
+const CRASHES_DIR: &str = "crashes";
+/// 报告中附带的日志末尾字节数
+const LOG_TAIL_BYTES: u64 = 32 * 1024;
+/// 上传超时
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 配置文件读写锁
+static CONFIG_LOCK: Mutex<()> = Mutex::new(());
+
+/// 配置文件结构（上传配置与报告状态）
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CrashReporterState {
+    #[serde(flatten)]
+    config: CrashReporterConfig,
+    /// 已查看的报告
+    #[serde(default)]
+    acknowledged: Vec<String>,
+    /// 已上传的报告
+    #[serde(default)]
+    uploaded: Vec<String>,
+}
+
+fn crashes_dir() -> PathBuf {
+    get_logs_dir().join(CRASHES_DIR)
+}
+
+fn config_path() -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?
+        .join("config")
+        .join("crash_reporter.json"))
+}
+
+fn load_state() -> Result<CrashReporterState, String> {
+    let path = config_path()?;
+    if !path.exists() {
+        return Ok(CrashReporterState::default());
+    }
+    let content =
+        std::fs::read_to_string(&path).map_err(|e| format!("无法读取崩溃报告配置: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("无法解析崩溃报告配置: {}", e))
+}
+
+fn modify_state(f: impl FnOnce(&mut CrashReporterState)) -> Result<(), String> {
+    let _guard = CONFIG_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut state = load_state()?;
+    f(&mut state);
+
+    let path = config_path()?;
+    Ok(save_json_config(&path, &state)?)
+}
+
+/// 校验报告文件名并返回完整路径（防止路径穿越）
+fn report_path(name: &str) -> Result<PathBuf, String> {
+    if name.is_empty() || name.contains(['/', '\\']) || name.contains("..") {
+        return Err(format!("无效的崩溃报告名称: {}", name));
+    }
+    let path = crashes_dir().join(name);
+    if !path.is_file() {
+        return Err(format!("崩溃报告不存在: {}", name));
+    }
+    Ok(path)
+}
+
+/// 安装 panic hook（保留默认 hook 的输出行为）
+pub fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let message = if let Some(s) = info.payload().downcast_ref::<&str>() {
+            s.to_string()
+        } else if let Some(s) = info.payload().downcast_ref::<String>() {
+            s.clone()
+        } else {
+            "Unknown panic payload".to_string()
+        };
+        let location = info
+            .location()
+            .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()))
+            .unwrap_or_else(|| "unknown".to_string());
+        let thread = std::thread::current()
+            .name()
+            .unwrap_or("unnamed")
+            .to_string();
+
+        error!("[crash_reporter] Panic at {}: {}", location, message);
+        match write_report(&message, &location, &thread) {
+            Ok(path) => error!("[crash_reporter] Crash report written to {:?}", path),
+            Err(e) => error!("[crash_reporter] Failed to write crash report: {}", e),
+        }
+
+        default_hook(info);
+    }));
+}
+
+fn write_report(message: &str, location: &str, thread: &str) -> Result<PathBuf, String> {
+    let dir = crashes_dir();
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    let now = chrono::Local::now();
+    let info = os_info::get();
+    let backtrace = std::backtrace::Backtrace::force_capture();
+    let log_tail = read_backend_log_tail(LOG_TAIL_BYTES).unwrap_or_default();
+
+    let content = format!(
+        "MXU 崩溃报告\n\
+         时间: {}\n\
+         版本: {}\n\
+         系统: {} {} ({})\n\
+         线程: {}\n\
+         位置: {}\n\
+         信息: {}\n\
+         \n== Backtrace ==\n{}\n\
+         \n== 最近日志 ==\n{}\n",
+        now.to_rfc3339(),
+        env!("CARGO_PKG_VERSION"),
+        info.os_type(),
+        info.version(),
+        std::env::consts::ARCH,
+        thread,
+        location,
+        message,
+        backtrace,
+        log_tail
+    );
+
+    let path = dir.join(format!("crash-{}.txt", now.format("%Y%m%d-%H%M%S-%3f")));
+    std::fs::write(&path, content).map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+/// 从报告内容中提取 panic 信息
+fn report_summary(content: &str) -> String {
+    content
+        .lines()
+        .find_map(|line| line.strip_prefix("信息: "))
+        .unwrap_or("")
+        .to_string()
+}
+
+fn list_report_names() -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(crashes_dir()) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .flatten()
+        .filter(|e| e.path().is_file())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|n| n.starts_with("crash-") && n.ends_with(".txt"))
+        .collect();
+    names.sort();
+    names.reverse();
+    names
+}
+
+async fn upload_report(endpoint: &str, name: &str) -> Result<(), String> {
+    let content = std::fs::read_to_string(report_path(name)?)
+        .map_err(|e| format!("无法读取崩溃报告: {}", e))?;
+    let client = reqwest::Client::builder()
+        .timeout(UPLOAD_TIMEOUT)
+        .user_agent(build_user_agent())
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+    let response = client
+        .post(endpoint)
+        .json(&serde_json::json!({
+            "name": name,
+            "version": env!("CARGO_PKG_VERSION"),
+            "os": std::env::consts::OS,
+            "arch": std::env::consts::ARCH,
+            "content": content,
+        }))
+        .send()
+        .await
+        .map_err(|e| format!("上传崩溃报告失败: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("上传崩溃报告失败: HTTP {}", response.status()));
+    }
+
+    modify_state(|s| {
+        if !s.uploaded.iter().any(|n| n == name) {
+            s.uploaded.push(name.to_string());
+        }
+    })?;
+    info!("[crash_reporter] Uploaded crash report {}", name);
+    Ok(())
+}
+
+/// 启动时在后台上传未上传的报告（仅在用户开启上传时）
+pub fn spawn_pending_uploads() {
+    let Ok(state) = load_state() else {
+        return;
+    };
+    let (true, Some(endpoint)) = (state.config.upload_enabled, state.config.endpoint) else {
+        return;
+    };
+    let pending: Vec<String> = list_report_names()
+        .into_iter()
+        .filter(|n| !state.uploaded.contains(n))
+        .collect();
+    if pending.is_empty() {
+        return;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        for name in pending {
+            if let Err(e) = upload_report(&endpoint, &name).await {
+                warn!("[crash_reporter] {}", e);
+                break;
+            }
+        }
+    });
+}
+
+/// 列出崩溃报告（新的在前）
+#[tauri::command]
+pub fn crash_reports_list() -> Result<Vec<CrashReportInfo>, MxuError> {
+    let state = load_state()?;
+    Ok(list_report_names()
+        .into_iter()
+        .map(|name| {
+            let path = crashes_dir().join(&name);
+            let content = std::fs::read_to_string(&path).unwrap_or_default();
+            let created_at = std::fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .map(|t| chrono::DateTime::<chrono::Local>::from(t).to_rfc3339())
+                .unwrap_or_default();
+            CrashReportInfo {
+                summary: report_summary(&content),
+                created_at,
+                acknowledged: state.acknowledged.contains(&name),
+                uploaded: state.uploaded.contains(&name),
+                name,
+            }
+        })
+        .collect())
+}
+
+/// 读取崩溃报告全文
+#[tauri::command]
+pub fn crash_report_read(name: String) -> Result<String, MxuError> {
+    Ok(std::fs::read_to_string(report_path(&name)?)
+        .map_err(|e| format!("无法读取崩溃报告: {}", e))?)
+}
+
+/// 标记崩溃报告为已查看（names 为空时标记全部）
+#[tauri::command]
+pub fn crash_reports_acknowledge(names: Option<Vec<String>>) -> Result<(), MxuError> {
+    let names = names.unwrap_or_else(list_report_names);
+    Ok(modify_state(|s| {
+        for name in names {
+            if !s.acknowledged.contains(&name) {
+                s.acknowledged.push(name);
+            }
+        }
+    })?)
+}
+
+/// 手动上传指定崩溃报告
+#[tauri::command]
+pub async fn crash_report_upload(name: String) -> Result<(), MxuError> {
+    let state = load_state()?;
+    if !state.config.upload_enabled {
+        return Err(MxuError::new(ErrorCode::CrashUploadDisabled));
+    }
+    let endpoint = state
+        .config
+        .endpoint
+        .ok_or_else(|| MxuError::new(ErrorCode::CrashUploadEndpointMissing))?;
+    Ok(upload_report(&endpoint, &name).await?)
+}
+
+/// 获取崩溃报告上传配置
+#[tauri::command]
+pub fn crash_reporter_get_config() -> Result<CrashReporterConfig, MxuError> {
+    Ok(load_state()?.config)
+}
+
+/// 设置崩溃报告上传配置
+#[tauri::command]
+pub fn crash_reporter_set_config(config: CrashReporterConfig) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    if let Some(endpoint) = &config.endpoint {
+        if !endpoint.starts_with("https://") && !endpoint.starts_with("http://") {
+            return Err(MxuError::with_args(ErrorCode::InvalidUrl, [endpoint]));
+        }
+    }
+    Ok(modify_state(|s| s.config = config)?)
+}
@@ -0,0 +1,155 @@
+//! 数据目录迁移
+//!
+//! 默认数据目录为 exe 目录（便携模式；macOS 为 ~/Library/Application Support/MXU）。
+//! set_data_dir 将现有配置与用户数据复制到新目录，并在默认数据目录写入重定向文件 data_dir.json，
+//! 重启后生效；原目录中的数据保留，确认无误后可手动删除
+
+use log::info;
+use std::path::PathBuf;
+
+use super::error::MxuError;
+use super::i18n::ErrorCode;
+use super::types::{DataDirInfo, DataDirMigrationResult, DataDirRedirect};
+use super::update::copy_dir_recursive;
+use super::utils::{
+    default_data_dir, ensure_free_space, get_app_data_dir, normalize_path, path_size,
+    read_data_redirect, DATA_REDIRECT_FILE,
+};
+
+/// 迁移时复制的条目（其余如 cache 可重新生成，不复制）
+const MIGRATED_ENTRIES: &[&str] = &[
+    "config",
+    "profiles",
+    "backups",
+    "resource_packs",
+    "screenshots",
+    "debug",
+];
+
+/// 系统用户数据目录，便携模式与之互相切换
+fn system_data_dir() -> Option<PathBuf> {
+    #[cfg(windows)]
+    {
+        std::env::var_os("APPDATA").map(|dir| PathBuf::from(dir).join("MXU"))
+    }
+    #[cfg(target_os = "linux")]
+    {
+        std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".local/share")))
+            .map(|dir| dir.join("MXU"))
+    }
+    #[cfg(target_os = "macos")]
+    {
+        None
+    }
+}
+
+/// 获取数据目录信息
+#[tauri::command]
+pub fn get_data_dir_info() -> Result<DataDirInfo, MxuError> {
+    let default_dir = default_data_dir()?;
+    let current = get_app_data_dir()?;
+    let next = read_data_redirect(&default_dir).unwrap_or_else(|| default_dir.clone());
+    Ok(DataDirInfo {
+        current: current.to_string_lossy().to_string(),
+        default: default_dir.to_string_lossy().to_string(),
+        redirected: current != default_dir,
+        system_dir: system_data_dir().map(|d| d.to_string_lossy().to_string()),
+        pending: (next != current).then(|| next.to_string_lossy().to_string()),
+    })
+}
+
+/// 修改数据目录：复制现有数据到新目录并写入重定向文件，重启后生效
+/// path 为空时恢复默认数据目录（Windows/Linux 即便携模式）
+#[tauri::command]
+pub fn set_data_dir(path: Option<String>) -> Result<DataDirMigrationResult, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+
+    let default_dir = default_data_dir()?;
+    let current = get_app_data_dir()?;
+    let target = match path.as_deref().map(str::trim) {
+        Some(p) if !p.is_empty() => normalize_path(p),
+        _ => default_dir.clone(),
+    };
+    info!(
+        "[data_dir] set_data_dir: {} -> {}",
+        current.display(),
+        target.display()
+    );
+
+    if !target.is_absolute() {
+        return Err(MxuError::new(ErrorCode::DataDirNotAbsolute));
+    }
+    if let Some(entry) = MIGRATED_ENTRIES
+        .iter()
+        .find(|e| target.starts_with(current.join(e)))
+    {
+        return Err(MxuError::with_args(
+            ErrorCode::DataDirInsideCurrent,
+            [entry],
+        ));
+    }
+
+    let mut copied = Vec::new();
+    let mut bytes = 0;
+    if target != current {
+        std::fs::create_dir_all(&target)
+            .map_err(|e| format!("无法创建数据目录 [{}]: {}", target.display(), e))?;
+
+        let entries: Vec<&str> = MIGRATED_ENTRIES
+            .iter()
+            .copied()
+            .filter(|e| current.join(e).exists())
+            .collect();
+        let required: u64 = entries.iter().map(|e| path_size(&current.join(e))).sum();
+        ensure_free_space(&target, required)?;
+
+        for entry in entries {
+            let src = current.join(entry);
+            let dst = target.join(entry);
+            if src.is_dir() {
+                copy_dir_recursive(&src, &dst)?;
+            } else {
+                std::fs::copy(&src, &dst)
+                    .map_err(|e| format!("无法复制 [{}]: {}", src.display(), e))?;
+            }
+            copied.push(entry.to_string());
+        }
+        bytes = required;
+    }
+
+    let redirect_path = default_dir.join(DATA_REDIRECT_FILE);
+    if target == default_dir {
+        if redirect_path.exists() {
+            std::fs::remove_file(&redirect_path)
+                .map_err(|e| format!("无法删除重定向文件: {}", e))?;
+        }
+    } else {
+        std::fs::create_dir_all(&default_dir)
+            .map_err(|e| format!("无法创建默认数据目录: {}", e))?;
+        let redirect = DataDirRedirect {
+            path: target.to_string_lossy().to_string(),
+        };
+        let content = serde_json::to_string_pretty(&redirect).map_err(|e| e.to_string())?;
+        std::fs::write(&redirect_path, content).map_err(|e| {
+            MxuError::with_args(
+                ErrorCode::FileWriteFailed,
+                [redirect_path.display().to_string(), e.to_string()],
+            )
+        })?;
+    }
+
+    info!(
+        "[data_dir] Data dir set to {} ({} entries, {} bytes copied)",
+        target.display(),
+        copied.len(),
+        bytes
+    );
+    Ok(DataDirMigrationResult {
+        path: target.to_string_lossy().to_string(),
+        copied,
+        bytes,
+        requires_restart: target != current,
+    })
+}
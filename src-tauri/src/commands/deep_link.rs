@@ -0,0 +1,252 @@
+//! mxu:// 深度链接
+//!
+//! 安装版由安装程序注册 mxu:// 协议；便携版可通过 deep_link_set_settings 为当前用户注册一次。
+//! 单实例（把后续启动时的链接转发给已运行的实例）需手动开启：single-instance 插件以应用标识区分程序，
+//! 而所有基于 MXU 的项目共用同一标识，默认开启会导致不同项目无法同时运行。
+//! 支持的链接：
+//! - `mxu://open`：显示并聚焦主窗口
+//! - `mxu://run?profile=<档案 ID 或名称>`：确认后运行配置档案（与托盘快速启动相同）
+//! - `mxu://install-resource?url=<https 地址>`：下载安装包后走拖放安装的确认流程
+//!
+//! 链接可能来自任意网页，运行任务前先弹出原生确认框；访客模式下只允许 open
+
+use log::{info, warn};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Emitter, Manager, Url};
+use tauri_plugin_deep_link::DeepLinkExt;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons};
+
+use super::error::MxuError;
+use super::i18n::{self, Prompt};
+use super::types::DeepLinkSettings;
+use super::utils::{get_app_data_dir, save_json_config};
+
+/// 协议名
+const SCHEME: &str = "mxu";
+/// 冷启动时等待前端就绪的最长时间
+const FRONTEND_READY_TIMEOUT: Duration = Duration::from_secs(30);
+/// 资源包下载的大小上限
+const MAX_DOWNLOAD_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+fn config_path() -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?.join("config").join("deep_link.json"))
+}
+
+fn load_settings() -> DeepLinkSettings {
+    config_path()
+        .ok()
+        .and_then(|p| std::fs::read_to_string(p).ok())
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+/// 是否启用单实例（启动时构建插件前调用）
+pub fn single_instance_enabled() -> bool {
+    load_settings().single_instance
+}
+
+/// 监听链接（setup 中调用）
+pub fn init(app: &AppHandle) {
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            spawn_handle(handle.clone(), url);
+        }
+    });
+
+    // 通过链接冷启动时处理启动参数中的链接
+    match app.deep_link().get_current() {
+        Ok(Some(urls)) => {
+            for url in urls {
+                spawn_handle(app.clone(), url);
+            }
+        }
+        Ok(None) => {}
+        Err(e) => warn!("[deep_link] Failed to read launch URL: {}", e),
+    }
+}
+
+/// 显示并聚焦主窗口
+pub fn focus_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+/// 在后台线程处理链接（确认框与下载均为阻塞调用）
+fn spawn_handle(app: AppHandle, url: Url) {
+    std::thread::spawn(move || {
+        if let Err(e) = handle_url(&app, &url) {
+            warn!("[deep_link] Failed to handle {}: {}", url, e);
+            let _ = app.emit("deep-link-error", e);
+        }
+    });
+}
+
+/// 等待前端完成加载（以首次心跳确认为准）
+fn wait_frontend_ready() {
+    let start = Instant::now();
+    while super::heartbeat::last_ack().is_none() && start.elapsed() < FRONTEND_READY_TIMEOUT {
+        std::thread::sleep(Duration::from_millis(200));
+    }
+}
+
+fn query_param(url: &Url, key: &str) -> Option<String> {
+    url.query_pairs()
+        .find(|(k, _)| k == key)
+        .map(|(_, v)| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+fn confirm(app: &AppHandle, message: &str) -> bool {
+    app.dialog()
+        .message(message)
+        .title("MXU")
+        .buttons(MessageDialogButtons::OkCancel)
+        .blocking_show()
+}
+
+fn handle_url(app: &AppHandle, url: &Url) -> Result<(), String> {
+    if url.scheme() != SCHEME {
+        return Err(format!("不支持的链接协议: {}", url.scheme()));
+    }
+    // mxu://run?... 中 run 解析为 host，mxu:run?... 时为 path
+    let action = url
+        .host_str()
+        .unwrap_or_else(|| url.path())
+        .trim_matches('/')
+        .to_string();
+    info!("[deep_link] Received {} ({})", url, action);
+
+    focus_main_window(app);
+    if action == "open" || action.is_empty() {
+        return Ok(());
+    }
+    super::guest_mode::ensure_not_guest()?;
+
+    match action.as_str() {
+        "run" => {
+            let profile = query_param(url, "profile").ok_or("链接缺少 profile 参数")?;
+            let summary = super::profiles::find_summary(&profile)?;
+            if !confirm(
+                app,
+                &i18n::prompt(Prompt::DeepLinkRunProfile, &[summary.name.clone()]),
+            ) {
+                info!("[deep_link] Run of profile {} cancelled", summary.name);
+                return Ok(());
+            }
+            wait_frontend_ready();
+            // 与托盘快速启动相同，由前端切换实例并开始任务
+            app.emit("tray-start-profile", summary.id)
+                .map_err(|e| e.to_string())
+        }
+        "install-resource" => {
+            let target = query_param(url, "url").ok_or("链接缺少 url 参数")?;
+            let target = Url::parse(&target).map_err(|e| format!("无效的下载地址: {}", e))?;
+            if target.scheme() != "https" {
+                return Err("仅支持 https 下载地址".to_string());
+            }
+            if !confirm(
+                app,
+                &i18n::prompt(Prompt::DeepLinkDownloadPackage, &[target.to_string()]),
+            ) {
+                info!("[deep_link] Resource download cancelled");
+                return Ok(());
+            }
+            let path = download_package(&target)?;
+            wait_frontend_ready();
+            // 走拖放安装的识别与确认流程
+            super::package_install::handle_dropped_paths(app, &[path]);
+            Ok(())
+        }
+        _ => Err(format!("不支持的链接操作: {}", action)),
+    }
+}
+
+/// 下载安装包到 cache/deep_link/
+fn download_package(url: &Url) -> Result<PathBuf, String> {
+    use std::io::Read;
+
+    let file_name = url
+        .path_segments()
+        .and_then(|mut s| s.next_back())
+        .map(|name| {
+            name.chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                        c
+                    } else {
+                        '_'
+                    }
+                })
+                .collect::<String>()
+        })
+        .filter(|name| !name.trim_matches('.').is_empty())
+        .unwrap_or_else(|| "package.zip".to_string());
+    let dir = get_app_data_dir()?.join("cache").join("deep_link");
+    std::fs::create_dir_all(&dir).map_err(|e| format!("无法创建下载目录: {}", e))?;
+    let path = dir.join(file_name);
+
+    info!("[deep_link] Downloading {} -> {}", url, path.display());
+    let response = reqwest::blocking::Client::builder()
+        .connect_timeout(Duration::from_secs(10))
+        .timeout(Duration::from_secs(600))
+        .build()
+        .map_err(|e| format!("创建 HTTP 客户端失败: {}", e))?
+        .get(url.as_str())
+        .send()
+        .map_err(|e| format!("下载失败: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("HTTP 错误: {}", response.status()));
+    }
+    if response
+        .content_length()
+        .is_some_and(|len| len > MAX_DOWNLOAD_BYTES)
+    {
+        return Err("资源包过大".to_string());
+    }
+
+    let mut file = std::fs::File::create(&path).map_err(|e| format!("无法创建文件: {}", e))?;
+    let copied = std::io::copy(&mut response.take(MAX_DOWNLOAD_BYTES + 1), &mut file)
+        .map_err(|e| format!("下载失败: {}", e))?;
+    if copied > MAX_DOWNLOAD_BYTES {
+        drop(file);
+        let _ = std::fs::remove_file(&path);
+        return Err("资源包过大".to_string());
+    }
+    Ok(path)
+}
+
+/// 获取深度链接设置
+#[tauri::command]
+pub fn deep_link_get_settings() -> DeepLinkSettings {
+    load_settings()
+}
+
+/// 保存深度链接设置：按 register_scheme 为当前用户注册或取消注册协议，单实例重启后生效
+#[tauri::command]
+pub fn deep_link_set_settings(app: AppHandle, settings: DeepLinkSettings) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    #[cfg(any(windows, target_os = "linux"))]
+    {
+        let registered = app.deep_link().is_registered(SCHEME).unwrap_or(false);
+        if settings.register_scheme && !registered {
+            app.deep_link()
+                .register(SCHEME)
+                .map_err(|e| format!("无法注册 {}:// 协议: {}", SCHEME, e))?;
+            info!("[deep_link] Registered {}:// scheme", SCHEME);
+        } else if !settings.register_scheme && registered {
+            app.deep_link()
+                .unregister(SCHEME)
+                .map_err(|e| format!("无法取消注册 {}:// 协议: {}", SCHEME, e))?;
+            info!("[deep_link] Unregistered {}:// scheme", SCHEME);
+        }
+    }
+    #[cfg(not(any(windows, target_os = "linux")))]
+    let _ = &app;
+    save_json_config(&config_path()?, &settings)
+}
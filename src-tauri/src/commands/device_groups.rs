@@ -0,0 +1,301 @@
+//! 设备分组
+//!
+//! 在 config/device_groups.json 中保存命名的设备分组（成员为实例 ID 与控制器配置），
+//! 提供批量连接、批量停止与汇总状态（同时合并到 maa_get_all_states）。
+//! 在分组上运行配置档案时，任务列表由前端构建，后端发送 device-group-run-profile 事件交由前端逐个启动
+
+use log::{info, warn};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use tauri::{AppHandle, Emitter, State};
+
+use super::error::MxuError;
+use super::i18n::ErrorCode;
+use super::types::{
+    DeviceGroup, DeviceGroupMemberStatus, DeviceGroupResult, DeviceGroupRunProfileEvent,
+    DeviceGroupStatus, InstanceState, MaaState,
+};
+use super::utils::{get_app_data_dir, save_json_config};
+
+/// 配置读写锁
+static CONFIG_LOCK: Mutex<()> = Mutex::new(());
+
+fn config_path() -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?
+        .join("config")
+        .join("device_groups.json"))
+}
+
+/// 读取全部设备分组
+pub fn load_groups() -> Result<Vec<DeviceGroup>, String> {
+    let path = config_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content =
+        std::fs::read_to_string(&path).map_err(|e| format!("无法读取设备分组配置: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("无法解析设备分组配置: {}", e))
+}
+
+fn save_groups(groups: &[DeviceGroup]) -> Result<(), String> {
+    let path = config_path()?;
+    Ok(save_json_config(&path, groups)?)
+}
+
+fn find_group(id: &str) -> Result<DeviceGroup, String> {
+    load_groups()?
+        .into_iter()
+        .find(|g| g.id == id)
+        .ok_or_else(|| format!("设备分组不存在: {}", id))
+}
+
+/// 按实例状态汇总分组状态
+pub fn aggregate(
+    groups: &[DeviceGroup],
+    instances: &HashMap<String, InstanceState>,
+) -> Vec<DeviceGroupStatus> {
+    groups
+        .iter()
+        .map(|group| {
+            let members: Vec<DeviceGroupMemberStatus> = group
+                .members
+                .iter()
+                .map(|m| {
+                    let state = instances.get(&m.instance_id);
+                    DeviceGroupMemberStatus {
+                        instance_id: m.instance_id.clone(),
+                        exists: state.is_some(),
+                        connected: state.is_some_and(|s| s.connected),
+                        is_running: state.is_some_and(|s| s.is_running),
+                    }
+                })
+                .collect();
+            DeviceGroupStatus {
+                id: group.id.clone(),
+                name: group.name.clone(),
+                total: members.len(),
+                connected: members.iter().filter(|m| m.connected).count(),
+                running: members.iter().filter(|m| m.is_running).count(),
+                members,
+            }
+        })
+        .collect()
+}
+
+/// 列出设备分组
+#[tauri::command]
+pub fn device_groups_list() -> Result<Vec<DeviceGroup>, MxuError> {
+    Ok(load_groups()?)
+}
+
+/// 创建或更新设备分组（id 为空时创建）
+#[tauri::command]
+pub fn device_group_save(mut group: DeviceGroup) -> Result<DeviceGroup, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    group.name = group.name.trim().to_string();
+    if group.name.is_empty() {
+        return Err(MxuError::new(ErrorCode::EmptyName));
+    }
+    let mut seen = std::collections::HashSet::new();
+    if let Some(dup) = group
+        .members
+        .iter()
+        .find(|m| !seen.insert(m.instance_id.as_str()))
+    {
+        return Err(MxuError::with_args(
+            ErrorCode::DuplicateGroupInstance,
+            [&dup.instance_id],
+        ));
+    }
+
+    let _guard = CONFIG_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut groups = load_groups()?;
+    if group.id.is_empty() {
+        group.id = chrono::Local::now().format("%Y%m%d%H%M%S%3f").to_string();
+        groups.push(group.clone());
+    } else if let Some(existing) = groups.iter_mut().find(|g| g.id == group.id) {
+        *existing = group.clone();
+    } else {
+        return Err(MxuError::with_args(
+            ErrorCode::DeviceGroupNotFound,
+            [&group.id],
+        ));
+    }
+    save_groups(&groups)?;
+    info!(
+        "[device_groups] Saved group {} ({} member(s))",
+        group.name,
+        group.members.len()
+    );
+    Ok(group)
+}
+
+/// 删除设备分组（不影响成员实例）
+#[tauri::command]
+pub fn device_group_delete(id: String) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    let _guard = CONFIG_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut groups = load_groups()?;
+    let before = groups.len();
+    groups.retain(|g| g.id != id);
+    if groups.len() == before {
+        return Err(MxuError::with_args(ErrorCode::DeviceGroupNotFound, [&id]));
+    }
+    save_groups(&groups)?;
+    info!("[device_groups] Deleted group {}", id);
+    Ok(())
+}
+
+/// 获取设备分组的汇总状态
+#[tauri::command]
+pub fn device_group_status(
+    state: State<Arc<MaaState>>,
+    id: String,
+) -> Result<DeviceGroupStatus, MxuError> {
+    let group = find_group(&id)?;
+    let instances = super::state::maa_get_all_states(state)?.instances;
+    Ok(aggregate(std::slice::from_ref(&group), &instances)
+        .pop()
+        .ok_or_else(|| format!("设备分组不存在: {}", id))?)
+}
+
+/// 连接分组内的所有设备（实例不存在时自动创建，已连接的设备跳过），
+/// 连接结果通过 maa-callback 事件通知
+#[tauri::command]
+pub async fn device_group_connect(
+    app: AppHandle,
+    state: State<'_, Arc<MaaState>>,
+    id: String,
+) -> Result<Vec<DeviceGroupResult>, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    let group = find_group(&id)?;
+    info!(
+        "[device_groups] Connecting {} device(s) in group {}",
+        group.members.len(),
+        group.name
+    );
+
+    let instances = super::state::maa_get_all_states(state.clone())?.instances;
+    let mut results = Vec::with_capacity(group.members.len());
+    for member in group.members {
+        if instances
+            .get(&member.instance_id)
+            .is_some_and(|s| s.connected)
+        {
+            results.push(DeviceGroupResult {
+                instance_id: member.instance_id,
+                success: true,
+                error: None,
+            });
+            continue;
+        }
+        let result = async {
+            super::maa_core::maa_create_instance(state.clone(), member.instance_id.clone())?;
+            super::maa_core::maa_connect_controller(
+                app.clone(),
+                state.clone(),
+                member.instance_id.clone(),
+                member.controller,
+            )
+            .await
+        }
+        .await;
+        if let Err(e) = &result {
+            warn!(
+                "[device_groups] Failed to connect {}: {}",
+                member.instance_id, e
+            );
+        }
+        results.push(DeviceGroupResult {
+            instance_id: member.instance_id,
+            success: result.is_ok(),
+            error: result.err().map(String::from),
+        });
+    }
+    Ok(results)
+}
+
+/// 停止分组内所有设备上的任务
+#[tauri::command]
+pub fn device_group_stop(
+    app: AppHandle,
+    state: State<Arc<MaaState>>,
+    id: String,
+) -> Result<Vec<DeviceGroupResult>, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    let group = find_group(&id)?;
+    let instances = super::state::maa_get_all_states(state.clone())?.instances;
+
+    let results = group
+        .members
+        .into_iter()
+        .filter(|m| instances.get(&m.instance_id).is_some_and(|s| s.is_running))
+        .map(|m| {
+            let result = super::maa_core::maa_stop_task(
+                app.clone(),
+                state.clone(),
+                m.instance_id.clone(),
+                None,
+            );
+            DeviceGroupResult {
+                instance_id: m.instance_id,
+                success: result.is_ok(),
+                error: result.err().map(String::from),
+            }
+        })
+        .collect::<Vec<_>>();
+    info!(
+        "[device_groups] Stopped {} running device(s) in group {}",
+        results.len(),
+        id
+    );
+    Ok(results)
+}
+
+/// 在分组内所有已连接设备上运行配置档案
+#[tauri::command]
+pub fn device_group_run_profile(
+    app: AppHandle,
+    state: State<Arc<MaaState>>,
+    id: String,
+    profile_id: String,
+) -> Result<Vec<String>, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    let group = find_group(&id)?;
+    // 校验档案存在
+    super::profiles::profile_get(profile_id.clone())?;
+    let instances = super::state::maa_get_all_states(state)?.instances;
+
+    let instance_ids: Vec<String> = group
+        .members
+        .iter()
+        .filter(|m| {
+            instances
+                .get(&m.instance_id)
+                .is_some_and(|s| s.connected && !s.is_running)
+        })
+        .map(|m| m.instance_id.clone())
+        .collect();
+    if instance_ids.is_empty() {
+        return Err(MxuError::new(ErrorCode::NoIdleGroupDevices));
+    }
+
+    info!(
+        "[device_groups] Running profile {} on {} device(s) in group {}",
+        profile_id,
+        instance_ids.len(),
+        group.name
+    );
+    app.emit(
+        "device-group-run-profile",
+        DeviceGroupRunProfileEvent {
+            group_id: id,
+            profile_id,
+            instance_ids: instance_ids.clone(),
+        },
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(instance_ids)
+}
@@ -0,0 +1,523 @@
+//! 与其他自动化工具的设备互斥
+//!
+//! 开始运行前检测是否有其他工具正在控制同一设备：
+//! - 锁文件约定：<临时目录>/maa-device-locks/<设备>.lock，内容为 DeviceLockInfo（JSON），
+//!   持有进程已退出的锁视为失效。MXU 运行期间同样写入锁文件，实例空闲后释放
+//! - ADB 客户端：命令行指向同一设备地址、且不是由 MXU 派生的 adb / 已知工具进程
+//! - 已知工具进程：正在运行但无法确定目标设备时（如 Win32 窗口）只作为提示，不阻止运行
+//!
+//! 按策略提示、拒绝或排队等待，并通过 device-conflict 事件通知前端
+
+use log::{info, warn};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::error::MxuError;
+use super::i18n::ErrorCode;
+use super::types::{
+    ControllerConfig, DeviceConflict, DeviceConflictAction, DeviceConflictEvent, DeviceLockInfo,
+    DeviceLockMode, DeviceLockPolicy, MaaState,
+};
+use super::utils::{get_app_data_dir, save_json_config};
+
+/// 已知自动化工具的进程名（小写，不含 .exe）
+const KNOWN_TOOLS: &[&str] = &[
+    "maa",
+    "maa-cli",
+    "maapicli",
+    "mfaavalonia",
+    "mfawpf",
+    "alas",
+    "baas",
+    "m9a",
+    "march7thassistant",
+    "ok-ww",
+];
+
+/// 排队等待时的检查间隔
+const WAIT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// 检查实例是否空闲并释放锁的间隔
+const KEEPER_INTERVAL: Duration = Duration::from_secs(2);
+/// 获取锁后等待任务开始运行的时长，期间实例空闲也不释放（Agent 启动等）
+const STARTUP_GRACE: Duration = Duration::from_secs(120);
+/// 判断进程是否由 MXU 派生时向上追溯的层数
+const MAX_ANCESTOR_DEPTH: usize = 8;
+
+/// 实例当前连接的设备
+#[derive(Clone)]
+struct Device {
+    /// 锁文件名（不含扩展名）
+    key: String,
+    /// 显示名称
+    label: String,
+    /// ADB 设备地址（用于匹配其他 ADB 客户端）
+    adb_address: Option<String>,
+}
+
+static DEVICES: LazyLock<Mutex<HashMap<String, Device>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// MXU 持有的锁
+struct HeldLock {
+    path: PathBuf,
+    acquired: Instant,
+    /// 是否已观察到任务在运行
+    seen_busy: bool,
+}
+
+static HELD: LazyLock<Mutex<HashMap<String, HeldLock>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 已请求取消排队等待的实例
+static WAIT_CANCELLED: LazyLock<Mutex<HashSet<String>>> =
+    LazyLock::new(|| Mutex::new(HashSet::new()));
+
+static SYSTEM: LazyLock<Mutex<System>> = LazyLock::new(|| Mutex::new(System::new()));
+
+/// 配置读写锁
+static CONFIG_LOCK: Mutex<()> = Mutex::new(());
+
+fn config_path() -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?.join("config").join("device_lock.json"))
+}
+
+fn load_policy() -> DeviceLockPolicy {
+    config_path()
+        .ok()
+        .and_then(|p| std::fs::read_to_string(p).ok())
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+/// 锁文件目录（各工具共享）
+fn lock_dir() -> PathBuf {
+    std::env::temp_dir().join("maa-device-locks")
+}
+
+fn sanitize(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// 记录实例连接的设备（maa_connect_controller 时调用）
+pub fn note_controller(instance_id: &str, config: &ControllerConfig) {
+    let device = match config {
+        ControllerConfig::Adb { address, .. } => Device {
+            key: format!("adb_{}", sanitize(address)),
+            label: address.clone(),
+            adb_address: Some(address.clone()),
+        },
+        ControllerConfig::Win32 { handle, .. } | ControllerConfig::Gamepad { handle, .. } => {
+            Device {
+                key: format!("win32_{}", handle),
+                label: format!("HWND {:#x}", handle),
+                adb_address: None,
+            }
+        }
+        ControllerConfig::PlayCover { address, .. } => Device {
+            key: format!("playcover_{}", sanitize(address)),
+            label: address.clone(),
+            adb_address: None,
+        },
+        ControllerConfig::Mock { image_dir, .. } => Device {
+            key: format!("mock_{}", sanitize(image_dir)),
+            label: image_dir.clone(),
+            adb_address: None,
+        },
+    };
+    if let Ok(mut devices) = DEVICES.lock() {
+        devices.insert(instance_id.to_string(), device);
+    }
+}
+
+fn device_of(instance_id: &str) -> Option<Device> {
+    DEVICES.lock().ok()?.get(instance_id).cloned()
+}
+
+fn process_stem(name: &str) -> String {
+    let name = name.to_lowercase();
+    name.strip_suffix(".exe").unwrap_or(&name).to_string()
+}
+
+/// 进程是否为 MXU 自身或由 MXU 派生（MaaFramework 调用的 adb、Agent 等）
+fn is_ours(system: &System, pid: Pid) -> bool {
+    let own = Pid::from_u32(std::process::id());
+    let mut current = Some(pid);
+    for _ in 0..MAX_ANCESTOR_DEPTH {
+        let Some(pid) = current else {
+            return false;
+        };
+        if pid == own {
+            return true;
+        }
+        current = system.process(pid).and_then(|p| p.parent());
+    }
+    false
+}
+
+fn is_alive(pid: u32) -> bool {
+    let Ok(mut system) = SYSTEM.lock() else {
+        return true;
+    };
+    let pid = Pid::from_u32(pid);
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[pid]),
+        true,
+        ProcessRefreshKind::nothing(),
+    );
+    system.process(pid).is_some()
+}
+
+/// 检测设备冲突
+fn detect(device: &Device, policy: &DeviceLockPolicy) -> Vec<DeviceConflict> {
+    let mut conflicts = Vec::new();
+
+    // 锁文件
+    let lock_path = lock_dir().join(format!("{}.lock", device.key));
+    if let Some(info) = std::fs::read_to_string(&lock_path)
+        .ok()
+        .and_then(|c| serde_json::from_str::<DeviceLockInfo>(&c).ok())
+    {
+        if info.pid != std::process::id() && is_alive(info.pid) {
+            conflicts.push(DeviceConflict {
+                source: "lock_file".to_string(),
+                tool: info.tool.clone(),
+                pid: info.pid,
+                detail: format!("{} 自 {} 起持有设备锁", info.tool, info.acquired_at),
+                blocking: true,
+            });
+        }
+    }
+
+    // 进程检测
+    let Ok(mut system) = SYSTEM.lock() else {
+        return conflicts;
+    };
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::nothing().with_cmd(UpdateKind::OnlyIfNotSet),
+    );
+    let extra: Vec<String> = policy.extra_tools.iter().map(|t| process_stem(t)).collect();
+    for (pid, process) in system.processes() {
+        let stem = process_stem(&process.name().to_string_lossy());
+        let is_tool = KNOWN_TOOLS.contains(&stem.as_str()) || extra.contains(&stem);
+        let is_adb = stem == "adb";
+        if !(is_tool || is_adb) || is_ours(&system, *pid) {
+            continue;
+        }
+        let targets_device = device.adb_address.as_ref().is_some_and(|address| {
+            process
+                .cmd()
+                .iter()
+                .any(|arg| arg.to_string_lossy().contains(address.as_str()))
+        });
+        if targets_device {
+            conflicts.push(DeviceConflict {
+                source: "adb_client".to_string(),
+                tool: stem.clone(),
+                pid: pid.as_u32(),
+                detail: format!("进程 {} 正在访问 {}", stem, device.label),
+                blocking: true,
+            });
+        } else if is_tool {
+            conflicts.push(DeviceConflict {
+                source: "tool_process".to_string(),
+                tool: stem.clone(),
+                pid: pid.as_u32(),
+                detail: format!("{} 正在运行，可能在控制同一设备", stem),
+                blocking: false,
+            });
+        }
+    }
+    conflicts
+}
+
+fn emit(
+    app: &AppHandle,
+    instance_id: &str,
+    device: &Device,
+    action: DeviceConflictAction,
+    conflicts: &[DeviceConflict],
+) {
+    let _ = app.emit(
+        "device-conflict",
+        DeviceConflictEvent {
+            instance_id: instance_id.to_string(),
+            device: device.label.clone(),
+            action,
+            conflicts: conflicts.to_vec(),
+        },
+    );
+}
+
+fn describe(conflicts: &[DeviceConflict]) -> String {
+    conflicts
+        .iter()
+        .filter(|c| c.blocking)
+        .map(|c| format!("{} (PID {})", c.tool, c.pid))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// 运行开始前检查设备冲突并获取设备锁；拒绝、等待超时或等待被取消时返回错误。
+/// 排队模式会阻塞等待，须在阻塞线程中调用
+pub fn acquire_for_run(app: &AppHandle, instance_id: &str) -> Result<(), String> {
+    let policy = load_policy();
+    let Some(device) = device_of(instance_id) else {
+        return Ok(());
+    };
+    if policy.mode == DeviceLockMode::Off {
+        return write_lock(instance_id, &device);
+    }
+    if let Ok(mut cancelled) = WAIT_CANCELLED.lock() {
+        cancelled.remove(instance_id);
+    }
+
+    let start = Instant::now();
+    let mut waiting = false;
+    loop {
+        let conflicts = detect(&device, &policy);
+        let blocking = conflicts.iter().any(|c| c.blocking);
+        if !blocking {
+            if waiting {
+                info!("[device_lock] Device {} is free now", device.label);
+                emit(
+                    app,
+                    instance_id,
+                    &device,
+                    DeviceConflictAction::Resolved,
+                    &conflicts,
+                );
+            } else if !conflicts.is_empty() {
+                emit(
+                    app,
+                    instance_id,
+                    &device,
+                    DeviceConflictAction::Warned,
+                    &conflicts,
+                );
+            }
+            return write_lock(instance_id, &device);
+        }
+
+        match policy.mode {
+            DeviceLockMode::Off | DeviceLockMode::Warn => {
+                warn!(
+                    "[device_lock] Device {} is used by {}, starting anyway",
+                    device.label,
+                    describe(&conflicts)
+                );
+                emit(
+                    app,
+                    instance_id,
+                    &device,
+                    DeviceConflictAction::Warned,
+                    &conflicts,
+                );
+                return write_lock(instance_id, &device);
+            }
+            DeviceLockMode::Refuse => {
+                emit(
+                    app,
+                    instance_id,
+                    &device,
+                    DeviceConflictAction::Refused,
+                    &conflicts,
+                );
+                return Err(format!(
+                    "设备 {} 正被其他工具使用: {}",
+                    device.label,
+                    describe(&conflicts)
+                ));
+            }
+            DeviceLockMode::Queue => {}
+        }
+
+        if policy.max_wait_secs > 0 && start.elapsed().as_secs() >= policy.max_wait_secs {
+            emit(
+                app,
+                instance_id,
+                &device,
+                DeviceConflictAction::TimedOut,
+                &conflicts,
+            );
+            return Err(format!(
+                "等待设备 {} 空闲超时: {}",
+                device.label,
+                describe(&conflicts)
+            ));
+        }
+        if !waiting {
+            waiting = true;
+            info!(
+                "[device_lock] Device {} is used by {}, waiting",
+                device.label,
+                describe(&conflicts)
+            );
+            emit(
+                app,
+                instance_id,
+                &device,
+                DeviceConflictAction::Waiting,
+                &conflicts,
+            );
+        }
+        thread::sleep(WAIT_POLL_INTERVAL);
+        if WAIT_CANCELLED
+            .lock()
+            .map(|mut c| c.remove(instance_id))
+            .unwrap_or(false)
+        {
+            emit(
+                app,
+                instance_id,
+                &device,
+                DeviceConflictAction::Cancelled,
+                &conflicts,
+            );
+            return Err("已取消等待设备空闲".to_string());
+        }
+    }
+}
+
+/// 取消实例的排队等待（停止任务时调用）
+pub fn cancel_wait(instance_id: &str) {
+    if let Ok(mut cancelled) = WAIT_CANCELLED.lock() {
+        cancelled.insert(instance_id.to_string());
+    }
+}
+
+fn write_lock(instance_id: &str, device: &Device) -> Result<(), String> {
+    let dir = lock_dir();
+    std::fs::create_dir_all(&dir).map_err(|e| format!("无法创建设备锁目录: {}", e))?;
+    let path = dir.join(format!("{}.lock", device.key));
+    let info = DeviceLockInfo {
+        tool: "MXU".to_string(),
+        pid: std::process::id(),
+        device: device.label.clone(),
+        acquired_at: chrono::Local::now().to_rfc3339(),
+    };
+    let content = serde_json::to_string_pretty(&info).map_err(|e| e.to_string())?;
+    std::fs::write(&path, content).map_err(|e| format!("无法写入设备锁: {}", e))?;
+    if let Ok(mut held) = HELD.lock() {
+        held.insert(
+            instance_id.to_string(),
+            HeldLock {
+                path,
+                acquired: Instant::now(),
+                seen_busy: false,
+            },
+        );
+    }
+    Ok(())
+}
+
+/// 释放实例持有的锁（仅删除本进程写入的锁文件）
+fn release(instance_id: &str) {
+    let Some(HeldLock { path, .. }) = HELD.lock().ok().and_then(|mut h| h.remove(instance_id))
+    else {
+        return;
+    };
+    let owned = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|c| serde_json::from_str::<DeviceLockInfo>(&c).ok())
+        .is_some_and(|info| info.pid == std::process::id());
+    if owned {
+        let _ = std::fs::remove_file(&path);
+    }
+}
+
+/// 释放全部锁（窗口销毁时调用）
+pub fn release_all() {
+    let ids: Vec<String> = HELD
+        .lock()
+        .map(|h| h.keys().cloned().collect())
+        .unwrap_or_default();
+    for id in ids {
+        release(&id);
+    }
+}
+
+fn is_busy(state: &MaaState, instance_id: &str) -> bool {
+    let queue_active = state
+        .task_queues
+        .lock()
+        .map(|q| q.get(instance_id).is_some_and(|q| q.is_active()))
+        .unwrap_or(false);
+    let tasker_running = state
+        .instances
+        .lock()
+        .map(|i| {
+            i.get(instance_id)
+                .and_then(|inst| inst.tasker.as_ref())
+                .is_some_and(|t| t.running())
+        })
+        .unwrap_or(false);
+    queue_active || tasker_running
+}
+
+/// 实例空闲后释放锁（setup 中调用）
+pub fn spawn_lock_keeper(app: AppHandle) {
+    thread::spawn(move || loop {
+        thread::sleep(KEEPER_INTERVAL);
+        let held: Vec<String> = HELD
+            .lock()
+            .map(|h| h.keys().cloned().collect())
+            .unwrap_or_default();
+        if held.is_empty() {
+            continue;
+        }
+        let state = app.state::<Arc<MaaState>>();
+        for id in held {
+            let busy = is_busy(&state, &id);
+            let releasable = HELD.lock().ok().is_some_and(|mut h| {
+                h.get_mut(&id).is_some_and(|lock| {
+                    lock.seen_busy |= busy;
+                    !busy && (lock.seen_busy || lock.acquired.elapsed() >= STARTUP_GRACE)
+                })
+            });
+            if releasable {
+                info!("[device_lock] Releasing device lock of instance {}", id);
+                release(&id);
+            }
+        }
+    });
+}
+
+/// 获取互斥策略
+#[tauri::command]
+pub fn device_lock_get_policy() -> DeviceLockPolicy {
+    load_policy()
+}
+
+/// 设置互斥策略
+#[tauri::command]
+pub fn device_lock_set_policy(policy: DeviceLockPolicy) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    let _guard = CONFIG_LOCK.lock().map_err(|e| e.to_string())?;
+    let path = config_path()?;
+    save_json_config(&path, &policy)
+}
+
+/// 检查实例设备当前的冲突情况（不获取锁）
+#[tauri::command]
+pub fn device_lock_check(instance_id: String) -> Result<Vec<DeviceConflict>, MxuError> {
+    let device =
+        device_of(&instance_id).ok_or_else(|| MxuError::new(ErrorCode::ControllerNotConnected))?;
+    Ok(detect(&device, &load_policy()))
+}
@@ -0,0 +1,373 @@
+//! 首次运行环境诊断
+//!
+//! 依次检查 VC++ 运行库、MaaFramework 加载、ADB、磁盘空间、程序路径、
+//! 被杀毒软件删除的文件与目录写入权限，汇总为结构化报告供前端渲染检查清单
+
+use log::{info, warn};
+use std::path::{Path, PathBuf};
+
+use super::error::MxuError;
+use super::types::{DiagnosticCheck, DiagnosticReport, DiagnosticStatus};
+use super::utils::{available_space, get_app_data_dir, get_exe_directory, get_maafw_dir};
+
+/// 可用空间低于此值时警告
+const LOW_SPACE_WARNING: u64 = 1024 * 1024 * 1024;
+/// 可用空间低于此值时报错（更新、截图与日志都可能写入失败）
+const LOW_SPACE_ERROR: u64 = 200 * 1024 * 1024;
+
+/// MaaFramework 主库文件名
+#[cfg(windows)]
+const MAAFW_LIBRARY: &str = "MaaFramework.dll";
+#[cfg(target_os = "macos")]
+const MAAFW_LIBRARY: &str = "libMaaFramework.dylib";
+#[cfg(target_os = "linux")]
+const MAAFW_LIBRARY: &str = "libMaaFramework.so";
+
+/// maafw 目录中必须存在的库（不含扩展名与前缀），缺失多半是被杀毒软件隔离
+const REQUIRED_MAAFW_LIBRARIES: &[&str] = &[
+    "MaaFramework",
+    "MaaToolkit",
+    "MaaUtils",
+    "MaaAdbControlUnit",
+    #[cfg(windows)]
+    "MaaWin32ControlUnit",
+];
+
+fn check(id: &str, status: DiagnosticStatus, message: impl Into<String>) -> DiagnosticCheck {
+    DiagnosticCheck {
+        id: id.to_string(),
+        status,
+        message: message.into(),
+        details: Vec::new(),
+    }
+}
+
+fn with_details(mut check: DiagnosticCheck, details: Vec<String>) -> DiagnosticCheck {
+    check.details = details;
+    check
+}
+
+fn library_file_name(name: &str) -> String {
+    if cfg!(windows) {
+        format!("{}.dll", name)
+    } else if cfg!(target_os = "macos") {
+        format!("lib{}.dylib", name)
+    } else {
+        format!("lib{}.so", name)
+    }
+}
+
+/// VC++ 运行库：系统目录或 maafw 目录中存在所需的 DLL 即视为已安装
+fn check_vcredist() -> DiagnosticCheck {
+    const ID: &str = "vcredist";
+    if !cfg!(windows) {
+        return check(ID, DiagnosticStatus::Skipped, "仅 Windows 需要 VC++ 运行库");
+    }
+
+    let system_dir = std::env::var_os("SystemRoot")
+        .map(|root| PathBuf::from(root).join("System32"))
+        .unwrap_or_else(|| PathBuf::from(r"C:\Windows\System32"));
+    let maafw_dir = get_maafw_dir().ok();
+
+    let missing: Vec<String> = ["msvcp140.dll", "vcruntime140.dll", "vcruntime140_1.dll"]
+        .into_iter()
+        .filter(|dll| {
+            !system_dir.join(dll).is_file()
+                && !maafw_dir.as_ref().is_some_and(|d| d.join(dll).is_file())
+        })
+        .map(String::from)
+        .collect();
+
+    if missing.is_empty() {
+        check(ID, DiagnosticStatus::Ok, "VC++ 运行库已安装")
+    } else {
+        with_details(
+            check(
+                ID,
+                DiagnosticStatus::Error,
+                "缺少 VC++ 运行库，请安装后重启",
+            ),
+            missing,
+        )
+    }
+}
+
+/// 已加载的 MaaFramework 版本
+fn loaded_maafw_version() -> Option<String> {
+    std::panic::catch_unwind(|| maa_framework::maa_version().to_string())
+        .ok()
+        .filter(|v| !v.is_empty() && v != "unknown")
+}
+
+/// MaaFramework：尚未加载时尝试加载一次
+fn check_maafw() -> DiagnosticCheck {
+    const ID: &str = "maafw";
+    if let Some(version) = loaded_maafw_version() {
+        return check(
+            ID,
+            DiagnosticStatus::Ok,
+            format!("MaaFramework {} 已加载", version),
+        );
+    }
+
+    let dll_path = match get_maafw_dir() {
+        Ok(dir) => dir.join(MAAFW_LIBRARY),
+        Err(e) => return check(ID, DiagnosticStatus::Error, e),
+    };
+    if !dll_path.is_file() {
+        return with_details(
+            check(ID, DiagnosticStatus::Error, "未找到 MaaFramework 运行库"),
+            vec![dll_path.display().to_string()],
+        );
+    }
+
+    match super::system::load_maa_library(&dll_path) {
+        Ok(_) => {
+            let version = loaded_maafw_version().unwrap_or_default();
+            check(
+                ID,
+                DiagnosticStatus::Ok,
+                format!("MaaFramework {} 加载成功", version),
+            )
+        }
+        Err(e) => with_details(
+            check(
+                ID,
+                DiagnosticStatus::Error,
+                format!("MaaFramework 加载失败: {}", e),
+            ),
+            vec![dll_path.display().to_string()],
+        ),
+    }
+}
+
+/// 在 PATH 中查找可执行文件
+fn find_in_path(name: &str) -> Option<PathBuf> {
+    let paths = std::env::var_os("PATH")?;
+    std::env::split_paths(&paths)
+        .map(|dir| dir.join(name))
+        .find(|p| p.is_file())
+}
+
+/// ADB：未在 PATH 中找到时连接模拟器仍可使用其自带的 adb，因此只作警告
+fn check_adb() -> DiagnosticCheck {
+    const ID: &str = "adb";
+    let name = if cfg!(windows) { "adb.exe" } else { "adb" };
+    let Some(adb_path) = find_in_path(name) else {
+        return check(
+            ID,
+            DiagnosticStatus::Warning,
+            "未在 PATH 中找到 adb，连接模拟器时将使用模拟器自带的 adb",
+        );
+    };
+
+    #[cfg(windows)]
+    let mut cmd = {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        let mut c = std::process::Command::new(&adb_path);
+        c.creation_flags(CREATE_NO_WINDOW);
+        c
+    };
+    #[cfg(not(windows))]
+    let mut cmd = std::process::Command::new(&adb_path);
+
+    let details = vec![adb_path.display().to_string()];
+    match cmd.arg("version").output() {
+        Ok(output) if output.status.success() => {
+            let version = String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .to_string();
+            with_details(
+                check(ID, DiagnosticStatus::Ok, format!("adb 可用: {}", version)),
+                details,
+            )
+        }
+        Ok(output) => with_details(
+            check(
+                ID,
+                DiagnosticStatus::Warning,
+                format!("adb 运行失败: {}", output.status),
+            ),
+            details,
+        ),
+        Err(e) => with_details(
+            check(
+                ID,
+                DiagnosticStatus::Warning,
+                format!("adb 无法运行: {}", e),
+            ),
+            details,
+        ),
+    }
+}
+
+/// 需要检查的目录（exe 目录与数据目录，相同时只保留一个）
+fn checked_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = [get_exe_directory(), get_app_data_dir()]
+        .into_iter()
+        .filter_map(Result::ok)
+        .collect();
+    dirs.dedup();
+    dirs
+}
+
+/// 磁盘空间
+fn check_disk_space(dirs: &[PathBuf]) -> DiagnosticCheck {
+    const ID: &str = "disk_space";
+    let mut status = DiagnosticStatus::Ok;
+    let mut details = Vec::new();
+    for dir in dirs {
+        let Some(available) = available_space(dir) else {
+            continue;
+        };
+        if available < LOW_SPACE_ERROR {
+            status = DiagnosticStatus::Error;
+        } else if available < LOW_SPACE_WARNING && status == DiagnosticStatus::Ok {
+            status = DiagnosticStatus::Warning;
+        }
+        details.push(format!("{}: {} MB", dir.display(), available / 1024 / 1024));
+    }
+
+    let message = match status {
+        DiagnosticStatus::Ok => "磁盘空间充足",
+        DiagnosticStatus::Warning => "磁盘可用空间不足 1 GB",
+        _ => "磁盘空间严重不足，更新与日志可能写入失败",
+    };
+    with_details(check(ID, status, message), details)
+}
+
+/// 程序路径（磁盘根目录、临时目录）
+fn check_exe_path() -> DiagnosticCheck {
+    const ID: &str = "exe_path";
+    match super::file_ops::check_exe_path().as_deref() {
+        None => check(ID, DiagnosticStatus::Ok, "程序路径正常"),
+        Some("root") => check(
+            ID,
+            DiagnosticStatus::Error,
+            "程序位于磁盘根目录，请移动到单独的文件夹中",
+        ),
+        Some("temp") => check(
+            ID,
+            DiagnosticStatus::Error,
+            "程序位于临时目录（可能未解压直接运行），请解压到固定的文件夹中",
+        ),
+        Some(other) => check(
+            ID,
+            DiagnosticStatus::Warning,
+            format!("程序路径异常: {}", other),
+        ),
+    }
+}
+
+/// 缺失或被清空的文件（常见于杀毒软件隔离）
+fn check_missing_files() -> DiagnosticCheck {
+    const ID: &str = "missing_files";
+    let Ok(maafw_dir) = get_maafw_dir() else {
+        return check(ID, DiagnosticStatus::Skipped, "无法获取 maafw 目录");
+    };
+    if !maafw_dir.is_dir() {
+        return with_details(
+            check(ID, DiagnosticStatus::Error, "maafw 目录不存在"),
+            vec![maafw_dir.display().to_string()],
+        );
+    }
+
+    let mut problems: Vec<String> = REQUIRED_MAAFW_LIBRARIES
+        .iter()
+        .map(|name| maafw_dir.join(library_file_name(name)))
+        .filter(|p| !p.is_file())
+        .map(|p| format!("缺失: {}", p.display()))
+        .collect();
+    // 部分杀毒软件会将文件清空而非删除
+    if let Ok(entries) = std::fs::read_dir(&maafw_dir) {
+        problems.extend(
+            entries
+                .filter_map(|e| e.ok())
+                .filter(|e| e.metadata().is_ok_and(|m| m.is_file() && m.len() == 0))
+                .map(|e| format!("文件为空: {}", e.path().display())),
+        );
+    }
+
+    if problems.is_empty() {
+        check(ID, DiagnosticStatus::Ok, "运行库文件完整")
+    } else {
+        with_details(
+            check(
+                ID,
+                DiagnosticStatus::Error,
+                "运行库文件缺失或损坏，可能被杀毒软件隔离，请添加信任后重新解压",
+            ),
+            problems,
+        )
+    }
+}
+
+/// 尝试在目录中创建并删除临时文件
+fn probe_write(dir: &Path) -> Result<(), String> {
+    let probe = dir.join(".mxu_write_test");
+    std::fs::write(&probe, b"").map_err(|e| e.to_string())?;
+    let _ = std::fs::remove_file(&probe);
+    Ok(())
+}
+
+/// 目录写入权限
+fn check_write_permission(dirs: &[PathBuf]) -> DiagnosticCheck {
+    const ID: &str = "write_permission";
+    let failures: Vec<String> = dirs
+        .iter()
+        .filter_map(|dir| {
+            probe_write(dir)
+                .err()
+                .map(|e| format!("{}: {}", dir.display(), e))
+        })
+        .collect();
+
+    if failures.is_empty() {
+        check(ID, DiagnosticStatus::Ok, "目录可写")
+    } else {
+        with_details(
+            check(
+                ID,
+                DiagnosticStatus::Error,
+                "目录无写入权限，配置与日志无法保存，请移动到其他位置或以管理员身份运行",
+            ),
+            failures,
+        )
+    }
+}
+
+/// 运行环境诊断
+#[tauri::command]
+pub async fn run_diagnostics() -> Result<DiagnosticReport, MxuError> {
+    info!("run_diagnostics called");
+
+    let checks = tauri::async_runtime::spawn_blocking(|| {
+        let dirs = checked_dirs();
+        vec![
+            check_vcredist(),
+            check_maafw(),
+            check_adb(),
+            check_disk_space(&dirs),
+            check_exe_path(),
+            check_missing_files(),
+            check_write_permission(&dirs),
+        ]
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    for c in checks.iter().filter(|c| {
+        matches!(
+            c.status,
+            DiagnosticStatus::Warning | DiagnosticStatus::Error
+        )
+    }) {
+        warn!("[diagnostics] {} {:?}: {}", c.id, c.status, c.message);
+    }
+
+    let has_errors = checks.iter().any(|c| c.status == DiagnosticStatus::Error);
+    Ok(DiagnosticReport { checks, has_errors })
+}
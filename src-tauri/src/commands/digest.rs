@@ -0,0 +1,357 @@
+//! 周报摘要
+//!
+//! 每次运行结束时向 runs/history.jsonl 追加一条运行历史（状态、失败任务、数值型任务变量），
+//! 按配置的星期与时间汇总最近 7 天的历史（运行次数、成功率、变量累计值、按任务统计的失败次数），
+//! 格式化后通过系统通知或 Webhook 发送。
+//! 错过的发送时间（如 MXU 未运行）在下次检查时补发一次
+
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, Local, TimeZone};
+use log::{info, warn};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use super::error::MxuError;
+use super::i18n::ErrorCode;
+use super::types::{DigestChannel, DigestConfig, DigestSummary, RunHistoryEntry, ToastAction};
+use super::utils::{get_app_data_dir, save_json_config};
+
+/// 检查是否到达发送时间的间隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// Webhook 请求超时
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+/// 正文中列出的失败任务数量上限
+const MAX_FAILURE_LINES: usize = 5;
+
+/// 配置与历史文件读写锁
+static CONFIG_LOCK: Mutex<()> = Mutex::new(());
+static HISTORY_LOCK: Mutex<()> = Mutex::new(());
+
+fn config_path() -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?.join("config").join("digests.json"))
+}
+
+fn history_path() -> PathBuf {
+    super::runs::get_runs_dir().join("history.jsonl")
+}
+
+fn load_digests() -> Result<Vec<DigestConfig>, String> {
+    let path = config_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(&path).map_err(|e| format!("无法读取周报配置: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("无法解析周报配置: {}", e))
+}
+
+fn save_digests(digests: &[DigestConfig]) -> Result<(), String> {
+    let path = config_path()?;
+    Ok(save_json_config(&path, digests)?)
+}
+
+/// 追加一条运行历史（runs 模块在运行结束时调用）
+pub fn record_run(entry: RunHistoryEntry) {
+    let _guard = HISTORY_LOCK.lock();
+    let path = history_path();
+    let result = serde_json::to_string(&entry)
+        .map_err(|e| e.to_string())
+        .and_then(|line| {
+            std::fs::create_dir_all(super::runs::get_runs_dir()).map_err(|e| e.to_string())?;
+            let mut file = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .map_err(|e| e.to_string())?;
+            writeln!(file, "{}", line).map_err(|e| e.to_string())
+        });
+    if let Err(e) = result {
+        warn!("[digest] Failed to record run {}: {}", entry.run_id, e);
+    }
+}
+
+/// 读取结束时间在 [from, to) 内的运行历史
+fn load_history(from: DateTime<Local>, to: DateTime<Local>) -> Vec<RunHistoryEntry> {
+    let _guard = HISTORY_LOCK.lock();
+    let Ok(content) = std::fs::read_to_string(history_path()) else {
+        return Vec::new();
+    };
+    content
+        .lines()
+        .filter_map(|line| serde_json::from_str::<RunHistoryEntry>(line).ok())
+        .filter(|entry| {
+            DateTime::parse_from_rfc3339(&entry.finished_at).is_ok_and(|t| t >= from && t < to)
+        })
+        .collect()
+}
+
+fn is_success(entry: &RunHistoryEntry) -> bool {
+    entry.failed_tasks.is_empty() && matches!(entry.status.as_str(), "succeeded" | "completed")
+}
+
+/// 格式化数值（整数不带小数位）
+fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{}", value as i64)
+    } else {
+        format!("{:.2}", value)
+    }
+}
+
+/// 汇总区间内的运行历史
+fn summarize(from: DateTime<Local>, to: DateTime<Local>, instances: &[String]) -> DigestSummary {
+    let entries: Vec<RunHistoryEntry> = load_history(from, to)
+        .into_iter()
+        .filter(|e| instances.is_empty() || instances.contains(&e.instance_id))
+        .collect();
+
+    let succeeded = entries.iter().filter(|e| is_success(e)).count();
+    let mut counters: BTreeMap<String, f64> = BTreeMap::new();
+    let mut failures: BTreeMap<String, usize> = BTreeMap::new();
+    for entry in &entries {
+        for (name, value) in &entry.counters {
+            *counters.entry(name.clone()).or_default() += value;
+        }
+        for task in &entry.failed_tasks {
+            *failures.entry(task.clone()).or_default() += 1;
+        }
+    }
+    let success_rate = if entries.is_empty() {
+        0.0
+    } else {
+        succeeded as f64 / entries.len() as f64
+    };
+
+    let title = format!(
+        "MXU 周报 {} ~ {}",
+        from.format("%m-%d"),
+        (to - ChronoDuration::seconds(1)).format("%m-%d")
+    );
+    let mut lines = vec![format!(
+        "运行 {} 次，成功 {} 次，成功率 {:.0}%",
+        entries.len(),
+        succeeded,
+        success_rate * 100.0
+    )];
+    if !counters.is_empty() {
+        let items: Vec<String> = counters
+            .iter()
+            .map(|(name, value)| format!("{} {}", name, format_number(*value)))
+            .collect();
+        lines.push(format!("累计: {}", items.join("，")));
+    }
+    if !failures.is_empty() {
+        let mut sorted: Vec<(&String, &usize)> = failures.iter().collect();
+        sorted.sort_by(|a, b| b.1.cmp(a.1));
+        let items: Vec<String> = sorted
+            .iter()
+            .take(MAX_FAILURE_LINES)
+            .map(|(task, count)| format!("{} ×{}", task, count))
+            .collect();
+        lines.push(format!("失败: {}", items.join("，")));
+    }
+
+    DigestSummary {
+        from: from.to_rfc3339(),
+        to: to.to_rfc3339(),
+        runs: entries.len(),
+        succeeded,
+        failed: entries.len() - succeeded,
+        success_rate,
+        task_count: entries.iter().map(|e| e.task_count).sum(),
+        counters,
+        failures,
+        title,
+        body: lines.join("\n"),
+    }
+}
+
+/// now 之前（含）最近一次的发送时间
+fn last_scheduled(digest: &DigestConfig, now: DateTime<Local>) -> Option<DateTime<Local>> {
+    (0..=7).find_map(|days_ago| {
+        let date = (now - ChronoDuration::days(days_ago)).date_naive();
+        if date.weekday().num_days_from_sunday() != digest.weekday {
+            return None;
+        }
+        let time = date.and_hms_opt(digest.hour, digest.minute, 0)?;
+        let at = Local.from_local_datetime(&time).earliest()?;
+        (at <= now).then_some(at)
+    })
+}
+
+/// 通过单个渠道发送
+fn send(channel: &DigestChannel, summary: &DigestSummary) -> Result<(), String> {
+    match channel {
+        DigestChannel::System => {
+            super::notifications::show(&summary.title, &summary.body, &[ToastAction::Open], None)
+        }
+        DigestChannel::Webhook { url } => {
+            let response = reqwest::blocking::Client::builder()
+                .timeout(WEBHOOK_TIMEOUT)
+                .build()
+                .map_err(|e| format!("创建 HTTP 客户端失败: {}", e))?
+                .post(url)
+                .json(summary)
+                .send()
+                .map_err(|e| format!("Webhook 请求失败: {}", e))?;
+            if !response.status().is_success() {
+                return Err(format!("Webhook 返回 HTTP {}", response.status()));
+            }
+            Ok(())
+        }
+    }
+}
+
+/// 汇总并通过全部渠道发送，任一渠道失败时返回错误（其余渠道仍会发送）
+fn deliver(digest: &DigestConfig, to: DateTime<Local>) -> Result<DigestSummary, String> {
+    let summary = summarize(to - ChronoDuration::days(7), to, &digest.instances);
+    let errors: Vec<String> = digest
+        .channels
+        .iter()
+        .filter_map(|channel| send(channel, &summary).err())
+        .collect();
+    info!(
+        "[digest] Sent digest {} ({} run(s), {} channel error(s))",
+        digest.name,
+        summary.runs,
+        errors.len()
+    );
+    if errors.is_empty() {
+        Ok(summary)
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
+/// 发送到期的周报
+fn check_due() {
+    let now = Local::now();
+    let due: Vec<(DigestConfig, DateTime<Local>)> = match load_digests() {
+        Ok(digests) => digests
+            .into_iter()
+            .filter(|d| d.enabled && !d.channels.is_empty())
+            .filter_map(|d| {
+                let scheduled = last_scheduled(&d, now)?;
+                let sent = d
+                    .last_sent
+                    .as_deref()
+                    .and_then(|s| DateTime::parse_from_rfc3339(s).ok());
+                sent.is_none_or(|sent| sent < scheduled)
+                    .then_some((d, scheduled))
+            })
+            .collect(),
+        Err(e) => {
+            warn!("[digest] Failed to load digests: {}", e);
+            return;
+        }
+    };
+
+    for (digest, scheduled) in due {
+        if let Err(e) = deliver(&digest, scheduled) {
+            warn!("[digest] Failed to send digest {}: {}", digest.name, e);
+        }
+        // 发送失败也记录，避免每分钟重试
+        let _guard = CONFIG_LOCK.lock();
+        if let Ok(mut digests) = load_digests() {
+            if let Some(d) = digests.iter_mut().find(|d| d.id == digest.id) {
+                d.last_sent = Some(now.to_rfc3339());
+            }
+            if let Err(e) = save_digests(&digests) {
+                warn!("[digest] Failed to save digest state: {}", e);
+            }
+        }
+    }
+}
+
+/// 启动周报发送检查线程（setup 中调用）
+pub fn spawn_digest_scheduler() {
+    thread::spawn(|| loop {
+        check_due();
+        thread::sleep(CHECK_INTERVAL);
+    });
+}
+
+fn validate(digest: &DigestConfig) -> Result<(), String> {
+    if digest.weekday > 6 || digest.hour > 23 || digest.minute > 59 {
+        return Err("无效的发送时间".to_string());
+    }
+    for channel in &digest.channels {
+        if let DigestChannel::Webhook { url } = channel {
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                return Err(format!("无效的 Webhook 地址: {}", url));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 列出周报配置
+#[tauri::command]
+pub fn digest_list() -> Result<Vec<DigestConfig>, MxuError> {
+    Ok(load_digests()?)
+}
+
+/// 新增或更新周报配置（id 为空时新增）
+#[tauri::command]
+pub fn digest_save(mut digest: DigestConfig) -> Result<DigestConfig, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    validate(&digest)?;
+    let _guard = CONFIG_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut digests = load_digests()?;
+    let existing = digests
+        .iter()
+        .position(|d| !digest.id.is_empty() && d.id == digest.id);
+    match existing {
+        Some(index) => {
+            // 修改配置不影响发送状态
+            digest.last_sent = digests[index].last_sent.clone();
+            digests[index] = digest.clone();
+        }
+        None => {
+            digest.id = Local::now().format("%Y%m%d%H%M%S%3f").to_string();
+            // 从保存时开始计算，不补发已过去的发送时间
+            digest.last_sent = Some(Local::now().to_rfc3339());
+            digests.push(digest.clone());
+        }
+    }
+    save_digests(&digests)?;
+    info!("[digest] Saved digest {} ({})", digest.name, digest.id);
+    Ok(digest)
+}
+
+/// 删除周报配置
+#[tauri::command]
+pub fn digest_delete(id: String) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    let _guard = CONFIG_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut digests = load_digests()?;
+    let before = digests.len();
+    digests.retain(|d| d.id != id);
+    if digests.len() == before {
+        return Err(MxuError::with_args(ErrorCode::DigestNotFound, [&id]));
+    }
+    Ok(save_digests(&digests)?)
+}
+
+/// 预览最近 7 天的周报（不发送）
+#[tauri::command]
+pub fn digest_preview(instances: Vec<String>) -> DigestSummary {
+    let now = Local::now();
+    summarize(now - ChronoDuration::days(7), now, &instances)
+}
+
+/// 立即发送指定周报（统计截至当前，不影响定时发送）
+#[tauri::command]
+pub async fn digest_send_now(id: String) -> Result<DigestSummary, MxuError> {
+    let digest = load_digests()?
+        .into_iter()
+        .find(|d| d.id == id)
+        .ok_or_else(|| format!("周报不存在: {}", id))?;
+    Ok(
+        tauri::async_runtime::spawn_blocking(move || deliver(&digest, Local::now()))
+            .await
+            .map_err(|e| e.to_string())??,
+    )
+}
@@ -7,12 +7,14 @@ use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 
 use tauri::Emitter;
 
+use super::error::MxuError;
+use super::i18n::ErrorCode;
 use super::types::GitHubRelease;
 use reqwest::header::{ACCEPT, USER_AGENT, AUTHORIZATION};
 
 use super::types::{DownloadProgressEvent, DownloadResult};
 use super::update::move_to_old_folder;
-use super::utils::build_user_agent;
+use super::utils::{build_user_agent, ensure_free_space};
 
 /// 全局下载取消标志
 static DOWNLOAD_CANCELLED: AtomicBool = AtomicBool::new(false);
@@ -30,7 +32,7 @@ pub async fn get_github_release_by_version(
     target_version: String,
     github_pat: Option<String>,
     proxy_url: Option<String>,
-) -> Result<Option<GitHubRelease>, String> {
+) -> Result<Option<GitHubRelease>, MxuError> {
     let url = format!(
         "https://api.github.com/repos/{}/{}/releases",
         owner, repo
@@ -83,7 +85,7 @@ pub async fn get_github_release_by_version(
         .map_err(|e| format!("请求失败: {}", e))?;
 
     if !response.status().is_success() {
-        return Err(format!("GitHub API 错误: {}", response.status()));
+        return Err(MxuError::with_args(ErrorCode::HttpRequestFailed, [response.status()]));
     }
 
     let releases: Vec<GitHubRelease> = response
@@ -119,7 +121,8 @@ pub async fn download_file(
     save_path: String,
     total_size: Option<u64>,
     proxy_url: Option<String>,
-) -> Result<DownloadResult, String> {
+) -> Result<DownloadResult, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
     use futures_util::StreamExt;
     use std::io::Write;
 
@@ -176,7 +179,7 @@ pub async fn download_file(
         .map_err(|e| format!("请求失败: {}", e))?;
 
     if !response.status().is_success() {
-        return Err(format!("HTTP 错误: {}", response.status()));
+        return Err(MxuError::with_args(ErrorCode::HttpRequestFailed, [response.status()]));
     }
 
     // 尝试从 Content-Disposition header 或最终 URL 提取文件名
@@ -206,9 +209,19 @@ pub async fn download_file(
     let content_length = response.content_length();
     let total = total_size.or(content_length).unwrap_or(0);
 
+    // 预检磁盘空间（大小未知时跳过）
+    if total > 0 {
+        if let Some(parent) = actual_save_path_obj.parent() {
+            ensure_free_space(parent, total)?;
+        }
+    }
+
     // 创建临时文件
     let mut file = std::fs::File::create(&temp_path).map_err(|e| format!("无法创建文件: {}", e))?;
 
+    // 任务栏显示下载进度，结束（含失败与取消）时清除
+    let taskbar_progress = crate::taskbar::ProgressGuard::new("download");
+
     // 流式下载
     let mut stream = response.bytes_stream();
     let mut downloaded: u64 = 0;
@@ -227,7 +240,7 @@ pub async fn download_file(
             drop(file);
             // 清理临时文件
             let _ = std::fs::remove_file(&temp_path);
-            return Err("下载已取消".to_string());
+            return Err(MxuError::new(ErrorCode::Cancelled));
         }
 
         let chunk = chunk.map_err(|e| format!("下载数据失败: {}", e))?;
@@ -264,6 +277,7 @@ pub async fn download_file(
                     progress,
                 },
             );
+            taskbar_progress.set_progress(downloaded, total);
 
             last_progress_time = now;
             last_downloaded = downloaded;
@@ -280,7 +294,7 @@ pub async fn download_file(
         );
         drop(file);
         let _ = std::fs::remove_file(&temp_path);
-        return Err("下载已取消".to_string());
+        return Err(MxuError::new(ErrorCode::Cancelled));
     }
 
     // 写入剩余缓冲区
@@ -328,7 +342,7 @@ pub async fn download_file(
 
 /// 取消下载
 #[tauri::command]
-pub fn cancel_download(save_path: String) -> Result<(), String> {
+pub fn cancel_download(save_path: String) -> Result<(), MxuError> {
     info!("cancel_download called for: {}", save_path);
 
     // 设置取消标志，让下载循环退出
@@ -0,0 +1,78 @@
+//! 命令错误类型
+//!
+//! 所有 Tauri 命令返回 `MxuError`，序列化为 `{ code, message, details }`：
+//! - `code`: 错误码（见 `i18n::ErrorCode`），前端据此区分错误类型而不必匹配字符串
+//! - `message`: 按当前语言生成、可直接展示的信息
+//! - `details`: 可选的附加数据（如消息参数）
+//!
+//! 面向用户的错误须通过 `MxuError::new` / `MxuError::with_args` 使用目录中的错误码；
+//! 内部辅助函数（I/O、锁、FFI 等）仍返回 `Result<_, String>`，仅这类失败经 `?` 转换为 `Internal` 错误
+
+use serde::Serialize;
+use serde_json::Value;
+
+use super::i18n::{self, ErrorCode};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MxuError {
+    pub code: ErrorCode,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<Value>,
+}
+
+impl MxuError {
+    pub fn new(code: ErrorCode) -> Self {
+        Self::with_args(code, Vec::<String>::new())
+    }
+
+    /// 带消息参数的错误，参数同时放入 details.args
+    pub fn with_args<I, T>(code: ErrorCode, args: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: ToString,
+    {
+        let args: Vec<String> = args.into_iter().map(|a| a.to_string()).collect();
+        Self {
+            code,
+            message: i18n::message(code, &args),
+            details: (!args.is_empty()).then(|| serde_json::json!({ "args": args })),
+        }
+    }
+
+    /// 未分类的错误，信息原样保留
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self {
+            code: ErrorCode::Internal,
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    /// 附加数据
+    pub fn with_details(mut self, details: Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+}
+
+impl std::fmt::Display for MxuError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for MxuError {}
+
+/// 仅用于内部辅助函数失败的 `?` 传播，不应用于构造面向用户的错误
+impl From<String> for MxuError {
+    fn from(message: String) -> Self {
+        Self::internal(message)
+    }
+}
+
+impl From<MxuError> for String {
+    fn from(e: MxuError) -> Self {
+        e.message
+    }
+}
@@ -0,0 +1,144 @@
+//! 问题反馈
+//!
+//! 汇总版本、系统、设备类型与最近的错误日志，生成预填的 issue 正文并打开仓库的新建 issue 页面，
+//! 日志压缩包仍由用户通过「导出日志」生成后手动附加
+
+use log::{info, warn};
+use tauri::AppHandle;
+use tauri_plugin_opener::OpenerExt;
+
+use super::error::MxuError;
+use super::i18n::ErrorCode;
+use super::types::FeedbackDraft;
+use super::utils::read_backend_log_tail;
+
+/// 读取日志末尾的字节数
+const LOG_TAIL_BYTES: u64 = 256 * 1024;
+/// 最多附带的错误行数与单行长度
+const MAX_ERROR_LINES: usize = 8;
+const MAX_ERROR_LINE_CHARS: usize = 200;
+/// 预填链接的最大长度（超过时省略错误日志，避免浏览器或 GitHub 拒绝）
+const MAX_URL_LEN: usize = 8000;
+
+/// 从后端日志末尾提取最近的错误行
+fn recent_error_lines() -> Vec<String> {
+    let Some(content) = read_backend_log_tail(LOG_TAIL_BYTES) else {
+        return Vec::new();
+    };
+    let mut lines: Vec<String> = content
+        .lines()
+        .filter(|line| line.contains("][ERROR]"))
+        .map(|line| line.chars().take(MAX_ERROR_LINE_CHARS).collect())
+        .collect();
+    let skip = lines.len().saturating_sub(MAX_ERROR_LINES);
+    lines.drain(..skip);
+    lines
+}
+
+fn build_body(
+    app: &AppHandle,
+    project_name: &str,
+    project_version: &str,
+    description: &str,
+    errors: &[String],
+) -> String {
+    let info = super::system::get_system_info();
+    let maafw_version = super::maa_core::maa_get_version().unwrap_or_else(|_| "未加载".to_string());
+    let device = super::startup_actions::last_device_kind().unwrap_or_else(|| "未知".to_string());
+
+    let mut body = String::new();
+    body.push_str("### 问题描述\n\n");
+    if description.trim().is_empty() {
+        body.push_str("<!-- 请描述遇到的问题、复现步骤与期望结果 -->\n\n");
+    } else {
+        body.push_str(description.trim());
+        body.push_str("\n\n");
+    }
+
+    body.push_str("### 环境信息\n\n");
+    body.push_str(&format!(
+        "- 项目: {} {}\n",
+        project_name,
+        if project_version.is_empty() {
+            "未知版本"
+        } else {
+            project_version
+        }
+    ));
+    body.push_str(&format!("- MXU: {}\n", app.package_info().version));
+    body.push_str(&format!("- MaaFramework: {}\n", maafw_version));
+    body.push_str(&format!("- Tauri: {}\n", info.tauri_version));
+    body.push_str(&format!("- 系统: {} ({})\n", info.os_version, info.arch));
+    body.push_str(&format!("- 设备类型: {}\n\n", device));
+
+    if !errors.is_empty() {
+        body.push_str("### 最近的错误日志\n\n```\n");
+        for line in errors {
+            body.push_str(line);
+            body.push('\n');
+        }
+        body.push_str("```\n\n");
+    }
+
+    body.push_str("### 日志\n\n");
+    body.push_str("请在 MXU 设置中点击「导出日志」，并将生成的 zip 压缩包拖拽到此处上传。\n");
+    body
+}
+
+fn build_issue_url(repo_url: &str, title: &str, body: &str) -> String {
+    format!(
+        "{}/issues/new?title={}&body={}",
+        repo_url,
+        urlencoding::encode(title),
+        urlencoding::encode(body)
+    )
+}
+
+/// 生成预填的问题反馈并（默认）在浏览器中打开新建 issue 页面
+///
+/// repo_url 为项目仓库地址（如 https://github.com/owner/repo），open 为 false 时仅返回草稿
+#[tauri::command]
+pub fn prepare_feedback(
+    app: AppHandle,
+    repo_url: String,
+    project_name: Option<String>,
+    project_version: Option<String>,
+    title: Option<String>,
+    description: Option<String>,
+    open: Option<bool>,
+) -> Result<FeedbackDraft, MxuError> {
+    let repo_url = repo_url
+        .trim()
+        .trim_end_matches('/')
+        .trim_end_matches(".git")
+        .to_string();
+    if !repo_url.starts_with("https://") && !repo_url.starts_with("http://") {
+        return Err(MxuError::with_args(ErrorCode::InvalidUrl, [&repo_url]));
+    }
+
+    let project_name = project_name.unwrap_or_else(|| "MXU".to_string());
+    let project_version = project_version.unwrap_or_default();
+    let description = description.unwrap_or_default();
+    let title = title
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or_else(|| format!("[{}] ", project_name));
+
+    let errors = recent_error_lines();
+    let mut body = build_body(&app, &project_name, &project_version, &description, &errors);
+    let mut url = build_issue_url(&repo_url, &title, &body);
+    if url.len() > MAX_URL_LEN {
+        // 链接过长时省略错误日志，完整内容见导出的日志压缩包
+        body = build_body(&app, &project_name, &project_version, &description, &[]);
+        url = build_issue_url(&repo_url, &title, &body);
+    }
+
+    if open.unwrap_or(true) {
+        if let Err(e) = app.opener().open_url(&url, None::<&str>) {
+            warn!("[feedback] Failed to open issue page: {}", e);
+            return Err(MxuError::with_args(ErrorCode::OpenUrlFailed, [e]));
+        }
+        info!("[feedback] Opened new issue page for {}", repo_url);
+    }
+
+    Ok(FeedbackDraft { title, body, url })
+}
@@ -0,0 +1,365 @@
+//! MaaFramework 调用超时与死锁检测
+//!
+//! 前端频繁调用的同步命令（停止、状态查询、截图）先从实例中取出句柄并释放实例锁，
+//! 再在独立线程中执行原生调用并按 config/ffi_timeouts.json 中的超时等待结果，
+//! 避免原生代码卡死时连带阻塞实例锁与界面。
+//! 看守线程发现调用超过阈值仍未返回时记录线程信息并将实例标记为异常（instance-unhealthy 事件），
+//! 之后该实例的调用直接返回错误，需通过 recover_instance 重建运行时后重新连接
+
+use log::{error, info, warn};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Emitter, State};
+
+use super::error::MxuError;
+use super::i18n::ErrorCode;
+use super::types::{FfiTimeoutConfig, InstanceHealth, InstanceRuntime, MaaState, StuckCall};
+use super::utils::{get_app_data_dir, save_json_config};
+
+/// 看守线程检查间隔
+const WATCHDOG_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 进行中的调用
+struct InFlight {
+    instance_id: String,
+    operation: &'static str,
+    started: Instant,
+    thread: String,
+    /// 是否已被看守线程报告
+    reported: bool,
+}
+
+static NEXT_CALL_ID: AtomicU64 = AtomicU64::new(1);
+
+static IN_FLIGHT: LazyLock<Mutex<HashMap<u64, InFlight>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 异常实例及原因
+static UNHEALTHY: LazyLock<Mutex<HashMap<String, String>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+static CONFIG: LazyLock<Mutex<Option<FfiTimeoutConfig>>> = LazyLock::new(|| Mutex::new(None));
+
+fn config_path() -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?.join("config").join("ffi_timeouts.json"))
+}
+
+/// 读取配置（缓存，修改配置时刷新）
+fn config() -> FfiTimeoutConfig {
+    if let Some(config) = CONFIG.lock().ok().and_then(|c| c.clone()) {
+        return config;
+    }
+    let config = config_path()
+        .ok()
+        .and_then(|p| std::fs::read_to_string(p).ok())
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default();
+    if let Ok(mut cache) = CONFIG.lock() {
+        *cache = Some(config.clone());
+    }
+    config
+}
+
+/// 配置文件被外部修改后刷新缓存（配置无法解析时保持当前配置）
+pub fn reload_ffi_timeouts() -> Result<(), String> {
+    let path = config_path()?;
+    let config = if path.exists() {
+        let content =
+            std::fs::read_to_string(&path).map_err(|e| format!("无法读取超时配置: {}", e))?;
+        serde_json::from_str(&content).map_err(|e| format!("无法解析超时配置: {}", e))?
+    } else {
+        FfiTimeoutConfig::default()
+    };
+    *CONFIG.lock().map_err(|e| e.to_string())? = Some(config);
+    Ok(())
+}
+
+fn mark_unhealthy(instance_id: &str, reason: String) -> bool {
+    let Ok(mut unhealthy) = UNHEALTHY.lock() else {
+        return false;
+    };
+    if unhealthy.contains_key(instance_id) {
+        return false;
+    }
+    error!(
+        "[ffi_guard] Instance {} marked unhealthy: {}",
+        instance_id, reason
+    );
+    unhealthy.insert(instance_id.to_string(), reason);
+    true
+}
+
+/// 实例异常时返回错误，引导用户恢复
+pub fn ensure_healthy(instance_id: &str) -> Result<(), String> {
+    match UNHEALTHY
+        .lock()
+        .ok()
+        .and_then(|u| u.get(instance_id).cloned())
+    {
+        Some(reason) => Err(format!(
+            "实例状态异常（{}），请执行恢复操作后重新连接",
+            reason
+        )),
+        None => Ok(()),
+    }
+}
+
+/// 在独立线程中执行原生调用并等待结果，超时返回错误（调用线程会继续等待原生调用返回）
+pub fn call<T, F>(instance_id: &str, operation: &'static str, f: F) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    ensure_healthy(instance_id)?;
+
+    let config = config();
+    let timeout = Duration::from_secs(
+        config
+            .per_operation
+            .get(operation)
+            .copied()
+            .unwrap_or(config.default_timeout_secs)
+            .max(1),
+    );
+
+    let call_id = NEXT_CALL_ID.fetch_add(1, Ordering::Relaxed);
+    let thread_name = format!("maa-{}-{}", operation, call_id);
+    if let Ok(mut in_flight) = IN_FLIGHT.lock() {
+        in_flight.insert(
+            call_id,
+            InFlight {
+                instance_id: instance_id.to_string(),
+                operation,
+                started: Instant::now(),
+                thread: thread_name.clone(),
+                reported: false,
+            },
+        );
+    }
+
+    let (tx, rx) = mpsc::channel();
+    let spawned = std::thread::Builder::new()
+        .name(thread_name)
+        .spawn(move || {
+            let result = f();
+            if let Ok(mut in_flight) = IN_FLIGHT.lock() {
+                in_flight.remove(&call_id);
+            }
+            let _ = tx.send(result);
+        });
+    if let Err(e) = spawned {
+        if let Ok(mut in_flight) = IN_FLIGHT.lock() {
+            in_flight.remove(&call_id);
+        }
+        return Err(format!("无法创建调用线程: {}", e));
+    }
+
+    rx.recv_timeout(timeout).map_err(|_| {
+        warn!(
+            "[ffi_guard] {} on instance {} timed out after {}s",
+            operation,
+            instance_id,
+            timeout.as_secs()
+        );
+        format!("{} 调用超时（{} 秒）", operation, timeout.as_secs())
+    })
+}
+
+/// 进程线程列表（尽力而为，用于死锁诊断日志）
+#[cfg(target_os = "linux")]
+fn thread_dump() -> String {
+    let Ok(entries) = std::fs::read_dir("/proc/self/task") else {
+        return String::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let tid = entry.file_name().to_string_lossy().to_string();
+            let comm = std::fs::read_to_string(entry.path().join("comm")).unwrap_or_default();
+            let stat = std::fs::read_to_string(entry.path().join("stat")).unwrap_or_default();
+            // stat 格式：tid (comm) state ...
+            let state = stat
+                .rsplit(')')
+                .next()?
+                .split_whitespace()
+                .next()?
+                .to_string();
+            Some(format!("  {} {} [{}]", tid, comm.trim(), state))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(windows)]
+fn thread_dump() -> String {
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, Thread32First, Thread32Next, TH32CS_SNAPTHREAD, THREADENTRY32,
+    };
+
+    let pid = std::process::id();
+    let mut lines = Vec::new();
+    unsafe {
+        let Ok(snapshot) = CreateToolhelp32Snapshot(TH32CS_SNAPTHREAD, 0) else {
+            return String::new();
+        };
+        let mut entry = THREADENTRY32 {
+            dwSize: std::mem::size_of::<THREADENTRY32>() as u32,
+            ..Default::default()
+        };
+        if Thread32First(snapshot, &mut entry).is_ok() {
+            loop {
+                if entry.th32OwnerProcessID == pid {
+                    lines.push(format!(
+                        "  {} priority={}",
+                        entry.th32ThreadID, entry.tpBasePri
+                    ));
+                }
+                if Thread32Next(snapshot, &mut entry).is_err() {
+                    break;
+                }
+            }
+        }
+        let _ = CloseHandle(snapshot);
+    }
+    lines.join("\n")
+}
+
+#[cfg(all(not(windows), not(target_os = "linux")))]
+fn thread_dump() -> String {
+    String::new()
+}
+
+fn stuck_calls(instance_id: &str) -> Vec<StuckCall> {
+    IN_FLIGHT
+        .lock()
+        .map(|in_flight| {
+            in_flight
+                .values()
+                .filter(|c| c.instance_id == instance_id)
+                .map(|c| StuckCall {
+                    operation: c.operation.to_string(),
+                    elapsed_secs: c.started.elapsed().as_secs(),
+                    thread: c.thread.clone(),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn health_of(instance_id: &str) -> InstanceHealth {
+    let reason = UNHEALTHY
+        .lock()
+        .ok()
+        .and_then(|u| u.get(instance_id).cloned());
+    InstanceHealth {
+        instance_id: instance_id.to_string(),
+        healthy: reason.is_none(),
+        reason,
+        stuck_calls: stuck_calls(instance_id),
+    }
+}
+
+/// 启动死锁看守线程
+pub fn spawn_ffi_watchdog(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(WATCHDOG_INTERVAL);
+        let threshold = Duration::from_secs(config().stuck_threshold_secs.max(1));
+
+        let newly_stuck: Vec<(String, &'static str, u64, String)> = match IN_FLIGHT.lock() {
+            Ok(mut in_flight) => in_flight
+                .values_mut()
+                .filter(|c| !c.reported && c.started.elapsed() >= threshold)
+                .map(|c| {
+                    c.reported = true;
+                    (
+                        c.instance_id.clone(),
+                        c.operation,
+                        c.started.elapsed().as_secs(),
+                        c.thread.clone(),
+                    )
+                })
+                .collect(),
+            Err(_) => continue,
+        };
+        if newly_stuck.is_empty() {
+            continue;
+        }
+
+        let dump = thread_dump();
+        for (instance_id, operation, elapsed, thread) in newly_stuck {
+            error!(
+                "[ffi_guard] {} on instance {} has not returned for {}s (thread {}), \
+                 possible deadlock in native code",
+                operation, instance_id, elapsed, thread
+            );
+            if !dump.is_empty() {
+                error!("[ffi_guard] Threads:\n{}", dump);
+            }
+            let reason = format!("{} 已 {} 秒未返回", operation, elapsed);
+            if mark_unhealthy(&instance_id, reason) {
+                let _ = app.emit("instance-unhealthy", health_of(&instance_id));
+            }
+        }
+    });
+}
+
+/// 获取实例健康状态
+#[tauri::command]
+pub fn get_instance_health(instance_id: String) -> InstanceHealth {
+    health_of(&instance_id)
+}
+
+/// 恢复异常实例：停止队列并丢弃原有运行时（在后台线程释放，避免再次卡住），
+/// 之后需重新加载资源并连接控制器
+#[tauri::command]
+pub fn recover_instance(
+    app: AppHandle,
+    state: State<Arc<MaaState>>,
+    instance_id: String,
+) -> Result<(), MxuError> {
+    super::task_queue::cancel_queue(&state, &instance_id);
+
+    let old = {
+        let mut instances = state.instances.lock().map_err(|e| e.to_string())?;
+        let runtime = instances
+            .get_mut(&instance_id)
+            .ok_or_else(|| MxuError::new(ErrorCode::InstanceNotFound))?;
+        std::mem::take::<InstanceRuntime>(runtime)
+    };
+    std::thread::spawn(move || drop(old));
+
+    if let Ok(mut unhealthy) = UNHEALTHY.lock() {
+        unhealthy.remove(&instance_id);
+    }
+    // 卡住的调用不再计入该实例
+    if let Ok(mut in_flight) = IN_FLIGHT.lock() {
+        in_flight.retain(|_, c| c.instance_id != instance_id);
+    }
+
+    info!("[ffi_guard] Instance {} recovered", instance_id);
+    let _ = app.emit("instance-recovered", instance_id);
+    Ok(())
+}
+
+/// 获取调用超时配置
+#[tauri::command]
+pub fn get_ffi_timeouts() -> FfiTimeoutConfig {
+    config()
+}
+
+/// 设置调用超时配置
+#[tauri::command]
+pub fn set_ffi_timeouts(config: FfiTimeoutConfig) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    let path = config_path()?;
+    save_json_config(&path, &config)?;
+    if let Ok(mut cache) = CONFIG.lock() {
+        *cache = Some(config);
+    }
+    Ok(())
+}
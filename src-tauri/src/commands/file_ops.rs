@@ -3,11 +3,23 @@
 //! 提供本地文件读取和路径检查功能
 
 use log::debug;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 
+use super::error::MxuError;
+use super::i18n::ErrorCode;
+use super::types::{ErrorImagePolicy, FileSearchMatch, HashProgressEvent, LogExportProgressEvent};
 use super::utils::{get_app_data_dir, get_exe_directory, normalize_path};
 
-fn resolve_local_file_path(filename: &str) -> Result<PathBuf, String> {
+/// 内容搜索时单个文件的大小上限（超过则只匹配文件名）
+const SEARCH_MAX_FILE_SIZE: u64 = 2 * 1024 * 1024;
+/// 搜索结果默认数量上限
+const SEARCH_DEFAULT_LIMIT: usize = 200;
+/// 命中行预览的最大字符数
+const SEARCH_PREVIEW_MAX_CHARS: usize = 200;
+
+/// 解析相对于 exe 目录的文件路径（不允许超出 exe 目录）
+pub fn resolve_local_file_path(filename: &str) -> Result<PathBuf, String> {
     let exe_dir = get_exe_directory()?;
     let file_path = normalize_path(&exe_dir.join(filename).to_string_lossy());
     // 防止路径穿越，确保仍在 exe 目录下
@@ -17,19 +29,54 @@ fn resolve_local_file_path(filename: &str) -> Result<PathBuf, String> {
     Ok(file_path)
 }
 
+/// 获取允许访问的沙箱根目录（exe 目录与数据目录）
+pub fn sandbox_roots() -> Vec<PathBuf> {
+    let mut roots = Vec::new();
+    if let Ok(exe_dir) = get_exe_directory() {
+        roots.push(exe_dir);
+    }
+    if let Ok(data_dir) = get_app_data_dir() {
+        if !roots.contains(&data_dir) {
+            roots.push(data_dir);
+        }
+    }
+    roots
+}
+
+/// 解析路径并确保其位于沙箱根目录内
+/// 相对路径基于数据目录解析
+pub fn resolve_sandboxed_path(path: &str) -> Result<PathBuf, String> {
+    let raw = Path::new(path);
+    let joined = if raw.is_absolute() {
+        raw.to_path_buf()
+    } else {
+        get_app_data_dir()?.join(raw)
+    };
+    let resolved = normalize_path(&joined.to_string_lossy());
+
+    if sandbox_roots()
+        .iter()
+        .any(|root| resolved.starts_with(root))
+    {
+        Ok(resolved)
+    } else {
+        Err(format!("路径不在允许的目录范围内: {}", path))
+    }
+}
+
 /// 读取 exe 同目录下的文本文件
 #[tauri::command]
-pub fn read_local_file(filename: String) -> Result<String, String> {
+pub fn read_local_file(filename: String) -> Result<String, MxuError> {
     let file_path = resolve_local_file_path(&filename)?;
     debug!("Reading local file: {:?}", file_path);
 
-    std::fs::read_to_string(&file_path)
-        .map_err(|e| format!("读取文件失败 [{}]: {}", file_path.display(), e))
+    Ok(std::fs::read_to_string(&file_path)
+        .map_err(|e| format!("读取文件失败 [{}]: {}", file_path.display(), e))?)
 }
 
 /// 读取 exe 同目录下的二进制文件，返回 base64 编码
 #[tauri::command]
-pub fn read_local_file_base64(filename: String) -> Result<String, String> {
+pub fn read_local_file_base64(filename: String) -> Result<String, MxuError> {
     use base64::{engine::general_purpose::STANDARD, Engine as _};
 
     let file_path = resolve_local_file_path(&filename)?;
@@ -43,14 +90,14 @@ pub fn read_local_file_base64(filename: String) -> Result<String, String> {
 
 /// 检查 exe 同目录下的文件是否存在
 #[tauri::command]
-pub fn local_file_exists(filename: String) -> Result<bool, String> {
+pub fn local_file_exists(filename: String) -> Result<bool, MxuError> {
     let file_path = resolve_local_file_path(&filename)?;
     Ok(file_path.exists())
 }
 
 /// 获取 exe 所在目录路径
 #[tauri::command]
-pub fn get_exe_dir() -> Result<String, String> {
+pub fn get_exe_dir() -> Result<String, MxuError> {
     let exe_dir = get_exe_directory()?;
     Ok(exe_dir.to_string_lossy().to_string())
 }
@@ -58,18 +105,19 @@ pub fn get_exe_dir() -> Result<String, String> {
 /// 获取应用数据目录路径
 /// - macOS: ~/Library/Application Support/MXU/
 /// - Windows/Linux: exe 所在目录
+/// 通过 set_data_dir 修改后为重定向的目录
 #[tauri::command]
-pub fn get_data_dir() -> Result<String, String> {
+pub fn get_data_dir() -> Result<String, MxuError> {
     let data_dir = get_app_data_dir()?;
     Ok(data_dir.to_string_lossy().to_string())
 }
 
 /// 获取当前工作目录
 #[tauri::command]
-pub fn get_cwd() -> Result<String, String> {
-    std::env::current_dir()
+pub fn get_cwd() -> Result<String, MxuError> {
+    Ok(std::env::current_dir()
         .map(|p| p.to_string_lossy().to_string())
-        .map_err(|e| format!("Failed to get current directory: {}", e))
+        .map_err(|e| format!("Failed to get current directory: {}", e))?)
 }
 
 /// 检查 exe 路径是否存在问题
@@ -144,7 +192,8 @@ pub fn check_exe_path() -> Option<String> {
 /// 为文件设置可执行权限（仅 Unix 系统）
 /// Windows 上此命令不做任何操作
 #[tauri::command]
-pub fn set_executable(file_path: String) -> Result<(), String> {
+pub fn set_executable(file_path: String) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
     #[cfg(unix)]
     {
         use std::os::unix::fs::PermissionsExt;
@@ -165,24 +214,347 @@ pub fn set_executable(file_path: String) -> Result<(), String> {
     Ok(())
 }
 
+/// 导出时 on_error 图片默认数量上限（取最新的）
+const EXPORT_MAX_ERROR_IMAGES: usize = 50;
+
+/// 导出包内的图片索引页
+const EXPORT_INDEX_FILE: &str = "index.html";
+
+/// 日志导出代数，取消时递增，使进行中的导出失效
+static EXPORT_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// 导出条目类型
+enum ExportKind {
+    /// 日志文本（按时间范围过滤日志行，可脱敏）
+    Log,
+    /// 其他文本（配置、pipeline 快照等，可脱敏）
+    Text,
+    /// 二进制文件（图片），原样打包
+    Binary,
+}
+
+struct ExportEntry {
+    path: PathBuf,
+    archive_name: String,
+    kind: ExportKind,
+}
+
+/// 解析后的 on_error 图片选择条件
+struct ImageSelection {
+    max_count: usize,
+    max_bytes: Option<u64>,
+    since: Option<chrono::NaiveDateTime>,
+    until: Option<chrono::NaiveDateTime>,
+}
+
+impl ImageSelection {
+    /// 解析策略；only_last_run 时使用最近一次运行的开始时间与运行日志的最后修改时间
+    fn resolve(
+        policy: ErrorImagePolicy,
+        since: Option<chrono::NaiveDateTime>,
+        until: Option<chrono::NaiveDateTime>,
+    ) -> Result<Self, String> {
+        let mut selection = Self {
+            max_count: policy.max_count.unwrap_or(EXPORT_MAX_ERROR_IMAGES),
+            max_bytes: policy.max_total_mb.map(|mb| mb * 1024 * 1024),
+            since: match policy.since.filter(|s| !s.trim().is_empty()) {
+                Some(s) => Some(parse_export_time(&s, false)?),
+                None => since,
+            },
+            until: match policy.until.filter(|s| !s.trim().is_empty()) {
+                Some(s) => Some(parse_export_time(&s, true)?),
+                None => until,
+            },
+        };
+        if policy.only_last_run {
+            let run_id = super::runs::list_runs()?
+                .into_iter()
+                .next()
+                .ok_or("没有运行记录")?;
+            let snapshot = super::runs::get_run_pipeline(run_id.clone())?;
+            let started = chrono::DateTime::parse_from_rfc3339(&snapshot.started_at)
+                .map_err(|e| format!("无效的运行开始时间: {}", e))?
+                .with_timezone(&chrono::Local)
+                .naive_local();
+            let finished =
+                file_modified(&super::runs::get_runs_dir().join(format!("{}.log", run_id)));
+            selection.since = Some(started);
+            selection.until = finished;
+        }
+        Ok(selection)
+    }
+
+    fn contains(&self, path: &Path) -> bool {
+        let modified = file_modified(path);
+        let after_since = match (self.since, modified) {
+            (Some(since), Some(modified)) => modified >= since,
+            _ => true,
+        };
+        let before_until = match (self.until, modified) {
+            (Some(until), Some(modified)) => modified <= until,
+            _ => true,
+        };
+        after_since && before_until
+    }
+}
+
+/// 转义 HTML 文本
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// 生成图片索引页：按时间倒序列出导出的 on_error 图片，便于快速排查
+fn build_image_index(images: &[(String, Option<chrono::NaiveDateTime>, usize)]) -> String {
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>on_error</title>\n\
+         <style>body{font-family:sans-serif;margin:16px}figure{display:inline-block;margin:8px;\
+         vertical-align:top}img{max-width:480px;border:1px solid #ccc}\
+         figcaption{font-size:12px;color:#555}</style>\n</head>\n<body>\n",
+    );
+    html.push_str(&format!("<h1>on_error ({})</h1>\n", images.len()));
+    for (name, modified, size) in images {
+        let name = escape_html(name);
+        let time = modified
+            .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_default();
+        html.push_str(&format!(
+            "<figure><a href=\"{name}\"><img src=\"{name}\" loading=\"lazy\"></a>\
+             <figcaption>{time} · {name} · {} KB</figcaption></figure>\n",
+            size / 1024
+        ));
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+/// 脱敏规则：令牌/密码类字段、Bearer 凭据、webhook 与推送地址
+static REDACT_RULES: std::sync::LazyLock<Vec<(regex::Regex, &'static str)>> =
+    std::sync::LazyLock::new(|| {
+        [
+            (
+                r#"(?i)("?(?:[a-z_]*token|secret|password|passwd|api[_-]?key|access[_-]?key|sendkey)"?\s*[:=]\s*"?)[^"\s,}&]+"#,
+                "${1}***",
+            ),
+            (r"(?i)(bearer\s+)[a-z0-9\-._~+/]+=*", "${1}***"),
+            (
+                r#"(?i)(https?://[^\s"'/]+)/[^\s"']*(?:webhook|hooks|/bot|/send|/robot|key=)[^\s"']*"#,
+                "${1}/***",
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(pattern, replacement)| {
+            regex::Regex::new(pattern).ok().map(|re| (re, replacement))
+        })
+        .collect()
+    });
+
+/// 日志行开头的时间戳（兼容 "[2024-01-01][12:00:00]" 与 "[2024-01-01 12:00:00.000]"）
+static LOG_TIME_RE: std::sync::LazyLock<regex::Regex> = std::sync::LazyLock::new(|| {
+    regex::Regex::new(r"^\[(\d{4}-\d{2}-\d{2})(?:\]\[|[ T])(\d{2}:\d{2}:\d{2})").unwrap()
+});
+
+fn redact_text(text: &str) -> String {
+    REDACT_RULES
+        .iter()
+        .fold(text.to_string(), |acc, (re, replacement)| {
+            re.replace_all(&acc, *replacement).into_owned()
+        })
+}
+
+/// 解析时间范围参数（RFC3339 或 YYYY-MM-DD，日期格式的结束时间取当天末尾）
+fn parse_export_time(value: &str, end_of_day: bool) -> Result<chrono::NaiveDateTime, String> {
+    let value = value.trim();
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Ok(dt.with_timezone(&chrono::Local).naive_local());
+    }
+    let date = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| format!("无效的时间: {}", value))?;
+    let time = if end_of_day {
+        chrono::NaiveTime::from_hms_opt(23, 59, 59)
+    } else {
+        chrono::NaiveTime::from_hms_opt(0, 0, 0)
+    };
+    Ok(date.and_time(time.unwrap_or_default()))
+}
+
+/// 按时间范围过滤日志行；无时间戳的行（多行日志的后续行）跟随上一行
+fn filter_log_lines(
+    text: &str,
+    since: Option<chrono::NaiveDateTime>,
+    until: Option<chrono::NaiveDateTime>,
+) -> String {
+    if since.is_none() && until.is_none() {
+        return text.to_string();
+    }
+    let mut keep = false;
+    let mut out = String::with_capacity(text.len());
+    for line in text.lines() {
+        if let Some(caps) = LOG_TIME_RE.captures(line) {
+            let parsed = chrono::NaiveDateTime::parse_from_str(
+                &format!("{} {}", &caps[1], &caps[2]),
+                "%Y-%m-%d %H:%M:%S",
+            );
+            if let Ok(time) = parsed {
+                keep = since.is_none_or(|s| time >= s) && until.is_none_or(|u| time <= u);
+            }
+        }
+        if keep {
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// 文件修改时间（本地时间）
+fn file_modified(path: &Path) -> Option<chrono::NaiveDateTime> {
+    let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    Some(chrono::DateTime::<chrono::Local>::from(modified).naive_local())
+}
+
+/// 列出目录下指定扩展名的文件
+fn list_files_with_ext(dir: &Path, exts: &[&str]) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_file())
+        .filter(|p| {
+            p.extension()
+                .map(|ext| exts.contains(&ext.to_string_lossy().to_lowercase().as_str()))
+                .unwrap_or(false)
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// 收集待导出的文件：日志（含 MaaFramework 日志与运行日志）、崩溃报告、on_error 图片与配置快照
+fn collect_export_entries(
+    data_dir: &Path,
+    debug_dir: &Path,
+    since: Option<chrono::NaiveDateTime>,
+    until: Option<chrono::NaiveDateTime>,
+    images: &ImageSelection,
+) -> Vec<ExportEntry> {
+    // 文件最后修改早于起始时间时，其中不会有范围内的内容
+    let modified_in_range = |path: &Path| match (since, file_modified(path)) {
+        (Some(since), Some(modified)) => modified >= since,
+        _ => true,
+    };
+    let entry = |path: PathBuf, prefix: &str, kind: ExportKind| {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        ExportEntry {
+            archive_name: format!("{}{}", prefix, name),
+            path,
+            kind,
+        }
+    };
+
+    let mut entries = Vec::new();
+
+    // debug 目录下的 .log（MXU 前后端日志与 MaaFramework 的 maa.log）
+    for path in list_files_with_ext(debug_dir, &["log"]) {
+        if modified_in_range(&path) {
+            entries.push(entry(path, "", ExportKind::Log));
+        }
+    }
+
+    // 运行日志与 pipeline 快照
+    let runs_dir = debug_dir.join("runs");
+    for path in list_files_with_ext(&runs_dir, &["log"]) {
+        if modified_in_range(&path) {
+            entries.push(entry(path, "runs/", ExportKind::Log));
+        }
+    }
+
+    // 崩溃报告
+    for path in list_files_with_ext(&debug_dir.join("crashes"), &["txt"]) {
+        let in_range = match (until, file_modified(&path)) {
+            (Some(until), Some(modified)) => modified <= until,
+            _ => true,
+        };
+        if modified_in_range(&path) && in_range {
+            entries.push(entry(path, "crashes/", ExportKind::Text));
+        }
+    }
+
+    // on_error 图片（范围内最新的若干张，不超过总大小上限）
+    let mut error_images: Vec<PathBuf> =
+        list_files_with_ext(&debug_dir.join("on_error"), &["png", "jpg", "jpeg"])
+            .into_iter()
+            .filter(|p| images.contains(p))
+            .collect();
+    error_images.sort_by_key(|p| std::cmp::Reverse(file_modified(p)));
+    let mut total_bytes = 0u64;
+    for path in error_images.into_iter().take(images.max_count) {
+        let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        if images.max_bytes.is_some_and(|max| total_bytes + size > max) {
+            break;
+        }
+        total_bytes += size;
+        entries.push(entry(path, "on_error/", ExportKind::Binary));
+    }
+
+    // 配置快照
+    for path in list_files_with_ext(&data_dir.join("config"), &["json"]) {
+        entries.push(entry(path, "config/", ExportKind::Text));
+    }
+
+    entries
+}
+
 /// 导出日志文件为 zip 压缩包
-/// 返回生成的 zip 文件路径
+///
+/// since/until 为可选时间范围（RFC3339 或 YYYY-MM-DD），范围外的日志行与文件会被跳过；
+/// redact 默认开启，打包前屏蔽令牌、密码与 webhook 地址等敏感信息。
+/// image_policy 控制 on_error 图片的数量、总大小、时间范围与是否仅取最近一次运行，
+/// 包含图片时在压缩包根目录生成 index.html 索引页。
+/// 导出过程中发送 log-export-progress 事件，可通过 cancel_export_logs 取消，返回生成的 zip 文件路径
 #[tauri::command]
-pub fn export_logs(
+pub async fn export_logs(
+    app: tauri::AppHandle,
     project_name: Option<String>,
     project_version: Option<String>,
-) -> Result<String, String> {
+    since: Option<String>,
+    until: Option<String>,
+    redact: Option<bool>,
+    image_policy: Option<ErrorImagePolicy>,
+) -> Result<String, MxuError> {
     use std::fs::File;
-    use std::io::{Read, Write};
+    use std::io::Write;
+    use tauri::Emitter;
     use zip::write::SimpleFileOptions;
     use zip::ZipWriter;
 
+    let since = since
+        .filter(|s| !s.trim().is_empty())
+        .map(|s| parse_export_time(&s, false))
+        .transpose()?;
+    let until = until
+        .filter(|s| !s.trim().is_empty())
+        .map(|s| parse_export_time(&s, true))
+        .transpose()?;
+    let redact = redact.unwrap_or(true);
+    let images = ImageSelection::resolve(image_policy.unwrap_or_default(), since, until)?;
+
     // 日志在数据目录下（macOS: ~/Library/Application Support/MXU/debug）
     let data_dir = get_app_data_dir()?;
     let debug_dir = data_dir.join("debug");
 
     if !debug_dir.exists() {
-        return Err("日志目录不存在".to_string());
+        return Err(MxuError::with_args(
+            ErrorCode::DirectoryNotFound,
+            [debug_dir.display()],
+        ));
     }
 
     // 生成带时间戳的文件名：项目名-版本号-日期.zip
@@ -197,106 +569,345 @@ pub fn export_logs(
     };
     let zip_path = debug_dir.join(&filename);
 
-    let file = File::create(&zip_path).map_err(|e| format!("创建压缩文件失败: {}", e))?;
-    let mut zip = ZipWriter::new(file);
-    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
-
-    // 辅助函数：将文件添加到 zip
-    fn add_file_to_zip(
-        zip: &mut ZipWriter<File>,
-        path: &std::path::Path,
-        archive_name: &str,
-        options: SimpleFileOptions,
-    ) -> bool {
-        let mut file = match File::open(path) {
-            Ok(f) => f,
-            Err(e) => {
-                log::warn!("无法打开文件 {:?}: {}", path, e);
-                return false;
+    let generation = EXPORT_GENERATION.load(Ordering::SeqCst);
+    let cancelled = move || EXPORT_GENERATION.load(Ordering::SeqCst) != generation;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let entries = collect_export_entries(&data_dir, &debug_dir, since, until, &images);
+        let total = entries.len();
+
+        let file = File::create(&zip_path).map_err(|e| format!("创建压缩文件失败: {}", e))?;
+        let mut zip = ZipWriter::new(file);
+        let options =
+            SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+        let mut indexed_images = Vec::new();
+        for (index, entry) in entries.iter().enumerate() {
+            if cancelled() {
+                drop(zip);
+                let _ = std::fs::remove_file(&zip_path);
+                log::info!("日志导出已取消");
+                return Err(MxuError::new(ErrorCode::Cancelled));
+            }
+            let _ = app.emit(
+                "log-export-progress",
+                LogExportProgressEvent {
+                    current: entry.archive_name.clone(),
+                    processed: index,
+                    total,
+                    progress: index as f64 / total.max(1) as f64 * 100.0,
+                },
+            );
+
+            let content = match std::fs::read(&entry.path) {
+                Ok(content) => content,
+                Err(e) => {
+                    log::warn!("读取文件失败 {:?}: {}", entry.path, e);
+                    continue;
+                }
+            };
+            let content = match entry.kind {
+                ExportKind::Binary => {
+                    match super::screenshot_redaction::redact_export_image(content) {
+                        Some(content) => {
+                            indexed_images.push((
+                                entry.archive_name.clone(),
+                                file_modified(&entry.path),
+                                content.len(),
+                            ));
+                            content
+                        }
+                        None => continue,
+                    }
+                }
+                ExportKind::Log | ExportKind::Text => {
+                    let mut text = String::from_utf8_lossy(&content).into_owned();
+                    if matches!(entry.kind, ExportKind::Log) {
+                        text = filter_log_lines(&text, since, until);
+                    }
+                    if redact {
+                        text = redact_text(&text);
+                    }
+                    text.into_bytes()
+                }
+            };
+
+            if let Err(e) = zip.start_file(entry.archive_name.as_str(), options) {
+                log::warn!("创建 zip 条目失败 {}: {}", entry.archive_name, e);
+                continue;
+            }
+            if let Err(e) = zip.write_all(&content) {
+                log::warn!("写入 zip 失败 {}: {}", entry.archive_name, e);
             }
-        };
-
-        let mut content = Vec::new();
-        if let Err(e) = file.read_to_end(&mut content) {
-            log::warn!("读取文件失败 {:?}: {}", path, e);
-            return false;
         }
 
-        if let Err(e) = zip.start_file(archive_name, options) {
-            log::warn!("创建 zip 条目失败 {}: {}", archive_name, e);
-            return false;
+        if !indexed_images.is_empty() {
+            let index = build_image_index(&indexed_images);
+            if zip.start_file(EXPORT_INDEX_FILE, options).is_ok() {
+                if let Err(e) = zip.write_all(index.as_bytes()) {
+                    log::warn!("写入图片索引失败: {}", e);
+                }
+            }
         }
 
-        if let Err(e) = zip.write_all(&content) {
-            log::warn!("写入 zip 失败 {}: {}", archive_name, e);
-            return false;
-        }
+        zip.finish().map_err(|e| format!("完成压缩失败: {}", e))?;
+        let _ = app.emit(
+            "log-export-progress",
+            LogExportProgressEvent {
+                current: String::new(),
+                processed: total,
+                total,
+                progress: 100.0,
+            },
+        );
+
+        Ok(zip_path.to_string_lossy().to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
 
-        true
+/// 取消进行中的日志导出（已生成的部分文件会被删除）
+#[tauri::command]
+pub fn cancel_export_logs() {
+    EXPORT_GENERATION.fetch_add(1, Ordering::SeqCst);
+}
+
+/// 在沙箱目录中搜索文件
+/// - 文件名包含 query 即命中（不区分大小写）
+/// - 文本文件（不超过 2MB 且不含 NUL 字节）会逐行搜索内容
+///
+/// exts: 仅搜索这些扩展名的文件（不含点，如 ["json", "png"]），为空表示不限制
+/// limit: 最多返回的命中数，默认 200
+#[tauri::command]
+pub async fn search_files(
+    root: String,
+    query: String,
+    exts: Option<Vec<String>>,
+    limit: Option<usize>,
+) -> Result<Vec<FileSearchMatch>, MxuError> {
+    debug!(
+        "search_files called, root: {}, query: {}, exts: {:?}, limit: {:?}",
+        root, query, exts, limit
+    );
+
+    if query.trim().is_empty() {
+        return Ok(Vec::new());
     }
 
-    // 辅助函数：检查是否为图片文件
-    fn is_image_file(path: &std::path::Path) -> bool {
-        if !path.is_file() {
-            return false;
-        }
-        path.extension()
-            .map(|ext| {
-                let ext_lower = ext.to_string_lossy().to_lowercase();
-                ext_lower == "png" || ext_lower == "jpg" || ext_lower == "jpeg"
-            })
-            .unwrap_or(false)
+    let root_path = resolve_sandboxed_path(&root)?;
+    if !root_path.is_dir() {
+        return Err(MxuError::with_args(
+            ErrorCode::DirectoryNotFound,
+            [root_path.display()],
+        ));
     }
 
-    // 遍历 debug 目录下的所有 .log 文件
-    let entries = std::fs::read_dir(&debug_dir).map_err(|e| format!("读取日志目录失败: {}", e))?;
+    let exts: Vec<String> = exts
+        .unwrap_or_default()
+        .into_iter()
+        .map(|e| e.trim_start_matches('.').to_lowercase())
+        .filter(|e| !e.is_empty())
+        .collect();
+    let limit = limit.unwrap_or(SEARCH_DEFAULT_LIMIT).max(1);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let query_lower = query.to_lowercase();
+        let mut results = Vec::new();
+        search_dir(
+            &root_path,
+            &root_path,
+            &query_lower,
+            &exts,
+            limit,
+            &mut results,
+        );
+        debug!("search_files found {} match(es)", results.len());
+        Ok(results)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 递归搜索目录（不跟随符号链接，避免循环）
+fn search_dir(
+    root: &Path,
+    dir: &Path,
+    query_lower: &str,
+    exts: &[String],
+    limit: usize,
+    results: &mut Vec<FileSearchMatch>,
+) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
 
     for entry in entries.flatten() {
+        if results.len() >= limit {
+            return;
+        }
+
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
         let path = entry.path();
 
-        // 使用 early-continue 简化逻辑
-        if !path.is_file() {
+        if file_type.is_dir() {
+            search_dir(root, &path, query_lower, exts, limit, results);
             continue;
         }
-        if path.extension().map(|e| e != "log").unwrap_or(true) {
+        if !file_type.is_file() {
             continue;
         }
-        let Some(name) = path.file_name() else {
+
+        let ext = path
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        if !exts.is_empty() && !exts.contains(&ext) {
+            continue;
+        }
+
+        let relative_path = path
+            .strip_prefix(root)
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .replace('\\', "/");
+        let path_str = path.to_string_lossy().to_string();
+
+        let name_lower = entry.file_name().to_string_lossy().to_lowercase();
+        if name_lower.contains(query_lower) {
+            results.push(FileSearchMatch {
+                path: path_str.clone(),
+                relative_path: relative_path.clone(),
+                line: None,
+                preview: None,
+            });
+        }
+
+        let size = entry.metadata().map(|m| m.len()).unwrap_or(u64::MAX);
+        if size > SEARCH_MAX_FILE_SIZE {
+            continue;
+        }
+        let Ok(content) = std::fs::read(&path) else {
             continue;
         };
+        // 含 NUL 字节视为二进制文件，跳过内容搜索
+        if content.iter().take(8192).any(|&b| b == 0) {
+            continue;
+        }
 
-        let name_str = name.to_string_lossy();
-        add_file_to_zip(&mut zip, &path, &name_str, options);
+        let text = String::from_utf8_lossy(&content);
+        for (idx, line) in text.lines().enumerate() {
+            if results.len() >= limit {
+                return;
+            }
+            if line.to_lowercase().contains(query_lower) {
+                let preview: String = line.trim().chars().take(SEARCH_PREVIEW_MAX_CHARS).collect();
+                results.push(FileSearchMatch {
+                    path: path_str.clone(),
+                    relative_path: relative_path.clone(),
+                    line: Some(idx + 1),
+                    preview: Some(preview),
+                });
+            }
+        }
     }
+}
 
-    // 处理 on_error 文件夹（只包含前50张图片）
-    let on_error_dir = debug_dir.join("on_error");
-    if on_error_dir.exists() && on_error_dir.is_dir() {
-        if let Ok(rd) = std::fs::read_dir(&on_error_dir) {
-            let mut images: Vec<_> = rd.flatten().filter(|e| is_image_file(&e.path())).collect();
-
-            // 按修改时间排序（最新的在前）
-            images.sort_by(|a, b| {
-                let time_a = a.metadata().and_then(|m| m.modified()).ok();
-                let time_b = b.metadata().and_then(|m| m.modified()).ok();
-                time_b.cmp(&time_a)
-            });
+/// 计算本地文件哈希（流式读取，大文件会通过 hash-progress 事件报告进度）
+/// algo: sha256 / md5 / crc32，返回小写十六进制字符串
+#[tauri::command]
+pub async fn hash_file(
+    app: tauri::AppHandle,
+    path: String,
+    algo: String,
+) -> Result<String, MxuError> {
+    debug!("hash_file called, path: {}, algo: {}", path, algo);
+
+    Ok(
+        tauri::async_runtime::spawn_blocking(move || compute_file_hash(&app, &path, &algo))
+            .await
+            .map_err(|e| e.to_string())??,
+    )
+}
 
-            // 只取前50张
-            for entry in images.into_iter().take(50) {
-                let path = entry.path();
-                let Some(name) = path.file_name() else {
-                    continue;
-                };
-                let archive_name = format!("on_error/{}", name.to_string_lossy());
-                add_file_to_zip(&mut zip, &path, &archive_name, options);
-            }
-        } else {
-            log::warn!("无法读取 on_error 目录");
+/// 流式计算文件哈希（供其他模块复用）
+pub fn compute_file_hash(app: &tauri::AppHandle, path: &str, algo: &str) -> Result<String, String> {
+    use md5::Md5;
+    use sha2::{Digest, Sha256};
+    use std::io::Read;
+    use tauri::Emitter;
+
+    enum Hasher {
+        Sha256(Sha256),
+        Md5(Md5),
+        Crc32(crc32fast::Hasher),
+    }
+
+    let mut hasher = match algo.to_lowercase().as_str() {
+        "sha256" => Hasher::Sha256(Sha256::new()),
+        "md5" => Hasher::Md5(Md5::new()),
+        "crc32" => Hasher::Crc32(crc32fast::Hasher::new()),
+        other => return Err(format!("不支持的哈希算法: {}", other)),
+    };
+
+    let mut file =
+        std::fs::File::open(path).map_err(|e| format!("打开文件失败 [{}]: {}", path, e))?;
+    let total = file.metadata().map(|m| m.len()).unwrap_or(0);
+
+    let mut buffer = vec![0u8; 256 * 1024];
+    let mut processed: u64 = 0;
+    let mut last_progress_time = std::time::Instant::now();
+
+    loop {
+        let n = file
+            .read(&mut buffer)
+            .map_err(|e| format!("读取文件失败 [{}]: {}", path, e))?;
+        if n == 0 {
+            break;
+        }
+
+        match &mut hasher {
+            Hasher::Sha256(h) => h.update(&buffer[..n]),
+            Hasher::Md5(h) => h.update(&buffer[..n]),
+            Hasher::Crc32(h) => h.update(&buffer[..n]),
+        }
+        processed += n as u64;
+
+        // 每 100ms 发送一次进度更新
+        if last_progress_time.elapsed().as_millis() >= 100 {
+            let _ = app.emit(
+                "hash-progress",
+                HashProgressEvent {
+                    path: path.to_string(),
+                    processed_size: processed,
+                    total_size: total,
+                    progress: if total > 0 {
+                        (processed as f64 / total as f64) * 100.0
+                    } else {
+                        0.0
+                    },
+                },
+            );
+            last_progress_time = std::time::Instant::now();
         }
     }
 
-    zip.finish().map_err(|e| format!("完成压缩失败: {}", e))?;
+    let digest = match hasher {
+        Hasher::Sha256(h) => to_hex(&h.finalize()),
+        Hasher::Md5(h) => to_hex(&h.finalize()),
+        Hasher::Crc32(h) => format!("{:08x}", h.finalize()),
+    };
+
+    debug!(
+        "hash_file done: {} ({} bytes) -> {}",
+        path, processed, digest
+    );
+    Ok(digest)
+}
 
-    Ok(zip_path.to_string_lossy().to_string())
+/// 字节数组转小写十六进制字符串
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
 }
@@ -0,0 +1,259 @@
+//! 游戏客户端更新检测
+//!
+//! 开始任务前的预检：按资源包配置识别「需要更新」界面节点或 OCR 版本号，
+//! 若游戏客户端需要更新则跳过本次运行并发送通知，避免任务卡在无法跳过的更新界面。
+//!
+//! 配置来源（后者优先）：资源包目录下的 mxu_update_check.json（资源作者提供），
+//! 以及 config/game_update_check.json 中以资源包目录名为键的用户配置。
+//! 需要重试时，到时发送 game-update-retry 事件，由前端重新开始任务
+
+use log::{info, warn};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use maa_framework::tasker::Tasker;
+use maa_framework::MaaStatus;
+use serde_json::json;
+use tauri::{AppHandle, Emitter};
+
+use super::error::MxuError;
+use super::types::{GameUpdateCheck, GameUpdateRequiredEvent};
+use super::utils::{get_app_data_dir, save_json_config};
+
+/// 资源包内的默认配置文件名
+const RESOURCE_CONFIG_FILE: &str = "mxu_update_check.json";
+
+/// 预检使用的临时节点
+const ENTRY_NODE: &str = "MXU_GameUpdateCheck";
+const VERSION_NODE: &str = "MXU_GameUpdateCheck_Version";
+const NONE_NODE: &str = "MXU_GameUpdateCheck_None";
+
+/// 预检超时
+const CHECK_TIMEOUT: Duration = Duration::from_secs(30);
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 各实例已加载的资源包路径
+static RESOURCE_PATHS: LazyLock<Mutex<HashMap<String, Vec<String>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn config_path() -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?
+        .join("config")
+        .join("game_update_check.json"))
+}
+
+fn load_user_config() -> Result<HashMap<String, GameUpdateCheck>, String> {
+    let path = config_path()?;
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+    let content =
+        std::fs::read_to_string(&path).map_err(|e| format!("无法读取更新检测配置: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("无法解析更新检测配置: {}", e))
+}
+
+fn bundle_key(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string())
+}
+
+/// 记录实例加载的资源包（load_resource_bundles 时调用）
+pub fn note_resource_paths(instance_id: &str, paths: &[String]) {
+    if let Ok(mut map) = RESOURCE_PATHS.lock() {
+        let loaded = map.entry(instance_id.to_string()).or_default();
+        for path in paths {
+            if !loaded.contains(path) {
+                loaded.push(path.clone());
+            }
+        }
+    }
+}
+
+/// 实例已加载的资源包目录名（按加载顺序）
+pub fn loaded_bundles(instance_id: &str) -> Vec<String> {
+    RESOURCE_PATHS
+        .lock()
+        .ok()
+        .and_then(|map| map.get(instance_id).cloned())
+        .map(|paths| paths.iter().map(|p| bundle_key(p)).collect())
+        .unwrap_or_default()
+}
+
+/// 查找实例生效的检测配置（后加载的资源包优先）
+fn check_for(instance_id: &str) -> Option<GameUpdateCheck> {
+    let paths = RESOURCE_PATHS
+        .lock()
+        .ok()
+        .and_then(|map| map.get(instance_id).cloned())?;
+    let user_config = load_user_config().unwrap_or_default();
+
+    paths.iter().rev().find_map(|path| {
+        if let Some(check) = user_config.get(&bundle_key(path)) {
+            return Some(check.clone());
+        }
+        let content = std::fs::read_to_string(Path::new(path).join(RESOURCE_CONFIG_FILE)).ok()?;
+        serde_json::from_str(&content).ok()
+    })
+}
+
+/// 构造预检 pipeline：依次尝试更新界面节点、版本号 OCR，均未命中时落到兜底节点
+fn build_pipeline(check: &GameUpdateCheck) -> serde_json::Value {
+    let mut pipeline = serde_json::Map::new();
+    let mut next = Vec::new();
+
+    if let Some(node) = &check.update_node {
+        // 只识别不执行动作
+        pipeline.insert(node.clone(), json!({ "action": "DoNothing", "next": [] }));
+        next.push(node.clone());
+    }
+    if let Some(ocr) = &check.version_ocr {
+        pipeline.insert(
+            VERSION_NODE.to_string(),
+            json!({
+                "recognition": "OCR",
+                "roi": ocr.roi,
+                "expected": [ocr.expected],
+                "action": "DoNothing",
+                "next": [],
+            }),
+        );
+        next.push(VERSION_NODE.to_string());
+    }
+    pipeline.insert(
+        NONE_NODE.to_string(),
+        json!({ "recognition": "DirectHit", "action": "DoNothing", "next": [] }),
+    );
+    next.push(NONE_NODE.to_string());
+    pipeline.insert(
+        ENTRY_NODE.to_string(),
+        json!({ "recognition": "DirectHit", "action": "DoNothing", "next": next }),
+    );
+    serde_json::Value::Object(pipeline)
+}
+
+/// 执行预检，返回需要更新的原因（无需更新时返回 None）
+fn run_check(tasker: &Tasker, check: &GameUpdateCheck) -> Result<Option<String>, String> {
+    let since = chrono::Utc::now().timestamp_millis();
+    let pipeline = build_pipeline(check).to_string();
+    let job = tasker
+        .post_task(ENTRY_NODE, &pipeline)
+        .map_err(|e| format!("提交更新检测失败: {}", e))?;
+
+    let start = Instant::now();
+    loop {
+        let status = tasker
+            .get_task_detail(job.id)
+            .ok()
+            .flatten()
+            .map(|d| d.status)
+            .unwrap_or(MaaStatus::INVALID);
+        match status {
+            MaaStatus::PENDING | MaaStatus::RUNNING if start.elapsed() < CHECK_TIMEOUT => {
+                std::thread::sleep(POLL_INTERVAL);
+            }
+            MaaStatus::PENDING | MaaStatus::RUNNING => return Err("更新检测超时".to_string()),
+            _ => break,
+        }
+    }
+
+    let records = super::recognition::records_since(since);
+    let hit = |node: &str| records.iter().any(|r| r.node_name == node && r.hit);
+
+    if let Some(node) = &check.update_node {
+        if hit(node) {
+            return Ok(Some(format!("识别到更新界面（{}）", node)));
+        }
+    }
+    if check.version_ocr.is_some() && !hit(VERSION_NODE) {
+        return Ok(Some("游戏版本号与资源支持的版本不符".to_string()));
+    }
+    Ok(None)
+}
+
+/// 开始任务前的更新预检；需要更新时发送通知并返回错误，检测本身出错时只记录警告
+pub fn preflight(app: &AppHandle, instance_id: &str, tasker: &Tasker) -> Result<(), String> {
+    let Some(check) = check_for(instance_id).filter(|c| c.enabled) else {
+        return Ok(());
+    };
+    if check.update_node.is_none() && check.version_ocr.is_none() {
+        return Ok(());
+    }
+
+    let reason = match run_check(tasker, &check) {
+        Ok(Some(reason)) => reason,
+        Ok(None) => {
+            info!("[game_update_check] No client update required");
+            return Ok(());
+        }
+        Err(e) => {
+            warn!("[game_update_check] Check failed, continuing: {}", e);
+            return Ok(());
+        }
+    };
+
+    warn!("[game_update_check] Client update required: {}", reason);
+    let retry_at = check
+        .retry_after_minutes
+        .map(|minutes| chrono::Utc::now().timestamp_millis() + (minutes as i64) * 60_000);
+    let _ = app.emit(
+        "game-update-required",
+        GameUpdateRequiredEvent {
+            instance_id: instance_id.to_string(),
+            reason: reason.clone(),
+            retry_at,
+        },
+    );
+    if let Err(e) = notify_rust::Notification::new()
+        .summary("MXU")
+        .body(&format!("游戏客户端需要更新，已跳过本次运行：{}", reason))
+        .show()
+    {
+        warn!("[game_update_check] Failed to send notification: {}", e);
+    }
+
+    if let Some(minutes) = check.retry_after_minutes {
+        let app = app.clone();
+        let instance_id = instance_id.to_string();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_secs(minutes * 60));
+            let _ = app.emit("game-update-retry", instance_id);
+        });
+    }
+
+    Err(format!("游戏客户端需要更新，已跳过本次运行：{}", reason))
+}
+
+/// 获取用户配置的更新检测（键为资源包目录名）
+#[tauri::command]
+pub fn game_update_check_get_config() -> Result<HashMap<String, GameUpdateCheck>, MxuError> {
+    Ok(load_user_config()?)
+}
+
+/// 设置指定资源包的更新检测，check 为空时删除用户配置（恢复资源包默认配置）
+#[tauri::command]
+pub fn game_update_check_set_config(
+    bundle: String,
+    check: Option<GameUpdateCheck>,
+) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    if let Some(ocr) = check.as_ref().and_then(|c| c.version_ocr.as_ref()) {
+        regex::Regex::new(&ocr.expected).map_err(|e| format!("无效的版本号正则: {}", e))?;
+    }
+
+    let mut config = load_user_config()?;
+    match check {
+        Some(check) => {
+            config.insert(bundle, check);
+        }
+        None => {
+            config.remove(&bundle);
+        }
+    }
+
+    let path = config_path()?;
+    save_json_config(&path, &config)
+}
@@ -0,0 +1,109 @@
+//! 只读访客模式
+//!
+//! 开启后界面与远程 API 仍可查看状态、日志和截图，但不能启停任务或修改设置。
+//! 可选设置 PIN，关闭访客模式时需要验证；状态持久化到 config/guest_mode.json
+
+use log::info;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tauri::{AppHandle, Emitter};
+
+use super::error::MxuError;
+use super::file_ops::to_hex;
+use super::i18n::ErrorCode;
+use super::utils::{get_app_data_dir, save_json_config};
+
+/// 访客模式是否开启
+static GUEST_MODE: AtomicBool = AtomicBool::new(false);
+
+/// 持久化的访客模式状态
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct GuestModeState {
+    enabled: bool,
+    #[serde(default)]
+    pin_hash: Option<String>,
+}
+
+fn state_path() -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?.join("config").join("guest_mode.json"))
+}
+
+fn load_state() -> GuestModeState {
+    state_path()
+        .ok()
+        .and_then(|p| std::fs::read_to_string(p).ok())
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(state: &GuestModeState) -> Result<(), String> {
+    let path = state_path()?;
+    Ok(save_json_config(&path, state)?)
+}
+
+fn hash_pin(pin: &str) -> String {
+    to_hex(&Sha256::digest(pin.as_bytes()))
+}
+
+/// 启动时恢复持久化的访客模式状态
+pub fn init_guest_mode() {
+    let state = load_state();
+    GUEST_MODE.store(state.enabled, Ordering::SeqCst);
+    if state.enabled {
+        info!("[guest_mode] Restored guest mode from config");
+    }
+}
+
+/// 是否处于访客模式
+pub fn is_guest_mode() -> bool {
+    GUEST_MODE.load(Ordering::SeqCst)
+}
+
+/// 访客模式下拒绝修改操作
+pub fn ensure_not_guest() -> Result<(), MxuError> {
+    if is_guest_mode() {
+        Err(MxuError::new(ErrorCode::GuestModeReadOnly))
+    } else {
+        Ok(())
+    }
+}
+
+/// 查询访客模式状态
+#[tauri::command]
+pub fn guest_mode_get() -> bool {
+    is_guest_mode()
+}
+
+/// 开启访客模式，可选设置退出时需要的 PIN
+/// 已处于访客模式时拒绝，避免覆盖已设置的 PIN
+#[tauri::command]
+pub fn guest_mode_enable(app: AppHandle, pin: Option<String>) -> Result<(), MxuError> {
+    ensure_not_guest()?;
+    save_state(&GuestModeState {
+        enabled: true,
+        pin_hash: pin.filter(|p| !p.is_empty()).map(|p| hash_pin(&p)),
+    })?;
+    GUEST_MODE.store(true, Ordering::SeqCst);
+    info!("[guest_mode] Guest mode enabled");
+    let _ = app.emit("guest-mode-changed", true);
+    Ok(())
+}
+
+/// 关闭访客模式，设置过 PIN 时需要验证
+#[tauri::command]
+pub fn guest_mode_disable(app: AppHandle, pin: Option<String>) -> Result<(), MxuError> {
+    let state = load_state();
+    if let Some(expected) = &state.pin_hash {
+        if pin.as_deref().map(hash_pin).as_ref() != Some(expected) {
+            return Err(MxuError::new(ErrorCode::GuestModePinIncorrect));
+        }
+    }
+    save_state(&GuestModeState::default())?;
+    GUEST_MODE.store(false, Ordering::SeqCst);
+    info!("[guest_mode] Guest mode disabled");
+    let _ = app.emit("guest-mode-changed", false);
+    Ok(())
+}
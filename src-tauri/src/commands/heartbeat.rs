@@ -0,0 +1,130 @@
+//! 前后端心跳
+//!
+//! 后端定时发送 mxu://heartbeat 事件（带序号与状态版本号），前端收到后调用 heartbeat_ack 确认，
+//! 后端据此记录 IPC 往返延迟，供 WebView 看门狗判断页面是否无响应以及诊断信息使用
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Emitter};
+
+use super::error::MxuError;
+use super::types::{HeartbeatEvent, HeartbeatStats};
+
+/// 心跳间隔
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+/// 保留的延迟样本数
+const MAX_SAMPLES: usize = 120;
+/// 等待确认的心跳上限（超出的视为丢失）
+const MAX_PENDING: usize = 16;
+
+/// 后端状态版本号
+static REVISION: AtomicU64 = AtomicU64::new(0);
+
+static CHANNEL: LazyLock<Mutex<Channel>> = LazyLock::new(|| Mutex::new(Channel::default()));
+
+#[derive(Default)]
+struct Channel {
+    next_seq: u64,
+    acked: u64,
+    /// 已发送未确认的心跳
+    pending: HashMap<u64, Instant>,
+    /// (Unix 毫秒, 往返延迟毫秒)
+    samples: VecDeque<(i64, f64)>,
+    last_ack: Option<Instant>,
+}
+
+/// 递增状态版本号（后端状态变化时调用）
+pub fn bump_revision() {
+    REVISION.fetch_add(1, Ordering::Relaxed);
+}
+
+/// 最近一次收到前端确认的时间
+pub fn last_ack() -> Option<Instant> {
+    CHANNEL.lock().ok().and_then(|c| c.last_ack)
+}
+
+/// 启动心跳发送线程
+pub fn spawn_heartbeat(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(HEARTBEAT_INTERVAL);
+
+        let seq = match CHANNEL.lock() {
+            Ok(mut channel) => {
+                channel.next_seq += 1;
+                let seq = channel.next_seq;
+                if channel.pending.len() >= MAX_PENDING {
+                    let oldest = channel.pending.keys().min().copied();
+                    if let Some(oldest) = oldest {
+                        channel.pending.remove(&oldest);
+                    }
+                }
+                channel.pending.insert(seq, Instant::now());
+                seq
+            }
+            Err(_) => continue,
+        };
+
+        let _ = app.emit(
+            "mxu://heartbeat",
+            HeartbeatEvent {
+                seq,
+                revision: REVISION.load(Ordering::Relaxed),
+                sent_at: chrono::Utc::now().timestamp_millis(),
+            },
+        );
+    });
+}
+
+/// 前端确认心跳
+#[tauri::command]
+pub fn heartbeat_ack(seq: u64) -> Result<(), MxuError> {
+    let mut channel = CHANNEL.lock().map_err(|e| e.to_string())?;
+    let Some(sent) = channel.pending.remove(&seq) else {
+        return Ok(());
+    };
+    let now = Instant::now();
+    channel.acked += 1;
+    channel.last_ack = Some(now);
+    channel.samples.push_back((
+        chrono::Utc::now().timestamp_millis(),
+        now.duration_since(sent).as_secs_f64() * 1000.0,
+    ));
+    if channel.samples.len() > MAX_SAMPLES {
+        channel.samples.pop_front();
+    }
+    Ok(())
+}
+
+/// 获取心跳延迟统计
+#[tauri::command]
+pub fn get_heartbeat_stats() -> Result<HeartbeatStats, MxuError> {
+    let channel = CHANNEL.lock().map_err(|e| e.to_string())?;
+    let mut rtts: Vec<f64> = channel.samples.iter().map(|(_, rtt)| *rtt).collect();
+    let last_rtt_ms = rtts.last().copied();
+    rtts.sort_by(|a, b| a.total_cmp(b));
+    let (avg_rtt_ms, p95_rtt_ms, max_rtt_ms) = if rtts.is_empty() {
+        (None, None, None)
+    } else {
+        let p95_index = ((rtts.len() as f64 * 0.95).ceil() as usize).clamp(1, rtts.len()) - 1;
+        (
+            Some(rtts.iter().sum::<f64>() / rtts.len() as f64),
+            Some(rtts[p95_index]),
+            rtts.last().copied(),
+        )
+    };
+
+    Ok(HeartbeatStats {
+        revision: REVISION.load(Ordering::Relaxed),
+        sent: channel.next_seq,
+        acked: channel.acked,
+        last_rtt_ms,
+        avg_rtt_ms,
+        p95_rtt_ms,
+        max_rtt_ms,
+        last_ack_age_ms: channel.last_ack.map(|t| t.elapsed().as_millis() as u64),
+        samples: channel.samples.iter().copied().collect(),
+    })
+}
@@ -0,0 +1,324 @@
+//! 配置档案任务钩子
+//!
+//! 在队列开始前、每个任务前后、队列结束后与任务失败时，按配置档案中的声明运行程序、
+//! 请求 Webhook 或发送通知，替代在 pipeline 中手写 MXU_LAUNCH 节点。
+//! before_* 钩子在队列线程中同步执行（运行程序可等待退出），其余钩子在后台执行，不阻塞队列。
+//! 每个任务与失败钩子需要队列模式；直接提交任务时只执行 before_queue 钩子。
+//! 运行程序与 Webhook 钩子需经本机用户确认后才会执行：在本机创建或编辑的钩子自动确认，
+//! 导入、拖放、链接安装或同步得到的钩子需通过 hook_confirm 确认（确认记录不参与同步）
+
+use log::{info, warn};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::{LazyLock, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::error::MxuError;
+use super::file_ops::to_hex;
+use super::types::{HookAction, HookEvent, TaskHook, ToastAction};
+use super::utils::{get_app_data_dir, save_json_config};
+
+/// 运行程序未指定超时时的等待时间
+const DEFAULT_PROGRAM_TIMEOUT: Duration = Duration::from_secs(60);
+/// Webhook 请求超时
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 已确认钩子的指纹，首次使用时从文件加载
+static TRUSTED: LazyLock<Mutex<Option<HashSet<String>>>> = LazyLock::new(|| Mutex::new(None));
+
+/// 钩子确认记录文件名（位于 config 目录，不参与同步）
+pub const TRUST_FILE: &str = "hook_trust.json";
+
+/// 钩子触发时的上下文
+#[derive(Debug, Clone, Default)]
+pub struct HookContext {
+    pub instance_id: String,
+    pub run_id: Option<String>,
+    pub task: Option<String>,
+    pub status: Option<String>,
+}
+
+fn event_name(event: HookEvent) -> &'static str {
+    match event {
+        HookEvent::BeforeQueue => "before_queue",
+        HookEvent::BeforeTask => "before_task",
+        HookEvent::AfterTask => "after_task",
+        HookEvent::AfterQueue => "after_queue",
+        HookEvent::OnFailure => "on_failure",
+    }
+}
+
+/// 读取配置档案的钩子，未指定档案时为空
+pub fn profile_hooks(profile_id: Option<&str>) -> Result<Vec<TaskHook>, String> {
+    match profile_id {
+        Some(id) => Ok(super::profiles::profile_get(id.to_string())?.content.hooks),
+        None => Ok(Vec::new()),
+    }
+}
+
+fn trust_path() -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?.join("config").join(TRUST_FILE))
+}
+
+/// 钩子指纹：动作内容（程序与参数、地址与请求体等）的哈希
+fn fingerprint(hook: &TaskHook) -> String {
+    let content = serde_json::to_string(&hook.action).unwrap_or_default();
+    to_hex(&Sha256::digest(content.as_bytes()))
+}
+
+/// 通知钩子无副作用，无需确认
+fn needs_confirm(hook: &TaskHook) -> bool {
+    !matches!(hook.action, HookAction::Notify { .. })
+}
+
+/// 在锁内访问已确认的指纹，changed 为 true 时保存
+fn with_trusted<T>(f: impl FnOnce(&mut HashSet<String>) -> (T, bool)) -> Result<T, String> {
+    let mut guard = TRUSTED.lock().map_err(|e| e.to_string())?;
+    let trusted = guard.get_or_insert_with(|| {
+        trust_path()
+            .ok()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|c| serde_json::from_str(&c).ok())
+            .unwrap_or_default()
+    });
+    let (result, changed) = f(trusted);
+    if changed {
+        save_json_config(&trust_path()?, &*trusted)?;
+    }
+    Ok(result)
+}
+
+fn is_confirmed(hook: &TaskHook) -> bool {
+    !needs_confirm(hook)
+        || with_trusted(|trusted| (trusted.contains(&fingerprint(hook)), false)).unwrap_or(false)
+}
+
+/// 确认钩子（用户在本机创建、编辑或确认后调用）
+pub fn confirm(hooks: &[TaskHook]) -> Result<(), String> {
+    with_trusted(|trusted| {
+        let mut changed = false;
+        for hook in hooks.iter().filter(|h| needs_confirm(h)) {
+            changed |= trusted.insert(fingerprint(hook));
+        }
+        ((), changed)
+    })
+}
+
+/// 确认编辑后新增或修改的钩子，未改动的钩子保持原确认状态
+pub fn confirm_edited(previous: &[TaskHook], hooks: &[TaskHook]) -> Result<(), String> {
+    let previous: HashSet<String> = previous.iter().map(fingerprint).collect();
+    let edited: Vec<TaskHook> = hooks
+        .iter()
+        .filter(|h| !previous.contains(&fingerprint(h)))
+        .cloned()
+        .collect();
+    confirm(&edited)
+}
+
+/// 未确认的钩子
+pub fn unconfirmed(hooks: &[TaskHook]) -> Vec<TaskHook> {
+    hooks.iter().filter(|h| !is_confirmed(h)).cloned().collect()
+}
+
+/// 替换占位符，escape 用于转义代入的值
+fn expand_with(
+    template: &str,
+    event: HookEvent,
+    ctx: &HookContext,
+    escape: fn(&str) -> String,
+) -> String {
+    template
+        .replace("{instance}", &escape(&ctx.instance_id))
+        .replace("{task}", &escape(ctx.task.as_deref().unwrap_or("")))
+        .replace("{status}", &escape(ctx.status.as_deref().unwrap_or("")))
+        .replace("{run}", &escape(ctx.run_id.as_deref().unwrap_or("")))
+        .replace("{event}", &escape(event_name(event)))
+}
+
+/// 替换占位符（程序参数与通知内容）
+fn expand(template: &str, event: HookEvent, ctx: &HookContext) -> String {
+    expand_with(template, event, ctx, str::to_owned)
+}
+
+/// JSON 字符串转义（不含两侧引号），用于 Webhook 请求体模板
+fn json_escape(value: &str) -> String {
+    let quoted = serde_json::Value::String(value.to_string()).to_string();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+fn url_escape(value: &str) -> String {
+    urlencoding::encode(value).into_owned()
+}
+
+fn matches(hook: &TaskHook, event: HookEvent, ctx: &HookContext) -> bool {
+    hook.enabled
+        && hook.event == event
+        && (hook.tasks.is_empty()
+            || ctx
+                .task
+                .as_ref()
+                .is_some_and(|task| hook.tasks.contains(task)))
+}
+
+/// 执行单个钩子
+fn run(hook: &TaskHook, event: HookEvent, ctx: &HookContext) -> Result<(), String> {
+    match &hook.action {
+        HookAction::RunProgram {
+            program,
+            args,
+            wait,
+            timeout_secs,
+        } => {
+            let mut child = Command::new(expand(program, event, ctx))
+                .args(args.iter().map(|a| expand(a, event, ctx)))
+                .spawn()
+                .map_err(|e| format!("无法运行程序 {}: {}", program, e))?;
+            if !*wait {
+                return Ok(());
+            }
+            let timeout = timeout_secs
+                .map(Duration::from_secs)
+                .unwrap_or(DEFAULT_PROGRAM_TIMEOUT);
+            let start = Instant::now();
+            loop {
+                match child.try_wait() {
+                    Ok(Some(status)) if status.success() => return Ok(()),
+                    Ok(Some(status)) => return Err(format!("程序 {} 退出码: {}", program, status)),
+                    Ok(None) if start.elapsed() >= timeout => {
+                        let _ = child.kill();
+                        let _ = child.wait();
+                        return Err(format!("程序 {} 运行超时", program));
+                    }
+                    Ok(None) => thread::sleep(Duration::from_millis(200)),
+                    Err(e) => return Err(e.to_string()),
+                }
+            }
+        }
+        HookAction::Webhook { url, method, body } => {
+            let method = method.as_deref().unwrap_or("POST").to_uppercase();
+            let method = reqwest::Method::from_bytes(method.as_bytes())
+                .map_err(|_| format!("无效的请求方法: {}", method))?;
+            let body = match body {
+                Some(body) => expand_with(body, event, ctx, json_escape),
+                None => serde_json::json!({
+                    "event": event_name(event),
+                    "instance_id": ctx.instance_id,
+                    "run_id": ctx.run_id,
+                    "task": ctx.task,
+                    "status": ctx.status,
+                    "time": chrono::Local::now().to_rfc3339(),
+                })
+                .to_string(),
+            };
+            let response = reqwest::blocking::Client::builder()
+                .timeout(WEBHOOK_TIMEOUT)
+                .build()
+                .map_err(|e| format!("创建 HTTP 客户端失败: {}", e))?
+                .request(method, expand_with(url, event, ctx, url_escape))
+                .header("Content-Type", "application/json")
+                .body(body)
+                .send()
+                .map_err(|e| format!("Webhook 请求失败: {}", e))?;
+            if !response.status().is_success() {
+                return Err(format!("Webhook 返回 HTTP {}", response.status()));
+            }
+            Ok(())
+        }
+        HookAction::Notify { title, body } => super::notifications::show(
+            &expand(title, event, ctx),
+            &expand(body, event, ctx),
+            &[ToastAction::Open],
+            None,
+        ),
+    }
+}
+
+fn run_logged(hook: &TaskHook, event: HookEvent, ctx: &HookContext) {
+    let result = run(hook, event, ctx);
+    let message = match &result {
+        Ok(()) => format!("Hook {} ({:?}) succeeded", event_name(event), hook.action),
+        Err(e) => format!("Hook {} failed: {}", event_name(event), e),
+    };
+    match &result {
+        Ok(()) => info!("[hooks] {}", message),
+        Err(_) => warn!("[hooks] {}", message),
+    }
+    if let Some(run_id) = &ctx.run_id {
+        super::runs::run_log(run_id, &message);
+    }
+}
+
+/// 触发钩子：before_* 钩子同步执行，其余在后台执行；未确认的钩子跳过
+pub fn fire(hooks: &[TaskHook], event: HookEvent, ctx: &HookContext) {
+    let (matched, skipped): (Vec<TaskHook>, Vec<TaskHook>) = hooks
+        .iter()
+        .filter(|h| matches(h, event, ctx))
+        .cloned()
+        .partition(is_confirmed);
+    for hook in &skipped {
+        let message = format!(
+            "Hook {} ({:?}) skipped: not confirmed on this device",
+            event_name(event),
+            hook.action
+        );
+        warn!("[hooks] {}", message);
+        if let Some(run_id) = &ctx.run_id {
+            super::runs::run_log(run_id, &message);
+        }
+    }
+    if matched.is_empty() {
+        return;
+    }
+    if matches!(event, HookEvent::BeforeQueue | HookEvent::BeforeTask) {
+        for hook in &matched {
+            run_logged(hook, event, ctx);
+        }
+    } else {
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            for hook in &matched {
+                run_logged(hook, event, &ctx);
+            }
+        });
+    }
+}
+
+/// 测试钩子（使用示例上下文立即执行）
+#[tauri::command]
+pub async fn hook_test(hook: TaskHook) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        let ctx = HookContext {
+            instance_id: "test".to_string(),
+            run_id: None,
+            task: hook.tasks.first().cloned().or(Some("Test".to_string())),
+            status: Some("succeeded".to_string()),
+        };
+        run(&hook, hook.event, &ctx)
+    })
+    .await
+    .map_err(|e| e.to_string())??)
+}
+
+/// 获取配置档案中未确认的钩子（导入或同步得到，确认前不会执行）
+#[tauri::command]
+pub fn hook_list_unconfirmed(profile_id: String) -> Result<Vec<TaskHook>, MxuError> {
+    Ok(unconfirmed(&profile_hooks(Some(&profile_id))?))
+}
+
+/// 确认配置档案中的全部钩子
+#[tauri::command]
+pub fn hook_confirm(profile_id: String) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    let hooks = profile_hooks(Some(&profile_id))?;
+    confirm(&hooks)?;
+    info!(
+        "[hooks] Confirmed {} hook(s) of profile {}",
+        hooks.len(),
+        profile_id
+    );
+    Ok(())
+}
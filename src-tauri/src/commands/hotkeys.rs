@@ -0,0 +1,301 @@
+//! 全局快捷键
+//!
+//! 基于 tauri_plugin_global_shortcut 在后端注册快捷键，绑定保存在 config/hotkeys.json，
+//! 启动时恢复注册（无需等待前端加载）。开始/停止任务通过 hotkey-action 事件交由前端执行，
+//! 显示/隐藏窗口与截图在后端直接完成。
+//! 绑定时检测冲突：同一快捷键绑定多个操作、格式无效或已被其他程序占用时返回错误
+
+use log::{info, warn};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use super::error::MxuError;
+use super::i18n::ErrorCode;
+use super::types::{HotkeyAction, HotkeyConfig, MaaState};
+use super::utils::{get_app_data_dir, save_json_config};
+
+/// 同一操作的最短触发间隔（按住不放或系统重复触发时去抖）
+const THROTTLE: Duration = Duration::from_millis(1000);
+/// 截图等待超时
+const SCREENSHOT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 配置读写锁
+static CONFIG_LOCK: Mutex<()> = Mutex::new(());
+
+/// 当前已注册的快捷键
+static REGISTERED: LazyLock<Mutex<HashMap<HotkeyAction, Shortcut>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 各操作上次触发时间
+static LAST_TRIGGER: LazyLock<Mutex<HashMap<HotkeyAction, Instant>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn config_path() -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?.join("config").join("hotkeys.json"))
+}
+
+fn load_config() -> Result<HotkeyConfig, String> {
+    let path = config_path()?;
+    if !path.exists() {
+        return Ok(HotkeyConfig::default());
+    }
+    let content =
+        std::fs::read_to_string(&path).map_err(|e| format!("无法读取快捷键配置: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("无法解析快捷键配置: {}", e))
+}
+
+fn save_config(config: &HotkeyConfig) -> Result<(), String> {
+    let path = config_path()?;
+    Ok(save_json_config(&path, config)?)
+}
+
+fn parse_shortcut(combo: &str) -> Result<Shortcut, String> {
+    combo
+        .trim()
+        .parse::<Shortcut>()
+        .map_err(|e| format!("无效的快捷键 {}: {}", combo, e))
+}
+
+/// 保存所有已连接实例的截图到 screenshots/，返回保存的路径
+fn capture_screenshots(app: &AppHandle) -> Vec<String> {
+    let Some(state) = app.try_state::<Arc<MaaState>>() else {
+        return Vec::new();
+    };
+    let controllers: Vec<_> = match state.instances.lock() {
+        Ok(instances) => instances
+            .iter()
+            .filter_map(|(id, i)| i.controller.clone().map(|c| (id.clone(), c)))
+            .filter(|(_, c)| c.connected())
+            .collect(),
+        Err(_) => return Vec::new(),
+    };
+    let Ok(dir) = get_app_data_dir().map(|d| d.join("screenshots")) else {
+        return Vec::new();
+    };
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        warn!("[hotkeys] Failed to create screenshots dir: {}", e);
+        return Vec::new();
+    }
+
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+    let mut saved = Vec::new();
+    for (instance_id, controller) in controllers {
+        let previous = controller.cached_image().ok().and_then(|b| b.to_vec());
+        if controller.post_screencap().is_err() {
+            continue;
+        }
+        // 等待缓存截图更新
+        let start = Instant::now();
+        let data = loop {
+            let data = controller.cached_image().ok().and_then(|b| b.to_vec());
+            if data.as_ref().is_some_and(|d| !d.is_empty()) && data != previous {
+                break data;
+            }
+            if start.elapsed() > SCREENSHOT_TIMEOUT {
+                break data.filter(|d| !d.is_empty());
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        };
+        let Some(data) = data else {
+            warn!("[hotkeys] No screenshot for instance {}", instance_id);
+            continue;
+        };
+        let path = dir.join(format!("{}_{}.png", instance_id, timestamp));
+        match std::fs::write(&path, data) {
+            Ok(()) => saved.push(path.to_string_lossy().to_string()),
+            Err(e) => warn!("[hotkeys] Failed to save screenshot: {}", e),
+        }
+    }
+    saved
+}
+
+fn trigger(app: &AppHandle, action: HotkeyAction) {
+    if let Ok(mut last) = LAST_TRIGGER.lock() {
+        if last.get(&action).is_some_and(|t| t.elapsed() < THROTTLE) {
+            return;
+        }
+        last.insert(action, Instant::now());
+    }
+    info!("[hotkeys] Triggered {:?}", action);
+
+    match action {
+        HotkeyAction::StartTasks | HotkeyAction::StopTasks => {
+            let _ = app.emit("hotkey-action", action);
+        }
+        HotkeyAction::ToggleWindow => {
+            if let Some(window) = app.get_webview_window("main") {
+                let visible =
+                    window.is_visible().unwrap_or(false) && !window.is_minimized().unwrap_or(false);
+                if visible {
+                    let _ = window.hide();
+                } else {
+                    let _ = window.show();
+                    let _ = window.unminimize();
+                    let _ = window.set_focus();
+                }
+            }
+        }
+        HotkeyAction::CaptureScreenshot => {
+            let app = app.clone();
+            std::thread::spawn(move || {
+                let saved = capture_screenshots(&app);
+                if !saved.is_empty() {
+                    info!("[hotkeys] Saved {} screenshot(s)", saved.len());
+                }
+                let _ = app.emit("hotkey-screenshot-saved", saved);
+            });
+        }
+    }
+}
+
+/// 注销全部已注册的快捷键
+fn unregister_all(app: &AppHandle) {
+    let Ok(mut registered) = REGISTERED.lock() else {
+        return;
+    };
+    for (_, shortcut) in registered.drain() {
+        if let Err(e) = app.global_shortcut().unregister(shortcut) {
+            warn!("[hotkeys] Failed to unregister {}: {}", shortcut, e);
+        }
+    }
+}
+
+/// 按配置重新注册全部快捷键，返回注册失败的操作与原因
+fn apply_config(app: &AppHandle, config: &HotkeyConfig) -> Vec<(HotkeyAction, String)> {
+    unregister_all(app);
+    if !config.enabled {
+        return Vec::new();
+    }
+
+    let mut failures = Vec::new();
+    for (action, combo) in &config.bindings {
+        let action = *action;
+        let shortcut = match parse_shortcut(combo) {
+            Ok(shortcut) => shortcut,
+            Err(e) => {
+                failures.push((action, e));
+                continue;
+            }
+        };
+        let result = app
+            .global_shortcut()
+            .on_shortcut(shortcut, move |app, _, event| {
+                if event.state() == ShortcutState::Pressed {
+                    trigger(app, action);
+                }
+            });
+        match result {
+            Ok(()) => {
+                if let Ok(mut registered) = REGISTERED.lock() {
+                    registered.insert(action, shortcut);
+                }
+            }
+            Err(e) => failures.push((
+                action,
+                format!("快捷键 {} 已被占用或无法注册: {}", combo, e),
+            )),
+        }
+    }
+    failures
+}
+
+/// 启动时恢复快捷键注册
+pub fn restore_hotkeys(app: &AppHandle) {
+    let config = load_config().unwrap_or_default();
+    for (action, reason) in apply_config(app, &config) {
+        warn!("[hotkeys] Failed to restore {:?}: {}", action, reason);
+    }
+    if config.enabled {
+        info!(
+            "[hotkeys] Restored {} hotkey(s)",
+            REGISTERED.lock().map(|r| r.len()).unwrap_or(0)
+        );
+    }
+}
+
+/// 配置文件被外部修改后重新注册（配置无法解析时保持当前注册）
+pub fn reload_hotkeys(app: &AppHandle) -> Result<(), String> {
+    let _guard = CONFIG_LOCK.lock().map_err(|e| e.to_string())?;
+    let config = load_config()?;
+    let failures = apply_config(app, &config);
+    match failures.into_iter().next() {
+        Some((action, reason)) => Err(format!("{:?}: {}", action, reason)),
+        None => Ok(()),
+    }
+}
+
+/// 保存配置并重新注册；存在注册失败时恢复原配置并返回错误
+fn update_config(
+    app: &AppHandle,
+    f: impl FnOnce(&mut HotkeyConfig),
+) -> Result<HotkeyConfig, String> {
+    super::guest_mode::ensure_not_guest()?;
+    let _guard = CONFIG_LOCK.lock().map_err(|e| e.to_string())?;
+    let previous = load_config()?;
+    let mut config = previous.clone();
+    f(&mut config);
+
+    let failures = apply_config(app, &config);
+    if let Some((action, reason)) = failures.into_iter().next() {
+        apply_config(app, &previous);
+        return Err(format!("{:?}: {}", action, reason));
+    }
+    save_config(&config)?;
+    Ok(config)
+}
+
+/// 获取快捷键配置
+#[tauri::command]
+pub fn hotkeys_get() -> Result<HotkeyConfig, MxuError> {
+    Ok(load_config()?)
+}
+
+/// 绑定快捷键（与其他操作冲突时返回错误）
+#[tauri::command]
+pub fn hotkeys_bind(
+    app: AppHandle,
+    action: HotkeyAction,
+    shortcut: String,
+) -> Result<HotkeyConfig, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    let parsed = parse_shortcut(&shortcut)?;
+    let current = load_config()?;
+    if let Some((other, _)) = current.bindings.iter().find(|(a, combo)| {
+        **a != action && parse_shortcut(combo).is_ok_and(|s| s.id() == parsed.id())
+    }) {
+        return Err(MxuError::with_args(
+            ErrorCode::HotkeyConflict,
+            [shortcut, format!("{:?}", other)],
+        ));
+    }
+    if current.bindings.get(&action) == Some(&shortcut) {
+        return Ok(current);
+    }
+    Ok(update_config(&app, |config| {
+        config.bindings.insert(action, shortcut);
+    })?)
+}
+
+/// 解除操作的快捷键绑定
+#[tauri::command]
+pub fn hotkeys_unbind(app: AppHandle, action: HotkeyAction) -> Result<HotkeyConfig, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    Ok(update_config(&app, |config| {
+        config.bindings.remove(&action);
+    })?)
+}
+
+/// 启用或停用全局快捷键
+#[tauri::command]
+pub fn hotkeys_set_enabled(app: AppHandle, enabled: bool) -> Result<HotkeyConfig, MxuError> {
+    if load_config()?.enabled == enabled {
+        return Ok(load_config()?);
+    }
+    super::guest_mode::ensure_not_guest()?;
+    Ok(update_config(&app, |config| config.enabled = enabled)?)
+}
@@ -0,0 +1,604 @@
+//! 后端错误信息本地化
+//!
+//! 常见错误以错误码登记在消息目录中（至少包含中文与英文），
+//! 命令返回时按前端当前语言生成可直接展示给用户的信息，
+//! 不再在各处 `format!` 中混用中英文（错误类型见 `error` 模块）。
+//! 后端直接弹出的原生对话框文字同样登记在提示目录中。
+//! 前端切换语言时调用 i18n_set_language 同步
+
+use log::info;
+use std::sync::RwLock;
+
+use serde::Serialize;
+
+/// 后端消息语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lang {
+    Zh,
+    En,
+}
+
+impl Lang {
+    /// 由 BCP 47 语言代码解析（zh-CN / zh-TW → 中文，其他语言回退到英文）
+    fn from_code(code: &str) -> Self {
+        if code.to_ascii_lowercase().starts_with("zh") {
+            Lang::Zh
+        } else {
+            Lang::En
+        }
+    }
+}
+
+/// 当前语言，前端同步前默认中文（与原有错误信息保持一致）
+static LANGUAGE: RwLock<Lang> = RwLock::new(Lang::Zh);
+
+/// 当前语言
+pub fn current_lang() -> Lang {
+    LANGUAGE.read().map(|l| *l).unwrap_or(Lang::Zh)
+}
+
+/// 错误码
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ErrorCode {
+    /// 实例不存在
+    InstanceNotFound,
+    /// 控制器未连接
+    ControllerNotConnected,
+    /// 资源未加载
+    ResourceNotLoaded,
+    /// Tasker 未创建
+    TaskerNotCreated,
+    /// 只读访客模式下不允许的操作
+    GuestModeReadOnly,
+    /// 无法创建配置目录，参数：{0} 原因
+    ConfigDirCreateFailed,
+    /// 设备或窗口不存在，参数：{0} 设备
+    DeviceNotFound,
+    /// 可能缺少 VC++ 运行库，参数：{0} 原因
+    VcredistMissing,
+    /// 有任务运行时无法应用更新
+    UpdateConflict,
+    /// 磁盘空间不足，参数：{0} 需要的空间，{1} 可用空间
+    InsufficientDiskSpace,
+    /// SHA-256 校验失败，参数：{0} 期望值，{1} 实际值
+    ChecksumMismatch,
+    /// 仅 Windows 可用的功能
+    WindowsOnly,
+    /// 压缩包中缺少文件，参数：{0} 文件名
+    FileNotInArchive,
+    /// 无法读取文件，参数：{0} 路径，{1} 原因
+    FileReadFailed,
+    /// 无法写入文件，参数：{0} 路径，{1} 原因
+    FileWriteFailed,
+    /// 网络请求失败，参数：{0} 原因
+    HttpRequestFailed,
+    /// 不是当前平台的可执行程序，参数：{0} 路径
+    InvalidExecutable,
+    /// 当前平台不支持程序自更新
+    AppUpdateUnsupported,
+    /// 只能应用暂存目录中的程序更新
+    AppUpdateNotStaged,
+    /// 无法启动程序，参数：{0} 原因
+    ProcessLaunchFailed,
+    /// 数据目录不是绝对路径
+    DataDirNotAbsolute,
+    /// 数据目录位于待迁移的文件夹中，参数：{0} 文件夹
+    DataDirInsideCurrent,
+    /// 未找到完整性清单，参数：{0} 路径
+    IntegrityManifestMissing,
+    /// 完整性清单无效，参数：{0} 原因
+    IntegrityManifestInvalid,
+    /// 完整性清单未提供下载地址
+    IntegrityNoDownloadUrl,
+    /// VC++ 安装程序签名无效，参数：{0} 签名状态
+    VcredistSignatureInvalid,
+    /// VC++ 运行库安装失败，参数：{0} 退出码
+    VcredistInstallFailed,
+    /// 非法的 MaaFramework 版本名，参数：{0} 版本
+    MaaFrameworkVersionInvalid,
+    /// MaaFramework 版本已安装，参数：{0} 版本
+    MaaFrameworkVersionInstalled,
+    /// MaaFramework 版本不存在，参数：{0} 版本
+    MaaFrameworkVersionNotFound,
+    /// adb 无法运行，参数：{0} 原因
+    AdbNotRunnable,
+    /// 坐标无效，参数：{0} x，{1} y
+    InvalidCoordinates,
+    /// 输入文本为空
+    EmptyInputText,
+    /// 未知的控制器方式，参数：{0} 方式名
+    UnknownControllerMethod,
+    /// 没有可探测的控制器方式
+    NoProbeMethods,
+    /// 缺少脚本内容
+    ScriptMissingSource,
+    /// 脚本语法错误，参数：{0} 原因
+    ScriptSyntaxError,
+    /// 脚本已停止
+    ScriptStopped,
+    /// 脚本执行超时
+    ScriptTimedOut,
+    /// 脚本执行失败，参数：{0} 原因
+    ScriptFailed,
+    /// 当前构建未启用脚本功能
+    ScriptingDisabled,
+    /// 操作已取消
+    Cancelled,
+    /// 无效的地址，参数：{0} 地址
+    InvalidUrl,
+    /// 端口无效
+    InvalidPort,
+    /// 无法获取局域网地址
+    LanAddressUnavailable,
+    /// 无法打开网页，参数：{0} 原因
+    OpenUrlFailed,
+    /// 名称为空
+    EmptyName,
+    /// 参数不是有效的 JSON
+    InvalidJson,
+    /// 数值超出范围，参数：{0} 最小值，{1} 最大值
+    ValueOutOfRange,
+    /// 间隔不大于 0
+    IntervalNotPositive,
+    /// 区域无效，参数：{0} 区域
+    InvalidRegion,
+    /// 区域或坐标超出截图范围，参数：{0} 区域或坐标，{1} 宽，{2} 高
+    RegionOutOfBounds,
+    /// 目录不存在，参数：{0} 路径
+    DirectoryNotFound,
+    /// 无法创建目录，参数：{0} 路径，{1} 原因
+    DirectoryCreateFailed,
+    /// 无法读取目录，参数：{0} 路径，{1} 原因
+    DirectoryReadFailed,
+    /// 无法删除文件，参数：{0} 路径，{1} 原因
+    FileDeleteFailed,
+    /// 无法解析文件，参数：{0} 路径，{1} 原因
+    FileParseFailed,
+    /// 配置文件不是 JSON 对象，参数：{0} 路径
+    ConfigNotObject,
+    /// 没有正在运行的任务
+    NoRunningTasks,
+    /// 访客模式 PIN 错误
+    GuestModePinIncorrect,
+    /// 非法的备份文件名，参数：{0} 文件名
+    BackupNameInvalid,
+    /// 备份文件不存在，参数：{0} 文件名
+    BackupNotFound,
+    /// 无法读取备份文件，参数：{0} 原因
+    BackupReadFailed,
+    /// 无法写入备份文件，参数：{0} 原因
+    BackupWriteFailed,
+    /// 已取消恢复并回滚
+    BackupRestoreCancelled,
+    /// 恢复失败并已回滚，参数：{0} 原因
+    BackupRestoreFailed,
+    /// 恢复失败且回滚失败，参数：{0} 恢复错误，{1} 回滚错误
+    BackupRollbackFailed,
+    /// 未配置 WebDAV 同步
+    SyncNotConfigured,
+    /// WebDAV 请求失败，参数：{0} 远程路径，{1} 原因
+    SyncRequestFailed,
+    /// 远程同步清单无效，参数：{0} 原因
+    SyncManifestInvalid,
+    /// 集群节点不存在，参数：{0} 节点 ID
+    ClusterNodeNotFound,
+    /// 集群分发需要开启远程 API 服务
+    ClusterApiServerRequired,
+    /// 上一轮集群分发尚未结束
+    ClusterRoundActive,
+    /// 没有可用的集群节点
+    ClusterNoNodes,
+    /// 快捷键已被占用，参数：{0} 快捷键，{1} 已绑定的操作
+    HotkeyConflict,
+    /// 安装包未经拖放确认，参数：{0} 路径
+    PackageNotConfirmed,
+    /// 文件不是有效的安装包，参数：{0} 路径
+    PackageInvalid,
+    /// 更新包不在暂存目录中，参数：{0} 文件名
+    UpdatePackageNotStaged,
+    /// 更新包不存在或格式不支持，参数：{0} 文件名
+    UpdatePackageInvalid,
+    /// 命令不支持远程调用，参数：{0} 命令
+    CommandNotRemotable,
+    /// 无效的环境变量名，参数：{0} 名称
+    InvalidEnvVarName,
+    /// 没有可导入的任务
+    NothingToImport,
+    /// 未开启崩溃报告上传
+    CrashUploadDisabled,
+    /// 设备分组不存在，参数：{0} 分组 ID
+    DeviceGroupNotFound,
+    /// 设备分组中存在重复的实例，参数：{0} 实例 ID
+    DuplicateGroupInstance,
+    /// 分组内没有已连接且空闲的设备
+    NoIdleGroupDevices,
+    /// 周报不存在，参数：{0} 周报 ID
+    DigestNotFound,
+    /// 无效的推理设备序号，参数：{0} 序号
+    InvalidDeviceIndex,
+    /// 截图目录中没有图片，参数：{0} 路径
+    NoImagesInDirectory,
+    /// 覆盖预设不是 JSON 对象
+    OverrideNotObject,
+    /// 运行程序操作未指定程序路径
+    RunProgramPathRequired,
+    /// 配置档案不存在，参数：{0} 档案 ID
+    ProfileNotFound,
+    /// 未提供节点名模式
+    EmptyNodePatterns,
+    /// 资源包不存在，参数：{0} 名称
+    ResourcePackNotFound,
+    /// 没有可监听的资源目录
+    NoWatchableResourceDirs,
+    /// 未找到 scrcpy-server，参数：{0} 路径
+    ScrcpyServerNotFound,
+    /// 无效的模板名称，参数：{0} 名称
+    InvalidTemplateName,
+    /// 无效的资源目录，参数：{0} 目录
+    InvalidResourceDir,
+    /// 未知的全局选项，参数：{0} 选项
+    UnknownGlobalOption,
+    /// 全局选项已不受支持，参数：{0} 选项
+    GlobalOptionDeprecated,
+    /// 全局选项的值无效，参数：{0} 选项，{1} 值
+    InvalidGlobalOptionValue,
+    /// 当前系统不支持定时唤醒
+    WakeTimerUnsupported,
+    /// 剪贴板文本过长
+    ClipboardTextTooLong,
+    /// 读取剪贴板失败，参数：{0} 原因
+    ClipboardReadFailed,
+    /// MaaFramework 未初始化
+    MaaFrameworkNotInitialized,
+    /// Agent 启动失败，参数：{0} 原因
+    AgentStartFailed,
+    /// 没有可用的图像数据
+    NoImageData,
+    /// 以管理员身份启动失败，参数：{0} 错误码
+    ElevationFailed,
+    /// 任务未暂停
+    TaskNotPaused,
+    /// 任务队列未运行
+    TaskQueueNotRunning,
+    /// 远程 API 服务未开启
+    RemoteApiNotRunning,
+    /// 访问令牌无效
+    InvalidAccessToken,
+    /// 未配置崩溃报告上传地址
+    CrashUploadEndpointMissing,
+    /// 无法获取程序路径，参数：{0} 原因
+    ExePathUnavailable,
+    /// 未分类的错误，参数：{0} 原始信息
+    Internal,
+}
+
+impl ErrorCode {
+    /// 消息模板，`{0}`、`{1}` 等按位置替换为参数
+    fn template(self, lang: Lang) -> &'static str {
+        use ErrorCode::*;
+        match (self, lang) {
+            (InstanceNotFound, Lang::Zh) => "实例不存在",
+            (InstanceNotFound, Lang::En) => "Instance not found",
+            (ControllerNotConnected, Lang::Zh) => "控制器未连接",
+            (ControllerNotConnected, Lang::En) => "Controller not connected",
+            (ResourceNotLoaded, Lang::Zh) => "资源未加载",
+            (ResourceNotLoaded, Lang::En) => "Resource not loaded",
+            (TaskerNotCreated, Lang::Zh) => "Tasker 未创建",
+            (TaskerNotCreated, Lang::En) => "Tasker not created",
+            (GuestModeReadOnly, Lang::Zh) => "只读访客模式下不允许此操作",
+            (GuestModeReadOnly, Lang::En) => {
+                "This operation is not allowed in read-only guest mode"
+            }
+            (ConfigDirCreateFailed, Lang::Zh) => "无法创建配置目录: {0}",
+            (ConfigDirCreateFailed, Lang::En) => "Failed to create config directory: {0}",
+            (DeviceNotFound, Lang::Zh) => "设备或窗口不存在: {0}",
+            (DeviceNotFound, Lang::En) => "Device or window not found: {0}",
+            (VcredistMissing, Lang::Zh) => "MaaFramework 加载失败，可能缺少 VC++ 运行库: {0}",
+            (VcredistMissing, Lang::En) => {
+                "Failed to load MaaFramework, the VC++ runtime may be missing: {0}"
+            }
+            (UpdateConflict, Lang::Zh) => "有任务正在运行，无法应用更新",
+            (UpdateConflict, Lang::En) => "Cannot apply the update while tasks are running",
+            (InsufficientDiskSpace, Lang::Zh) => "磁盘空间不足：需要 {0}，可用 {1}",
+            (InsufficientDiskSpace, Lang::En) => {
+                "Not enough disk space: {0} required, {1} available"
+            }
+            (ChecksumMismatch, Lang::Zh) => "校验失败：SHA-256 不匹配（期望 {0}，实际 {1}）",
+            (ChecksumMismatch, Lang::En) => "Checksum mismatch: expected SHA-256 {0}, got {1}",
+            (WindowsOnly, Lang::Zh) => "此功能仅在 Windows 上可用",
+            (WindowsOnly, Lang::En) => "This feature is only available on Windows",
+            (FileNotInArchive, Lang::Zh) => "压缩包中未找到 {0}",
+            (FileNotInArchive, Lang::En) => "{0} not found in the archive",
+            (FileReadFailed, Lang::Zh) => "无法读取文件 [{0}]: {1}",
+            (FileReadFailed, Lang::En) => "Failed to read file [{0}]: {1}",
+            (FileWriteFailed, Lang::Zh) => "无法写入文件 [{0}]: {1}",
+            (FileWriteFailed, Lang::En) => "Failed to write file [{0}]: {1}",
+            (HttpRequestFailed, Lang::Zh) => "网络请求失败: {0}",
+            (HttpRequestFailed, Lang::En) => "Network request failed: {0}",
+            (InvalidExecutable, Lang::Zh) => "文件不是有效的可执行程序: {0}",
+            (InvalidExecutable, Lang::En) => "Not a valid executable: {0}",
+            (AppUpdateUnsupported, Lang::Zh) => "macOS 请通过安装包更新 MXU",
+            (AppUpdateUnsupported, Lang::En) => "On macOS, update MXU with the installer package",
+            (AppUpdateNotStaged, Lang::Zh) => "只能应用暂存目录中的程序更新",
+            (AppUpdateNotStaged, Lang::En) => {
+                "Only updates from the staging directory can be applied"
+            }
+            (ProcessLaunchFailed, Lang::Zh) => "启动程序失败: {0}",
+            (ProcessLaunchFailed, Lang::En) => "Failed to launch the program: {0}",
+            (DataDirNotAbsolute, Lang::Zh) => "数据目录必须是绝对路径",
+            (DataDirNotAbsolute, Lang::En) => "The data directory must be an absolute path",
+            (DataDirInsideCurrent, Lang::Zh) => "数据目录不能位于当前数据目录的 {0} 文件夹中",
+            (DataDirInsideCurrent, Lang::En) => {
+                "The data directory cannot be inside the {0} folder of the current data directory"
+            }
+            (IntegrityManifestMissing, Lang::Zh) => "未找到完整性清单: {0}",
+            (IntegrityManifestMissing, Lang::En) => "Install manifest not found: {0}",
+            (IntegrityManifestInvalid, Lang::Zh) => "完整性清单无效: {0}",
+            (IntegrityManifestInvalid, Lang::En) => "Invalid install manifest: {0}",
+            (IntegrityNoDownloadUrl, Lang::Zh) => "完整性清单未提供下载地址，请重新下载完整安装包",
+            (IntegrityNoDownloadUrl, Lang::En) => {
+                "The install manifest has no download URL, please download the full package again"
+            }
+            (VcredistSignatureInvalid, Lang::Zh) => "安装程序签名无效: {0}",
+            (VcredistSignatureInvalid, Lang::En) => "Invalid installer signature: {0}",
+            (VcredistInstallFailed, Lang::Zh) => "VC++ 运行库安装失败，退出码 {0}",
+            (VcredistInstallFailed, Lang::En) => {
+                "VC++ runtime installation failed with exit code {0}"
+            }
+            (MaaFrameworkVersionInvalid, Lang::Zh) => "非法的 MaaFramework 版本名: {0}",
+            (MaaFrameworkVersionInvalid, Lang::En) => "Invalid MaaFramework version name: {0}",
+            (MaaFrameworkVersionInstalled, Lang::Zh) => "MaaFramework {0} 已安装",
+            (MaaFrameworkVersionInstalled, Lang::En) => "MaaFramework {0} is already installed",
+            (MaaFrameworkVersionNotFound, Lang::Zh) => "MaaFramework 版本不存在: {0}",
+            (MaaFrameworkVersionNotFound, Lang::En) => "MaaFramework version not found: {0}",
+            (AdbNotRunnable, Lang::Zh) => "adb 无法运行: {0}",
+            (AdbNotRunnable, Lang::En) => "adb cannot be run: {0}",
+            (InvalidCoordinates, Lang::Zh) => "无效的坐标: ({0}, {1})",
+            (InvalidCoordinates, Lang::En) => "Invalid coordinates: ({0}, {1})",
+            (EmptyInputText, Lang::Zh) => "输入文本不能为空",
+            (EmptyInputText, Lang::En) => "Input text cannot be empty",
+            (UnknownControllerMethod, Lang::Zh) => "未知的控制器方式: {0}",
+            (UnknownControllerMethod, Lang::En) => "Unknown controller method: {0}",
+            (NoProbeMethods, Lang::Zh) => "没有可探测的方式",
+            (NoProbeMethods, Lang::En) => "No methods to probe",
+            (ScriptMissingSource, Lang::Zh) => "缺少 script 或 file 参数",
+            (ScriptMissingSource, Lang::En) => "Missing script or file parameter",
+            (ScriptSyntaxError, Lang::Zh) => "脚本语法错误: {0}",
+            (ScriptSyntaxError, Lang::En) => "Script syntax error: {0}",
+            (ScriptStopped, Lang::Zh) => "脚本已停止",
+            (ScriptStopped, Lang::En) => "Script stopped",
+            (ScriptTimedOut, Lang::Zh) => "脚本执行超时",
+            (ScriptTimedOut, Lang::En) => "Script timed out",
+            (ScriptFailed, Lang::Zh) => "脚本执行失败: {0}",
+            (ScriptFailed, Lang::En) => "Script failed: {0}",
+            (ScriptingDisabled, Lang::Zh) => "当前构建未启用脚本功能（scripting feature）",
+            (ScriptingDisabled, Lang::En) => {
+                "Scripting is not enabled in this build (scripting feature)"
+            }
+            (Cancelled, Lang::Zh) => "操作已取消",
+            (Cancelled, Lang::En) => "Operation cancelled",
+            (InvalidUrl, Lang::Zh) => "无效的地址: {0}",
+            (InvalidUrl, Lang::En) => "Invalid URL: {0}",
+            (InvalidPort, Lang::Zh) => "端口不能为 0",
+            (InvalidPort, Lang::En) => "The port cannot be 0",
+            (LanAddressUnavailable, Lang::Zh) => "无法获取局域网地址",
+            (LanAddressUnavailable, Lang::En) => "Unable to determine the LAN address",
+            (OpenUrlFailed, Lang::Zh) => "无法打开网页: {0}",
+            (OpenUrlFailed, Lang::En) => "Failed to open the web page: {0}",
+            (EmptyName, Lang::Zh) => "名称不能为空",
+            (EmptyName, Lang::En) => "The name cannot be empty",
+            (InvalidJson, Lang::Zh) => "参数不是有效的 JSON",
+            (InvalidJson, Lang::En) => "The parameter is not valid JSON",
+            (ValueOutOfRange, Lang::Zh) => "数值需在 {0} ~ {1} 之间",
+            (ValueOutOfRange, Lang::En) => "The value must be between {0} and {1}",
+            (IntervalNotPositive, Lang::Zh) => "间隔必须大于 0",
+            (IntervalNotPositive, Lang::En) => "The interval must be greater than 0",
+            (InvalidRegion, Lang::Zh) => "无效的区域: {0}",
+            (InvalidRegion, Lang::En) => "Invalid region: {0}",
+            (RegionOutOfBounds, Lang::Zh) => "{0} 超出截图范围（截图尺寸 {1}x{2}）",
+            (RegionOutOfBounds, Lang::En) => "{0} is outside the screenshot ({1}x{2})",
+            (DirectoryNotFound, Lang::Zh) => "目录不存在: {0}",
+            (DirectoryNotFound, Lang::En) => "Directory not found: {0}",
+            (DirectoryCreateFailed, Lang::Zh) => "无法创建目录 [{0}]: {1}",
+            (DirectoryCreateFailed, Lang::En) => "Failed to create directory [{0}]: {1}",
+            (DirectoryReadFailed, Lang::Zh) => "无法读取目录 [{0}]: {1}",
+            (DirectoryReadFailed, Lang::En) => "Failed to read directory [{0}]: {1}",
+            (FileDeleteFailed, Lang::Zh) => "无法删除文件 [{0}]: {1}",
+            (FileDeleteFailed, Lang::En) => "Failed to delete file [{0}]: {1}",
+            (FileParseFailed, Lang::Zh) => "无法解析文件 [{0}]: {1}",
+            (FileParseFailed, Lang::En) => "Failed to parse file [{0}]: {1}",
+            (ConfigNotObject, Lang::Zh) => "配置文件格式错误（应为对象）: {0}",
+            (ConfigNotObject, Lang::En) => "Invalid config file (expected an object): {0}",
+            (NoRunningTasks, Lang::Zh) => "没有正在运行的任务",
+            (NoRunningTasks, Lang::En) => "No tasks are running",
+            (GuestModePinIncorrect, Lang::Zh) => "PIN 错误",
+            (GuestModePinIncorrect, Lang::En) => "Incorrect PIN",
+            (BackupNameInvalid, Lang::Zh) => "非法的备份文件名: {0}",
+            (BackupNameInvalid, Lang::En) => "Invalid backup file name: {0}",
+            (BackupNotFound, Lang::Zh) => "备份文件不存在: {0}",
+            (BackupNotFound, Lang::En) => "Backup file not found: {0}",
+            (BackupReadFailed, Lang::Zh) => "无法读取备份文件: {0}",
+            (BackupReadFailed, Lang::En) => "Failed to read the backup file: {0}",
+            (BackupWriteFailed, Lang::Zh) => "无法写入备份文件: {0}",
+            (BackupWriteFailed, Lang::En) => "Failed to write the backup file: {0}",
+            (BackupRestoreCancelled, Lang::Zh) => "已取消恢复，设置已回滚",
+            (BackupRestoreCancelled, Lang::En) => {
+                "Restore cancelled, settings have been rolled back"
+            }
+            (BackupRestoreFailed, Lang::Zh) => "恢复失败，设置已回滚: {0}",
+            (BackupRestoreFailed, Lang::En) => {
+                "Restore failed, settings have been rolled back: {0}"
+            }
+            (BackupRollbackFailed, Lang::Zh) => "恢复失败且回滚失败: {0} ({1})",
+            (BackupRollbackFailed, Lang::En) => {
+                "Restore failed and could not be rolled back: {0} ({1})"
+            }
+            (SyncNotConfigured, Lang::Zh) => "未配置 WebDAV 同步",
+            (SyncNotConfigured, Lang::En) => "WebDAV sync is not configured",
+            (SyncRequestFailed, Lang::Zh) => "WebDAV 请求失败 [{0}]: {1}",
+            (SyncRequestFailed, Lang::En) => "WebDAV request failed [{0}]: {1}",
+            (SyncManifestInvalid, Lang::Zh) => "无法解析远程清单: {0}",
+            (SyncManifestInvalid, Lang::En) => "Invalid remote sync manifest: {0}",
+            (ClusterNodeNotFound, Lang::Zh) => "节点不存在: {0}",
+            (ClusterNodeNotFound, Lang::En) => "Cluster node not found: {0}",
+            (ClusterApiServerRequired, Lang::Zh) => "需要开启远程 API 服务以接收节点回报",
+            (ClusterApiServerRequired, Lang::En) => {
+                "The remote API server must be enabled to receive node reports"
+            }
+            (ClusterRoundActive, Lang::Zh) => "上一轮集群分发尚未结束",
+            (ClusterRoundActive, Lang::En) => "The previous cluster dispatch has not finished",
+            (ClusterNoNodes, Lang::Zh) => "没有可用的集群节点",
+            (ClusterNoNodes, Lang::En) => "No cluster nodes are available",
+            (HotkeyConflict, Lang::Zh) => "快捷键 {0} 已绑定到 {1}",
+            (HotkeyConflict, Lang::En) => "Shortcut {0} is already bound to {1}",
+            (PackageNotConfirmed, Lang::Zh) => "未经拖放确认的安装包: {0}",
+            (PackageNotConfirmed, Lang::En) => "The package was not confirmed from a drop: {0}",
+            (PackageInvalid, Lang::Zh) => "文件不是有效的安装包: {0}",
+            (PackageInvalid, Lang::En) => "Not a valid package: {0}",
+            (UpdatePackageNotStaged, Lang::Zh) => "更新包不在暂存目录中: {0}",
+            (UpdatePackageNotStaged, Lang::En) => {
+                "The update package is not in the staging directory: {0}"
+            }
+            (UpdatePackageInvalid, Lang::Zh) => "更新包不存在或格式不支持: {0}",
+            (UpdatePackageInvalid, Lang::En) => {
+                "The update package does not exist or is not supported: {0}"
+            }
+            (CommandNotRemotable, Lang::Zh) => "命令不支持远程调用: {0}",
+            (CommandNotRemotable, Lang::En) => "The command cannot be called remotely: {0}",
+            (InvalidEnvVarName, Lang::Zh) => "无效的环境变量名: {0}",
+            (InvalidEnvVarName, Lang::En) => "Invalid environment variable name: {0}",
+            (NothingToImport, Lang::Zh) => "没有可导入的任务",
+            (NothingToImport, Lang::En) => "There are no tasks to import",
+            (CrashUploadDisabled, Lang::Zh) => "未开启崩溃报告上传",
+            (CrashUploadDisabled, Lang::En) => "Crash report upload is not enabled",
+            (DeviceGroupNotFound, Lang::Zh) => "设备分组不存在: {0}",
+            (DeviceGroupNotFound, Lang::En) => "Device group not found: {0}",
+            (DuplicateGroupInstance, Lang::Zh) => "设备分组中存在重复的实例: {0}",
+            (DuplicateGroupInstance, Lang::En) => {
+                "The device group contains a duplicate instance: {0}"
+            }
+            (NoIdleGroupDevices, Lang::Zh) => "分组内没有已连接且空闲的设备",
+            (NoIdleGroupDevices, Lang::En) => "No connected and idle devices in the group",
+            (DigestNotFound, Lang::Zh) => "周报不存在: {0}",
+            (DigestNotFound, Lang::En) => "Weekly digest not found: {0}",
+            (InvalidDeviceIndex, Lang::Zh) => "无效的设备序号: {0}",
+            (InvalidDeviceIndex, Lang::En) => "Invalid device index: {0}",
+            (NoImagesInDirectory, Lang::Zh) => "截图目录中没有图片: {0}",
+            (NoImagesInDirectory, Lang::En) => "No images in the screenshot directory: {0}",
+            (OverrideNotObject, Lang::Zh) => "覆盖预设必须是 JSON 对象",
+            (OverrideNotObject, Lang::En) => "The override preset must be a JSON object",
+            (RunProgramPathRequired, Lang::Zh) => "运行程序操作需要指定程序路径",
+            (RunProgramPathRequired, Lang::En) => "The run program action requires a program path",
+            (ProfileNotFound, Lang::Zh) => "配置档案不存在: {0}",
+            (ProfileNotFound, Lang::En) => "Profile not found: {0}",
+            (EmptyNodePatterns, Lang::Zh) => "至少需要一个节点名模式",
+            (EmptyNodePatterns, Lang::En) => "At least one node name pattern is required",
+            (ResourcePackNotFound, Lang::Zh) => "资源包不存在: {0}",
+            (ResourcePackNotFound, Lang::En) => "Resource pack not found: {0}",
+            (NoWatchableResourceDirs, Lang::Zh) => "没有可监听的资源目录",
+            (NoWatchableResourceDirs, Lang::En) => "No resource directories to watch",
+            (ScrcpyServerNotFound, Lang::Zh) => "未找到 scrcpy-server: {0}",
+            (ScrcpyServerNotFound, Lang::En) => "scrcpy-server not found: {0}",
+            (InvalidTemplateName, Lang::Zh) => "无效的模板名称: {0}",
+            (InvalidTemplateName, Lang::En) => "Invalid template name: {0}",
+            (InvalidResourceDir, Lang::Zh) => "无效的资源目录: {0}",
+            (InvalidResourceDir, Lang::En) => "Invalid resource directory: {0}",
+            (UnknownGlobalOption, Lang::Zh) => "未知的全局选项: {0}",
+            (UnknownGlobalOption, Lang::En) => "Unknown global option: {0}",
+            (GlobalOptionDeprecated, Lang::Zh) => "当前 MaaFramework 版本已不支持全局选项: {0}",
+            (GlobalOptionDeprecated, Lang::En) => {
+                "The global option is no longer supported by this MaaFramework version: {0}"
+            }
+            (InvalidGlobalOptionValue, Lang::Zh) => "全局选项 {0} 的值无效: {1}",
+            (InvalidGlobalOptionValue, Lang::En) => "Invalid value for global option {0}: {1}",
+            (WakeTimerUnsupported, Lang::Zh) => "当前系统不支持定时唤醒",
+            (WakeTimerUnsupported, Lang::En) => "Scheduled wake-up is not supported on this system",
+            (ClipboardTextTooLong, Lang::Zh) => "剪贴板文本过长",
+            (ClipboardTextTooLong, Lang::En) => "The clipboard text is too long",
+            (ClipboardReadFailed, Lang::Zh) => "读取剪贴板失败: {0}",
+            (ClipboardReadFailed, Lang::En) => "Failed to read the clipboard: {0}",
+            (MaaFrameworkNotInitialized, Lang::Zh) => "MaaFramework 未初始化",
+            (MaaFrameworkNotInitialized, Lang::En) => "MaaFramework is not initialized",
+            (AgentStartFailed, Lang::Zh) => "Agent 启动失败: {0}",
+            (AgentStartFailed, Lang::En) => "Agent start failed: {0}",
+            (NoImageData, Lang::Zh) => "没有可用的图像数据",
+            (NoImageData, Lang::En) => "No image data available",
+            (ElevationFailed, Lang::Zh) => "以管理员身份启动失败: 错误码 {0}",
+            (ElevationFailed, Lang::En) => "Failed to restart as administrator: error code {0}",
+            (TaskNotPaused, Lang::Zh) => "任务未暂停",
+            (TaskNotPaused, Lang::En) => "Tasks are not paused",
+            (TaskQueueNotRunning, Lang::Zh) => "任务队列未运行",
+            (TaskQueueNotRunning, Lang::En) => "Task queue is not running",
+            (RemoteApiNotRunning, Lang::Zh) => "远程 API 服务未开启",
+            (RemoteApiNotRunning, Lang::En) => "Remote API server is not running",
+            (InvalidAccessToken, Lang::Zh) => "无效的访问令牌",
+            (InvalidAccessToken, Lang::En) => "Invalid access token",
+            (CrashUploadEndpointMissing, Lang::Zh) => "未配置崩溃报告上传地址",
+            (CrashUploadEndpointMissing, Lang::En) => {
+                "Crash report upload endpoint is not configured"
+            }
+            (ExePathUnavailable, Lang::Zh) => "获取 exe 路径失败: {0}",
+            (ExePathUnavailable, Lang::En) => "Failed to get the executable path: {0}",
+            (Internal, _) => "{0}",
+        }
+    }
+}
+
+/// 后端原生对话框的提示文字
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Prompt {
+    /// 链接请求运行配置档案，参数：{0} 档案名称
+    DeepLinkRunProfile,
+    /// 链接请求下载资源包，参数：{0} 下载地址
+    DeepLinkDownloadPackage,
+}
+
+impl Prompt {
+    /// 提示模板，`{0}`、`{1}` 等按位置替换为参数
+    fn template(self, lang: Lang) -> &'static str {
+        use Prompt::*;
+        match (self, lang) {
+            (DeepLinkRunProfile, Lang::Zh) => "链接请求运行配置档案「{0}」，是否继续？",
+            (DeepLinkRunProfile, Lang::En) => {
+                "A link requested to run the profile \"{0}\". Continue?"
+            }
+            (DeepLinkDownloadPackage, Lang::Zh) => {
+                "链接请求从以下地址下载资源包：\n{0}\n\n下载完成后仍需确认安装，是否继续？"
+            }
+            (DeepLinkDownloadPackage, Lang::En) => {
+                "A link requested to download a resource package from:\n{0}\n\nYou will still be asked to confirm the installation after the download. Continue?"
+            }
+        }
+    }
+}
+
+/// 按位置替换模板参数
+fn fill(template: &str, args: &[String]) -> String {
+    let mut text = template.to_string();
+    for (i, arg) in args.iter().enumerate() {
+        text = text.replace(&format!("{{{}}}", i), arg);
+    }
+    text
+}
+
+/// 按当前语言生成消息
+pub fn message(code: ErrorCode, args: &[String]) -> String {
+    fill(code.template(current_lang()), args)
+}
+
+/// 按当前语言生成提示文字
+pub fn prompt(key: Prompt, args: &[String]) -> String {
+    fill(key.template(current_lang()), args)
+}
+
+/// 同步前端语言（BCP 47 代码，如 zh-CN、en-US）
+#[tauri::command]
+pub fn i18n_set_language(language: String) {
+    let lang = Lang::from_code(&language);
+    if let Ok(mut current) = LANGUAGE.write() {
+        if *current != lang {
+            info!("[i18n] Backend language set to {:?} ({})", lang, language);
+        }
+        *current = lang;
+    }
+}
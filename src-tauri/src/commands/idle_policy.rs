@@ -0,0 +1,129 @@
+//! 用户空闲策略
+//!
+//! 用户正在使用电脑时推迟任务队列中的下一个任务，避免 Win32 控制器自动化抢占鼠标。
+//! 空闲时间来源：Windows 为 GetLastInputInfo，macOS 为 IOHIDSystem 的 HIDIdleTime，
+//! Linux 为 xprintidle（X11，未安装时不生效）。
+//! 任务执行中无法中断，用户恢复操作后在当前任务结束、下一个任务开始前等待
+
+use log::info;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex};
+
+use super::error::MxuError;
+use super::types::{ControllerConfig, IdlePolicy};
+use super::utils::{get_app_data_dir, save_json_config};
+
+/// 各实例是否为 Win32 控制器（maa_connect_controller 时记录）
+static WIN32_INSTANCES: LazyLock<Mutex<HashMap<String, bool>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn config_path() -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?.join("config").join("idle_policy.json"))
+}
+
+fn load_policy() -> Result<IdlePolicy, String> {
+    let path = config_path()?;
+    if !path.exists() {
+        return Ok(IdlePolicy::default());
+    }
+    let content =
+        std::fs::read_to_string(&path).map_err(|e| format!("无法读取空闲策略配置: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("无法解析空闲策略配置: {}", e))
+}
+
+/// 记录实例的控制器类型
+pub fn note_controller(instance_id: &str, config: &ControllerConfig) {
+    if let Ok(mut map) = WIN32_INSTANCES.lock() {
+        map.insert(
+            instance_id.to_string(),
+            matches!(config, ControllerConfig::Win32 { .. }),
+        );
+    }
+}
+
+/// 获取对指定实例生效的空闲策略（未启用或不适用时返回 None）
+pub fn policy_for(instance_id: &str) -> Option<IdlePolicy> {
+    let policy = load_policy().ok().filter(|p| p.enabled)?;
+    if policy.win32_only {
+        let is_win32 = WIN32_INSTANCES
+            .lock()
+            .ok()
+            .and_then(|map| map.get(instance_id).copied())
+            .unwrap_or(false);
+        if !is_win32 {
+            return None;
+        }
+    }
+    Some(policy)
+}
+
+/// 用户无输入的秒数（无法获取时返回 None）
+#[cfg(windows)]
+pub fn user_idle_secs() -> Option<u64> {
+    use windows::Win32::System::SystemInformation::GetTickCount;
+    use windows::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+
+    let mut info = LASTINPUTINFO {
+        cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+        dwTime: 0,
+    };
+    unsafe {
+        if !GetLastInputInfo(&mut info).as_bool() {
+            return None;
+        }
+        Some(u64::from(GetTickCount().wrapping_sub(info.dwTime)) / 1000)
+    }
+}
+
+/// 用户无输入的秒数（无法获取时返回 None）
+#[cfg(target_os = "macos")]
+pub fn user_idle_secs() -> Option<u64> {
+    let output = std::process::Command::new("ioreg")
+        .args(["-c", "IOHIDSystem", "-d", "4"])
+        .output()
+        .ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let line = text.lines().find(|l| l.contains("\"HIDIdleTime\""))?;
+    let nanos: u64 = line.rsplit('=').next()?.trim().parse().ok()?;
+    Some(nanos / 1_000_000_000)
+}
+
+/// 用户无输入的秒数（无法获取时返回 None）
+#[cfg(all(not(windows), not(target_os = "macos")))]
+pub fn user_idle_secs() -> Option<u64> {
+    let output = std::process::Command::new("xprintidle").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let millis: u64 = String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse()
+        .ok()?;
+    Some(millis / 1000)
+}
+
+/// 获取空闲策略
+#[tauri::command]
+pub fn get_idle_policy() -> Result<IdlePolicy, MxuError> {
+    Ok(load_policy()?)
+}
+
+/// 设置空闲策略
+#[tauri::command]
+pub fn set_idle_policy(policy: IdlePolicy) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    let path = config_path()?;
+    save_json_config(&path, &policy)?;
+    info!(
+        "[idle_policy] Policy updated: enabled={}, idle_secs={}",
+        policy.enabled, policy.idle_secs
+    );
+    Ok(())
+}
+
+/// 获取当前用户空闲秒数（不支持时返回 None）
+#[tauri::command]
+pub fn get_user_idle_secs() -> Option<u64> {
+    user_idle_secs()
+}
@@ -0,0 +1,235 @@
+//! 推理后端选择
+//!
+//! 管理 OCR/神经网络识别的执行后端（CPU、DirectML、CoreML、CUDA）与设备选择，
+//! 配置持久化到 config/inference.json，创建资源时应用
+
+use log::{info, warn};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use maa_framework::resource::Resource;
+use tauri::State;
+
+use super::error::MxuError;
+use super::i18n::ErrorCode;
+use super::types::{InferenceDevice, InferenceOptions, InferenceProvider, MaaState};
+use super::utils::{get_app_data_dir, save_json_config};
+
+/// 当前推理选项
+static OPTIONS: Mutex<Option<InferenceOptions>> = Mutex::new(None);
+
+fn options_path() -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?.join("config").join("inference.json"))
+}
+
+fn load_options() -> InferenceOptions {
+    options_path()
+        .ok()
+        .and_then(|p| std::fs::read_to_string(p).ok())
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn save_options(options: &InferenceOptions) -> Result<(), String> {
+    let path = options_path()?;
+    Ok(save_json_config(&path, options)?)
+}
+
+fn current_options() -> InferenceOptions {
+    let mut guard = match OPTIONS.lock() {
+        Ok(guard) => guard,
+        Err(_) => return load_options(),
+    };
+    guard.get_or_insert_with(load_options).clone()
+}
+
+/// 配置文件被外部修改后刷新缓存（配置无法解析时保持当前选项）
+///
+/// 与设置命令相同，已加载的资源需重新加载后生效，CPU 线程数需重启后生效
+pub fn reload_inference_options() -> Result<(), String> {
+    let path = options_path()?;
+    let options = if path.exists() {
+        let content =
+            std::fs::read_to_string(&path).map_err(|e| format!("无法读取推理选项: {}", e))?;
+        serde_json::from_str(&content).map_err(|e| format!("无法解析推理选项: {}", e))?
+    } else {
+        InferenceOptions::default()
+    };
+    *OPTIONS.lock().map_err(|e| e.to_string())? = Some(options);
+    Ok(())
+}
+
+/// 启动时应用 CPU 线程数
+///
+/// MaaFramework 未提供线程数选项，通过 OMP_NUM_THREADS 限制 OpenCV / ONNX Runtime 的线程数，
+/// 必须在加载运行库前设置
+pub fn init_inference_options() {
+    if let Some(threads) = current_options().cpu_threads.filter(|t| *t > 0) {
+        std::env::set_var("OMP_NUM_THREADS", threads.to_string());
+        info!("[inference] CPU threads limited to {}", threads);
+    }
+}
+
+/// 将推理选项应用到资源（创建资源时调用，需在加载资源包前设置）
+pub fn apply_to_resource(resource: &Resource) {
+    let options = current_options();
+    let device_id = options.device_id.unwrap_or(0);
+    let result = match options.provider {
+        InferenceProvider::Auto => return,
+        InferenceProvider::Cpu => resource.use_cpu(),
+        InferenceProvider::DirectMl => resource.use_directml(device_id),
+        InferenceProvider::CoreMl => resource.use_coreml(0),
+        InferenceProvider::Cuda => resource.use_cuda(device_id),
+    };
+    match result {
+        Ok(_) => info!(
+            "[inference] Using {:?} (device {:?})",
+            options.provider, options.device_id
+        ),
+        Err(e) => warn!(
+            "[inference] Failed to set inference provider {:?}: {}",
+            options.provider, e
+        ),
+    }
+}
+
+/// 获取推理选项
+#[tauri::command]
+pub fn maa_get_inference_options() -> InferenceOptions {
+    current_options()
+}
+
+/// 设置推理选项
+///
+/// 已加载的资源需重新加载后生效；CPU 线程数需重启后生效
+#[tauri::command]
+pub fn maa_set_inference_options(
+    state: State<Arc<MaaState>>,
+    options: InferenceOptions,
+) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    if options.device_id.is_some_and(|id| id < 0) {
+        return Err(MxuError::with_args(
+            ErrorCode::InvalidDeviceIndex,
+            [format!("{:?}", options.device_id)],
+        ));
+    }
+    save_options(&options)?;
+    *OPTIONS.lock().map_err(|e| e.to_string())? = Some(options.clone());
+
+    // 已创建的资源立即更新选项，下次加载资源包时生效
+    let instances = state.instances.lock().map_err(|e| e.to_string())?;
+    for instance in instances.values() {
+        if let Some(resource) = &instance.resource {
+            apply_to_resource(resource);
+        }
+    }
+    info!("[inference] Options updated: {:?}", options);
+    Ok(())
+}
+
+/// 列出可用的推理设备（CPU 始终可用）
+#[tauri::command]
+pub async fn maa_list_inference_devices() -> Result<Vec<InferenceDevice>, MxuError> {
+    tauri::async_runtime::spawn_blocking(|| {
+        let mut devices = vec![InferenceDevice {
+            provider: InferenceProvider::Cpu,
+            device_id: None,
+            name: "CPU".to_string(),
+            memory: None,
+        }];
+        devices.extend(list_gpu_devices());
+        devices
+    })
+    .await
+    .map_err(|e| e.to_string().into())
+}
+
+/// 通过 DXGI 枚举显卡（DirectML 设备序号与 DXGI 适配器序号一致），并补充 CUDA 设备
+#[cfg(windows)]
+fn list_gpu_devices() -> Vec<InferenceDevice> {
+    use windows::Win32::Graphics::Dxgi::{
+        CreateDXGIFactory1, IDXGIFactory1, DXGI_ADAPTER_FLAG_SOFTWARE,
+    };
+
+    let mut devices = Vec::new();
+    unsafe {
+        let Ok(factory) = CreateDXGIFactory1::<IDXGIFactory1>() else {
+            return list_cuda_devices();
+        };
+        let mut index = 0u32;
+        while let Ok(adapter) = factory.EnumAdapters1(index) {
+            if let Ok(desc) = adapter.GetDesc1() {
+                let software = desc.Flags & DXGI_ADAPTER_FLAG_SOFTWARE.0 as u32 != 0;
+                if !software {
+                    let len = desc.Description.iter().position(|&c| c == 0).unwrap_or(128);
+                    devices.push(InferenceDevice {
+                        provider: InferenceProvider::DirectMl,
+                        device_id: Some(index as i32),
+                        name: String::from_utf16_lossy(&desc.Description[..len]),
+                        memory: Some(desc.DedicatedVideoMemory as u64),
+                    });
+                }
+            }
+            index += 1;
+        }
+    }
+    devices.extend(list_cuda_devices());
+    devices
+}
+
+/// macOS 使用 CoreML（由系统在 GPU / 神经网络引擎间调度）
+#[cfg(target_os = "macos")]
+fn list_gpu_devices() -> Vec<InferenceDevice> {
+    vec![InferenceDevice {
+        provider: InferenceProvider::CoreMl,
+        device_id: None,
+        name: "CoreML".to_string(),
+        memory: None,
+    }]
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+fn list_gpu_devices() -> Vec<InferenceDevice> {
+    list_cuda_devices()
+}
+
+/// 通过 nvidia-smi 枚举 CUDA 设备（未安装 NVIDIA 驱动时返回空）
+#[cfg(not(target_os = "macos"))]
+fn list_cuda_devices() -> Vec<InferenceDevice> {
+    #[allow(unused_mut)]
+    let mut cmd = std::process::Command::new("nvidia-smi");
+    cmd.args([
+        "--query-gpu=index,name,memory.total",
+        "--format=csv,noheader,nounits",
+    ]);
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+    let Ok(output) = cmd.output() else {
+        return Vec::new();
+    };
+    if !output.status.success() {
+        return Vec::new();
+    }
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split(',').map(str::trim);
+            let index = parts.next()?.parse::<i32>().ok()?;
+            let name = parts.next()?.to_string();
+            // nvidia-smi 以 MiB 为单位
+            let memory = parts.next().and_then(|m| m.parse::<u64>().ok());
+            Some(InferenceDevice {
+                provider: InferenceProvider::Cuda,
+                device_id: Some(index),
+                name,
+                memory: memory.map(|m| m * 1024 * 1024),
+            })
+        })
+        .collect()
+}
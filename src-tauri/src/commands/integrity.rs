@@ -0,0 +1,293 @@
+//! 安装完整性校验与修复
+//!
+//! 清单（exe 目录下的 install_manifest.json）记录 maafw、resource 与 interface.json 中每个文件的
+//! SHA-256 与大小，可随发布包分发，也可由 install_manifest_generate 在本地生成。
+//! verify_install 找出缺失或损坏的文件（杀毒软件误删很常见），
+//! repair_install 按清单的 base_url 只重新下载这些文件
+
+use log::{info, warn};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use tauri::{AppHandle, Emitter, State};
+
+use super::error::MxuError;
+use super::file_ops::to_hex;
+use super::i18n::ErrorCode;
+use super::types::{
+    InstallManifest, InstallManifestEntry, InstallRepairFailure, InstallRepairProgressEvent,
+    InstallRepairResult, InstallVerifyReport, MaaState,
+};
+use super::update::{ensure_idle, is_safe_relative_path, move_to_old_folder};
+use super::utils::{build_user_agent, get_exe_directory};
+
+/// 清单文件名
+const MANIFEST_FILE: &str = "install_manifest.json";
+/// 清单格式版本
+const MANIFEST_SCHEMA_VERSION: u32 = 1;
+/// 清单覆盖的条目（相对于 exe 目录）
+const MANIFEST_ROOTS: &[&str] = &["maafw", "resource", "interface.json"];
+
+/// 计算文件的 SHA-256
+fn file_sha256(path: &Path) -> Result<String, String> {
+    let mut file = std::fs::File::open(path)
+        .map_err(|e| format!("无法打开文件 [{}]: {}", path.display(), e))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)
+        .map_err(|e| format!("无法读取文件 [{}]: {}", path.display(), e))?;
+    Ok(to_hex(&hasher.finalize()))
+}
+
+/// 递归收集文件，键为相对于 exe 目录、以 / 分隔的路径
+fn collect_files(path: &Path, relative: &str, out: &mut BTreeMap<String, InstallManifestEntry>) {
+    if path.is_dir() {
+        let Ok(entries) = std::fs::read_dir(path) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            collect_files(&entry.path(), &format!("{}/{}", relative, name), out);
+        }
+    } else if path.is_file() {
+        match file_sha256(path) {
+            Ok(sha256) => {
+                let size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+                out.insert(relative.to_string(), InstallManifestEntry { sha256, size });
+            }
+            Err(e) => warn!("[integrity] Skipped {}: {}", relative, e),
+        }
+    }
+}
+
+fn read_manifest(exe_dir: &Path) -> Result<InstallManifest, MxuError> {
+    let path = exe_dir.join(MANIFEST_FILE);
+    let content = std::fs::read_to_string(&path)
+        .map_err(|_| MxuError::with_args(ErrorCode::IntegrityManifestMissing, [path.display()]))?;
+    let manifest: InstallManifest = serde_json::from_str(&content)
+        .map_err(|e| MxuError::with_args(ErrorCode::IntegrityManifestInvalid, [e]))?;
+    if manifest.schema_version > MANIFEST_SCHEMA_VERSION {
+        return Err(MxuError::with_args(
+            ErrorCode::IntegrityManifestInvalid,
+            [format!(
+                "schema_version {} > {}",
+                manifest.schema_version, MANIFEST_SCHEMA_VERSION
+            )],
+        ));
+    }
+    if let Some(path) = manifest.files.keys().find(|p| !is_safe_relative_path(p)) {
+        return Err(MxuError::with_args(
+            ErrorCode::IntegrityManifestInvalid,
+            [format!("unsafe path {}", path)],
+        ));
+    }
+    Ok(manifest)
+}
+
+/// 按清单逐个检查文件
+fn verify(exe_dir: &Path, manifest: &InstallManifest) -> InstallVerifyReport {
+    let mut missing = Vec::new();
+    let mut corrupted = Vec::new();
+    for (relative, entry) in &manifest.files {
+        let path = exe_dir.join(relative);
+        let Ok(metadata) = std::fs::metadata(&path) else {
+            missing.push(relative.clone());
+            continue;
+        };
+        // 大小不同时不必计算哈希
+        let intact = metadata.len() == entry.size
+            && file_sha256(&path).is_ok_and(|h| h.eq_ignore_ascii_case(&entry.sha256));
+        if !intact {
+            corrupted.push(relative.clone());
+        }
+    }
+    InstallVerifyReport {
+        version: manifest.version.clone(),
+        checked: manifest.files.len(),
+        missing,
+        corrupted,
+    }
+}
+
+/// 为当前安装生成完整性清单，返回记录的文件数
+/// base_url: 单个文件的下载地址前缀，供 repair_install 使用
+#[tauri::command]
+pub async fn install_manifest_generate(
+    version: Option<String>,
+    base_url: Option<String>,
+) -> Result<usize, MxuError> {
+    info!("install_manifest_generate called, version: {:?}", version);
+
+    let count = tauri::async_runtime::spawn_blocking(move || -> Result<usize, String> {
+        let exe_dir = get_exe_directory()?;
+        let mut files = BTreeMap::new();
+        for root in MANIFEST_ROOTS {
+            collect_files(&exe_dir.join(root), root, &mut files);
+        }
+        let manifest = InstallManifest {
+            schema_version: MANIFEST_SCHEMA_VERSION,
+            version,
+            base_url: base_url.filter(|u| !u.trim().is_empty()),
+            files,
+        };
+        let content = serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?;
+        std::fs::write(exe_dir.join(MANIFEST_FILE), content)
+            .map_err(|e| format!("无法写入完整性清单: {}", e))?;
+        Ok(manifest.files.len())
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    info!("[integrity] Manifest generated with {} files", count);
+    Ok(count)
+}
+
+/// 按清单检查安装文件
+#[tauri::command]
+pub async fn verify_install() -> Result<InstallVerifyReport, MxuError> {
+    info!("verify_install called");
+
+    let report = tauri::async_runtime::spawn_blocking(|| -> Result<_, MxuError> {
+        let exe_dir = get_exe_directory()?;
+        let manifest = read_manifest(&exe_dir)?;
+        Ok(verify(&exe_dir, &manifest))
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    info!(
+        "[integrity] Verified {} files: {} missing, {} corrupted",
+        report.checked,
+        report.missing.len(),
+        report.corrupted.len()
+    );
+    Ok(report)
+}
+
+/// 拼接单个文件的下载地址（逐段编码）
+fn file_url(base_url: &str, relative: &str) -> String {
+    let encoded: Vec<String> = relative
+        .split('/')
+        .map(|s| urlencoding::encode(s).into_owned())
+        .collect();
+    format!("{}/{}", base_url.trim_end_matches('/'), encoded.join("/"))
+}
+
+/// 下载单个文件，校验后替换（旧文件移动到 cache/old）
+async fn repair_file(
+    client: &reqwest::Client,
+    base_url: &str,
+    exe_dir: &Path,
+    relative: &str,
+    entry: &InstallManifestEntry,
+) -> Result<(), String> {
+    let url = file_url(base_url, relative);
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| MxuError::with_args(ErrorCode::HttpRequestFailed, [e]))?;
+    if !response.status().is_success() {
+        return Err(MxuError::with_args(ErrorCode::HttpRequestFailed, [response.status()]).into());
+    }
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| MxuError::with_args(ErrorCode::HttpRequestFailed, [e]))?;
+
+    let sha256 = to_hex(&Sha256::digest(&bytes));
+    if !sha256.eq_ignore_ascii_case(&entry.sha256) {
+        return Err(
+            MxuError::with_args(ErrorCode::ChecksumMismatch, [&entry.sha256, &sha256]).into(),
+        );
+    }
+
+    let target = exe_dir.join(relative);
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("无法创建目录: {}", e))?;
+    }
+    let mut temp = target.clone().into_os_string();
+    temp.push(".repairing");
+    let temp = PathBuf::from(temp);
+    std::fs::write(&temp, &bytes).map_err(|e| format!("无法写入文件: {}", e))?;
+    if target.exists() {
+        move_to_old_folder(&target)?;
+    }
+    std::fs::rename(&temp, &target).map_err(|e| {
+        let _ = std::fs::remove_file(&temp);
+        format!("无法替换文件: {}", e)
+    })
+}
+
+/// 重新下载缺失或损坏的文件，进度通过 install-repair-progress 事件发送
+#[tauri::command]
+pub async fn repair_install(
+    app: AppHandle,
+    state: State<'_, Arc<MaaState>>,
+    proxy_url: Option<String>,
+) -> Result<InstallRepairResult, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    info!("repair_install called");
+    ensure_idle(&state)?;
+
+    let exe_dir = get_exe_directory()?;
+    let manifest = read_manifest(&exe_dir)?;
+    let Some(base_url) = manifest.base_url.clone() else {
+        return Err(MxuError::new(ErrorCode::IntegrityNoDownloadUrl));
+    };
+
+    let scan_dir = exe_dir.clone();
+    let scan_manifest = manifest.clone();
+    let report = tauri::async_runtime::spawn_blocking(move || verify(&scan_dir, &scan_manifest))
+        .await
+        .map_err(|e| e.to_string())?;
+    let broken: Vec<String> = report.missing.into_iter().chain(report.corrupted).collect();
+    if broken.is_empty() {
+        info!("[integrity] Nothing to repair");
+        return Ok(InstallRepairResult {
+            repaired: Vec::new(),
+            failed: Vec::new(),
+        });
+    }
+
+    let mut client_builder = reqwest::Client::builder()
+        .user_agent(build_user_agent())
+        .connect_timeout(std::time::Duration::from_secs(10));
+    if let Some(proxy) = proxy_url.as_deref().filter(|p| !p.is_empty()) {
+        let proxy = reqwest::Proxy::all(proxy).map_err(|e| format!("代理配置失败: {}", e))?;
+        client_builder = client_builder.proxy(proxy);
+    }
+    let client = client_builder
+        .build()
+        .map_err(|e| format!("创建 HTTP 客户端失败: {}", e))?;
+
+    let mut repaired = Vec::new();
+    let mut failed = Vec::new();
+    for (index, relative) in broken.iter().enumerate() {
+        let _ = app.emit(
+            "install-repair-progress",
+            InstallRepairProgressEvent {
+                current: index + 1,
+                total: broken.len(),
+                path: relative.clone(),
+            },
+        );
+        let entry = &manifest.files[relative];
+        match repair_file(&client, &base_url, &exe_dir, relative, entry).await {
+            Ok(()) => {
+                info!("[integrity] Repaired {}", relative);
+                repaired.push(relative.clone());
+            }
+            Err(error) => {
+                warn!("[integrity] Failed to repair {}: {}", relative, error);
+                failed.push(InstallRepairFailure {
+                    path: relative.clone(),
+                    error,
+                });
+            }
+        }
+    }
+
+    Ok(InstallRepairResult { repaired, failed })
+}
@@ -0,0 +1,250 @@
+//! 旧版残留清理
+//!
+//! 查找历次升级后遗留的旧版产物（注册表自启动项、指向已不存在程序的计划任务、
+//! 旧版位于数据目录根部的配置文件、更新残留、中断的下载、过期的配置迁移备份），
+//! 迁移或删除并返回报告
+
+use log::{info, warn};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use super::error::MxuError;
+use super::file_ops::get_exe_dir;
+use super::types::{LegacyCleanupItem, LegacyCleanupReport};
+use super::update::cleanup_dir_contents;
+use super::utils::get_app_data_dir;
+
+/// 配置迁移备份保留天数
+const CONFIG_BACKUP_MAX_AGE: Duration = Duration::from_secs(90 * 24 * 60 * 60);
+
+/// 中断下载的临时文件后缀（见 download 模块）
+const DOWNLOADING_SUFFIX: &str = ".downloading";
+
+struct Report {
+    dry_run: bool,
+    items: Vec<LegacyCleanupItem>,
+}
+
+impl Report {
+    fn push(&mut self, category: &str, target: String, result: Result<&str, String>) {
+        let (action, message) = match result {
+            Ok(_) if self.dry_run => ("found".to_string(), None),
+            Ok(action) => (action.to_string(), None),
+            Err(e) => ("failed".to_string(), Some(e)),
+        };
+        self.items.push(LegacyCleanupItem {
+            category: category.to_string(),
+            target,
+            action,
+            message,
+        });
+    }
+
+    /// 删除文件或目录并记录结果
+    fn remove_path(&mut self, category: &str, path: &Path) {
+        let result = if self.dry_run {
+            Ok("removed")
+        } else if path.is_dir() {
+            std::fs::remove_dir_all(path)
+                .map(|_| "removed")
+                .map_err(|e| e.to_string())
+        } else {
+            std::fs::remove_file(path)
+                .map(|_| "removed")
+                .map_err(|e| e.to_string())
+        };
+        self.push(category, path.display().to_string(), result);
+    }
+}
+
+/// 旧版注册表自启动项：迁移到任务计划程序
+#[cfg(windows)]
+fn check_registry_autostart(report: &mut Report) {
+    use super::system::{
+        create_schtask_autostart, has_legacy_registry_autostart, remove_legacy_registry_autostart,
+    };
+
+    if !has_legacy_registry_autostart() {
+        return;
+    }
+    let result = if report.dry_run {
+        Ok("migrated")
+    } else {
+        create_schtask_autostart().map(|_| {
+            remove_legacy_registry_autostart();
+            "migrated"
+        })
+    };
+    report.push(
+        "registry",
+        r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run\MXU".to_string(),
+        result,
+    );
+}
+
+/// 计划任务指向的程序已不存在（程序目录被移动或删除）：重建为当前 exe。
+/// 指向其他仍存在的 MXU 安装时保留，避免抢占另一份安装的自启动
+#[cfg(windows)]
+fn check_stale_schtask(report: &mut Report) {
+    let Ok(output) = std::process::Command::new("schtasks")
+        .args(["/query", "/tn", "MXU", "/xml"])
+        .output()
+    else {
+        return;
+    };
+    if !output.status.success() {
+        return;
+    }
+    let xml = String::from_utf8_lossy(&output.stdout);
+    let Some(command) = regex::Regex::new(r"<Command>(.*?)</Command>")
+        .ok()
+        .and_then(|re| re.captures(&xml))
+        .map(|c| c[1].trim_matches('"').to_string())
+    else {
+        return;
+    };
+    let Ok(current) = std::env::current_exe() else {
+        return;
+    };
+    if command.eq_ignore_ascii_case(&current.to_string_lossy()) || Path::new(&command).exists() {
+        return;
+    }
+
+    let result = if report.dry_run {
+        Ok("migrated")
+    } else {
+        super::system::create_schtask_autostart().map(|_| "migrated")
+    };
+    report.push("schtask", format!("MXU -> {}", command), result);
+}
+
+/// 旧版配置文件名：早期版本将 mxu.json / mxu-<项目>.json 直接保存在数据目录根部，
+/// 现位于 config 子目录。新位置尚无配置时迁移，已有时保留旧文件并提示
+fn check_legacy_config(report: &mut Report, data_dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(data_dir) else {
+        return;
+    };
+    let config_dir = data_dir.join("config");
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        let is_config = name == "mxu.json" || (name.starts_with("mxu-") && name.ends_with(".json"));
+        if !is_config || !path.is_file() {
+            continue;
+        }
+        let target = config_dir.join(&name);
+        let result = if target.exists() {
+            Err(format!(
+                "新位置已存在配置，保留旧文件: {}",
+                target.display()
+            ))
+        } else if report.dry_run {
+            Ok("migrated")
+        } else {
+            std::fs::create_dir_all(&config_dir)
+                .and_then(|_| std::fs::rename(&path, &target))
+                .map(|_| "migrated")
+                .map_err(|e| e.to_string())
+        };
+        report.push("config", path.display().to_string(), result);
+    }
+}
+
+/// 更新残留的 cache/old 目录（与 update::move_to_old_folder 一致，位于 exe 目录）
+fn check_update_leftovers(report: &mut Report) {
+    let Ok(exe_dir) = get_exe_dir() else {
+        return;
+    };
+    let old_dir = Path::new(&exe_dir).join("cache").join("old");
+    let has_content = std::fs::read_dir(&old_dir).is_ok_and(|mut d| d.next().is_some());
+    if !has_content {
+        return;
+    }
+    let result = if report.dry_run {
+        Ok("removed")
+    } else {
+        match cleanup_dir_contents(&old_dir) {
+            (_, 0) => Ok("removed"),
+            (deleted, failed) => Err(format!("已删除 {} 项，{} 项删除失败", deleted, failed)),
+        }
+    };
+    report.push("cache", old_dir.display().to_string(), result);
+}
+
+/// 中断下载遗留的 .downloading 临时文件
+fn check_partial_downloads(report: &mut Report, data_dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(data_dir.join("cache")) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_file() && path.to_string_lossy().ends_with(DOWNLOADING_SUFFIX) {
+            report.remove_path("download", &path);
+        }
+    }
+}
+
+/// 过期的配置迁移备份（config/backup/*.bak）
+fn check_config_backups(report: &mut Report, data_dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(data_dir.join("config").join("backup")) else {
+        return;
+    };
+    let now = SystemTime::now();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let expired = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| now.duration_since(t).ok())
+            .is_some_and(|age| age > CONFIG_BACKUP_MAX_AGE);
+        if expired && path.extension().is_some_and(|e| e == "bak") {
+            report.remove_path("config_backup", &path);
+        }
+    }
+}
+
+/// 查找并清理旧版残留
+///
+/// dry_run 为 true 时只检测不修改
+#[tauri::command]
+pub fn cleanup_legacy_install(dry_run: Option<bool>) -> Result<LegacyCleanupReport, MxuError> {
+    let dry_run = dry_run.unwrap_or(false);
+    if !dry_run {
+        super::guest_mode::ensure_not_guest()?;
+    }
+    let data_dir = get_app_data_dir()?;
+    let mut report = Report {
+        dry_run,
+        items: Vec::new(),
+    };
+
+    #[cfg(windows)]
+    {
+        check_registry_autostart(&mut report);
+        check_stale_schtask(&mut report);
+    }
+    check_legacy_config(&mut report, &data_dir);
+    check_update_leftovers(&mut report);
+    check_partial_downloads(&mut report, &data_dir);
+    check_config_backups(&mut report, &data_dir);
+
+    let failed = report.items.iter().filter(|i| i.action == "failed").count();
+    if failed > 0 {
+        warn!(
+            "[legacy_cleanup] {} item(s) processed, {} failed",
+            report.items.len(),
+            failed
+        );
+    } else {
+        info!(
+            "[legacy_cleanup] {} item(s) processed (dry_run: {})",
+            report.items.len(),
+            dry_run
+        );
+    }
+    Ok(LegacyCleanupReport {
+        dry_run,
+        items: report.items,
+    })
+}
@@ -0,0 +1,169 @@
+//! 后端日志级别配置
+//!
+//! 日志插件以 Trace 级别初始化，实际输出由本模块的过滤器决定：
+//! 全局级别加按模块（target 前缀）覆盖，可在运行时通过 set_log_config 调整并持久化到
+//! config/log_config.json，例如屏蔽 maa_ffi 的调试日志而保留 mxu_actions 的调试输出
+
+use log::{info, LevelFilter, Metadata};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::{LazyLock, Mutex, RwLock};
+
+use super::error::MxuError;
+use super::types::LogConfig;
+use super::utils::{get_app_data_dir, save_json_config};
+
+/// 配置文件读写锁
+static CONFIG_LOCK: Mutex<()> = Mutex::new(());
+
+/// 生效中的过滤规则
+static ACTIVE: LazyLock<RwLock<ActiveFilter>> =
+    LazyLock::new(|| RwLock::new(ActiveFilter::from_config(&LogConfig::default())));
+
+/// 解析后的过滤规则，模块按前缀长度降序排列
+struct ActiveFilter {
+    level: LevelFilter,
+    modules: Vec<(String, LevelFilter)>,
+}
+
+impl ActiveFilter {
+    fn from_config(config: &LogConfig) -> Self {
+        let mut modules: Vec<(String, LevelFilter)> = config
+            .module_filters
+            .iter()
+            .filter_map(|(module, level)| Some((module.clone(), parse_level(level).ok()?)))
+            .collect();
+        modules.sort_by(|a, b| b.0.len().cmp(&a.0.len()));
+        Self {
+            level: parse_level(&config.level).unwrap_or(LevelFilter::Debug),
+            modules,
+        }
+    }
+
+    fn max_level(&self) -> LevelFilter {
+        self.modules
+            .iter()
+            .map(|(_, level)| *level)
+            .fold(self.level, LevelFilter::max)
+    }
+
+    fn level_for(&self, target: &str) -> LevelFilter {
+        self.modules
+            .iter()
+            .find(|(module, _)| target_matches(target, module))
+            .map(|(_, level)| *level)
+            .unwrap_or(self.level)
+    }
+}
+
+/// target 与模块名相同或以 "模块名::" 开头
+fn target_matches(target: &str, module: &str) -> bool {
+    target == module
+        || target
+            .strip_prefix(module)
+            .is_some_and(|rest| rest.starts_with("::"))
+}
+
+fn parse_level(level: &str) -> Result<LevelFilter, String> {
+    LevelFilter::from_str(level.trim()).map_err(|_| format!("无效的日志级别: {}", level))
+}
+
+fn config_path() -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?.join("config").join("log_config.json"))
+}
+
+fn load_config() -> Result<LogConfig, String> {
+    let path = config_path()?;
+    if !path.exists() {
+        return Ok(LogConfig::default());
+    }
+    let content = std::fs::read_to_string(&path).map_err(|e| format!("无法读取日志配置: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("无法解析日志配置: {}", e))
+}
+
+fn save_config(config: &LogConfig) -> Result<(), String> {
+    let path = config_path()?;
+    Ok(save_json_config(&path, config)?)
+}
+
+fn apply(config: &LogConfig) {
+    let filter = ActiveFilter::from_config(config);
+    log::set_max_level(filter.max_level());
+    if let Ok(mut active) = ACTIVE.write() {
+        *active = filter;
+    }
+}
+
+/// 启动时加载持久化的日志配置（须在日志插件初始化前调用）
+pub fn init_log_config() {
+    let config = load_config().unwrap_or_default();
+    if let Ok(mut active) = ACTIVE.write() {
+        *active = ActiveFilter::from_config(&config);
+    }
+}
+
+/// 配置文件被外部修改后重新应用（配置无法解析时保持当前过滤）
+pub fn reload_log_config() -> Result<(), String> {
+    let config = load_config()?;
+    parse_level(&config.level)?;
+    for filter in config.module_filters.values() {
+        parse_level(filter)?;
+    }
+    apply(&config);
+    Ok(())
+}
+
+/// 日志插件初始化时使用的最大级别
+pub fn max_level() -> LevelFilter {
+    ACTIVE
+        .read()
+        .map(|active| active.max_level())
+        .unwrap_or(LevelFilter::Debug)
+}
+
+/// 日志插件过滤器：按当前配置判断记录是否输出
+pub fn is_enabled(metadata: &Metadata) -> bool {
+    ACTIVE
+        .read()
+        .map(|active| metadata.level() <= active.level_for(metadata.target()))
+        .unwrap_or(true)
+}
+
+/// 获取当前日志配置
+#[tauri::command]
+pub fn get_log_config() -> Result<LogConfig, MxuError> {
+    Ok(load_config()?)
+}
+
+/// 设置日志级别与模块过滤（立即生效并持久化）
+#[tauri::command]
+pub fn set_log_config(
+    level: String,
+    module_filters: Option<std::collections::HashMap<String, String>>,
+) -> Result<LogConfig, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+
+    parse_level(&level)?;
+    let module_filters = module_filters.unwrap_or_default();
+    for filter in module_filters.values() {
+        parse_level(filter)?;
+    }
+
+    let _guard = CONFIG_LOCK.lock().map_err(|e| e.to_string())?;
+    let config = LogConfig {
+        level: level.trim().to_lowercase(),
+        module_filters: module_filters
+            .into_iter()
+            .map(|(module, level)| (module.trim().to_string(), level.trim().to_lowercase()))
+            .filter(|(module, _)| !module.is_empty())
+            .collect(),
+    };
+    save_config(&config)?;
+    apply(&config);
+    info!(
+        "[log_config] Log level set to {} with {} module filter(s)",
+        config.level,
+        config.module_filters.len()
+    );
+    Ok(config)
+}
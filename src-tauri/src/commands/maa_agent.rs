@@ -1,24 +1,32 @@
 //! Agent 相关命令
 //!
-//! 提供 MaaFramework Agent 启动和管理功能
+//! 提供 MaaFramework Agent 启动和管理功能。
+//! Agent 子进程的输出写入各自的日志文件；监控线程定期检查子进程是否存活，
+//! 意外退出时发送 agent-crashed 事件并按退避间隔自动重启（次数上限见 AgentConfig.max_restarts），
+//! 超过上限后停止当前任务，避免任务因等待 Agent 而一直卡住
 
 use log::{debug, error, info, warn};
+use std::collections::HashSet;
 use std::fs::OpenOptions;
 use std::io::{BufRead, BufReader, Write};
 use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
+use std::time::Duration;
 
 use chrono::Local;
-use tauri::{Emitter, State};
+use tauri::{Emitter, Manager, State};
 
 use maa_framework::agent_client::AgentClient;
 use maa_framework::controller::Controller;
 use maa_framework::resource::Resource;
 use maa_framework::tasker::Tasker;
 
-use super::types::{AgentConfig, MaaState, TaskConfig};
-use super::utils::{emit_callback_event, get_logs_dir, normalize_path};
+use super::error::MxuError;
+use super::i18n::ErrorCode;
+use super::types::{AgentConfig, AgentLaunch, HookEvent, MaaState, QueueOptions, TaskConfig};
+use super::utils::{append_override, emit_callback_event, get_logs_dir, normalize_path};
 use regex::Regex;
 use std::sync::LazyLock;
 
@@ -30,6 +38,35 @@ pub struct AgentOutputEvent {
     pub line: String,
 }
 
+/// Agent 进程意外退出事件载荷
+#[derive(Clone, serde::Serialize)]
+pub struct AgentCrashedEvent {
+    pub instance_id: String,
+    pub agent_index: usize,
+    pub exit_code: Option<i32>,
+    /// 是否会自动重启
+    pub will_restart: bool,
+    /// 本次为第几次重启（从 1 开始）
+    pub attempt: u32,
+}
+
+/// Agent 重启完成事件载荷
+#[derive(Clone, serde::Serialize)]
+pub struct AgentRestartedEvent {
+    pub instance_id: String,
+    pub agent_index: usize,
+    pub attempt: u32,
+}
+
+/// 默认最大重启次数
+const DEFAULT_MAX_RESTARTS: u32 = 3;
+/// 监控检查间隔
+const SUPERVISE_INTERVAL: Duration = Duration::from_secs(1);
+/// 重启退避上限
+const MAX_RESTART_BACKOFF: Duration = Duration::from_secs(30);
+
+static NEXT_LAUNCH_ID: AtomicU64 = AtomicU64::new(1);
+
 /// 发送 Agent 输出事件
 fn emit_agent_output(app: &tauri::AppHandle, instance_id: &str, stream: &str, line: &str) {
     let event = AgentOutputEvent {
@@ -97,11 +134,6 @@ async fn start_single_agent(
         let joined = std::path::Path::new(&cwd).join(&agent.child_exec);
         let exec_path = normalize_path(&joined.to_string_lossy());
 
-        info!(
-            "[agent#{}] Spawning process: {:?} {:?} in {}",
-            agent_index, exec_path, args, cwd
-        );
-
         #[cfg(windows)]
         let mut cmd = {
             use std::os::windows::process::CommandExt;
@@ -114,8 +146,26 @@ async fn start_single_agent(
         #[cfg(not(windows))]
         let mut cmd = Command::new(&exec_path);
 
+        // 隔离设置须在设置其他环境变量之前应用
+        let work_dir = super::agent_sandbox::prepare(
+            &mut cmd,
+            &super::agent_sandbox::load_settings(),
+            &instance_id,
+            agent_index,
+            std::path::Path::new(&cwd),
+            &mut args,
+        )?;
+
+        info!(
+            "[agent#{}] Spawning process: {:?} {:?} in {}",
+            agent_index,
+            exec_path,
+            args,
+            work_dir.display()
+        );
+
         cmd.args(&args)
-            .current_dir(&cwd)
+            .current_dir(&work_dir)
             .env("PYTHONIOENCODING", "utf-8")
             .env("PYTHONUTF8", "1")
             .stdout(Stdio::piped())
@@ -127,6 +177,7 @@ async fn start_single_agent(
                 agent_index, e, exec_path
             )
         })?;
+        super::agent_sandbox::attach(&child);
 
         // 创建 agent 日志文件（多 agent、多实例时使用不同文件名，包含进程 PID）
         let pid = child.id();
@@ -210,8 +261,7 @@ async fn start_single_agent(
 
         if let Err(e) = client.connect() {
              error!("[agent#{}] Connection failed: {}", agent_index, e);
-             let _ = child.kill();
-             let _ = child.wait();
+             super::agent_sandbox::kill_tree(&mut child);
              return Err(e.to_string());
         }
 
@@ -220,8 +270,7 @@ async fn start_single_agent(
         // 注册 Agent sink
         if let Err(e) = client.register_sinks(resource, controller, tasker) {
             error!("[agent#{}] Failed to register sinks: {}", agent_index, e);
-            let _ = child.kill();
-            let _ = child.wait();
+            super::agent_sandbox::kill_tree(&mut child);
             return Err(e.to_string());
         }
 
@@ -230,6 +279,8 @@ async fn start_single_agent(
 }
 
 /// 启动任务（支持多个 Agent）
+/// 传入 queue_options 时使用任务队列引擎逐个执行任务（支持优先级、重试、任务间延迟），
+/// 此时返回空列表，任务进度通过 queue_state 命令和 task-queue-update 事件获取
 #[tauri::command]
 pub async fn maa_start_tasks(
     app: tauri::AppHandle,
@@ -239,13 +290,18 @@ pub async fn maa_start_tasks(
     agent_configs: Option<Vec<AgentConfig>>,
     cwd: String,
     tcp_compat_mode: bool,
-) -> Result<Vec<i64>, String> {
+    queue_options: Option<QueueOptions>,
+    profile_id: Option<String>,
+) -> Result<Vec<i64>, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
     info!("maa_start_tasks called");
 
     info!("instance_id: {}", instance_id);
     info!("tasks: {:?}", tasks);
     info!("agent_configs: {:?}", agent_configs);
     info!("cwd: {}, tcp_compat_mode: {}", cwd, tcp_compat_mode);
+    info!("queue_options: {:?}", queue_options);
+    info!("profile_id: {:?}", profile_id);
 
     let (resource, controller, tasker) = {
         debug!("[start_tasks] Acquiring instances lock...");
@@ -253,20 +309,20 @@ pub async fn maa_start_tasks(
         debug!("[start_tasks] Instances lock acquired");
         let instance = instances
             .get_mut(&instance_id)
-            .ok_or("Instance not found")?;
+            .ok_or_else(|| MxuError::new(ErrorCode::InstanceNotFound))?;
         debug!("[start_tasks] Instance found: {}", instance_id);
 
         let res = instance
             .resource
             .as_ref()
-            .ok_or("Resource not loaded")?
+            .ok_or_else(|| MxuError::new(ErrorCode::ResourceNotLoaded))?
             .clone();
         debug!("[start_tasks] Resource acquired");
 
         let ctrl = instance
             .controller
             .as_ref()
-            .ok_or("Controller not connected")?
+            .ok_or_else(|| MxuError::new(ErrorCode::ControllerNotConnected))?
             .clone();
         debug!("[start_tasks] Controller acquired");
 
@@ -285,11 +341,14 @@ pub async fn maa_start_tasks(
             .map_err(|e| e.to_string())?;
             debug!("[start_tasks] Tasker sink added");
 
-            // 添加 Context Sink，用于接收 Node 级别的通知（包含 focus 消息）
+            // 添加 Context Sink，用于接收 Node 级别的通知（包含 focus 消息）与识别订阅分发，并作为暂停检查点
             debug!("[start_tasks] Adding tasker context sink...");
             let app_handle = app.clone();
+            let sink_instance_id = instance_id.clone();
             t.add_context_sink(move |msg, detail| {
                 emit_callback_event(&app_handle, msg, detail);
+                super::recognition_feed::dispatch(&app_handle, &sink_instance_id, msg, detail);
+                super::pause_gate::checkpoint(&app_handle, &sink_instance_id, msg, detail);
             })
             .map_err(|e| e.to_string())?;
             debug!("[start_tasks] Tasker context sink added");
@@ -312,7 +371,18 @@ pub async fn maa_start_tasks(
     // 检查 Tasker 初始化状态
     if !tasker.inited() {
         error!("[start_tasks] Tasker not properly initialized");
-        return Err("Tasker not properly initialized".to_string());
+        return Err(MxuError::new(ErrorCode::TaskerNotCreated));
+    }
+
+    // 检查是否有其他自动化工具在控制同一设备，按策略拒绝或等待
+    {
+        let app = app.clone();
+        let instance_id = instance_id.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            super::device_lock::acquire_for_run(&app, &instance_id)
+        })
+        .await
+        .map_err(|e| e.to_string())??;
     }
 
     // 启动所有 Agent（如果配置了）
@@ -322,6 +392,12 @@ pub async fn maa_start_tasks(
             debug!("[start_tasks] Agent configs list is empty, skipping agent setup");
         } else {
             info!("[start_tasks] Starting {} agent(s)...", configs.len());
+            let taskbar_source = format!("agent:{}", instance_id);
+            crate::taskbar::set(
+                &taskbar_source,
+                crate::taskbar::TaskbarState::Indeterminate,
+                None,
+            );
 
             // 用于收集所有成功启动的 agent，失败时需要回滚清理
             let mut new_clients = Vec::new();
@@ -363,19 +439,35 @@ pub async fn maa_start_tasks(
                             let _ = client.disconnect();
                         }
                         for mut child in new_children {
-                            let _ = child.kill();
-                            let _ = child.wait();
+                            super::agent_sandbox::kill_tree(&mut child);
                         }
-                        return Err(format!("Agent start failed: {}", e));
+                        crate::taskbar::set(
+                            &taskbar_source,
+                            crate::taskbar::TaskbarState::Error,
+                            None,
+                        );
+                        return Err(MxuError::with_args(ErrorCode::AgentStartFailed, [e]));
                     }
                 }
             }
 
+            crate::taskbar::clear(&taskbar_source);
+
             // 保存所有 agent 状态到 instance
             let mut instances = state.instances.lock().map_err(|e| e.to_string())?;
             if let Some(instance) = instances.get_mut(&instance_id) {
+                let base = instance.agent_children.len();
                 instance.agent_clients.extend(new_clients);
                 instance.agent_children.extend(new_children);
+                instance.agent_launch = Some(AgentLaunch {
+                    id: NEXT_LAUNCH_ID.fetch_add(1, Ordering::Relaxed),
+                    configs: configs.clone(),
+                    cwd: cwd.clone(),
+                    tcp_compat_mode,
+                    base,
+                    restarts: vec![0; configs.len()],
+                    handled: HashSet::new(),
+                });
             }
 
             info!(
@@ -389,6 +481,102 @@ pub async fn maa_start_tasks(
         debug!("[start_tasks] No agent configs, skipping agent setup");
     };
 
+    // 预检游戏客户端是否需要更新，需要时跳过本次运行
+    {
+        let app = app.clone();
+        let instance_id = instance_id.clone();
+        let tasker = tasker.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            super::game_update_check::preflight(&app, &instance_id, &tasker)
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+    }
+
+    // 按包含条件解析本次运行的任务
+    let flags = super::queue_templates::profile_flags(profile_id.as_deref())?;
+    let resolution = super::queue_templates::resolve(tasks, &flags)?;
+    for task in &resolution.excluded {
+        info!(
+            "[start_tasks] Excluded task {} ({})",
+            task.entry, task.reason
+        );
+    }
+
+    // 应用选中的覆盖预设
+    let mut tasks = resolution.tasks;
+    super::override_presets::apply_selected_presets(&mut tasks);
+
+    // 启用颜色校准时映射 ColorMatch 颜色范围
+    if let Some(patch) = super::color_calibration::calibration_override(&state, &instance_id) {
+        for task in &mut tasks {
+            task.pipeline_override = append_override(&task.pipeline_override, patch.clone());
+        }
+    }
+
+    // 记录本次运行的 pipeline 快照
+    let run_id = match super::runs::create_run(&app, &instance_id, &tasks, &resolution.excluded) {
+        Ok(id) => Some(id),
+        Err(e) => {
+            warn!("[start_tasks] Failed to create run record: {}", e);
+            None
+        }
+    };
+
+    // 配置档案的任务钩子
+    let hooks = super::hooks::profile_hooks(profile_id.as_deref())?;
+
+    // 队列模式：由任务队列引擎在后台逐个提交
+    if let Some(mut options) = queue_options {
+        if options.max_duration_secs.is_none() {
+            if let Some(id) = &profile_id {
+                options.max_duration_secs = super::profiles::profile_get(id.clone())?
+                    .content
+                    .max_duration_secs;
+            }
+        }
+        super::task_queue::start_queue(
+            app.clone(),
+            state.inner().clone(),
+            instance_id.clone(),
+            tasker.clone(),
+            tasks,
+            options,
+            run_id,
+            hooks,
+        )?;
+        info!("[start_tasks] Tasks started in queue mode");
+        return Ok(Vec::new());
+    }
+
+    // 直接提交时只执行 before_queue 钩子
+    if !hooks.is_empty() {
+        let ctx = super::hooks::HookContext {
+            instance_id: instance_id.clone(),
+            run_id: run_id.clone(),
+            ..Default::default()
+        };
+        tauri::async_runtime::spawn_blocking(move || {
+            super::hooks::fire(&hooks, HookEvent::BeforeQueue, &ctx);
+            if hooks.iter().any(|h| h.event != HookEvent::BeforeQueue) {
+                info!("[start_tasks] Task and queue-end hooks require queue mode, skipped");
+            }
+        })
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+
+    if tasks.iter().any(|t| t.max_duration_secs.is_some()) {
+        info!("[start_tasks] Task timeouts require queue mode, ignored");
+    }
+    if profile_id
+        .as_ref()
+        .and_then(|id| super::profiles::profile_get(id.clone()).ok())
+        .is_some_and(|p| p.content.max_duration_secs.is_some())
+    {
+        info!("[start_tasks] Run duration limit requires queue mode, ignored");
+    }
+
     debug!("[start_tasks] Submitting {} tasks...", tasks.len());
     let mut task_ids = Vec::new();
     for (idx, task) in tasks.iter().enumerate() {
@@ -402,6 +590,9 @@ pub async fn maa_start_tasks(
             Ok(job) => {
                 info!("[start_tasks] post_task returned task_id: {}", job.id);
                 task_ids.push(job.id);
+                if let Some(run_id) = &run_id {
+                    super::runs::register_task(run_id, job.id);
+                }
                 debug!(
                     "[start_tasks] Task {} submitted successfully, task_id: {}",
                     idx, job.id
@@ -409,9 +600,15 @@ pub async fn maa_start_tasks(
             }
             Err(_e) => {
                 warn!("[start_tasks] Failed to post task: {}", task.entry);
+                if let Some(run_id) = &run_id {
+                    super::runs::run_log(run_id, &format!("Failed to post task: {}", task.entry));
+                }
             }
         }
     }
+    if let Some(run_id) = &run_id {
+        super::runs::seal_run(&app, run_id);
+    }
 
     debug!(
         "[start_tasks] All tasks submitted, total: {} task_ids",
@@ -438,16 +635,21 @@ pub async fn maa_start_tasks(
 /// 停止所有 Agent 并断开连接（异步执行，避免阻塞 UI）
 /// 不强制 kill 子进程，等待 MaaTaskerPostStop 触发子进程自行退出
 #[tauri::command]
-pub fn maa_stop_agent(state: State<'_, Arc<MaaState>>, instance_id: String) -> Result<(), String> {
+pub fn maa_stop_agent(
+    state: State<'_, Arc<MaaState>>,
+    instance_id: String,
+) -> Result<(), MxuError> {
     info!("maa_stop_agent called for instance: {}", instance_id);
 
     let (clients, children) = {
         let mut instances = state.instances.lock().map_err(|e| e.to_string())?;
         let instance = instances
             .get_mut(&instance_id)
-            .ok_or("Instance not found")?;
+            .ok_or_else(|| MxuError::new(ErrorCode::InstanceNotFound))?;
 
-        // 取出所有 agent clients 和 children，准备在后台线程清理
+        // 取出所有 agent clients 和 children，准备在后台线程清理；
+        // 同时结束监控，进行中的重启完成后会被丢弃
+        instance.agent_launch = None;
         (
             std::mem::take(&mut instance.agent_clients),
             std::mem::take(&mut instance.agent_children),
@@ -499,13 +701,256 @@ pub fn maa_stop_agent(state: State<'_, Arc<MaaState>>, instance_id: String) -> R
             // 超时未退出则强制 kill
             if !exited {
                 warn!("Agent process #{} did not exit in time, killing it...", i);
-                let _ = child.kill();
-                let _ = child.wait();
+                super::agent_sandbox::kill_tree(&mut child);
             } else {
                 info!("Background: Agent #{} child process exited", i);
+                // 结束 Agent 遗留的子进程
+                super::agent_sandbox::kill_tree(&mut child);
             }
         }
     });
 
     Ok(())
 }
+
+/// 崩溃待处理的 Agent
+struct CrashedAgent {
+    instance_id: String,
+    launch_id: u64,
+    agent_index: usize,
+    pid: u32,
+    exit_code: Option<i32>,
+    /// 本次重启序号，为 None 表示已达上限不再重启
+    attempt: Option<u32>,
+    config: AgentConfig,
+    cwd: String,
+    tcp_compat_mode: bool,
+}
+
+/// 启动 Agent 监控线程（setup 中调用）
+pub fn spawn_agent_supervisor(app: tauri::AppHandle) {
+    thread::spawn(move || loop {
+        thread::sleep(SUPERVISE_INTERVAL);
+        let state = app.state::<Arc<MaaState>>().inner().clone();
+        for crashed in collect_crashed(&state) {
+            handle_crash(&app, &state, crashed);
+        }
+    });
+}
+
+/// 检查所有实例的 Agent 子进程，返回新发现的意外退出
+fn collect_crashed(state: &MaaState) -> Vec<CrashedAgent> {
+    let Ok(mut instances) = state.instances.lock() else {
+        return Vec::new();
+    };
+    let mut crashed = Vec::new();
+    for (instance_id, instance) in instances.iter_mut() {
+        let Some(launch) = instance.agent_launch.as_mut() else {
+            continue;
+        };
+        for (agent_index, config) in launch.configs.iter().enumerate() {
+            if launch.handled.contains(&agent_index) {
+                continue;
+            }
+            let Some(child) = instance.agent_children.get_mut(launch.base + agent_index) else {
+                continue;
+            };
+            let status = match child.try_wait() {
+                Ok(Some(status)) => status,
+                Ok(None) => continue,
+                Err(e) => {
+                    warn!("[agent#{}] Failed to query process: {}", agent_index, e);
+                    continue;
+                }
+            };
+
+            launch.handled.insert(agent_index);
+            let max_restarts = config.max_restarts.unwrap_or(DEFAULT_MAX_RESTARTS);
+            let restarts = &mut launch.restarts[agent_index];
+            let attempt = if *restarts < max_restarts {
+                *restarts += 1;
+                Some(*restarts)
+            } else {
+                None
+            };
+            crashed.push(CrashedAgent {
+                instance_id: instance_id.clone(),
+                launch_id: launch.id,
+                agent_index,
+                pid: child.id(),
+                exit_code: status.code(),
+                attempt,
+                config: config.clone(),
+                cwd: launch.cwd.clone(),
+                tcp_compat_mode: launch.tcp_compat_mode,
+            });
+        }
+    }
+    crashed
+}
+
+/// 在 Agent 日志文件中追加一行监控记录
+fn append_agent_log(agent_index: usize, pid: u32, message: &str) {
+    let path = get_logs_dir().join(format!("mxu-agent-{}-{}.log", agent_index, pid));
+    if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(path) {
+        let timestamp = Local::now().format("%Y-%m-%d %H:%M:%S");
+        let _ = writeln!(file, "{} [supervisor] {}", timestamp, message);
+    }
+}
+
+fn handle_crash(app: &tauri::AppHandle, state: &Arc<MaaState>, crashed: CrashedAgent) {
+    let CrashedAgent {
+        instance_id,
+        agent_index,
+        attempt,
+        ..
+    } = &crashed;
+    error!(
+        "[agent#{}] Process {} of instance {} exited unexpectedly (code {:?})",
+        agent_index, crashed.pid, instance_id, crashed.exit_code
+    );
+    append_agent_log(
+        *agent_index,
+        crashed.pid,
+        &format!("process exited with code {:?}", crashed.exit_code),
+    );
+    let _ = app.emit(
+        "agent-crashed",
+        AgentCrashedEvent {
+            instance_id: instance_id.clone(),
+            agent_index: *agent_index,
+            exit_code: crashed.exit_code,
+            will_restart: attempt.is_some(),
+            attempt: attempt.unwrap_or(0),
+        },
+    );
+
+    let Some(attempt) = *attempt else {
+        // 不再重启：停止当前任务，避免等待 Agent 的任务一直卡住
+        warn!(
+            "[agent#{}] Restart limit reached, stopping tasks of instance {}",
+            agent_index, instance_id
+        );
+        if let Err(e) = super::maa_core::maa_stop_task(
+            app.clone(),
+            app.state::<Arc<MaaState>>(),
+            instance_id.clone(),
+            None,
+        ) {
+            warn!("[agent#{}] Failed to stop tasks: {}", agent_index, e);
+        }
+        return;
+    };
+
+    let app = app.clone();
+    let state = state.clone();
+    thread::spawn(move || restart_agent(&app, &state, crashed, attempt));
+}
+
+/// 按退避间隔重启 Agent，并替换实例中对应的 client 与子进程
+fn restart_agent(app: &tauri::AppHandle, state: &MaaState, crashed: CrashedAgent, attempt: u32) {
+    let CrashedAgent {
+        instance_id,
+        launch_id,
+        agent_index,
+        ..
+    } = crashed;
+    let backoff = Duration::from_secs(1 << attempt.min(5)).min(MAX_RESTART_BACKOFF);
+    info!(
+        "[agent#{}] Restarting in {:?} (attempt {})",
+        agent_index, backoff, attempt
+    );
+    thread::sleep(backoff);
+
+    // 重启时绑定当前的资源、控制器与 tasker
+    let handles = state.instances.lock().ok().and_then(|instances| {
+        let instance = instances.get(&instance_id)?;
+        if instance.agent_launch.as_ref().map(|l| l.id) != Some(launch_id) {
+            return None;
+        }
+        Some((
+            instance.resource.clone()?,
+            instance.controller.clone()?,
+            instance.tasker.clone()?,
+        ))
+    });
+    let Some((resource, controller, tasker)) = handles else {
+        info!("[agent#{}] Agent stopped, restart cancelled", agent_index);
+        return;
+    };
+
+    let result = tauri::async_runtime::block_on(start_single_agent(
+        app.clone(),
+        crashed.config,
+        agent_index,
+        instance_id.clone(),
+        crashed.cwd,
+        crashed.tcp_compat_mode,
+        resource,
+        controller,
+        tasker,
+    ));
+    let (client, mut child) = match result {
+        Ok(agent) => agent,
+        Err(e) => {
+            error!("[agent#{}] Restart failed: {}", agent_index, e);
+            // 交还给监控线程：旧进程仍为已退出状态，下一轮会再次计入重启次数
+            if let Ok(mut instances) = state.instances.lock() {
+                if let Some(launch) = instances
+                    .get_mut(&instance_id)
+                    .and_then(|i| i.agent_launch.as_mut())
+                    .filter(|l| l.id == launch_id)
+                {
+                    launch.handled.remove(&agent_index);
+                }
+            }
+            return;
+        }
+    };
+
+    let mut instances = match state.instances.lock() {
+        Ok(instances) => instances,
+        Err(e) => {
+            error!("[agent#{}] Failed to lock instances: {}", agent_index, e);
+            let _ = client.disconnect();
+            super::agent_sandbox::kill_tree(&mut child);
+            return;
+        }
+    };
+    let Some(instance) = instances
+        .get_mut(&instance_id)
+        .filter(|i| i.agent_launch.as_ref().map(|l| l.id) == Some(launch_id))
+    else {
+        // 重启期间 Agent 已被停止
+        drop(instances);
+        let _ = client.disconnect();
+        super::agent_sandbox::kill_tree(&mut child);
+        return;
+    };
+
+    let slot = instance.agent_launch.as_ref().map_or(0, |l| l.base) + agent_index;
+    if slot >= instance.agent_clients.len() || slot >= instance.agent_children.len() {
+        drop(instances);
+        let _ = client.disconnect();
+        super::agent_sandbox::kill_tree(&mut child);
+        return;
+    }
+    let old_client = std::mem::replace(&mut instance.agent_clients[slot], client);
+    let _ = old_client.disconnect();
+    let mut old_child = std::mem::replace(&mut instance.agent_children[slot], child);
+    super::agent_sandbox::kill_tree(&mut old_child);
+    if let Some(launch) = instance.agent_launch.as_mut() {
+        launch.handled.remove(&agent_index);
+    }
+    drop(instances);
+
+    info!("[agent#{}] Restarted (attempt {})", agent_index, attempt);
+    let _ = app.emit(
+        "agent-restarted",
+        AgentRestartedEvent {
+            instance_id,
+            agent_index,
+            attempt,
+        },
+    );
+}
@@ -14,9 +14,11 @@ use maa_framework::tasker::Tasker;
 use maa_framework::toolkit::Toolkit;
 use maa_framework::MaaStatus;
 
+use super::error::MxuError;
+use super::i18n::ErrorCode;
 use super::types::{
-    AdbDevice, ConnectionStatus, ControllerConfig, MaaState, TaskStatus, VersionCheckResult,
-    Win32Window,
+    AdbDevice, ConnectionStatus, ControllerConfig, MaaState, StopMode, TaskStatus,
+    VersionCheckResult, Win32Window,
 };
 use super::utils::{emit_callback_event, get_maafw_dir, normalize_path};
 
@@ -30,7 +32,7 @@ const MIN_MAAFW_VERSION: &str = "5.5.0-beta.1";
 /// 初始化 MaaFramework
 /// 如果提供 lib_dir 则使用该路径，否则自动从 exe 目录/maafw 加载
 #[tauri::command]
-pub fn maa_init(state: State<Arc<MaaState>>, lib_dir: Option<String>) -> Result<String, String> {
+pub fn maa_init(state: State<Arc<MaaState>>, lib_dir: Option<String>) -> Result<String, MxuError> {
     info!("maa_init called, lib_dir: {:?}", lib_dir);
 
     let lib_path = match lib_dir {
@@ -46,7 +48,7 @@ pub fn maa_init(state: State<Arc<MaaState>>, lib_dir: Option<String>) -> Result<
             lib_path.display()
         );
         error!("{}", err);
-        return Err(err);
+        return Err(err.into());
     }
 
     // Windows: 将 lib_dir 添加到 DLL 搜索路径，确保依赖 DLL 能被找到
@@ -102,12 +104,14 @@ pub fn maa_init(state: State<Arc<MaaState>>, lib_dir: Option<String>) -> Result<
         lib_path.join(name)
     };
 
-    match maa_framework::load_library(&dll_path) {
-        Ok(()) => info!("maa_init library loaded successfully"),
-        Err(e) if e.contains("already loaded") => {
-            info!("maa_init library already loaded, skipping");
-        }
-        Err(e) => return Err(e),
+    let loaded = super::system::load_maa_library(&dll_path)?;
+    if loaded == dll_path {
+        info!("maa_init library loaded from {:?}", loaded);
+    } else {
+        warn!(
+            "maa_init library already loaded from {:?}, ignoring {:?}",
+            loaded, dll_path
+        );
     }
 
     // 初始化 Toolkit
@@ -133,7 +137,7 @@ pub fn maa_init(state: State<Arc<MaaState>>, lib_dir: Option<String>) -> Result<
 pub fn maa_set_resource_dir(
     state: State<Arc<MaaState>>,
     resource_dir: String,
-) -> Result<(), String> {
+) -> Result<(), MxuError> {
     info!(
         "maa_set_resource_dir called, resource_dir: {}",
         resource_dir
@@ -146,17 +150,17 @@ pub fn maa_set_resource_dir(
 
 /// 获取 MaaFramework 版本
 #[tauri::command]
-pub fn maa_get_version() -> Result<String, String> {
+pub fn maa_get_version() -> Result<String, MxuError> {
     debug!("maa_get_version called");
     let version = std::panic::catch_unwind(|| maa_framework::maa_version().to_string())
-        .map_err(|_| "MaaFramework library not loaded".to_string())?;
+        .map_err(|_| MxuError::new(ErrorCode::MaaFrameworkNotInitialized))?;
     info!("maa_get_version result: {}", version);
     Ok(version)
 }
 
 /// 检查 MaaFramework 版本是否满足最小要求
 #[tauri::command]
-pub fn maa_check_version(state: State<Arc<MaaState>>) -> Result<VersionCheckResult, String> {
+pub fn maa_check_version(state: State<Arc<MaaState>>) -> Result<VersionCheckResult, MxuError> {
     debug!("maa_check_version called");
 
     let lib_dir = state.lib_dir.lock().map_err(|e| e.to_string())?.clone();
@@ -169,22 +173,20 @@ pub fn maa_check_version(state: State<Arc<MaaState>>) -> Result<VersionCheckResu
         #[cfg(target_os = "linux")]
         let dll_path = dir.join("libMaaFramework.so");
 
-        if let Err(e) = maa_framework::load_library(&dll_path) {
-            if !e.contains("already loaded") {
-                error!(
-                    "Failed to load MaaFramework library from {:?}: {:?}",
-                    dll_path, e
-                );
-                return Err(format!("MaaFramework library failed to load: {}", e));
-            }
+        if let Err(e) = super::system::load_maa_library(&dll_path) {
+            error!(
+                "Failed to load MaaFramework library from {:?}: {}",
+                dll_path, e
+            );
+            return Err(e);
         }
     }
 
     let current_str = std::panic::catch_unwind(|| maa_framework::maa_version().to_string())
-        .map_err(|_| "MaaFramework library not loaded (panic in maa_version)".to_string())?;
+        .map_err(|_| MxuError::new(ErrorCode::MaaFrameworkNotInitialized))?;
 
     if current_str == "unknown" || current_str.is_empty() {
-        return Err("MaaFramework not initialized".to_string());
+        return Err(MxuError::new(ErrorCode::MaaFrameworkNotInitialized));
     }
 
     // 去掉版本号前缀 'v'（如 "v5.5.0-beta.1" -> "5.5.0-beta.1"）
@@ -213,7 +215,7 @@ pub fn maa_check_version(state: State<Arc<MaaState>>) -> Result<VersionCheckResu
 #[tauri::command]
 pub async fn maa_find_adb_devices(
     state: State<'_, Arc<MaaState>>,
-) -> Result<Vec<AdbDevice>, String> {
+) -> Result<Vec<AdbDevice>, MxuError> {
     info!("maa_find_adb_devices called");
 
     let state_arc = state.inner().clone();
@@ -251,7 +253,7 @@ pub async fn maa_find_win32_windows(
     state: State<'_, Arc<MaaState>>,
     class_regex: Option<String>,
     window_regex: Option<String>,
-) -> Result<Vec<Win32Window>, String> {
+) -> Result<Vec<Win32Window>, MxuError> {
     info!(
         "maa_find_win32_windows called, class_regex: {:?}, window_regex: {:?}",
         class_regex, window_regex
@@ -312,7 +314,10 @@ pub async fn maa_find_win32_windows(
 
 /// 创建实例（幂等操作，实例已存在时直接返回成功）
 #[tauri::command]
-pub fn maa_create_instance(state: State<Arc<MaaState>>, instance_id: String) -> Result<(), String> {
+pub fn maa_create_instance(
+    state: State<Arc<MaaState>>,
+    instance_id: String,
+) -> Result<(), MxuError> {
     info!("maa_create_instance called, instance_id: {}", instance_id);
 
     let mut instances = state.instances.lock().map_err(|e| e.to_string())?;
@@ -335,7 +340,7 @@ pub fn maa_create_instance(state: State<Arc<MaaState>>, instance_id: String) ->
 pub fn maa_destroy_instance(
     state: State<Arc<MaaState>>,
     instance_id: String,
-) -> Result<(), String> {
+) -> Result<(), MxuError> {
     info!("maa_destroy_instance called, instance_id: {}", instance_id);
 
     let mut instances = state.instances.lock().map_err(|e| e.to_string())?;
@@ -364,15 +369,38 @@ pub async fn maa_connect_controller(
     state: State<'_, Arc<MaaState>>,
     instance_id: String,
     config: ControllerConfig,
-) -> Result<i64, String> {
+) -> Result<i64, MxuError> {
     info!(
         "maa_connect_controller called, instance_id: {}",
         instance_id
     );
 
+    // 窗口已关闭时直接报告设备不存在，不必等待连接超时
+    #[cfg(windows)]
+    if let ControllerConfig::Win32 { handle, .. } | ControllerConfig::Gamepad { handle, .. } =
+        &config
+    {
+        use windows::Win32::Foundation::HWND;
+        use windows::Win32::UI::WindowsAndMessaging::IsWindow;
+        if !unsafe { IsWindow(HWND(*handle as *mut std::ffi::c_void)) }.as_bool() {
+            return Err(MxuError::with_args(
+                ErrorCode::DeviceNotFound,
+                [format!("{:#x}", handle)],
+            ));
+        }
+    }
+
     let state_arc = state.inner().clone();
     let app_handle = app.clone();
 
+    // 记录为上次连接的设备，供启动动作自动重连
+    super::startup_actions::remember_last_device(&instance_id, &config);
+    // 记录目标进程，用于资源占用监控
+    super::monitor::register_target(&instance_id, &config);
+    super::idle_policy::note_controller(&instance_id, &config);
+    super::device_lock::note_controller(&instance_id, &config);
+    super::adb_tuning::note_controller(&instance_id, &config);
+
     // Move blocking controller creation and connection to spawn_blocking
     tauri::async_runtime::spawn_blocking(move || {
         let controller = match &config {
@@ -383,13 +411,17 @@ pub async fn maa_connect_controller(
                 input_methods,
                 config,
             } => {
-                // 将字符串解析为 u64
-                let screencap = screencap_methods.parse::<u64>().map_err(|e| {
-                    format!("Invalid screencap_methods '{}': {}", screencap_methods, e)
-                })?;
-                let input = input_methods
-                    .parse::<u64>()
-                    .map_err(|e| format!("Invalid input_methods '{}': {}", input_methods, e))?;
+                // 将字符串解析为 u64（数字或方式名称）
+                let screencap = super::adb_tuning::parse_methods(
+                    super::adb_tuning::SCREENCAP_METHODS,
+                    screencap_methods,
+                )
+                .map_err(|e| format!("Invalid screencap_methods '{}': {}", screencap_methods, e))?;
+                let input = super::adb_tuning::parse_methods(
+                    super::adb_tuning::INPUT_METHODS,
+                    input_methods,
+                )
+                .map_err(|e| format!("Invalid input_methods '{}': {}", input_methods, e))?;
                 let agent_path = get_maafw_dir()
                     .map(|p| p.join("MaaAgentBinary").to_string_lossy().to_string())
                     .unwrap_or_else(|_| "./MaaAgentBinary".to_string());
@@ -431,6 +463,39 @@ pub async fn maa_connect_controller(
                 let uuid_str = uuid.as_deref().unwrap_or("");
                 Controller::new_playcover(address, uuid_str).map_err(|e| e.to_string())?
             }
+            ControllerConfig::Mock {
+                image_dir,
+                record_dir,
+            } => {
+                let image_dir = normalize_path(image_dir);
+                let has_images = std::fs::read_dir(&image_dir)
+                    .map_err(|e| format!("无法读取截图目录 [{}]: {}", image_dir.display(), e))?
+                    .flatten()
+                    .any(|e| {
+                        let name = e.file_name().to_string_lossy().to_lowercase();
+                        name.ends_with(".png") || name.ends_with(".jpg") || name.ends_with(".jpeg")
+                    });
+                if !has_images {
+                    return Err(MxuError::with_args(
+                        ErrorCode::NoImagesInDirectory,
+                        [image_dir.display()],
+                    ));
+                }
+                let record_dir = match record_dir {
+                    Some(dir) => normalize_path(dir),
+                    None => crate::commands::utils::get_app_data_dir()?
+                        .join("debug")
+                        .join("mock_controller"),
+                };
+                let _ = std::fs::create_dir_all(&record_dir);
+                Controller::new_dbg(
+                    &image_dir.to_string_lossy(),
+                    &record_dir.to_string_lossy(),
+                    maa_framework::common::DbgControllerType::CarouselImage,
+                    "{}",
+                )
+                .map_err(|e| e.to_string())?
+            }
             ControllerConfig::Gamepad {
                 handle,
                 gamepad_type,
@@ -474,12 +539,20 @@ pub async fn maa_connect_controller(
             let mut instances = state_arc.instances.lock().map_err(|e| e.to_string())?;
             let instance = instances
                 .get_mut(&instance_id)
-                .ok_or("Instance not found")?;
+                .ok_or_else(|| MxuError::new(ErrorCode::InstanceNotFound))?;
 
-            instance.controller = Some(controller);
+            instance.controller = Some(controller.clone());
             instance.tasker = None;
         }
 
+        // 连接完成后检查设备分辨率是否与资源匹配
+        super::resolution_check::spawn_after_connect(
+            app_handle.clone(),
+            instance_id.clone(),
+            controller,
+            super::resolution_check::controller_kind(&config),
+        );
+
         Ok(conn_id)
     })
     .await
@@ -491,9 +564,11 @@ pub async fn maa_connect_controller(
 pub fn maa_get_connection_status(
     state: State<Arc<MaaState>>,
     instance_id: String,
-) -> Result<ConnectionStatus, String> {
+) -> Result<ConnectionStatus, MxuError> {
     let instances = state.instances.lock().map_err(|e| e.to_string())?;
-    let instance = instances.get(&instance_id).ok_or("Instance not found")?;
+    let instance = instances
+        .get(&instance_id)
+        .ok_or_else(|| MxuError::new(ErrorCode::InstanceNotFound))?;
 
     if instance.controller.as_ref().is_some_and(|c| c.connected()) {
         Ok(ConnectionStatus::Connected)
@@ -514,21 +589,37 @@ pub fn maa_load_resource(
     state: State<Arc<MaaState>>,
     instance_id: String,
     paths: Vec<String>,
-) -> Result<Vec<i64>, String> {
+) -> Result<Vec<i64>, MxuError> {
     info!(
         "maa_load_resource called, instance: {}, paths: {:?}",
         instance_id, paths
     );
 
+    Ok(load_resource_bundles(&app, &state, &instance_id, paths)?)
+}
+
+/// 创建（如需要）资源并提交资源包加载，返回资源加载请求 ID 列表
+pub fn load_resource_bundles(
+    app: &tauri::AppHandle,
+    state: &MaaState,
+    instance_id: &str,
+    paths: Vec<String>,
+) -> Result<Vec<i64>, String> {
+    // 记录资源包路径，用于按资源包查找游戏更新检测配置
+    super::game_update_check::note_resource_paths(instance_id, &paths);
+
     let mut instances = state.instances.lock().map_err(|e| e.to_string())?;
     let instance = instances
-        .get_mut(&instance_id)
-        .ok_or("Instance not found")?;
+        .get_mut(instance_id)
+        .ok_or_else(|| MxuError::new(ErrorCode::InstanceNotFound))?;
 
     // 创建或获取资源
     if instance.resource.is_none() {
         let res = Resource::new().map_err(|e| e.to_string())?;
 
+        // 应用推理后端选项（需在加载资源包前设置）
+        super::inference::apply_to_resource(&res);
+
         // 注册回调
         let app_handle = app.clone();
         res.add_sink(move |msg, detail| {
@@ -536,10 +627,13 @@ pub fn maa_load_resource(
         })
         .map_err(|e| e.to_string())?;
 
-        // 注册 MXU Custom Actions
+        // 注册 MXU Custom Actions 与 Custom Recognitions
         if let Err(e) = crate::mxu_actions::register_all_mxu_actions(&res) {
             warn!("Failed to register MXU custom actions: {}", e);
         }
+        if let Err(e) = crate::mxu_recognizers::register_all_mxu_recognizers(&res) {
+            warn!("Failed to register MXU custom recognitions: {}", e);
+        }
 
         instance.resource = Some(res);
     }
@@ -547,6 +641,10 @@ pub fn maa_load_resource(
     let resource = instance.resource.as_ref().unwrap();
     let mut res_ids = Vec::new();
 
+    // 在项目资源之后追加已启用的资源包（后加载的覆盖先加载的）
+    let mut paths = paths;
+    paths.extend(super::resource_manager::enabled_pack_paths());
+
     for path in paths {
         let normalized = normalize_path(&path).to_string_lossy().to_string();
         match resource.post_bundle(&normalized) {
@@ -568,9 +666,11 @@ pub fn maa_load_resource(
 pub fn maa_is_resource_loaded(
     state: State<Arc<MaaState>>,
     instance_id: String,
-) -> Result<bool, String> {
+) -> Result<bool, MxuError> {
     let instances = state.instances.lock().map_err(|e| e.to_string())?;
-    let instance = instances.get(&instance_id).ok_or("Instance not found")?;
+    let instance = instances
+        .get(&instance_id)
+        .ok_or_else(|| MxuError::new(ErrorCode::InstanceNotFound))?;
 
     Ok(instance.resource.as_ref().is_some_and(|r| r.loaded()))
 }
@@ -580,11 +680,11 @@ pub fn maa_is_resource_loaded(
 pub fn maa_destroy_resource(
     state: State<Arc<MaaState>>,
     instance_id: String,
-) -> Result<(), String> {
+) -> Result<(), MxuError> {
     let mut instances = state.instances.lock().map_err(|e| e.to_string())?;
     let instance = instances
         .get_mut(&instance_id)
-        .ok_or("Instance not found")?;
+        .ok_or_else(|| MxuError::new(ErrorCode::InstanceNotFound))?;
 
     // 销毁旧的资源
     instance.resource = None;
@@ -606,19 +706,23 @@ pub fn maa_run_task(
     instance_id: String,
     entry: String,
     pipeline_override: String,
-) -> Result<i64, String> {
+) -> Result<i64, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
     info!("maa_run_task called, entry: {}", entry);
 
     let mut instances = state.instances.lock().map_err(|e| e.to_string())?;
     let instance = instances
         .get_mut(&instance_id)
-        .ok_or("Instance not found")?;
+        .ok_or_else(|| MxuError::new(ErrorCode::InstanceNotFound))?;
 
-    let resource = instance.resource.as_ref().ok_or("Resource not loaded")?;
+    let resource = instance
+        .resource
+        .as_ref()
+        .ok_or_else(|| MxuError::new(ErrorCode::ResourceNotLoaded))?;
     let controller = instance
         .controller
         .as_ref()
-        .ok_or("Controller not connected")?;
+        .ok_or_else(|| MxuError::new(ErrorCode::ControllerNotConnected))?;
 
     // 创建或获取 tasker
     if instance.tasker.is_none() {
@@ -632,11 +736,14 @@ pub fn maa_run_task(
             })
             .map_err(|e| e.to_string())?;
 
-        // 添加 Context Sink，用于接收 Node 级别的通知（包含 focus 消息）
+        // 添加 Context Sink，用于接收 Node 级别的通知（包含 focus 消息）与识别订阅分发，并作为暂停检查点
         let app_handle = app.clone();
+        let sink_instance_id = instance_id.clone();
         tasker
             .add_context_sink(move |msg, detail| {
                 emit_callback_event(&app_handle, msg, detail);
+                super::recognition_feed::dispatch(&app_handle, &sink_instance_id, msg, detail);
+                super::pause_gate::checkpoint(&app_handle, &sink_instance_id, msg, detail);
             })
             .map_err(|e| e.to_string())?;
 
@@ -652,7 +759,7 @@ pub fn maa_run_task(
 
     // 检查初始化状态
     if !tasker.inited() {
-        return Err("Tasker not initialized".to_string());
+        return Err(MxuError::new(ErrorCode::TaskerNotCreated).into());
     }
 
     let job = tasker
@@ -671,16 +778,23 @@ pub fn maa_get_task_status(
     state: State<Arc<MaaState>>,
     instance_id: String,
     task_id: i64,
-) -> Result<TaskStatus, String> {
-    let instances = state.instances.lock().map_err(|e| e.to_string())?;
-    let instance = instances.get(&instance_id).ok_or("Instance not found")?;
-    let tasker = instance.tasker.as_ref().ok_or("Tasker not created")?;
+) -> Result<TaskStatus, MxuError> {
+    let tasker = {
+        let instances = state.instances.lock().map_err(|e| e.to_string())?;
+        let instance = instances
+            .get(&instance_id)
+            .ok_or_else(|| MxuError::new(ErrorCode::InstanceNotFound))?;
+        instance
+            .tasker
+            .clone()
+            .ok_or_else(|| MxuError::new(ErrorCode::TaskerNotCreated))?
+    };
 
-    let status = tasker
-        .get_task_detail(task_id)
-        .map_err(|e| e.to_string())?
-        .map(|d| d.status)
-        .unwrap_or(MaaStatus::INVALID);
+    let status = super::ffi_guard::call(&instance_id, "get_task_detail", move || {
+        tasker.get_task_detail(task_id).map_err(|e| e.to_string())
+    })??
+    .map(|d| d.status)
+    .unwrap_or(MaaStatus::INVALID);
 
     let result = match status {
         MaaStatus::PENDING => TaskStatus::Pending,
@@ -693,36 +807,81 @@ pub fn maa_get_task_status(
 }
 
 /// 停止任务
+///
+/// mode 为停止方式（默认 soft），超时未停止时自动升级，进度通过 stop-progress 事件通知
 #[tauri::command]
-pub fn maa_stop_task(state: State<Arc<MaaState>>, instance_id: String) -> Result<(), String> {
-    let mut instances = state.instances.lock().map_err(|e| e.to_string())?;
-    let instance = instances
-        .get_mut(&instance_id)
-        .ok_or("Instance not found")?;
-    let tasker = instance.tasker.as_ref().ok_or("Tasker not created")?;
-
-    if instance.stop_in_progress {
-        if !tasker.running() {
-            instance.stop_in_progress = false;
-            instance.stop_started_at = None;
-            return Ok(());
-        }
-        let elapsed = instance
-            .stop_started_at
-            .map(|t| t.elapsed())
-            .unwrap_or(Duration::from_secs(0));
-        if elapsed < Duration::from_millis(500) {
-            return Ok(());
-        }
+pub fn maa_stop_task(
+    app: tauri::AppHandle,
+    state: State<Arc<MaaState>>,
+    instance_id: String,
+    mode: Option<StopMode>,
+) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    super::ffi_guard::ensure_healthy(&instance_id)?;
+    let mut mode = mode.unwrap_or_default();
+    // graceful 依赖队列在任务之间停止，直接提交的任务按 soft 处理
+    if mode == StopMode::Graceful && !super::stop_control::queue_active(&state, &instance_id) {
+        info!("[stop_task] No active queue, graceful stop falls back to soft");
+        mode = StopMode::Soft;
     }
+    let tasker = {
+        let mut instances = state.instances.lock().map_err(|e| e.to_string())?;
+        let instance = instances
+            .get_mut(&instance_id)
+            .ok_or_else(|| MxuError::new(ErrorCode::InstanceNotFound))?;
+        let tasker = instance
+            .tasker
+            .clone()
+            .ok_or_else(|| MxuError::new(ErrorCode::TaskerNotCreated))?;
+
+        if instance.stop_in_progress {
+            if !tasker.running() {
+                instance.stop_in_progress = false;
+                instance.stop_started_at = None;
+                return Ok(());
+            }
+            let elapsed = instance
+                .stop_started_at
+                .map(|t| t.elapsed())
+                .unwrap_or(Duration::from_secs(0));
+            // hard 停止不受重复请求限制
+            if elapsed < Duration::from_millis(500) && mode != StopMode::Hard {
+                return Ok(());
+            }
+        }
 
-    instance.stop_in_progress = true;
-    instance.stop_started_at = Some(Instant::now());
-    // 清空缓存的 task_ids
-    instance.task_ids.clear();
+        // 取消等待其他工具释放设备
+        super::device_lock::cancel_wait(&instance_id);
+        // 解除暂停，否则 Tasker 线程无法响应停止
+        super::pause_gate::release(&instance_id);
+
+        if mode != StopMode::Graceful {
+            // 队列模式下停止提交后续任务
+            super::task_queue::cancel_queue(&state, &instance_id);
+            instance.stop_in_progress = true;
+            instance.stop_started_at = Some(Instant::now());
+            // 清空缓存的 task_ids
+            instance.task_ids.clear();
+        }
+        tasker
+    };
 
-    tasker.post_stop().map_err(|e| e.to_string())?;
-    Ok(())
+    let result = match mode {
+        StopMode::Graceful => {
+            super::task_queue::request_stop_after_current(&app, &state, Some(&instance_id), true)
+                .map(|_| ())
+        }
+        StopMode::Soft => {
+            // 在实例锁外发起停止，原生调用卡住时不阻塞其他命令
+            super::stop_control::post_stop(&instance_id, tasker.clone())
+        }
+        StopMode::Hard => {
+            super::stop_control::kill_agents(&state, &instance_id);
+            super::stop_control::post_stop(&instance_id, tasker.clone())
+        }
+    };
+    super::stop_control::track(app, state.inner().clone(), instance_id, tasker, mode);
+    Ok(result?)
 }
 
 /// 覆盖已提交任务的 Pipeline 配置（用于运行中修改尚未执行的任务选项）
@@ -732,40 +891,76 @@ pub fn maa_override_pipeline(
     instance_id: String,
     task_id: i64,
     pipeline_override: String,
-) -> Result<bool, String> {
+) -> Result<bool, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
     let instances = state.instances.lock().map_err(|e| e.to_string())?;
-    let instance = instances.get(&instance_id).ok_or("Instance not found")?;
-    let tasker = instance.tasker.as_ref().ok_or("Tasker not created")?;
+    let instance = instances
+        .get(&instance_id)
+        .ok_or_else(|| MxuError::new(ErrorCode::InstanceNotFound))?;
+    let tasker = instance
+        .tasker
+        .as_ref()
+        .ok_or_else(|| MxuError::new(ErrorCode::TaskerNotCreated))?;
 
-    tasker
+    let applied = tasker
         .override_pipeline(task_id, &pipeline_override)
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+    if applied {
+        super::runs::record_late_override(&instance_id, task_id, &pipeline_override);
+    }
+    Ok(applied)
 }
 
 /// 检查是否正在运行
 #[tauri::command]
-pub fn maa_is_running(state: State<Arc<MaaState>>, instance_id: String) -> Result<bool, String> {
-    let instances = state.instances.lock().map_err(|e| e.to_string())?;
-    let instance = instances.get(&instance_id).ok_or("Instance not found")?;
+pub fn maa_is_running(state: State<Arc<MaaState>>, instance_id: String) -> Result<bool, MxuError> {
+    let tasker = {
+        let instances = state.instances.lock().map_err(|e| e.to_string())?;
+        let instance = instances
+            .get(&instance_id)
+            .ok_or_else(|| MxuError::new(ErrorCode::InstanceNotFound))?;
+        instance.tasker.clone()
+    };
+    let Some(tasker) = tasker else {
+        return Ok(false);
+    };
 
-    Ok(instance.tasker.as_ref().is_some_and(|t| t.running()))
+    Ok(super::ffi_guard::call(
+        &instance_id,
+        "running",
+        move || tasker.running(),
+    )?)
 }
 
 // ============================================================================
 // 截图命令
 // ============================================================================
 
-/// 发起截图请求
-#[tauri::command]
-pub fn maa_post_screencap(state: State<Arc<MaaState>>, instance_id: String) -> Result<i64, String> {
+/// 取出已连接的控制器（克隆句柄后立即释放实例锁）
+pub fn connected_controller(state: &MaaState, instance_id: &str) -> Result<Controller, String> {
     let instances = state.instances.lock().map_err(|e| e.to_string())?;
-    let instance = instances.get(&instance_id).ok_or("Instance not found")?;
-    let controller = instance
+    let instance = instances
+        .get(instance_id)
+        .ok_or_else(|| MxuError::new(ErrorCode::InstanceNotFound))?;
+    instance
         .controller
-        .as_ref()
-        .ok_or("Controller not connected")?;
+        .clone()
+        .ok_or_else(|| MxuError::new(ErrorCode::ControllerNotConnected).into())
+}
 
-    controller.post_screencap().map_err(|e| e.to_string())
+/// 发起截图请求
+#[tauri::command]
+pub fn maa_post_screencap(
+    state: State<Arc<MaaState>>,
+    instance_id: String,
+) -> Result<i64, MxuError> {
+    let controller = connected_controller(&state, &instance_id)?;
+
+    Ok(super::ffi_guard::call(
+        &instance_id,
+        "post_screencap",
+        move || controller.post_screencap().map_err(|e| e.to_string()),
+    )??)
 }
 
 /// 获取缓存的截图（返回 base64 编码的 PNG 图像）
@@ -773,21 +968,18 @@ pub fn maa_post_screencap(state: State<Arc<MaaState>>, instance_id: String) -> R
 pub fn maa_get_cached_image(
     state: State<Arc<MaaState>>,
     instance_id: String,
-) -> Result<String, String> {
-    let instances = state.instances.lock().map_err(|e| e.to_string())?;
-    let instance = instances.get(&instance_id).ok_or("Instance not found")?;
-    let controller = instance
-        .controller
-        .as_ref()
-        .ok_or("Controller not connected")?;
+) -> Result<String, MxuError> {
+    let controller = connected_controller(&state, &instance_id)?;
 
-    let buffer = controller.cached_image().map_err(|e| e.to_string())?;
-    let data = buffer
-        .to_vec()
-        .ok_or("Failed to convert image buffer".to_string())?;
+    let data = super::ffi_guard::call(&instance_id, "cached_image", move || {
+        let buffer = controller.cached_image().map_err(|e| e.to_string())?;
+        buffer
+            .to_vec()
+            .ok_or("Failed to convert image buffer".to_string())
+    })??;
 
     if data.is_empty() {
-        return Err("No image data available".to_string());
+        return Err(MxuError::new(ErrorCode::NoImageData));
     }
 
     // 复制数据并转换为 base64
@@ -0,0 +1,327 @@
+//! MaaFramework 多版本管理
+//!
+//! 随程序分发的版本位于 exe 目录下的 maafw，额外安装的版本位于 maafw_versions/<版本名>，
+//! 当前使用的版本记录在 maafw_versions/active.json，get_maafw_dir 据此解析库目录。
+//! MaaFramework 库加载后无法在进程内替换，此时切换版本需要重启 MXU 才能生效。
+//! 配置档案可固定使用某个版本（ProfileContent::maafw_version），避免新版本破坏已有资源
+
+use log::{info, warn};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use tauri::{AppHandle, State};
+
+use super::error::MxuError;
+use super::i18n::ErrorCode;
+use super::types::{
+    MaaState, MaafwActiveVersion, MaafwSwitchResult, MaafwVersionInfo, MaafwVersionList,
+};
+use super::update::{ensure_idle, extract_zip};
+use super::utils::{get_app_data_dir, get_exe_directory, get_maafw_dir, path_size};
+
+/// 额外安装的版本所在目录（相对于 exe 目录）
+pub const MAAFW_VERSIONS_DIR: &str = "maafw_versions";
+/// 当前版本记录文件
+const ACTIVE_FILE: &str = "active.json";
+
+/// 当前平台的 MaaFramework 库文件名
+fn library_file_name() -> &'static str {
+    #[cfg(windows)]
+    {
+        "MaaFramework.dll"
+    }
+    #[cfg(target_os = "macos")]
+    {
+        "libMaaFramework.dylib"
+    }
+    #[cfg(target_os = "linux")]
+    {
+        "libMaaFramework.so"
+    }
+}
+
+/// 校验版本名，只允许作为单层目录名的安全字符
+fn validate_version_name(name: &str) -> Result<&str, MxuError> {
+    let name = name.trim();
+    if name.is_empty()
+        || name.starts_with('.')
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '+'))
+    {
+        return Err(MxuError::with_args(
+            ErrorCode::MaaFrameworkVersionInvalid,
+            [name],
+        ));
+    }
+    Ok(name)
+}
+
+/// 读取 active.json 中记录的版本（未选择或文件无效时为 None，即使用随程序分发的版本）
+pub fn active_version(exe_dir: &Path) -> Option<String> {
+    let path = exe_dir.join(MAAFW_VERSIONS_DIR).join(ACTIVE_FILE);
+    let content = std::fs::read_to_string(path).ok()?;
+    let active: MaafwActiveVersion = serde_json::from_str(&content).ok()?;
+    active.version.filter(|v| validate_version_name(v).is_ok())
+}
+
+fn write_active_version(exe_dir: &Path, version: Option<&str>) -> Result<(), String> {
+    let dir = exe_dir.join(MAAFW_VERSIONS_DIR);
+    let path = dir.join(ACTIVE_FILE);
+    match version {
+        Some(version) => {
+            std::fs::create_dir_all(&dir)
+                .map_err(|e| format!("无法创建版本目录 [{}]: {}", dir.display(), e))?;
+            let active = MaafwActiveVersion {
+                version: Some(version.to_string()),
+            };
+            let content = serde_json::to_string_pretty(&active).map_err(|e| e.to_string())?;
+            std::fs::write(&path, content).map_err(|e| format!("无法写入版本记录: {}", e))
+        }
+        None if path.exists() => {
+            std::fs::remove_file(&path).map_err(|e| format!("无法删除版本记录: {}", e))
+        }
+        None => Ok(()),
+    }
+}
+
+/// 版本名对应的库目录（None 为随程序分发的 maafw）
+fn version_dir(exe_dir: &Path, version: Option<&str>) -> Result<PathBuf, String> {
+    match version {
+        Some(version) => Ok(exe_dir
+            .join(MAAFW_VERSIONS_DIR)
+            .join(validate_version_name(version)?)),
+        None => Ok(exe_dir.join("maafw")),
+    }
+}
+
+/// 已加载的 MaaFramework 版本
+fn loaded_version() -> Option<String> {
+    std::panic::catch_unwind(|| maa_framework::maa_version().to_string())
+        .ok()
+        .filter(|v| !v.is_empty() && v != "unknown")
+}
+
+/// 在解压目录中查找包含库文件的目录（发布包中通常位于 bin 子目录）
+fn find_library_dir(dir: &Path, depth: u32) -> Option<PathBuf> {
+    if dir.join(library_file_name()).is_file() {
+        return Some(dir.to_path_buf());
+    }
+    if depth == 0 {
+        return None;
+    }
+    std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_dir())
+        .find_map(|e| find_library_dir(&e.path(), depth - 1))
+}
+
+/// 列出已安装的 MaaFramework 版本
+#[tauri::command]
+pub fn maafw_version_list() -> Result<MaafwVersionList, MxuError> {
+    let exe_dir = get_exe_directory()?;
+    let active = active_version(&exe_dir);
+
+    let mut versions = Vec::new();
+    let bundled = exe_dir.join("maafw");
+    if bundled.is_dir() {
+        versions.push(MaafwVersionInfo {
+            name: None,
+            path: bundled.to_string_lossy().to_string(),
+            size: path_size(&bundled),
+            active: active.is_none(),
+        });
+    }
+
+    let mut installed: Vec<MaafwVersionInfo> = std::fs::read_dir(exe_dir.join(MAAFW_VERSIONS_DIR))
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.join(library_file_name()).is_file())
+                .filter_map(|p| {
+                    let name = p.file_name()?.to_string_lossy().to_string();
+                    validate_version_name(&name).ok()?;
+                    Some(MaafwVersionInfo {
+                        active: active.as_deref() == Some(name.as_str()),
+                        name: Some(name),
+                        path: p.to_string_lossy().to_string(),
+                        size: path_size(&p),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    installed.sort_by(|a, b| a.name.cmp(&b.name));
+    versions.extend(installed);
+
+    Ok(MaafwVersionList {
+        active,
+        loaded_version: loaded_version(),
+        versions,
+    })
+}
+
+/// 下载并安装指定版本的 MaaFramework 发布包（zip / tar.gz）到 maafw_versions/<version>
+/// sha256: 可选的发布包 SHA-256，提供时下载后校验
+/// 下载进度通过 download-progress 事件发送
+#[tauri::command]
+pub async fn maafw_version_install(
+    app: AppHandle,
+    version: String,
+    url: String,
+    sha256: Option<String>,
+    proxy_url: Option<String>,
+) -> Result<MaafwVersionInfo, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    let version = validate_version_name(&version)?.to_string();
+    info!("[maafw_versions] Installing {} from {}", version, url);
+
+    let exe_dir = get_exe_directory()?;
+    let versions_dir = exe_dir.join(MAAFW_VERSIONS_DIR);
+    let target = versions_dir.join(&version);
+    if target.exists() {
+        return Err(MxuError::with_args(
+            ErrorCode::MaaFrameworkVersionInstalled,
+            [version],
+        ));
+    }
+
+    let cache_dir = get_app_data_dir()?.join("cache").join("maafw_versions");
+    let file_name = url
+        .rsplit('/')
+        .next()
+        .filter(|n| n.ends_with(".zip") || n.ends_with(".tar.gz") || n.ends_with(".tgz"))
+        .unwrap_or("maafw.zip");
+    let save_path = cache_dir.join(format!("{}-{}", version, file_name));
+    let download = super::download::download_file(
+        app.clone(),
+        url,
+        save_path.to_string_lossy().to_string(),
+        None,
+        proxy_url,
+    )
+    .await?;
+    let archive = PathBuf::from(&download.actual_save_path);
+
+    if let Some(expected) = sha256
+        .map(|s| s.trim().to_lowercase())
+        .filter(|s| !s.is_empty())
+    {
+        let hash_app = app.clone();
+        let hash_path = download.actual_save_path.clone();
+        let actual = tauri::async_runtime::spawn_blocking(move || {
+            super::file_ops::compute_file_hash(&hash_app, &hash_path, "sha256")
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+        if actual != expected {
+            let _ = std::fs::remove_file(&archive);
+            return Err(MxuError::with_args(
+                ErrorCode::ChecksumMismatch,
+                [expected, actual],
+            ));
+        }
+    }
+
+    // 先解压到临时目录，找到库文件所在目录后整体移动到版本目录
+    let extract_dir = versions_dir.join(format!(".{}.installing", version));
+    if extract_dir.exists() {
+        let _ = std::fs::remove_dir_all(&extract_dir);
+    }
+    let installed = (|| -> Result<(), MxuError> {
+        extract_zip(
+            download.actual_save_path.clone(),
+            extract_dir.to_string_lossy().to_string(),
+        )?;
+        let lib_dir = find_library_dir(&extract_dir, 3).ok_or_else(|| {
+            MxuError::with_args(ErrorCode::FileNotInArchive, [library_file_name()])
+        })?;
+        std::fs::rename(&lib_dir, &target)
+            .map_err(|e| format!("无法移动到版本目录 [{}]: {}", target.display(), e))?;
+        Ok(())
+    })();
+    let _ = std::fs::remove_dir_all(&extract_dir);
+    let _ = std::fs::remove_file(&archive);
+    installed?;
+
+    info!(
+        "[maafw_versions] Installed {} to {}",
+        version,
+        target.display()
+    );
+    Ok(MaafwVersionInfo {
+        name: Some(version),
+        path: target.to_string_lossy().to_string(),
+        size: path_size(&target),
+        active: false,
+    })
+}
+
+/// 切换当前使用的 MaaFramework 版本（version 为空时恢复随程序分发的版本）
+/// 库尚未加载时立即加载新版本，否则在重启后生效
+#[tauri::command]
+pub fn maafw_version_switch(
+    state: State<Arc<MaaState>>,
+    version: Option<String>,
+) -> Result<MaafwSwitchResult, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    ensure_idle(&state)?;
+    switch_to(version.as_deref().filter(|v| !v.trim().is_empty()))
+}
+
+/// 切换到配置档案固定的 MaaFramework 版本，未固定时不做任何改动
+#[tauri::command]
+pub fn maafw_version_use_profile(
+    state: State<Arc<MaaState>>,
+    id: String,
+) -> Result<Option<MaafwSwitchResult>, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    let profile = super::profiles::profile_get(id)?;
+    let Some(version) = profile.content.maafw_version else {
+        return Ok(None);
+    };
+    let exe_dir = get_exe_directory()?;
+    if active_version(&exe_dir).as_deref() == Some(version.as_str()) {
+        return Ok(None);
+    }
+    ensure_idle(&state)?;
+    info!(
+        "[maafw_versions] Profile {} pins MaaFramework {}",
+        profile.id, version
+    );
+    switch_to(Some(&version)).map(Some)
+}
+
+fn switch_to(version: Option<&str>) -> Result<MaafwSwitchResult, MxuError> {
+    let exe_dir = get_exe_directory()?;
+    let dir = version_dir(&exe_dir, version)?;
+    let dll_path = dir.join(library_file_name());
+    if !dll_path.is_file() {
+        return Err(MxuError::with_args(
+            ErrorCode::MaaFrameworkVersionNotFound,
+            [dir.display()],
+        ));
+    }
+    info!("[maafw_versions] Switching to {}", dir.display());
+
+    // 库加载后无法在进程内替换：已加载其他目录的库时仅记录选择，重启后生效
+    let requires_restart = match super::system::loaded_library_path() {
+        Some(loaded) => loaded != dll_path,
+        None => {
+            super::system::load_maa_library(&dll_path)?;
+            false
+        }
+    };
+    write_active_version(&exe_dir, version)?;
+
+    if requires_restart {
+        warn!("[maafw_versions] MaaFramework already loaded, switch takes effect after restart");
+    }
+    Ok(MaafwSwitchResult {
+        active: version.map(String::from),
+        loaded_version: loaded_version(),
+        requires_restart,
+    })
+}
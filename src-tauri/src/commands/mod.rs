@@ -7,24 +7,172 @@
 //! - `utils`: 辅助函数
 //! - `maa_core`: Maa 核心命令（初始化、设备搜索、控制器、资源、任务）
 //! - `maa_agent`: Agent 相关命令
+//! - `override_presets`: Pipeline 覆盖预设
+//! - `task_queue`: 任务队列引擎
+//! - `queue_templates`: 任务包含条件（队列模板）解析
+//! - `runs`: 运行记录与 pipeline 快照
+//! - `run_report`: 运行报告截图拼接
+//! - `variables`: 任务变量存储
+//! - `profiles`: 配置档案管理
+//! - `progress_events`: 结构化任务进度事件
+//! - `qrcode_scan`: 截图二维码识别（MXU_QRCODE_ACTION / scan_qrcode）
+//! - `project_interface`: interface.json 解析、校验与选项求值
+//! - `recognition`: 识别结果检查
+//! - `resolution_check`: 设备分辨率与资源基准分辨率不匹配检查
+//! - `recognition_feed`: 识别结果订阅（供自定义面板与远程客户端）
+//! - `post_actions`: 队列完成后操作（关机/睡眠/退出/运行程序）
+//! - `startup_actions`: 可配置的启动动作
 //! - `state`: 状态查询命令
+//! - `color_calibration`: 设备颜色校准
+//! - `cluster`: 集群模式（向远程节点分发配置档案）
+//! - `config_import`: 从其他 MaaFramework GUI 导入配置
+//! - `config_reload`: 配置文件外部修改后的热重载
+//! - `config_migration`: 用户配置加载与版本迁移
+//! - `controller_input`: 控制器输入透传（人工介入时的遥控面板）
+//! - `crash_reporter`: 崩溃报告记录与上传
+//! - `data_dir`: 数据目录迁移（便携模式与系统目录切换）
+//! - `deep_link`: mxu:// 深度链接
+//! - `device_groups`: 设备分组与批量操作
+//! - `device_lock`: 与其他自动化工具的设备互斥
+//! - `diagnostics`: 首次运行环境诊断
+//! - `digest`: 运行历史周报
+//! - `feedback`: 预填环境信息的问题反馈
+//! - `ffi_guard`: MaaFramework 调用超时与死锁检测
 //! - `file_ops`: 文件操作命令
 //! - `update`: 更新安装相关命令
+//! - `action_test`: MXU 内置动作测试调用
+//! - `adb_manager`: ADB 可执行文件查找、下载与 server 冲突检测
+//! - `adb_tuning`: ADB 截图与输入方式列表与延迟测量
+//! - `api_server`: 远程 API 服务（局域网 HTTP 接口）
+//! - `agent_sandbox`: Agent 子进程隔离（工作目录、环境变量、作业对象）
+//! - `app_update`: MXU 程序自身更新（替换 exe 并重启）
+//! - `adaptive_threshold`: 识别得分统计与自适应阈值重试
+//! - `background_mode`: 隐藏到托盘时的低功耗后台模式
+//! - `backup`: 设置备份与恢复
+//! - `capabilities`: 当前构建的功能清单
 //! - `download`: 下载相关命令
+//! - `error`: 命令错误类型（错误码 + 本地化信息）
+//! - `game_update_check`: 开始任务前的游戏客户端更新检测
+//! - `guest_mode`: 只读访客模式
+//! - `heartbeat`: 前后端心跳与 IPC 延迟统计
+//! - `hooks`: 配置档案任务钩子
+//! - `hotkeys`: 全局快捷键绑定
+//! - `i18n`: 后端错误信息本地化（错误码与中英文消息目录）
+//! - `integrity`: 安装完整性清单、校验与修复
+//! - `idle_policy`: 用户使用电脑时推迟任务
+//! - `inference`: 推理后端与设备选择
+//! - `log_config`: 后端日志级别与模块过滤
+//! - `maafw_versions`: MaaFramework 多版本安装与切换
+//! - `monitor`: MXU、Agent 与目标进程的资源占用监控
+//! - `notifications`: 带操作按钮的系统通知
+//! - `pause_gate`: 在节点之间暂停与恢复任务
+//! - `power`: 任务运行期间防止系统休眠
+//! - `legacy_cleanup`: 旧版残留清理
+//! - `package_install`: 拖放安装包识别与安装
+//! - `remote_auth`: 远程 API 令牌与白名单授权
+//! - `resource_manager`: 资源包管理
+//! - `resource_watcher`: 资源文件变化监听与热重载
+//! - `stop_control`: 分级停止与超时升级
+//! - `sync`: WebDAV 云同步
 //! - `system`: 系统相关命令
+//! - `tts`: 语音播报
 //! - `tray`: 托盘相关命令
+//! - `vcredist`: VC++ 运行库一键安装
+//! - `win32_capture`: Win32 截图与输入方式列表与探测
+//! - `wake_timer`: 定时执行前唤醒系统
+//! - `webview_watchdog`: WebView 无响应检测
+//! - `window_preview`: Win32 窗口缩略图
+//! - `scrcpy`: scrcpy 高帧率预览
+//! - `screencap_tools`: 截图工具（模板裁剪、像素取色）
+//! - `screenshot_redaction`: 截图中账号名 / UID 自动打码
+//! - `scripting`: 内嵌 Rhai 脚本自动化（MXU_SCRIPT_ACTION / run_script）
+//! - `clipboard`: 剪贴板命令
 
 pub mod types;
 pub mod utils;
 
+pub mod action_test;
+pub mod adaptive_threshold;
+pub mod adb_manager;
+pub mod adb_tuning;
+pub mod agent_sandbox;
+pub mod api_server;
+pub mod app_update;
+pub mod background_mode;
+pub mod backup;
+pub mod capabilities;
+pub mod clipboard;
+pub mod cluster;
+pub mod color_calibration;
+pub mod config_import;
+pub mod config_migration;
+pub mod config_reload;
+pub mod controller_input;
+pub mod crash_reporter;
+pub mod data_dir;
+pub mod deep_link;
+pub mod device_groups;
+pub mod device_lock;
+pub mod diagnostics;
+pub mod digest;
 pub mod download;
+pub mod error;
+pub mod feedback;
+pub mod ffi_guard;
 pub mod file_ops;
+pub mod game_update_check;
+pub mod guest_mode;
+pub mod heartbeat;
+pub mod hooks;
+pub mod hotkeys;
+pub mod i18n;
+pub mod idle_policy;
+pub mod inference;
+pub mod integrity;
+pub mod legacy_cleanup;
+pub mod log_config;
 pub mod maa_agent;
 pub mod maa_core;
+pub mod maafw_versions;
+pub mod monitor;
+pub mod notifications;
+pub mod override_presets;
+pub mod package_install;
+pub mod pause_gate;
+pub mod post_actions;
+pub mod power;
+pub mod profiles;
+pub mod progress_events;
+pub mod project_interface;
+pub mod qrcode_scan;
+pub mod queue_templates;
+pub mod recognition;
+pub mod recognition_feed;
+pub mod remote_auth;
+pub mod resolution_check;
+pub mod resource_manager;
+pub mod resource_watcher;
+pub mod run_report;
+pub mod runs;
+pub mod scrcpy;
+pub mod screencap_tools;
+pub mod screenshot_redaction;
+pub mod scripting;
+pub mod startup_actions;
 pub mod state;
+pub mod stop_control;
+pub mod sync;
 pub mod system;
+pub mod task_queue;
 pub mod tray;
+pub mod tts;
 pub mod update;
+pub mod variables;
+pub mod vcredist;
+pub mod wake_timer;
+pub mod webview_watchdog;
+pub mod win32_capture;
+pub mod window_preview;
 
 // 重新导出类型（供 lib.rs 使用）
 pub use types::MaaState;
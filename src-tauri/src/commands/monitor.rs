@@ -0,0 +1,199 @@
+//! 资源占用监控
+//!
+//! 定时采样 MXU 自身、各实例 Agent 子进程与目标进程（Win32 窗口所属进程或 ADB 对应的本地模拟器）
+//! 的 CPU 与内存占用，通过 process-metrics 事件推送，也可通过 get_process_metrics 查询，
+//! 便于判断识别期间模拟器是否资源不足
+
+use log::debug;
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::Duration;
+
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::error::MxuError;
+use super::types::{
+    ControllerConfig, MaaState, MonitoredRole, ProcessMetric, ProcessMetricsSnapshot,
+};
+
+/// 采样间隔
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(3);
+
+/// 常见模拟器的主进程名（不含扩展名，小写），用于 ADB 连接到本地模拟器时定位进程
+const EMULATOR_PROCESS_NAMES: &[&str] = &[
+    "mumuvmmheadless",
+    "mumunxdevice",
+    "nemuheadless",
+    "hd-player",
+    "ldvboxheadless",
+    "dnplayer",
+    "noxvmhandle",
+    "memuheadless",
+    "qemu-system-x86_64",
+    "qemu-system-aarch64",
+];
+
+static SYSTEM: LazyLock<Mutex<System>> = LazyLock::new(|| Mutex::new(System::new()));
+
+/// 各实例的目标进程 PID
+static TARGETS: LazyLock<Mutex<HashMap<String, u32>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 最近一次采样结果
+static LATEST: Mutex<Option<ProcessMetricsSnapshot>> = Mutex::new(None);
+
+/// Win32 窗口句柄所属进程
+#[cfg(windows)]
+fn window_pid(handle: u64) -> Option<u32> {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::WindowsAndMessaging::GetWindowThreadProcessId;
+
+    let mut pid = 0u32;
+    unsafe {
+        GetWindowThreadProcessId(HWND(handle as *mut std::ffi::c_void), Some(&mut pid));
+    }
+    (pid != 0).then_some(pid)
+}
+
+#[cfg(not(windows))]
+fn window_pid(_handle: u64) -> Option<u32> {
+    None
+}
+
+/// 查找正在运行的本地模拟器进程
+fn find_emulator_pid() -> Option<u32> {
+    let mut system = SYSTEM.lock().ok()?;
+    system.refresh_processes_specifics(ProcessesToUpdate::All, true, ProcessRefreshKind::nothing());
+    system.processes().values().find_map(|process| {
+        let name = process.name().to_string_lossy().to_lowercase();
+        let stem = name.strip_suffix(".exe").unwrap_or(&name);
+        EMULATOR_PROCESS_NAMES
+            .contains(&stem)
+            .then(|| process.pid().as_u32())
+    })
+}
+
+/// 记录实例的目标进程（maa_connect_controller 时调用）
+pub fn register_target(instance_id: &str, config: &ControllerConfig) {
+    let pid = match config {
+        ControllerConfig::Win32 { handle, .. } | ControllerConfig::Gamepad { handle, .. } => {
+            window_pid(*handle)
+        }
+        ControllerConfig::Adb { address, .. } => {
+            let local = address.starts_with("127.0.0.1")
+                || address.starts_with("localhost")
+                || address.starts_with("emulator-");
+            if local {
+                find_emulator_pid()
+            } else {
+                None
+            }
+        }
+        ControllerConfig::PlayCover { .. } | ControllerConfig::Mock { .. } => None,
+    };
+
+    if let Ok(mut targets) = TARGETS.lock() {
+        match pid {
+            Some(pid) => {
+                debug!(
+                    "[monitor] Target process for instance {}: {}",
+                    instance_id, pid
+                );
+                targets.insert(instance_id.to_string(), pid);
+            }
+            None => {
+                targets.remove(instance_id);
+            }
+        }
+    }
+}
+
+/// 采样一次资源占用
+fn sample(state: &MaaState) -> ProcessMetricsSnapshot {
+    let mut watched: Vec<(MonitoredRole, Option<String>, u32)> =
+        vec![(MonitoredRole::App, None, std::process::id())];
+    if let Ok(instances) = state.instances.lock() {
+        for (instance_id, instance) in instances.iter() {
+            for child in &instance.agent_children {
+                watched.push((MonitoredRole::Agent, Some(instance_id.clone()), child.id()));
+            }
+        }
+    }
+    if let Ok(targets) = TARGETS.lock() {
+        for (instance_id, pid) in targets.iter() {
+            watched.push((MonitoredRole::Target, Some(instance_id.clone()), *pid));
+        }
+    }
+
+    let cpu_count = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1) as f32;
+    let pids: Vec<Pid> = watched
+        .iter()
+        .map(|(_, _, pid)| Pid::from_u32(*pid))
+        .collect();
+
+    let mut snapshot = ProcessMetricsSnapshot {
+        timestamp: chrono::Utc::now().timestamp_millis(),
+        system_memory_total: 0,
+        system_memory_used: 0,
+        processes: Vec::new(),
+    };
+    let Ok(mut system) = SYSTEM.lock() else {
+        return snapshot;
+    };
+    system.refresh_memory();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&pids),
+        true,
+        ProcessRefreshKind::nothing().with_cpu().with_memory(),
+    );
+
+    snapshot.system_memory_total = system.total_memory();
+    snapshot.system_memory_used = system.used_memory();
+    snapshot.processes = watched
+        .into_iter()
+        .filter_map(|(role, instance_id, pid)| {
+            let process = system.process(Pid::from_u32(pid))?;
+            Some(ProcessMetric {
+                role,
+                instance_id,
+                pid,
+                name: process.name().to_string_lossy().to_string(),
+                cpu_percent: process.cpu_usage() / cpu_count,
+                memory_bytes: process.memory(),
+            })
+        })
+        .collect();
+    snapshot
+}
+
+/// 启动采样线程
+pub fn spawn_monitor(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(SAMPLE_INTERVAL);
+        let Some(state) = app.try_state::<Arc<MaaState>>() else {
+            continue;
+        };
+        let snapshot = sample(&state);
+        // 后台模式下无人查看，暂停推送（仍可通过 get_process_metrics 查询）
+        if !super::background_mode::is_active() {
+            let _ = app.emit("process-metrics", &snapshot);
+        }
+        if let Ok(mut latest) = LATEST.lock() {
+            *latest = Some(snapshot);
+        }
+    });
+}
+
+/// 获取最近一次资源占用采样（尚未采样时立即采样，此时 CPU 占用为 0）
+#[tauri::command]
+pub fn get_process_metrics(
+    state: tauri::State<Arc<MaaState>>,
+) -> Result<ProcessMetricsSnapshot, MxuError> {
+    if let Some(snapshot) = LATEST.lock().map_err(|e| e.to_string())?.clone() {
+        return Ok(snapshot);
+    }
+    Ok(sample(&state))
+}
@@ -0,0 +1,165 @@
+//! 系统通知
+//!
+//! Windows 上使用 WinRT toast，可附带「打开 MXU」「停止任务」「查看截图」按钮，
+//! 点击按钮或通知本身时经激活回调回到后端处理（点击通知本身视为打开 MXU）。
+//! 其他平台退回 notify-rust 的普通通知，不显示按钮
+
+use log::{info, warn};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_opener::OpenerExt;
+
+use super::types::{MaaState, ToastAction};
+use super::utils::get_app_data_dir;
+
+/// 应用句柄（通知激活回调在系统线程上执行，需要全局访问）
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+/// 保存应用句柄
+pub fn init(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+}
+
+/// 按钮 ID 与显示文字
+#[cfg_attr(not(windows), allow(dead_code))]
+fn action_info(action: ToastAction) -> (&'static str, &'static str) {
+    match action {
+        ToastAction::Open => ("open", "打开 MXU"),
+        ToastAction::StopTasks => ("stop_tasks", "停止任务"),
+        ToastAction::ViewScreenshot => ("view_screenshot", "查看截图"),
+    }
+}
+
+#[cfg(windows)]
+fn parse_action(id: &str) -> Option<ToastAction> {
+    [
+        ToastAction::Open,
+        ToastAction::StopTasks,
+        ToastAction::ViewScreenshot,
+    ]
+    .into_iter()
+    .find(|a| action_info(*a).0 == id)
+}
+
+/// 处理通知按钮操作
+#[cfg_attr(not(windows), allow(dead_code))]
+fn handle_action(action: ToastAction, screenshot: Option<&Path>) {
+    let Some(app) = APP.get() else {
+        return;
+    };
+    info!("[notifications] Toast action: {:?}", action);
+    match action {
+        ToastAction::Open => {
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.show();
+                let _ = window.unminimize();
+                let _ = window.set_focus();
+            }
+        }
+        ToastAction::StopTasks => {
+            if super::guest_mode::is_guest_mode() {
+                info!("[notifications] Stop action ignored in guest mode");
+                return;
+            }
+            // 与托盘菜单的停止任务一致，由前端执行停止流程
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.emit("tray-stop-tasks", ());
+            }
+        }
+        ToastAction::ViewScreenshot => {
+            let Some(path) = screenshot else {
+                return;
+            };
+            if let Err(e) = app
+                .opener()
+                .open_path(path.to_string_lossy().to_string(), None::<&str>)
+            {
+                warn!("[notifications] Failed to open screenshot: {}", e);
+            }
+        }
+    }
+}
+
+/// 保存任务所属实例的最近一帧截图（用于通知的查看截图按钮），返回保存路径
+pub fn save_task_screenshot(task_id: i64) -> Option<PathBuf> {
+    let instance_id = super::runs::instance_for_task(task_id)?;
+    let state = APP.get()?.try_state::<Arc<MaaState>>()?;
+    let controller = state
+        .instances
+        .lock()
+        .ok()?
+        .get(&instance_id)?
+        .controller
+        .clone()?;
+    let data = controller
+        .cached_image()
+        .ok()
+        .and_then(|b| b.to_vec())
+        .filter(|d| !d.is_empty())?;
+
+    let dir = get_app_data_dir().ok()?.join("screenshots");
+    std::fs::create_dir_all(&dir).ok()?;
+    let path = dir.join(format!(
+        "{}_{}_notify.png",
+        instance_id,
+        chrono::Local::now().format("%Y%m%d_%H%M%S")
+    ));
+    match std::fs::write(&path, data) {
+        Ok(()) => Some(path),
+        Err(e) => {
+            warn!("[notifications] Failed to save screenshot: {}", e);
+            None
+        }
+    }
+}
+
+/// 发送通知，actions 为要显示的按钮，screenshot 为附带的截图（同时作为查看截图按钮的目标）
+pub fn show(
+    title: &str,
+    body: &str,
+    actions: &[ToastAction],
+    screenshot: Option<PathBuf>,
+) -> Result<(), String> {
+    #[cfg(windows)]
+    {
+        use tauri_winrt_notification::Toast;
+
+        let mut toast = Toast::new(Toast::POWERSHELL_APP_ID)
+            .title(title)
+            .text1(body);
+        if let Some(path) = &screenshot {
+            toast = toast.image(path, "screenshot");
+        }
+        for action in actions {
+            if *action == ToastAction::ViewScreenshot && screenshot.is_none() {
+                continue;
+            }
+            let (id, label) = action_info(*action);
+            toast = toast.add_button(label, id);
+        }
+        toast
+            .on_activated(move |arg: Option<String>| {
+                let action = arg
+                    .as_deref()
+                    .and_then(parse_action)
+                    .unwrap_or(ToastAction::Open);
+                handle_action(action, screenshot.as_deref());
+                Ok(())
+            })
+            .show()
+            .map_err(|e| e.to_string())
+    }
+
+    #[cfg(not(windows))]
+    {
+        let _ = (actions, screenshot);
+        notify_rust::Notification::new()
+            .summary(title)
+            .body(body)
+            .show()
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+}
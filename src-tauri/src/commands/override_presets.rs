@@ -0,0 +1,205 @@
+//! Pipeline 覆盖预设
+//!
+//! 将命名的 pipeline 覆盖集合保存在 config/override_presets.json 中，
+//! 可按任务 entry 选择预设，任务启动时自动追加到该任务的覆盖末尾；
+//! 运行中也可通过 override_preset_apply 将预设应用到已提交的任务
+
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use tauri::State;
+
+use super::error::MxuError;
+use super::i18n::ErrorCode;
+use super::types::{MaaState, TaskConfig};
+use super::utils::{append_override, get_app_data_dir, merge_json, save_json_config};
+
+/// 选择键：应用于所有任务
+const ALL_TASKS_KEY: &str = "*";
+
+/// 预设文件读写锁
+static PRESETS_LOCK: Mutex<()> = Mutex::new(());
+
+/// 预设文件结构
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct OverridePresets {
+    /// 预设名称 -> 覆盖内容
+    #[serde(default)]
+    presets: BTreeMap<String, serde_json::Value>,
+    /// 任务 entry（或 "*"）-> 选中的预设名称
+    #[serde(default)]
+    selected: BTreeMap<String, String>,
+}
+
+fn presets_path() -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?
+        .join("config")
+        .join("override_presets.json"))
+}
+
+fn load_presets() -> Result<OverridePresets, String> {
+    let path = presets_path()?;
+    if !path.exists() {
+        return Ok(OverridePresets::default());
+    }
+    let content = std::fs::read_to_string(&path).map_err(|e| format!("无法读取覆盖预设: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("无法解析覆盖预设: {}", e))
+}
+
+fn modify_presets<T>(
+    f: impl FnOnce(&mut OverridePresets) -> Result<T, String>,
+) -> Result<T, String> {
+    let _guard = PRESETS_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut presets = load_presets()?;
+    let result = f(&mut presets)?;
+
+    let path = presets_path()?;
+    save_json_config(&path, &presets)?;
+    Ok(result)
+}
+
+/// 按顺序合并多个预设
+fn merge_presets(presets: &OverridePresets, names: &[String]) -> Result<serde_json::Value, String> {
+    let mut merged = serde_json::Value::Object(Default::default());
+    for name in names {
+        let preset = presets
+            .presets
+            .get(name)
+            .ok_or_else(|| format!("覆盖预设不存在: {}", name))?;
+        merge_json(&mut merged, preset);
+    }
+    Ok(merged)
+}
+
+/// 列出所有预设
+#[tauri::command]
+pub fn override_preset_list() -> Result<BTreeMap<String, serde_json::Value>, MxuError> {
+    Ok(load_presets()?.presets)
+}
+
+/// 保存（新建或覆盖）预设
+#[tauri::command]
+pub fn override_preset_save(
+    name: String,
+    pipeline_override: serde_json::Value,
+) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    if name.trim().is_empty() {
+        return Err(MxuError::new(ErrorCode::EmptyName));
+    }
+    if !pipeline_override.is_object() {
+        return Err(MxuError::new(ErrorCode::OverrideNotObject));
+    }
+    modify_presets(|presets| {
+        presets
+            .presets
+            .insert(name.trim().to_string(), pipeline_override);
+        Ok(())
+    })?;
+    info!("[override_presets] Preset saved: {}", name);
+    Ok(())
+}
+
+/// 删除预设（同时清除对它的选择）
+#[tauri::command]
+pub fn override_preset_delete(name: String) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    Ok(modify_presets(|presets| {
+        if presets.presets.remove(&name).is_none() {
+            return Err(format!("覆盖预设不存在: {}", name));
+        }
+        presets.selected.retain(|_, selected| selected != &name);
+        Ok(())
+    })?)
+}
+
+/// 按顺序合并多个预设，返回合并结果；save_as 不为空时保存为新预设
+#[tauri::command]
+pub fn override_preset_merge(
+    names: Vec<String>,
+    save_as: Option<String>,
+) -> Result<serde_json::Value, MxuError> {
+    let merged = merge_presets(&load_presets()?, &names)?;
+    if let Some(name) = save_as.filter(|n| !n.trim().is_empty()) {
+        override_preset_save(name, merged.clone())?;
+    }
+    Ok(merged)
+}
+
+/// 为任务 entry 选择预设（entry 为 "*" 时应用于所有任务），preset 为空时取消选择
+#[tauri::command]
+pub fn override_preset_select(entry: String, preset: Option<String>) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    Ok(modify_presets(|presets| {
+        match preset {
+            Some(name) => {
+                if !presets.presets.contains_key(&name) {
+                    return Err(format!("覆盖预设不存在: {}", name));
+                }
+                presets.selected.insert(entry, name);
+            }
+            None => {
+                presets.selected.remove(&entry);
+            }
+        }
+        Ok(())
+    })?)
+}
+
+/// 获取当前的预设选择（entry -> 预设名称）
+#[tauri::command]
+pub fn override_preset_get_selection() -> Result<BTreeMap<String, String>, MxuError> {
+    Ok(load_presets()?.selected)
+}
+
+/// 将预设应用到已提交的任务（等同于以预设内容调用 maa_override_pipeline）
+#[tauri::command]
+pub fn override_preset_apply(
+    state: State<Arc<MaaState>>,
+    instance_id: String,
+    task_id: i64,
+    name: String,
+) -> Result<bool, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    let preset = merge_presets(&load_presets()?, std::slice::from_ref(&name))?;
+    super::maa_core::maa_override_pipeline(state, instance_id, task_id, preset.to_string())
+}
+
+/// 任务启动前调用：将选中的预设追加到对应任务的覆盖末尾
+pub fn apply_selected_presets(tasks: &mut [TaskConfig]) {
+    let presets = match load_presets() {
+        Ok(p) if !p.selected.is_empty() => p,
+        Ok(_) => return,
+        Err(e) => {
+            log::warn!("[override_presets] Failed to load presets: {}", e);
+            return;
+        }
+    };
+
+    for task in tasks {
+        let names: Vec<String> = [ALL_TASKS_KEY, task.entry.as_str()]
+            .iter()
+            .filter_map(|key| presets.selected.get(*key).cloned())
+            .collect();
+        if names.is_empty() {
+            continue;
+        }
+        let preset = match merge_presets(&presets, &names) {
+            Ok(p) => p,
+            Err(e) => {
+                log::warn!("[override_presets] {}", e);
+                continue;
+            }
+        };
+
+        // 预设追加在末尾以获得最高优先级
+        task.pipeline_override = append_override(&task.pipeline_override, preset);
+        info!(
+            "[override_presets] Applied preset(s) {:?} to task {}",
+            names, task.entry
+        );
+    }
+}
@@ -0,0 +1,224 @@
+//! 拖放安装包识别与安装
+//!
+//! 窗口收到文件拖放后识别安装包类型（资源包、MaaFramework 包、配置档案），
+//! 通过 package-dropped 事件通知前端确认，确认后由 install_dropped_package 执行安装。
+//! install_dropped_package 只接受经拖放（或链接下载）识别过的路径，不能借此安装任意文件
+
+use log::info;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, Mutex};
+
+use tauri::{AppHandle, Emitter, State};
+
+use super::error::MxuError;
+use super::i18n::ErrorCode;
+use super::types::{DroppedPackage, MaaState, PackageInstallResult, PackageKind};
+use super::update::{copy_dir_contents, ensure_idle, full_update};
+use super::utils::{ensure_free_space, get_exe_directory, get_maafw_dir, path_size};
+
+/// MaaFramework 动态库文件名（任一平台）
+const MAAFW_LIBRARY_NAMES: [&str; 3] = [
+    "MaaFramework.dll",
+    "libMaaFramework.so",
+    "libMaaFramework.dylib",
+];
+
+/// 已识别、等待前端确认的安装包（路径 → 类型），安装时取出
+static PENDING: LazyLock<Mutex<HashMap<PathBuf, PackageKind>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 处理窗口文件拖放事件：逐个识别文件，已知类型记录后发送 package-dropped 事件
+pub fn handle_dropped_paths(app: &AppHandle, paths: &[PathBuf]) {
+    for path in paths {
+        let Some(kind) = classify_package(path) else {
+            info!("Dropped file is not a known package type: {:?}", path);
+            continue;
+        };
+
+        let package = DroppedPackage {
+            path: path.to_string_lossy().to_string(),
+            file_name: path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            kind,
+            size: std::fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+        };
+
+        info!("Dropped package detected: {:?}", package);
+        if let Ok(mut pending) = PENDING.lock() {
+            pending.insert(path.clone(), kind);
+        }
+        if let Err(e) = app.emit("package-dropped", package) {
+            log::error!("Failed to emit package-dropped: {}", e);
+        }
+    }
+}
+
+/// 根据文件内容识别安装包类型
+pub fn classify_package(path: &Path) -> Option<PackageKind> {
+    if !path.is_file() {
+        return None;
+    }
+
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    match ext.as_str() {
+        "zip" => classify_zip(path),
+        // 配置档案（profile_export 导出的文件）
+        "json" => super::profiles::is_profile_file(path).then_some(PackageKind::Profile),
+        _ => None,
+    }
+}
+
+/// 根据 zip 内的文件列表识别资源包或 MaaFramework 包
+fn classify_zip(path: &Path) -> Option<PackageKind> {
+    let file = std::fs::File::open(path).ok()?;
+    let archive = zip::ZipArchive::new(file).ok()?;
+
+    let mut has_interface = false;
+    let mut has_maafw = false;
+    for name in archive.file_names() {
+        let file_name = name.rsplit('/').next().unwrap_or(name);
+        if file_name == "interface.json" {
+            has_interface = true;
+        }
+        if MAAFW_LIBRARY_NAMES.contains(&file_name) {
+            has_maafw = true;
+        }
+    }
+
+    // 资源包通常也会附带 maafw 目录，以 interface.json 为准
+    if has_interface {
+        Some(PackageKind::Resource)
+    } else if has_maafw {
+        Some(PackageKind::MaaFramework)
+    } else {
+        None
+    }
+}
+
+/// 安装拖放的安装包（前端确认后调用）
+/// 资源包与 MaaFramework 包会覆盖程序目录下的文件，有任务运行时返回 UpdateConflict
+#[tauri::command]
+pub async fn install_dropped_package(
+    state: State<'_, Arc<MaaState>>,
+    path: String,
+    kind: PackageKind,
+) -> Result<PackageInstallResult, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    info!("install_dropped_package called: {} ({:?})", path, kind);
+    if kind != PackageKind::Profile {
+        ensure_idle(&state)?;
+    }
+
+    let source = PathBuf::from(&path);
+    let recorded = PENDING.lock().map_err(|e| e.to_string())?.remove(&source);
+    if recorded != Some(kind) {
+        return Err(MxuError::with_args(ErrorCode::PackageNotConfirmed, [&path]));
+    }
+
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        // 重新校验，防止确认期间文件被替换
+        if classify_package(&source) != Some(kind) {
+            return Err(MxuError::with_args(ErrorCode::PackageInvalid, [&path]));
+        }
+
+        match kind {
+            PackageKind::Resource => install_resource_package(&source),
+            PackageKind::MaaFramework => install_maafw_package(&source),
+            PackageKind::Profile => install_profile(&source),
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())??)
+}
+
+/// 解压到 cache/drop_extract 下的临时目录，返回临时目录路径
+fn extract_to_temp(source: &Path) -> Result<PathBuf, String> {
+    let temp_dir = get_exe_directory()?
+        .join("cache")
+        .join("drop_extract")
+        .join(chrono::Local::now().format("%Y%m%d%H%M%S").to_string());
+    super::update::extract_zip(
+        source.to_string_lossy().to_string(),
+        temp_dir.to_string_lossy().to_string(),
+    )?;
+    Ok(temp_dir)
+}
+
+/// 在解压目录中查找包含指定文件的目录（最多向下查找两层，兼容带顶层目录的压缩包）
+fn find_dir_containing(root: &Path, names: &[&str], depth: usize) -> Option<PathBuf> {
+    if names.iter().any(|n| root.join(n).is_file()) {
+        return Some(root.to_path_buf());
+    }
+    if depth == 0 {
+        return None;
+    }
+    std::fs::read_dir(root)
+        .ok()?
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .find_map(|e| find_dir_containing(&e.path(), names, depth - 1))
+}
+
+/// 安装资源包：按全量更新流程覆盖到 exe 目录
+fn install_resource_package(source: &Path) -> Result<PackageInstallResult, MxuError> {
+    let temp_dir = extract_to_temp(source)?;
+    let result = (|| -> Result<PackageInstallResult, MxuError> {
+        let root = find_dir_containing(&temp_dir, &["interface.json"], 2)
+            .ok_or_else(|| MxuError::with_args(ErrorCode::FileNotInArchive, ["interface.json"]))?;
+        let exe_dir = get_exe_directory()?;
+        ensure_free_space(&exe_dir, path_size(&root))?;
+        full_update(&root.to_string_lossy(), &exe_dir.to_string_lossy())?;
+        Ok(PackageInstallResult {
+            kind: PackageKind::Resource,
+            target: exe_dir.to_string_lossy().to_string(),
+            requires_restart: false,
+        })
+    })();
+    let _ = std::fs::remove_dir_all(&temp_dir);
+    result
+}
+
+/// 安装 MaaFramework 包：将动态库所在目录的内容复制到 maafw 目录
+/// 已加载的库文件会先移动到 cache/old，新库在重启后生效
+fn install_maafw_package(source: &Path) -> Result<PackageInstallResult, MxuError> {
+    let temp_dir = extract_to_temp(source)?;
+    let result = (|| -> Result<PackageInstallResult, MxuError> {
+        let lib_dir = find_dir_containing(&temp_dir, &MAAFW_LIBRARY_NAMES, 2).ok_or_else(|| {
+            MxuError::with_args(
+                ErrorCode::FileNotInArchive,
+                [MAAFW_LIBRARY_NAMES.join(" / ")],
+            )
+        })?;
+        let maafw_dir = get_maafw_dir()?;
+        ensure_free_space(&maafw_dir, path_size(&lib_dir))?;
+        copy_dir_contents(
+            &lib_dir.to_string_lossy(),
+            &maafw_dir.to_string_lossy(),
+            None,
+        )?;
+        Ok(PackageInstallResult {
+            kind: PackageKind::MaaFramework,
+            target: maafw_dir.to_string_lossy().to_string(),
+            requires_restart: true,
+        })
+    })();
+    let _ = std::fs::remove_dir_all(&temp_dir);
+    result
+}
+
+/// 作为新的配置档案导入（分配新 ID，不使用拖放文件的文件名，不会覆盖已有文件）
+fn install_profile(source: &Path) -> Result<PackageInstallResult, MxuError> {
+    let profile = super::profiles::profile_import(source.to_string_lossy().to_string())?;
+    Ok(PackageInstallResult {
+        kind: PackageKind::Profile,
+        target: profile.id,
+        requires_restart: false,
+    })
+}
@@ -0,0 +1,158 @@
+//! 任务暂停与恢复
+//!
+//! 在 Context Sink 中拦截节点动作结束的回调（Node.Action.Succeeded / Failed），
+//! 实例处于暂停状态时阻塞 Tasker 工作线程，使 pipeline 停在两个节点之间，
+//! 用户可以临时手动操作设备而不必中止整个队列。
+//! 恢复、停止任务或到达自动恢复时间后继续执行；状态变化通过 task-pause 事件通知
+
+use log::info;
+use std::collections::HashMap;
+use std::sync::{Arc, Condvar, LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Emitter, State};
+
+use super::error::MxuError;
+use super::i18n::ErrorCode;
+use super::types::{MaaState, TaskPauseEvent, TaskPauseState};
+
+/// 阻塞时重新检查状态的间隔
+const RECHECK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// 实例的暂停请求
+struct PauseRequest {
+    /// 到达该时间后自动恢复
+    resume_at: Option<Instant>,
+    /// Tasker 线程是否已停在检查点
+    blocked: bool,
+}
+
+/// 已请求暂停的实例
+static PAUSED: LazyLock<Mutex<HashMap<String, PauseRequest>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 暂停状态变化通知
+static CHANGED: Condvar = Condvar::new();
+
+fn emit(app: &AppHandle, instance_id: &str, state: TaskPauseState, node: Option<String>) {
+    let _ = app.emit(
+        "task-pause",
+        TaskPauseEvent {
+            instance_id: instance_id.to_string(),
+            state,
+            node,
+        },
+    );
+}
+
+/// 实例是否已请求暂停
+pub fn is_paused(instance_id: &str) -> bool {
+    PAUSED.lock().is_ok_and(|p| p.contains_key(instance_id))
+}
+
+/// 解除暂停（停止任务时调用，避免 Tasker 线程一直阻塞）
+pub fn release(instance_id: &str) {
+    if let Ok(mut paused) = PAUSED.lock() {
+        if paused.remove(instance_id).is_some() {
+            CHANGED.notify_all();
+        }
+    }
+}
+
+/// Context Sink 检查点：节点动作结束且实例已请求暂停时阻塞，直到恢复
+pub fn checkpoint(app: &AppHandle, instance_id: &str, message: &str, details: &str) {
+    if !matches!(message, "Node.Action.Succeeded" | "Node.Action.Failed") {
+        return;
+    }
+    let Ok(mut paused) = PAUSED.lock() else {
+        return;
+    };
+    let Some(request) = paused.get_mut(instance_id) else {
+        return;
+    };
+    request.blocked = true;
+
+    let node = serde_json::from_str::<serde_json::Value>(details)
+        .ok()
+        .and_then(|v| v.get("name").and_then(|n| n.as_str()).map(str::to_string));
+    info!(
+        "[pause_gate] Instance {} paused after node {:?}",
+        instance_id, node
+    );
+    emit(app, instance_id, TaskPauseState::Paused, node.clone());
+
+    loop {
+        let Some(request) = paused.get(instance_id) else {
+            break;
+        };
+        if request.resume_at.is_some_and(|at| Instant::now() >= at) {
+            info!("[pause_gate] Auto-resuming instance {}", instance_id);
+            paused.remove(instance_id);
+            break;
+        }
+        paused = match CHANGED.wait_timeout(paused, RECHECK_INTERVAL) {
+            Ok((guard, _)) => guard,
+            Err(_) => return,
+        };
+    }
+    drop(paused);
+
+    info!("[pause_gate] Instance {} resumed", instance_id);
+    emit(app, instance_id, TaskPauseState::Resumed, node);
+}
+
+/// 暂停任务：当前节点结束后停在两个节点之间
+///
+/// auto_resume_secs 不为空时到时自动恢复
+#[tauri::command]
+pub fn maa_pause_tasks(
+    app: AppHandle,
+    state: State<Arc<MaaState>>,
+    instance_id: String,
+    auto_resume_secs: Option<u64>,
+) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    info!(
+        "maa_pause_tasks called, instance_id: {}, auto_resume_secs: {:?}",
+        instance_id, auto_resume_secs
+    );
+    let running = {
+        let instances = state.instances.lock().map_err(|e| e.to_string())?;
+        let instance = instances
+            .get(&instance_id)
+            .ok_or_else(|| MxuError::new(ErrorCode::InstanceNotFound))?;
+        instance.tasker.as_ref().is_some_and(|t| t.running())
+    };
+    if !running && !super::stop_control::queue_active(&state, &instance_id) {
+        return Err(MxuError::new(ErrorCode::NoRunningTasks));
+    }
+
+    PAUSED.lock().map_err(|e| e.to_string())?.insert(
+        instance_id.clone(),
+        PauseRequest {
+            resume_at: auto_resume_secs.map(|secs| Instant::now() + Duration::from_secs(secs)),
+            blocked: false,
+        },
+    );
+    CHANGED.notify_all();
+    emit(&app, &instance_id, TaskPauseState::Pending, None);
+    Ok(())
+}
+
+/// 恢复已暂停的任务
+#[tauri::command]
+pub fn maa_resume_tasks(app: AppHandle, instance_id: String) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    info!("maa_resume_tasks called, instance_id: {}", instance_id);
+    let request = PAUSED
+        .lock()
+        .map_err(|e| e.to_string())?
+        .remove(&instance_id)
+        .ok_or_else(|| MxuError::new(ErrorCode::TaskNotPaused))?;
+    CHANGED.notify_all();
+    // 已停在检查点时由 Tasker 线程发送恢复事件，否则在这里通知前端撤销暂停
+    if !request.blocked {
+        emit(&app, &instance_id, TaskPauseState::Resumed, None);
+    }
+    Ok(())
+}
@@ -0,0 +1,214 @@
+//! 完成后操作
+//!
+//! 所有实例的任务队列执行完毕后，按统一策略执行关机/重启/睡眠/息屏/退出 MXU/运行程序，
+//! 执行前有可取消的倒计时（前端或托盘均可取消）。
+//! 任务列表中的 MXU_POWER 特殊任务同样经由这里的倒计时执行，两者共用同一取消入口
+
+use log::{info, warn};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock, Mutex, OnceLock};
+use std::thread;
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter};
+
+use super::error::MxuError;
+use super::i18n::ErrorCode;
+use super::types::{
+    MaaState, PostActionCountdownEvent, PostActionKind, PostActionPolicy, QueueStatus,
+};
+use super::utils::{get_app_data_dir, save_json_config};
+use crate::mxu_actions;
+
+/// 应用句柄（MXU_POWER 在 MaaFramework 回调线程上请求倒计时，需要全局访问）
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+/// 当前完成后操作策略（首次访问时从配置文件读取）
+static POLICY: LazyLock<Mutex<PostActionPolicy>> = LazyLock::new(|| Mutex::new(load_policy()));
+
+/// 倒计时是否进行中
+static COUNTDOWN_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// 倒计时代数，取消时递增，使进行中的倒计时失效
+static COUNTDOWN_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+fn config_path() -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?.join("config").join("post_actions.json"))
+}
+
+fn load_policy() -> PostActionPolicy {
+    config_path()
+        .ok()
+        .and_then(|p| std::fs::read_to_string(p).ok())
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+/// 保存应用句柄
+pub fn init(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+}
+
+/// 获取完成后操作策略
+#[tauri::command]
+pub fn post_action_get_policy() -> PostActionPolicy {
+    POLICY.lock().map(|p| p.clone()).unwrap_or_default()
+}
+
+/// 设置完成后操作策略
+#[tauri::command]
+pub fn post_action_set_policy(policy: PostActionPolicy) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    if policy.action == PostActionKind::RunProgram
+        && policy.program.as_deref().unwrap_or("").trim().is_empty()
+    {
+        return Err(MxuError::new(ErrorCode::RunProgramPathRequired));
+    }
+    save_json_config(&config_path()?, &policy)?;
+    info!("[post_actions] Policy updated: {:?}", policy);
+    *POLICY.lock().map_err(|e| e.to_string())? = policy;
+    Ok(())
+}
+
+/// 取消正在进行的倒计时
+#[tauri::command]
+pub fn post_action_cancel(app: AppHandle) -> bool {
+    cancel_countdown(&app)
+}
+
+/// 查询倒计时是否进行中
+#[tauri::command]
+pub fn post_action_is_pending() -> bool {
+    COUNTDOWN_ACTIVE.load(Ordering::SeqCst)
+}
+
+/// 取消倒计时，返回是否确实取消了进行中的倒计时
+pub fn cancel_countdown(app: &AppHandle) -> bool {
+    COUNTDOWN_GENERATION.fetch_add(1, Ordering::SeqCst);
+    if COUNTDOWN_ACTIVE.swap(false, Ordering::SeqCst) {
+        info!("[post_actions] Countdown cancelled");
+        let _ = app.emit("post-action-cancelled", ());
+        true
+    } else {
+        false
+    }
+}
+
+/// 队列结束时调用：所有实例队列均已结束时按策略触发完成后操作
+pub fn on_queue_finished(app: &AppHandle, state: &Arc<MaaState>, status: QueueStatus) {
+    let policy = post_action_get_policy();
+    if policy.action == PostActionKind::None {
+        return;
+    }
+
+    match status {
+        // 达到时长上限同样视为正常结束，照常执行完成后操作
+        QueueStatus::Completed | QueueStatus::TimeBoxed => {}
+        QueueStatus::Aborted if policy.run_on_failure => {}
+        _ => {
+            info!(
+                "[post_actions] Queue finished with {:?}, post action skipped",
+                status
+            );
+            return;
+        }
+    }
+
+    let any_active = state
+        .task_queues
+        .lock()
+        .map(|queues| queues.values().any(|q| q.is_active()))
+        .unwrap_or(false);
+    if any_active {
+        info!("[post_actions] Other queues still running, post action deferred");
+        return;
+    }
+
+    start_countdown(app.clone(), policy);
+}
+
+/// MXU_POWER 特殊任务调用：以当前策略的倒计时执行电源操作，返回倒计时是否已开始
+pub fn request_power_action(action: PostActionKind) -> bool {
+    let Some(app) = APP.get() else {
+        warn!("[post_actions] App handle not initialized");
+        return false;
+    };
+    let policy = PostActionPolicy {
+        action,
+        program: None,
+        args: Vec::new(),
+        ..post_action_get_policy()
+    };
+    start_countdown(app.clone(), policy)
+}
+
+/// 启动倒计时线程，结束后执行操作；已有倒计时进行中时返回 false
+fn start_countdown(app: AppHandle, policy: PostActionPolicy) -> bool {
+    if COUNTDOWN_ACTIVE.swap(true, Ordering::SeqCst) {
+        warn!("[post_actions] Countdown already in progress");
+        return false;
+    }
+    let generation = COUNTDOWN_GENERATION.load(Ordering::SeqCst);
+    info!(
+        "[post_actions] Starting {}s countdown for {:?}",
+        policy.countdown_secs, policy.action
+    );
+
+    thread::spawn(move || {
+        let cancelled = || COUNTDOWN_GENERATION.load(Ordering::SeqCst) != generation;
+
+        for remaining in (1..=policy.countdown_secs).rev() {
+            if cancelled() {
+                return;
+            }
+            let _ = app.emit(
+                "post-action-countdown",
+                PostActionCountdownEvent {
+                    action: policy.action,
+                    remaining_secs: remaining,
+                },
+            );
+            thread::sleep(Duration::from_secs(1));
+        }
+
+        if cancelled() || !COUNTDOWN_ACTIVE.swap(false, Ordering::SeqCst) {
+            return;
+        }
+
+        let success = execute(&app, &policy);
+        let _ = app.emit("post-action-executed", success);
+    });
+    true
+}
+
+/// 执行完成后操作
+fn execute(app: &AppHandle, policy: &PostActionPolicy) -> bool {
+    info!("[post_actions] Executing {:?}", policy.action);
+    match policy.action {
+        PostActionKind::None => true,
+        PostActionKind::Shutdown => mxu_actions::execute_power_shutdown(),
+        PostActionKind::Restart => mxu_actions::execute_power_restart(),
+        PostActionKind::Sleep => mxu_actions::execute_power_sleep(),
+        PostActionKind::ScreenOff => mxu_actions::execute_power_screenoff(),
+        PostActionKind::QuitApp => {
+            app.exit(0);
+            true
+        }
+        PostActionKind::RunProgram => {
+            let Some(program) = policy.program.as_deref() else {
+                return false;
+            };
+            match std::process::Command::new(program)
+                .args(&policy.args)
+                .spawn()
+            {
+                Ok(_) => true,
+                Err(e) => {
+                    log::error!("[post_actions] Failed to run {}: {}", program, e);
+                    false
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,178 @@
+//! 任务运行期间防止系统休眠
+//!
+//! 后台线程定时检查是否有实例在运行任务（tasker 运行中或任务队列未结束），
+//! 运行期间持有防休眠请求，全部结束后释放：
+//! Windows 使用 SetThreadExecutionState，macOS 使用 caffeinate，Linux 使用 systemd-inhibit
+
+use log::{info, warn};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+
+use super::error::MxuError;
+use super::types::{MaaState, PowerSettings};
+use super::utils::{get_app_data_dir, save_json_config};
+
+/// 检查间隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+fn config_path() -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?.join("config").join("power.json"))
+}
+
+fn load_settings() -> Result<PowerSettings, String> {
+    let path = config_path()?;
+    if !path.exists() {
+        return Ok(PowerSettings::default());
+    }
+    let content = std::fs::read_to_string(&path).map_err(|e| format!("无法读取电源设置: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("无法解析电源设置: {}", e))
+}
+
+/// 是否有实例正在运行任务
+pub fn any_running(state: &MaaState) -> bool {
+    let tasker_running = state.instances.lock().is_ok_and(|instances| {
+        instances
+            .values()
+            .any(|i| i.tasker.as_ref().is_some_and(|t| t.running()))
+    });
+    tasker_running
+        || state
+            .task_queues
+            .lock()
+            .is_ok_and(|queues| queues.values().any(|q| q.is_active()))
+}
+
+/// 防休眠请求（释放时恢复系统默认行为）
+struct WakeLock {
+    #[cfg(not(windows))]
+    child: Option<std::process::Child>,
+}
+
+impl WakeLock {
+    #[cfg(windows)]
+    fn acquire(settings: &PowerSettings) -> Result<Self, String> {
+        use windows::Win32::System::Power::{
+            SetThreadExecutionState, ES_CONTINUOUS, ES_DISPLAY_REQUIRED, ES_SYSTEM_REQUIRED,
+        };
+
+        let mut flags = ES_CONTINUOUS | ES_SYSTEM_REQUIRED;
+        if settings.keep_display_on {
+            flags |= ES_DISPLAY_REQUIRED;
+        }
+        // 执行状态绑定到调用线程，须在看守线程中获取与释放
+        let previous = unsafe { SetThreadExecutionState(flags) };
+        if previous.0 == 0 {
+            return Err("SetThreadExecutionState 调用失败".to_string());
+        }
+        Ok(Self {})
+    }
+
+    #[cfg(target_os = "macos")]
+    fn acquire(settings: &PowerSettings) -> Result<Self, String> {
+        let pid = std::process::id().to_string();
+        let flags = if settings.keep_display_on {
+            "-id"
+        } else {
+            "-i"
+        };
+        let child = std::process::Command::new("caffeinate")
+            .args([flags, "-w", &pid])
+            .spawn()
+            .map_err(|e| format!("无法启动 caffeinate: {}", e))?;
+        Ok(Self { child: Some(child) })
+    }
+
+    #[cfg(all(not(windows), not(target_os = "macos")))]
+    fn acquire(settings: &PowerSettings) -> Result<Self, String> {
+        let what = if settings.keep_display_on {
+            "sleep:idle"
+        } else {
+            "sleep"
+        };
+        let child = std::process::Command::new("systemd-inhibit")
+            .args([
+                &format!("--what={}", what),
+                "--who=MXU",
+                "--why=Automation tasks are running",
+                "--mode=block",
+                "sleep",
+                "infinity",
+            ])
+            .spawn()
+            .map_err(|e| format!("无法启动 systemd-inhibit: {}", e))?;
+        Ok(Self { child: Some(child) })
+    }
+}
+
+impl Drop for WakeLock {
+    fn drop(&mut self) {
+        #[cfg(windows)]
+        unsafe {
+            use windows::Win32::System::Power::{SetThreadExecutionState, ES_CONTINUOUS};
+            SetThreadExecutionState(ES_CONTINUOUS);
+        }
+        #[cfg(not(windows))]
+        if let Some(mut child) = self.child.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+/// 启动防休眠看守线程
+pub fn spawn_power_guard(app: AppHandle) {
+    std::thread::spawn(move || {
+        // 当前持有的防休眠请求及其是否保持显示器常亮
+        let mut lock: Option<(WakeLock, bool)> = None;
+        loop {
+            std::thread::sleep(CHECK_INTERVAL);
+            let Some(state) = app.try_state::<Arc<MaaState>>() else {
+                continue;
+            };
+            let settings = load_settings().unwrap_or_default();
+            // 定时唤醒后、运行开始前同样保持唤醒，避免系统在无人值守唤醒后很快再次睡眠
+            let wanted = (settings.prevent_sleep && any_running(&state))
+                || super::wake_timer::holding_awake();
+
+            // 设置变化时重新获取
+            if lock
+                .as_ref()
+                .is_some_and(|(_, display)| *display != settings.keep_display_on)
+            {
+                lock = None;
+            }
+
+            match (wanted, lock.is_some()) {
+                (true, false) => match WakeLock::acquire(&settings) {
+                    Ok(l) => {
+                        info!("[power] Tasks running, preventing system sleep");
+                        lock = Some((l, settings.keep_display_on));
+                    }
+                    Err(e) => warn!("[power] Failed to prevent system sleep: {}", e),
+                },
+                (false, true) => {
+                    lock = None;
+                    info!("[power] Tasks finished, system sleep allowed");
+                }
+                _ => {}
+            }
+        }
+    });
+}
+
+/// 获取防休眠设置
+#[tauri::command]
+pub fn get_power_settings() -> Result<PowerSettings, MxuError> {
+    Ok(load_settings()?)
+}
+
+/// 设置防休眠（数秒内生效）
+#[tauri::command]
+pub fn set_power_settings(settings: PowerSettings) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    let path = config_path()?;
+    save_json_config(&path, &settings)
+}
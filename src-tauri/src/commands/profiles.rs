@@ -0,0 +1,305 @@
+//! 配置档案管理
+//!
+//! 在数据目录 profiles/ 下以 JSON 文件保存命名的配置集合，
+//! 支持创建、克隆、重命名、删除以及导入/导出
+
+use log::info;
+use std::path::{Path, PathBuf};
+
+use super::error::MxuError;
+use super::i18n::ErrorCode;
+use super::types::{Profile, ProfileContent, ProfileSummary, PROFILE_VERSION};
+use super::utils::get_app_data_dir;
+
+/// 获取配置档案目录
+fn get_profiles_dir() -> Result<PathBuf, String> {
+    let dir = get_app_data_dir()?.join("profiles");
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("无法创建配置档案目录 [{}]: {}", dir.display(), e))?;
+    Ok(dir)
+}
+
+/// 获取配置档案文件路径，校验 id 只包含安全字符
+fn profile_path(id: &str) -> Result<PathBuf, String> {
+    if id.is_empty()
+        || !id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!("非法的配置档案 ID: {}", id));
+    }
+    Ok(get_profiles_dir()?.join(format!("{}.json", id)))
+}
+
+/// 生成新的配置档案 ID
+fn new_profile_id() -> Result<String, String> {
+    let base = chrono::Local::now().format("%Y%m%d%H%M%S%3f").to_string();
+    let mut id = base.clone();
+    let mut counter = 1;
+    while profile_path(&id)?.exists() {
+        id = format!("{}-{}", base, counter);
+        counter += 1;
+    }
+    Ok(id)
+}
+
+fn now_string() -> String {
+    chrono::Local::now().to_rfc3339()
+}
+
+/// 校验档案名称
+fn validate_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("配置档案名称不能为空".to_string());
+    }
+    Ok(name.to_string())
+}
+
+/// 读取并解析档案文件
+fn read_profile_file(path: &Path) -> Result<Profile, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("无法读取配置档案 [{}]: {}", path.display(), e))?;
+    let profile: Profile = serde_json::from_str(&content)
+        .map_err(|e| format!("无法解析配置档案 [{}]: {}", path.display(), e))?;
+    if profile.version > PROFILE_VERSION {
+        return Err(format!(
+            "配置档案版本过新: {}（最高支持 {}）",
+            profile.version, PROFILE_VERSION
+        ));
+    }
+    Ok(profile)
+}
+
+/// 文件是否为可导入的配置档案（拖放安装时用于识别文件类型）
+pub fn is_profile_file(path: &Path) -> bool {
+    read_profile_file(path).is_ok()
+}
+
+/// 写入档案文件
+fn write_profile(profile: &Profile) -> Result<(), String> {
+    let path = profile_path(&profile.id)?;
+    let content =
+        serde_json::to_string_pretty(profile).map_err(|e| format!("无法序列化配置档案: {}", e))?;
+    std::fs::write(&path, content)
+        .map_err(|e| format!("无法写入配置档案 [{}]: {}", path.display(), e))?;
+    crate::tray::mark_profiles_changed();
+    Ok(())
+}
+
+/// 列出所有配置档案
+#[tauri::command]
+pub fn profile_list() -> Result<Vec<ProfileSummary>, MxuError> {
+    let dir = get_profiles_dir()?;
+    let entries = std::fs::read_dir(&dir).map_err(|e| format!("无法读取配置档案目录: {}", e))?;
+
+    let mut profiles: Vec<ProfileSummary> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|p| match read_profile_file(&p) {
+            Ok(profile) => Some(ProfileSummary {
+                id: profile.id,
+                name: profile.name,
+                updated_at: profile.updated_at,
+            }),
+            Err(e) => {
+                log::warn!("[profiles] Skipping invalid profile: {}", e);
+                None
+            }
+        })
+        .collect();
+
+    profiles.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(profiles)
+}
+
+/// 按 ID 或名称查找配置档案
+pub fn find_summary(id_or_name: &str) -> Result<ProfileSummary, String> {
+    profile_list()?
+        .into_iter()
+        .find(|p| p.id == id_or_name || p.name == id_or_name)
+        .ok_or_else(|| format!("配置档案不存在: {}", id_or_name))
+}
+
+/// 读取配置档案
+#[tauri::command]
+pub fn profile_get(id: String) -> Result<Profile, MxuError> {
+    Ok(read_profile_file(&profile_path(&id)?)?)
+}
+
+/// 创建配置档案（用户在本机创建，其中的钩子视为已确认）
+#[tauri::command]
+pub fn profile_create(name: String, content: ProfileContent) -> Result<Profile, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    super::hooks::confirm(&content.hooks)?;
+    Ok(create_profile(name, content)?)
+}
+
+/// 写入新的配置档案，不改变其中钩子的确认状态
+fn create_profile(name: String, content: ProfileContent) -> Result<Profile, String> {
+    let now = now_string();
+    let profile = Profile {
+        version: PROFILE_VERSION,
+        id: new_profile_id()?,
+        name: validate_name(&name)?,
+        created_at: now.clone(),
+        updated_at: now,
+        content,
+    };
+    write_profile(&profile)?;
+    info!(
+        "[profiles] Created profile {} ({})",
+        profile.name, profile.id
+    );
+    Ok(profile)
+}
+
+/// 保存集群主实例分发的配置档案：ID 加 cluster- 前缀，重复分发时覆盖，
+/// 其中的钩子需在本机确认后才会执行
+pub fn save_dispatched(profile: Profile) -> Result<Profile, String> {
+    if profile.version > PROFILE_VERSION {
+        return Err(format!(
+            "配置档案版本过新: {}（最高支持 {}）",
+            profile.version, PROFILE_VERSION
+        ));
+    }
+    let id = format!("cluster-{}", profile.id);
+    let created_at = read_profile_file(&profile_path(&id)?)
+        .map(|p| p.created_at)
+        .unwrap_or_else(|_| now_string());
+    let profile = Profile {
+        version: PROFILE_VERSION,
+        id,
+        name: validate_name(&profile.name)?,
+        created_at,
+        updated_at: now_string(),
+        content: profile.content,
+    };
+    write_profile(&profile)?;
+    Ok(profile)
+}
+
+/// 更新配置档案内容
+#[tauri::command]
+pub fn profile_save(id: String, content: ProfileContent) -> Result<Profile, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    let mut profile = profile_get(id)?;
+    super::hooks::confirm_edited(&profile.content.hooks, &content.hooks)?;
+    profile.version = PROFILE_VERSION;
+    profile.content = content;
+    profile.updated_at = now_string();
+    write_profile(&profile)?;
+    Ok(profile)
+}
+
+/// 设置或清除（value 为空）配置档案标记，供任务的 include_if 条件引用
+#[tauri::command]
+pub fn profile_set_flag(
+    id: String,
+    name: String,
+    value: Option<serde_json::Value>,
+) -> Result<Profile, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(MxuError::new(ErrorCode::EmptyName));
+    }
+    let mut profile = profile_get(id)?;
+    match value {
+        Some(value) => {
+            profile.content.flags.insert(name.clone(), value);
+        }
+        None => {
+            profile.content.flags.remove(&name);
+        }
+    }
+    profile.updated_at = now_string();
+    write_profile(&profile)?;
+    info!("[profiles] Updated flag {} of profile {}", name, profile.id);
+    Ok(profile)
+}
+
+/// 固定或取消固定（version 为空）配置档案使用的 MaaFramework 版本
+#[tauri::command]
+pub fn profile_set_maafw_version(id: String, version: Option<String>) -> Result<Profile, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    let version = version
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty());
+    let mut profile = profile_get(id)?;
+    profile.content.maafw_version = version;
+    profile.updated_at = now_string();
+    write_profile(&profile)?;
+    info!(
+        "[profiles] Profile {} pinned MaaFramework version: {:?}",
+        profile.id, profile.content.maafw_version
+    );
+    Ok(profile)
+}
+
+/// 克隆配置档案
+#[tauri::command]
+pub fn profile_clone(id: String, name: String) -> Result<Profile, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    let source = profile_get(id)?;
+    Ok(create_profile(name, source.content)?)
+}
+
+/// 重命名配置档案
+#[tauri::command]
+pub fn profile_rename(id: String, name: String) -> Result<Profile, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    let mut profile = profile_get(id)?;
+    profile.name = validate_name(&name)?;
+    profile.updated_at = now_string();
+    write_profile(&profile)?;
+    info!(
+        "[profiles] Renamed profile {} to {}",
+        profile.id, profile.name
+    );
+    Ok(profile)
+}
+
+/// 删除配置档案
+#[tauri::command]
+pub fn profile_delete(id: String) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    let path = profile_path(&id)?;
+    if !path.exists() {
+        return Err(MxuError::with_args(ErrorCode::ProfileNotFound, [&id]));
+    }
+    std::fs::remove_file(&path).map_err(|e| format!("无法删除配置档案: {}", e))?;
+    info!("[profiles] Deleted profile {}", id);
+    crate::tray::mark_profiles_changed();
+    Ok(())
+}
+
+/// 导出配置档案到指定路径
+#[tauri::command]
+pub fn profile_export(id: String, dest_path: String) -> Result<(), MxuError> {
+    let profile = profile_get(id)?;
+    let content =
+        serde_json::to_string_pretty(&profile).map_err(|e| format!("无法序列化配置档案: {}", e))?;
+    std::fs::write(&dest_path, content).map_err(|e| format!("无法导出配置档案: {}", e))?;
+    info!(
+        "[profiles] Exported profile {} to {}",
+        profile.id, dest_path
+    );
+    Ok(())
+}
+
+/// 从文件导入配置档案，始终分配新 ID 以避免覆盖现有档案
+/// 导入的钩子需经 hook_confirm 确认后才会执行
+#[tauri::command]
+pub fn profile_import(src_path: String) -> Result<Profile, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    let imported = read_profile_file(Path::new(&src_path))?;
+    let profile = create_profile(imported.name, imported.content)?;
+    let pending = super::hooks::unconfirmed(&profile.content.hooks).len();
+    info!(
+        "[profiles] Imported profile from {} ({} hook(s) pending confirmation)",
+        src_path, pending
+    );
+    Ok(profile)
+}
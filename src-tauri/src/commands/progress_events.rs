@@ -0,0 +1,142 @@
+//! 结构化任务进度事件
+//!
+//! 将 MaaFramework 回调（maa-callback 中的原始消息与 JSON 字符串）转换为带版本号的
+//! task-progress 事件，前端与外部消费者无需再解析原始 JSON。
+//!
+//! 事件格式（version = 1）：
+//! `{ "version": 1, "timestamp": <ms>, "type": "<类型>", ...字段 }`
+//! - `RunStarted { task_id, entry }`：Tasker.Task.Starting
+//! - `NodeRecognized { task_id, reco_id, node, hit }`：Node.Recognition.Succeeded/Failed
+//! - `NodeActionDone { task_id, action_id, node, success }`：Node.Action.Succeeded/Failed
+//! - `RunCompleted { task_id, entry }`：Tasker.Task.Succeeded
+//! - `RunFailed { task_id, entry, reason }`：Tasker.Task.Failed，reason 由该任务最后一个失败节点推断
+//!
+//! 原始 maa-callback 事件保持不变以兼容现有前端
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde_json::Value;
+use tauri::AppHandle;
+
+use super::types::{
+    TaskFailureReason, TaskProgressEvent, TaskProgressKind, TASK_PROGRESS_SCHEMA_VERSION,
+};
+
+/// 各任务最近一次失败的节点，用于推断 RunFailed 原因
+static LAST_FAILURE: Mutex<Option<HashMap<i64, TaskFailureReason>>> = Mutex::new(None);
+
+fn remember_failure(task_id: i64, reason: TaskFailureReason) {
+    if let Ok(mut failures) = LAST_FAILURE.lock() {
+        failures
+            .get_or_insert_with(HashMap::new)
+            .insert(task_id, reason);
+    }
+}
+
+fn take_failure(task_id: i64) -> Option<TaskFailureReason> {
+    LAST_FAILURE.lock().ok()?.as_mut()?.remove(&task_id)
+}
+
+/// 将回调消息转换为进度事件，无需转换的消息返回 None
+fn convert(message: &str, details: &Value) -> Option<TaskProgressKind> {
+    let task_id = details.get("task_id").and_then(|v| v.as_i64())?;
+    let text = |key: &str| {
+        details
+            .get(key)
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string()
+    };
+    let id = |key: &str| {
+        details
+            .get(key)
+            .and_then(|v| v.as_i64())
+            .unwrap_or_default()
+    };
+
+    let event = match message {
+        "Tasker.Task.Starting" => TaskProgressKind::RunStarted {
+            task_id,
+            entry: text("entry"),
+        },
+        "Tasker.Task.Succeeded" => {
+            take_failure(task_id);
+            TaskProgressKind::RunCompleted {
+                task_id,
+                entry: text("entry"),
+            }
+        }
+        "Tasker.Task.Failed" => TaskProgressKind::RunFailed {
+            task_id,
+            entry: text("entry"),
+            reason: take_failure(task_id).unwrap_or(TaskFailureReason::Unknown),
+        },
+        "Node.Recognition.Succeeded" | "Node.Recognition.Failed" => {
+            TaskProgressKind::NodeRecognized {
+                task_id,
+                reco_id: id("reco_id"),
+                node: text("name"),
+                hit: message.ends_with("Succeeded"),
+            }
+        }
+        "Node.Action.Succeeded" | "Node.Action.Failed" => {
+            let success = message.ends_with("Succeeded");
+            if !success {
+                remember_failure(
+                    task_id,
+                    TaskFailureReason::ActionFailed { node: text("name") },
+                );
+            }
+            TaskProgressKind::NodeActionDone {
+                task_id,
+                action_id: id("action_id"),
+                node: text("name"),
+                success,
+            }
+        }
+        "Node.NextList.Failed" => {
+            remember_failure(
+                task_id,
+                TaskFailureReason::NextListFailed { node: text("name") },
+            );
+            return None;
+        }
+        "Node.PipelineNode.Failed" => {
+            // 已记录更具体的原因时不覆盖
+            let known = LAST_FAILURE
+                .lock()
+                .ok()
+                .and_then(|f| f.as_ref().map(|f| f.contains_key(&task_id)))
+                .unwrap_or(false);
+            if !known {
+                remember_failure(
+                    task_id,
+                    TaskFailureReason::NodeFailed { node: text("name") },
+                );
+            }
+            return None;
+        }
+        _ => return None,
+    };
+    Some(event)
+}
+
+/// 回调钩子：转换并发送 task-progress 事件
+pub fn handle_callback(app: &AppHandle, message: &str, details: &str) {
+    if !message.starts_with("Tasker.Task.") && !message.starts_with("Node.") {
+        return;
+    }
+    let Ok(value) = serde_json::from_str::<Value>(details) else {
+        return;
+    };
+    let Some(event) = convert(message, &value) else {
+        return;
+    };
+    let payload = TaskProgressEvent {
+        version: TASK_PROGRESS_SCHEMA_VERSION,
+        timestamp: chrono::Utc::now().timestamp_millis(),
+        event,
+    };
+    super::background_mode::emit(app, "task-progress", payload);
+}
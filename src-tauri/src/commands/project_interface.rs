@@ -0,0 +1,716 @@
+//! 项目接口（interface.json）解析
+//!
+//! 解析 MaaFramework ProjectInterface V2（支持 JSONC 注释与尾逗号）：合并 import 的任务与选项、
+//! 按平台过滤控制器、加载翻译文件，并校验任务、选项、控制器与 Agent 定义，
+//! 为任务解析选项默认值（含默认 case 的子选项），供前端直接使用而不必在 TypeScript 中重复解析。
+//!
+//! 另按选项的嵌套关系（选中某个 case 时才显示其子选项）与控制器限制求值选项的生效状态，
+//! 并生成选项组合对应的 pipeline_override，规则与前端 pipelineOverride.ts 保持一致
+
+use log::{info, warn};
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use std::sync::Mutex;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::error::MxuError;
+use super::file_ops::resolve_local_file_path;
+use super::types::{
+    InterfaceAgent, InterfaceCase, InterfaceLoadResult, InterfaceOption, InterfaceOptionType,
+    InterfaceTask, InterfaceTaskInfo, OptionResolution, ProjectInterface, TaskOptionResolution,
+};
+
+/// 默认的 interface.json 路径（相对于 exe 目录）
+const DEFAULT_INTERFACE_PATH: &str = "interface.json";
+
+/// 已知的控制器类型
+const CONTROLLER_TYPES: &[&str] = &["Adb", "Win32", "PlayCover", "Gamepad"];
+
+/// 开关选项表示开启/关闭的 case 名称
+const YES_CASE_NAMES: &[&str] = &["Yes", "yes", "Y", "y"];
+const NO_CASE_NAMES: &[&str] = &["No", "no", "N", "n"];
+
+/// 最近一次加载的结果
+static LOADED: Mutex<Option<InterfaceLoadResult>> = Mutex::new(None);
+
+/// 可导入的 PI 文件（只导入 task 与 option）
+#[derive(Deserialize, Default)]
+struct ImportedInterface {
+    #[serde(default)]
+    task: Vec<InterfaceTask>,
+    #[serde(default)]
+    option: BTreeMap<String, InterfaceOption>,
+}
+
+/// 移除 JSONC 中的注释与尾逗号，转换为标准 JSON
+pub fn strip_jsonc(content: &str) -> String {
+    let chars: Vec<char> = content.chars().collect();
+    let mut out = String::with_capacity(content.len());
+    let mut i = 0;
+    let mut in_string = false;
+    while i < chars.len() {
+        let c = chars[i];
+        if in_string {
+            out.push(c);
+            if c == '\\' && i + 1 < chars.len() {
+                out.push(chars[i + 1]);
+                i += 1;
+            } else if c == '"' {
+                in_string = false;
+            }
+            i += 1;
+            continue;
+        }
+        match (c, chars.get(i + 1)) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+                i += 1;
+            }
+            ('/', Some('/')) => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            ('/', Some('*')) => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    i += 1;
+                }
+                i += 2;
+            }
+            (',', _) => {
+                // 尾逗号：其后（跳过空白与注释）紧跟 } 或 ]
+                let mut j = i + 1;
+                loop {
+                    while j < chars.len() && chars[j].is_whitespace() {
+                        j += 1;
+                    }
+                    match (chars.get(j), chars.get(j + 1)) {
+                        (Some('/'), Some('/')) => {
+                            while j < chars.len() && chars[j] != '\n' {
+                                j += 1;
+                            }
+                        }
+                        (Some('/'), Some('*')) => {
+                            j += 2;
+                            while j < chars.len()
+                                && !(chars[j] == '*' && chars.get(j + 1) == Some(&'/'))
+                            {
+                                j += 1;
+                            }
+                            j += 2;
+                        }
+                        _ => break,
+                    }
+                }
+                if !matches!(chars.get(j), Some('}') | Some(']')) {
+                    out.push(c);
+                }
+                i += 1;
+            }
+            _ => {
+                out.push(c);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+/// 读取并解析 JSONC 文件
+fn read_jsonc<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("无法读取文件 [{}]: {}", path.display(), e))?;
+    serde_json::from_str(&strip_jsonc(&content))
+        .map_err(|e| format!("无法解析文件 [{}]: {}", path.display(), e))
+}
+
+/// 当前平台支持的控制器类型
+fn controller_supported(kind: &str) -> bool {
+    match kind {
+        "Win32" | "Gamepad" => cfg!(windows),
+        "PlayCover" => cfg!(target_os = "macos"),
+        _ => true,
+    }
+}
+
+/// 将 agent 字段（单个对象或数组）标准化为数组
+fn parse_agents(pi: &ProjectInterface, warnings: &mut Vec<String>) -> Vec<InterfaceAgent> {
+    let items = match pi.extra.get("agent") {
+        None | Some(Value::Null) => return Vec::new(),
+        Some(Value::Array(items)) => items.clone(),
+        Some(item) => vec![item.clone()],
+    };
+    items
+        .into_iter()
+        .enumerate()
+        .filter_map(
+            |(idx, item)| match serde_json::from_value::<InterfaceAgent>(item) {
+                Ok(agent) if agent.child_exec.trim().is_empty() => {
+                    warnings.push(format!("Agent #{} 未指定 child_exec", idx));
+                    None
+                }
+                Ok(agent) => Some(agent),
+                Err(e) => {
+                    warnings.push(format!("Agent #{} 定义无效: {}", idx, e));
+                    None
+                }
+            },
+        )
+        .collect()
+}
+
+fn check_duplicates<'a>(
+    kind: &str,
+    names: impl Iterator<Item = &'a String>,
+    warnings: &mut Vec<String>,
+) {
+    let mut seen = HashSet::new();
+    for name in names {
+        if !seen.insert(name) {
+            warnings.push(format!("{} 名称重复: {}", kind, name));
+        }
+    }
+}
+
+fn check_option_refs(
+    owner: &str,
+    refs: &[String],
+    pi: &ProjectInterface,
+    warnings: &mut Vec<String>,
+) {
+    for name in refs {
+        if !pi.option.contains_key(name) {
+            warnings.push(format!("{} 引用了未定义的选项: {}", owner, name));
+        }
+    }
+}
+
+/// 校验 interface，返回发现的问题
+fn validate(pi: &ProjectInterface) -> Vec<String> {
+    let mut warnings = Vec::new();
+    check_duplicates("任务", pi.task.iter().map(|t| &t.name), &mut warnings);
+    check_duplicates(
+        "控制器",
+        pi.controller.iter().map(|c| &c.name),
+        &mut warnings,
+    );
+    check_duplicates("资源", pi.resource.iter().map(|r| &r.name), &mut warnings);
+
+    let controllers: HashSet<&String> = pi.controller.iter().map(|c| &c.name).collect();
+    let resources: HashSet<&String> = pi.resource.iter().map(|r| &r.name).collect();
+
+    for controller in &pi.controller {
+        if !CONTROLLER_TYPES.contains(&controller.kind.as_str()) {
+            warnings.push(format!(
+                "控制器 {} 的类型未知: {}",
+                controller.name, controller.kind
+            ));
+        }
+    }
+    for resource in &pi.resource {
+        if resource.path.is_empty() {
+            warnings.push(format!("资源 {} 未指定 path", resource.name));
+        }
+        check_option_refs(
+            &format!("资源 {}", resource.name),
+            &resource.option,
+            pi,
+            &mut warnings,
+        );
+    }
+    for task in &pi.task {
+        let owner = format!("任务 {}", task.name);
+        if task.entry.trim().is_empty() {
+            warnings.push(format!("{} 未指定 entry", owner));
+        }
+        check_option_refs(&owner, &task.option, pi, &mut warnings);
+        for name in task.controller.iter().flatten() {
+            if !controllers.contains(name) {
+                warnings.push(format!("{} 引用了未定义的控制器: {}", owner, name));
+            }
+        }
+        for name in task.resource.iter().flatten() {
+            if !resources.contains(name) {
+                warnings.push(format!("{} 引用了未定义的资源: {}", owner, name));
+            }
+        }
+    }
+
+    for (name, option) in &pi.option {
+        let owner = format!("选项 {}", name);
+        match option.kind {
+            InterfaceOptionType::Select | InterfaceOptionType::Switch => {
+                if option.cases.is_empty() {
+                    warnings.push(format!("{} 没有 case", owner));
+                }
+                if let Some(default) = &option.default_case {
+                    if !option.cases.iter().any(|c| &c.name == default) {
+                        warnings.push(format!("{} 的 default_case 不存在: {}", owner, default));
+                    }
+                }
+                if option.kind == InterfaceOptionType::Switch
+                    && (find_switch_case(&option.cases, true).is_none()
+                        || find_switch_case(&option.cases, false).is_none())
+                {
+                    warnings.push(format!("{} 的 case 须为 Yes 与 No", owner));
+                }
+                for case in &option.cases {
+                    check_option_refs(
+                        &format!("{} 的 case {}", owner, case.name),
+                        &case.option,
+                        pi,
+                        &mut warnings,
+                    );
+                }
+            }
+            InterfaceOptionType::Input => {
+                if option.inputs.is_empty() {
+                    warnings.push(format!("{} 没有输入项", owner));
+                }
+                for input in &option.inputs {
+                    if let Some(verify) = &input.verify {
+                        if let Err(e) = regex::Regex::new(verify) {
+                            warnings.push(format!(
+                                "{} 的输入项 {} 校验正则无效: {}",
+                                owner, input.name, e
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+    }
+    warnings
+}
+
+fn find_switch_case(cases: &[InterfaceCase], on: bool) -> Option<&InterfaceCase> {
+    let names = if on { YES_CASE_NAMES } else { NO_CASE_NAMES };
+    cases.iter().find(|c| names.contains(&c.name.as_str()))
+}
+
+/// 选项的默认值（格式与前端 OptionValue 一致）及默认选中的 case
+fn default_value(option: &InterfaceOption) -> (Value, Option<&InterfaceCase>) {
+    let default_case = option
+        .default_case
+        .as_deref()
+        .or(option.cases.first().map(|c| c.name.as_str()));
+    match option.kind {
+        InterfaceOptionType::Input => {
+            let values: BTreeMap<&str, &str> = option
+                .inputs
+                .iter()
+                .map(|i| (i.name.as_str(), i.default.as_deref().unwrap_or("")))
+                .collect();
+            (json!({ "type": "input", "values": values }), None)
+        }
+        InterfaceOptionType::Switch => {
+            let on = YES_CASE_NAMES.contains(&default_case.unwrap_or("Yes"));
+            (
+                json!({ "type": "switch", "value": on }),
+                find_switch_case(&option.cases, on),
+            )
+        }
+        InterfaceOptionType::Select => {
+            let case_name = default_case.unwrap_or("");
+            (
+                json!({ "type": "select", "caseName": case_name }),
+                option.cases.iter().find(|c| c.name == case_name),
+            )
+        }
+    }
+}
+
+/// 递归解析选项默认值（包括默认 case 的子选项）
+fn resolve_defaults(keys: &[String], pi: &ProjectInterface, result: &mut BTreeMap<String, Value>) {
+    for key in keys {
+        // 已解析过的跳过（避免循环引用）
+        if result.contains_key(key) {
+            continue;
+        }
+        let Some(option) = pi.option.get(key) else {
+            continue;
+        };
+        let (value, selected) = default_value(option);
+        result.insert(key.clone(), value);
+        if let Some(case) = selected {
+            resolve_defaults(&case.option, pi, result);
+        }
+    }
+}
+
+/// 解析 interface.json：合并 import、过滤控制器、加载翻译并校验
+pub fn load(path: &str) -> Result<InterfaceLoadResult, String> {
+    let file = resolve_local_file_path(path)?;
+    let base_dir = file
+        .parent()
+        .ok_or_else(|| format!("无效的 interface 路径: {}", path))?
+        .to_path_buf();
+    let mut pi: ProjectInterface = read_jsonc(&file)?;
+    if pi.interface_version != 2 {
+        return Err(format!(
+            "不支持的 interface 版本: {}，仅支持 version 2",
+            pi.interface_version
+        ));
+    }
+
+    let mut warnings = Vec::new();
+    for import in pi.import.clone() {
+        match read_jsonc::<ImportedInterface>(&base_dir.join(&import)) {
+            Ok(imported) => {
+                info!(
+                    "[project_interface] Imported {} task(s) and {} option(s) from {}",
+                    imported.task.len(),
+                    imported.option.len(),
+                    import
+                );
+                pi.task.extend(imported.task);
+                pi.option.extend(imported.option);
+            }
+            Err(e) => warnings.push(format!("导入文件加载失败: {}", e)),
+        }
+    }
+
+    // 在过滤控制器之前校验，避免任务引用其他平台的控制器时误报
+    warnings.extend(validate(&pi));
+
+    let before = pi.controller.len();
+    pi.controller.retain(|c| controller_supported(&c.kind));
+    if pi.controller.len() < before {
+        info!(
+            "[project_interface] Filtered out {} controller(s) unsupported on this platform",
+            before - pi.controller.len()
+        );
+    }
+
+    let mut translations = BTreeMap::new();
+    for (lang, relative) in &pi.languages {
+        match read_jsonc::<BTreeMap<String, String>>(&base_dir.join(relative)) {
+            Ok(map) => {
+                translations.insert(lang.clone(), map);
+            }
+            Err(e) => warnings.push(format!("翻译文件加载失败 [{}]: {}", lang, e)),
+        }
+    }
+
+    let agents = parse_agents(&pi, &mut warnings);
+    for warning in &warnings {
+        warn!("[project_interface] {}", warning);
+    }
+    info!(
+        "[project_interface] Loaded {}: {} task(s), {} option(s), {} controller(s), {} warning(s)",
+        path,
+        pi.task.len(),
+        pi.option.len(),
+        pi.controller.len(),
+        warnings.len()
+    );
+
+    Ok(InterfaceLoadResult {
+        interface: pi,
+        agents,
+        translations,
+        base_path: base_dir.to_string_lossy().to_string(),
+        warnings,
+    })
+}
+
+/// 选项值中选中的 case 名称；与选项类型不符时返回 None
+fn selected_case_name(option: &InterfaceOption, value: &Value) -> Option<String> {
+    match (option.kind, value.get("type").and_then(|t| t.as_str())) {
+        (InterfaceOptionType::Input, _) => None,
+        (_, Some("switch")) => {
+            let on = value.get("value").and_then(|v| v.as_bool())?;
+            Some(
+                find_switch_case(&option.cases, on)
+                    .map(|c| c.name.clone())
+                    .unwrap_or_else(|| if on { "Yes" } else { "No" }.to_string()),
+            )
+        }
+        (_, Some("select")) => value
+            .get("caseName")
+            .and_then(|c| c.as_str())
+            .map(str::to_string),
+        _ => None,
+    }
+}
+
+/// 将输入值代入输入型选项的 pipeline_override（{输入名} 占位符）
+fn substitute_inputs(
+    option: &InterfaceOption,
+    template: &Value,
+    value: &Value,
+    key: &str,
+    issues: &mut Vec<String>,
+) -> Option<Value> {
+    let mut text = template.to_string();
+    for input in &option.inputs {
+        let raw = value
+            .get("values")
+            .and_then(|v| v.get(&input.name))
+            .and_then(|v| v.as_str())
+            .or(input.default.as_deref())
+            .unwrap_or("");
+        if let Some(verify) = &input.verify {
+            if regex::Regex::new(verify).is_ok_and(|re| !re.is_match(raw)) {
+                issues.push(format!(
+                    "选项 {} 的输入项 {} 未通过校验: {:?}",
+                    key, input.name, raw
+                ));
+            }
+        }
+        let placeholder = format!("{{{}}}", input.name);
+        let quoted = format!("\"{}\"", placeholder);
+        let pipeline_type = input
+            .extra
+            .get("pipeline_type")
+            .and_then(|t| t.as_str())
+            .unwrap_or("string");
+        match pipeline_type {
+            "int" => {
+                let number = if raw.is_empty() { "0" } else { raw };
+                text = text.replace(&quoted, number).replace(&placeholder, number);
+            }
+            "bool" => {
+                let on = matches!(raw.to_lowercase().as_str(), "true" | "1" | "yes" | "y");
+                let literal = if on { "true" } else { "false" };
+                text = text
+                    .replace(&quoted, literal)
+                    .replace(&placeholder, literal);
+            }
+            _ => text = text.replace(&placeholder, raw),
+        }
+    }
+    match serde_json::from_str(&text) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            issues.push(format!(
+                "选项 {} 代入输入值后的 pipeline_override 无效: {}",
+                key, e
+            ));
+            None
+        }
+    }
+}
+
+/// 选项求值的上下文
+struct Resolver<'a> {
+    pi: &'a ProjectInterface,
+    selections: &'a BTreeMap<String, Value>,
+    controller: Option<&'a str>,
+    overrides: Vec<Value>,
+    options: Vec<OptionResolution>,
+    issues: Vec<String>,
+}
+
+impl Resolver<'_> {
+    /// 求值单个选项及选中 case 的子选项，收集 pipeline_override
+    fn visit(&mut self, key: &str, parent: Option<&str>) {
+        if self.options.iter().any(|o| o.key == key) {
+            return;
+        }
+        let pi = self.pi;
+        let Some(option) = pi.option.get(key) else {
+            self.issues.push(format!("未定义的选项: {}", key));
+            return;
+        };
+        let compatible = match (option.extra.get("controller"), self.controller) {
+            (Some(Value::Array(list)), Some(controller)) if !list.is_empty() => {
+                list.iter().any(|c| c.as_str() == Some(controller))
+            }
+            _ => true,
+        };
+
+        let (default, _) = default_value(option);
+        let mut value = self
+            .selections
+            .get(key)
+            .cloned()
+            .unwrap_or_else(|| default.clone());
+        let case_name = match option.kind {
+            InterfaceOptionType::Input => None,
+            _ => match selected_case_name(option, &value) {
+                Some(name) => Some(name),
+                None => {
+                    self.issues
+                        .push(format!("选项 {} 的值与类型不符，使用默认值", key));
+                    value = default.clone();
+                    selected_case_name(option, &value)
+                }
+            },
+        };
+        if option.kind == InterfaceOptionType::Input
+            && value.get("type").and_then(|t| t.as_str()) != Some("input")
+        {
+            self.issues
+                .push(format!("选项 {} 的值与类型不符，使用默认值", key));
+            value = default;
+        }
+        if !compatible {
+            self.issues.push(format!(
+                "选项 {} 不适用于控制器 {}",
+                key,
+                self.controller.unwrap_or_default()
+            ));
+        }
+
+        self.options.push(OptionResolution {
+            key: key.to_string(),
+            parent: parent.map(str::to_string),
+            value: value.clone(),
+            selected_case: case_name.clone(),
+            compatible,
+        });
+
+        match option.kind {
+            InterfaceOptionType::Input => {
+                if let Some(template) = option
+                    .extra
+                    .get("pipeline_override")
+                    .filter(|v| !v.is_null())
+                {
+                    if let Some(patch) =
+                        substitute_inputs(option, template, &value, key, &mut self.issues)
+                    {
+                        self.overrides.push(patch);
+                    }
+                }
+            }
+            _ => {
+                let Some(case_name) = case_name else {
+                    return;
+                };
+                let Some(case) = option.cases.iter().find(|c| c.name == case_name) else {
+                    self.issues
+                        .push(format!("选项 {} 没有名为 {} 的 case", key, case_name));
+                    return;
+                };
+                if let Some(patch) = case.extra.get("pipeline_override").filter(|v| !v.is_null()) {
+                    self.overrides.push(patch.clone());
+                }
+                for nested in &case.option {
+                    self.visit(nested, Some(key));
+                }
+            }
+        }
+    }
+}
+
+/// 求值任务的选项组合：生效的选项、对应的 pipeline_override（JSON 数组）与发现的问题
+pub fn resolve_task(
+    pi: &ProjectInterface,
+    task_name: &str,
+    selections: &BTreeMap<String, Value>,
+    controller: Option<&str>,
+) -> Result<TaskOptionResolution, String> {
+    let task = pi
+        .task
+        .iter()
+        .find(|t| t.name == task_name)
+        .ok_or_else(|| format!("interface 中不存在任务: {}", task_name))?;
+    let mut resolver = Resolver {
+        pi,
+        selections,
+        controller,
+        overrides: Vec::new(),
+        options: Vec::new(),
+        issues: Vec::new(),
+    };
+    if let Some(patch) = task.extra.get("pipeline_override").filter(|v| !v.is_null()) {
+        resolver.overrides.push(patch.clone());
+    }
+    for key in &task.option {
+        resolver.visit(key, None);
+    }
+
+    // 选中 case 之外的子选项不生效
+    let inactive = selections
+        .keys()
+        .filter(|k| !resolver.options.iter().any(|o| &o.key == *k))
+        .cloned()
+        .collect();
+    Ok(TaskOptionResolution {
+        task: task.name.clone(),
+        entry: task.entry.clone(),
+        pipeline_override: Value::Array(resolver.overrides).to_string(),
+        options: resolver.options,
+        inactive,
+        issues: resolver.issues,
+    })
+}
+
+/// 在已加载（或自动加载默认路径）的 interface 上执行操作
+pub fn with_interface<T>(f: impl FnOnce(&ProjectInterface) -> T) -> Result<T, String> {
+    let mut loaded = LOADED.lock().map_err(|e| e.to_string())?;
+    if loaded.is_none() {
+        *loaded = Some(load(DEFAULT_INTERFACE_PATH)?);
+    }
+    loaded
+        .as_ref()
+        .map(|result| f(&result.interface))
+        .ok_or_else(|| "interface 未加载".to_string())
+}
+
+/// 加载 interface.json（path 相对于 exe 目录，默认 interface.json），结果会被缓存供 interface_get_tasks 使用
+#[tauri::command]
+pub fn interface_load(path: Option<String>) -> Result<InterfaceLoadResult, MxuError> {
+    let result = load(path.as_deref().unwrap_or(DEFAULT_INTERFACE_PATH))?;
+    *LOADED.lock().map_err(|e| e.to_string())? = Some(result.clone());
+    Ok(result)
+}
+
+/// 获取任务列表及其选项默认值
+///
+/// 指定 controller / resource 时按任务声明的适用范围计算 compatible，
+/// 选项默认值包含资源声明的选项；尚未加载时自动加载默认的 interface.json
+#[tauri::command]
+pub fn interface_get_tasks(
+    controller: Option<String>,
+    resource: Option<String>,
+) -> Result<Vec<InterfaceTaskInfo>, MxuError> {
+    Ok(with_interface(|pi| {
+        let resource_options: &[String] = resource
+            .as_ref()
+            .and_then(|name| pi.resource.iter().find(|r| &r.name == name))
+            .map_or(&[], |r| r.option.as_slice());
+
+        pi.task
+            .iter()
+            .map(|task| {
+                let allows = |list: &Option<Vec<String>>, selected: &Option<String>| match (
+                    list, selected,
+                ) {
+                    (Some(list), Some(selected)) => list.contains(selected),
+                    _ => true,
+                };
+                let mut option_defaults = BTreeMap::new();
+                resolve_defaults(&task.option, pi, &mut option_defaults);
+                resolve_defaults(resource_options, pi, &mut option_defaults);
+                InterfaceTaskInfo {
+                    task: task.clone(),
+                    compatible: allows(&task.controller, &controller)
+                        && allows(&task.resource, &resource),
+                    option_defaults,
+                }
+            })
+            .collect()
+    })?)
+}
+
+/// 求值任务的选项组合，返回生效的选项与对应的 pipeline_override
+///
+/// option_values 格式与前端 OptionValue 一致，未提供的选项使用默认值；
+/// 不在选中 case 之下的选项不生效，不适用于 controller 的选项会在 issues 中提示
+#[tauri::command]
+pub fn interface_resolve_options(
+    task_name: String,
+    option_values: BTreeMap<String, Value>,
+    controller: Option<String>,
+) -> Result<TaskOptionResolution, MxuError> {
+    Ok(with_interface(|pi| {
+        resolve_task(pi, &task_name, &option_values, controller.as_deref())
+    })??)
+}
@@ -0,0 +1,78 @@
+//! 二维码识别
+//!
+//! 从实例的最近一帧截图中识别二维码（解码见 mxu_recognizers::decode_qr_codes），
+//! 供 MXU_QRCODE_ACTION 将内容写入任务变量并通过 qrcode-detected 事件发送给前端，
+//! 用于兑换码、扫码登录等流程；前端也可通过 scan_qrcode 直接识别
+
+use log::info;
+use std::sync::{Arc, OnceLock};
+
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use super::error::MxuError;
+use super::screencap_tools::cached_screencap_image;
+use super::types::{MaaState, QrCodeDetected, QrCodeResult};
+
+/// 应用句柄（动作在 pipeline 线程中执行，需要全局访问实例状态与发送事件）
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+/// 保存应用句柄
+pub fn init(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+}
+
+fn scan(state: &MaaState, instance_id: &str, roi: [i32; 4]) -> Result<Vec<QrCodeResult>, String> {
+    let image = cached_screencap_image(state, instance_id)?;
+    Ok(crate::mxu_recognizers::decode_qr_codes(&image, roi)
+        .into_iter()
+        .map(|(content, rect)| QrCodeResult { content, rect })
+        .collect())
+}
+
+/// 识别实例最近一帧截图 ROI 中的二维码（动作中使用），roi 宽高为 0 时识别整张截图
+pub fn scan_instance(instance_id: &str, roi: [i32; 4]) -> Result<Vec<QrCodeResult>, String> {
+    let state = APP
+        .get()
+        .and_then(|app| app.try_state::<Arc<MaaState>>())
+        .ok_or("应用尚未初始化")?;
+    scan(&state, instance_id, roi)
+}
+
+/// 将识别到的二维码写入任务变量（variable 非空时）并发送 qrcode-detected 事件
+pub fn publish(instance_id: &str, variable: Option<&str>, code: &QrCodeResult) {
+    if let Some(variable) = variable {
+        super::variables::set_variable(
+            instance_id,
+            variable,
+            serde_json::Value::String(code.content.clone()),
+        );
+    }
+    if let Some(app) = APP.get() {
+        let _ = app.emit(
+            "qrcode-detected",
+            QrCodeDetected {
+                instance_id: instance_id.to_string(),
+                variable: variable.map(String::from),
+                content: code.content.clone(),
+                rect: code.rect,
+            },
+        );
+    }
+}
+
+/// 识别已连接实例最近一帧截图中的二维码
+/// roi: [x, y, width, height]，省略时识别整张截图
+#[tauri::command]
+pub fn scan_qrcode(
+    state: State<Arc<MaaState>>,
+    instance_id: String,
+    roi: Option<[i32; 4]>,
+) -> Result<Vec<QrCodeResult>, MxuError> {
+    let codes = scan(&state, &instance_id, roi.unwrap_or_default())?;
+    info!(
+        "[qrcode_scan] Instance {}: found {} QR code(s)",
+        instance_id,
+        codes.len()
+    );
+    Ok(codes)
+}
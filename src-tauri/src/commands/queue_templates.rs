@@ -0,0 +1,183 @@
+//! 任务队列模板
+//!
+//! 任务可带 include_if 包含条件（星期、日期、时间段、配置档案标记），
+//! 在运行开始时由后端求值，只把满足条件的任务加入本次运行；
+//! 解析后的具体队列与被排除的任务记录在运行的 pipeline 快照中（见 runs 模块）。
+//! 与 run_if 的区别：run_if 在队列执行过程中按前序任务结果求值，被跳过的任务仍出现在队列里
+
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, Local, NaiveDate, NaiveDateTime, NaiveTime};
+
+use super::error::MxuError;
+use super::types::{ExcludedTask, IncludeCondition, QueueResolution, TaskConfig};
+
+/// 求值上下文
+struct Context<'a> {
+    now: NaiveDateTime,
+    flags: &'a BTreeMap<String, serde_json::Value>,
+}
+
+/// 标记值是否为真（null / false / 0 / 空字符串视为假）
+fn is_truthy(value: &serde_json::Value) -> bool {
+    match value {
+        serde_json::Value::Null => false,
+        serde_json::Value::Bool(b) => *b,
+        serde_json::Value::Number(n) => n.as_f64().is_some_and(|n| n != 0.0),
+        serde_json::Value::String(s) => !s.is_empty(),
+        _ => true,
+    }
+}
+
+fn parse_date(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+        .map_err(|_| format!("无效的日期: {}（应为 YYYY-MM-DD）", value))
+}
+
+fn parse_time(value: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M")
+        .map_err(|_| format!("无效的时间: {}（应为 HH:MM）", value))
+}
+
+impl Context<'_> {
+    fn evaluate(&self, condition: &IncludeCondition) -> Result<bool, String> {
+        Ok(match condition {
+            IncludeCondition::Weekday { days } => {
+                days.contains(&self.now.weekday().number_from_monday())
+            }
+            IncludeCondition::MonthDay { days } => days.contains(&self.now.day()),
+            IncludeCondition::DateRange { from, to } => {
+                let today = self.now.date();
+                let after_start = match from {
+                    Some(from) => today >= parse_date(from)?,
+                    None => true,
+                };
+                let before_end = match to {
+                    Some(to) => today <= parse_date(to)?,
+                    None => true,
+                };
+                after_start && before_end
+            }
+            IncludeCondition::TimeRange { start, end } => {
+                let (start, end) = (parse_time(start)?, parse_time(end)?);
+                let time = self.now.time();
+                if start <= end {
+                    time >= start && time < end
+                } else {
+                    time >= start || time < end
+                }
+            }
+            IncludeCondition::Flag { name, equals } => match (self.flags.get(name), equals) {
+                (Some(value), Some(expected)) => value == expected,
+                (Some(value), None) => is_truthy(value),
+                (None, Some(expected)) => expected.is_null(),
+                (None, None) => false,
+            },
+            IncludeCondition::All { conditions } => {
+                for c in conditions {
+                    if !self.evaluate(c)? {
+                        return Ok(false);
+                    }
+                }
+                true
+            }
+            IncludeCondition::Any { conditions } => {
+                for c in conditions {
+                    if self.evaluate(c)? {
+                        return Ok(true);
+                    }
+                }
+                false
+            }
+            IncludeCondition::Not { condition } => !self.evaluate(condition)?,
+        })
+    }
+}
+
+/// 条件的简短说明（写入运行记录）
+fn describe(condition: &IncludeCondition) -> String {
+    match condition {
+        IncludeCondition::Weekday { days } => format!("weekday in {:?}", days),
+        IncludeCondition::MonthDay { days } => format!("day of month in {:?}", days),
+        IncludeCondition::DateRange { from, to } => format!(
+            "date in {}..{}",
+            from.as_deref().unwrap_or(""),
+            to.as_deref().unwrap_or("")
+        ),
+        IncludeCondition::TimeRange { start, end } => format!("time in {}-{}", start, end),
+        IncludeCondition::Flag { name, equals } => match equals {
+            Some(expected) => format!("flag {} == {}", name, expected),
+            None => format!("flag {}", name),
+        },
+        IncludeCondition::All { conditions } => format!(
+            "all({})",
+            conditions
+                .iter()
+                .map(describe)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        IncludeCondition::Any { conditions } => format!(
+            "any({})",
+            conditions
+                .iter()
+                .map(describe)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        IncludeCondition::Not { condition } => format!("not({})", describe(condition)),
+    }
+}
+
+/// 按包含条件解析任务列表，条件格式错误时返回错误（不静默排除）
+pub fn resolve(
+    tasks: Vec<TaskConfig>,
+    flags: &BTreeMap<String, serde_json::Value>,
+) -> Result<QueueResolution, String> {
+    let ctx = Context {
+        now: Local::now().naive_local(),
+        flags,
+    };
+    let mut resolution = QueueResolution {
+        tasks: Vec::with_capacity(tasks.len()),
+        excluded: Vec::new(),
+    };
+    for task in tasks {
+        let Some(condition) = &task.include_if else {
+            resolution.tasks.push(task);
+            continue;
+        };
+        let included = ctx
+            .evaluate(condition)
+            .map_err(|e| format!("任务 {} 的包含条件无效: {}", task.entry, e))?;
+        if included {
+            resolution.tasks.push(task);
+        } else {
+            resolution.excluded.push(ExcludedTask {
+                entry: task.entry.clone(),
+                id: task.id.clone(),
+                reason: describe(condition),
+            });
+        }
+    }
+    Ok(resolution)
+}
+
+/// 读取配置档案的标记，未指定档案时为空
+pub fn profile_flags(
+    profile_id: Option<&str>,
+) -> Result<BTreeMap<String, serde_json::Value>, String> {
+    match profile_id {
+        Some(id) => Ok(super::profiles::profile_get(id.to_string())?.content.flags),
+        None => Ok(BTreeMap::new()),
+    }
+}
+
+/// 预览按当前时间与档案标记解析后的队列
+#[tauri::command]
+pub fn resolve_queue_template(
+    tasks: Vec<TaskConfig>,
+    profile_id: Option<String>,
+) -> Result<QueueResolution, MxuError> {
+    Ok(resolve(tasks, &profile_flags(profile_id.as_deref())?)?)
+}
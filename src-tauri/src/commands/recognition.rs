@@ -0,0 +1,196 @@
+//! 识别结果检查
+//!
+//! 从回调中记录最近的识别 ID，并通过 Tasker 查询识别详情（命中框、得分、算法、绘制图），
+//! 供前端渲染识别调试面板
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use maa_framework::tasker::Tasker;
+use tauri::State;
+
+use super::error::MxuError;
+use super::i18n::ErrorCode;
+use super::types::{MaaState, RecognitionDetailInfo, RecognitionRecord};
+
+/// 最多保留的识别记录数
+const MAX_RECORDS: usize = 200;
+
+/// 最近的识别记录（新记录在末尾）
+static RECENT: Mutex<VecDeque<RecognitionRecord>> = Mutex::new(VecDeque::new());
+
+/// 回调钩子：记录识别完成事件中的 reco_id
+pub fn handle_callback(message: &str, details: &str) {
+    let hit = match message {
+        "Node.Recognition.Succeeded" => true,
+        "Node.Recognition.Failed" => false,
+        _ => return,
+    };
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(details) else {
+        return;
+    };
+    let Some(reco_id) = value.get("reco_id").and_then(|v| v.as_i64()) else {
+        return;
+    };
+    let node_name = value
+        .get("name")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string();
+
+    if let Ok(mut recent) = RECENT.lock() {
+        if recent.len() >= MAX_RECORDS {
+            recent.pop_front();
+        }
+        recent.push_back(RecognitionRecord {
+            reco_id,
+            node_name,
+            message: message.to_string(),
+            hit,
+            timestamp: chrono::Utc::now().timestamp_millis(),
+        });
+    }
+}
+
+/// 从算法详情中提取得分：优先取 best.score，未命中时取 all 中的最高分
+fn extract_score(detail: &serde_json::Value) -> Option<f64> {
+    if let Some(score) = detail
+        .get("best")
+        .and_then(|b| b.get("score"))
+        .and_then(|s| s.as_f64())
+    {
+        return Some(score);
+    }
+    if let Some(all) = detail.get("all").and_then(|a| a.as_array()) {
+        return all
+            .iter()
+            .filter_map(|r| r.get("score").and_then(|s| s.as_f64()))
+            .reduce(f64::max);
+    }
+    match detail {
+        serde_json::Value::Object(map) => map.get("score").and_then(|s| s.as_f64()),
+        serde_json::Value::Array(items) => items.iter().find_map(extract_score),
+        _ => None,
+    }
+}
+
+/// 获取指定时间（Unix 毫秒）之后的识别记录
+pub fn records_since(timestamp: i64) -> Vec<RecognitionRecord> {
+    RECENT
+        .lock()
+        .map(|recent| {
+            recent
+                .iter()
+                .filter(|r| r.timestamp >= timestamp)
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 通过 Tasker 查询识别详情，with_images 为 false 时不编码绘制图
+pub fn fetch_recognition_detail(
+    tasker: &Tasker,
+    reco_id: i64,
+    with_images: bool,
+) -> Result<RecognitionDetailInfo, String> {
+    let detail = tasker
+        .get_recognition_detail(reco_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("识别记录不存在: {}", reco_id))?;
+
+    let detail_json = serde_json::from_str::<serde_json::Value>(&detail.detail)
+        .unwrap_or(serde_json::Value::Null);
+    let draw_images = if with_images {
+        detail
+            .draw_images
+            .iter()
+            .filter_map(|img| img.to_vec())
+            .filter(|data| !data.is_empty())
+            .map(|data| format!("data:image/png;base64,{}", STANDARD.encode(&data)))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    Ok(RecognitionDetailInfo {
+        reco_id,
+        node_name: detail.node_name,
+        algorithm: detail.algorithm,
+        hit: detail.hit,
+        hit_box: [
+            detail.box_rect.x,
+            detail.box_rect.y,
+            detail.box_rect.width,
+            detail.box_rect.height,
+        ],
+        score: extract_score(&detail_json),
+        detail: detail_json,
+        draw_images,
+    })
+}
+
+/// 获取最近的识别记录（可按节点名过滤）
+#[tauri::command]
+pub fn maa_list_recent_recognitions(
+    node_name: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<RecognitionRecord>, MxuError> {
+    let recent = RECENT.lock().map_err(|e| e.to_string())?;
+    let limit = limit.unwrap_or(50);
+    Ok(recent
+        .iter()
+        .rev()
+        .filter(|r| node_name.as_deref().is_none_or(|n| r.node_name == n))
+        .take(limit)
+        .cloned()
+        .collect())
+}
+
+/// 清空识别记录
+#[tauri::command]
+pub fn maa_clear_recognitions() -> Result<(), MxuError> {
+    RECENT.lock().map_err(|e| e.to_string())?.clear();
+    Ok(())
+}
+
+/// 获取指定识别 ID 的详情
+#[tauri::command]
+pub fn maa_get_recognition_detail(
+    state: State<Arc<MaaState>>,
+    instance_id: String,
+    reco_id: i64,
+) -> Result<RecognitionDetailInfo, MxuError> {
+    let instances = state.instances.lock().map_err(|e| e.to_string())?;
+    let instance = instances
+        .get(&instance_id)
+        .ok_or_else(|| MxuError::new(ErrorCode::InstanceNotFound))?;
+    let tasker = instance
+        .tasker
+        .as_ref()
+        .ok_or_else(|| MxuError::new(ErrorCode::TaskerNotCreated))?;
+
+    Ok(fetch_recognition_detail(tasker, reco_id, true)?)
+}
+
+/// 获取指定节点最近一次识别的详情
+#[tauri::command]
+pub fn maa_get_latest_recognition(
+    state: State<Arc<MaaState>>,
+    instance_id: String,
+    node_name: String,
+) -> Result<Option<RecognitionDetailInfo>, MxuError> {
+    let reco_id = {
+        let recent = RECENT.lock().map_err(|e| e.to_string())?;
+        recent
+            .iter()
+            .rev()
+            .find(|r| r.node_name == node_name)
+            .map(|r| r.reco_id)
+    };
+    match reco_id {
+        Some(id) => maa_get_recognition_detail(state, instance_id, id).map(Some),
+        None => Ok(None),
+    }
+}
@@ -0,0 +1,287 @@
+//! 识别结果订阅
+//!
+//! 前端或远程 API 客户端按节点名模式（支持 `*` 与 `?` 通配符）订阅识别结果，
+//! 每次匹配的识别都会查询得分与命中框，可选附带从截图中裁剪的命中区域。
+//! 前端通过 recognition-hit 事件接收，远程客户端通过 recognition_poll 拉取缓存的结果，
+//! 无需修改资源即可实现“当前关卡计数”之类的自定义面板
+
+use log::{info, warn};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::error::MxuError;
+use super::i18n::ErrorCode;
+use super::types::{MaaState, RecognitionHit, RecognitionSubscription};
+
+/// 每个订阅缓存的最大结果数（供 recognition_poll 拉取）
+const MAX_BUFFERED_HITS: usize = 100;
+
+/// 订阅及其缓存的结果
+struct SubscriptionEntry {
+    subscription: RecognitionSubscription,
+    buffer: VecDeque<RecognitionHit>,
+}
+
+static SUBSCRIPTIONS: LazyLock<Mutex<HashMap<String, SubscriptionEntry>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// 通配符匹配：`*` 匹配任意长度字符，`?` 匹配单个字符
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // 最近一个 `*` 的位置及其匹配到的文本位置，用于回溯
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((sp, st)) = star {
+            p = sp + 1;
+            t = st + 1;
+            star = Some((sp, st + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+fn subscription_matches(
+    subscription: &RecognitionSubscription,
+    instance_id: &str,
+    node_name: &str,
+    hit: bool,
+) -> bool {
+    (hit || !subscription.hits_only)
+        && subscription
+            .instance_id
+            .as_deref()
+            .is_none_or(|id| id == instance_id)
+        && subscription
+            .patterns
+            .iter()
+            .any(|p| wildcard_match(p, node_name))
+}
+
+/// 从缓存截图中裁剪命中区域
+fn crop_hit(state: &MaaState, instance_id: &str, hit_box: [i32; 4]) -> Option<String> {
+    let [x, y, width, height] = hit_box;
+    if x < 0 || y < 0 || width <= 0 || height <= 0 {
+        return None;
+    }
+    let image = super::screencap_tools::cached_screencap_image(state, instance_id).ok()?;
+    let (x, y) = (x as u32, y as u32);
+    if x >= image.width() || y >= image.height() {
+        return None;
+    }
+    let width = (width as u32).min(image.width() - x);
+    let height = (height as u32).min(image.height() - y);
+    let cropped = image::imageops::crop_imm(&image, x, y, width, height).to_image();
+    super::utils::encode_png_data_url(&cropped).ok()
+}
+
+/// 查询识别详情并分发给匹配的订阅
+fn deliver(app: &AppHandle, instance_id: &str, reco_id: i64, targets: Vec<(String, bool)>) {
+    let state = app.state::<Arc<MaaState>>();
+    let tasker = state
+        .instances
+        .lock()
+        .ok()
+        .and_then(|instances| instances.get(instance_id).and_then(|i| i.tasker.clone()));
+    let Some(tasker) = tasker else {
+        return;
+    };
+    let detail = match super::recognition::fetch_recognition_detail(&tasker, reco_id, false) {
+        Ok(detail) => detail,
+        Err(e) => {
+            warn!(
+                "[recognition_feed] Failed to fetch recognition {}: {}",
+                reco_id, e
+            );
+            return;
+        }
+    };
+
+    // 裁剪图只在有订阅需要时生成一次
+    let image = if targets.iter().any(|(_, include_image)| *include_image) && detail.hit {
+        crop_hit(&state, instance_id, detail.hit_box)
+    } else {
+        None
+    };
+    let timestamp = chrono::Utc::now().timestamp_millis();
+
+    let Ok(mut subscriptions) = SUBSCRIPTIONS.lock() else {
+        return;
+    };
+    for (subscription_id, include_image) in targets {
+        let Some(entry) = subscriptions.get_mut(&subscription_id) else {
+            continue;
+        };
+        let hit = RecognitionHit {
+            subscription_id,
+            instance_id: instance_id.to_string(),
+            reco_id,
+            node_name: detail.node_name.clone(),
+            algorithm: detail.algorithm.clone(),
+            hit: detail.hit,
+            score: detail.score,
+            hit_box: detail.hit_box,
+            image: if include_image { image.clone() } else { None },
+            timestamp,
+        };
+        if entry.buffer.len() >= MAX_BUFFERED_HITS {
+            entry.buffer.pop_front();
+        }
+        entry.buffer.push_back(hit.clone());
+        let _ = app.emit("recognition-hit", hit);
+    }
+}
+
+/// Context Sink 钩子：识别完成且有匹配的订阅时，在后台查询详情并分发
+pub fn dispatch(app: &AppHandle, instance_id: &str, message: &str, details: &str) {
+    let hit = match message {
+        "Node.Recognition.Succeeded" => true,
+        "Node.Recognition.Failed" => false,
+        _ => return,
+    };
+    let targets: Vec<(String, bool)> = {
+        let Ok(subscriptions) = SUBSCRIPTIONS.lock() else {
+            return;
+        };
+        if subscriptions.is_empty() {
+            return;
+        }
+        let Ok(value) = serde_json::from_str::<serde_json::Value>(details) else {
+            return;
+        };
+        let node_name = value
+            .get("name")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default();
+        subscriptions
+            .values()
+            .filter(|e| subscription_matches(&e.subscription, instance_id, node_name, hit))
+            .map(|e| (e.subscription.id.clone(), e.subscription.include_image))
+            .collect()
+    };
+    if targets.is_empty() {
+        return;
+    }
+    let Some(reco_id) = serde_json::from_str::<serde_json::Value>(details)
+        .ok()
+        .and_then(|v| v.get("reco_id").and_then(|v| v.as_i64()))
+    else {
+        return;
+    };
+
+    // Sink 回调运行在 Tasker 线程中，查询详情与裁剪截图放到后台，避免拖慢 pipeline
+    let app = app.clone();
+    let instance_id = instance_id.to_string();
+    std::thread::spawn(move || deliver(&app, &instance_id, reco_id, targets));
+}
+
+/// 订阅识别结果，返回创建的订阅
+///
+/// - `patterns`: 节点名模式，支持 `*` 与 `?` 通配符
+/// - `instance_id`: 只接收指定实例的结果，省略时接收全部实例
+/// - `hits_only`: 只接收命中的识别（默认 true）
+/// - `include_image`: 附带命中区域的裁剪图（PNG data URL）
+#[tauri::command]
+pub fn recognition_subscribe(
+    patterns: Vec<String>,
+    instance_id: Option<String>,
+    hits_only: Option<bool>,
+    include_image: Option<bool>,
+) -> Result<RecognitionSubscription, MxuError> {
+    let patterns: Vec<String> = patterns
+        .into_iter()
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect();
+    if patterns.is_empty() {
+        return Err(MxuError::new(ErrorCode::EmptyNodePatterns));
+    }
+    let subscription = RecognitionSubscription {
+        id: format!("reco-sub-{}", NEXT_ID.fetch_add(1, Ordering::Relaxed)),
+        patterns,
+        instance_id,
+        hits_only: hits_only.unwrap_or(true),
+        include_image: include_image.unwrap_or(false),
+        created_at: chrono::Utc::now().timestamp_millis(),
+    };
+    info!(
+        "[recognition_feed] Subscription {} created for {:?}",
+        subscription.id, subscription.patterns
+    );
+    SUBSCRIPTIONS.lock().map_err(|e| e.to_string())?.insert(
+        subscription.id.clone(),
+        SubscriptionEntry {
+            subscription: subscription.clone(),
+            buffer: VecDeque::new(),
+        },
+    );
+    Ok(subscription)
+}
+
+/// 取消订阅
+#[tauri::command]
+pub fn recognition_unsubscribe(subscription_id: String) -> Result<(), MxuError> {
+    Ok(SUBSCRIPTIONS
+        .lock()
+        .map_err(|e| e.to_string())?
+        .remove(&subscription_id)
+        .map(|_| {
+            info!(
+                "[recognition_feed] Subscription {} removed",
+                subscription_id
+            )
+        })
+        .ok_or_else(|| format!("订阅不存在: {}", subscription_id))?)
+}
+
+/// 列出当前的订阅
+#[tauri::command]
+pub fn recognition_list_subscriptions() -> Result<Vec<RecognitionSubscription>, MxuError> {
+    let subscriptions = SUBSCRIPTIONS.lock().map_err(|e| e.to_string())?;
+    let mut list: Vec<RecognitionSubscription> = subscriptions
+        .values()
+        .map(|e| e.subscription.clone())
+        .collect();
+    list.sort_by_key(|s| s.created_at);
+    Ok(list)
+}
+
+/// 拉取订阅缓存的识别结果（供无法接收事件的远程客户端使用）
+///
+/// 返回时间晚于 `since`（Unix 毫秒）的结果；`consume` 为 true 时同时从缓存中移除已返回的结果
+#[tauri::command]
+pub fn recognition_poll(
+    subscription_id: String,
+    since: Option<i64>,
+    consume: Option<bool>,
+) -> Result<Vec<RecognitionHit>, MxuError> {
+    let mut subscriptions = SUBSCRIPTIONS.lock().map_err(|e| e.to_string())?;
+    let entry = subscriptions
+        .get_mut(&subscription_id)
+        .ok_or_else(|| format!("订阅不存在: {}", subscription_id))?;
+    let since = since.unwrap_or(i64::MIN);
+    let hits: Vec<RecognitionHit> = entry
+        .buffer
+        .iter()
+        .filter(|h| h.timestamp > since)
+        .cloned()
+        .collect();
+    if consume.unwrap_or(false) {
+        entry.buffer.retain(|h| h.timestamp <= since);
+    }
+    Ok(hits)
+}
@@ -0,0 +1,389 @@
+//! 远程 API 授权
+//!
+//! 管理远程访问令牌（权限范围、轮换、吊销）与允许远程调用的命令白名单，
+//! 并将每次鉴权结果写入审计日志。远程 API 服务（api_server）的每个请求都通过 `authorize` 鉴权。
+//! 另提供包含令牌与局域网地址的配对二维码，免去手动输入令牌
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use super::error::MxuError;
+use super::file_ops::to_hex;
+use super::i18n::ErrorCode;
+use super::types::{
+    RemoteAuditEntry, RemoteCommandRule, RemotePairingQr, RemoteScope, RemoteToken,
+    RemoteTokenInfo, RemoteTokenSecret,
+};
+use super::utils::{encode_png_data_url, get_app_data_dir, get_logs_dir, save_json_config};
+
+/// 配对二维码每个模块的像素大小与四周留白（模块数）
+const QR_MODULE_PIXELS: u32 = 8;
+const QR_QUIET_ZONE: u32 = 4;
+
+/// 审计日志文件名（位于日志目录）
+const AUDIT_LOG_FILE: &str = "remote_audit.jsonl";
+
+/// 轮转后的上一份审计日志
+const AUDIT_LOG_ROTATED_FILE: &str = "remote_audit.1.jsonl";
+
+/// 审计日志超过该大小时轮转，最多保留当前与上一份
+const MAX_AUDIT_LOG_BYTES: u64 = 1024 * 1024;
+
+/// 配置文件读写锁
+static CONFIG_LOCK: Mutex<()> = Mutex::new(());
+
+/// 审计日志写入与轮转锁
+static AUDIT_LOCK: Mutex<()> = Mutex::new(());
+
+/// 远程授权配置文件结构
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RemoteAuthConfig {
+    #[serde(default)]
+    tokens: Vec<RemoteToken>,
+    #[serde(default = "default_allowlist")]
+    allowlist: Vec<RemoteCommandRule>,
+}
+
+impl Default for RemoteAuthConfig {
+    fn default() -> Self {
+        Self {
+            tokens: Vec::new(),
+            allowlist: default_allowlist(),
+        }
+    }
+}
+
+/// 默认白名单：状态查询为只读，任务启停与接收集群分发需要控制权限
+fn default_allowlist() -> Vec<RemoteCommandRule> {
+    let rule = |command: &str, scope| RemoteCommandRule {
+        command: command.to_string(),
+        scope,
+    };
+    vec![
+        rule("status", RemoteScope::ReadOnly),
+        rule("queue_state", RemoteScope::ReadOnly),
+        rule("get_task_variables", RemoteScope::ReadOnly),
+        rule("maa_is_running", RemoteScope::ReadOnly),
+        rule("recognition_subscribe", RemoteScope::ReadOnly),
+        rule("recognition_unsubscribe", RemoteScope::ReadOnly),
+        rule("recognition_poll", RemoteScope::ReadOnly),
+        rule("start_profile", RemoteScope::Control),
+        rule("maa_stop_task", RemoteScope::Control),
+        rule("queue_pause", RemoteScope::Control),
+        rule("queue_resume", RemoteScope::Control),
+        rule("dispatch", RemoteScope::Control),
+    ]
+}
+
+fn config_path() -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?.join("config").join("remote_auth.json"))
+}
+
+fn load_config() -> Result<RemoteAuthConfig, String> {
+    let path = config_path()?;
+    if !path.exists() {
+        return Ok(RemoteAuthConfig::default());
+    }
+    let content =
+        std::fs::read_to_string(&path).map_err(|e| format!("无法读取远程授权配置: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("无法解析远程授权配置: {}", e))
+}
+
+fn save_config(config: &RemoteAuthConfig) -> Result<(), String> {
+    let path = config_path()?;
+    Ok(save_json_config(&path, config)?)
+}
+
+/// 在锁内读取-修改-保存配置
+fn modify_config<T>(
+    f: impl FnOnce(&mut RemoteAuthConfig) -> Result<T, String>,
+) -> Result<T, String> {
+    let _guard = CONFIG_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut config = load_config()?;
+    let result = f(&mut config)?;
+    save_config(&config)?;
+    Ok(result)
+}
+
+/// 生成随机令牌
+fn generate_token() -> Result<String, String> {
+    let mut buf = [0u8; 32];
+    getrandom::getrandom(&mut buf).map_err(|e| format!("无法生成随机令牌: {}", e))?;
+    Ok(format!("mxu_{}", to_hex(&buf)))
+}
+
+fn hash_token(token: &str) -> String {
+    to_hex(&Sha256::digest(token.as_bytes()))
+}
+
+fn to_info(token: &RemoteToken) -> RemoteTokenInfo {
+    RemoteTokenInfo {
+        id: token.id.clone(),
+        name: token.name.clone(),
+        scope: token.scope,
+        created_at: token.created_at.clone(),
+        rotated_at: token.rotated_at.clone(),
+    }
+}
+
+/// 追加审计记录，文件超过上限时先轮转
+fn write_audit(entry: &RemoteAuditEntry) {
+    let _guard = AUDIT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let path = get_logs_dir().join(AUDIT_LOG_FILE);
+    if std::fs::metadata(&path).is_ok_and(|m| m.len() >= MAX_AUDIT_LOG_BYTES) {
+        if let Err(e) = std::fs::rename(&path, get_logs_dir().join(AUDIT_LOG_ROTATED_FILE)) {
+            warn!("[remote_auth] Failed to rotate audit log: {}", e);
+        }
+    }
+    let result = serde_json::to_string(entry)
+        .map_err(|e| e.to_string())
+        .and_then(|line| {
+            std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .and_then(|mut f| writeln!(f, "{}", line))
+                .map_err(|e| e.to_string())
+        });
+    if let Err(e) = result {
+        warn!("[remote_auth] Failed to write audit log: {}", e);
+    }
+}
+
+/// 鉴权：校验令牌并检查命令是否在白名单且权限足够，结果写入审计日志
+pub fn authorize(token: &str, command: &str) -> Result<RemoteScope, String> {
+    let config = {
+        let _guard = CONFIG_LOCK.lock().map_err(|e| e.to_string())?;
+        load_config()?
+    };
+    let hash = hash_token(token);
+    let matched = config.tokens.iter().find(|t| t.token_hash == hash);
+
+    let result = match (
+        matched,
+        config.allowlist.iter().find(|r| r.command == command),
+    ) {
+        (None, _) => Err("无效的访问令牌".to_string()),
+        (Some(_), None) => Err(format!("命令未开放远程调用: {}", command)),
+        (Some(_), Some(rule))
+            if rule.scope > RemoteScope::ReadOnly && super::guest_mode::is_guest_mode() =>
+        {
+            Err("只读访客模式下仅开放只读命令".to_string())
+        }
+        (Some(t), Some(rule)) if t.scope < rule.scope => Err(format!(
+            "权限不足: 需要 {:?}，当前 {:?}",
+            rule.scope, t.scope
+        )),
+        (Some(t), Some(_)) => Ok(t.scope),
+    };
+
+    write_audit(&RemoteAuditEntry {
+        time: chrono::Local::now().to_rfc3339(),
+        token_id: matched.map(|t| t.id.clone()),
+        command: command.to_string(),
+        allowed: result.is_ok(),
+        reason: result.as_ref().err().cloned(),
+    });
+    result
+}
+
+/// 列出所有远程令牌
+#[tauri::command]
+pub fn remote_token_list() -> Result<Vec<RemoteTokenInfo>, MxuError> {
+    Ok(load_config()?.tokens.iter().map(to_info).collect())
+}
+
+/// 创建远程令牌，明文令牌仅返回一次
+#[tauri::command]
+pub fn remote_token_create(
+    name: String,
+    scope: RemoteScope,
+) -> Result<RemoteTokenSecret, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    let token = generate_token()?;
+    let entry = RemoteToken {
+        id: to_hex(&Sha256::digest(token.as_bytes())[..6]),
+        name: name.trim().to_string(),
+        scope,
+        token_hash: hash_token(&token),
+        created_at: chrono::Local::now().to_rfc3339(),
+        rotated_at: None,
+    };
+    let info = to_info(&entry);
+    modify_config(|config| {
+        config.tokens.push(entry);
+        Ok(())
+    })?;
+    info!(
+        "[remote_auth] Token created: {} ({:?})",
+        info.id, info.scope
+    );
+    Ok(RemoteTokenSecret { info, token })
+}
+
+/// 轮换令牌：保留 ID 与权限，旧令牌立即失效
+#[tauri::command]
+pub fn remote_token_rotate(id: String) -> Result<RemoteTokenSecret, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    let token = generate_token()?;
+    let info = modify_config(|config| {
+        let entry = config
+            .tokens
+            .iter_mut()
+            .find(|t| t.id == id)
+            .ok_or_else(|| format!("令牌不存在: {}", id))?;
+        entry.token_hash = hash_token(&token);
+        entry.rotated_at = Some(chrono::Local::now().to_rfc3339());
+        Ok(to_info(entry))
+    })?;
+    info!("[remote_auth] Token rotated: {}", id);
+    Ok(RemoteTokenSecret { info, token })
+}
+
+/// 吊销令牌
+#[tauri::command]
+pub fn remote_token_revoke(id: String) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    modify_config(|config| {
+        let before = config.tokens.len();
+        config.tokens.retain(|t| t.id != id);
+        if config.tokens.len() == before {
+            return Err(format!("令牌不存在: {}", id));
+        }
+        Ok(())
+    })?;
+    info!("[remote_auth] Token revoked: {}", id);
+    Ok(())
+}
+
+/// 获取远程命令白名单
+#[tauri::command]
+pub fn remote_get_allowlist() -> Result<Vec<RemoteCommandRule>, MxuError> {
+    Ok(load_config()?.allowlist)
+}
+
+/// 设置远程命令白名单
+#[tauri::command]
+pub fn remote_set_allowlist(rules: Vec<RemoteCommandRule>) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    modify_config(|config| {
+        config.allowlist = rules;
+        Ok(())
+    })?;
+    info!("[remote_auth] Allowlist updated");
+    Ok(())
+}
+
+/// 从文件末尾读取最多 max_bytes 字节的完整行
+fn read_tail_lines(path: &Path, max_bytes: u64) -> Result<Vec<String>, String> {
+    let mut file = std::fs::File::open(path).map_err(|e| format!("无法读取审计日志: {}", e))?;
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let start = len.saturating_sub(max_bytes);
+    file.seek(SeekFrom::Start(start))
+        .map_err(|e| format!("无法读取审计日志: {}", e))?;
+    let mut buf = Vec::new();
+    file.read_to_end(&mut buf)
+        .map_err(|e| format!("无法读取审计日志: {}", e))?;
+    let content = String::from_utf8_lossy(&buf);
+    let mut lines = content.lines();
+    // 从文件中间开始读取时，第一行可能不完整
+    if start > 0 {
+        lines.next();
+    }
+    Ok(lines.map(str::to_string).collect())
+}
+
+/// 读取最近的审计记录（最新在前）
+#[tauri::command]
+pub fn remote_audit_log(limit: Option<usize>) -> Result<Vec<RemoteAuditEntry>, MxuError> {
+    let limit = limit.unwrap_or(200);
+    let mut entries = Vec::new();
+    // 当前日志不足时再读取轮转的上一份
+    for file in [AUDIT_LOG_FILE, AUDIT_LOG_ROTATED_FILE] {
+        if entries.len() >= limit {
+            break;
+        }
+        let path = get_logs_dir().join(file);
+        if !path.exists() {
+            continue;
+        }
+        let remaining = limit - entries.len();
+        entries.extend(
+            read_tail_lines(&path, MAX_AUDIT_LOG_BYTES)?
+                .iter()
+                .rev()
+                .filter_map(|line| serde_json::from_str::<RemoteAuditEntry>(line).ok())
+                .take(remaining),
+        );
+    }
+    Ok(entries)
+}
+
+/// 获取本机局域网 IPv4 地址（通过 UDP 路由选择，不会实际发送数据）
+pub fn local_lan_ip() -> Option<std::net::IpAddr> {
+    let socket = std::net::UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("8.8.8.8:80").ok()?;
+    socket.local_addr().ok().map(|addr| addr.ip())
+}
+
+/// 将二维码渲染为 RGBA 图像
+fn render_qr(payload: &str) -> Result<image::RgbaImage, String> {
+    let code =
+        qrcode::QrCode::new(payload.as_bytes()).map_err(|e| format!("生成二维码失败: {}", e))?;
+    let width = code.width() as u32;
+    let colors = code.to_colors();
+    let size = (width + QR_QUIET_ZONE * 2) * QR_MODULE_PIXELS;
+
+    Ok(image::RgbaImage::from_fn(size, size, |px, py| {
+        let mx = (px / QR_MODULE_PIXELS).checked_sub(QR_QUIET_ZONE);
+        let my = (py / QR_MODULE_PIXELS).checked_sub(QR_QUIET_ZONE);
+        let dark = match (mx, my) {
+            (Some(x), Some(y)) if x < width && y < width => {
+                colors[(y * width + x) as usize] == qrcode::Color::Dark
+            }
+            _ => false,
+        };
+        if dark {
+            image::Rgba([0, 0, 0, 255])
+        } else {
+            image::Rgba([255, 255, 255, 255])
+        }
+    }))
+}
+
+/// 生成远程访问配对二维码（令牌 + 远程 API 服务的局域网地址），需先开启远程 API 服务
+///
+/// 令牌仅以哈希保存，因此需由前端传入创建/轮换时拿到的明文令牌
+#[tauri::command]
+pub fn remote_pairing_qr(token: String) -> Result<RemotePairingQr, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    let port = super::api_server::running_port()
+        .ok_or_else(|| MxuError::new(ErrorCode::RemoteApiNotRunning))?;
+    let hash = hash_token(&token);
+    let entry = load_config()?
+        .tokens
+        .into_iter()
+        .find(|t| t.token_hash == hash)
+        .ok_or_else(|| MxuError::new(ErrorCode::InvalidAccessToken))?;
+
+    let ip = local_lan_ip().ok_or_else(|| MxuError::new(ErrorCode::LanAddressUnavailable))?;
+    let address = format!("http://{}:{}", ip, port);
+    let payload = format!(
+        "mxu://pair?url={}&token={}&name={}",
+        urlencoding::encode(&address),
+        urlencoding::encode(&token),
+        urlencoding::encode(&entry.name)
+    );
+    let image = encode_png_data_url(&render_qr(&payload)?)?;
+
+    info!("[remote_auth] Pairing QR generated for token {}", entry.id);
+    Ok(RemotePairingQr {
+        payload,
+        address,
+        image,
+    })
+}
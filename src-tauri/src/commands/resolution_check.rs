@@ -0,0 +1,199 @@
+//! 设备分辨率检查
+//!
+//! 控制器连接成功后比较设备分辨率与资源的基准分辨率，宽高比不同、分辨率过低或非整数倍缩放
+//! （如 1600×900 缩放到 1280×720）时通过 resolution-warning 事件通知前端，解释识别失败的原因。
+//! 基准分辨率取自 interface.json 中控制器或顶层的 resolution 字段（[宽, 高] 或 {"width", "height"}），
+//! 未声明时按 MaaFramework 资源常用的 1280×720 处理
+
+use log::{info, warn};
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use maa_framework::controller::Controller;
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, State};
+
+use super::error::MxuError;
+use super::maa_core::connected_controller;
+use super::types::{ControllerConfig, MaaState, ResolutionCheckResult, ResolutionIssue};
+
+/// 未声明时的基准分辨率
+const DEFAULT_BASE_RESOLUTION: (u32, u32) = (1280, 720);
+/// 宽高比允许的相对误差
+const ASPECT_TOLERANCE: f64 = 0.01;
+/// 等待连接完成的最长时间
+const CONNECT_WAIT: Duration = Duration::from_secs(60);
+
+/// 各实例连接的控制器类型
+static KINDS: LazyLock<Mutex<HashMap<String, &'static str>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 控制器类型名称（与 interface.json 中 controller.type 一致）
+pub fn controller_kind(config: &ControllerConfig) -> &'static str {
+    match config {
+        ControllerConfig::Adb { .. } => "Adb",
+        ControllerConfig::Win32 { .. } => "Win32",
+        ControllerConfig::Gamepad { .. } => "Gamepad",
+        ControllerConfig::PlayCover { .. } => "PlayCover",
+        ControllerConfig::Mock { .. } => "Mock",
+    }
+}
+
+fn parse_resolution(value: &Value) -> Option<(u32, u32)> {
+    let (width, height) = match value {
+        Value::Array(items) if items.len() == 2 => (items[0].as_u64()?, items[1].as_u64()?),
+        Value::Object(map) => (map.get("width")?.as_u64()?, map.get("height")?.as_u64()?),
+        _ => return None,
+    };
+    (width > 0 && height > 0).then_some((width as u32, height as u32))
+}
+
+/// 资源的基准分辨率及其来源（controller / interface / default）
+fn expected_resolution(kind: &str) -> ((u32, u32), &'static str) {
+    let declared = super::project_interface::with_interface(|pi| {
+        let from_controller = pi
+            .controller
+            .iter()
+            .filter(|c| c.kind == kind)
+            .find_map(|c| c.extra.get("resolution").and_then(parse_resolution))
+            .map(|r| (r, "controller"));
+        from_controller.or_else(|| {
+            pi.extra
+                .get("resolution")
+                .and_then(parse_resolution)
+                .map(|r| (r, "interface"))
+        })
+    });
+    match declared {
+        Ok(Some(declared)) => declared,
+        Ok(None) => (DEFAULT_BASE_RESOLUTION, "default"),
+        Err(e) => {
+            warn!("[resolution_check] Failed to load interface: {}", e);
+            (DEFAULT_BASE_RESOLUTION, "default")
+        }
+    }
+}
+
+/// 按横竖方向对齐后比较设备与基准分辨率
+fn evaluate(device: (u32, u32), expected: (u32, u32)) -> Vec<ResolutionIssue> {
+    let (long, short) = (device.0.max(device.1), device.0.min(device.1));
+    let (base_long, base_short) = (expected.0.max(expected.1), expected.0.min(expected.1));
+    let mut issues = Vec::new();
+
+    let ratio = long as f64 / short as f64;
+    let base_ratio = base_long as f64 / base_short as f64;
+    if ((ratio - base_ratio) / base_ratio).abs() > ASPECT_TOLERANCE {
+        issues.push(ResolutionIssue {
+            kind: "aspect_ratio".to_string(),
+            severity: "warning".to_string(),
+            message: format!(
+                "设备宽高比 {:.3} 与资源要求的 {:.3}（{}×{}）不同，识别区域会错位",
+                ratio, base_ratio, base_long, base_short
+            ),
+        });
+    }
+    if short < base_short {
+        issues.push(ResolutionIssue {
+            kind: "low_resolution".to_string(),
+            severity: "warning".to_string(),
+            message: format!(
+                "设备分辨率 {}×{} 低于资源基准 {}×{}，截图放大后细节不足",
+                long, short, base_long, base_short
+            ),
+        });
+    } else if short % base_short != 0 {
+        issues.push(ResolutionIssue {
+            kind: "non_integer_scale".to_string(),
+            severity: "info".to_string(),
+            message: format!(
+                "设备分辨率 {}×{} 不是资源基准 {}×{} 的整数倍，缩放后模板匹配可能不稳定，建议改为 {}×{}",
+                long, short, base_long, base_short, base_long, base_short
+            ),
+        });
+    }
+    issues
+}
+
+fn check(
+    instance_id: &str,
+    controller: &Controller,
+    kind: &str,
+) -> Result<ResolutionCheckResult, String> {
+    let (width, height) = controller.resolution().map_err(|e| e.to_string())?;
+    if width <= 0 || height <= 0 {
+        return Err("无法获取设备分辨率".to_string());
+    }
+    let device = (width as u32, height as u32);
+    let (expected, source) = expected_resolution(kind);
+    let issues = evaluate(device, expected);
+    Ok(ResolutionCheckResult {
+        instance_id: instance_id.to_string(),
+        width: device.0,
+        height: device.1,
+        expected_width: expected.0,
+        expected_height: expected.1,
+        expected_source: source.to_string(),
+        issues,
+    })
+}
+
+/// 连接发起后在后台等待连接完成，再检查分辨率（maa_connect_controller 时调用）
+pub fn spawn_after_connect(
+    app: AppHandle,
+    instance_id: String,
+    controller: Controller,
+    kind: &'static str,
+) {
+    if let Ok(mut kinds) = KINDS.lock() {
+        kinds.insert(instance_id.clone(), kind);
+    }
+    std::thread::spawn(move || {
+        let start = Instant::now();
+        while !controller.connected() {
+            if start.elapsed() > CONNECT_WAIT {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(200));
+        }
+        match check(&instance_id, &controller, kind) {
+            Ok(result) if !result.issues.is_empty() => {
+                warn!(
+                    "[resolution_check] Instance {}: {}x{} vs base {}x{} ({} issue(s))",
+                    instance_id,
+                    result.width,
+                    result.height,
+                    result.expected_width,
+                    result.expected_height,
+                    result.issues.len()
+                );
+                let _ = app.emit("resolution-warning", result);
+            }
+            Ok(result) => info!(
+                "[resolution_check] Instance {} resolution {}x{} OK",
+                instance_id, result.width, result.height
+            ),
+            Err(e) => warn!("[resolution_check] Instance {}: {}", instance_id, e),
+        }
+    });
+}
+
+/// 检查已连接实例的设备分辨率
+#[tauri::command]
+pub fn check_device_resolution(
+    state: State<Arc<MaaState>>,
+    instance_id: String,
+) -> Result<ResolutionCheckResult, MxuError> {
+    let controller = connected_controller(&state, &instance_id)?;
+    let kind = KINDS
+        .lock()
+        .ok()
+        .and_then(|kinds| kinds.get(&instance_id).copied())
+        .unwrap_or("Adb");
+    let id = instance_id.clone();
+    Ok(super::ffi_guard::call(
+        &instance_id,
+        "resolution",
+        move || check(&id, &controller, kind),
+    )??)
+}
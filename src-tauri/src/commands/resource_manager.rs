@@ -0,0 +1,271 @@
+//! 资源包管理
+//!
+//! 发现数据目录 resource_packs/ 下的多个资源包（每个目录为一个 bundle，
+//! 可带 interface.json 描述名称与版本），维护启用状态与加载顺序，
+//! maa_load_resource 会在项目资源之后按顺序追加已启用的资源包
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::error::MxuError;
+use super::i18n::ErrorCode;
+use super::types::ResourcePackInfo;
+use super::update::{extract_zip, move_to_old_folder};
+use super::utils::{get_app_data_dir, save_json_config};
+
+/// 资源包状态配置
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ResourcePackState {
+    /// 加载顺序（目录名）
+    #[serde(default)]
+    order: Vec<String>,
+    /// 已禁用的资源包
+    #[serde(default)]
+    disabled: Vec<String>,
+}
+
+fn get_packs_dir() -> Result<PathBuf, String> {
+    let dir = get_app_data_dir()?.join("resource_packs");
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("无法创建资源包目录 [{}]: {}", dir.display(), e))?;
+    Ok(dir)
+}
+
+fn state_path() -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?
+        .join("config")
+        .join("resource_packs.json"))
+}
+
+fn load_state() -> ResourcePackState {
+    state_path()
+        .ok()
+        .and_then(|p| std::fs::read_to_string(p).ok())
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(state: &ResourcePackState) -> Result<(), String> {
+    let path = state_path()?;
+    Ok(save_json_config(&path, state)?)
+}
+
+/// 校验资源包名称，防止路径穿越
+fn validate_pack_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.contains(['/', '\\']) || name == "." || name == ".." {
+        return Err(format!("非法的资源包名称: {}", name));
+    }
+    Ok(())
+}
+
+/// 读取资源包 interface.json 中的 (label, version)
+fn read_pack_metadata(dir: &Path) -> (Option<String>, Option<String>) {
+    let Some(json) = std::fs::read_to_string(dir.join("interface.json"))
+        .ok()
+        .and_then(|c| serde_json::from_str::<serde_json::Value>(&c).ok())
+    else {
+        return (None, None);
+    };
+    let field = |key: &str| {
+        json.get(key)
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+    };
+    (field("label").or_else(|| field("name")), field("version"))
+}
+
+/// 扫描资源包并按已保存的顺序排列（新发现的资源包排在末尾）
+fn scan_packs() -> Result<Vec<ResourcePackInfo>, String> {
+    let dir = get_packs_dir()?;
+    let state = load_state();
+
+    let mut names: Vec<String> = std::fs::read_dir(&dir)
+        .map_err(|e| format!("无法读取资源包目录: {}", e))?
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .collect();
+    names.sort_by_key(|n| {
+        (
+            state
+                .order
+                .iter()
+                .position(|o| o == n)
+                .unwrap_or(usize::MAX),
+            n.clone(),
+        )
+    });
+
+    Ok(names
+        .into_iter()
+        .enumerate()
+        .map(|(order, name)| {
+            let path = dir.join(&name);
+            let (label, version) = read_pack_metadata(&path);
+            ResourcePackInfo {
+                enabled: !state.disabled.contains(&name),
+                path: path.to_string_lossy().to_string(),
+                name,
+                label,
+                version,
+                order,
+            }
+        })
+        .collect())
+}
+
+/// 已启用资源包的目录，按加载顺序排列
+pub fn enabled_pack_paths() -> Vec<String> {
+    match scan_packs() {
+        Ok(packs) => packs
+            .into_iter()
+            .filter(|p| p.enabled)
+            .map(|p| p.path)
+            .collect(),
+        Err(e) => {
+            warn!("[resource_manager] Failed to scan resource packs: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// 列出资源包
+#[tauri::command]
+pub fn resource_pack_list() -> Result<Vec<ResourcePackInfo>, MxuError> {
+    Ok(scan_packs()?)
+}
+
+/// 启用或禁用资源包
+#[tauri::command]
+pub fn resource_pack_set_enabled(name: String, enabled: bool) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    validate_pack_name(&name)?;
+    let mut state = load_state();
+    state.disabled.retain(|n| n != &name);
+    if !enabled {
+        state.disabled.push(name.clone());
+    }
+    save_state(&state)?;
+    info!("[resource_manager] Pack {} enabled: {}", name, enabled);
+    Ok(())
+}
+
+/// 设置资源包加载顺序
+#[tauri::command]
+pub fn resource_pack_reorder(names: Vec<String>) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    let mut state = load_state();
+    state.order = names;
+    Ok(save_state(&state)?)
+}
+
+/// 删除资源包（移动到 cache/old）
+#[tauri::command]
+pub fn resource_pack_remove(name: String) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    validate_pack_name(&name)?;
+    let path = get_packs_dir()?.join(&name);
+    if !path.exists() {
+        return Err(MxuError::with_args(
+            ErrorCode::ResourcePackNotFound,
+            [&name],
+        ));
+    }
+    move_to_old_folder(&path)?;
+
+    let mut state = load_state();
+    state.order.retain(|n| n != &name);
+    state.disabled.retain(|n| n != &name);
+    save_state(&state)?;
+    info!("[resource_manager] Pack removed: {}", name);
+    Ok(())
+}
+
+/// 在解压目录中定位资源包根目录：根目录本身，或唯一的子目录
+fn find_pack_root(extract_dir: &Path) -> Result<PathBuf, String> {
+    let entries: Vec<PathBuf> = std::fs::read_dir(extract_dir)
+        .map_err(|e| format!("无法读取解压目录: {}", e))?
+        .flatten()
+        .map(|e| e.path())
+        .collect();
+    match entries.as_slice() {
+        [single] if single.is_dir() && !extract_dir.join("interface.json").exists() => {
+            Ok(single.clone())
+        }
+        _ => Ok(extract_dir.to_path_buf()),
+    }
+}
+
+/// 从 zip 文件或 URL 安装资源包，同名资源包会被替换
+/// source: 本地 zip 路径或 http(s) 下载地址
+#[tauri::command]
+pub async fn resource_pack_install(source: String) -> Result<ResourcePackInfo, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    let cache_dir = get_app_data_dir()?.join("cache");
+    std::fs::create_dir_all(&cache_dir).map_err(|e| format!("无法创建缓存目录: {}", e))?;
+    let timestamp = chrono::Local::now().format("%Y%m%d%H%M%S%3f").to_string();
+
+    let (zip_path, downloaded) = if source.starts_with("http://") || source.starts_with("https://")
+    {
+        info!("[resource_manager] Downloading pack from {}", source);
+        let bytes = reqwest::get(&source)
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| format!("下载资源包失败: {}", e))?
+            .bytes()
+            .await
+            .map_err(|e| format!("下载资源包失败: {}", e))?;
+        let path = cache_dir.join(format!("resource_pack_{}.zip", timestamp));
+        std::fs::write(&path, &bytes).map_err(|e| format!("无法保存资源包: {}", e))?;
+        (path, true)
+    } else {
+        (PathBuf::from(&source), false)
+    };
+
+    let fallback_name = Path::new(&source)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| format!("pack_{}", timestamp));
+
+    let result = tauri::async_runtime::spawn_blocking(move || -> Result<String, String> {
+        let extract_dir = cache_dir.join(format!("resource_pack_extract_{}", timestamp));
+        extract_zip(
+            zip_path.to_string_lossy().to_string(),
+            extract_dir.to_string_lossy().to_string(),
+        )?;
+
+        let install = || -> Result<String, String> {
+            let root = find_pack_root(&extract_dir)?;
+            let name = root
+                .file_name()
+                .filter(|_| root != extract_dir)
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or(fallback_name);
+            validate_pack_name(&name)?;
+
+            let dest = get_packs_dir()?.join(&name);
+            if dest.exists() {
+                move_to_old_folder(&dest)?;
+            }
+            std::fs::rename(&root, &dest).map_err(|e| format!("无法安装资源包: {}", e))?;
+            Ok(name)
+        };
+        let result = install();
+
+        let _ = std::fs::remove_dir_all(&extract_dir);
+        if downloaded {
+            let _ = std::fs::remove_file(&zip_path);
+        }
+        result
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    info!("[resource_manager] Pack installed: {}", result);
+    Ok(scan_packs()?
+        .into_iter()
+        .find(|p| p.name == result)
+        .ok_or_else(|| format!("资源包安装后未找到: {}", result))?)
+}
@@ -0,0 +1,207 @@
+//! 资源热重载
+//!
+//! 监听当前资源目录中 pipeline/图片/模型文件的变化，防抖后在实例空闲时
+//! 重新加载资源，并发送 resource-reloaded 事件
+
+use log::{info, warn};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter, State};
+
+use super::error::MxuError;
+use super::i18n::ErrorCode;
+use super::maa_core::load_resource_bundles;
+use super::resource_manager::enabled_pack_paths;
+use super::types::{MaaState, ResourceReloadedEvent};
+use super::utils::normalize_path;
+
+/// 防抖时间：最后一次变化后等待的时长
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// 实例忙碌时重新检查的间隔
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 触发重载的文件扩展名
+const WATCHED_EXTENSIONS: &[&str] = &["json", "jsonc", "png", "jpg", "jpeg", "onnx"];
+
+/// 各实例的文件监听器，移除即停止监听
+static WATCHERS: LazyLock<Mutex<HashMap<String, RecommendedWatcher>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn is_resource_file(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .is_some_and(|ext| WATCHED_EXTENSIONS.contains(&ext.as_str()))
+}
+
+/// 实例是否空闲（无运行中的任务或队列）
+fn is_idle(state: &MaaState, instance_id: &str) -> bool {
+    let queue_active = state
+        .task_queues
+        .lock()
+        .map(|q| q.get(instance_id).is_some_and(|q| q.is_active()))
+        .unwrap_or(false);
+    let tasker_running = state
+        .instances
+        .lock()
+        .map(|i| {
+            i.get(instance_id)
+                .and_then(|inst| inst.tasker.as_ref())
+                .is_some_and(|t| t.running())
+        })
+        .unwrap_or(false);
+    !queue_active && !tasker_running
+}
+
+/// 开始监听资源目录变化
+/// paths: 与 maa_load_resource 相同的资源路径
+#[tauri::command]
+pub fn resource_watch_start(
+    app: AppHandle,
+    state: State<Arc<MaaState>>,
+    instance_id: String,
+    paths: Vec<String>,
+) -> Result<(), MxuError> {
+    let (tx, rx) = mpsc::channel::<PathBuf>();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        let Ok(event) = res else {
+            return;
+        };
+        if matches!(
+            event.kind,
+            EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+        ) {
+            for path in event.paths.into_iter().filter(|p| is_resource_file(p)) {
+                let _ = tx.send(path);
+            }
+        }
+    })
+    .map_err(|e| format!("无法创建文件监听器: {}", e))?;
+
+    let mut watched = 0;
+    for path in paths.iter().cloned().chain(enabled_pack_paths()) {
+        let normalized = normalize_path(&path);
+        match watcher.watch(&normalized, RecursiveMode::Recursive) {
+            Ok(()) => watched += 1,
+            Err(e) => warn!(
+                "[resource_watcher] Failed to watch {}: {}",
+                normalized.display(),
+                e
+            ),
+        }
+    }
+    if watched == 0 {
+        return Err(MxuError::new(ErrorCode::NoWatchableResourceDirs));
+    }
+
+    // 替换旧的监听器（旧线程会因通道断开而退出）
+    WATCHERS
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert(instance_id.clone(), watcher);
+
+    info!(
+        "[resource_watcher] Watching {} path(s) for instance {}",
+        watched, instance_id
+    );
+
+    let state = state.inner().clone();
+    thread::spawn(move || reload_loop(app, state, instance_id, paths, rx));
+    Ok(())
+}
+
+/// 停止监听资源目录
+#[tauri::command]
+pub fn resource_watch_stop(instance_id: String) -> Result<(), MxuError> {
+    if WATCHERS
+        .lock()
+        .map_err(|e| e.to_string())?
+        .remove(&instance_id)
+        .is_some()
+    {
+        info!(
+            "[resource_watcher] Stopped watching for instance {}",
+            instance_id
+        );
+    }
+    Ok(())
+}
+
+/// 等待变化 -> 防抖 -> 等待空闲 -> 重载，通道断开（监听器被移除）时退出
+fn reload_loop(
+    app: AppHandle,
+    state: Arc<MaaState>,
+    instance_id: String,
+    paths: Vec<String>,
+    rx: Receiver<PathBuf>,
+) {
+    while let Ok(first) = rx.recv() {
+        let mut changed = BTreeSet::new();
+        changed.insert(first);
+
+        // 防抖：持续收集直到一段时间内没有新变化
+        loop {
+            match rx.recv_timeout(DEBOUNCE) {
+                Ok(path) => {
+                    changed.insert(path);
+                }
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        }
+
+        // 等待实例空闲
+        while !is_idle(&state, &instance_id) {
+            match rx.recv_timeout(IDLE_CHECK_INTERVAL) {
+                Ok(path) => {
+                    changed.insert(path);
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        }
+
+        let changed: Vec<String> = changed
+            .into_iter()
+            .map(|p| p.to_string_lossy().to_string())
+            .collect();
+        info!(
+            "[resource_watcher] Reloading resource for instance {} ({} file(s) changed)",
+            instance_id,
+            changed.len()
+        );
+
+        // 与 maa_destroy_resource 相同：销毁旧资源与 tasker，下次运行时重新创建 tasker
+        if let Ok(mut instances) = state.instances.lock() {
+            if let Some(instance) = instances.get_mut(&instance_id) {
+                instance.resource = None;
+                instance.tasker = None;
+            }
+        }
+
+        let (res_ids, error) =
+            match load_resource_bundles(&app, &state, &instance_id, paths.clone()) {
+                Ok(ids) => (ids, None),
+                Err(e) => {
+                    warn!("[resource_watcher] Reload failed: {}", e);
+                    (Vec::new(), Some(e))
+                }
+            };
+
+        let _ = app.emit(
+            "resource-reloaded",
+            ResourceReloadedEvent {
+                instance_id: instance_id.clone(),
+                changed,
+                res_ids,
+                error,
+            },
+        );
+    }
+}
@@ -0,0 +1,192 @@
+//! 运行报告截图
+//!
+//! 运行过程中在关键时刻（开始、每个任务结束、任务失败）保存控制器缓存截图到
+//! runs/<run_id>/frames/，运行结束时拼接为一张 report.png，便于分享到 Discord/QQ。
+//! 每帧以边框颜色区分类型：灰色为开始，绿色为任务成功，红色为失败
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use image::{Rgba, RgbaImage};
+use log::{info, warn};
+use tauri::{AppHandle, Manager};
+
+use super::error::MxuError;
+use super::runs::get_run_dir;
+use super::screencap_tools::cached_screencap_image;
+use super::types::MaaState;
+use super::utils::encode_png_data_url;
+
+/// 帧目录与报告文件名
+const FRAMES_DIR: &str = "frames";
+const REPORT_FILE: &str = "report.png";
+
+/// 单帧缩放后的宽度
+const FRAME_WIDTH: u32 = 480;
+/// 边框与间距
+const BORDER: u32 = 6;
+const GAP: u32 = 8;
+/// 每行帧数
+const COLUMNS: u32 = 3;
+/// 最多拼接的帧数（保留开始帧与最后的帧）
+const MAX_FRAMES: usize = 12;
+
+/// 截图类型
+#[derive(Debug, Clone, Copy)]
+pub enum FrameKind {
+    Start,
+    Task,
+    Error,
+}
+
+impl FrameKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            FrameKind::Start => "start",
+            FrameKind::Task => "task",
+            FrameKind::Error => "error",
+        }
+    }
+
+    fn from_file_name(name: &str) -> Self {
+        if name.contains("_error") {
+            FrameKind::Error
+        } else if name.contains("_start") {
+            FrameKind::Start
+        } else {
+            FrameKind::Task
+        }
+    }
+
+    fn border_color(self) -> Rgba<u8> {
+        match self {
+            FrameKind::Start => Rgba([128, 128, 128, 255]),
+            FrameKind::Task => Rgba([46, 160, 67, 255]),
+            FrameKind::Error => Rgba([218, 54, 51, 255]),
+        }
+    }
+}
+
+fn frames_dir(run_id: &str) -> Result<PathBuf, String> {
+    Ok(get_run_dir(run_id)?.join(FRAMES_DIR))
+}
+
+/// 保存实例当前的缓存截图为运行报告帧（无截图时忽略）
+pub fn capture_frame(app: &AppHandle, instance_id: &str, run_id: &str, kind: FrameKind) {
+    let Some(state) = app.try_state::<Arc<MaaState>>() else {
+        return;
+    };
+    let Ok(mut frame) = cached_screencap_image(&state, instance_id) else {
+        return;
+    };
+    super::screenshot_redaction::redact_frame(&state, instance_id, &mut frame);
+    let result = frames_dir(run_id).and_then(|dir| {
+        std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+        let index = std::fs::read_dir(&dir).map(|d| d.count()).unwrap_or(0);
+        let height = frame.height() * FRAME_WIDTH / frame.width().max(1);
+        let resized = image::imageops::resize(
+            &frame,
+            FRAME_WIDTH,
+            height.max(1),
+            image::imageops::FilterType::Triangle,
+        );
+        resized
+            .save(dir.join(format!("{:03}_{}.png", index, kind.as_str())))
+            .map_err(|e| e.to_string())
+    });
+    if let Err(e) = result {
+        warn!(
+            "[run_report] Failed to capture frame for run {}: {}",
+            run_id, e
+        );
+    }
+}
+
+/// 读取运行的全部帧（按保存顺序），超出上限时保留开始帧与最后的帧
+fn load_frames(run_id: &str) -> Vec<(FrameKind, RgbaImage)> {
+    let Ok(entries) =
+        frames_dir(run_id).and_then(|d| std::fs::read_dir(d).map_err(|e| e.to_string()))
+    else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+    paths.sort();
+    if paths.len() > MAX_FRAMES {
+        let tail = paths.split_off(paths.len() - (MAX_FRAMES - 1));
+        paths.truncate(1);
+        paths.extend(tail);
+    }
+    paths
+        .iter()
+        .filter_map(|path| {
+            let name = path.file_name()?.to_string_lossy().to_string();
+            let image = image::open(path).ok()?.to_rgba8();
+            Some((FrameKind::from_file_name(&name), image))
+        })
+        .collect()
+}
+
+/// 将运行的帧拼接为报告图，返回报告路径（没有帧时返回 None）
+pub fn build_report(run_id: &str) -> Option<PathBuf> {
+    let frames = load_frames(run_id);
+    if frames.is_empty() {
+        return None;
+    }
+
+    let cell_width = FRAME_WIDTH + BORDER * 2;
+    let cell_height = frames.iter().map(|(_, f)| f.height()).max()? + BORDER * 2;
+    let columns = COLUMNS.min(frames.len() as u32);
+    let rows = (frames.len() as u32).div_ceil(columns);
+    let width = columns * cell_width + (columns + 1) * GAP;
+    let height = rows * cell_height + (rows + 1) * GAP;
+
+    let mut canvas = RgbaImage::from_pixel(width, height, Rgba([32, 32, 32, 255]));
+    for (idx, (kind, frame)) in frames.iter().enumerate() {
+        let (col, row) = (idx as u32 % columns, idx as u32 / columns);
+        let x = GAP + col * (cell_width + GAP);
+        let y = GAP + row * (cell_height + GAP);
+        let border =
+            RgbaImage::from_pixel(cell_width, frame.height() + BORDER * 2, kind.border_color());
+        image::imageops::overlay(&mut canvas, &border, x as i64, y as i64);
+        image::imageops::overlay(&mut canvas, frame, (x + BORDER) as i64, (y + BORDER) as i64);
+    }
+
+    let path = get_run_dir(run_id).ok()?.join(REPORT_FILE);
+    match canvas.save(&path) {
+        Ok(()) => {
+            info!(
+                "[run_report] Report for run {} saved ({} frames)",
+                run_id,
+                frames.len()
+            );
+            Some(path)
+        }
+        Err(e) => {
+            warn!(
+                "[run_report] Failed to save report for run {}: {}",
+                run_id, e
+            );
+            None
+        }
+    }
+}
+
+/// 获取运行报告拼接图（data URL），报告不存在时尝试重新生成
+#[tauri::command]
+pub fn get_run_report_image(run_id: String) -> Result<Option<String>, MxuError> {
+    let path = get_run_dir(&run_id)?.join(REPORT_FILE);
+    let path = if path.exists() {
+        Some(path)
+    } else {
+        build_report(&run_id)
+    };
+    match path {
+        Some(path) => {
+            let image = image::open(Path::new(&path))
+                .map_err(|e| format!("无法读取运行报告: {}", e))?
+                .to_rgba8();
+            Ok(Some(encode_png_data_url(&image)?))
+        }
+        None => Ok(None),
+    }
+}
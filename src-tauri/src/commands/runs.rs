@@ -0,0 +1,433 @@
+//! 运行记录
+//!
+//! 每次 maa_start_tasks 视为一次运行，分配 run_id 并在日志目录 runs/<run_id>/ 下
+//! 保存本次提交的 pipeline 覆盖快照（含运行中追加的覆盖及合并结果），
+//! 便于对比不同运行之间的差异。
+//!
+//! 同时为每次运行写入独立日志 runs/<run_id>.log，仅包含该运行的后端日志、
+//! custom action 执行结果与 Maa 回调（按 task_id 关联），运行结束时通过 run-completed 事件返回路径。
+//! 关键截图见 run_report 模块
+
+use log::{info, warn};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex};
+
+use tauri::{AppHandle, Emitter};
+
+use super::error::MxuError;
+use super::run_report::FrameKind;
+use super::types::{
+    ExcludedTask, RunCompletedEvent, RunHistoryEntry, RunLateOverride, RunPipelineSnapshot,
+    RunStartedEvent, RunTaskPipeline, TaskConfig,
+};
+use super::utils::{get_logs_dir, merge_json};
+
+/// 快照文件名
+const PIPELINE_FILE: &str = "pipeline.json";
+
+/// 各实例当前的 run_id
+static CURRENT_RUNS: LazyLock<Mutex<HashMap<String, String>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 进行中运行的日志状态
+struct RunLog {
+    instance_id: String,
+    file: File,
+    path: PathBuf,
+    /// 已提交但尚未结束的任务
+    pending: HashSet<i64>,
+    /// 是否有任务失败
+    failed: bool,
+    /// 是否已提交完全部任务（直接模式下用于判断运行结束；队列模式由队列结束时关闭）
+    sealed: bool,
+    started_at: String,
+    task_count: usize,
+    /// 失败任务的入口名（写入运行历史）
+    failed_tasks: Vec<String>,
+}
+
+/// run_id -> 日志状态
+static RUN_LOGS: LazyLock<Mutex<HashMap<String, RunLog>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// task_id -> run_id
+static TASK_RUNS: LazyLock<Mutex<HashMap<i64, String>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 运行记录根目录
+pub fn get_runs_dir() -> PathBuf {
+    get_logs_dir().join("runs")
+}
+
+/// 获取运行目录，校验 run_id 只包含安全字符
+pub fn get_run_dir(run_id: &str) -> Result<PathBuf, String> {
+    if run_id.is_empty()
+        || !run_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!("非法的 run_id: {}", run_id));
+    }
+    Ok(get_runs_dir().join(run_id))
+}
+
+/// 进行中的运行数量
+pub fn active_run_count() -> usize {
+    RUN_LOGS.lock().map(|logs| logs.len()).unwrap_or(0)
+}
+
+/// 获取实例当前的 run_id
+pub fn current_run_id(instance_id: &str) -> Option<String> {
+    CURRENT_RUNS
+        .lock()
+        .ok()
+        .and_then(|runs| runs.get(instance_id).cloned())
+}
+
+/// 合并单个覆盖，数组形式的覆盖按顺序逐个合并
+fn apply_override(merged: &mut serde_json::Value, pipeline_override: &serde_json::Value) {
+    match pipeline_override {
+        serde_json::Value::Array(items) => {
+            for item in items {
+                merge_json(merged, item);
+            }
+        }
+        other => merge_json(merged, other),
+    }
+}
+
+/// 解析覆盖字符串，解析失败时按原字符串保存
+fn parse_override(pipeline_override: &str) -> serde_json::Value {
+    serde_json::from_str(pipeline_override)
+        .unwrap_or_else(|_| serde_json::Value::String(pipeline_override.to_string()))
+}
+
+fn write_snapshot(snapshot: &RunPipelineSnapshot) -> Result<(), String> {
+    let dir = get_run_dir(&snapshot.run_id)?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("无法创建运行目录 [{}]: {}", dir.display(), e))?;
+    let content = serde_json::to_string_pretty(snapshot).map_err(|e| e.to_string())?;
+    std::fs::write(dir.join(PIPELINE_FILE), content)
+        .map_err(|e| format!("无法写入 pipeline 快照: {}", e))
+}
+
+/// 创建一次运行：分配 run_id、保存 pipeline 快照并发送 run-started 事件
+///
+/// excluded 为按包含条件未加入本次运行的任务（见 queue_templates）
+pub fn create_run(
+    app: &AppHandle,
+    instance_id: &str,
+    tasks: &[TaskConfig],
+    excluded: &[ExcludedTask],
+) -> Result<String, String> {
+    let now = chrono::Local::now();
+    let run_id = format!("{}-{}", now.format("%Y%m%d-%H%M%S%3f"), instance_id)
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+
+    let tasks: Vec<RunTaskPipeline> = tasks
+        .iter()
+        .map(|t| RunTaskPipeline {
+            entry: t.entry.clone(),
+            pipeline_override: parse_override(&t.pipeline_override),
+        })
+        .collect();
+    let mut merged = serde_json::Value::Object(Default::default());
+    for task in &tasks {
+        apply_override(&mut merged, &task.pipeline_override);
+    }
+
+    write_snapshot(&RunPipelineSnapshot {
+        run_id: run_id.clone(),
+        instance_id: instance_id.to_string(),
+        started_at: now.to_rfc3339(),
+        tasks,
+        excluded: excluded.to_vec(),
+        late_overrides: Vec::new(),
+        merged,
+    })?;
+
+    CURRENT_RUNS
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert(instance_id.to_string(), run_id.clone());
+
+    open_run_log(&run_id, instance_id, &now.to_rfc3339(), &tasks);
+    for task in excluded {
+        run_log(
+            &run_id,
+            &format!("Excluded: entry={} ({})", task.entry, task.reason),
+        );
+    }
+    super::run_report::capture_frame(app, instance_id, &run_id, FrameKind::Start);
+
+    super::cluster::on_run_started(&run_id);
+    info!("[runs] Run {} started for instance {}", run_id, instance_id);
+    let _ = app.emit(
+        "run-started",
+        RunStartedEvent {
+            instance_id: instance_id.to_string(),
+            run_id: run_id.clone(),
+        },
+    );
+    Ok(run_id)
+}
+
+/// 记录运行中追加的覆盖（maa_override_pipeline）
+pub fn record_late_override(instance_id: &str, task_id: i64, pipeline_override: &str) {
+    let Some(run_id) = current_run_id(instance_id) else {
+        return;
+    };
+    let result = get_run_pipeline(run_id)
+        .map_err(String::from)
+        .and_then(|mut snapshot| {
+            let value = parse_override(pipeline_override);
+            apply_override(&mut snapshot.merged, &value);
+            snapshot.late_overrides.push(RunLateOverride {
+                task_id,
+                pipeline_override: value,
+                time: chrono::Local::now().to_rfc3339(),
+            });
+            write_snapshot(&snapshot)
+        });
+    if let Err(e) = result {
+        warn!("[runs] Failed to record late override: {}", e);
+    }
+}
+
+/// 获取指定运行的 pipeline 快照
+#[tauri::command]
+pub fn get_run_pipeline(run_id: String) -> Result<RunPipelineSnapshot, MxuError> {
+    let path = get_run_dir(&run_id)?.join(PIPELINE_FILE);
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("无法读取运行记录 {}: {}", run_id, e))?;
+    Ok(
+        serde_json::from_str(&content)
+            .map_err(|e| format!("无法解析运行记录 {}: {}", run_id, e))?,
+    )
+}
+
+/// 列出所有运行 ID（最新在前）
+#[tauri::command]
+pub fn list_runs() -> Result<Vec<String>, MxuError> {
+    let dir = get_runs_dir();
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut runs: Vec<String> = std::fs::read_dir(&dir)
+        .map_err(|e| format!("无法读取运行记录目录: {}", e))?
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .collect();
+    runs.sort_by(|a, b| b.cmp(a));
+    Ok(runs)
+}
+
+/// 创建运行日志文件并写入任务列表
+fn open_run_log(run_id: &str, instance_id: &str, started_at: &str, tasks: &[RunTaskPipeline]) {
+    let path = get_runs_dir().join(format!("{}.log", run_id));
+    let file = std::fs::create_dir_all(get_runs_dir()).and_then(|_| File::create(&path));
+    let file = match file {
+        Ok(file) => file,
+        Err(e) => {
+            warn!("[runs] Failed to create run log {}: {}", path.display(), e);
+            return;
+        }
+    };
+    if let Ok(mut logs) = RUN_LOGS.lock() {
+        logs.insert(
+            run_id.to_string(),
+            RunLog {
+                instance_id: instance_id.to_string(),
+                file,
+                path,
+                pending: HashSet::new(),
+                failed: false,
+                sealed: false,
+                started_at: started_at.to_string(),
+                task_count: tasks.len(),
+                failed_tasks: Vec::new(),
+            },
+        );
+    }
+    run_log(
+        run_id,
+        &format!("Run {} started, instance: {}", run_id, instance_id),
+    );
+    for (idx, task) in tasks.iter().enumerate() {
+        run_log(run_id, &format!("Task[{}]: entry={}", idx, task.entry));
+    }
+}
+
+/// 写入一行运行日志
+pub fn run_log(run_id: &str, message: &str) {
+    if let Ok(mut logs) = RUN_LOGS.lock() {
+        if let Some(log) = logs.get_mut(run_id) {
+            let time = chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f");
+            let _ = writeln!(log.file, "[{}] {}", time, message);
+        }
+    }
+}
+
+/// 按 task_id 写入运行日志（custom action 等只知道 task_id 的场景）
+pub fn run_log_for_task(task_id: i64, message: &str) {
+    let run_id = TASK_RUNS
+        .lock()
+        .ok()
+        .and_then(|tasks| tasks.get(&task_id).cloned());
+    if let Some(run_id) = run_id {
+        run_log(&run_id, message);
+    }
+}
+
+/// 查询任务所属运行的实例 ID（仅限进行中的运行）
+pub fn instance_for_task(task_id: i64) -> Option<String> {
+    let run_id = TASK_RUNS.lock().ok()?.get(&task_id).cloned()?;
+    let logs = RUN_LOGS.lock().ok()?;
+    logs.get(&run_id).map(|log| log.instance_id.clone())
+}
+
+/// 登记运行中提交的任务，用于关联回调
+pub fn register_task(run_id: &str, task_id: i64) {
+    if let Ok(mut tasks) = TASK_RUNS.lock() {
+        tasks.insert(task_id, run_id.to_string());
+    }
+    if let Ok(mut logs) = RUN_LOGS.lock() {
+        if let Some(log) = logs.get_mut(run_id) {
+            log.pending.insert(task_id);
+        }
+    }
+    run_log(run_id, &format!("Task posted, task_id: {}", task_id));
+}
+
+/// 直接模式下全部任务提交完成：若任务均已结束则立即结束运行
+pub fn seal_run(app: &AppHandle, run_id: &str) {
+    let finished = RUN_LOGS.lock().ok().and_then(|mut logs| {
+        let log = logs.get_mut(run_id)?;
+        log.sealed = true;
+        log.pending.is_empty().then_some(log.failed)
+    });
+    if let Some(failed) = finished {
+        finish_run(app, run_id, !failed, None);
+    }
+}
+
+/// 运行结果状态名（运行历史、run-completed 事件与集群回报统一使用）
+pub fn status_name(succeeded: bool) -> &'static str {
+    if succeeded {
+        "succeeded"
+    } else {
+        "failed"
+    }
+}
+
+/// 结束运行：写入结束信息、关闭日志并发送 run-completed 事件
+///
+/// reason 为补充说明（队列模式下为队列最终状态）
+pub fn finish_run(app: &AppHandle, run_id: &str, succeeded: bool, reason: Option<&str>) {
+    let status = status_name(succeeded);
+    match reason {
+        Some(reason) => run_log(run_id, &format!("Run finished: {} ({})", status, reason)),
+        None => run_log(run_id, &format!("Run finished: {}", status)),
+    }
+    let Some(log) = RUN_LOGS
+        .lock()
+        .ok()
+        .and_then(|mut logs| logs.remove(run_id))
+    else {
+        return;
+    };
+    if let Ok(mut tasks) = TASK_RUNS.lock() {
+        tasks.retain(|_, id| id != run_id);
+    }
+    info!("[runs] Run {} finished: {}", run_id, status);
+    super::cluster::on_run_finished(run_id, succeeded, reason);
+    super::digest::record_run(RunHistoryEntry {
+        run_id: run_id.to_string(),
+        instance_id: log.instance_id.clone(),
+        started_at: log.started_at,
+        finished_at: chrono::Local::now().to_rfc3339(),
+        status: status.to_string(),
+        reason: reason.map(str::to_string),
+        task_count: log.task_count,
+        failed_tasks: log.failed_tasks,
+        counters: super::variables::numeric_variables(&log.instance_id),
+    });
+    let report_path = super::run_report::build_report(run_id);
+    let _ = app.emit(
+        "run-completed",
+        RunCompletedEvent {
+            instance_id: log.instance_id,
+            run_id: run_id.to_string(),
+            status: status.to_string(),
+            reason: reason.map(str::to_string),
+            log_path: log.path.to_string_lossy().to_string(),
+            report_path: report_path.map(|p| p.to_string_lossy().to_string()),
+        },
+    );
+}
+
+/// 回调钩子：将带 task_id 的 Maa 回调写入对应运行日志，并在直接模式下跟踪运行结束
+pub fn handle_callback(app: &AppHandle, message: &str, details: &str) {
+    let Some(details_json) = serde_json::from_str::<serde_json::Value>(details).ok() else {
+        return;
+    };
+    let Some(task_id) = details_json.get("task_id").and_then(|id| id.as_i64()) else {
+        return;
+    };
+    let Some(run_id) = TASK_RUNS
+        .lock()
+        .ok()
+        .and_then(|tasks| tasks.get(&task_id).cloned())
+    else {
+        return;
+    };
+    run_log(&run_id, &format!("[callback] {} {}", message, details));
+
+    let succeeded = match message {
+        "Tasker.Task.Succeeded" => true,
+        "Tasker.Task.Failed" => false,
+        _ => return,
+    };
+    // 保存任务结束时的截图
+    let instance_id = RUN_LOGS
+        .lock()
+        .ok()
+        .and_then(|logs| logs.get(&run_id).map(|l| l.instance_id.clone()));
+    if let Some(instance_id) = instance_id {
+        let kind = if succeeded {
+            FrameKind::Task
+        } else {
+            FrameKind::Error
+        };
+        super::run_report::capture_frame(app, &instance_id, &run_id, kind);
+    }
+
+    let finished = RUN_LOGS.lock().ok().and_then(|mut logs| {
+        let log = logs.get_mut(&run_id)?;
+        log.pending.remove(&task_id);
+        log.failed |= !succeeded;
+        if !succeeded {
+            let entry = details_json
+                .get("entry")
+                .and_then(|e| e.as_str())
+                .unwrap_or("unknown");
+            log.failed_tasks.push(entry.to_string());
+        }
+        (log.sealed && log.pending.is_empty()).then_some(log.failed)
+    });
+    if let Some(failed) = finished {
+        finish_run(app, &run_id, !failed, None);
+    }
+}
@@ -0,0 +1,388 @@
+//! scrcpy 高帧率预览
+//!
+//! 可选的实时预览来源：在设备上启动 scrcpy-server，通过 adb forward 接收视频流，
+//! 将 H.264 数据包原样转发给前端（前端使用 WebCodecs 解码），
+//! 用于交互式调试时替代较慢的 MaaFramework 截图轮询
+
+use log::{debug, error, info, warn};
+use std::collections::HashMap;
+use std::io::Read;
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use super::error::MxuError;
+use super::i18n::ErrorCode;
+use super::utils::get_exe_directory;
+
+/// scrcpy-server 协议版本（需与 exe 目录/scrcpy/scrcpy-server 文件版本一致）
+const SCRCPY_SERVER_VERSION: &str = "3.1";
+/// 设备上的 scrcpy-server 路径
+const DEVICE_SERVER_PATH: &str = "/data/local/tmp/scrcpy-server.jar";
+
+/// 预览会话 ID 计数器
+static NEXT_SESSION_ID: AtomicU64 = AtomicU64::new(1);
+
+/// 正在运行的预览会话
+static SESSIONS: OnceLock<Mutex<HashMap<u64, ScrcpySession>>> = OnceLock::new();
+
+struct ScrcpySession {
+    adb_path: String,
+    serial: String,
+    port: u16,
+    server: Child,
+    stop: Arc<AtomicBool>,
+}
+
+impl ScrcpySession {
+    /// 停止读取线程、结束 server 进程并移除端口转发
+    fn shutdown(mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        let _ = self.server.kill();
+        let _ = self.server.wait();
+        let _ = adb_command(&self.adb_path, &self.serial)
+            .args(["forward", "--remove", &format!("tcp:{}", self.port)])
+            .output();
+    }
+}
+
+fn sessions() -> &'static Mutex<HashMap<u64, ScrcpySession>> {
+    SESSIONS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 预览会话信息
+#[derive(Debug, Clone, Serialize)]
+pub struct ScrcpySessionInfo {
+    pub session_id: u64,
+    pub port: u16,
+}
+
+/// 视频流元信息事件（连接成功后发送一次）
+#[derive(Clone, Serialize)]
+pub struct ScrcpyMetaEvent {
+    pub session_id: u64,
+    pub device_name: String,
+    pub codec: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// 视频数据包事件
+#[derive(Clone, Serialize)]
+pub struct ScrcpyPacketEvent {
+    pub session_id: u64,
+    /// 是否为编解码器配置包（SPS/PPS）
+    pub config: bool,
+    pub key_frame: bool,
+    pub pts: u64,
+    /// base64 编码的 H.264 Annex-B 数据
+    pub data: String,
+}
+
+/// 预览结束事件
+#[derive(Clone, Serialize)]
+pub struct ScrcpyStoppedEvent {
+    pub session_id: u64,
+    pub reason: String,
+}
+
+fn server_file() -> Result<PathBuf, String> {
+    Ok(get_exe_directory()?.join("scrcpy").join("scrcpy-server"))
+}
+
+fn adb_command(adb_path: &str, serial: &str) -> Command {
+    #[cfg(windows)]
+    let mut cmd = {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        let mut c = Command::new(adb_path);
+        c.creation_flags(CREATE_NO_WINDOW);
+        c
+    };
+
+    #[cfg(not(windows))]
+    let mut cmd = Command::new(adb_path);
+
+    cmd.args(["-s", serial]);
+    cmd
+}
+
+/// 执行 adb 命令并检查退出状态
+fn run_adb(adb_path: &str, serial: &str, args: &[&str]) -> Result<(), String> {
+    let output = adb_command(adb_path, serial)
+        .args(args)
+        .output()
+        .map_err(|e| format!("执行 adb 失败: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "adb {} 失败: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+/// 由系统分配一个当前空闲的本地端口用于 adb forward
+fn pick_free_port() -> Result<u16, String> {
+    let listener =
+        TcpListener::bind(("127.0.0.1", 0)).map_err(|e| format!("无法分配本地端口: {}", e))?;
+    listener
+        .local_addr()
+        .map(|addr| addr.port())
+        .map_err(|e| format!("无法分配本地端口: {}", e))
+}
+
+/// 检查 scrcpy-server 是否可用
+#[tauri::command]
+pub fn scrcpy_is_available() -> bool {
+    server_file().is_ok_and(|p| p.is_file())
+}
+
+/// 启动 scrcpy 预览
+/// adb_path / serial: 与 ADB 控制器相同的 adb 路径和设备地址
+/// max_size: 视频最长边（0 表示不限制），max_fps: 最大帧率，bit_rate: 码率（bps）
+#[tauri::command]
+pub async fn scrcpy_start(
+    app: AppHandle,
+    adb_path: String,
+    serial: String,
+    max_size: Option<u32>,
+    max_fps: Option<u32>,
+    bit_rate: Option<u32>,
+) -> Result<ScrcpySessionInfo, MxuError> {
+    info!("scrcpy_start called: adb={}, serial={}", adb_path, serial);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let server = server_file()?;
+        if !server.is_file() {
+            return Err(MxuError::with_args(
+                ErrorCode::ScrcpyServerNotFound,
+                [server.display()],
+            ));
+        }
+
+        let session_id = NEXT_SESSION_ID.fetch_add(1, Ordering::SeqCst);
+        let port = pick_free_port()?;
+        // scid 为 31 位十六进制数，用于区分同一设备上的多个 server
+        let scid = format!(
+            "{:08x}",
+            (std::process::id() as u64 ^ session_id) & 0x7fff_ffff
+        );
+
+        run_adb(
+            &adb_path,
+            &serial,
+            &["push", &server.to_string_lossy(), DEVICE_SERVER_PATH],
+        )?;
+        // 端口在释放后到 adb 绑定前可能被占用，转发失败时直接报错而不是连接到其他进程
+        run_adb(
+            &adb_path,
+            &serial,
+            &[
+                "forward",
+                "--no-rebind",
+                &format!("tcp:{}", port),
+                &format!("localabstract:scrcpy_{}", scid),
+            ],
+        )
+        .map_err(|e| format!("无法建立端口转发 (tcp:{}): {}", port, e))?;
+
+        let server_args = [
+            format!("scid={}", scid),
+            "tunnel_forward=true".to_string(),
+            "audio=false".to_string(),
+            "control=false".to_string(),
+            "cleanup=true".to_string(),
+            "video_codec=h264".to_string(),
+            format!("max_size={}", max_size.unwrap_or(0)),
+            format!("max_fps={}", max_fps.unwrap_or(60)),
+            format!("video_bit_rate={}", bit_rate.unwrap_or(8_000_000)),
+        ];
+
+        let child = adb_command(&adb_path, &serial)
+            .args([
+                "shell",
+                &format!("CLASSPATH={}", DEVICE_SERVER_PATH),
+                "app_process",
+                "/",
+                "com.genymobile.scrcpy.Server",
+                SCRCPY_SERVER_VERSION,
+            ])
+            .args(&server_args)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("启动 scrcpy-server 失败: {}", e))?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let session = ScrcpySession {
+            adb_path: adb_path.clone(),
+            serial: serial.clone(),
+            port,
+            server: child,
+            stop: stop.clone(),
+        };
+
+        // server 启动需要时间，重试连接
+        let stream = match connect_video_socket(port) {
+            Ok(s) => s,
+            Err(e) => {
+                session.shutdown();
+                return Err(e.into());
+            }
+        };
+
+        sessions()
+            .lock()
+            .map_err(|e| e.to_string())?
+            .insert(session_id, session);
+
+        let app_handle = app.clone();
+        std::thread::spawn(move || {
+            let reason = match read_video_stream(&app_handle, session_id, stream, &stop) {
+                Ok(()) => "stopped".to_string(),
+                // 主动停止时 server 被结束，读取会以错误返回
+                Err(_) if stop.load(Ordering::SeqCst) => "stopped".to_string(),
+                Err(e) => {
+                    warn!("[scrcpy#{}] Video stream ended: {}", session_id, e);
+                    e
+                }
+            };
+            // 流异常结束时清理会话
+            if let Some(session) = sessions()
+                .lock()
+                .ok()
+                .and_then(|mut s| s.remove(&session_id))
+            {
+                session.shutdown();
+            }
+            let _ = app_handle.emit("scrcpy-stopped", ScrcpyStoppedEvent { session_id, reason });
+        });
+
+        info!("[scrcpy#{}] Preview started on port {}", session_id, port);
+        Ok(ScrcpySessionInfo { session_id, port })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 停止 scrcpy 预览
+#[tauri::command]
+pub fn scrcpy_stop(session_id: u64) -> Result<(), MxuError> {
+    info!("scrcpy_stop called, session_id: {}", session_id);
+    let session = sessions()
+        .lock()
+        .map_err(|e| e.to_string())?
+        .remove(&session_id);
+    if let Some(session) = session {
+        session.shutdown();
+    }
+    Ok(())
+}
+
+/// 停止所有 scrcpy 预览（窗口销毁时调用）
+pub fn stop_all_sessions() {
+    if let Ok(mut all) = sessions().lock() {
+        for (id, session) in all.drain() {
+            debug!("[scrcpy#{}] Shutting down", id);
+            session.shutdown();
+        }
+    }
+}
+
+/// 连接 forward 端口，tunnel_forward 模式下 server 就绪后会先发送一个字节
+fn connect_video_socket(port: u16) -> Result<TcpStream, String> {
+    let mut last_error = String::new();
+    for _ in 0..50 {
+        match TcpStream::connect(("127.0.0.1", port)) {
+            Ok(mut stream) => {
+                let mut dummy = [0u8; 1];
+                match stream.read_exact(&mut dummy) {
+                    Ok(()) => return Ok(stream),
+                    // adb forward 在 server 未监听时会接受连接后立即关闭
+                    Err(e) => last_error = e.to_string(),
+                }
+            }
+            Err(e) => last_error = e.to_string(),
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    Err(format!("连接 scrcpy-server 失败: {}", last_error))
+}
+
+/// 读取视频流：设备名(64) + 编码元信息(12) + 若干 [帧头(12) + 数据包]
+fn read_video_stream(
+    app: &AppHandle,
+    session_id: u64,
+    mut stream: TcpStream,
+    stop: &AtomicBool,
+) -> Result<(), String> {
+    use base64::{engine::general_purpose::STANDARD, Engine as _};
+
+    const FLAG_CONFIG: u64 = 1 << 63;
+    const FLAG_KEY_FRAME: u64 = 1 << 62;
+    const PTS_MASK: u64 = FLAG_KEY_FRAME - 1;
+
+    let mut device_name = [0u8; 64];
+    stream
+        .read_exact(&mut device_name)
+        .map_err(|e| e.to_string())?;
+    let device_name = String::from_utf8_lossy(&device_name)
+        .trim_end_matches('\0')
+        .to_string();
+
+    let mut codec_meta = [0u8; 12];
+    stream
+        .read_exact(&mut codec_meta)
+        .map_err(|e| e.to_string())?;
+    let codec = String::from_utf8_lossy(&codec_meta[0..4]).to_string();
+    let width = u32::from_be_bytes([codec_meta[4], codec_meta[5], codec_meta[6], codec_meta[7]]);
+    let height = u32::from_be_bytes([codec_meta[8], codec_meta[9], codec_meta[10], codec_meta[11]]);
+
+    info!(
+        "[scrcpy#{}] Connected to {}: {} {}x{}",
+        session_id, device_name, codec, width, height
+    );
+    let _ = app.emit(
+        "scrcpy-meta",
+        ScrcpyMetaEvent {
+            session_id,
+            device_name,
+            codec,
+            width,
+            height,
+        },
+    );
+
+    let mut header = [0u8; 12];
+    while !stop.load(Ordering::SeqCst) {
+        stream.read_exact(&mut header).map_err(|e| e.to_string())?;
+        let pts_flags = u64::from_be_bytes(header[0..8].try_into().unwrap());
+        let size = u32::from_be_bytes(header[8..12].try_into().unwrap()) as usize;
+
+        let mut packet = vec![0u8; size];
+        stream.read_exact(&mut packet).map_err(|e| e.to_string())?;
+
+        if let Err(e) = app.emit(
+            "scrcpy-packet",
+            ScrcpyPacketEvent {
+                session_id,
+                config: pts_flags & FLAG_CONFIG != 0,
+                key_frame: pts_flags & FLAG_KEY_FRAME != 0,
+                pts: pts_flags & PTS_MASK,
+                data: STANDARD.encode(&packet),
+            },
+        ) {
+            error!("[scrcpy#{}] Failed to emit packet: {}", session_id, e);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,183 @@
+//! 截图工具命令
+//!
+//! 基于控制器缓存截图提供模板裁剪、像素取色等调试工具
+
+use std::path::Path;
+use std::sync::Arc;
+
+use log::info;
+use tauri::State;
+
+use super::error::MxuError;
+use super::file_ops::get_exe_dir;
+use super::i18n::ErrorCode;
+use super::types::{MaaState, PixelSample, SavedTemplate};
+
+/// 从控制器缓存截图解码出 RGBA 图像
+pub fn cached_screencap_image(
+    state: &MaaState,
+    instance_id: &str,
+) -> Result<image::RgbaImage, String> {
+    let data = {
+        let instances = state.instances.lock().map_err(|e| e.to_string())?;
+        let instance = instances
+            .get(instance_id)
+            .ok_or_else(|| MxuError::new(ErrorCode::InstanceNotFound))?;
+        let controller = instance
+            .controller
+            .as_ref()
+            .ok_or_else(|| MxuError::new(ErrorCode::ControllerNotConnected))?;
+        let buffer = controller.cached_image().map_err(|e| e.to_string())?;
+        buffer
+            .to_vec()
+            .ok_or("Failed to convert image buffer".to_string())?
+    };
+    if data.is_empty() {
+        return Err("No image data available".to_string());
+    }
+    image::load_from_memory(&data)
+        .map(|img| img.to_rgba8())
+        .map_err(|e| format!("解析截图失败: {}", e))
+}
+
+/// 检查路径是否为安全的相对路径（不允许绝对路径和 ..）
+fn is_safe_relative_path(path: &str) -> bool {
+    use std::path::Component;
+
+    !path.is_empty()
+        && Path::new(path)
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+}
+
+/// 从缓存截图中裁剪区域保存为模板图片
+///
+/// - `rect`: [x, y, width, height]
+/// - `name`: 模板名称，可包含子目录（如 `Common/Confirm`），自动补全 .png
+/// - `resource_subdir`: 资源目录（相对于 exe 目录，如 `resource/base`），图片写入其 image 子目录
+#[tauri::command]
+pub fn save_template_from_screencap(
+    state: State<Arc<MaaState>>,
+    instance_id: String,
+    rect: [i32; 4],
+    name: String,
+    resource_subdir: String,
+) -> Result<SavedTemplate, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+
+    let [x, y, width, height] = rect;
+    if x < 0 || y < 0 || width <= 0 || height <= 0 {
+        return Err(MxuError::with_args(
+            ErrorCode::InvalidRegion,
+            [format!("{:?}", rect)],
+        ));
+    }
+    let name = name.trim().replace('\\', "/");
+    let template = if name.to_lowercase().ends_with(".png") {
+        name.clone()
+    } else {
+        format!("{}.png", name)
+    };
+    if !is_safe_relative_path(&template) {
+        return Err(MxuError::with_args(ErrorCode::InvalidTemplateName, [&name]));
+    }
+    if !is_safe_relative_path(&resource_subdir) {
+        return Err(MxuError::with_args(
+            ErrorCode::InvalidResourceDir,
+            [&resource_subdir],
+        ));
+    }
+
+    let image = cached_screencap_image(&state, &instance_id)?;
+    let (x, y, width, height) = (x as u32, y as u32, width as u32, height as u32);
+    if x + width > image.width() || y + height > image.height() {
+        return Err(MxuError::with_args(
+            ErrorCode::RegionOutOfBounds,
+            [
+                format!("{:?}", rect),
+                image.width().to_string(),
+                image.height().to_string(),
+            ],
+        ));
+    }
+
+    let resource_dir = Path::new(&get_exe_dir()?).join(&resource_subdir);
+    if !resource_dir.is_dir() {
+        return Err(MxuError::with_args(
+            ErrorCode::DirectoryNotFound,
+            [resource_dir.display()],
+        ));
+    }
+    let target = resource_dir.join("image").join(&template);
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| {
+            MxuError::with_args(
+                ErrorCode::DirectoryCreateFailed,
+                [parent.display().to_string(), e.to_string()],
+            )
+        })?;
+    }
+
+    // MaaFramework 模板要求为不带透明通道的 PNG
+    let cropped = image::imageops::crop_imm(&image, x, y, width, height).to_image();
+    image::DynamicImage::ImageRgba8(cropped)
+        .to_rgb8()
+        .save_with_format(&target, image::ImageFormat::Png)
+        .map_err(|e| {
+            MxuError::with_args(
+                ErrorCode::FileWriteFailed,
+                [target.display().to_string(), e.to_string()],
+            )
+        })?;
+
+    // 建议的识别区域：在命中框四周各扩展 50 像素
+    const ROI_MARGIN: u32 = 50;
+    let roi_x = x.saturating_sub(ROI_MARGIN);
+    let roi_y = y.saturating_sub(ROI_MARGIN);
+    let roi_w = (x + width + ROI_MARGIN).min(image.width()) - roi_x;
+    let roi_h = (y + height + ROI_MARGIN).min(image.height()) - roi_y;
+    let snippet = serde_json::json!({
+        "recognition": "TemplateMatch",
+        "template": template,
+        "roi": [roi_x, roi_y, roi_w, roi_h],
+        "threshold": 0.8,
+    });
+
+    info!("Template saved: {}", target.display());
+    Ok(SavedTemplate {
+        path: target.to_string_lossy().to_string(),
+        template,
+        snippet,
+    })
+}
+
+/// 从缓存截图中读取指定坐标的 RGB 颜色值，用于编写 ColorMatch 节点
+///
+/// `points`: [[x, y], ...]，超出截图范围的点返回错误
+#[tauri::command]
+pub fn sample_pixels(
+    state: State<Arc<MaaState>>,
+    instance_id: String,
+    points: Vec<[i32; 2]>,
+) -> Result<Vec<PixelSample>, MxuError> {
+    let image = cached_screencap_image(&state, &instance_id)?;
+    points
+        .into_iter()
+        .map(|[x, y]| {
+            if x < 0 || y < 0 || x as u32 >= image.width() || y as u32 >= image.height() {
+                return Err(MxuError::with_args(
+                    ErrorCode::RegionOutOfBounds,
+                    [
+                        format!("({}, {})", x, y),
+                        image.width().to_string(),
+                        image.height().to_string(),
+                    ],
+                ));
+            }
+            let [r, g, b, _] = image.get_pixel(x as u32, y as u32).0;
+            // 启用颜色校准时返回参考颜色空间的值
+            let rgb = super::color_calibration::correct_color(&instance_id, [r, g, b]);
+            Ok(PixelSample { x, y, rgb })
+        })
+        .collect()
+}
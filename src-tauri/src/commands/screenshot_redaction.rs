@@ -0,0 +1,268 @@
+//! 截图自动打码
+//!
+//! 运行报告帧与导出日志中的 on_error 截图在保存/打包前，对以下区域打码（纯色填充或马赛克）：
+//! - config/screenshot_redaction.json 中按资源包配置的固定区域（720p 坐标，按截图短边缩放）
+//! - 运行中 OCR 识别结果里包含账号名 / UID 的文字框（从识别详情中学习，记录到本次进程结束）
+//!
+//! 导出日志时无法确定截图所属的资源，使用全部已配置区域与已学习区域
+
+use log::{info, warn};
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::path::PathBuf;
+use std::sync::{Arc, LazyLock, Mutex};
+
+use image::{Rgba, RgbaImage};
+use tauri::State;
+
+use super::error::MxuError;
+use super::types::{MaaState, RedactionConfig, RedactionStyle};
+use super::utils::{encode_png_data_url, get_app_data_dir, save_json_config};
+
+/// 区域坐标基准（MaaFramework 截图短边）
+const BASE_SHORT_SIDE: f64 = 720.0;
+/// 每个实例最多记录的学习区域
+const MAX_LEARNED: usize = 32;
+/// 马赛克块大小
+const PIXELATE_BLOCK: u32 = 12;
+/// 打码区域外扩像素，避免文字边缘残留
+const PADDING: i32 = 4;
+
+/// 各实例从 OCR 结果中学习到的账号名区域
+static LEARNED: LazyLock<Mutex<HashMap<String, Vec<[i32; 4]>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 各实例上次扫描识别记录的时间（Unix 毫秒）
+static LAST_SCAN: LazyLock<Mutex<HashMap<String, i64>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn config_path() -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?
+        .join("config")
+        .join("screenshot_redaction.json"))
+}
+
+fn load_config() -> Result<RedactionConfig, String> {
+    let path = config_path()?;
+    if !path.exists() {
+        return Ok(RedactionConfig::default());
+    }
+    let content =
+        std::fs::read_to_string(&path).map_err(|e| format!("无法读取截图打码配置: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("无法解析截图打码配置: {}", e))
+}
+
+/// 从 OCR 识别详情中查找包含账号名的文字框
+fn matching_boxes(detail: &serde_json::Value, names: &[String]) -> Vec<[i32; 4]> {
+    let Some(results) = detail.get("all").and_then(|a| a.as_array()) else {
+        return Vec::new();
+    };
+    results
+        .iter()
+        .filter(|r| {
+            r.get("text")
+                .and_then(|t| t.as_str())
+                .is_some_and(|text| names.iter().any(|n| text.contains(n.as_str())))
+        })
+        .filter_map(|r| {
+            let b = r.get("box")?.as_array()?;
+            let v: Vec<i32> = b
+                .iter()
+                .filter_map(|x| x.as_i64())
+                .map(|x| x as i32)
+                .collect();
+            (v.len() == 4).then(|| [v[0], v[1], v[2], v[3]])
+        })
+        .collect()
+}
+
+/// 扫描实例上次扫描之后的 OCR 识别记录，记录包含账号名的区域
+fn learn_from_recognitions(state: &MaaState, instance_id: &str, names: &[String]) {
+    if names.is_empty() {
+        return;
+    }
+    let Some(tasker) = state
+        .instances
+        .lock()
+        .ok()
+        .and_then(|i| i.get(instance_id).and_then(|i| i.tasker.clone()))
+    else {
+        return;
+    };
+    let since = LAST_SCAN
+        .lock()
+        .ok()
+        .and_then(|m| m.get(instance_id).copied())
+        .unwrap_or(0);
+    let records = super::recognition::records_since(since);
+    if let Ok(mut last) = LAST_SCAN.lock() {
+        last.insert(
+            instance_id.to_string(),
+            chrono::Utc::now().timestamp_millis(),
+        );
+    }
+
+    let mut found = Vec::new();
+    for record in records {
+        let Ok(info) = super::recognition::fetch_recognition_detail(&tasker, record.reco_id, false)
+        else {
+            continue;
+        };
+        if info.algorithm.eq_ignore_ascii_case("OCR") {
+            found.extend(matching_boxes(&info.detail, names));
+        }
+    }
+    if found.is_empty() {
+        return;
+    }
+    if let Ok(mut learned) = LEARNED.lock() {
+        let boxes = learned.entry(instance_id.to_string()).or_default();
+        for b in found {
+            if !boxes.contains(&b) {
+                info!(
+                    "[screenshot_redaction] Learned account name region {:?} for instance {}",
+                    b, instance_id
+                );
+                boxes.push(b);
+            }
+        }
+        let overflow = boxes.len().saturating_sub(MAX_LEARNED);
+        boxes.drain(..overflow);
+    }
+}
+
+/// 在图片上对区域打码（区域坐标为 720p 基准）
+fn apply(image: &mut RgbaImage, regions: &[[i32; 4]], style: RedactionStyle) {
+    let short_side = image.width().min(image.height()) as f64;
+    let scale = short_side / BASE_SHORT_SIDE;
+    for [x, y, w, h] in regions {
+        let x0 = ((*x - PADDING) as f64 * scale).max(0.0) as u32;
+        let y0 = ((*y - PADDING) as f64 * scale).max(0.0) as u32;
+        let x1 = (((*x + *w + PADDING) as f64 * scale).max(0.0) as u32).min(image.width());
+        let y1 = (((*y + *h + PADDING) as f64 * scale).max(0.0) as u32).min(image.height());
+        if x0 >= x1 || y0 >= y1 {
+            continue;
+        }
+        match style {
+            RedactionStyle::Fill => {
+                for py in y0..y1 {
+                    for px in x0..x1 {
+                        image.put_pixel(px, py, Rgba([0, 0, 0, 255]));
+                    }
+                }
+            }
+            RedactionStyle::Pixelate => {
+                for by in (y0..y1).step_by(PIXELATE_BLOCK as usize) {
+                    for bx in (x0..x1).step_by(PIXELATE_BLOCK as usize) {
+                        let ex = (bx + PIXELATE_BLOCK).min(x1);
+                        let ey = (by + PIXELATE_BLOCK).min(y1);
+                        let mut sum = [0u64; 4];
+                        for py in by..ey {
+                            for px in bx..ex {
+                                let p = image.get_pixel(px, py).0;
+                                for (s, v) in sum.iter_mut().zip(p) {
+                                    *s += v as u64;
+                                }
+                            }
+                        }
+                        let count = ((ex - bx) * (ey - by)) as u64;
+                        let avg = Rgba(sum.map(|s| (s / count) as u8));
+                        for py in by..ey {
+                            for px in bx..ex {
+                                image.put_pixel(px, py, avg);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// 实例适用的打码区域：全局区域、已加载资源包的区域与学习到的区域
+fn regions_for(config: &RedactionConfig, instance_id: &str) -> Vec<[i32; 4]> {
+    let mut regions: Vec<[i32; 4]> = config.regions.get("*").cloned().unwrap_or_default();
+    for bundle in super::game_update_check::loaded_bundles(instance_id) {
+        if let Some(r) = config.regions.get(&bundle) {
+            regions.extend(r.iter().copied());
+        }
+    }
+    if let Some(learned) = LEARNED
+        .lock()
+        .ok()
+        .and_then(|l| l.get(instance_id).cloned())
+    {
+        regions.extend(learned);
+    }
+    regions
+}
+
+/// 对实例的截图打码（运行报告帧保存前调用），未启用时不做处理
+pub fn redact_frame(state: &MaaState, instance_id: &str, image: &mut RgbaImage) {
+    let config = load_config().unwrap_or_default();
+    if !config.enabled {
+        return;
+    }
+    learn_from_recognitions(state, instance_id, &config.account_names);
+    apply(image, &regions_for(&config, instance_id), config.style);
+}
+
+/// 对导出的截图打码，返回打码后的 PNG；未启用时原样返回，无法解码时返回 None（不导出该图片）
+pub fn redact_export_image(content: Vec<u8>) -> Option<Vec<u8>> {
+    let config = load_config().unwrap_or_default();
+    if !config.enabled {
+        return Some(content);
+    }
+    let mut image = match image::load_from_memory(&content) {
+        Ok(image) => image.to_rgba8(),
+        Err(e) => {
+            warn!(
+                "[screenshot_redaction] Cannot decode image, skipped from export: {}",
+                e
+            );
+            return None;
+        }
+    };
+    let mut regions: Vec<[i32; 4]> = config.regions.values().flatten().copied().collect();
+    if let Ok(learned) = LEARNED.lock() {
+        regions.extend(learned.values().flatten().copied());
+    }
+    apply(&mut image, &regions, config.style);
+
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+        .ok()?;
+    Some(png)
+}
+
+/// 获取截图打码配置
+#[tauri::command]
+pub fn get_redaction_config() -> Result<RedactionConfig, MxuError> {
+    Ok(load_config()?)
+}
+
+/// 保存截图打码配置
+#[tauri::command]
+pub fn set_redaction_config(config: RedactionConfig) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    let path = config_path()?;
+    save_json_config(&path, &config)
+}
+
+/// 预览实例当前截图的打码效果（用于调整打码区域），返回 data URL
+#[tauri::command]
+pub fn preview_redaction(
+    state: State<Arc<MaaState>>,
+    instance_id: String,
+) -> Result<String, MxuError> {
+    let mut image = super::screencap_tools::cached_screencap_image(&state, &instance_id)?;
+    let config = load_config()?;
+    learn_from_recognitions(&state, &instance_id, &config.account_names);
+    apply(
+        &mut image,
+        &regions_for(&config, &instance_id),
+        config.style,
+    );
+    Ok(encode_png_data_url(&image)?)
+}
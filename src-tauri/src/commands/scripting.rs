@@ -0,0 +1,448 @@
+//! 脚本自动化
+//!
+//! 内嵌 Rhai 脚本引擎（scripting feature，默认启用），高级用户可编写小脚本调用控制器
+//! （click / swipe / key / input_text / screencap）、取色（pixel / resolution）、通知（notify）
+//! 与任务变量（get_var / set_var）。脚本可作为 MXU_SCRIPT_ACTION 在 pipeline 中执行，
+//! 也可通过 run_script 直接运行；脚本中 PARAM 为传入的参数，INSTANCE_ID 为关联的实例。
+//! 脚本在独立线程中执行，超时或任务停止时中断；返回 false 时视为动作失败
+
+use log::info;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+
+use super::error::MxuError;
+use super::i18n::{self, ErrorCode};
+use super::types::{MaaState, ScriptRunResult};
+use super::utils::{get_exe_directory, normalize_path};
+
+/// 应用句柄（脚本在 pipeline 的动作线程中执行，需要全局访问实例状态）
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+/// 默认超时
+const DEFAULT_TIMEOUT_SECS: u64 = 60;
+/// 最长超时
+const MAX_TIMEOUT_SECS: u64 = 3600;
+/// 检查停止请求的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 保存应用句柄
+pub fn init(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+}
+
+/// 脚本执行结果
+pub struct ScriptOutcome {
+    /// 脚本最后一个表达式的值（无返回值时为 None）
+    pub value: Option<Value>,
+    /// print 输出
+    pub logs: Vec<String>,
+    pub error: Option<String>,
+}
+
+impl ScriptOutcome {
+    /// 执行成功且未返回 false
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none() && self.value != Some(Value::Bool(false))
+    }
+}
+
+#[cfg_attr(not(feature = "scripting"), allow(dead_code))]
+fn maa_state() -> Result<Arc<MaaState>, String> {
+    APP.get()
+        .and_then(|app| app.try_state::<Arc<MaaState>>())
+        .map(|state| state.inner().clone())
+        .ok_or_else(|| "应用尚未初始化".to_string())
+}
+
+/// 超时秒数（未指定时为默认值，超过上限时截断）
+pub fn timeout_from(secs: Option<u64>) -> Duration {
+    Duration::from_secs(
+        secs.unwrap_or(DEFAULT_TIMEOUT_SECS)
+            .clamp(1, MAX_TIMEOUT_SECS),
+    )
+}
+
+/// 读取脚本源码：script 为内联源码，file 为脚本文件（相对路径基于 exe 目录）
+pub fn load_source(script: Option<&str>, file: Option<&str>) -> Result<String, String> {
+    if let Some(script) = script.filter(|s| !s.trim().is_empty()) {
+        return Ok(script.to_string());
+    }
+    let file = file
+        .filter(|f| !f.trim().is_empty())
+        .ok_or_else(|| i18n::message(ErrorCode::ScriptMissingSource, &[]))?;
+    let path = PathBuf::from(file);
+    let path = if path.is_absolute() {
+        normalize_path(file)
+    } else {
+        get_exe_directory()?.join(normalize_path(file))
+    };
+    std::fs::read_to_string(&path).map_err(|e| {
+        i18n::message(
+            ErrorCode::FileReadFailed,
+            &[path.display().to_string(), e.to_string()],
+        )
+    })
+}
+
+/// 只编译不执行，用于 dry-run 校验语法
+pub fn check(source: &str) -> Result<(), String> {
+    engine::compile(source)
+}
+
+/// 在独立线程中执行脚本，等待结束；stopping 返回 true 时中断脚本
+pub fn execute(
+    source: &str,
+    instance_id: Option<String>,
+    param: Value,
+    timeout: Duration,
+    stopping: &dyn Fn() -> bool,
+) -> ScriptOutcome {
+    let cancel = Arc::new(AtomicBool::new(false));
+    let logs = Arc::new(Mutex::new(Vec::new()));
+    let deadline = Instant::now() + timeout;
+
+    let result = std::thread::scope(|s| {
+        let handle = s.spawn(|| {
+            engine::eval(
+                source,
+                instance_id,
+                param,
+                deadline,
+                cancel.clone(),
+                logs.clone(),
+            )
+        });
+        while !handle.is_finished() {
+            if stopping() {
+                cancel.store(true, Ordering::Relaxed);
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+        handle
+            .join()
+            .unwrap_or_else(|_| Err("脚本执行时发生 panic".to_string()))
+    });
+
+    let logs = std::mem::take(&mut *logs.lock().unwrap_or_else(|e| e.into_inner()));
+    match result {
+        Ok(value) => ScriptOutcome {
+            value,
+            logs,
+            error: None,
+        },
+        Err(e) => ScriptOutcome {
+            value: None,
+            logs,
+            error: Some(e),
+        },
+    }
+}
+
+#[cfg(feature = "scripting")]
+mod engine {
+    use log::info;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    use maa_framework::controller::Controller;
+    use maa_framework::MaaStatus;
+    use rhai::{Array, Dynamic, Engine, EvalAltResult, Scope};
+    use serde_json::Value;
+
+    use super::maa_state;
+    use crate::commands::i18n::{message, ErrorCode};
+    use crate::commands::types::ToastAction;
+
+    type FnResult<T> = Result<T, Box<EvalAltResult>>;
+
+    /// 单次执行的最大操作数（防止死循环占满 CPU，超时之外的第二道限制）
+    const MAX_OPERATIONS: u64 = 50_000_000;
+    /// 字符串最大长度（字节）
+    const MAX_STRING_SIZE: usize = 1024 * 1024;
+    /// 数组最大元素数
+    const MAX_ARRAY_SIZE: usize = 100_000;
+    /// 对象映射最大键数
+    const MAX_MAP_SIZE: usize = 10_000;
+    /// 最大函数调用深度
+    const MAX_CALL_LEVELS: usize = 64;
+
+    /// 创建带资源限制的引擎，避免脚本耗尽内存或栈空间拖垮 MXU
+    fn new_engine() -> Engine {
+        let mut engine = Engine::new();
+        engine
+            .set_max_operations(MAX_OPERATIONS)
+            .set_max_string_size(MAX_STRING_SIZE)
+            .set_max_array_size(MAX_ARRAY_SIZE)
+            .set_max_map_size(MAX_MAP_SIZE)
+            .set_max_call_levels(MAX_CALL_LEVELS);
+        engine
+    }
+
+    pub fn compile(source: &str) -> Result<(), String> {
+        new_engine()
+            .compile(source)
+            .map(|_| ())
+            .map_err(|e| message(ErrorCode::ScriptSyntaxError, &[e.to_string()]))
+    }
+
+    fn instance(instance_id: &Option<String>) -> FnResult<&str> {
+        Ok(instance_id
+            .as_deref()
+            .ok_or("脚本未关联实例，无法使用控制器")?)
+    }
+
+    /// 提交控制器操作并等待完成
+    fn post_and_wait(
+        instance_id: &Option<String>,
+        operation: &'static str,
+        post: impl FnOnce(&Controller) -> Result<i64, String> + Send + 'static,
+    ) -> FnResult<()> {
+        let id = instance(instance_id)?;
+        let state = maa_state()?;
+        let controller = crate::commands::maa_core::connected_controller(&state, id)?;
+        crate::commands::ffi_guard::call(id, operation, move || {
+            let ctrl_id = post(&controller)?;
+            match controller.wait(ctrl_id) {
+                MaaStatus::SUCCEEDED => Ok(()),
+                _ => Err(format!("控制器操作 {} 失败", operation)),
+            }
+        })??;
+        Ok(())
+    }
+
+    fn register_api(
+        engine: &mut Engine,
+        instance_id: &Option<String>,
+        deadline: Instant,
+        cancel: &Arc<AtomicBool>,
+    ) {
+        let id = instance_id.clone();
+        engine.register_fn("click", move |x: i64, y: i64| {
+            post_and_wait(&id, "post_click", move |c| {
+                c.post_click(x as i32, y as i32).map_err(|e| e.to_string())
+            })
+        });
+        let id = instance_id.clone();
+        engine.register_fn(
+            "swipe",
+            move |x1: i64, y1: i64, x2: i64, y2: i64, duration: i64| {
+                post_and_wait(&id, "post_swipe", move |c| {
+                    c.post_swipe(x1 as i32, y1 as i32, x2 as i32, y2 as i32, duration as i32)
+                        .map_err(|e| e.to_string())
+                })
+            },
+        );
+        let id = instance_id.clone();
+        engine.register_fn("key", move |keycode: i64| {
+            post_and_wait(&id, "post_click_key", move |c| {
+                c.post_click_key(keycode as i32).map_err(|e| e.to_string())
+            })
+        });
+        let id = instance_id.clone();
+        engine.register_fn("input_text", move |text: &str| {
+            let text = text.to_string();
+            post_and_wait(&id, "post_input_text", move |c| {
+                c.post_input_text(&text).map_err(|e| e.to_string())
+            })
+        });
+        let id = instance_id.clone();
+        engine.register_fn("screencap", move || {
+            post_and_wait(&id, "post_screencap", |c| {
+                c.post_screencap().map_err(|e| e.to_string())
+            })
+        });
+
+        // 取色基于最近一帧截图，需要最新画面时先调用 screencap()
+        let id = instance_id.clone();
+        engine.register_fn("pixel", move |x: i64, y: i64| -> FnResult<Array> {
+            let id = instance(&id)?;
+            let image =
+                crate::commands::screencap_tools::cached_screencap_image(&maa_state()?, id)?;
+            if x < 0 || y < 0 || x as u32 >= image.width() || y as u32 >= image.height() {
+                let args = [
+                    format!("({}, {})", x, y),
+                    image.width().to_string(),
+                    image.height().to_string(),
+                ];
+                return Err(message(ErrorCode::RegionOutOfBounds, &args).into());
+            }
+            let [r, g, b, _] = image.get_pixel(x as u32, y as u32).0;
+            let rgb = crate::commands::color_calibration::correct_color(id, [r, g, b]);
+            Ok(rgb.iter().map(|&v| Dynamic::from(v as i64)).collect())
+        });
+        let id = instance_id.clone();
+        engine.register_fn("resolution", move || -> FnResult<Array> {
+            let id = instance(&id)?;
+            let controller = crate::commands::maa_core::connected_controller(&maa_state()?, id)?;
+            let (width, height) = controller.resolution().map_err(|e| e.to_string())?;
+            Ok(vec![
+                Dynamic::from(width as i64),
+                Dynamic::from(height as i64),
+            ])
+        });
+
+        engine.register_fn("notify", |title: &str, body: &str| -> FnResult<()> {
+            crate::commands::notifications::show(title, body, &[ToastAction::Open], None)?;
+            Ok(())
+        });
+        // 变量按实例隔离，未关联实例的脚本共用空实例 ID
+        let id = instance_id.clone().unwrap_or_default();
+        engine.register_fn("get_var", move |name: &str| -> FnResult<Dynamic> {
+            match crate::commands::variables::get_variable(&id, name) {
+                Some(value) => rhai::serde::to_dynamic(value),
+                None => Ok(Dynamic::UNIT),
+            }
+        });
+        let id = instance_id.clone().unwrap_or_default();
+        engine.register_fn(
+            "set_var",
+            move |name: &str, value: Dynamic| -> FnResult<()> {
+                let value: Value = rhai::serde::from_dynamic(&value)?;
+                crate::commands::variables::set_variable(&id, name, value);
+                Ok(())
+            },
+        );
+
+        // 可中断的等待
+        let cancel = cancel.clone();
+        engine.register_fn("sleep", move |ms: i64| -> FnResult<()> {
+            let until = Instant::now() + Duration::from_millis(ms.max(0) as u64);
+            while Instant::now() < until {
+                if cancel.load(Ordering::Relaxed) {
+                    return Err(message(ErrorCode::ScriptStopped, &[]).into());
+                }
+                if Instant::now() > deadline {
+                    return Err(message(ErrorCode::ScriptTimedOut, &[]).into());
+                }
+                std::thread::sleep(
+                    until
+                        .saturating_duration_since(Instant::now())
+                        .min(super::POLL_INTERVAL),
+                );
+            }
+            Ok(())
+        });
+    }
+
+    pub fn eval(
+        source: &str,
+        instance_id: Option<String>,
+        param: Value,
+        deadline: Instant,
+        cancel: Arc<AtomicBool>,
+        logs: Arc<Mutex<Vec<String>>>,
+    ) -> Result<Option<Value>, String> {
+        let mut engine = new_engine();
+        register_api(&mut engine, &instance_id, deadline, &cancel);
+        engine.on_print(move |text| {
+            info!("[scripting] {}", text);
+            if let Ok(mut logs) = logs.lock() {
+                logs.push(text.to_string());
+            }
+        });
+        engine.on_progress(move |_| {
+            if cancel.load(Ordering::Relaxed) {
+                Some(Dynamic::from(message(ErrorCode::ScriptStopped, &[])))
+            } else if Instant::now() > deadline {
+                Some(Dynamic::from(message(ErrorCode::ScriptTimedOut, &[])))
+            } else {
+                None
+            }
+        });
+
+        let ast = engine
+            .compile(source)
+            .map_err(|e| message(ErrorCode::ScriptSyntaxError, &[e.to_string()]))?;
+        let mut scope = Scope::new();
+        scope.push_constant(
+            "PARAM",
+            rhai::serde::to_dynamic(param).map_err(|e| e.to_string())?,
+        );
+        scope.push_constant("INSTANCE_ID", instance_id.unwrap_or_default());
+
+        let result = engine
+            .eval_ast_with_scope::<Dynamic>(&mut scope, &ast)
+            .map_err(|e| match *e {
+                EvalAltResult::ErrorTerminated(reason, _) => reason.to_string(),
+                e => message(ErrorCode::ScriptFailed, &[e.to_string()]),
+            })?;
+        if result.is_unit() {
+            return Ok(None);
+        }
+        rhai::serde::from_dynamic::<Value>(&result)
+            .map(Some)
+            .map_err(|e| format!("无法转换脚本返回值: {}", e))
+    }
+}
+
+#[cfg(not(feature = "scripting"))]
+mod engine {
+    use std::sync::atomic::AtomicBool;
+    use std::sync::{Arc, Mutex};
+    use std::time::Instant;
+
+    use serde_json::Value;
+
+    use crate::commands::i18n::{message, ErrorCode};
+
+    pub fn compile(_source: &str) -> Result<(), String> {
+        Err(message(ErrorCode::ScriptingDisabled, &[]))
+    }
+
+    pub fn eval(
+        _source: &str,
+        _instance_id: Option<String>,
+        _param: Value,
+        _deadline: Instant,
+        _cancel: Arc<AtomicBool>,
+        _logs: Arc<Mutex<Vec<String>>>,
+    ) -> Result<Option<Value>, String> {
+        Err(message(ErrorCode::ScriptingDisabled, &[]))
+    }
+}
+
+/// 直接运行脚本
+/// instance_id: 关联的实例（使用控制器接口时必填）；param: 脚本中的 PARAM；timeout_secs: 默认 60 秒
+#[tauri::command]
+pub async fn run_script(
+    source: String,
+    instance_id: Option<String>,
+    param: Option<Value>,
+    timeout_secs: Option<u64>,
+) -> Result<ScriptRunResult, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    info!("run_script called, instance_id: {:?}", instance_id);
+
+    let timeout = timeout_from(timeout_secs);
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let start = Instant::now();
+        let outcome = execute(
+            &source,
+            instance_id,
+            param.unwrap_or(Value::Null),
+            timeout,
+            &|| false,
+        );
+        ScriptRunResult {
+            success: outcome.succeeded(),
+            value: outcome.value,
+            logs: outcome.logs,
+            error: outcome.error,
+            elapsed_ms: start.elapsed().as_millis() as u64,
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    info!(
+        "[scripting] Script finished in {}ms, success: {}",
+        result.elapsed_ms, result.success
+    );
+    Ok(result)
+}
@@ -0,0 +1,262 @@
+//! 启动动作
+//!
+//! 按 config/startup_actions.json 中的顺序在启动后依次执行（加载运行库、连接上次设备、
+//! 预览截图、启用定时任务、检查更新），每项可单独开关。
+//! 定时任务与检查更新由前端实现，后端按顺序发送 startup-action 事件交由前端执行
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::error::MxuError;
+use super::types::{
+    ControllerConfig, MaaState, StartupAction, StartupActionEvent, StartupActionKind,
+};
+use super::utils::{get_app_data_dir, save_json_config};
+
+/// 等待设备连接完成的超时时间
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 配置文件读写锁
+static CONFIG_LOCK: Mutex<()> = Mutex::new(());
+
+/// 本次启动已执行动作的结果（前端晚于事件加载时通过 startup_actions_results 查询）
+static RESULTS: Mutex<Vec<StartupActionEvent>> = Mutex::new(Vec::new());
+
+/// 上次成功连接的设备
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LastDevice {
+    instance_id: String,
+    config: ControllerConfig,
+}
+
+/// 启动动作配置文件结构
+#[derive(Debug, Serialize, Deserialize)]
+struct StartupActionsConfig {
+    #[serde(default = "default_actions")]
+    actions: Vec<StartupAction>,
+    #[serde(default)]
+    last_device: Option<LastDevice>,
+}
+
+impl Default for StartupActionsConfig {
+    fn default() -> Self {
+        Self {
+            actions: default_actions(),
+            last_device: None,
+        }
+    }
+}
+
+/// 默认动作列表：全部列出，仅加载运行库默认启用（与旧版启动行为一致）
+fn default_actions() -> Vec<StartupAction> {
+    [
+        StartupActionKind::LoadLibrary,
+        StartupActionKind::ConnectLastDevice,
+        StartupActionKind::StartPreview,
+        StartupActionKind::ArmScheduler,
+        StartupActionKind::CheckUpdates,
+    ]
+    .into_iter()
+    .map(|kind| StartupAction {
+        kind,
+        enabled: kind == StartupActionKind::LoadLibrary,
+    })
+    .collect()
+}
+
+fn config_path() -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?
+        .join("config")
+        .join("startup_actions.json"))
+}
+
+fn load_config() -> Result<StartupActionsConfig, String> {
+    let path = config_path()?;
+    if !path.exists() {
+        return Ok(StartupActionsConfig::default());
+    }
+    let content =
+        std::fs::read_to_string(&path).map_err(|e| format!("无法读取启动动作配置: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("无法解析启动动作配置: {}", e))
+}
+
+fn modify_config(f: impl FnOnce(&mut StartupActionsConfig)) -> Result<(), String> {
+    let _guard = CONFIG_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut config = load_config()?;
+    f(&mut config);
+
+    let path = config_path()?;
+    Ok(save_json_config(&path, &config)?)
+}
+
+/// 记录最近一次连接的设备（maa_connect_controller 时调用）
+pub fn remember_last_device(instance_id: &str, config: &ControllerConfig) {
+    let result = modify_config(|c| {
+        c.last_device = Some(LastDevice {
+            instance_id: instance_id.to_string(),
+            config: config.clone(),
+        })
+    });
+    if let Err(e) = result {
+        warn!("[startup_actions] Failed to remember last device: {}", e);
+    }
+}
+
+/// 最近连接设备的类型描述（不含地址等可识别信息，用于问题反馈）
+pub fn last_device_kind() -> Option<String> {
+    let device = load_config().ok()?.last_device?;
+    Some(match device.config {
+        ControllerConfig::Adb { .. } => "ADB".to_string(),
+        ControllerConfig::Win32 {
+            screencap_method,
+            mouse_method,
+            ..
+        } => format!(
+            "Win32 (screencap: {}, mouse: {})",
+            screencap_method, mouse_method
+        ),
+        ControllerConfig::Gamepad { gamepad_type, .. } => {
+            format!("Gamepad ({})", gamepad_type.as_deref().unwrap_or("default"))
+        }
+        ControllerConfig::PlayCover { .. } => "PlayCover".to_string(),
+        ControllerConfig::Mock { .. } => "Mock".to_string(),
+    })
+}
+
+/// 获取启动动作列表
+#[tauri::command]
+pub fn startup_actions_get() -> Result<Vec<StartupAction>, MxuError> {
+    Ok(load_config()?.actions)
+}
+
+/// 设置启动动作列表（顺序即执行顺序）
+#[tauri::command]
+pub fn startup_actions_set(actions: Vec<StartupAction>) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    modify_config(|c| c.actions = actions)?;
+    info!("[startup_actions] Startup actions updated");
+    Ok(())
+}
+
+/// 获取本次启动已执行动作的结果
+#[tauri::command]
+pub fn startup_actions_results() -> Result<Vec<StartupActionEvent>, MxuError> {
+    Ok(RESULTS.lock().map_err(|e| e.to_string())?.clone())
+}
+
+fn emit_result(app: &AppHandle, kind: StartupActionKind, status: &str, message: Option<String>) {
+    let event = StartupActionEvent {
+        kind,
+        status: status.to_string(),
+        message,
+    };
+    if let Ok(mut results) = RESULTS.lock() {
+        results.push(event.clone());
+    }
+    if let Err(e) = app.emit("startup-action", event) {
+        log::error!("Failed to emit startup-action: {}", e);
+    }
+}
+
+/// 连接上次的设备并等待连接完成
+async fn connect_last_device(app: &AppHandle, device: &LastDevice) -> Result<(), String> {
+    let state = app.state::<Arc<MaaState>>();
+    super::maa_core::maa_create_instance(state.clone(), device.instance_id.clone())?;
+    super::maa_core::maa_connect_controller(
+        app.clone(),
+        state.clone(),
+        device.instance_id.clone(),
+        device.config.clone(),
+    )
+    .await?;
+
+    let start = Instant::now();
+    while start.elapsed() < CONNECT_TIMEOUT {
+        let connected = state.instances.lock().is_ok_and(|instances| {
+            instances
+                .get(&device.instance_id)
+                .and_then(|i| i.controller.as_ref())
+                .is_some_and(|c| c.connected())
+        });
+        if connected {
+            return Ok(());
+        }
+        sleep_async(Duration::from_millis(500)).await;
+    }
+    Err("等待设备连接超时".to_string())
+}
+
+/// 异步等待（tokio 未启用 time 特性，借助阻塞线程池实现）
+async fn sleep_async(duration: Duration) {
+    let _ = tauri::async_runtime::spawn_blocking(move || std::thread::sleep(duration)).await;
+}
+
+/// 执行单个启动动作，返回 (状态, 说明)
+async fn run_action(
+    app: &AppHandle,
+    kind: StartupActionKind,
+    last_device: Option<&LastDevice>,
+    connected: &mut Option<String>,
+) -> Result<(&'static str, Option<String>), String> {
+    match kind {
+        StartupActionKind::LoadLibrary => {
+            let version = super::maa_core::maa_init(app.state::<Arc<MaaState>>(), None)?;
+            Ok(("done", Some(version)))
+        }
+        StartupActionKind::ConnectLastDevice => {
+            let Some(device) = last_device else {
+                return Ok(("skipped", Some("没有上次连接的设备".to_string())));
+            };
+            connect_last_device(app, device).await?;
+            *connected = Some(device.instance_id.clone());
+            Ok(("done", Some(device.instance_id.clone())))
+        }
+        StartupActionKind::StartPreview => {
+            let Some(instance_id) = connected.clone() else {
+                return Ok(("skipped", Some("设备未连接".to_string())));
+            };
+            super::maa_core::maa_post_screencap(app.state::<Arc<MaaState>>(), instance_id.clone())?;
+            Ok(("done", Some(instance_id)))
+        }
+        StartupActionKind::ArmScheduler | StartupActionKind::CheckUpdates => {
+            Ok(("delegated", None))
+        }
+    }
+}
+
+/// 在后台按顺序执行已启用的启动动作
+pub fn spawn_startup_actions(app: AppHandle) {
+    let config = match load_config() {
+        Ok(config) => config,
+        Err(e) => {
+            warn!("[startup_actions] {}", e);
+            return;
+        }
+    };
+
+    tauri::async_runtime::spawn(async move {
+        let mut connected = None;
+        for action in config.actions.iter().filter(|a| a.enabled) {
+            info!("[startup_actions] Running {:?}", action.kind);
+            let result = run_action(
+                &app,
+                action.kind,
+                config.last_device.as_ref(),
+                &mut connected,
+            )
+            .await;
+            match result {
+                Ok((status, message)) => emit_result(&app, action.kind, status, message),
+                Err(e) => {
+                    warn!("[startup_actions] {:?} failed: {}", action.kind, e);
+                    emit_result(&app, action.kind, "failed", Some(e));
+                }
+            }
+        }
+    });
+}
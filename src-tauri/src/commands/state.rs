@@ -2,12 +2,14 @@
 //!
 //! 提供实例状态和缓存数据查询功能
 
-use log::debug;
+use log::{debug, warn};
 use std::collections::HashMap;
 use std::sync::Arc;
 
 use tauri::State;
 
+use super::error::MxuError;
+use super::i18n::ErrorCode;
 use super::types::{AdbDevice, AllInstanceStates, InstanceState, MaaState, Win32Window};
 
 /// 获取单个实例的运行时状态
@@ -15,7 +17,7 @@ use super::types::{AdbDevice, AllInstanceStates, InstanceState, MaaState, Win32W
 pub fn maa_get_instance_state(
     state: State<Arc<MaaState>>,
     instance_id: String,
-) -> Result<InstanceState, String> {
+) -> Result<InstanceState, MxuError> {
     debug!(
         "maa_get_instance_state called, instance_id: {}",
         instance_id
@@ -24,7 +26,7 @@ pub fn maa_get_instance_state(
     let mut instances = state.instances.lock().map_err(|e| e.to_string())?;
     let instance = instances
         .get_mut(&instance_id)
-        .ok_or("Instance not found")?;
+        .ok_or_else(|| MxuError::new(ErrorCode::InstanceNotFound))?;
 
     // 通过 Maa API 查询真实状态
     let is_running = instance.tasker.as_ref().is_some_and(|t| t.running());
@@ -40,12 +42,13 @@ pub fn maa_get_instance_state(
         tasker_inited: instance.tasker.as_ref().is_some_and(|t| t.inited()),
         is_running,
         task_ids: instance.task_ids.clone(),
+        is_paused: super::pause_gate::is_paused(&instance_id),
     })
 }
 
 /// 获取所有实例的状态快照（用于前端启动时恢复状态）
 #[tauri::command]
-pub fn maa_get_all_states(state: State<Arc<MaaState>>) -> Result<AllInstanceStates, String> {
+pub fn maa_get_all_states(state: State<Arc<MaaState>>) -> Result<AllInstanceStates, MxuError> {
     debug!("maa_get_all_states called");
 
     let mut instances = state.instances.lock().map_err(|e| e.to_string())?;
@@ -74,20 +77,28 @@ pub fn maa_get_all_states(state: State<Arc<MaaState>>) -> Result<AllInstanceStat
                 tasker_inited: instance.tasker.as_ref().is_some_and(|t| t.inited()),
                 is_running,
                 task_ids: instance.task_ids.clone(),
+                is_paused: super::pause_gate::is_paused(id),
             },
         );
     }
 
+    let groups = super::device_groups::load_groups().unwrap_or_else(|e| {
+        warn!("Failed to load device groups: {}", e);
+        Vec::new()
+    });
+    let device_groups = super::device_groups::aggregate(&groups, &instance_states);
+
     Ok(AllInstanceStates {
         instances: instance_states,
         cached_adb_devices: cached_adb.clone(),
         cached_win32_windows: cached_win32.clone(),
+        device_groups,
     })
 }
 
 /// 获取缓存的 ADB 设备列表
 #[tauri::command]
-pub fn maa_get_cached_adb_devices(state: State<Arc<MaaState>>) -> Result<Vec<AdbDevice>, String> {
+pub fn maa_get_cached_adb_devices(state: State<Arc<MaaState>>) -> Result<Vec<AdbDevice>, MxuError> {
     debug!("maa_get_cached_adb_devices called");
     let cached = state.cached_adb_devices.lock().map_err(|e| e.to_string())?;
     Ok(cached.clone())
@@ -97,7 +108,7 @@ pub fn maa_get_cached_adb_devices(state: State<Arc<MaaState>>) -> Result<Vec<Adb
 #[tauri::command]
 pub fn maa_get_cached_win32_windows(
     state: State<Arc<MaaState>>,
-) -> Result<Vec<Win32Window>, String> {
+) -> Result<Vec<Win32Window>, MxuError> {
     debug!("maa_get_cached_win32_windows called");
     let cached = state
         .cached_win32_windows
@@ -0,0 +1,261 @@
+//! 分级停止
+//!
+//! maa_stop_task 支持三种停止方式（见 StopMode）：
+//! - graceful：队列模式下等待当前任务结束后停止，然后执行配置的收尾任务
+//! - soft：请求 Tasker 停止，在下一个停止检查点中断
+//! - hard：请求停止的同时立即结束全部 Agent 进程
+//!
+//! 发起停止后由后台线程跟踪，超时未停止时依次升级（graceful → soft → hard），
+//! 各阶段通过 stop-progress 事件通知前端
+
+use log::{info, warn};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Emitter};
+
+use maa_framework::tasker::Tasker;
+
+use super::error::MxuError;
+use super::types::{MaaState, StopMode, StopProgressEvent, StopSettings, StopStage};
+use super::utils::{get_app_data_dir, save_json_config};
+
+/// 状态检查间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+/// hard 停止后等待 Tasker 停止的时间
+const HARD_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 各实例最近一次停止请求的序号，新的请求会替换旧的跟踪线程
+static GENERATIONS: LazyLock<Mutex<HashMap<String, u64>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 配置读写锁
+static CONFIG_LOCK: Mutex<()> = Mutex::new(());
+
+fn config_path() -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?.join("config").join("stop.json"))
+}
+
+/// 读取停止设置，文件不存在或无法解析时使用默认值
+pub fn load_settings() -> StopSettings {
+    config_path()
+        .ok()
+        .and_then(|p| std::fs::read_to_string(p).ok())
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+/// 实例是否有进行中的任务队列
+pub fn queue_active(state: &MaaState, instance_id: &str) -> bool {
+    state
+        .task_queues
+        .lock()
+        .is_ok_and(|queues| queues.get(instance_id).is_some_and(|q| q.is_active()))
+}
+
+/// 请求 Tasker 停止（soft）
+pub fn post_stop(instance_id: &str, tasker: Tasker) -> Result<(), String> {
+    super::ffi_guard::call(instance_id, "post_stop", move || {
+        tasker.post_stop().map(|_| ()).map_err(|e| e.to_string())
+    })?
+}
+
+/// 立即结束实例的全部 Agent 进程（hard），同时结束崩溃监控避免被重启
+pub fn kill_agents(state: &MaaState, instance_id: &str) {
+    let (clients, children) = match state.instances.lock() {
+        Ok(mut instances) => match instances.get_mut(instance_id) {
+            Some(instance) => {
+                instance.agent_launch = None;
+                (
+                    std::mem::take(&mut instance.agent_clients),
+                    std::mem::take(&mut instance.agent_children),
+                )
+            }
+            None => return,
+        },
+        Err(_) => return,
+    };
+    info!(
+        "[stop_control] Killing {} agent process(es) of instance {}",
+        children.len(),
+        instance_id
+    );
+    for mut child in children {
+        super::agent_sandbox::kill_tree(&mut child);
+    }
+    for client in clients {
+        let _ = client.disconnect();
+    }
+}
+
+fn emit(app: &AppHandle, instance_id: &str, mode: StopMode, stage: StopStage, started: Instant) {
+    let _ = app.emit(
+        "stop-progress",
+        StopProgressEvent {
+            instance_id: instance_id.to_string(),
+            mode,
+            stage,
+            elapsed_ms: started.elapsed().as_millis() as u64,
+        },
+    );
+}
+
+/// 任务是否已全部停止（队列已结束且 Tasker 空闲）
+fn is_stopped(state: &MaaState, instance_id: &str, tasker: &Tasker) -> bool {
+    !tasker.running() && !queue_active(state, instance_id)
+}
+
+/// 本次跟踪是否已被新的停止请求替换
+fn superseded(instance_id: &str, generation: u64) -> bool {
+    GENERATIONS.lock().is_ok_and(|g| {
+        g.get(instance_id)
+            .is_some_and(|&current| current != generation)
+    })
+}
+
+/// 等待停止，超时返回 false；被新的停止请求替换时返回 None
+fn wait_stopped(
+    state: &MaaState,
+    instance_id: &str,
+    tasker: &Tasker,
+    generation: u64,
+    timeout: Duration,
+) -> Option<bool> {
+    let start = Instant::now();
+    loop {
+        if superseded(instance_id, generation) {
+            return None;
+        }
+        if is_stopped(state, instance_id, tasker) {
+            return Some(true);
+        }
+        if start.elapsed() >= timeout {
+            return Some(false);
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// 执行收尾任务并等待完成
+fn run_cleanup(state: &MaaState, instance_id: &str, tasker: &Tasker, generation: u64) {
+    let settings = load_settings();
+    for task in &settings.cleanup_tasks {
+        if let Err(e) = tasker.post_task(&task.entry, &task.pipeline_override) {
+            warn!(
+                "[stop_control] Failed to post cleanup task {}: {}",
+                task.entry, e
+            );
+        }
+    }
+    let timeout = Duration::from_secs(settings.graceful_timeout_secs);
+    if wait_stopped(state, instance_id, tasker, generation, timeout) == Some(false) {
+        warn!(
+            "[stop_control] Cleanup tasks of instance {} timed out, stopping",
+            instance_id
+        );
+        let _ = post_stop(instance_id, tasker.clone());
+    }
+}
+
+/// 跟踪停止进度并按超时升级（maa_stop_task 发起停止后调用）
+pub fn track(
+    app: AppHandle,
+    state: Arc<MaaState>,
+    instance_id: String,
+    tasker: Tasker,
+    mode: StopMode,
+) {
+    let generation = match GENERATIONS.lock() {
+        Ok(mut generations) => {
+            let next = generations.get(&instance_id).map_or(0, |g| g + 1);
+            generations.insert(instance_id.clone(), next);
+            next
+        }
+        Err(_) => return,
+    };
+    let started = Instant::now();
+    emit(&app, &instance_id, mode, StopStage::Requested, started);
+    info!(
+        "[stop_control] Stop requested for instance {} ({:?})",
+        instance_id, mode
+    );
+
+    thread::spawn(move || {
+        let settings = load_settings();
+        let mut current = mode;
+        loop {
+            let timeout = match current {
+                StopMode::Graceful => Duration::from_secs(settings.graceful_timeout_secs),
+                StopMode::Soft => Duration::from_secs(settings.soft_timeout_secs),
+                StopMode::Hard => HARD_TIMEOUT,
+            };
+            match wait_stopped(&state, &instance_id, &tasker, generation, timeout) {
+                None => return,
+                Some(true) => break,
+                Some(false) => {}
+            }
+            current = match current {
+                StopMode::Graceful => {
+                    super::task_queue::cancel_queue(&state, &instance_id);
+                    if let Err(e) = post_stop(&instance_id, tasker.clone()) {
+                        warn!("[stop_control] Failed to escalate to soft stop: {}", e);
+                    }
+                    StopMode::Soft
+                }
+                StopMode::Soft => {
+                    kill_agents(&state, &instance_id);
+                    let _ = post_stop(&instance_id, tasker.clone());
+                    StopMode::Hard
+                }
+                StopMode::Hard => {
+                    warn!(
+                        "[stop_control] Instance {} did not stop after hard stop",
+                        instance_id
+                    );
+                    emit(&app, &instance_id, current, StopStage::TimedOut, started);
+                    return;
+                }
+            };
+            info!(
+                "[stop_control] Stop of instance {} escalated to {:?}",
+                instance_id, current
+            );
+            emit(&app, &instance_id, current, StopStage::Escalated, started);
+        }
+
+        // 只有按 graceful 正常结束时执行收尾任务
+        if current == StopMode::Graceful && !settings.cleanup_tasks.is_empty() {
+            emit(&app, &instance_id, current, StopStage::Cleanup, started);
+            info!(
+                "[stop_control] Running {} cleanup task(s) for instance {}",
+                settings.cleanup_tasks.len(),
+                instance_id
+            );
+            run_cleanup(&state, &instance_id, &tasker, generation);
+        }
+        info!(
+            "[stop_control] Instance {} stopped in {:?}",
+            instance_id,
+            started.elapsed()
+        );
+        emit(&app, &instance_id, current, StopStage::Completed, started);
+    });
+}
+
+/// 获取停止设置
+#[tauri::command]
+pub fn stop_get_settings() -> StopSettings {
+    load_settings()
+}
+
+/// 保存停止设置
+#[tauri::command]
+pub fn stop_set_settings(settings: StopSettings) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    let _guard = CONFIG_LOCK.lock().map_err(|e| e.to_string())?;
+    let path = config_path()?;
+    save_json_config(&path, &settings)
+}
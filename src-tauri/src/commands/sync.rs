@@ -0,0 +1,394 @@
+//! WebDAV 云同步
+//!
+//! 将数据目录下的 config/ 与 profiles/ 推送到 / 拉取自用户提供的 WebDAV 服务。
+//! 远程目录下保存一份清单（文件哈希），本地记录上次同步时的哈希作为基准，
+//! 两端自基准起都发生修改的文件视为冲突，除非 force 否则跳过
+
+use log::{info, warn};
+use reqwest::{Method, StatusCode};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::error::MxuError;
+use super::file_ops::to_hex;
+use super::i18n::ErrorCode;
+use super::types::{SyncConfig, SyncResult};
+use super::update::is_safe_relative_path;
+use super::utils::{get_app_data_dir, save_json_config};
+
+/// 参与同步的数据目录子目录
+const SYNC_DIRS: &[&str] = &["config", "profiles"];
+
+/// 不参与同步的文件（包含本机凭据、本机的钩子确认记录，
+/// 以及远程 API、访客模式、链接协议注册这类只对本机生效的安全设置）
+const SYNC_EXCLUDED_FILES: &[&str] = &[
+    "sync.json",
+    "remote_auth.json",
+    "cluster_nodes.json",
+    "api_server.json",
+    "guest_mode.json",
+    "deep_link.json",
+    super::hooks::TRUST_FILE,
+];
+
+/// 不参与同步的子目录
+const SYNC_EXCLUDED_DIRS: &[&str] = &["backup"];
+
+/// 远程清单文件名
+const MANIFEST_FILE: &str = "mxu-sync-manifest.json";
+
+/// 请求超时
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 文件哈希表：相对路径（/ 分隔） -> sha256
+type Manifest = HashMap<String, String>;
+
+fn config_path() -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?.join("config").join("sync.json"))
+}
+
+/// 本地基准（上次同步时的哈希）
+fn base_path() -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?.join("cache").join("sync_base.json"))
+}
+
+fn read_json<T: serde::de::DeserializeOwned + Default>(path: &Path) -> Result<T, MxuError> {
+    if !path.exists() {
+        return Ok(T::default());
+    }
+    let content = std::fs::read_to_string(path).map_err(|e| {
+        MxuError::with_args(
+            ErrorCode::FileReadFailed,
+            [path.display().to_string(), e.to_string()],
+        )
+    })?;
+    serde_json::from_str(&content).map_err(|e| {
+        MxuError::with_args(
+            ErrorCode::FileParseFailed,
+            [path.display().to_string(), e.to_string()],
+        )
+    })
+}
+
+/// WebDAV 请求失败的错误
+fn request_failed(relative: &str, reason: impl std::fmt::Display) -> MxuError {
+    MxuError::with_args(
+        ErrorCode::SyncRequestFailed,
+        [relative.to_string(), reason.to_string()],
+    )
+}
+
+/// 获取同步配置
+#[tauri::command]
+pub fn sync_get_config() -> Result<SyncConfig, MxuError> {
+    read_json(&config_path()?)
+}
+
+/// 保存同步配置
+#[tauri::command]
+pub fn sync_set_config(config: SyncConfig) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    if !config.url.starts_with("http://") && !config.url.starts_with("https://") {
+        return Err(MxuError::with_args(ErrorCode::InvalidUrl, [&config.url]));
+    }
+    save_json_config(&config_path()?, &config)
+}
+
+/// 扫描本地参与同步的文件
+fn scan_local() -> Result<Manifest, MxuError> {
+    fn walk(dir: &Path, prefix: &str, out: &mut Manifest) -> Result<(), MxuError> {
+        let entries = std::fs::read_dir(dir).map_err(|e| {
+            MxuError::with_args(
+                ErrorCode::DirectoryReadFailed,
+                [dir.display().to_string(), e.to_string()],
+            )
+        })?;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            let relative = format!("{}/{}", prefix, name);
+            if path.is_dir() {
+                if !SYNC_EXCLUDED_DIRS.contains(&name.as_str()) {
+                    walk(&path, &relative, out)?;
+                }
+            } else if path.is_file() && !SYNC_EXCLUDED_FILES.contains(&name.as_str()) {
+                let content = std::fs::read(&path).map_err(|e| {
+                    MxuError::with_args(
+                        ErrorCode::FileReadFailed,
+                        [path.display().to_string(), e.to_string()],
+                    )
+                })?;
+                out.insert(relative, to_hex(&Sha256::digest(&content)));
+            }
+        }
+        Ok(())
+    }
+
+    let data_dir = get_app_data_dir()?;
+    let mut manifest = Manifest::new();
+    for dir in SYNC_DIRS {
+        let path = data_dir.join(dir);
+        if path.is_dir() {
+            walk(&path, dir, &mut manifest)?;
+        }
+    }
+    Ok(manifest)
+}
+
+/// WebDAV 客户端
+struct DavClient {
+    client: reqwest::Client,
+    root: String,
+    config: SyncConfig,
+}
+
+impl DavClient {
+    fn new(config: SyncConfig) -> Result<Self, MxuError> {
+        if config.url.is_empty() {
+            return Err(MxuError::new(ErrorCode::SyncNotConfigured));
+        }
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+        let remote_dir = config
+            .remote_dir
+            .clone()
+            .filter(|d| !d.trim().is_empty())
+            .unwrap_or_else(|| "mxu".to_string());
+        let root = format!(
+            "{}/{}",
+            config.url.trim_end_matches('/'),
+            remote_dir.trim_matches('/')
+        );
+        Ok(Self {
+            client,
+            root,
+            config,
+        })
+    }
+
+    fn request(&self, method: Method, relative: &str) -> reqwest::RequestBuilder {
+        let url = if relative.is_empty() {
+            format!("{}/", self.root)
+        } else {
+            format!("{}/{}", self.root, relative)
+        };
+        self.client
+            .request(method, url)
+            .basic_auth(&self.config.username, Some(&self.config.password))
+    }
+
+    /// 创建目录（已存在时服务端返回 405，忽略）
+    async fn mkcol(&self, relative: &str) -> Result<(), MxuError> {
+        let method = Method::from_bytes(b"MKCOL").map_err(|e| e.to_string())?;
+        let response = self
+            .request(method, relative)
+            .send()
+            .await
+            .map_err(|e| request_failed(relative, e))?;
+        match response.status() {
+            s if s.is_success() || s == StatusCode::METHOD_NOT_ALLOWED => Ok(()),
+            s => Err(request_failed(relative, format!("HTTP {}", s))),
+        }
+    }
+
+    /// 确保文件所在的远程目录存在
+    async fn ensure_parent_dirs(&self, relative: &str) -> Result<(), MxuError> {
+        self.mkcol("").await?;
+        let parts: Vec<&str> = relative.split('/').collect();
+        for i in 1..parts.len() {
+            self.mkcol(&parts[..i].join("/")).await?;
+        }
+        Ok(())
+    }
+
+    async fn get(&self, relative: &str) -> Result<Option<Vec<u8>>, MxuError> {
+        let response = self
+            .request(Method::GET, relative)
+            .send()
+            .await
+            .map_err(|e| request_failed(relative, e))?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(request_failed(
+                relative,
+                format!("HTTP {}", response.status()),
+            ));
+        }
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| request_failed(relative, e))?;
+        Ok(Some(bytes.to_vec()))
+    }
+
+    async fn put(&self, relative: &str, content: Vec<u8>) -> Result<(), MxuError> {
+        let response = self
+            .request(Method::PUT, relative)
+            .body(content)
+            .send()
+            .await
+            .map_err(|e| request_failed(relative, e))?;
+        if !response.status().is_success() {
+            return Err(request_failed(
+                relative,
+                format!("HTTP {}", response.status()),
+            ));
+        }
+        Ok(())
+    }
+
+    async fn read_manifest(&self) -> Result<Manifest, MxuError> {
+        match self.get(MANIFEST_FILE).await? {
+            Some(content) => serde_json::from_slice(&content)
+                .map_err(|e| MxuError::with_args(ErrorCode::SyncManifestInvalid, [e])),
+            None => Ok(Manifest::new()),
+        }
+    }
+}
+
+/// 判断文件自基准后是否两端都被修改
+fn is_conflict(base: Option<&String>, local: Option<&String>, remote: Option<&String>) -> bool {
+    local != remote && local != base && remote != base
+}
+
+/// 推送本地修改到 WebDAV
+#[tauri::command]
+pub async fn sync_push(force: Option<bool>) -> Result<SyncResult, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    let force = force.unwrap_or(false);
+    let dav = DavClient::new(sync_get_config()?)?;
+    let data_dir = get_app_data_dir()?;
+
+    let base: Manifest = read_json(&base_path()?)?;
+    let local = scan_local()?;
+    let mut remote = dav.read_manifest().await?;
+    let mut result = SyncResult::default();
+
+    for (path, hash) in &local {
+        if remote.get(path) == Some(hash) {
+            continue;
+        }
+        if !force && is_conflict(base.get(path), Some(hash), remote.get(path)) {
+            result.conflicts.push(path.clone());
+            continue;
+        }
+        let content = std::fs::read(data_dir.join(path)).map_err(|e| {
+            MxuError::with_args(ErrorCode::FileReadFailed, [path.clone(), e.to_string()])
+        })?;
+        dav.ensure_parent_dirs(path).await?;
+        dav.put(path, content).await?;
+        remote.insert(path.clone(), hash.clone());
+        result.uploaded.push(path.clone());
+    }
+
+    let manifest = serde_json::to_vec_pretty(&remote).map_err(|e| e.to_string())?;
+    dav.mkcol("").await?;
+    dav.put(MANIFEST_FILE, manifest).await?;
+
+    // 更新基准：已同步（一致）的文件记为当前哈希
+    let mut new_base = base;
+    for (path, hash) in &local {
+        if remote.get(path) == Some(hash) {
+            new_base.insert(path.clone(), hash.clone());
+        }
+    }
+    save_json_config(&base_path()?, &new_base)?;
+
+    info!(
+        "[sync] Push finished: {} uploaded, {} conflict(s)",
+        result.uploaded.len(),
+        result.conflicts.len()
+    );
+    Ok(result)
+}
+
+/// 远端清单中的路径是否可以写入本地：须位于同步目录内、不含 ..、反斜杠、盘符或根路径，且不是本机专属文件
+fn is_allowed_remote_path(path: &str) -> bool {
+    SYNC_DIRS
+        .iter()
+        .any(|d| path.starts_with(&format!("{}/", d)))
+        && !path.contains(['\\', ':'])
+        && !path.split('/').any(|c| c == ".." || c.is_empty())
+        && is_safe_relative_path(path)
+        && !path
+            .rsplit('/')
+            .next()
+            .is_some_and(|name| SYNC_EXCLUDED_FILES.contains(&name))
+}
+
+/// 从 WebDAV 拉取远程修改
+#[tauri::command]
+pub async fn sync_pull(force: Option<bool>) -> Result<SyncResult, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    let force = force.unwrap_or(false);
+    let dav = DavClient::new(sync_get_config()?)?;
+    let data_dir = get_app_data_dir()?;
+
+    let mut base: Manifest = read_json(&base_path()?)?;
+    let local = scan_local()?;
+    let remote = dav.read_manifest().await?;
+    let mut result = SyncResult::default();
+
+    for (path, hash) in &remote {
+        if !is_allowed_remote_path(path) {
+            warn!("[sync] Skipping unexpected remote path: {}", path);
+            continue;
+        }
+        if local.get(path) == Some(hash) {
+            base.insert(path.clone(), hash.clone());
+            continue;
+        }
+        if !force && is_conflict(base.get(path), local.get(path), Some(hash)) {
+            result.conflicts.push(path.clone());
+            continue;
+        }
+        let Some(content) = dav.get(path).await? else {
+            warn!(
+                "[sync] Remote file listed in manifest but missing: {}",
+                path
+            );
+            continue;
+        };
+        // 清单与文件不一致（上传中断或被其他客户端改写）时不写入，等待下次同步
+        let actual = to_hex(&Sha256::digest(&content));
+        if !actual.eq_ignore_ascii_case(hash) {
+            warn!(
+                "[sync] Hash mismatch for {} (manifest {}, actual {}), skipped",
+                path, hash, actual
+            );
+            continue;
+        }
+        let dest = data_dir.join(path);
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| {
+                MxuError::with_args(
+                    ErrorCode::DirectoryCreateFailed,
+                    [parent.display().to_string(), e.to_string()],
+                )
+            })?;
+        }
+        std::fs::write(&dest, content).map_err(|e| {
+            MxuError::with_args(
+                ErrorCode::FileWriteFailed,
+                [dest.display().to_string(), e.to_string()],
+            )
+        })?;
+        base.insert(path.clone(), hash.clone());
+        result.downloaded.push(path.clone());
+    }
+
+    save_json_config(&base_path()?, &base)?;
+
+    info!(
+        "[sync] Pull finished: {} downloaded, {} conflict(s)",
+        result.downloaded.len(),
+        result.conflicts.len()
+    );
+    Ok(result)
+}
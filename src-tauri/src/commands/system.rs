@@ -3,9 +3,15 @@
 //! 提供权限检查、系统信息查询、全局选项设置等功能
 
 use log::info;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 
-use super::types::SystemInfo;
+use tauri::{AppHandle, Emitter, State};
+
+use super::error::MxuError;
+use super::i18n::ErrorCode;
+use super::types::{GlobalOptionInfo, GlobalOptionKind, MaaReloadResult, MaaState, SystemInfo};
 use super::utils::get_maafw_dir;
 
 /// 标记是否检测到可能缺少 VC++ 运行库
@@ -16,6 +22,34 @@ pub fn set_vcredist_missing(missing: bool) {
     VCREDIST_MISSING.store(missing, Ordering::SeqCst);
 }
 
+/// MaaFramework 加载失败时的错误：Windows 上 DLL 存在却无法加载，多半是缺少 VC++ 运行库
+pub fn library_load_error(dll_path: &Path, error: String) -> MxuError {
+    if cfg!(windows) && dll_path.exists() {
+        MxuError::with_args(ErrorCode::VcredistMissing, [error])
+    } else {
+        MxuError::internal(error)
+    }
+}
+
+/// 本进程已加载的 MaaFramework 库路径（库加载后无法卸载，进程内只会加载一次）
+static LOADED_LIBRARY: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// 加载 MaaFramework 库并记录路径；已加载时不再重复加载，返回已加载的库路径
+pub fn load_maa_library(dll_path: &Path) -> Result<PathBuf, MxuError> {
+    let mut loaded = LOADED_LIBRARY.lock().map_err(|e| e.to_string())?;
+    if let Some(path) = loaded.as_ref() {
+        return Ok(path.clone());
+    }
+    maa_framework::load_library(dll_path).map_err(|e| library_load_error(dll_path, e))?;
+    *loaded = Some(dll_path.to_path_buf());
+    Ok(dll_path.to_path_buf())
+}
+
+/// 本进程已加载的 MaaFramework 库路径，尚未加载时返回 None
+pub fn loaded_library_path() -> Option<PathBuf> {
+    LOADED_LIBRARY.lock().ok()?.clone()
+}
+
 /// 检查当前进程是否以管理员权限运行
 #[tauri::command]
 pub fn is_elevated() -> bool {
@@ -65,7 +99,7 @@ pub fn is_elevated() -> bool {
 
 /// 以管理员权限重启应用
 #[tauri::command]
-pub fn restart_as_admin(app_handle: tauri::AppHandle) -> Result<(), String> {
+pub fn restart_as_admin(app_handle: tauri::AppHandle) -> Result<(), MxuError> {
     #[cfg(windows)]
     {
         use std::ffi::OsStr;
@@ -106,9 +140,9 @@ pub fn restart_as_admin(app_handle: tauri::AppHandle) -> Result<(), String> {
                 app_handle.exit(0);
                 Ok(())
             } else {
-                Err(format!(
-                    "以管理员身份启动失败: 错误码 {}",
-                    result.0 as usize
+                Err(MxuError::with_args(
+                    ErrorCode::ElevationFailed,
+                    [result.0 as usize],
                 ))
             }
         }
@@ -117,24 +151,167 @@ pub fn restart_as_admin(app_handle: tauri::AppHandle) -> Result<(), String> {
     #[cfg(not(windows))]
     {
         let _ = app_handle;
-        Err("此功能仅在 Windows 上可用".to_string())
+        Err(MxuError::new(ErrorCode::WindowsOnly))
     }
 }
 
 /// 设置全局选项 - 保存调试图像
 #[tauri::command]
-pub fn maa_set_save_draw(enabled: bool) -> Result<bool, String> {
-    maa_framework::set_save_draw(enabled)
+pub fn maa_set_save_draw(enabled: bool) -> Result<bool, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    Ok(maa_framework::set_save_draw(enabled)
         .map(|_| {
             info!("保存调试图像: {}", if enabled { "启用" } else { "禁用" });
             true
         })
-        .map_err(|e| format!("设置保存调试图像失败: {}", e))
+        .map_err(|e| format!("设置保存调试图像失败: {}", e))?)
+}
+
+/// 已知的全局选项：(键, 取值类型, 说明, 是否已废弃)
+const GLOBAL_OPTIONS: &[(&str, GlobalOptionKind, &str, bool)] = &[
+    ("log_dir", GlobalOptionKind::Path, "日志目录", false),
+    (
+        "save_draw",
+        GlobalOptionKind::Bool,
+        "保存识别调试图像",
+        false,
+    ),
+    (
+        "recording",
+        GlobalOptionKind::Bool,
+        "录制操作（已移除）",
+        true,
+    ),
+    (
+        "stdout_level",
+        GlobalOptionKind::LogLevel,
+        "控制台日志级别",
+        false,
+    ),
+    (
+        "debug_mode",
+        GlobalOptionKind::Bool,
+        "调试模式（收集全部识别详情）",
+        false,
+    ),
+    (
+        "save_on_error",
+        GlobalOptionKind::Bool,
+        "任务出错时保存截图",
+        false,
+    ),
+    (
+        "draw_quality",
+        GlobalOptionKind::Int,
+        "调试图像 JPEG 质量（0-100）",
+        false,
+    ),
+    (
+        "reco_image_cache_limit",
+        GlobalOptionKind::Int,
+        "识别图像缓存数量上限",
+        false,
+    ),
+];
+
+/// 解析日志级别（名称或数值）
+fn parse_log_level(value: &serde_json::Value) -> Option<i32> {
+    if let Some(n) = value.as_i64() {
+        return (0..=7).contains(&n).then_some(n as i32);
+    }
+    let level = match value.as_str()?.to_ascii_lowercase().as_str() {
+        "off" => 0,
+        "fatal" => 1,
+        "error" => 2,
+        "warn" | "warning" => 3,
+        "info" => 4,
+        "debug" => 5,
+        "trace" => 6,
+        "all" => 7,
+        _ => return None,
+    };
+    Some(level)
+}
+
+/// 列出可通过 maa_set_global_option 设置的全局选项
+#[tauri::command]
+pub fn maa_list_global_options() -> Vec<GlobalOptionInfo> {
+    GLOBAL_OPTIONS
+        .iter()
+        .map(|(key, kind, description, deprecated)| GlobalOptionInfo {
+            key: key.to_string(),
+            kind: *kind,
+            description: description.to_string(),
+            deprecated: *deprecated,
+        })
+        .collect()
+}
+
+/// 设置全局选项 - 通用透传，用于尚无专门命令的选项
+///
+/// key 须为已知选项（见 maa_list_global_options），value 按选项类型校验
+#[tauri::command]
+pub fn maa_set_global_option(key: String, value: serde_json::Value) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    let Some((_, kind, _, deprecated)) = GLOBAL_OPTIONS.iter().find(|(k, ..)| *k == key) else {
+        return Err(MxuError::with_args(ErrorCode::UnknownGlobalOption, [&key]));
+    };
+    if *deprecated {
+        return Err(MxuError::with_args(
+            ErrorCode::GlobalOptionDeprecated,
+            [&key],
+        ));
+    }
+
+    let invalid = || {
+        MxuError::with_args(
+            ErrorCode::InvalidGlobalOptionValue,
+            [key.clone(), value.to_string()],
+        )
+    };
+    let result = match kind {
+        GlobalOptionKind::Bool => {
+            let enabled = value.as_bool().ok_or_else(invalid)?;
+            match key.as_str() {
+                "save_draw" => maa_framework::set_save_draw(enabled),
+                "debug_mode" => maa_framework::set_debug_mode(enabled),
+                _ => maa_framework::set_save_on_error(enabled),
+            }
+        }
+        GlobalOptionKind::Int => {
+            let n = value.as_i64().filter(|n| *n >= 0).ok_or_else(invalid)?;
+            match key.as_str() {
+                "draw_quality" if n > 100 => return Err(invalid()),
+                "draw_quality" => maa_framework::set_draw_quality(n as i32),
+                _ => maa_framework::set_reco_image_cache_limit(n as u64),
+            }
+        }
+        GlobalOptionKind::LogLevel => {
+            maa_framework::set_stdout_level(parse_log_level(&value).ok_or_else(invalid)?)
+        }
+        GlobalOptionKind::Path => {
+            let dir = value
+                .as_str()
+                .filter(|s| !s.trim().is_empty())
+                .ok_or_else(invalid)?;
+            std::fs::create_dir_all(dir).map_err(|e| {
+                MxuError::with_args(
+                    ErrorCode::DirectoryCreateFailed,
+                    [dir.to_string(), e.to_string()],
+                )
+            })?;
+            maa_framework::set_log_dir(dir)
+        }
+    };
+
+    result.map_err(|e| format!("设置全局选项 {} 失败: {}", key, e))?;
+    info!("全局选项 {} = {}", key, value);
+    Ok(())
 }
 
 /// 打开文件（使用系统默认程序）
 #[tauri::command]
-pub async fn open_file(file_path: String) -> Result<(), String> {
+pub async fn open_file(file_path: String) -> Result<(), MxuError> {
     info!("open_file: {}", file_path);
 
     #[cfg(windows)]
@@ -170,7 +347,8 @@ pub async fn open_file(file_path: String) -> Result<(), String> {
 
 /// 运行程序并等待其退出
 #[tauri::command]
-pub async fn run_and_wait(file_path: String) -> Result<i32, String> {
+pub async fn run_and_wait(file_path: String) -> Result<i32, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
     info!("run_and_wait: {}", file_path);
 
     #[cfg(windows)]
@@ -188,7 +366,7 @@ pub async fn run_and_wait(file_path: String) -> Result<i32, String> {
     #[cfg(not(windows))]
     {
         let _ = file_path;
-        Err("run_and_wait is only supported on Windows".to_string())
+        Err(MxuError::new(ErrorCode::WindowsOnly))
     }
 }
 
@@ -439,7 +617,8 @@ pub async fn run_action(
     args: String,
     cwd: Option<String>,
     wait_for_exit: bool,
-) -> Result<i32, String> {
+) -> Result<i32, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
     use std::process::Command;
 
     info!(
@@ -494,12 +673,15 @@ pub async fn run_action(
 
 /// 重新尝试加载 MaaFramework 库
 #[tauri::command]
-pub async fn retry_load_maa_library() -> Result<String, String> {
+pub async fn retry_load_maa_library() -> Result<String, MxuError> {
     info!("retry_load_maa_library");
 
     let maafw_dir = get_maafw_dir()?;
     if !maafw_dir.exists() {
-        return Err("MaaFramework directory not found".to_string());
+        return Err(MxuError::with_args(
+            ErrorCode::DirectoryNotFound,
+            [maafw_dir.display()],
+        ));
     }
 
     // Load library
@@ -510,7 +692,7 @@ pub async fn retry_load_maa_library() -> Result<String, String> {
     #[cfg(target_os = "linux")]
     let dll_path = maafw_dir.join("libMaaFramework.so");
 
-    maa_framework::load_library(&dll_path).map_err(|e| e.to_string())?;
+    load_maa_library(&dll_path)?;
 
     let version = maa_framework::maa_version().to_string();
     info!("MaaFramework loaded successfully, version: {}", version);
@@ -518,6 +700,58 @@ pub async fn retry_load_maa_library() -> Result<String, String> {
     Ok(version)
 }
 
+/// 销毁所有实例后加载 MaaFramework 库（用于更新 MaaFramework 文件后）
+/// 动态库加载后无法在进程内卸载：尚未加载时直接加载，已加载时重启 MXU 以使用新文件
+#[tauri::command]
+pub async fn reload_maa_library(
+    app: AppHandle,
+    state: State<'_, Arc<MaaState>>,
+) -> Result<MaaReloadResult, MxuError> {
+    info!("reload_maa_library");
+    super::update::ensure_idle(&state)?;
+
+    let destroyed_instances = state.teardown_all();
+    info!(
+        "Destroyed {} instance(s) before reload",
+        destroyed_instances
+    );
+    let _ = app.emit("maa-library-reloading", destroyed_instances);
+
+    if let Some(loaded) = loaded_library_path() {
+        info!(
+            "MaaFramework already loaded from {}, restarting to use new files",
+            loaded.display()
+        );
+        // 等命令返回后再重启，前端可先收到结果并提示
+        std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(500));
+            app.request_restart();
+        });
+        return Ok(MaaReloadResult {
+            version: None,
+            destroyed_instances,
+            restarting: true,
+        });
+    }
+
+    let maafw_dir = get_maafw_dir()?;
+    #[cfg(windows)]
+    let dll_path = maafw_dir.join("MaaFramework.dll");
+    #[cfg(target_os = "macos")]
+    let dll_path = maafw_dir.join("libMaaFramework.dylib");
+    #[cfg(target_os = "linux")]
+    let dll_path = maafw_dir.join("libMaaFramework.so");
+
+    load_maa_library(&dll_path)?;
+    let version = maa_framework::maa_version().to_string();
+    info!("MaaFramework loaded, version: {}", version);
+    Ok(MaaReloadResult {
+        version: Some(version),
+        destroyed_instances,
+        restarting: false,
+    })
+}
+
 /// 检查是否检测到 VC++ 运行库缺失（检查后自动清除标记）
 #[tauri::command]
 pub fn check_vcredist_missing() -> bool {
@@ -552,7 +786,7 @@ fn to_wide(s: &str) -> Vec<u16> {
 }
 
 #[cfg(windows)]
-fn create_schtask_autostart() -> Result<(), String> {
+pub(crate) fn create_schtask_autostart() -> Result<(), String> {
     let exe_path = std::env::current_exe().map_err(|e| format!("获取程序路径失败: {}", e))?;
     let exe = exe_path.to_string_lossy();
     let output = std::process::Command::new("schtasks")
@@ -581,7 +815,7 @@ fn create_schtask_autostart() -> Result<(), String> {
 
 /// 清理旧版注册表自启动条目（tauri-plugin-autostart 遗留）
 #[cfg(windows)]
-fn remove_legacy_registry_autostart() {
+pub(crate) fn remove_legacy_registry_autostart() {
     use windows::core::PCWSTR;
     use windows::Win32::System::Registry::*;
 
@@ -608,7 +842,7 @@ fn remove_legacy_registry_autostart() {
 
 /// 检查旧版注册表中是否存在自启动条目
 #[cfg(windows)]
-fn has_legacy_registry_autostart() -> bool {
+pub(crate) fn has_legacy_registry_autostart() -> bool {
     use windows::core::PCWSTR;
     use windows::Win32::System::Registry::*;
 
@@ -637,7 +871,8 @@ fn has_legacy_registry_autostart() -> bool {
 
 /// 通过 Windows 任务计划程序启用开机自启动（以最高权限运行，避免 UAC 弹窗）
 #[tauri::command]
-pub fn autostart_enable() -> Result<(), String> {
+pub fn autostart_enable() -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
     #[cfg(windows)]
     {
         create_schtask_autostart()?;
@@ -646,13 +881,14 @@ pub fn autostart_enable() -> Result<(), String> {
     }
     #[cfg(not(windows))]
     {
-        Err("此功能仅在 Windows 上可用".to_string())
+        Err(MxuError::new(ErrorCode::WindowsOnly))
     }
 }
 
 /// 通过 Windows 任务计划程序禁用开机自启动
 #[tauri::command]
-pub fn autostart_disable() -> Result<(), String> {
+pub fn autostart_disable() -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
     #[cfg(windows)]
     {
         // 删除计划任务（不存在时忽略错误）
@@ -665,7 +901,7 @@ pub fn autostart_disable() -> Result<(), String> {
     }
     #[cfg(not(windows))]
     {
-        Err("此功能仅在 Windows 上可用".to_string())
+        Err(MxuError::new(ErrorCode::WindowsOnly))
     }
 }
 
@@ -0,0 +1,902 @@
+//! 任务队列引擎
+//!
+//! 队列模式下逐个提交任务并等待完成，支持优先级排序、失败重试/跳过/中止策略、
+//! 任务间延迟、整个队列的暂停/恢复、在当前任务结束后停止、
+//! 按空闲策略在用户使用电脑时推迟下一个任务，
+//! 单个任务超时后中止、执行恢复任务并继续队列，
+//! 以及整个运行达到时长上限后在下一个节点边界停止
+
+use log::{debug, info, warn};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter, State};
+
+use maa_framework::tasker::Tasker;
+use maa_framework::MaaStatus;
+
+use super::error::MxuError;
+use super::hooks::HookContext;
+use super::i18n::ErrorCode;
+use super::types::{
+    FailurePolicy, HookEvent, MaaState, QueueItemState, QueueItemStatus, QueueOptions,
+    QueueSnapshot, QueueStatus, TaskCondition, TaskConfig, TaskHook,
+};
+use super::utils::append_override;
+
+/// 任务状态轮询间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+/// 超时中止任务后等待 Tasker 停止的时间
+const ABORT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 单个实例的任务队列
+pub struct TaskQueue {
+    instance_id: String,
+    snapshot: Mutex<QueueSnapshot>,
+    paused: AtomicBool,
+    cancelled: AtomicBool,
+    stop_after_current: AtomicBool,
+    /// 是否因达到运行时长上限而停止
+    time_boxed: AtomicBool,
+    /// 配置档案的任务钩子
+    hooks: Vec<TaskHook>,
+}
+
+impl TaskQueue {
+    fn new(
+        instance_id: &str,
+        run_id: Option<String>,
+        tasks: &[TaskConfig],
+        hooks: Vec<TaskHook>,
+    ) -> Self {
+        let items = tasks
+            .iter()
+            .enumerate()
+            .map(|(idx, task)| QueueItemState {
+                id: task.id.clone().unwrap_or_else(|| idx.to_string()),
+                entry: task.entry.clone(),
+                status: QueueItemStatus::Pending,
+                attempts: 0,
+                task_id: None,
+            })
+            .collect();
+
+        Self {
+            instance_id: instance_id.to_string(),
+            snapshot: Mutex::new(QueueSnapshot {
+                instance_id: instance_id.to_string(),
+                run_id,
+                status: QueueStatus::Running,
+                current_index: None,
+                stop_after_current: false,
+                items,
+            }),
+            paused: AtomicBool::new(false),
+            cancelled: AtomicBool::new(false),
+            stop_after_current: AtomicBool::new(false),
+            time_boxed: AtomicBool::new(false),
+            hooks,
+        }
+    }
+
+    /// 触发钩子
+    fn fire_hook(&self, event: HookEvent, task: Option<&str>, status: Option<&str>) {
+        if self.hooks.is_empty() {
+            return;
+        }
+        let Some(snapshot) = self.snapshot() else {
+            return;
+        };
+        let ctx = HookContext {
+            instance_id: snapshot.instance_id,
+            run_id: snapshot.run_id,
+            task: task.map(str::to_string),
+            status: status.map(str::to_string),
+        };
+        super::hooks::fire(&self.hooks, event, &ctx);
+    }
+
+    /// 获取当前状态快照
+    pub fn snapshot(&self) -> Option<QueueSnapshot> {
+        self.snapshot.lock().ok().map(|s| s.clone())
+    }
+
+    /// 队列是否仍在执行（未结束）
+    pub fn is_active(&self) -> bool {
+        self.snapshot.lock().is_ok_and(|s| {
+            matches!(
+                s.status,
+                QueueStatus::Running
+                    | QueueStatus::Paused
+                    | QueueStatus::WaitingForIdle
+                    | QueueStatus::Stopping
+            )
+        })
+    }
+
+    /// 请求停止队列：不再提交新任务，当前任务由 post_stop 结束
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+        if let Ok(mut s) = self.snapshot.lock() {
+            if matches!(
+                s.status,
+                QueueStatus::Running | QueueStatus::Paused | QueueStatus::WaitingForIdle
+            ) {
+                s.status = QueueStatus::Stopping;
+            }
+        }
+    }
+
+    /// 设置是否在当前任务结束后停止（不中断正在执行的任务）
+    fn set_stop_after_current(&self, app: &AppHandle, enabled: bool) {
+        self.stop_after_current.store(enabled, Ordering::SeqCst);
+        self.update(app, |s| s.stop_after_current = enabled);
+    }
+
+    /// 本次运行 ID
+    fn run_id(&self) -> Option<String> {
+        self.snapshot.lock().ok().and_then(|s| s.run_id.clone())
+    }
+
+    /// 写入本次运行的独立日志
+    fn run_log(&self, message: &str) {
+        if let Some(run_id) = self.run_id() {
+            super::runs::run_log(&run_id, message);
+        }
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// 修改状态并发送 task-queue-update 事件
+    fn update(&self, app: &AppHandle, f: impl FnOnce(&mut QueueSnapshot)) {
+        let snapshot = match self.snapshot.lock() {
+            Ok(mut s) => {
+                f(&mut s);
+                s.clone()
+            }
+            Err(e) => {
+                warn!("[task_queue] Failed to lock snapshot: {}", e);
+                return;
+            }
+        };
+        if let Err(e) = app.emit("task-queue-update", snapshot) {
+            log::error!("[task_queue] Failed to emit task-queue-update: {}", e);
+        }
+        update_taskbar(&snapshot);
+        crate::tray::request_menu_refresh();
+    }
+
+    /// 可中断等待，返回 false 表示等待期间队列被停止
+    fn sleep_interruptible(&self, duration: Duration) -> bool {
+        let start = std::time::Instant::now();
+        while start.elapsed() < duration {
+            if self.is_cancelled() {
+                return false;
+            }
+            thread::sleep(duration.saturating_sub(start.elapsed()).min(POLL_INTERVAL));
+        }
+        !self.is_cancelled()
+    }
+
+    /// 队列暂停时阻塞，返回 false 表示等待期间队列被停止
+    fn wait_if_paused(&self, app: &AppHandle) -> bool {
+        if !self.paused.load(Ordering::SeqCst) {
+            return !self.is_cancelled();
+        }
+
+        self.update(app, |s| s.status = QueueStatus::Paused);
+        while self.paused.load(Ordering::SeqCst) && !self.is_cancelled() {
+            thread::sleep(POLL_INTERVAL);
+        }
+        if self.is_cancelled() {
+            return false;
+        }
+        self.update(app, |s| s.status = QueueStatus::Running);
+        true
+    }
+
+    /// 按空闲策略等待用户停止操作电脑，返回 false 表示等待期间队列被停止
+    fn wait_for_user_idle(&self, app: &AppHandle, instance_id: &str) -> bool {
+        let Some(policy) = super::idle_policy::policy_for(instance_id) else {
+            return !self.is_cancelled();
+        };
+
+        let start = std::time::Instant::now();
+        let mut waiting = false;
+        loop {
+            if self.is_cancelled() {
+                return false;
+            }
+            // 无法获取空闲时间时不阻塞
+            match super::idle_policy::user_idle_secs() {
+                Some(idle) if idle < policy.idle_secs => {}
+                _ => break,
+            }
+            if policy.max_wait_secs > 0 && start.elapsed().as_secs() >= policy.max_wait_secs {
+                info!("[task_queue] Idle wait timed out, starting anyway");
+                self.run_log("Idle wait timed out, starting anyway");
+                break;
+            }
+            if !waiting {
+                waiting = true;
+                info!("[task_queue] User is active, waiting for idle");
+                self.run_log("User is active, waiting for idle");
+                self.update(app, |s| s.status = QueueStatus::WaitingForIdle);
+            }
+            if !self.sleep_interruptible(Duration::from_secs(1)) {
+                return false;
+            }
+        }
+
+        if waiting {
+            self.update(app, |s| s.status = QueueStatus::Running);
+        }
+        true
+    }
+
+    /// 查询指定队列项的状态
+    fn item_status(&self, id: &str) -> Option<QueueItemStatus> {
+        let s = self.snapshot.lock().ok()?;
+        s.items.iter().find(|i| i.id == id).map(|i| i.status)
+    }
+
+    /// 评估执行条件
+    fn evaluate(&self, condition: &TaskCondition) -> bool {
+        match condition {
+            TaskCondition::Succeeded { task } => {
+                self.item_status(task) == Some(QueueItemStatus::Succeeded)
+            }
+            TaskCondition::Failed { task } => matches!(
+                self.item_status(task),
+                Some(QueueItemStatus::Failed | QueueItemStatus::TimedOut)
+            ),
+            TaskCondition::Variable { name, equals } => {
+                match (
+                    super::variables::get_variable(&self.instance_id, name),
+                    equals,
+                ) {
+                    (Some(value), Some(expected)) => &value == expected,
+                    (Some(_), None) => true,
+                    (None, _) => false,
+                }
+            }
+            TaskCondition::All { conditions } => conditions.iter().all(|c| self.evaluate(c)),
+            TaskCondition::Any { conditions } => conditions.iter().any(|c| self.evaluate(c)),
+            TaskCondition::Not { condition } => !self.evaluate(condition),
+        }
+    }
+
+    /// 等待任务结束，返回最终状态；超过 timeout 时返回 None
+    fn wait_task(
+        &self,
+        tasker: &Tasker,
+        task_id: i64,
+        timeout: Option<Duration>,
+    ) -> Option<MaaStatus> {
+        let start = std::time::Instant::now();
+        loop {
+            let status = tasker
+                .get_task_detail(task_id)
+                .ok()
+                .flatten()
+                .map(|d| d.status)
+                .unwrap_or(MaaStatus::INVALID);
+
+            match status {
+                MaaStatus::PENDING | MaaStatus::RUNNING => {
+                    // 已停止且 tasker 不再运行，说明任务已被丢弃
+                    if self.is_cancelled() && !tasker.running() {
+                        return Some(MaaStatus::FAILED);
+                    }
+                    if timeout.is_some_and(|t| start.elapsed() >= t) {
+                        return None;
+                    }
+                    thread::sleep(POLL_INTERVAL);
+                }
+                other => return Some(other),
+            }
+        }
+    }
+}
+
+/// 中止超时的任务，并执行其恢复任务（如有），返回 false 表示期间队列被停止
+fn recover_from_timeout(
+    queue: &TaskQueue,
+    instance_id: &str,
+    tasker: &Tasker,
+    task: &TaskConfig,
+) -> bool {
+    if let Err(e) = super::stop_control::post_stop(instance_id, tasker.clone()) {
+        warn!(
+            "[task_queue] Failed to stop timed out task {}: {}",
+            task.entry, e
+        );
+    }
+    let start = std::time::Instant::now();
+    while tasker.running() {
+        if start.elapsed() >= ABORT_TIMEOUT {
+            warn!(
+                "[task_queue] Tasker still running {:?} after aborting {}",
+                ABORT_TIMEOUT, task.entry
+            );
+            queue.run_log(&format!(
+                "Tasker did not stop after aborting {}",
+                task.entry
+            ));
+            break;
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+    if queue.is_cancelled() {
+        return false;
+    }
+
+    let Some(recovery) = &task.on_timeout_task else {
+        return true;
+    };
+    info!(
+        "[task_queue] Running recovery task {} for {}",
+        recovery.entry, task.entry
+    );
+    queue.run_log(&format!(
+        "Running recovery task {} for {}",
+        recovery.entry, task.entry
+    ));
+    let task_id = match tasker.post_task(&recovery.entry, &recovery.pipeline_override) {
+        Ok(job) => job.id,
+        Err(e) => {
+            warn!(
+                "[task_queue] Failed to post recovery task {}: {}",
+                recovery.entry, e
+            );
+            queue.run_log(&format!(
+                "Failed to post recovery task {}: {}",
+                recovery.entry, e
+            ));
+            return true;
+        }
+    };
+    if let Some(run_id) = queue.run_id() {
+        super::runs::register_task(&run_id, task_id);
+    }
+    let timeout = recovery.max_duration_secs.map(Duration::from_secs);
+    match queue.wait_task(tasker, task_id, timeout) {
+        Some(status) => queue.run_log(&format!(
+            "Recovery task {} (task_id: {}) finished: {:?}",
+            recovery.entry, task_id, status
+        )),
+        None => {
+            warn!(
+                "[task_queue] Recovery task {} timed out, stopping it",
+                recovery.entry
+            );
+            queue.run_log(&format!("Recovery task {} timed out", recovery.entry));
+            let _ = super::stop_control::post_stop(instance_id, tasker.clone());
+        }
+    }
+    !queue.is_cancelled()
+}
+
+/// 运行时长上限：到达后停止队列，当前任务在下一个节点边界结束
+fn spawn_time_box(queue: Arc<TaskQueue>, instance_id: String, tasker: Tasker, limit: Duration) {
+    thread::spawn(move || {
+        let start = std::time::Instant::now();
+        while start.elapsed() < limit {
+            if !queue.is_active() || queue.is_cancelled() {
+                return;
+            }
+            thread::sleep(Duration::from_secs(1));
+        }
+        if !queue.is_active() || queue.is_cancelled() {
+            return;
+        }
+        info!(
+            "[task_queue] Queue for instance {} reached its {:?} limit, stopping",
+            instance_id, limit
+        );
+        queue.run_log(&format!("Run reached its {:?} limit, stopping", limit));
+        queue.time_boxed.store(true, Ordering::SeqCst);
+        queue.cancel();
+        super::pause_gate::release(&instance_id);
+        if let Err(e) = super::stop_control::post_stop(&instance_id, tasker) {
+            warn!("[task_queue] Failed to stop time-boxed queue: {}", e);
+        }
+    });
+}
+
+/// 收集条件中引用的队列项 ID（succeeded / failed）
+fn condition_refs<'a>(condition: &'a TaskCondition, refs: &mut Vec<&'a str>) {
+    match condition {
+        TaskCondition::Succeeded { task } | TaskCondition::Failed { task } => refs.push(task),
+        TaskCondition::Variable { .. } => {}
+        TaskCondition::All { conditions } | TaskCondition::Any { conditions } => {
+            for c in conditions {
+                condition_refs(c, refs);
+            }
+        }
+        TaskCondition::Not { condition } => condition_refs(condition, refs),
+    }
+}
+
+/// 按优先级排序（数值大的在前，相同优先级保持原顺序），
+/// 但 run_if 引用了其他队列项的任务始终排在被引用的任务之后
+fn sort_by_priority(tasks: Vec<TaskConfig>) -> Vec<TaskConfig> {
+    // 未指定 ID 的任务使用排序前的下标，保证条件中按下标的引用不受排序影响
+    let mut remaining: Vec<TaskConfig> = tasks
+        .into_iter()
+        .enumerate()
+        .map(|(idx, mut task)| {
+            task.id.get_or_insert_with(|| idx.to_string());
+            task
+        })
+        .collect();
+    remaining.sort_by_key(|t| std::cmp::Reverse(t.priority));
+
+    let mut sorted: Vec<TaskConfig> = Vec::with_capacity(remaining.len());
+    while !remaining.is_empty() {
+        // 取第一个所引用的任务都已排入（或不在队列中）的任务；存在循环引用时按优先级取第一个
+        let ready = remaining
+            .iter()
+            .position(|task| {
+                let mut refs = Vec::new();
+                if let Some(condition) = &task.run_if {
+                    condition_refs(condition, &mut refs);
+                }
+                refs.iter().all(|id| {
+                    remaining
+                        .iter()
+                        .all(|t| t.id.as_deref() != Some(*id) || std::ptr::eq(t, task))
+                })
+            })
+            .unwrap_or(0);
+        sorted.push(remaining.remove(ready));
+    }
+    sorted
+}
+
+/// 创建任务队列并在后台线程中执行
+pub fn start_queue(
+    app: AppHandle,
+    state: Arc<MaaState>,
+    instance_id: String,
+    tasker: Tasker,
+    tasks: Vec<TaskConfig>,
+    options: QueueOptions,
+    run_id: Option<String>,
+    hooks: Vec<TaskHook>,
+) -> Result<(), String> {
+    let tasks = sort_by_priority(tasks);
+    let queue = Arc::new(TaskQueue::new(&instance_id, run_id, &tasks, hooks));
+
+    {
+        let mut queues = state.task_queues.lock().map_err(|e| e.to_string())?;
+        if queues.get(&instance_id).is_some_and(|q| q.is_active()) {
+            return Err("Task queue is already running".to_string());
+        }
+        queues.insert(instance_id.clone(), queue.clone());
+    }
+
+    super::variables::clear_variables(&instance_id);
+    // 新队列开始时取消尚未执行的完成后操作
+    super::post_actions::cancel_countdown(&app);
+    super::wake_timer::on_queue_started();
+    crate::tray::on_queue_started();
+
+    info!(
+        "[task_queue] Starting queue for instance {} with {} task(s), delay: {}ms",
+        instance_id,
+        tasks.len(),
+        options.delay_ms
+    );
+    queue.update(&app, |_| {});
+
+    if let Some(secs) = options.max_duration_secs.filter(|&secs| secs > 0) {
+        spawn_time_box(
+            queue.clone(),
+            instance_id.clone(),
+            tasker.clone(),
+            Duration::from_secs(secs),
+        );
+    }
+
+    thread::spawn(move || {
+        queue.fire_hook(HookEvent::BeforeQueue, None, None);
+        let mut final_status = run_queue(
+            &app,
+            &state,
+            &instance_id,
+            &tasker,
+            &tasks,
+            &options,
+            &queue,
+        );
+        if final_status == QueueStatus::Stopped && queue.time_boxed.load(Ordering::SeqCst) {
+            final_status = QueueStatus::TimeBoxed;
+            queue.update(&app, |s| {
+                for item in s.items.iter_mut() {
+                    if item.status == QueueItemStatus::Pending {
+                        item.status = QueueItemStatus::Skipped;
+                    }
+                }
+            });
+        }
+
+        // 清空缓存的 task_ids
+        if let Ok(mut instances) = state.instances.lock() {
+            if let Some(instance) = instances.get_mut(&instance_id) {
+                instance.task_ids.clear();
+            }
+        }
+
+        info!(
+            "[task_queue] Queue for instance {} finished: {:?}",
+            instance_id, final_status
+        );
+        queue.update(&app, |s| {
+            s.status = final_status;
+            s.current_index = None;
+            s.stop_after_current = false;
+        });
+        crate::tray::set_stop_after_current_label(false);
+        let any_item_failed = queue
+            .snapshot()
+            .is_some_and(|s| s.items.iter().any(|i| i.status == QueueItemStatus::Failed));
+        crate::tray::on_queue_finished(final_status, any_item_failed);
+        let status_name = serde_json::to_value(final_status)
+            .ok()
+            .and_then(|v| v.as_str().map(str::to_string));
+        if let Some(run_id) = queue.run_id() {
+            // 仅全部任务完成且没有失败时视为成功，队列状态作为补充说明
+            let succeeded = final_status == QueueStatus::Completed && !any_item_failed;
+            super::runs::finish_run(&app, &run_id, succeeded, status_name.as_deref());
+        }
+
+        queue.fire_hook(HookEvent::AfterQueue, None, status_name.as_deref());
+
+        super::post_actions::on_queue_finished(&app, &state, final_status);
+        super::wake_timer::on_queue_finished(&state);
+    });
+
+    Ok(())
+}
+
+/// 按队列状态更新任务栏进度：运行中显示进度，暂停为黄色，中止或有任务失败时为红色
+fn update_taskbar(snapshot: &QueueSnapshot) {
+    use crate::taskbar::{self, TaskbarState};
+
+    let source = format!("queue:{}", snapshot.instance_id);
+    let total = snapshot.items.len() as u64;
+    let done = snapshot
+        .items
+        .iter()
+        .filter(|i| {
+            !matches!(
+                i.status,
+                QueueItemStatus::Pending | QueueItemStatus::Running
+            )
+        })
+        .count() as u64;
+    let progress = (total > 0).then_some((done, total));
+    let any_failed = snapshot.items.iter().any(|i| {
+        matches!(
+            i.status,
+            QueueItemStatus::Failed | QueueItemStatus::TimedOut
+        )
+    });
+
+    match snapshot.status {
+        QueueStatus::Running | QueueStatus::Stopping => {
+            taskbar::set_progress(&source, done, total);
+        }
+        QueueStatus::Paused | QueueStatus::WaitingForIdle => {
+            taskbar::set(&source, TaskbarState::Paused, progress);
+        }
+        QueueStatus::Aborted => taskbar::set(&source, TaskbarState::Error, progress),
+        QueueStatus::Completed if any_failed => {
+            taskbar::set(&source, TaskbarState::Error, progress);
+        }
+        QueueStatus::Completed | QueueStatus::Stopped | QueueStatus::TimeBoxed => {
+            taskbar::clear(&source)
+        }
+    }
+}
+
+/// 执行队列，返回队列最终状态
+fn run_queue(
+    app: &AppHandle,
+    state: &Arc<MaaState>,
+    instance_id: &str,
+    tasker: &Tasker,
+    tasks: &[TaskConfig],
+    options: &QueueOptions,
+    queue: &TaskQueue,
+) -> QueueStatus {
+    for (idx, task) in tasks.iter().enumerate() {
+        if !queue.wait_if_paused(app) {
+            return QueueStatus::Stopped;
+        }
+        if !queue.wait_for_user_idle(app, instance_id) {
+            return QueueStatus::Stopped;
+        }
+        if queue.stop_after_current.load(Ordering::SeqCst) {
+            info!("[task_queue] Stopping after current task as requested");
+            queue.run_log("Stopping after current task as requested");
+            queue.update(app, |s| {
+                for item in s.items.iter_mut().skip(idx) {
+                    item.status = QueueItemStatus::Skipped;
+                }
+            });
+            return QueueStatus::Stopped;
+        }
+        if let Some(condition) = &task.run_if {
+            if !queue.evaluate(condition) {
+                info!(
+                    "[task_queue] Condition not met, skipping task {}: {:?}",
+                    task.entry, condition
+                );
+                queue.run_log(&format!("Condition not met, skipping task {}", task.entry));
+                queue.update(app, |s| s.items[idx].status = QueueItemStatus::Skipped);
+                continue;
+            }
+        }
+        if idx > 0
+            && options.delay_ms > 0
+            && !queue.sleep_interruptible(Duration::from_millis(options.delay_ms))
+        {
+            return QueueStatus::Stopped;
+        }
+
+        queue.fire_hook(HookEvent::BeforeTask, Some(&task.entry), None);
+
+        let mut max_attempts = match task.on_failure {
+            FailurePolicy::Retry => task.retry_count.max(1) + 1,
+            _ => 1,
+        };
+
+        let mut succeeded = false;
+        let mut timed_out = false;
+        // 自适应阈值放宽的覆盖（每个任务最多额外重试一次）
+        let mut relaxed: Option<serde_json::Value> = None;
+        let mut attempt = 0;
+        while attempt < max_attempts {
+            attempt += 1;
+            if queue.is_cancelled() {
+                return QueueStatus::Stopped;
+            }
+
+            let pipeline_override = match &relaxed {
+                Some(patch) => append_override(&task.pipeline_override, patch.clone()),
+                None => task.pipeline_override.clone(),
+            };
+            let attempt_started = chrono::Utc::now().timestamp_millis();
+            let task_id = match tasker.post_task(&task.entry, &pipeline_override) {
+                Ok(job) => job.id,
+                Err(e) => {
+                    warn!("[task_queue] Failed to post task {}: {}", task.entry, e);
+                    queue.run_log(&format!("Failed to post task {}: {}", task.entry, e));
+                    queue.update(app, |s| {
+                        s.items[idx].attempts = attempt;
+                        s.items[idx].status = QueueItemStatus::Failed;
+                    });
+                    continue;
+                }
+            };
+            debug!(
+                "[task_queue] Task {} posted (attempt {}/{}), task_id: {}",
+                task.entry, attempt, max_attempts, task_id
+            );
+
+            if let Some(run_id) = queue.run_id() {
+                super::runs::register_task(&run_id, task_id);
+            }
+
+            // 缓存当前 task_id，用于刷新后恢复状态
+            if let Ok(mut instances) = state.instances.lock() {
+                if let Some(instance) = instances.get_mut(instance_id) {
+                    instance.task_ids = vec![task_id];
+                }
+            }
+            queue.update(app, |s| {
+                s.current_index = Some(idx);
+                s.items[idx].status = QueueItemStatus::Running;
+                s.items[idx].attempts = attempt;
+                s.items[idx].task_id = Some(task_id);
+            });
+
+            let timeout = task.max_duration_secs.map(Duration::from_secs);
+            let Some(status) = queue.wait_task(tasker, task_id, timeout) else {
+                // 超时：中止任务、执行恢复任务后继续下一个任务，不再重试
+                warn!(
+                    "[task_queue] Task {} exceeded {}s, aborting",
+                    task.entry,
+                    task.max_duration_secs.unwrap_or_default()
+                );
+                queue.run_log(&format!(
+                    "Task {} (task_id: {}) exceeded {}s, aborting",
+                    task.entry,
+                    task_id,
+                    task.max_duration_secs.unwrap_or_default()
+                ));
+                queue.update(app, |s| s.items[idx].status = QueueItemStatus::TimedOut);
+                if !recover_from_timeout(queue, instance_id, tasker, task) {
+                    return QueueStatus::Stopped;
+                }
+                timed_out = true;
+                break;
+            };
+            succeeded = status == MaaStatus::SUCCEEDED;
+            queue.run_log(&format!(
+                "Task {} (task_id: {}, attempt {}/{}) finished: {:?}",
+                task.entry, task_id, attempt, max_attempts, status
+            ));
+            queue.update(app, |s| {
+                s.items[idx].status = if succeeded {
+                    QueueItemStatus::Succeeded
+                } else {
+                    QueueItemStatus::Failed
+                };
+            });
+
+            let plan =
+                super::adaptive_threshold::record_and_plan(app, tasker, attempt_started, succeeded);
+            if succeeded {
+                break;
+            }
+            if queue.is_cancelled() {
+                return QueueStatus::Stopped;
+            }
+            if relaxed.is_none() {
+                if let Some(patch) = plan {
+                    info!(
+                        "[task_queue] Task {} failed marginally, retrying with relaxed thresholds",
+                        task.entry
+                    );
+                    queue.run_log(&format!("Retrying with relaxed thresholds: {}", patch));
+                    relaxed = Some(patch);
+                    max_attempts += 1;
+                    continue;
+                }
+            }
+            if attempt < max_attempts {
+                info!(
+                    "[task_queue] Task {} failed, retrying ({}/{})",
+                    task.entry,
+                    attempt,
+                    max_attempts - 1
+                );
+            }
+        }
+
+        let status = if succeeded {
+            "succeeded"
+        } else if timed_out {
+            "timed_out"
+        } else {
+            "failed"
+        };
+        queue.fire_hook(HookEvent::AfterTask, Some(&task.entry), Some(status));
+        if !succeeded {
+            queue.fire_hook(HookEvent::OnFailure, Some(&task.entry), Some(status));
+        }
+
+        if !succeeded && !timed_out {
+            if task.on_failure == FailurePolicy::Abort {
+                warn!("[task_queue] Task {} failed, aborting queue", task.entry);
+                queue.update(app, |s| {
+                    for item in s.items.iter_mut().skip(idx + 1) {
+                        item.status = QueueItemStatus::Skipped;
+                    }
+                });
+                return QueueStatus::Aborted;
+            }
+            info!("[task_queue] Task {} failed, skipping", task.entry);
+        }
+    }
+
+    QueueStatus::Completed
+}
+
+/// 获取实例任务队列状态，未使用队列模式时返回 None
+#[tauri::command]
+pub fn queue_state(
+    state: State<Arc<MaaState>>,
+    instance_id: String,
+) -> Result<Option<QueueSnapshot>, MxuError> {
+    let queues = state.task_queues.lock().map_err(|e| e.to_string())?;
+    Ok(queues.get(&instance_id).and_then(|q| q.snapshot()))
+}
+
+/// 暂停任务队列（当前任务执行完后不再提交新任务）
+#[tauri::command]
+pub fn queue_pause(state: State<Arc<MaaState>>, instance_id: String) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    info!("queue_pause called, instance_id: {}", instance_id);
+    let queues = state.task_queues.lock().map_err(|e| e.to_string())?;
+    let queue = queues
+        .get(&instance_id)
+        .filter(|q| q.is_active())
+        .ok_or_else(|| MxuError::new(ErrorCode::TaskQueueNotRunning))?;
+    queue.paused.store(true, Ordering::SeqCst);
+    Ok(())
+}
+
+/// 恢复已暂停的任务队列
+#[tauri::command]
+pub fn queue_resume(state: State<Arc<MaaState>>, instance_id: String) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    info!("queue_resume called, instance_id: {}", instance_id);
+    let queues = state.task_queues.lock().map_err(|e| e.to_string())?;
+    let queue = queues
+        .get(&instance_id)
+        .filter(|q| q.is_active())
+        .ok_or_else(|| MxuError::new(ErrorCode::TaskQueueNotRunning))?;
+    queue.paused.store(false, Ordering::SeqCst);
+    Ok(())
+}
+
+/// 设置在当前任务结束后停止，instance_id 为空时作用于所有运行中的队列
+///
+/// 返回受影响的队列数量
+pub fn request_stop_after_current(
+    app: &AppHandle,
+    state: &MaaState,
+    instance_id: Option<&str>,
+    enabled: bool,
+) -> Result<usize, String> {
+    let queues = state.task_queues.lock().map_err(|e| e.to_string())?;
+    let mut count = 0;
+    for (id, queue) in queues.iter() {
+        if instance_id.is_some_and(|target| target != id) || !queue.is_active() {
+            continue;
+        }
+        queue.set_stop_after_current(app, enabled);
+        count += 1;
+    }
+    crate::tray::set_stop_after_current_label(enabled && count > 0);
+    Ok(count)
+}
+
+/// 当前任务结束后停止队列（不同于 maa_stop_task 的立即停止）
+///
+/// enabled 为 false 时撤销请求
+#[tauri::command]
+pub fn stop_after_current(
+    app: AppHandle,
+    state: State<Arc<MaaState>>,
+    instance_id: Option<String>,
+    enabled: Option<bool>,
+) -> Result<usize, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    let enabled = enabled.unwrap_or(true);
+    info!(
+        "stop_after_current called, instance_id: {:?}, enabled: {}",
+        instance_id, enabled
+    );
+    let count = request_stop_after_current(&app, &state, instance_id.as_deref(), enabled)?;
+    if count == 0 && enabled {
+        return Err(MxuError::new(ErrorCode::NoRunningTasks));
+    }
+    Ok(count)
+}
+
+/// 是否有队列已请求在当前任务结束后停止
+pub fn is_stop_after_current_pending(state: &MaaState) -> bool {
+    state.task_queues.lock().is_ok_and(|queues| {
+        queues
+            .values()
+            .any(|q| q.is_active() && q.stop_after_current.load(Ordering::SeqCst))
+    })
+}
+
+/// 停止实例的任务队列（maa_stop_task 时调用）
+pub fn cancel_queue(state: &MaaState, instance_id: &str) {
+    if let Ok(queues) = state.task_queues.lock() {
+        if let Some(queue) = queues.get(instance_id) {
+            queue.cancel();
+        }
+    }
+}
@@ -1,5 +1,7 @@
 //! 托盘相关命令
 
+use super::error::MxuError;
+use super::types::{TrayIconStyle, TrayStatus};
 use crate::tray;
 
 /// 设置关闭时是否最小化到托盘
@@ -17,12 +19,31 @@ pub fn get_minimize_to_tray() -> bool {
 
 /// 更新托盘图标
 #[tauri::command]
-pub fn update_tray_icon(icon_path: String) -> Result<(), String> {
-    tray::update_tray_icon(&icon_path)
+pub fn update_tray_icon(icon_path: String) -> Result<(), MxuError> {
+    Ok(tray::update_tray_icon(&icon_path)?)
 }
 
 /// 更新托盘 tooltip
 #[tauri::command]
-pub fn update_tray_tooltip(tooltip: String) -> Result<(), String> {
-    tray::update_tray_tooltip(&tooltip)
+pub fn update_tray_tooltip(tooltip: String) -> Result<(), MxuError> {
+    Ok(tray::update_tray_tooltip(&tooltip)?)
+}
+
+/// 设置托盘状态（决定图标角标）
+#[tauri::command]
+pub fn set_tray_status(status: TrayStatus) -> Result<(), MxuError> {
+    Ok(tray::set_tray_status(status)?)
+}
+
+/// 设置托盘角标风格（彩色 / 色盲友好形状 / 单色）
+#[tauri::command]
+pub fn set_tray_icon_style(style: TrayIconStyle) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    Ok(tray::set_tray_icon_style(style)?)
+}
+
+/// 获取托盘角标风格
+#[tauri::command]
+pub fn get_tray_icon_style() -> TrayIconStyle {
+    tray::get_tray_icon_style()
 }
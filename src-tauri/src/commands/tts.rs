@@ -0,0 +1,250 @@
+//! 语音播报
+//!
+//! 将关键事件（任务失败）与 focus 提示通过系统语音朗读：
+//! Windows 使用 SAPI（System.Speech），macOS 使用 say，Linux 使用 espeak。
+//! 朗读在后台线程中按顺序执行，不阻塞回调
+
+use log::{info, warn};
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{LazyLock, Mutex, OnceLock};
+use std::thread;
+
+use super::error::MxuError;
+use super::types::TtsConfig;
+
+/// 当前语音配置
+static CONFIG: LazyLock<Mutex<TtsConfig>> = LazyLock::new(|| Mutex::new(TtsConfig::default()));
+
+/// 朗读队列
+static SPEAKER: OnceLock<Mutex<Sender<String>>> = OnceLock::new();
+
+/// focus 模板占位符
+static PLACEHOLDER_RE: LazyLock<regex::Regex> =
+    LazyLock::new(|| regex::Regex::new(r"\{(\w+)\}").unwrap());
+
+/// HTML 标签
+static HTML_TAG_RE: LazyLock<regex::Regex> =
+    LazyLock::new(|| regex::Regex::new(r"<[^>]*>").unwrap());
+
+/// 单条朗读文本的最大长度
+const MAX_TEXT_LEN: usize = 500;
+
+fn current_config() -> TtsConfig {
+    CONFIG.lock().map(|c| c.clone()).unwrap_or_default()
+}
+
+/// 将文本加入朗读队列
+pub fn speak(text: &str) {
+    let text: String = text.trim().chars().take(MAX_TEXT_LEN).collect();
+    if text.is_empty() {
+        return;
+    }
+
+    let sender = SPEAKER.get_or_init(|| {
+        let (tx, rx) = mpsc::channel::<String>();
+        thread::spawn(move || {
+            for text in rx {
+                if let Err(e) = speak_blocking(&text, &current_config()) {
+                    warn!("[tts] Failed to speak: {}", e);
+                }
+            }
+        });
+        Mutex::new(tx)
+    });
+    if let Ok(sender) = sender.lock() {
+        let _ = sender.send(text);
+    }
+}
+
+/// 创建不弹出控制台窗口的命令
+fn hidden_command(program: &str) -> Command {
+    #[allow(unused_mut)]
+    let mut cmd = Command::new(program);
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    cmd
+}
+
+/// 同步朗读文本（文本通过 stdin 传入，避免命令注入）
+fn speak_blocking(text: &str, config: &TtsConfig) -> Result<(), String> {
+    let rate = config.rate.clamp(-10, 10);
+    let voice = config.voice.as_deref().filter(|v| !v.is_empty());
+
+    #[cfg(windows)]
+    let mut cmd = {
+        let mut c = hidden_command("powershell");
+        c.args([
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            "Add-Type -AssemblyName System.Speech; \
+             $s = New-Object System.Speech.Synthesis.SpeechSynthesizer; \
+             $s.Rate = [int]$env:MXU_TTS_RATE; \
+             if ($env:MXU_TTS_VOICE) { $s.SelectVoice($env:MXU_TTS_VOICE) }; \
+             $s.Speak([Console]::In.ReadToEnd())",
+        ]);
+        c.env("MXU_TTS_RATE", rate.to_string());
+        c.env("MXU_TTS_VOICE", voice.unwrap_or(""));
+        c
+    };
+
+    #[cfg(target_os = "macos")]
+    let mut cmd = {
+        let mut c = hidden_command("say");
+        c.args(["-r", &(175 + rate * 15).to_string()]);
+        if let Some(voice) = voice {
+            c.args(["-v", voice]);
+        }
+        c
+    };
+
+    #[cfg(not(any(windows, target_os = "macos")))]
+    let mut cmd = {
+        let mut c = hidden_command("espeak");
+        c.args(["-s", &(175 + rate * 15).to_string(), "--stdin"]);
+        if let Some(voice) = voice {
+            c.args(["-v", voice]);
+        }
+        c
+    };
+
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("无法启动语音引擎: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(text.as_bytes())
+            .map_err(|e| format!("无法写入朗读文本: {}", e))?;
+    }
+    child
+        .wait()
+        .map_err(|e| format!("语音引擎执行失败: {}", e))?;
+    Ok(())
+}
+
+/// 替换 focus 模板中的 {key} 占位符，无法解析的占位符及 HTML 标签被移除
+fn render_focus_template(template: &str, details: &serde_json::Value) -> String {
+    let text = PLACEHOLDER_RE.replace_all(template, |caps: &regex::Captures| {
+        match details.get(&caps[1]) {
+            Some(serde_json::Value::String(s)) => s.clone(),
+            Some(v @ serde_json::Value::Number(_)) => v.to_string(),
+            _ => String::new(),
+        }
+    });
+    HTML_TAG_RE.replace_all(&text, "").to_string()
+}
+
+/// 处理 MaaFramework 回调，按配置播报 focus 提示与任务失败
+pub fn handle_callback(message: &str, details: &str) {
+    let config = current_config();
+    if !config.enabled {
+        return;
+    }
+    let Ok(details) = serde_json::from_str::<serde_json::Value>(details) else {
+        return;
+    };
+
+    if config.speak_focus {
+        if let Some(template) = details
+            .get("focus")
+            .and_then(|f| f.get(message))
+            .and_then(|t| t.as_str())
+        {
+            speak(&render_focus_template(template, &details));
+            return;
+        }
+    }
+
+    if config.speak_failures && message == "Tasker.Task.Failed" {
+        let entry = details.get("entry").and_then(|e| e.as_str()).unwrap_or("");
+        speak(&format!("任务失败：{}", entry));
+    }
+}
+
+/// 设置语音播报配置
+#[tauri::command]
+pub fn tts_set_config(config: TtsConfig) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    info!("[tts] Config updated: {:?}", config);
+    *CONFIG.lock().map_err(|e| e.to_string())? = config;
+    Ok(())
+}
+
+/// 获取语音播报配置
+#[tauri::command]
+pub fn tts_get_config() -> TtsConfig {
+    current_config()
+}
+
+/// 朗读指定文本（用于设置页试听）
+#[tauri::command]
+pub fn tts_speak(text: String) {
+    speak(&text);
+}
+
+/// 列出系统可用的语音名称
+#[tauri::command]
+pub async fn tts_list_voices() -> Result<Vec<String>, MxuError> {
+    tauri::async_runtime::spawn_blocking(|| {
+        #[cfg(windows)]
+        let output = hidden_command("powershell")
+            .args([
+                "-NoProfile",
+                "-NonInteractive",
+                "-Command",
+                "Add-Type -AssemblyName System.Speech; \
+                 (New-Object System.Speech.Synthesis.SpeechSynthesizer).GetInstalledVoices() | \
+                 ForEach-Object { $_.VoiceInfo.Name }",
+            ])
+            .output();
+
+        #[cfg(target_os = "macos")]
+        let output = hidden_command("say").args(["-v", "?"]).output();
+
+        #[cfg(not(any(windows, target_os = "macos")))]
+        let output = hidden_command("espeak").arg("--voices").output();
+
+        let output = output.map_err(|e| format!("无法查询语音列表: {}", e))?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        #[cfg(windows)]
+        let voices: Vec<String> = stdout
+            .lines()
+            .map(|l| l.trim().to_string())
+            .filter(|l| !l.is_empty())
+            .collect();
+
+        // say -v ? 每行格式：名称  语言  # 示例文本
+        #[cfg(target_os = "macos")]
+        let voices: Vec<String> = stdout
+            .lines()
+            .filter_map(|l| l.split("  ").next())
+            .map(|n| n.trim().to_string())
+            .filter(|n| !n.is_empty())
+            .collect();
+
+        // espeak --voices 首行为表头，第 4 列为语音名称
+        #[cfg(not(any(windows, target_os = "macos")))]
+        let voices: Vec<String> = stdout
+            .lines()
+            .skip(1)
+            .filter_map(|l| l.split_whitespace().nth(3))
+            .map(|n| n.to_string())
+            .collect();
+
+        Ok(voices)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
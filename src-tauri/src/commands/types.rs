@@ -2,10 +2,10 @@
 //!
 //! 包含 Tauri 命令使用的数据结构和枚举
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::PathBuf;
 use std::process::Child;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
 use serde::{Deserialize, Serialize};
@@ -15,6 +15,8 @@ use maa_framework::controller::Controller;
 use maa_framework::resource::Resource;
 use maa_framework::tasker::Tasker;
 
+use super::task_queue::TaskQueue;
+
 // ============================================================================
 // 数据类型定义
 // ============================================================================
@@ -52,6 +54,30 @@ mod u64_as_string {
     }
 }
 
+/// 反序列化 Win32 截图/输入方式：接受位标志或方式名称
+mod win32_method {
+    use serde::{Deserialize, Deserializer};
+    use serde_json::Value;
+
+    use crate::commands::win32_capture::{method_bits, INPUT_METHODS, SCREENCAP_METHODS};
+
+    pub fn screencap<'de, D>(deserializer: D) -> Result<u64, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = Value::deserialize(deserializer)?;
+        method_bits(SCREENCAP_METHODS, &value).map_err(serde::de::Error::custom)
+    }
+
+    pub fn input<'de, D>(deserializer: D) -> Result<u64, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = Value::deserialize(deserializer)?;
+        method_bits(INPUT_METHODS, &value).map_err(serde::de::Error::custom)
+    }
+}
+
 /// Win32 窗口信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Win32Window {
@@ -60,6 +86,14 @@ pub struct Win32Window {
     pub window_name: String,
 }
 
+/// Win32 窗口缩略图
+#[derive(Debug, Clone, Serialize)]
+pub struct Win32WindowThumbnail {
+    pub handle: u64,
+    /// data URL 格式的 PNG 缩略图，截取失败（窗口已关闭、最小化等）时为 None
+    pub image: Option<String>,
+}
+
 /// 控制器类型
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
@@ -67,14 +101,18 @@ pub enum ControllerConfig {
     Adb {
         adb_path: String,
         address: String,
-        screencap_methods: String, // u64 作为字符串传递，避免 JS 精度丢失
-        input_methods: String,     // u64 作为字符串传递
+        screencap_methods: String, // u64 作为字符串传递，避免 JS 精度丢失；也可为 | 分隔的方式名称
+        input_methods: String,     // u64 作为字符串传递；也可为 | 分隔的方式名称
         config: String,
     },
+    /// 方式可为位标志或名称（如 "DXGI_DesktopDup"、["SendMessage", "PostMessage"]）
     Win32 {
         handle: u64,
+        #[serde(deserialize_with = "win32_method::screencap")]
         screencap_method: u64,
+        #[serde(deserialize_with = "win32_method::input")]
         mouse_method: u64,
+        #[serde(deserialize_with = "win32_method::input")]
         keyboard_method: u64,
     },
     Gamepad {
@@ -89,6 +127,14 @@ pub enum ControllerConfig {
         #[serde(default)]
         uuid: Option<String>,
     },
+    /// 模拟控制器：依次回放目录中预先截取的截图，输入操作不产生效果，
+    /// 用于在没有设备的环境下验证资源包或开发 pipeline
+    Mock {
+        image_dir: String,
+        /// 调试输出目录，默认为数据目录下的 debug/mock_controller
+        #[serde(default)]
+        record_dir: Option<String>,
+    },
 }
 
 /// 连接状态
@@ -122,6 +168,9 @@ pub struct InstanceState {
     pub is_running: bool,
     /// 当前运行的任务 ID 列表
     pub task_ids: Vec<i64>,
+    /// 是否已请求暂停（见 pause_gate）
+    #[serde(default)]
+    pub is_paused: bool,
 }
 
 /// 所有实例状态的快照
@@ -130,6 +179,65 @@ pub struct AllInstanceStates {
     pub instances: HashMap<String, InstanceState>,
     pub cached_adb_devices: Vec<AdbDevice>,
     pub cached_win32_windows: Vec<Win32Window>,
+    /// 设备分组的汇总状态
+    #[serde(default)]
+    pub device_groups: Vec<DeviceGroupStatus>,
+}
+
+/// 设备分组成员：实例及其控制器配置（用于批量连接）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceGroupMember {
+    pub instance_id: String,
+    pub controller: ControllerConfig,
+}
+
+/// 设备分组（config/device_groups.json）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceGroup {
+    /// 为空时保存时自动分配
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub members: Vec<DeviceGroupMember>,
+}
+
+/// 设备分组成员状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceGroupMemberStatus {
+    pub instance_id: String,
+    /// 实例是否已创建
+    pub exists: bool,
+    pub connected: bool,
+    pub is_running: bool,
+}
+
+/// 设备分组汇总状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceGroupStatus {
+    pub id: String,
+    pub name: String,
+    pub total: usize,
+    pub connected: usize,
+    pub running: usize,
+    pub members: Vec<DeviceGroupMemberStatus>,
+}
+
+/// 批量操作中单个成员的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceGroupResult {
+    pub instance_id: String,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+/// 在分组所有设备上运行配置档案的请求（device-group-run-profile 事件，由前端逐个启动）
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceGroupRunProfileEvent {
+    pub group_id: String,
+    pub profile_id: String,
+    /// 已连接的成员实例
+    pub instance_ids: Vec<String>,
 }
 
 /// 实例运行时状态（持有 MaaFramework 对象句柄）
@@ -146,6 +254,23 @@ pub struct InstanceRuntime {
     pub stop_in_progress: bool,
     /// stop 请求的起始时间（用于节流/重试）
     pub stop_started_at: Option<Instant>,
+    /// Agent 启动参数与重启记录（用于崩溃后自动重启）
+    pub agent_launch: Option<AgentLaunch>,
+}
+
+/// 一次 Agent 启动的参数与重启记录
+pub struct AgentLaunch {
+    /// 启动标识，停止或重新启动 Agent 后变化，用于丢弃过期的重启结果
+    pub id: u64,
+    pub configs: Vec<AgentConfig>,
+    pub cwd: String,
+    pub tcp_compat_mode: bool,
+    /// 本次启动的第一个 Agent 在 agent_children 中的下标
+    pub base: usize,
+    /// 各 Agent 已重启的次数
+    pub restarts: Vec<u32>,
+    /// 正在重启或已放弃重启的 Agent（监控时跳过）
+    pub handled: HashSet<usize>,
 }
 
 impl Drop for InstanceRuntime {
@@ -156,10 +281,9 @@ impl Drop for InstanceRuntime {
         }
         self.agent_clients.clear();
 
-        // 终止并回收所有 agent 子进程
+        // 终止并回收所有 agent 子进程（连同其派生的子进程）
         for mut child in self.agent_children.drain(..) {
-            let _ = child.kill();
-            let _ = child.wait();
+            super::agent_sandbox::kill_tree(&mut child);
         }
 
         if let Some(tasker) = self.tasker.take() {
@@ -184,6 +308,8 @@ pub struct MaaState {
     pub cached_adb_devices: Mutex<Vec<AdbDevice>>,
     /// 缓存的 Win32 窗口列表（全局共享）
     pub cached_win32_windows: Mutex<Vec<Win32Window>>,
+    /// 各实例的任务队列（队列模式下由 maa_start_tasks 创建）
+    pub task_queues: Mutex<HashMap<String, Arc<TaskQueue>>>,
 }
 
 impl MaaState {
@@ -193,19 +319,45 @@ impl MaaState {
             for (id, instance) in instances.iter_mut() {
                 for mut child in instance.agent_children.drain(..) {
                     log::info!("Killing agent child process for instance: {}", id);
-                    if let Err(e) = child.kill() {
-                        log::warn!(
-                            "Failed to kill agent child process for instance {}: {:?}",
-                            id,
-                            e
-                        );
-                    }
-                    // 回收子进程，避免 *nix 上产生僵尸进程
-                    let _ = child.wait();
+                    // 连同 Agent 派生的子进程一起结束，并回收子进程，避免 *nix 上产生僵尸进程
+                    super::agent_sandbox::kill_tree(&mut child);
                 }
             }
         }
     }
+
+    /// 销毁所有实例（任务队列、Agent、Tasker、控制器与资源）并释放其中的回调，返回销毁的实例数
+    /// 按 Tasker → 控制器 → 资源的顺序销毁，Tasker 绑定的对象不会先于它释放
+    pub fn teardown_all(&self) -> usize {
+        if let Ok(mut queues) = self.task_queues.lock() {
+            for queue in queues.values() {
+                queue.cancel();
+            }
+            queues.clear();
+        }
+        self.cleanup_all_agent_children();
+
+        let drained: Vec<(String, InstanceRuntime)> = match self.instances.lock() {
+            Ok(mut instances) => instances.drain().collect(),
+            Err(_) => return 0,
+        };
+        let count = drained.len();
+        for (id, mut instance) in drained {
+            log::info!("Tearing down instance: {}", id);
+            instance.agent_clients.clear();
+            drop(instance.tasker.take());
+            drop(instance.controller.take());
+            drop(instance.resource.take());
+        }
+
+        if let Ok(mut devices) = self.cached_adb_devices.lock() {
+            devices.clear();
+        }
+        if let Ok(mut windows) = self.cached_win32_windows.lock() {
+            windows.clear();
+        }
+        count
+    }
 }
 
 /// Maa回调事件
@@ -223,6 +375,26 @@ pub struct AgentConfig {
     pub identifier: Option<String>,
     /// 连接超时时间（毫秒），-1 表示无限等待
     pub timeout: Option<i64>,
+    /// 进程意外退出后的最大自动重启次数，默认 3，0 表示不重启
+    #[serde(default)]
+    pub max_restarts: Option<u32>,
+}
+
+/// Agent 子进程隔离设置（config/agent_sandbox.json）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AgentSandboxSettings {
+    /// 在独立工作目录中运行 Agent
+    #[serde(default)]
+    pub isolate_working_dir: bool,
+    /// 清理继承的环境变量，只保留系统必需变量与 env_passthrough
+    #[serde(default)]
+    pub scrub_env: bool,
+    /// 清理环境变量时额外保留的变量名
+    #[serde(default)]
+    pub env_passthrough: Vec<String>,
+    /// 注入的环境变量
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
 }
 
 /// 任务配置
@@ -230,72 +402,2662 @@ pub struct AgentConfig {
 pub struct TaskConfig {
     pub entry: String,
     pub pipeline_override: String,
+    /// 队列项 ID（由前端指定，用于在队列状态中对应任务），为空时使用下标
+    #[serde(default)]
+    pub id: Option<String>,
+    /// 优先级，数值越大越先执行，相同优先级保持原顺序（仅队列模式）
+    #[serde(default)]
+    pub priority: i32,
+    /// 失败后的最大重试次数（仅 on_failure 为 retry 时生效）
+    #[serde(default)]
+    pub retry_count: u32,
+    /// 失败处理策略（仅队列模式）
+    #[serde(default)]
+    pub on_failure: FailurePolicy,
+    /// 执行条件，不满足时跳过该任务（仅队列模式）
+    #[serde(default)]
+    pub run_if: Option<TaskCondition>,
+    /// 包含条件，运行开始时求值，不满足时不加入本次运行（见 queue_templates）
+    #[serde(default)]
+    pub include_if: Option<IncludeCondition>,
+    /// 单次执行的最长时间（秒），超时后中止任务并继续队列（仅队列模式）
+    #[serde(default)]
+    pub max_duration_secs: Option<u64>,
+    /// 超时后执行的恢复任务（如重启游戏），只使用其 entry、pipeline_override 与 max_duration_secs
+    #[serde(default)]
+    pub on_timeout_task: Option<Box<TaskConfig>>,
 }
 
-/// 版本检查结果
-#[derive(Serialize)]
-pub struct VersionCheckResult {
-    /// 当前 MaaFramework 版本
-    pub current: String,
-    /// 最小支持版本
-    pub minimum: String,
-    /// 是否满足最小版本要求
-    pub is_compatible: bool,
+/// 任务包含条件（运行开始时求值）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum IncludeCondition {
+    /// 星期几（1 = 周一 … 7 = 周日）
+    Weekday { days: Vec<u32> },
+    /// 每月的日期（1-31）
+    MonthDay { days: Vec<u32> },
+    /// 日期区间（YYYY-MM-DD，含首尾，任一端可省略）
+    DateRange {
+        #[serde(default)]
+        from: Option<String>,
+        #[serde(default)]
+        to: Option<String>,
+    },
+    /// 时间段（HH:MM，含 start 不含 end，end 早于 start 时跨越午夜）
+    TimeRange { start: String, end: String },
+    /// 配置档案标记为真；给出 equals 时需值相等
+    Flag {
+        name: String,
+        #[serde(default)]
+        equals: Option<serde_json::Value>,
+    },
+    /// 所有子条件均满足
+    All { conditions: Vec<IncludeCondition> },
+    /// 任一子条件满足
+    Any { conditions: Vec<IncludeCondition> },
+    /// 子条件不满足
+    Not { condition: Box<IncludeCondition> },
 }
 
-/// changes.json 结构
+/// 运行开始时因包含条件不满足而未加入的任务
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ChangesJson {
+pub struct ExcludedTask {
+    pub entry: String,
+    /// 队列项 ID
     #[serde(default)]
-    pub added: Vec<String>,
+    pub id: Option<String>,
+    /// 未满足的条件说明
+    pub reason: String,
+}
+
+/// 队列模板解析结果（resolve_queue_template 返回值）
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueResolution {
+    pub tasks: Vec<TaskConfig>,
+    pub excluded: Vec<ExcludedTask>,
+}
+
+/// 任务执行条件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TaskCondition {
+    /// 指定队列项执行成功
+    Succeeded { task: String },
+    /// 指定队列项执行失败
+    Failed { task: String },
+    /// 变量存储中存在指定变量；给出 equals 时还需值相等
+    Variable {
+        name: String,
+        #[serde(default)]
+        equals: Option<serde_json::Value>,
+    },
+    /// 所有子条件均满足
+    All { conditions: Vec<TaskCondition> },
+    /// 任一子条件满足
+    Any { conditions: Vec<TaskCondition> },
+    /// 子条件不满足
+    Not { condition: Box<TaskCondition> },
+}
+
+/// 任务失败处理策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailurePolicy {
+    /// 跳过失败任务，继续执行后续任务
+    #[default]
+    Skip,
+    /// 中止整个队列
+    Abort,
+    /// 重试 retry_count 次（至少 1 次），仍失败则跳过
+    Retry,
+}
+
+/// 任务队列选项（传入时 maa_start_tasks 使用队列引擎逐个执行任务）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QueueOptions {
+    /// 任务之间的等待时间（毫秒）
     #[serde(default)]
-    pub deleted: Vec<String>,
+    pub delay_ms: u64,
+    /// 整个运行的最长时间（秒），超过后在下一个节点边界停止，未指定时使用配置档案的设置
     #[serde(default)]
-    pub modified: Vec<String>,
+    pub max_duration_secs: Option<u64>,
 }
 
-/// 下载进度事件数据
-#[derive(Clone, Serialize)]
-pub struct DownloadProgressEvent {
-    pub session_id: u64,
-    pub downloaded_size: u64,
-    pub total_size: u64,
-    pub speed: u64,
-    pub progress: f64,
+/// 队列项状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QueueItemStatus {
+    Pending,
+    Running,
+    Succeeded,
+    Failed,
+    Skipped,
+    /// 超过 max_duration_secs 被中止
+    TimedOut,
 }
 
-/// 下载结果
-#[derive(Clone, Serialize)]
-pub struct DownloadResult {
-    /// 下载会话 ID
-    pub session_id: u64,
-    /// 实际保存的文件路径（可能与请求的路径不同，如果从 URL 或 header 检测到正确的文件名）
-    pub actual_save_path: String,
-    /// 从 URL 或 Content-Disposition 提取的文件名（如果有）
-    pub detected_filename: Option<String>,
+/// 队列项运行时信息
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueItemState {
+    pub id: String,
+    pub entry: String,
+    pub status: QueueItemStatus,
+    /// 已执行次数（包含重试）
+    pub attempts: u32,
+    /// 最近一次提交的 MaaFramework 任务 ID
+    pub task_id: Option<i64>,
 }
 
-/// 系统信息结构
+/// 队列整体状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QueueStatus {
+    Running,
+    Paused,
+    /// 按空闲策略等待用户停止操作电脑
+    WaitingForIdle,
+    /// 已请求停止，等待当前任务结束
+    Stopping,
+    Completed,
+    Aborted,
+    Stopped,
+    /// 达到运行时长上限后停止
+    TimeBoxed,
+}
+
+/// 队列状态快照（queue_state 命令返回值及 task-queue-update 事件载荷）
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueSnapshot {
+    pub instance_id: String,
+    /// 本次运行 ID（见 runs 模块）
+    pub run_id: Option<String>,
+    pub status: QueueStatus,
+    /// 当前执行到的队列项下标
+    pub current_index: Option<usize>,
+    /// 是否已请求在当前任务结束后停止
+    #[serde(default)]
+    pub stop_after_current: bool,
+    pub items: Vec<QueueItemState>,
+}
+
+/// 队列全部结束后的操作类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PostActionKind {
+    #[default]
+    None,
+    Shutdown,
+    Restart,
+    Sleep,
+    ScreenOff,
+    /// 退出 MXU
+    QuitApp,
+    /// 运行外部程序
+    RunProgram,
+}
+
+/// 完成后操作策略
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SystemInfo {
-    pub os: String,
-    pub os_version: String,
-    pub arch: String,
-    pub tauri_version: String,
+pub struct PostActionPolicy {
+    #[serde(default)]
+    pub action: PostActionKind,
+    /// RunProgram 时执行的程序路径
+    #[serde(default)]
+    pub program: Option<String>,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// 执行前的倒计时秒数，期间可取消
+    #[serde(default = "default_post_action_countdown")]
+    pub countdown_secs: u32,
+    /// 队列因失败中止时是否仍然执行
+    #[serde(default)]
+    pub run_on_failure: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct GitHubAsset {
+fn default_post_action_countdown() -> u32 {
+    60
+}
+
+impl Default for PostActionPolicy {
+    fn default() -> Self {
+        Self {
+            action: PostActionKind::None,
+            program: None,
+            args: Vec::new(),
+            countdown_secs: default_post_action_countdown(),
+            run_on_failure: false,
+        }
+    }
+}
+
+/// 完成后操作倒计时事件（post-action-countdown）
+#[derive(Debug, Clone, Serialize)]
+pub struct PostActionCountdownEvent {
+    pub action: PostActionKind,
+    pub remaining_secs: u32,
+}
+
+/// 当前配置档案格式版本
+pub const PROFILE_VERSION: u32 = 1;
+
+/// 配置档案：一组可快速切换的设备、资源、任务选择与选项覆盖
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Profile {
+    #[serde(default = "default_profile_version")]
+    pub version: u32,
+    pub id: String,
     pub name: String,
-    pub browser_download_url: String,
+    /// 创建时间（RFC 3339）
+    pub created_at: String,
+    /// 更新时间（RFC 3339）
+    pub updated_at: String,
+    #[serde(flatten)]
+    pub content: ProfileContent,
+}
+
+fn default_profile_version() -> u32 {
+    1
+}
+
+/// 配置档案内容，具体结构由前端定义
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProfileContent {
+    /// 设备/控制器配置
+    #[serde(default)]
+    pub device: serde_json::Value,
+    /// 资源名称
+    #[serde(default)]
+    pub resource: Option<String>,
+    /// 任务选择
+    #[serde(default)]
+    pub tasks: serde_json::Value,
+    /// 选项覆盖
+    #[serde(default)]
+    pub option_overrides: serde_json::Value,
+    /// 标记（如活动开关），供任务的 include_if 条件引用
+    #[serde(default)]
+    pub flags: BTreeMap<String, serde_json::Value>,
+    /// 任务钩子
+    #[serde(default)]
+    pub hooks: Vec<TaskHook>,
+    /// 运行的最长时间（秒），队列选项未指定时使用
+    #[serde(default)]
+    pub max_duration_secs: Option<u64>,
+    /// 固定使用的 MaaFramework 版本（maafw_versions 下的版本名），为空时使用当前版本
+    #[serde(default)]
+    pub maafw_version: Option<String>,
+}
+
+/// 钩子触发时机
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HookEvent {
+    /// 队列开始前
+    BeforeQueue,
+    /// 每个任务开始前
+    BeforeTask,
+    /// 每个任务结束后（含重试后的最终结果）
+    AfterTask,
+    /// 队列结束后
+    AfterQueue,
+    /// 任务最终失败时
+    OnFailure,
+}
+
+/// 钩子操作，文本中可使用 {instance} {task} {status} {run} {event} 占位符
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HookAction {
+    /// 运行程序
+    RunProgram {
+        program: String,
+        #[serde(default)]
+        args: Vec<String>,
+        /// 是否等待程序退出（仅 before_* 钩子阻塞队列）
+        #[serde(default)]
+        wait: bool,
+        /// 等待的最长时间（秒），超时后结束程序
+        #[serde(default)]
+        timeout_secs: Option<u64>,
+    },
+    /// 请求 Webhook，未指定 body 时发送包含事件信息的 JSON
+    Webhook {
+        url: String,
+        #[serde(default)]
+        method: Option<String>,
+        #[serde(default)]
+        body: Option<String>,
+    },
+    /// 系统通知
+    Notify { title: String, body: String },
+}
+
+/// 配置档案中的任务钩子
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskHook {
+    pub event: HookEvent,
+    pub action: HookAction,
+    /// 仅对这些任务入口生效（before_task / after_task / on_failure），为空时对所有任务生效
+    #[serde(default)]
+    pub tasks: Vec<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+/// 可导入的外部配置来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportSource {
+    /// MaaPiCli（maa_pi_config.json）
+    MaaPiCli,
+    /// MFAAvalonia（config.json 中的 TaskItems）
+    MfaAvalonia,
+}
+
+/// 导入预览中的任务
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportedTask {
+    pub name: String,
+    pub enabled: bool,
+    /// 成功转换的选项数量
+    pub option_count: usize,
+}
+
+/// 外部配置导入预览
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportPreview {
+    pub source: ImportSource,
+    /// 实际读取的配置文件
+    pub path: String,
+    pub resource: Option<String>,
+    pub controller: Option<String>,
+    pub tasks: Vec<ImportedTask>,
+    /// 被跳过的任务、无法转换的选项等提示
+    pub warnings: Vec<String>,
+}
+
+/// 配置档案列表项
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileSummary {
+    pub id: String,
+    pub name: String,
+    pub updated_at: String,
+}
+
+/// 远程访问令牌权限范围（按权限从低到高排序）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RemoteScope {
+    /// 只读状态查询
+    ReadOnly,
+    /// 开始/停止任务
+    Control,
+    /// 完全控制
+    Full,
+}
+
+/// 远程访问令牌（仅保存哈希）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteToken {
+    pub id: String,
+    pub name: String,
+    pub scope: RemoteScope,
+    pub token_hash: String,
+    pub created_at: String,
+    #[serde(default)]
+    pub rotated_at: Option<String>,
+}
+
+/// 远程令牌信息（返回给前端，不含哈希）
+#[derive(Debug, Clone, Serialize)]
+pub struct RemoteTokenInfo {
+    pub id: String,
+    pub name: String,
+    pub scope: RemoteScope,
+    pub created_at: String,
+    pub rotated_at: Option<String>,
+}
+
+/// 新建/轮换令牌的结果，明文令牌仅在此时返回一次
+#[derive(Debug, Clone, Serialize)]
+pub struct RemoteTokenSecret {
+    pub info: RemoteTokenInfo,
+    pub token: String,
+}
+
+/// 远程访问配对二维码
+#[derive(Debug, Clone, Serialize)]
+pub struct RemotePairingQr {
+    /// 二维码内容（mxu://pair?...）
+    pub payload: String,
+    /// 远程 API 根地址
+    pub address: String,
+    /// 二维码 PNG（data URL）
+    pub image: String,
+}
+
+/// 允许远程调用的命令及其所需权限
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteCommandRule {
+    pub command: String,
+    pub scope: RemoteScope,
+}
+
+/// 远程访问审计记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteAuditEntry {
+    pub time: String,
+    pub token_id: Option<String>,
+    pub command: String,
+    pub allowed: bool,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// 配置加载结果（含迁移信息）
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigLoadResult {
+    /// 配置内容，文件不存在时为 None
+    pub config: Option<serde_json::Value>,
+    /// 是否执行了迁移
+    pub migrated: bool,
+    pub from_version: u32,
+    pub to_version: u32,
+    /// 迁移前备份文件路径
+    pub backup_path: Option<String>,
+    /// 迁移过程中的变更说明
+    pub changes: Vec<String>,
+}
+
+/// 导出日志时 on_error 图片的选择策略（未指定的项使用默认值）
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ErrorImagePolicy {
+    /// 最多导出的张数（取最新的，默认 50）
+    #[serde(default)]
+    pub max_count: Option<usize>,
+    /// 图片总大小上限（MB）
+    #[serde(default)]
+    pub max_total_mb: Option<u64>,
+    /// 图片时间范围（RFC3339 或 YYYY-MM-DD，未指定时使用日志的时间范围）
+    #[serde(default)]
+    pub since: Option<String>,
+    #[serde(default)]
+    pub until: Option<String>,
+    /// 仅导出最近一次运行期间的图片
+    #[serde(default)]
+    pub only_last_run: bool,
+}
+
+/// 备份进度事件（backup-progress）
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupProgressEvent {
+    /// create / restore
+    pub operation: String,
+    /// 正在处理的压缩包内路径（完成时为空）
+    pub current: String,
+    pub processed: usize,
+    pub total: usize,
+    pub progress: f64,
+}
+
+/// 设置备份信息
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupInfo {
+    pub path: String,
+    pub file_name: String,
     pub size: u64,
+    /// 创建时间（Unix 毫秒时间戳）
+    pub created: Option<u64>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct GitHubRelease {
-    pub tag_name: String,
+/// 集群远程节点
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterNode {
+    pub id: String,
     pub name: String,
-    pub body: Option<String>,
-    pub prerelease: bool,
-    pub assets: Vec<GitHubAsset>,
+    /// 节点远程 API 根地址，如 http://192.168.1.10:18080
+    pub url: String,
+    /// 节点签发的访问令牌
+    pub token: String,
+}
+
+/// 集群节点可用性
+#[derive(Debug, Clone, Serialize)]
+pub struct ClusterNodeStatus {
+    pub node_id: String,
+    pub online: bool,
+    pub busy: bool,
+    pub error: Option<String>,
+}
+
+/// 配置档案分配结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ClusterAssignment {
+    pub profile_id: String,
+    /// 排队中的档案为空
+    pub node_id: String,
+    /// 等待节点空闲后再提交
+    pub queued: bool,
+    pub error: Option<String>,
+}
+
+/// 远程节点回报的运行结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterRunResult {
+    pub node_id: String,
+    pub profile_id: String,
+    pub success: bool,
+    #[serde(default)]
+    pub message: Option<String>,
+    #[serde(default)]
+    pub finished_at: Option<String>,
+}
+
+/// 主实例提交给节点的分发请求（POST /dispatch）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterDispatchRequest {
+    /// 主实例中该节点的 ID（回报结果时原样返回）
+    pub node_id: String,
+    pub profile: Profile,
+    /// 回报运行结果的地址（主实例的 /cluster/report）
+    pub report_url: String,
+    /// 本轮分发的回报密钥
+    pub report_secret: String,
+}
+
+/// 节点状态（GET /status）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterNodeState {
+    /// 正在执行分发任务或有进行中的运行
+    pub busy: bool,
+    pub version: String,
+    /// 正在执行的分发任务对应的主实例档案 ID
+    pub dispatched_profile: Option<String>,
+}
+
+/// 一轮分发全部结束后的汇总（cluster-summary 事件）
+#[derive(Debug, Clone, Serialize)]
+pub struct ClusterSummary {
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub results: Vec<ClusterRunResult>,
+}
+
+/// WebDAV 同步配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SyncConfig {
+    /// WebDAV 根地址
+    pub url: String,
+    #[serde(default)]
+    pub username: String,
+    #[serde(default)]
+    pub password: String,
+    /// 远程目录，默认 mxu
+    #[serde(default)]
+    pub remote_dir: Option<String>,
+}
+
+/// 同步结果
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SyncResult {
+    pub uploaded: Vec<String>,
+    pub downloaded: Vec<String>,
+    /// 本地与远程自上次同步后均被修改的文件，未做处理
+    pub conflicts: Vec<String>,
+}
+
+/// 语音播报配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TtsConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 语音名称，为空时使用系统默认
+    #[serde(default)]
+    pub voice: Option<String>,
+    /// 语速，-10 ~ 10，0 为默认
+    #[serde(default)]
+    pub rate: i32,
+    /// 播报 focus 提示
+    #[serde(default = "default_true")]
+    pub speak_focus: bool,
+    /// 播报任务失败等关键事件
+    #[serde(default = "default_true")]
+    pub speak_failures: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for TtsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            voice: None,
+            rate: 0,
+            speak_focus: true,
+            speak_failures: true,
+        }
+    }
+}
+
+/// 资源包信息
+#[derive(Debug, Clone, Serialize)]
+pub struct ResourcePackInfo {
+    /// 资源包目录名（唯一标识）
+    pub name: String,
+    pub path: String,
+    /// interface.json 中的显示名称
+    pub label: Option<String>,
+    pub version: Option<String>,
+    pub enabled: bool,
+    /// 加载顺序，越靠后优先级越高
+    pub order: usize,
+}
+
+/// 托盘状态（决定图标角标）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrayStatus {
+    #[default]
+    Idle,
+    Running,
+    Paused,
+    Success,
+    Error,
+    /// 有已下载待安装的更新
+    UpdateAvailable,
+}
+
+/// 托盘角标图标风格
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrayIconStyle {
+    /// 彩色圆点
+    #[default]
+    Color,
+    /// 色盲友好：形状区分状态，使用 Okabe-Ito 配色
+    Shape,
+    /// 单色形状，仅依靠形状区分
+    Monochrome,
+}
+
+/// 资源热重载事件（resource-reloaded）
+#[derive(Debug, Clone, Serialize)]
+pub struct ResourceReloadedEvent {
+    pub instance_id: String,
+    /// 触发重载的文件
+    pub changed: Vec<String>,
+    /// 新的资源加载请求 ID
+    pub res_ids: Vec<i64>,
+    pub error: Option<String>,
+}
+
+/// 热重载失败的配置文件
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigReloadFailure {
+    pub file: String,
+    pub error: String,
+}
+
+/// 配置热重载事件（config-reloaded）
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigReloadedEvent {
+    /// 已校验并重新加载的文件
+    pub reloaded: Vec<String>,
+    /// 校验失败的文件（保持当前配置）
+    pub failed: Vec<ConfigReloadFailure>,
+    /// 需要重启后生效的文件
+    pub restart_required: Vec<String>,
+    /// 前端用户设置（含定时任务）是否变化，需由前端重新读取
+    pub settings_changed: bool,
+    /// 配置档案是否变化
+    pub profiles_changed: bool,
+}
+
+/// 单个任务提交时的 pipeline 覆盖
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunTaskPipeline {
+    pub entry: String,
+    pub pipeline_override: serde_json::Value,
+}
+
+/// 运行中通过 maa_override_pipeline 追加的覆盖
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunLateOverride {
+    pub task_id: i64,
+    pub pipeline_override: serde_json::Value,
+    pub time: String,
+}
+
+/// 单次运行的 pipeline 快照（runs/<run_id>/pipeline.json）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunPipelineSnapshot {
+    pub run_id: String,
+    pub instance_id: String,
+    pub started_at: String,
+    pub tasks: Vec<RunTaskPipeline>,
+    /// 因包含条件不满足而未加入本次运行的任务
+    #[serde(default)]
+    pub excluded: Vec<ExcludedTask>,
+    #[serde(default)]
+    pub late_overrides: Vec<RunLateOverride>,
+    /// 按提交顺序深度合并后的全部覆盖
+    pub merged: serde_json::Value,
+}
+
+/// 运行开始事件（run-started）
+#[derive(Debug, Clone, Serialize)]
+pub struct RunStartedEvent {
+    pub instance_id: String,
+    pub run_id: String,
+}
+
+/// 运行结束事件
+#[derive(Debug, Clone, Serialize)]
+pub struct RunCompletedEvent {
+    pub instance_id: String,
+    pub run_id: String,
+    /// succeeded / failed（见 runs::status_name）
+    pub status: String,
+    /// 队列模式下的队列最终状态（completed / stopped / aborted / time_boxed），直接模式为空
+    pub reason: Option<String>,
+    /// 本次运行的独立日志文件路径
+    pub log_path: String,
+    /// 关键截图拼接的报告图路径（没有截图时为空）
+    pub report_path: Option<String>,
+}
+
+/// 版本检查结果
+#[derive(Serialize)]
+pub struct VersionCheckResult {
+    /// 当前 MaaFramework 版本
+    pub current: String,
+    /// 最小支持版本
+    pub minimum: String,
+    /// 是否满足最小版本要求
+    pub is_compatible: bool,
+}
+
+/// 当前支持的 changes.json 最高版本
+pub const CHANGES_SCHEMA_VERSION: u32 = 2;
+
+/// changes.json 结构
+/// v1 仅包含 added/deleted/modified；v2 新增 renamed/deleted_dirs/permissions，缺省字段按 v1 处理
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangesJson {
+    #[serde(default = "default_changes_schema_version")]
+    pub schema_version: u32,
+    #[serde(default)]
+    pub added: Vec<String>,
+    #[serde(default)]
+    pub deleted: Vec<String>,
+    #[serde(default)]
+    pub modified: Vec<String>,
+    /// 文件/目录重命名或移动（v2）
+    #[serde(default)]
+    pub renamed: Vec<RenameOperation>,
+    /// 整个目录删除（v2）
+    #[serde(default)]
+    pub deleted_dirs: Vec<String>,
+    /// 可执行权限变更（v2）
+    #[serde(default)]
+    pub permissions: Vec<PermissionOperation>,
+}
+
+fn default_changes_schema_version() -> u32 {
+    1
+}
+
+/// 重命名/移动操作，路径均相对于安装目录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenameOperation {
+    pub from: String,
+    pub to: String,
+}
+
+/// 可执行权限变更操作
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PermissionOperation {
+    pub path: String,
+    pub executable: bool,
+}
+
+/// 暂存区中已下载的更新包
+#[derive(Debug, Clone, Serialize)]
+pub struct StagedUpdatePackage {
+    pub path: String,
+    pub file_name: String,
+    pub size: u64,
+    /// 修改时间（Unix 毫秒时间戳）
+    pub modified: Option<u64>,
+}
+
+/// 更新包条目的变更类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateEntryAction {
+    /// 新增文件
+    Add,
+    /// 覆盖已有文件
+    Modify,
+    /// 删除文件（来自 changes.json 的 deleted）
+    Delete,
+}
+
+/// 更新包中的单个条目
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdatePreviewEntry {
+    pub path: String,
+    pub size: u64,
+    pub action: UpdateEntryAction,
+}
+
+/// 更新包预览结果
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdatePreview {
+    pub file_name: String,
+    /// 是否为增量包（包含 changes.json）
+    pub incremental: bool,
+    pub changes: Option<ChangesJson>,
+    pub entries: Vec<UpdatePreviewEntry>,
+    /// 解压后的总大小（字节）
+    pub total_size: u64,
+    /// 当前安装的 interface.json 版本
+    pub current_version: Option<String>,
+    /// 更新包内 interface.json 的版本
+    pub new_version: Option<String>,
+}
+
+/// 下载进度事件数据
+#[derive(Clone, Serialize)]
+pub struct DownloadProgressEvent {
+    pub session_id: u64,
+    pub downloaded_size: u64,
+    pub total_size: u64,
+    pub speed: u64,
+    pub progress: f64,
+}
+
+/// 下载结果
+#[derive(Clone, Serialize)]
+pub struct DownloadResult {
+    /// 下载会话 ID
+    pub session_id: u64,
+    /// 实际保存的文件路径（可能与请求的路径不同，如果从 URL 或 header 检测到正确的文件名）
+    pub actual_save_path: String,
+    /// 从 URL 或 Content-Disposition 提取的文件名（如果有）
+    pub detected_filename: Option<String>,
+}
+
+/// 已下载并校验的 MXU 程序更新
+#[derive(Debug, Clone, Serialize)]
+pub struct StagedAppUpdate {
+    /// 暂存的新版可执行文件路径（传给 app_update_apply）
+    pub exe_path: String,
+    /// 下载文件的 SHA-256
+    pub sha256: String,
+    /// 可执行文件大小（字节）
+    pub size: u64,
+}
+
+/// 系统信息结构
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SystemInfo {
+    pub os: String,
+    pub os_version: String,
+    pub arch: String,
+    pub tauri_version: String,
+}
+
+/// MaaFramework 全局选项的取值类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GlobalOptionKind {
+    Bool,
+    Int,
+    /// 日志级别：off / fatal / error / warn / info / debug / trace / all 或对应数值
+    LogLevel,
+    Path,
+}
+
+/// 可通过 maa_set_global_option 设置的全局选项说明
+#[derive(Debug, Clone, Serialize)]
+pub struct GlobalOptionInfo {
+    pub key: String,
+    pub kind: GlobalOptionKind,
+    pub description: String,
+    /// 当前 MaaFramework 版本已不再支持
+    pub deprecated: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitHubAsset {
+    pub name: String,
+    pub browser_download_url: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitHubRelease {
+    pub tag_name: String,
+    pub name: String,
+    pub body: Option<String>,
+    pub prerelease: bool,
+    pub assets: Vec<GitHubAsset>,
+}
+
+/// 文件搜索命中项
+#[derive(Debug, Clone, Serialize)]
+pub struct FileSearchMatch {
+    /// 文件完整路径
+    pub path: String,
+    /// 相对于搜索根目录的路径（统一使用 `/` 分隔）
+    pub relative_path: String,
+    /// 命中行号（从 1 开始），文件名命中时为 None
+    pub line: Option<usize>,
+    /// 命中行内容（已截断），文件名命中时为 None
+    pub preview: Option<String>,
+}
+
+/// 文件哈希计算进度事件数据
+#[derive(Clone, Serialize)]
+pub struct HashProgressEvent {
+    pub path: String,
+    pub processed_size: u64,
+    pub total_size: u64,
+    pub progress: f64,
+}
+
+/// 日志导出进度事件
+#[derive(Debug, Clone, Serialize)]
+pub struct LogExportProgressEvent {
+    /// 正在处理的压缩包内路径（完成时为空）
+    pub current: String,
+    pub processed: usize,
+    pub total: usize,
+    pub progress: f64,
+}
+
+/// 拖放安装包类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PackageKind {
+    /// 资源包（包含 interface.json 的 zip）
+    Resource,
+    /// MaaFramework 运行库包（包含 MaaFramework 动态库的 zip）
+    MaaFramework,
+    /// 配置档案（profile_export 导出的 JSON），导入为新档案
+    Profile,
+}
+
+/// 拖放到窗口上的安装包信息（通过 package-dropped 事件发送给前端确认）
+#[derive(Debug, Clone, Serialize)]
+pub struct DroppedPackage {
+    pub path: String,
+    pub file_name: String,
+    pub kind: PackageKind,
+    pub size: u64,
+}
+
+/// 安装包安装结果
+#[derive(Debug, Clone, Serialize)]
+pub struct PackageInstallResult {
+    pub kind: PackageKind,
+    /// 安装后的目标路径（配置档案为新档案的 ID）
+    pub target: String,
+    /// 是否需要重启 MXU 才能生效
+    pub requires_restart: bool,
+}
+
+/// 识别结果摘要（来自回调中的 reco_id）
+#[derive(Debug, Clone, Serialize)]
+pub struct RecognitionRecord {
+    pub reco_id: i64,
+    pub node_name: String,
+    /// 回调消息（Node.Recognition.Succeeded / Failed）
+    pub message: String,
+    pub hit: bool,
+    /// 记录时间（Unix 毫秒）
+    pub timestamp: i64,
+}
+
+/// 识别详情（用于前端“为何匹配/未匹配”调试面板）
+#[derive(Debug, Clone, Serialize)]
+pub struct RecognitionDetailInfo {
+    pub reco_id: i64,
+    pub node_name: String,
+    pub algorithm: String,
+    pub hit: bool,
+    /// 命中框 [x, y, width, height]
+    pub hit_box: [i32; 4],
+    /// 得分（从算法详情中提取，不存在时为 None）
+    pub score: Option<f64>,
+    /// 算法原始详情（JSON）
+    pub detail: serde_json::Value,
+    /// 调试绘制图（data URL 形式的 PNG）
+    pub draw_images: Vec<String>,
+}
+
+/// 识别结果订阅
+#[derive(Debug, Clone, Serialize)]
+pub struct RecognitionSubscription {
+    pub id: String,
+    /// 节点名模式（支持 `*` 与 `?` 通配符）
+    pub patterns: Vec<String>,
+    /// 只接收指定实例的结果，None 表示全部实例
+    pub instance_id: Option<String>,
+    /// 只接收命中的识别
+    pub hits_only: bool,
+    /// 附带命中区域的裁剪图
+    pub include_image: bool,
+    /// 创建时间（Unix 毫秒）
+    pub created_at: i64,
+}
+
+/// 推送给订阅者的识别结果（recognition-hit 事件）
+#[derive(Debug, Clone, Serialize)]
+pub struct RecognitionHit {
+    pub subscription_id: String,
+    pub instance_id: String,
+    pub reco_id: i64,
+    pub node_name: String,
+    pub algorithm: String,
+    pub hit: bool,
+    pub score: Option<f64>,
+    /// 命中框 [x, y, width, height]
+    pub hit_box: [i32; 4],
+    /// 命中区域裁剪图（data URL 形式的 PNG），未请求或未命中时为 None
+    pub image: Option<String>,
+    /// 识别完成时间（Unix 毫秒）
+    pub timestamp: i64,
+}
+
+/// 从截图保存的模板图片
+#[derive(Debug, Clone, Serialize)]
+pub struct SavedTemplate {
+    /// 模板图片绝对路径
+    pub path: String,
+    /// pipeline 中引用的模板路径（相对于资源 image 目录）
+    pub template: String,
+    /// 建议的 pipeline 节点片段
+    pub snippet: serde_json::Value,
+}
+
+/// 截图像素采样结果
+#[derive(Debug, Clone, Serialize)]
+pub struct PixelSample {
+    pub x: i32,
+    pub y: i32,
+    /// RGB 颜色值（与 ColorMatch 默认的 RGB 通道顺序一致）
+    pub rgb: [u8; 3],
+}
+
+/// 启动动作类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StartupActionKind {
+    /// 加载 MaaFramework 运行库
+    LoadLibrary,
+    /// 连接上次成功连接的设备
+    ConnectLastDevice,
+    /// 连接后截图一次，供前端预览使用
+    StartPreview,
+    /// 启用定时任务（由前端调度器执行）
+    ArmScheduler,
+    /// 检查更新（由前端更新服务执行）
+    CheckUpdates,
+}
+
+/// 启动动作配置项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupAction {
+    pub kind: StartupActionKind,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+/// 启动动作执行结果（通过 startup-action 事件发送）
+#[derive(Debug, Clone, Serialize)]
+pub struct StartupActionEvent {
+    pub kind: StartupActionKind,
+    /// done / skipped / failed / delegated（交由前端执行）
+    pub status: String,
+    pub message: Option<String>,
+}
+
+/// 旧版残留清理项
+#[derive(Debug, Clone, Serialize)]
+pub struct LegacyCleanupItem {
+    /// 类别：registry / schtask / config / cache / download / config_backup
+    pub category: String,
+    /// 路径或条目描述
+    pub target: String,
+    /// found（仅检测）/ migrated / removed / failed
+    pub action: String,
+    pub message: Option<String>,
+}
+
+/// 旧版残留清理报告
+#[derive(Debug, Clone, Serialize)]
+pub struct LegacyCleanupReport {
+    pub dry_run: bool,
+    pub items: Vec<LegacyCleanupItem>,
+}
+
+/// 推理执行后端
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InferenceProvider {
+    /// 由 MaaFramework 自动选择
+    #[default]
+    Auto,
+    Cpu,
+    DirectMl,
+    CoreMl,
+    Cuda,
+}
+
+/// 推理选项（OCR 与神经网络识别使用）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InferenceOptions {
+    #[serde(default)]
+    pub provider: InferenceProvider,
+    /// GPU 设备序号（DirectML / CUDA），为空时使用默认设备
+    #[serde(default)]
+    pub device_id: Option<i32>,
+    /// CPU 推理线程数，为空时使用默认值（重启后生效）
+    #[serde(default)]
+    pub cpu_threads: Option<u32>,
+}
+
+/// 可用的推理设备
+#[derive(Debug, Clone, Serialize)]
+pub struct InferenceDevice {
+    pub provider: InferenceProvider,
+    /// 设备序号（CPU 为 None）
+    pub device_id: Option<i32>,
+    pub name: String,
+    /// 显存大小（字节），未知时为 None
+    pub memory: Option<u64>,
+}
+
+/// 自适应阈值配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdaptiveThresholdConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 失败得分距历史最低命中分的最大差距，超出则不视为“勉强失败”
+    #[serde(default = "default_threshold_margin")]
+    pub margin: f64,
+    /// 相对历史最低命中分最多放宽的幅度
+    #[serde(default = "default_threshold_margin")]
+    pub max_relax: f64,
+    /// 节点累计多少次勉强失败后才自动放宽
+    #[serde(default = "default_min_near_misses")]
+    pub min_near_misses: u32,
+}
+
+fn default_threshold_margin() -> f64 {
+    0.05
+}
+
+fn default_min_near_misses() -> u32 {
+    2
+}
+
+impl Default for AdaptiveThresholdConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            margin: default_threshold_margin(),
+            max_relax: default_threshold_margin(),
+            min_near_misses: default_min_near_misses(),
+        }
+    }
+}
+
+/// 单个节点的识别得分统计
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NodeScoreStats {
+    pub hits: u32,
+    pub misses: u32,
+    /// 历史命中的最低得分
+    pub min_hit_score: Option<f64>,
+    /// 最近的失败得分（最多保留 20 个）
+    pub recent_miss_scores: Vec<f64>,
+    /// 勉强失败次数（得分接近历史最低命中分）
+    pub near_misses: u32,
+    /// 自动放宽阈值重试的次数
+    pub relaxed: u32,
+}
+
+/// 自动放宽阈值记录（通过 threshold-relaxed 事件发送）
+#[derive(Debug, Clone, Serialize)]
+pub struct ThresholdRelaxation {
+    pub node_name: String,
+    pub algorithm: String,
+    /// 本次失败得分
+    pub score: f64,
+    /// 历史最低命中分
+    pub baseline: f64,
+    /// 放宽后的阈值
+    pub threshold: f64,
+}
+
+/// 设备颜色校准结果：将设备截图颜色映射到参考颜色的仿射矩阵
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColorCalibration {
+    /// 每行对应输出 R/G/B：out = m[0]*r + m[1]*g + m[2]*b + m[3]（颜色归一化到 0~1）
+    pub matrix: [[f64; 4]; 3],
+    /// 参与拟合的像素数
+    pub samples: usize,
+    /// 校准前后的平均通道误差（0~255）
+    pub error_before: f64,
+    pub error_after: f64,
+    pub created_at: String,
+}
+
+/// 任务进度事件 schema 版本（字段有不兼容变更时递增）
+pub const TASK_PROGRESS_SCHEMA_VERSION: u32 = 1;
+
+/// 任务失败原因
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "code", rename_all = "snake_case")]
+pub enum TaskFailureReason {
+    /// 节点动作执行失败
+    ActionFailed { node: String },
+    /// 节点的 next 列表在超时前均未识别成功
+    NextListFailed { node: String },
+    /// 节点执行失败（识别或动作之外的原因）
+    NodeFailed { node: String },
+    /// 无法确定原因（如任务被停止）
+    Unknown,
+}
+
+/// 任务进度事件（通过 task-progress 事件发送）
+///
+/// 这里的 run 指一次 Tasker 任务（一个 entry）的执行，task_id 为 MaaFramework 任务 ID
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type")]
+pub enum TaskProgressKind {
+    RunStarted {
+        task_id: i64,
+        entry: String,
+    },
+    NodeRecognized {
+        task_id: i64,
+        reco_id: i64,
+        node: String,
+        hit: bool,
+    },
+    NodeActionDone {
+        task_id: i64,
+        action_id: i64,
+        node: String,
+        success: bool,
+    },
+    RunCompleted {
+        task_id: i64,
+        entry: String,
+    },
+    RunFailed {
+        task_id: i64,
+        entry: String,
+        reason: TaskFailureReason,
+    },
+}
+
+/// 任务进度事件信封
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskProgressEvent {
+    pub version: u32,
+    /// Unix 毫秒
+    pub timestamp: i64,
+    #[serde(flatten)]
+    pub event: TaskProgressKind,
+}
+
+/// Win32 截图方式探测结果
+#[derive(Debug, Clone, Serialize)]
+pub struct Win32ScreencapProbe {
+    /// 截图方式名称（与前端 Win32ScreencapMethodNames 一致）
+    pub method: String,
+    /// 截图方式位标志
+    pub value: u64,
+    pub supported: bool,
+    /// 截图耗时（毫秒）
+    pub elapsed_ms: Option<u64>,
+    /// 截图分辨率
+    pub resolution: Option<[u32; 2]>,
+    pub error: Option<String>,
+}
+
+/// Win32 截图方式探测报告
+#[derive(Debug, Clone, Serialize)]
+pub struct Win32ScreencapReport {
+    pub probes: Vec<Win32ScreencapProbe>,
+    /// 推荐使用的截图方式（可用方式中耗时最短的）
+    pub recommended: Option<String>,
+}
+
+/// 后端日志级别与按模块过滤配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogConfig {
+    /// 全局日志级别（off/error/warn/info/debug/trace）
+    #[serde(default = "default_log_level")]
+    pub level: String,
+    /// 按模块（target 前缀）覆盖的日志级别，最长前缀优先，如 {"maa_ffi": "warn"}
+    #[serde(default)]
+    pub module_filters: HashMap<String, String>,
+}
+
+fn default_log_level() -> String {
+    "debug".to_string()
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            level: default_log_level(),
+            module_filters: HashMap::new(),
+        }
+    }
+}
+
+/// 问题反馈草稿
+#[derive(Debug, Clone, Serialize)]
+pub struct FeedbackDraft {
+    pub title: String,
+    /// 预填的 issue 正文（Markdown）
+    pub body: String,
+    /// 带预填参数的新建 issue 链接
+    pub url: String,
+}
+
+/// 崩溃报告上传配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CrashReporterConfig {
+    /// 是否允许上传崩溃报告（默认关闭，需用户主动开启）
+    #[serde(default)]
+    pub upload_enabled: bool,
+    /// 用户配置的上传地址（POST JSON）
+    #[serde(default)]
+    pub endpoint: Option<String>,
+}
+
+/// 崩溃报告摘要
+#[derive(Debug, Clone, Serialize)]
+pub struct CrashReportInfo {
+    /// 报告文件名（debug/crashes 下）
+    pub name: String,
+    pub created_at: String,
+    /// panic 信息首行
+    pub summary: String,
+    /// 用户是否已查看
+    pub acknowledged: bool,
+    pub uploaded: bool,
+}
+
+/// WebView 看门狗配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebviewWatchdogConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// 超过该秒数未收到前端心跳视为无响应
+    #[serde(default = "default_webview_timeout_secs")]
+    pub timeout_secs: u64,
+    /// 无响应时是否自动重新加载窗口
+    #[serde(default)]
+    pub auto_restart: bool,
+}
+
+fn default_webview_timeout_secs() -> u64 {
+    30
+}
+
+impl Default for WebviewWatchdogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            timeout_secs: default_webview_timeout_secs(),
+            auto_restart: false,
+        }
+    }
+}
+
+/// 后端发送的心跳事件（mxu://heartbeat）
+#[derive(Debug, Clone, Serialize)]
+pub struct HeartbeatEvent {
+    pub seq: u64,
+    /// 后端状态版本号（每次回调事件递增），前端可据此发现丢失的事件
+    pub revision: u64,
+    /// 发送时间（Unix 毫秒）
+    pub sent_at: i64,
+}
+
+/// 心跳往返延迟统计
+#[derive(Debug, Clone, Serialize)]
+pub struct HeartbeatStats {
+    pub revision: u64,
+    /// 已发送 / 已确认的心跳数
+    pub sent: u64,
+    pub acked: u64,
+    /// 统计窗口内的往返延迟（毫秒）
+    pub last_rtt_ms: Option<f64>,
+    pub avg_rtt_ms: Option<f64>,
+    pub p95_rtt_ms: Option<f64>,
+    pub max_rtt_ms: Option<f64>,
+    /// 距上次确认的时间（毫秒），从未确认时为 None
+    pub last_ack_age_ms: Option<u64>,
+    /// 统计窗口内的延迟样本（按时间顺序，[Unix 毫秒, 延迟毫秒]）
+    pub samples: Vec<(i64, f64)>,
+}
+
+/// 被监控进程的角色
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MonitoredRole {
+    /// MXU 自身
+    App,
+    /// Agent 子进程
+    Agent,
+    /// 目标模拟器/游戏进程
+    Target,
+}
+
+/// 单个进程的资源占用
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessMetric {
+    pub role: MonitoredRole,
+    pub instance_id: Option<String>,
+    pub pid: u32,
+    pub name: String,
+    /// CPU 占用（占全部核心的百分比，0-100）
+    pub cpu_percent: f32,
+    /// 常驻内存（字节）
+    pub memory_bytes: u64,
+}
+
+/// 资源占用快照（process-metrics 事件与 get_process_metrics 返回）
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessMetricsSnapshot {
+    /// 采样时间（Unix 毫秒）
+    pub timestamp: i64,
+    pub system_memory_total: u64,
+    pub system_memory_used: u64,
+    pub processes: Vec<ProcessMetric>,
+}
+
+/// 检测到设备冲突时的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceLockMode {
+    /// 不检测（仍写入设备锁供其他工具识别）
+    Off,
+    /// 发送提示后照常运行
+    Warn,
+    /// 拒绝运行
+    #[default]
+    Refuse,
+    /// 等待设备空闲后运行
+    Queue,
+}
+
+/// 与其他自动化工具的设备互斥策略（config/device_lock.json）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceLockPolicy {
+    #[serde(default)]
+    pub mode: DeviceLockMode,
+    /// 排队模式下的最长等待时间（秒），0 表示不限
+    #[serde(default = "default_device_lock_max_wait_secs")]
+    pub max_wait_secs: u64,
+    /// 额外识别的工具进程名
+    #[serde(default)]
+    pub extra_tools: Vec<String>,
+}
+
+fn default_device_lock_max_wait_secs() -> u64 {
+    1800
+}
+
+impl Default for DeviceLockPolicy {
+    fn default() -> Self {
+        Self {
+            mode: DeviceLockMode::default(),
+            max_wait_secs: default_device_lock_max_wait_secs(),
+            extra_tools: Vec::new(),
+        }
+    }
+}
+
+/// 设备锁文件内容（<临时目录>/maa-device-locks/<设备>.lock，各工具共用的约定）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceLockInfo {
+    /// 持有锁的工具名称
+    pub tool: String,
+    pub pid: u32,
+    pub device: String,
+    /// RFC 3339 时间
+    pub acquired_at: String,
+}
+
+/// 检测到的设备冲突
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceConflict {
+    /// 检测来源：lock_file / adb_client / tool_process
+    pub source: String,
+    pub tool: String,
+    pub pid: u32,
+    pub detail: String,
+    /// 是否确定在控制同一设备（否则仅为提示）
+    pub blocking: bool,
+}
+
+/// 设备冲突的处理结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceConflictAction {
+    Warned,
+    Refused,
+    Waiting,
+    Resolved,
+    TimedOut,
+    Cancelled,
+}
+
+/// 设备冲突事件（device-conflict）
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceConflictEvent {
+    pub instance_id: String,
+    pub device: String,
+    pub action: DeviceConflictAction,
+    pub conflicts: Vec<DeviceConflict>,
+}
+
+/// 用户空闲策略：用户正在使用电脑时推迟任务
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdlePolicy {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 用户无输入超过该秒数视为空闲
+    #[serde(default = "default_idle_secs")]
+    pub idle_secs: u64,
+    /// 最长等待秒数，超过后仍开始任务（0 表示一直等待）
+    #[serde(default)]
+    pub max_wait_secs: u64,
+    /// 仅对 Win32 控制器生效（ADB 等控制器不会占用鼠标）
+    #[serde(default = "default_true")]
+    pub win32_only: bool,
+}
+
+fn default_idle_secs() -> u64 {
+    60
+}
+
+impl Default for IdlePolicy {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            idle_secs: default_idle_secs(),
+            max_wait_secs: 0,
+            win32_only: true,
+        }
+    }
+}
+
+/// 游戏客户端更新检测配置（按资源包配置）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameUpdateCheck {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// 识别「需要更新」界面的 pipeline 节点名，命中即视为需要更新
+    #[serde(default)]
+    pub update_node: Option<String>,
+    /// 版本号 OCR 检查，识别结果不匹配时视为需要更新
+    #[serde(default)]
+    pub version_ocr: Option<VersionOcrCheck>,
+    /// 检测到需要更新后，多少分钟后通知前端重试（为空时不重试）
+    #[serde(default)]
+    pub retry_after_minutes: Option<u64>,
+}
+
+/// 版本号 OCR 检查
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionOcrCheck {
+    /// 版本号所在区域 [x, y, w, h]
+    pub roi: [i32; 4],
+    /// 受支持版本的正则表达式
+    pub expected: String,
+}
+
+/// 游戏客户端需要更新事件（game-update-required）
+#[derive(Debug, Clone, Serialize)]
+pub struct GameUpdateRequiredEvent {
+    pub instance_id: String,
+    pub reason: String,
+    /// 建议重试时间（Unix 毫秒），到时会发送 game-update-retry 事件
+    pub retry_at: Option<i64>,
+}
+
+/// 任务运行期间的防休眠设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PowerSettings {
+    /// 任务运行期间阻止系统休眠
+    #[serde(default = "default_true")]
+    pub prevent_sleep: bool,
+    /// 同时保持显示器常亮（Win32 截图在息屏后可能失败）
+    #[serde(default)]
+    pub keep_display_on: bool,
+}
+
+impl Default for PowerSettings {
+    fn default() -> Self {
+        Self {
+            prevent_sleep: true,
+            keep_display_on: false,
+        }
+    }
+}
+
+/// MaaFramework 调用超时配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfiTimeoutConfig {
+    /// 同步调用的默认超时（秒）
+    #[serde(default = "default_ffi_timeout_secs")]
+    pub default_timeout_secs: u64,
+    /// 按操作名覆盖的超时（秒），如 {"post_stop": 10}
+    #[serde(default)]
+    pub per_operation: HashMap<String, u64>,
+    /// 调用超过该秒数仍未返回时视为疑似死锁，标记实例异常
+    #[serde(default = "default_ffi_stuck_secs")]
+    pub stuck_threshold_secs: u64,
+}
+
+fn default_ffi_timeout_secs() -> u64 {
+    15
+}
+
+fn default_ffi_stuck_secs() -> u64 {
+    60
+}
+
+impl Default for FfiTimeoutConfig {
+    fn default() -> Self {
+        Self {
+            default_timeout_secs: default_ffi_timeout_secs(),
+            per_operation: HashMap::new(),
+            stuck_threshold_secs: default_ffi_stuck_secs(),
+        }
+    }
+}
+
+/// 未返回的 MaaFramework 调用
+#[derive(Debug, Clone, Serialize)]
+pub struct StuckCall {
+    pub operation: String,
+    pub elapsed_secs: u64,
+    pub thread: String,
+}
+
+/// 实例健康状态（instance-unhealthy 事件与 get_instance_health 返回）
+#[derive(Debug, Clone, Serialize)]
+pub struct InstanceHealth {
+    pub instance_id: String,
+    pub healthy: bool,
+    /// 异常原因
+    pub reason: Option<String>,
+    pub stuck_calls: Vec<StuckCall>,
+}
+
+/// 定时唤醒设置（config/wake_timer.json）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WakeTimerSettings {
+    /// 在定时执行前唤醒睡眠中的电脑（仅 Windows）
+    #[serde(default)]
+    pub enabled: bool,
+    /// 提前唤醒的分钟数（留出系统恢复与网络连接的时间）
+    #[serde(default = "default_wake_lead_minutes")]
+    pub lead_minutes: u32,
+    /// 由定时唤醒触发的运行结束后重新进入睡眠
+    #[serde(default)]
+    pub resleep: bool,
+}
+
+fn default_wake_lead_minutes() -> u32 {
+    2
+}
+
+impl Default for WakeTimerSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            lead_minutes: default_wake_lead_minutes(),
+            resleep: false,
+        }
+    }
+}
+
+/// 定时执行时间点（由前端定时策略同步，与 SchedulePolicy 字段一致）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WakeScheduleSlot {
+    /// 重复日期 (0-6, 0=周日)
+    pub weekdays: Vec<u32>,
+    /// 开始时间 (0-23)
+    pub hours: Vec<u32>,
+}
+
+/// 定时唤醒状态
+#[derive(Debug, Clone, Serialize)]
+pub struct WakeTimerStatus {
+    /// 是否已设置系统唤醒计时器
+    pub armed: bool,
+    /// 下次唤醒时间（本地时间，RFC 3339）
+    pub next_wake: Option<String>,
+    /// 对应的定时执行时间（本地时间，RFC 3339）
+    pub next_run: Option<String>,
+    /// 当前是否处于定时唤醒后等待运行/运行中的状态
+    pub woke_for_run: bool,
+}
+
+/// 后台模式设置（config/background_mode.json）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackgroundModeSettings {
+    /// 主窗口隐藏到托盘时启用后台模式
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// 后台模式下合并发送任务回调与进度事件的间隔（秒）
+    #[serde(default = "default_background_batch_secs")]
+    pub batch_interval_secs: u64,
+    /// 隐藏后挂起 WebView 进程（仅 Windows WebView2），窗口显示时恢复
+    #[serde(default)]
+    pub suspend_webview: bool,
+}
+
+fn default_background_batch_secs() -> u64 {
+    3
+}
+
+impl Default for BackgroundModeSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            batch_interval_secs: default_background_batch_secs(),
+            suspend_webview: false,
+        }
+    }
+}
+
+/// 后台模式下合并发送的事件
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchedEvent {
+    pub event: String,
+    pub payload: serde_json::Value,
+}
+
+/// 截图打码方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RedactionStyle {
+    /// 纯色填充
+    #[default]
+    Fill,
+    /// 马赛克
+    Pixelate,
+}
+
+/// 截图打码配置（config/screenshot_redaction.json）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RedactionConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 按资源包目录名配置的打码区域 [x, y, width, height]（720p 坐标），键 "*" 对所有资源生效
+    #[serde(default)]
+    pub regions: HashMap<String, Vec<[i32; 4]>>,
+    /// 账号名 / UID：运行中 OCR 识别到包含这些文本的区域会被自动打码
+    #[serde(default)]
+    pub account_names: Vec<String>,
+    #[serde(default)]
+    pub style: RedactionStyle,
+}
+
+/// 通知按钮对应的操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToastAction {
+    /// 打开 MXU 主窗口
+    Open,
+    /// 停止任务
+    StopTasks,
+    /// 查看通知附带的截图
+    ViewScreenshot,
+}
+
+/// 可绑定全局快捷键的操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HotkeyAction {
+    /// 开始任务（由前端执行，复用工具栏启动逻辑）
+    StartTasks,
+    /// 停止任务（由前端执行）
+    StopTasks,
+    /// 显示/隐藏主窗口
+    ToggleWindow,
+    /// 保存所有已连接实例的截图
+    CaptureScreenshot,
+}
+
+/// 全局快捷键配置（config/hotkeys.json）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HotkeyConfig {
+    /// 是否启用全局快捷键
+    #[serde(default)]
+    pub enabled: bool,
+    /// 操作到快捷键的绑定，如 "Ctrl+Shift+F10"
+    #[serde(default)]
+    pub bindings: HashMap<HotkeyAction, String>,
+}
+
+/// 运行历史记录（runs/history.jsonl 中的一行，运行结束时追加）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunHistoryEntry {
+    pub run_id: String,
+    pub instance_id: String,
+    pub started_at: String,
+    pub finished_at: String,
+    /// 与 run-completed 事件的 status 一致
+    pub status: String,
+    /// 与 run-completed 事件的 reason 一致
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    pub task_count: usize,
+    /// 失败任务的入口名
+    #[serde(default)]
+    pub failed_tasks: Vec<String>,
+    /// 运行结束时的数值型任务变量（如 custom action 记录的掉落数量）
+    #[serde(default)]
+    pub counters: BTreeMap<String, f64>,
+}
+
+/// 周报发送渠道
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DigestChannel {
+    /// 系统通知
+    System,
+    /// 以 JSON POST 到 Webhook（可对接邮件转发、IM 机器人等服务）
+    Webhook { url: String },
+}
+
+/// 周报配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DigestConfig {
+    pub id: String,
+    pub name: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// 发送日期 (0-6, 0=周日)
+    pub weekday: u32,
+    /// 发送时间 (0-23)
+    pub hour: u32,
+    #[serde(default)]
+    pub minute: u32,
+    pub channels: Vec<DigestChannel>,
+    /// 只统计这些实例，为空时统计全部
+    #[serde(default)]
+    pub instances: Vec<String>,
+    /// 上次发送时间（RFC 3339）
+    #[serde(default)]
+    pub last_sent: Option<String>,
+}
+
+/// 周报统计结果
+#[derive(Debug, Clone, Serialize)]
+pub struct DigestSummary {
+    /// 统计区间（RFC 3339）
+    pub from: String,
+    pub to: String,
+    pub runs: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    /// 成功率 (0-1)，没有运行时为 0
+    pub success_rate: f64,
+    pub task_count: usize,
+    /// 数值型任务变量之和
+    pub counters: BTreeMap<String, f64>,
+    /// 按任务入口统计的失败次数
+    pub failures: BTreeMap<String, usize>,
+    /// 格式化后的消息标题与正文
+    pub title: String,
+    pub body: String,
+}
+
+/// MXU 内置动作测试调用结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ActionTestResult {
+    /// 完整动作名（如 MXU_WEBHOOK_ACTION）
+    pub action: String,
+    /// 动作返回值
+    pub success: bool,
+    /// 是否只校验了参数而未实际执行
+    pub dry_run: bool,
+    /// 执行过程中的说明与警告
+    pub notes: Vec<String>,
+}
+
+/// 内置动作参数说明
+#[derive(Debug, Clone, Serialize)]
+pub struct ActionParamSchema {
+    pub name: String,
+    /// 参数类型：string / integer / boolean / array / any
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub required: bool,
+    /// 未填写时使用的值
+    pub default: Option<serde_json::Value>,
+}
+
+/// 能力清单中的内置动作
+#[derive(Debug, Clone, Serialize)]
+pub struct ActionCapability {
+    /// 完整动作名（如 MXU_WEBHOOK_ACTION）
+    pub name: String,
+    pub params: Vec<ActionParamSchema>,
+    /// mxu_action_test 未指定 dry_run 时是否只校验参数
+    pub dry_run_by_default: bool,
+    /// mxu_action_test 是否始终只校验参数
+    pub always_dry_run: bool,
+}
+
+/// 当前构建支持的功能清单（供资源与前端按功能检测，而非按版本号猜测）
+#[derive(Debug, Clone, Serialize)]
+pub struct Capabilities {
+    /// 清单格式版本，字段有不兼容变化时递增
+    pub schema_version: u32,
+    /// MXU 版本
+    pub version: String,
+    /// MaaFramework 版本，库未加载时为 None
+    pub maafw_version: Option<String>,
+    pub os: String,
+    pub arch: String,
+    /// 已注册的内置 custom actions
+    pub custom_actions: Vec<ActionCapability>,
+    /// 已注册的内置 custom recognitions
+    pub custom_recognitions: Vec<String>,
+    /// 可用的控制器类型（与 ControllerConfig 的 type 一致）
+    pub controllers: Vec<String>,
+    /// 可用的通知渠道
+    pub notification_channels: Vec<String>,
+    /// 远程 API 服务（api_server）是否正在运行
+    pub api_server: bool,
+    /// 平台相关功能开关
+    pub features: BTreeMap<String, bool>,
+}
+
+/// 停止方式（maa_stop_task 的 mode 参数）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StopMode {
+    /// 当前任务结束后停止并执行收尾任务（仅队列模式，直接提交时同 soft）
+    Graceful,
+    /// 在下一个停止检查点中断（节点之间、MXU_SLEEP 等待中）
+    #[default]
+    Soft,
+    /// 请求停止并立即结束全部 Agent 进程
+    Hard,
+}
+
+/// 停止进度阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StopStage {
+    /// 已发起停止
+    Requested,
+    /// 超时未停止，已升级为更强的停止方式
+    Escalated,
+    /// 正在执行收尾任务
+    Cleanup,
+    /// 已停止
+    Completed,
+    /// 强制停止后仍未停止
+    TimedOut,
+}
+
+/// 停止进度事件（stop-progress）
+#[derive(Debug, Clone, Serialize)]
+pub struct StopProgressEvent {
+    pub instance_id: String,
+    /// 当前生效的停止方式
+    pub mode: StopMode,
+    pub stage: StopStage,
+    /// 自发起停止以来的时间（毫秒）
+    pub elapsed_ms: u64,
+}
+
+/// 停止设置（config/stop.json）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StopSettings {
+    /// graceful 停止的超时时间（秒），超时后升级为 soft
+    #[serde(default = "default_graceful_timeout_secs")]
+    pub graceful_timeout_secs: u64,
+    /// soft 停止的超时时间（秒），超时后升级为 hard
+    #[serde(default = "default_soft_timeout_secs")]
+    pub soft_timeout_secs: u64,
+    /// graceful 停止后执行的收尾任务
+    #[serde(default)]
+    pub cleanup_tasks: Vec<TaskConfig>,
+}
+
+fn default_graceful_timeout_secs() -> u64 {
+    600
+}
+
+fn default_soft_timeout_secs() -> u64 {
+    30
+}
+
+impl Default for StopSettings {
+    fn default() -> Self {
+        Self {
+            graceful_timeout_secs: default_graceful_timeout_secs(),
+            soft_timeout_secs: default_soft_timeout_secs(),
+            cleanup_tasks: Vec::new(),
+        }
+    }
+}
+
+/// 任务暂停状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskPauseState {
+    /// 已请求暂停，等待当前节点结束
+    Pending,
+    /// 已停在两个节点之间
+    Paused,
+    /// 已恢复执行
+    Resumed,
+}
+
+/// 任务暂停事件（task-pause）
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskPauseEvent {
+    pub instance_id: String,
+    pub state: TaskPauseState,
+    /// 暂停所在的节点（刚执行完动作的节点）
+    pub node: Option<String>,
+}
+
+/// interface.json（MaaFramework ProjectInterface V2）
+///
+/// 只声明后端解析与校验用到的字段，其余字段（label、icon、adb 等）原样保留在 extra 中返回前端
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectInterface {
+    pub interface_version: u32,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub languages: BTreeMap<String, String>,
+    #[serde(default)]
+    pub controller: Vec<InterfaceController>,
+    #[serde(default)]
+    pub resource: Vec<InterfaceResource>,
+    #[serde(default)]
+    pub task: Vec<InterfaceTask>,
+    #[serde(default)]
+    pub option: BTreeMap<String, InterfaceOption>,
+    /// 导入的其他 PI 文件（只导入 task 与 option）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub import: Vec<String>,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+/// interface.json 中的 Agent 定义（agent 字段可为单个对象或数组）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterfaceAgent {
+    pub child_exec: String,
+    #[serde(default)]
+    pub child_args: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub identifier: Option<String>,
+    /// 连接超时（毫秒），-1 表示无限等待
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<i64>,
+}
+
+/// interface.json 中的控制器
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterfaceController {
+    pub name: String,
+    /// Adb / Win32 / PlayCover / Gamepad
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+/// interface.json 中的资源
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterfaceResource {
+    pub name: String,
+    #[serde(default)]
+    pub path: Vec<String>,
+    /// 适用的控制器，None 表示全部
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub controller: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub option: Vec<String>,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+/// interface.json 中的任务
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterfaceTask {
+    pub name: String,
+    #[serde(default)]
+    pub entry: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_check: Option<bool>,
+    /// 适用的资源，None 表示全部
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource: Option<Vec<String>>,
+    /// 适用的控制器，None 表示全部
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub controller: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub option: Vec<String>,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+/// 选项类型（省略时为 select）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InterfaceOptionType {
+    #[default]
+    Select,
+    Input,
+    Switch,
+}
+
+/// interface.json 中的选项定义
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterfaceOption {
+    #[serde(rename = "type", default)]
+    pub kind: InterfaceOptionType,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cases: Vec<InterfaceCase>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_case: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inputs: Vec<InterfaceInput>,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+/// 选项的 case
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterfaceCase {
+    pub name: String,
+    /// 选中该 case 时显示的子选项
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub option: Vec<String>,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+/// 输入型选项的输入项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterfaceInput {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    /// 输入值的校验正则
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verify: Option<String>,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+/// interface_load 的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct InterfaceLoadResult {
+    /// 已合并 import 并按平台过滤控制器后的 interface
+    pub interface: ProjectInterface,
+    /// 标准化后的 Agent 列表
+    pub agents: Vec<InterfaceAgent>,
+    /// 各语言的翻译表
+    pub translations: BTreeMap<String, BTreeMap<String, String>>,
+    /// interface.json 所在目录的绝对路径
+    pub base_path: String,
+    /// 校验发现的问题（不影响加载）
+    pub warnings: Vec<String>,
+}
+
+/// interface_get_tasks 返回的任务
+#[derive(Debug, Clone, Serialize)]
+pub struct InterfaceTaskInfo {
+    pub task: InterfaceTask,
+    /// 是否适用于指定的控制器与资源
+    pub compatible: bool,
+    /// 全部选项（含默认 case 的子选项）的默认值，格式与前端 OptionValue 一致
+    pub option_defaults: BTreeMap<String, serde_json::Value>,
+}
+
+/// 选项组合中生效的单个选项
+#[derive(Debug, Clone, Serialize)]
+pub struct OptionResolution {
+    pub key: String,
+    /// 所属的上级选项（作为其选中 case 的子选项生效），顶层选项为 None
+    pub parent: Option<String>,
+    /// 实际使用的值（格式与前端 OptionValue 一致）
+    pub value: serde_json::Value,
+    /// 选中的 case（输入型选项为 None）
+    pub selected_case: Option<String>,
+    /// 是否适用于指定的控制器
+    pub compatible: bool,
+}
+
+/// interface_resolve_options 的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskOptionResolution {
+    pub task: String,
+    pub entry: String,
+    /// 按顺序依次覆盖的 pipeline_override（JSON 数组字符串）
+    pub pipeline_override: String,
+    /// 生效的选项（按求值顺序）
+    pub options: Vec<OptionResolution>,
+    /// 提供了值但未生效的选项
+    pub inactive: Vec<String>,
+    /// 求值中发现的问题
+    pub issues: Vec<String>,
+}
+
+/// 环境诊断项的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticStatus {
+    Ok,
+    /// 可能影响部分功能，但不阻止使用
+    Warning,
+    /// 需要用户处理才能正常使用
+    Error,
+    /// 当前平台不适用
+    Skipped,
+}
+
+/// 单项环境诊断
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosticCheck {
+    /// 检查项标识（vcredist / maafw / adb / disk_space / exe_path / missing_files / write_permission）
+    pub id: String,
+    pub status: DiagnosticStatus,
+    /// 检查结果说明
+    pub message: String,
+    /// 相关的路径、文件列表等附加信息
+    pub details: Vec<String>,
+}
+
+/// run_diagnostics 的结果，按检查顺序排列，前端据此渲染首次运行检查清单
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosticReport {
+    pub checks: Vec<DiagnosticCheck>,
+    /// 是否存在 Error 级别的检查项
+    pub has_errors: bool,
+}
+
+/// 数据目录重定向文件（默认数据目录下的 data_dir.json）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataDirRedirect {
+    pub path: String,
+}
+
+/// 数据目录信息
+#[derive(Debug, Clone, Serialize)]
+pub struct DataDirInfo {
+    /// 本次运行使用的数据目录
+    pub current: String,
+    /// 默认数据目录（Windows/Linux 为 exe 目录，即便携模式）
+    pub default: String,
+    /// 是否通过重定向文件使用了其他目录
+    pub redirected: bool,
+    /// 系统用户数据目录（Windows 为 %APPDATA%\MXU），macOS 默认已使用系统目录，为 None
+    pub system_dir: Option<String>,
+    /// 重启后将使用的数据目录（已修改但未重启时与 current 不同）
+    pub pending: Option<String>,
+}
+
+/// set_data_dir 的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct DataDirMigrationResult {
+    /// 新的数据目录
+    pub path: String,
+    /// 已复制的条目（config、profiles 等）
+    pub copied: Vec<String>,
+    /// 复制的总字节数
+    pub bytes: u64,
+    /// 需要重启后生效
+    pub requires_restart: bool,
+}
+
+/// 安装完整性清单（exe 目录下的 install_manifest.json），路径相对于 exe 目录并使用 / 分隔
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstallManifest {
+    pub schema_version: u32,
+    /// 清单对应的版本
+    #[serde(default)]
+    pub version: Option<String>,
+    /// 单个文件的下载地址前缀（拼接相对路径），为空时无法自动修复
+    #[serde(default)]
+    pub base_url: Option<String>,
+    pub files: BTreeMap<String, InstallManifestEntry>,
+}
+
+/// 清单中的单个文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstallManifestEntry {
+    pub sha256: String,
+    pub size: u64,
+}
+
+/// verify_install 的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct InstallVerifyReport {
+    pub version: Option<String>,
+    /// 检查的文件数
+    pub checked: usize,
+    /// 缺失的文件（常见于被杀毒软件删除）
+    pub missing: Vec<String>,
+    /// 大小或哈希不一致的文件
+    pub corrupted: Vec<String>,
+}
+
+/// repair_install 的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct InstallRepairResult {
+    /// 已重新下载并替换的文件
+    pub repaired: Vec<String>,
+    /// 修复失败的文件及原因
+    pub failed: Vec<InstallRepairFailure>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InstallRepairFailure {
+    pub path: String,
+    pub error: String,
+}
+
+/// 修复进度事件（install-repair-progress）
+#[derive(Debug, Clone, Serialize)]
+pub struct InstallRepairProgressEvent {
+    /// 当前文件序号（从 1 开始）
+    pub current: usize,
+    pub total: usize,
+    pub path: String,
+}
+
+/// vcredist_install 的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct VcredistInstallResult {
+    /// 安装程序退出码（0 成功、3010 需要重启、1638 已安装更新版本）
+    pub exit_code: u32,
+    pub reboot_required: bool,
+    /// 重新加载成功时的 MaaFramework 版本
+    pub maafw_version: Option<String>,
+    /// 安装完成但 MaaFramework 仍无法加载时的错误信息
+    pub load_error: Option<String>,
+}
+
+/// maafw_versions/active.json 的内容
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MaafwActiveVersion {
+    /// 当前使用的版本名，为空时使用随程序分发的版本
+    pub version: Option<String>,
+}
+
+/// 已安装的 MaaFramework 版本
+#[derive(Debug, Clone, Serialize)]
+pub struct MaafwVersionInfo {
+    /// 版本名，随程序分发的版本（maafw 目录）为 None
+    pub name: Option<String>,
+    pub path: String,
+    /// 占用空间（字节）
+    pub size: u64,
+    /// 是否为当前选择的版本
+    pub active: bool,
+}
+
+/// maafw_version_list 的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct MaafwVersionList {
+    /// 当前选择的版本名
+    pub active: Option<String>,
+    /// 已加载的 MaaFramework 版本号
+    pub loaded_version: Option<String>,
+    pub versions: Vec<MaafwVersionInfo>,
+}
+
+/// 切换 MaaFramework 版本的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct MaafwSwitchResult {
+    pub active: Option<String>,
+    /// 已加载的 MaaFramework 版本号
+    pub loaded_version: Option<String>,
+    /// 已加载其他版本，需要重启 MXU 才能生效
+    pub requires_restart: bool,
+}
+
+/// reload_maa_library 的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct MaaReloadResult {
+    /// 新加载的 MaaFramework 版本号（重启时为空）
+    pub version: Option<String>,
+    /// 已销毁的实例数，前端需重新创建实例
+    pub destroyed_instances: usize,
+    /// 进程内已加载过 MaaFramework，MXU 即将重启以使用新文件
+    pub restarting: bool,
+}
+
+/// 找到的 adb 可执行文件及校验结果
+#[derive(Debug, Clone, Serialize)]
+pub struct AdbBinaryInfo {
+    pub path: String,
+    /// 来源：bundled（MXU 自带 platform-tools）/ path（PATH 环境变量）/ device（设备搜索结果）
+    pub source: String,
+    /// `adb version` 能否正常运行
+    pub valid: bool,
+    /// adb 版本号（如 1.0.41）
+    pub version: Option<String>,
+    pub error: Option<String>,
+}
+
+/// 正在运行的 adb 进程
+#[derive(Debug, Clone, Serialize)]
+pub struct AdbServerProcess {
+    pub pid: u32,
+    pub path: Option<String>,
+    pub version: Option<String>,
+}
+
+/// adb_detect_conflicts 的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct AdbConflictReport {
+    pub processes: Vec<AdbServerProcess>,
+    /// 调用方指定的 adb 的版本
+    pub client_version: Option<String>,
+    /// 存在多个不同版本的 adb
+    pub conflict: bool,
+    pub message: Option<String>,
+}
+
+/// 控制器截图或输入方式
+#[derive(Debug, Clone, Serialize)]
+pub struct ControllerMethodOption {
+    pub name: String,
+    /// 位标志
+    pub value: u64,
+}
+
+/// MaaFramework 支持的 Win32 截图与输入方式
+#[derive(Debug, Clone, Serialize)]
+pub struct Win32MethodList {
+    pub screencap: Vec<ControllerMethodOption>,
+    pub input: Vec<ControllerMethodOption>,
+}
+
+/// 截图方式与输入方式组合的探测结果
+#[derive(Debug, Clone, Serialize)]
+pub struct Win32MethodCombo {
+    pub screencap: String,
+    pub screencap_value: u64,
+    pub input: String,
+    pub input_value: u64,
+    pub supported: bool,
+    /// 截图耗时（毫秒）
+    pub elapsed_ms: Option<u64>,
+    pub error: Option<String>,
+}
+
+/// probe_win32_methods 的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct Win32MethodProbeReport {
+    /// 各截图方式（默认输入方式下）的探测结果
+    pub screencap: Vec<Win32ScreencapProbe>,
+    /// 最快截图方式与各输入方式的组合
+    pub combinations: Vec<Win32MethodCombo>,
+    pub recommended_screencap: Option<String>,
+    pub recommended_input: Option<String>,
+}
+
+/// MaaFramework 支持的 ADB 截图与输入方式
+#[derive(Debug, Clone, Serialize)]
+pub struct AdbMethodList {
+    pub screencap: Vec<ControllerMethodOption>,
+    pub input: Vec<ControllerMethodOption>,
+}
+
+/// 单个 ADB 方式的测量结果
+#[derive(Debug, Clone, Serialize)]
+pub struct AdbMethodBenchmark {
+    /// screencap / input
+    pub kind: String,
+    pub method: String,
+    /// 位标志
+    pub value: u64,
+    pub supported: bool,
+    /// 连接耗时（毫秒）
+    pub connect_ms: Option<u64>,
+    /// 单次操作的平均耗时（毫秒）
+    pub average_ms: Option<u64>,
+    pub error: Option<String>,
+}
+
+/// benchmark_adb_methods 的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct AdbMethodBenchmarkReport {
+    pub screencap: Vec<AdbMethodBenchmark>,
+    pub input: Vec<AdbMethodBenchmark>,
+    /// 每种方式的测量轮数
+    pub rounds: u32,
+    pub recommended_screencap: Option<String>,
+    pub recommended_input: Option<String>,
+}
+
+/// 设备分辨率问题
+#[derive(Debug, Clone, Serialize)]
+pub struct ResolutionIssue {
+    /// aspect_ratio / low_resolution / non_integer_scale
+    pub kind: String,
+    /// warning（基本会导致识别失败）/ info（可能影响识别稳定性）
+    pub severity: String,
+    pub message: String,
+}
+
+/// 设备分辨率检查结果（resolution-warning 事件）
+#[derive(Debug, Clone, Serialize)]
+pub struct ResolutionCheckResult {
+    pub instance_id: String,
+    pub width: u32,
+    pub height: u32,
+    pub expected_width: u32,
+    pub expected_height: u32,
+    /// 基准分辨率来源：controller / interface / default
+    pub expected_source: String,
+    pub issues: Vec<ResolutionIssue>,
+}
+
+/// 脚本运行结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ScriptRunResult {
+    /// 执行成功且未返回 false
+    pub success: bool,
+    /// 脚本最后一个表达式的值
+    pub value: Option<serde_json::Value>,
+    /// print 输出
+    pub logs: Vec<String>,
+    pub error: Option<String>,
+    pub elapsed_ms: u64,
+}
+
+/// 识别到的二维码
+#[derive(Debug, Clone, Serialize)]
+pub struct QrCodeResult {
+    pub content: String,
+    /// 外接矩形 [x, y, width, height]
+    pub rect: [i32; 4],
+}
+
+/// qrcode-detected 事件
+#[derive(Debug, Clone, Serialize)]
+pub struct QrCodeDetected {
+    pub instance_id: String,
+    /// 写入的任务变量名
+    pub variable: Option<String>,
+    pub content: String,
+    pub rect: [i32; 4],
+}
+
+/// 深度链接设置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeepLinkSettings {
+    /// 单实例：再次启动时转发链接并聚焦已运行的窗口（重启后生效）。
+    /// 所有基于 MXU 的项目共用同一应用标识，开启后不同项目无法同时运行
+    #[serde(default)]
+    pub single_instance: bool,
+    /// 为当前用户注册 mxu:// 协议（便携版使用，安装版由安装程序注册）
+    #[serde(default)]
+    pub register_scheme: bool,
+}
+
+/// 远程 API 服务设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiServerSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_api_server_port")]
+    pub port: u16,
+}
+
+fn default_api_server_port() -> u16 {
+    18080
+}
+
+impl Default for ApiServerSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: default_api_server_port(),
+        }
+    }
+}
+
+/// 远程 API 服务运行状态
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiServerStatus {
+    pub running: bool,
+    pub port: Option<u16>,
+    /// 最近一次启动失败的原因
+    pub error: Option<String>,
 }
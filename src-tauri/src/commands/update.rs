@@ -4,22 +4,58 @@
 
 use log::{info, warn};
 
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use tauri::State;
+
+use super::error::MxuError;
 use super::file_ops::get_exe_dir;
-use super::types::ChangesJson;
+use super::i18n::ErrorCode;
+use super::types::{
+    ChangesJson, MaaState, PermissionOperation, RenameOperation, StagedUpdatePackage,
+    UpdateEntryAction, UpdatePreview, UpdatePreviewEntry, CHANGES_SCHEMA_VERSION,
+};
+use super::utils::{ensure_free_space, get_app_data_dir, normalize_path, path_size};
 
 /// 解压压缩文件到指定目录，支持 zip 和 tar.gz/tgz 格式
 #[tauri::command]
-pub fn extract_zip(zip_path: String, dest_dir: String) -> Result<(), String> {
+pub fn extract_zip(zip_path: String, dest_dir: String) -> Result<(), MxuError> {
     info!("extract_zip called: {} -> {}", zip_path, dest_dir);
 
     let path_lower = zip_path.to_lowercase();
+    let is_tar_gz = path_lower.ends_with(".tar.gz") || path_lower.ends_with(".tgz");
+
+    if let Some(required) = uncompressed_size(&zip_path, is_tar_gz) {
+        ensure_free_space(Path::new(&dest_dir), required)?;
+    }
 
     // 根据文件扩展名判断格式
-    if path_lower.ends_with(".tar.gz") || path_lower.ends_with(".tgz") {
-        extract_tar_gz(&zip_path, &dest_dir)
+    if is_tar_gz {
+        extract_tar_gz(&zip_path, &dest_dir)?;
     } else {
-        extract_zip_file(&zip_path, &dest_dir)
+        extract_zip_file(&zip_path, &dest_dir)?;
     }
+    Ok(())
+}
+
+/// 解压后的总大小：zip 取中央目录中记录的大小，tar.gz 取 gzip 尾部的 ISIZE（原始大小对 4 GiB 取模）
+fn uncompressed_size(path: &str, is_tar_gz: bool) -> Option<u64> {
+    use std::io::{Seek, SeekFrom};
+
+    let mut file = std::fs::File::open(path).ok()?;
+    if is_tar_gz {
+        let mut isize = [0u8; 4];
+        file.seek(SeekFrom::End(-4)).ok()?;
+        file.read_exact(&mut isize).ok()?;
+        return Some(u32::from_le_bytes(isize) as u64);
+    }
+
+    let mut archive = zip::ZipArchive::new(file).ok()?;
+    (0..archive.len())
+        .map(|i| archive.by_index_raw(i).map(|f| f.size()).ok())
+        .sum()
 }
 
 /// 解压 ZIP 文件
@@ -90,7 +126,7 @@ fn extract_tar_gz(tar_path: &str, dest_dir: &str) -> Result<(), String> {
 
 /// 检查解压目录中是否存在 changes.json（增量包标识）
 #[tauri::command]
-pub fn check_changes_json(extract_dir: String) -> Result<Option<ChangesJson>, String> {
+pub fn check_changes_json(extract_dir: String) -> Result<Option<ChangesJson>, MxuError> {
     let changes_path = std::path::Path::new(&extract_dir).join("changes.json");
 
     if !changes_path.exists() {
@@ -102,10 +138,44 @@ pub fn check_changes_json(extract_dir: String) -> Result<Option<ChangesJson>, St
 
     let changes: ChangesJson =
         serde_json::from_str(&content).map_err(|e| format!("无法解析 changes.json: {}", e))?;
+    validate_changes_json(&changes)?;
 
     Ok(Some(changes))
 }
 
+/// 校验 changes.json 版本与路径安全性
+fn validate_changes_json(changes: &ChangesJson) -> Result<(), String> {
+    if changes.schema_version > CHANGES_SCHEMA_VERSION {
+        return Err(format!(
+            "不支持的 changes.json 版本: {}（最高支持 {}）",
+            changes.schema_version, CHANGES_SCHEMA_VERSION
+        ));
+    }
+
+    let paths = changes
+        .deleted
+        .iter()
+        .chain(&changes.deleted_dirs)
+        .chain(changes.renamed.iter().flat_map(|r| [&r.from, &r.to]))
+        .chain(changes.permissions.iter().map(|p| &p.path));
+    for path in paths {
+        if !is_safe_relative_path(path) {
+            return Err(format!("changes.json 中包含非法路径: {}", path));
+        }
+    }
+    Ok(())
+}
+
+/// 判断是否为不越出安装目录的相对路径
+pub fn is_safe_relative_path(path: &str) -> bool {
+    use std::path::Component;
+
+    !path.is_empty()
+        && Path::new(path)
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+}
+
 /// 递归清理目录内容，逐个删除文件和空目录，返回 (成功数, 失败数)
 pub fn cleanup_dir_contents(dir: &std::path::Path) -> (usize, usize) {
     let mut deleted = 0;
@@ -142,15 +212,18 @@ pub fn cleanup_dir_contents(dir: &std::path::Path) -> (usize, usize) {
 /// 将文件或目录移动到程序目录下的 cache/old 文件夹，处理重名冲突
 /// 供前端调用，统一文件移动逻辑
 #[tauri::command]
-pub fn move_file_to_old(file_path: String) -> Result<(), String> {
+pub fn move_file_to_old(file_path: String) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
     let path = std::path::Path::new(&file_path);
-    move_to_old_folder(path)
+    move_to_old_folder(path)?;
+    Ok(())
 }
 
 /// 将文件或目录移动到程序目录下的 cache/old 文件夹，处理重名冲突（内部函数）
-pub fn move_to_old_folder(source: &std::path::Path) -> Result<(), String> {
+/// 返回实际移动到的路径，源不存在时返回 None
+pub fn move_to_old_folder(source: &std::path::Path) -> Result<Option<std::path::PathBuf>, String> {
     if !source.exists() {
-        return Ok(());
+        return Ok(None);
     }
 
     // 统一移动到 exe_dir/cache/old
@@ -206,26 +279,70 @@ pub fn move_to_old_folder(source: &std::path::Path) -> Result<(), String> {
     })?;
 
     info!("Moved to old: {} -> {}", source.display(), dest.display());
+    Ok(Some(dest))
+}
+
+/// 有任务运行时拒绝应用更新，避免替换正在使用的文件
+pub fn ensure_idle(state: &MaaState) -> Result<(), MxuError> {
+    if super::power::any_running(state) {
+        return Err(MxuError::new(ErrorCode::UpdateConflict));
+    }
     Ok(())
 }
 
 /// 应用增量更新：将 deleted 中的文件移动到 old 文件夹，然后复制新文件
 /// 即使移动旧文件失败，也会继续复制新文件，确保程序可用
+/// changes 为空时从 extract_dir 读取 changes.json，以支持 v2 的重命名、目录删除和权限操作
 #[tauri::command]
 pub fn apply_incremental_update(
+    state: State<Arc<MaaState>>,
     extract_dir: String,
     target_dir: String,
     deleted_files: Vec<String>,
-) -> Result<(), String> {
+    changes: Option<ChangesJson>,
+) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
     info!("apply_incremental_update called");
+    ensure_idle(&state)?;
     info!("extract_dir: {}, target_dir: {}", extract_dir, target_dir);
     info!("deleted_files: {:?}", deleted_files);
 
+    super::backup::backup_before_update();
+
+    let changes = match changes {
+        Some(changes) => {
+            validate_changes_json(&changes)?;
+            Some(changes)
+        }
+        None => check_changes_json(extract_dir.clone())?,
+    };
+    if let Some(changes) = &changes {
+        info!("changes.json schema_version: {}", changes.schema_version);
+    }
+
     let target_path = std::path::Path::new(&target_dir);
     let mut move_errors: Vec<String> = Vec::new();
 
-    // 1. 尝试将 deleted 中列出的文件移动到 old 文件夹（失败不阻断）
-    for file in &deleted_files {
+    // 1. 执行重命名/移动（v2），需在复制新文件前完成，避免新文件被覆盖
+    if let Some(changes) = &changes {
+        for rename in &changes.renamed {
+            if let Err(e) = apply_rename(target_path, rename) {
+                warn!("重命名失败（将继续更新）: {}", e);
+                move_errors.push(e);
+            }
+        }
+    }
+
+    // 2. 尝试将 deleted 中列出的文件及 deleted_dirs 中的目录移动到 old 文件夹（失败不阻断）
+    let deleted_dirs = changes
+        .as_ref()
+        .map(|c| c.deleted_dirs.as_slice())
+        .unwrap_or_default();
+    for file in deleted_files.iter().chain(deleted_dirs) {
+        if !is_safe_relative_path(file) {
+            warn!("跳过非法删除路径: {}", file);
+            continue;
+        }
         let file_path = target_path.join(file);
         if file_path.exists() {
             if let Err(e) = move_to_old_folder(&file_path) {
@@ -235,9 +352,19 @@ pub fn apply_incremental_update(
         }
     }
 
-    // 2. 复制新包内容到目标目录（覆盖）- 这一步必须执行
+    // 3. 复制新包内容到目标目录（覆盖）- 这一步必须执行
     copy_dir_contents(&extract_dir, &target_dir, None)?;
 
+    // 4. 应用可执行权限变更（v2）
+    if let Some(changes) = &changes {
+        for permission in &changes.permissions {
+            if let Err(e) = apply_permission(target_path, permission) {
+                warn!("设置权限失败: {}", e);
+                move_errors.push(e);
+            }
+        }
+    }
+
     if !move_errors.is_empty() {
         info!(
             "apply_incremental_update completed with {} move warnings",
@@ -249,15 +376,89 @@ pub fn apply_incremental_update(
     Ok(())
 }
 
+/// 执行单个重命名操作，目标已存在时先移动到 old 文件夹
+fn apply_rename(target_path: &Path, rename: &RenameOperation) -> Result<(), String> {
+    let from = target_path.join(&rename.from);
+    let to = target_path.join(&rename.to);
+
+    if !from.exists() {
+        info!("Rename source not found, skipped: {}", from.display());
+        return Ok(());
+    }
+    if to.exists() {
+        move_to_old_folder(&to)?;
+    }
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("无法创建目录 [{}]: {}", parent.display(), e))?;
+    }
+
+    std::fs::rename(&from, &to).map_err(|e| {
+        format!(
+            "无法重命名 [{}] -> [{}]: {}",
+            from.display(),
+            to.display(),
+            e
+        )
+    })?;
+    info!("Renamed: {} -> {}", from.display(), to.display());
+    Ok(())
+}
+
+/// 设置或清除文件的可执行权限（非 Unix 平台忽略）
+fn apply_permission(target_path: &Path, permission: &PermissionOperation) -> Result<(), String> {
+    let path = target_path.join(&permission.path);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let metadata = std::fs::metadata(&path)
+            .map_err(|e| format!("无法获取文件元数据 [{}]: {}", path.display(), e))?;
+        let mut permissions = metadata.permissions();
+        let mode = if permission.executable {
+            permissions.mode() | 0o111
+        } else {
+            permissions.mode() & !0o111
+        };
+        permissions.set_mode(mode);
+        std::fs::set_permissions(&path, permissions)
+            .map_err(|e| format!("无法设置执行权限 [{}]: {}", path.display(), e))?;
+        info!(
+            "Set executable={} permission: {}",
+            permission.executable,
+            path.display()
+        );
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = path; // 避免未使用警告
+    }
+    Ok(())
+}
+
 /// 应用全量更新：将与新包根目录同名的文件夹/文件移动到 old 文件夹，然后复制新文件
 /// 即使移动旧文件失败，也会继续复制新文件，确保程序可用
 #[tauri::command]
-pub fn apply_full_update(extract_dir: String, target_dir: String) -> Result<(), String> {
+pub fn apply_full_update(
+    state: State<Arc<MaaState>>,
+    extract_dir: String,
+    target_dir: String,
+) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
     info!("apply_full_update called");
+    ensure_idle(&state)?;
+    ensure_free_space(Path::new(&target_dir), path_size(Path::new(&extract_dir)))?;
+    Ok(full_update(&extract_dir, &target_dir)?)
+}
+
+/// 全量更新的实际流程（供资源包安装复用）
+pub fn full_update(extract_dir: &str, target_dir: &str) -> Result<(), String> {
     info!("extract_dir: {}, target_dir: {}", extract_dir, target_dir);
 
-    let extract_path = std::path::Path::new(&extract_dir);
-    let target_path = std::path::Path::new(&target_dir);
+    super::backup::backup_before_update();
+
+    let extract_path = std::path::Path::new(extract_dir);
+    let target_path = std::path::Path::new(target_dir);
     let mut move_errors: Vec<String> = Vec::new();
 
     // 1. 获取解压目录中的根级条目
@@ -285,7 +486,7 @@ pub fn apply_full_update(extract_dir: String, target_dir: String) -> Result<(),
     }
 
     // 3. 复制新包内容到目标目录 - 这一步必须执行
-    copy_dir_contents(&extract_dir, &target_dir, Some(&["changes.json"]))?;
+    copy_dir_contents(extract_dir, target_dir, Some(&["changes.json"]))?;
 
     if !move_errors.is_empty() {
         info!(
@@ -326,7 +527,7 @@ fn copy_file_with_move_old(src: &std::path::Path, dst: &std::path::Path) -> Resu
 }
 
 /// 递归复制目录内容（不包含根目录本身）
-fn copy_dir_contents(src: &str, dst: &str, skip_files: Option<&[&str]>) -> Result<(), String> {
+pub fn copy_dir_contents(src: &str, dst: &str, skip_files: Option<&[&str]>) -> Result<(), String> {
     let src_path = std::path::Path::new(src);
     let dst_path = std::path::Path::new(dst);
 
@@ -361,7 +562,7 @@ fn copy_dir_contents(src: &str, dst: &str, skip_files: Option<&[&str]>) -> Resul
 }
 
 /// 递归复制整个目录
-fn copy_dir_recursive(src: &std::path::Path, dst: &std::path::Path) -> Result<(), String> {
+pub fn copy_dir_recursive(src: &std::path::Path, dst: &std::path::Path) -> Result<(), String> {
     std::fs::create_dir_all(dst).map_err(|e| format!("无法创建目录 [{}]: {}", dst.display(), e))?;
 
     for entry in
@@ -383,7 +584,7 @@ fn copy_dir_recursive(src: &std::path::Path, dst: &std::path::Path) -> Result<()
 
 /// 清理临时解压目录
 #[tauri::command]
-pub fn cleanup_extract_dir(extract_dir: String) -> Result<(), String> {
+pub fn cleanup_extract_dir(extract_dir: String) -> Result<(), MxuError> {
     info!("cleanup_extract_dir: {}", extract_dir);
 
     let path = std::path::Path::new(&extract_dir);
@@ -402,7 +603,8 @@ pub fn fallback_update(
     extract_dir: String,
     target_dir: String,
     new_version: String,
-) -> Result<String, String> {
+) -> Result<String, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
     info!(
         "fallback_update called: extract_dir={}, target_dir={}, new_version={}",
         extract_dir, target_dir, new_version
@@ -450,3 +652,212 @@ pub fn fallback_update(
 
     Ok(result_path)
 }
+
+// ============================================================================
+// 更新暂存区检查
+// ============================================================================
+
+/// 获取更新包暂存目录（数据目录下的 cache，与前端 getCacheDir 一致）
+fn get_staging_dir() -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?.join("cache"))
+}
+
+/// 判断是否为支持的更新包格式
+fn is_update_package(path: &Path) -> bool {
+    let name = path.to_string_lossy().to_lowercase();
+    name.ends_with(".zip") || name.ends_with(".tar.gz") || name.ends_with(".tgz")
+}
+
+/// 列出暂存区中已下载完成的更新包（按修改时间从新到旧）
+#[tauri::command]
+pub fn update_staging_list() -> Result<Vec<StagedUpdatePackage>, MxuError> {
+    let staging_dir = get_staging_dir()?;
+    if !staging_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let entries =
+        std::fs::read_dir(&staging_dir).map_err(|e| format!("无法读取暂存目录: {}", e))?;
+
+    let mut packages: Vec<StagedUpdatePackage> = entries
+        .flatten()
+        .filter(|e| e.path().is_file() && is_update_package(&e.path()))
+        .map(|e| {
+            let metadata = e.metadata().ok();
+            StagedUpdatePackage {
+                path: e.path().to_string_lossy().to_string(),
+                file_name: e.file_name().to_string_lossy().to_string(),
+                size: metadata.as_ref().map(|m| m.len()).unwrap_or(0),
+                modified: metadata
+                    .and_then(|m| m.modified().ok())
+                    .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                    .map(|d| d.as_millis() as u64),
+            }
+        })
+        .collect();
+
+    packages.sort_by(|a, b| b.modified.cmp(&a.modified));
+    Ok(packages)
+}
+
+/// 预览暂存区中的更新包：列出将新增/覆盖/删除的文件及版本信息，不解压
+/// file: 暂存区中的文件名或完整路径
+#[tauri::command]
+pub fn update_staging_preview(file: String) -> Result<UpdatePreview, MxuError> {
+    info!("update_staging_preview called: {}", file);
+
+    let staging_dir = get_staging_dir()?;
+    let package_path = normalize_path(&staging_dir.join(&file).to_string_lossy());
+    if !package_path.starts_with(&staging_dir) {
+        return Err(MxuError::with_args(
+            ErrorCode::UpdatePackageNotStaged,
+            [&file],
+        ));
+    }
+    if !package_path.is_file() || !is_update_package(&package_path) {
+        return Err(MxuError::with_args(
+            ErrorCode::UpdatePackageInvalid,
+            [&file],
+        ));
+    }
+
+    let (files, changes_content, interface_content) = read_package_index(&package_path)?;
+
+    let changes: Option<ChangesJson> = match changes_content {
+        Some(content) => Some(
+            serde_json::from_str(&content).map_err(|e| format!("无法解析 changes.json: {}", e))?,
+        ),
+        None => None,
+    };
+    if let Some(changes) = &changes {
+        validate_changes_json(changes)?;
+    }
+
+    let target_dir = PathBuf::from(get_exe_dir()?);
+    let mut entries: Vec<UpdatePreviewEntry> = files
+        .into_iter()
+        .filter(|(path, _)| path != "changes.json")
+        .map(|(path, size)| {
+            let action = if target_dir.join(&path).exists() {
+                UpdateEntryAction::Modify
+            } else {
+                UpdateEntryAction::Add
+            };
+            UpdatePreviewEntry { path, size, action }
+        })
+        .collect();
+
+    if let Some(changes) = &changes {
+        for deleted in changes.deleted.iter().chain(&changes.deleted_dirs) {
+            let existing = target_dir.join(deleted);
+            if existing.exists() {
+                entries.push(UpdatePreviewEntry {
+                    path: deleted.clone(),
+                    size: existing.metadata().map(|m| m.len()).unwrap_or(0),
+                    action: UpdateEntryAction::Delete,
+                });
+            }
+        }
+    }
+
+    let total_size = entries
+        .iter()
+        .filter(|e| e.action != UpdateEntryAction::Delete)
+        .map(|e| e.size)
+        .sum();
+
+    let read_version = |content: &str| {
+        serde_json::from_str::<serde_json::Value>(content)
+            .ok()
+            .and_then(|v| v.get("version")?.as_str().map(|s| s.to_string()))
+    };
+    let current_version = std::fs::read_to_string(target_dir.join("interface.json"))
+        .ok()
+        .and_then(|c| read_version(&c));
+    let new_version = interface_content.and_then(|c| read_version(&c));
+
+    Ok(UpdatePreview {
+        file_name: package_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        incremental: changes.is_some(),
+        changes,
+        entries,
+        total_size,
+        current_version,
+        new_version,
+    })
+}
+
+/// 读取更新包目录：返回 (文件列表[(相对路径, 大小)], changes.json 内容, 根目录 interface.json 内容)
+#[allow(clippy::type_complexity)]
+fn read_package_index(
+    path: &Path,
+) -> Result<(Vec<(String, u64)>, Option<String>, Option<String>), String> {
+    let mut files = Vec::new();
+    let mut changes = None;
+    let mut interface = None;
+
+    let lower = path.to_string_lossy().to_lowercase();
+    if lower.ends_with(".tar.gz") || lower.ends_with(".tgz") {
+        let file = std::fs::File::open(path).map_err(|e| format!("无法打开更新包: {}", e))?;
+        let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(file));
+        for entry in archive
+            .entries()
+            .map_err(|e| format!("无法读取 tar.gz: {}", e))?
+        {
+            let mut entry = entry.map_err(|e| format!("无法读取 tar.gz 条目: {}", e))?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            let name = entry
+                .path()
+                .map_err(|e| e.to_string())?
+                .to_string_lossy()
+                .trim_start_matches("./")
+                .to_string();
+            let size = entry.size();
+            if name == "changes.json" || name == "interface.json" {
+                let mut content = String::new();
+                let _ = entry.read_to_string(&mut content);
+                if name == "changes.json" {
+                    changes = Some(content);
+                } else {
+                    interface = Some(content);
+                }
+            }
+            files.push((name, size));
+        }
+    } else {
+        let file = std::fs::File::open(path).map_err(|e| format!("无法打开更新包: {}", e))?;
+        let mut archive =
+            zip::ZipArchive::new(file).map_err(|e| format!("无法解析 ZIP 文件: {}", e))?;
+        for i in 0..archive.len() {
+            let mut entry = archive
+                .by_index(i)
+                .map_err(|e| format!("无法读取 ZIP 条目 {}: {}", i, e))?;
+            if entry.is_dir() {
+                continue;
+            }
+            let Some(name) = entry
+                .enclosed_name()
+                .map(|p| p.to_string_lossy().replace('\\', "/"))
+            else {
+                continue;
+            };
+            if name == "changes.json" || name == "interface.json" {
+                let mut content = String::new();
+                let _ = entry.read_to_string(&mut content);
+                if name == "changes.json" {
+                    changes = Some(content);
+                } else {
+                    interface = Some(content);
+                }
+            }
+            files.push((name, entry.size()));
+        }
+    }
+
+    Ok((files, changes, interface))
+}
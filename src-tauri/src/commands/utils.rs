@@ -2,9 +2,12 @@
 //!
 //! 提供路径处理和其他通用工具函数
 
-use super::types::MaaCallbackEvent;
+use super::error::MxuError;
+use super::i18n::ErrorCode;
+use super::types::{DataDirRedirect, MaaCallbackEvent};
 use std::path::PathBuf;
-use tauri::{AppHandle, Emitter};
+use std::sync::OnceLock;
+use tauri::AppHandle;
 
 /// 发送回调事件到前端
 pub fn emit_callback_event<S: Into<String>>(app: &AppHandle, message: S, details: S) {
@@ -12,15 +15,24 @@ pub fn emit_callback_event<S: Into<String>>(app: &AppHandle, message: S, details
         message: message.into(),
         details: details.into(),
     };
-    if let Err(e) = app.emit("maa-callback", event) {
-        log::error!("Failed to emit maa-callback: {}", e);
-    }
+    super::heartbeat::bump_revision();
+    super::tts::handle_callback(&event.message, &event.details);
+    super::recognition::handle_callback(&event.message, &event.details);
+    super::progress_events::handle_callback(app, &event.message, &event.details);
+    super::runs::handle_callback(app, &event.message, &event.details);
+    super::background_mode::emit(app, "maa-callback", event);
 }
 
-/// 获取应用数据目录
+/// 数据目录重定向文件名（位于默认数据目录，由 set_data_dir 写入）
+pub const DATA_REDIRECT_FILE: &str = "data_dir.json";
+
+/// 本次运行使用的数据目录（首次获取时解析，修改后需重启生效）
+static APP_DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// 默认数据目录
 /// - macOS: ~/Library/Application Support/MXU/
 /// - Windows/Linux: exe 所在目录（保持便携式部署）
-pub fn get_app_data_dir() -> Result<PathBuf, String> {
+pub fn default_data_dir() -> Result<PathBuf, String> {
     #[cfg(target_os = "macos")]
     {
         let home = std::env::var("HOME").map_err(|_| "无法获取 HOME 环境变量".to_string())?;
@@ -38,6 +50,43 @@ pub fn get_app_data_dir() -> Result<PathBuf, String> {
     }
 }
 
+/// 读取默认数据目录中的重定向文件，目标目录不存在时忽略
+pub fn read_data_redirect(default_dir: &std::path::Path) -> Option<PathBuf> {
+    let content = std::fs::read_to_string(default_dir.join(DATA_REDIRECT_FILE)).ok()?;
+    let redirect: DataDirRedirect = serde_json::from_str(&content).ok()?;
+    let path = PathBuf::from(redirect.path);
+    path.is_dir().then_some(path)
+}
+
+/// 获取应用数据目录：默认数据目录，存在重定向文件时使用其指向的目录
+pub fn get_app_data_dir() -> Result<PathBuf, String> {
+    if let Some(dir) = APP_DATA_DIR.get() {
+        return Ok(dir.clone());
+    }
+    let default_dir = default_data_dir()?;
+    let dir = read_data_redirect(&default_dir).unwrap_or(default_dir);
+    Ok(APP_DATA_DIR.get_or_init(|| dir).clone())
+}
+
+/// 以格式化 JSON 保存配置文件，父目录不存在时自动创建
+pub fn save_json_config<T: serde::Serialize + ?Sized>(
+    path: &std::path::Path,
+    value: &T,
+) -> Result<(), MxuError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| MxuError::with_args(ErrorCode::ConfigDirCreateFailed, [e]))?;
+    }
+    let content =
+        serde_json::to_string_pretty(value).map_err(|e| format!("无法序列化配置: {}", e))?;
+    std::fs::write(path, content).map_err(|e| {
+        MxuError::with_args(
+            ErrorCode::FileWriteFailed,
+            [path.display().to_string(), e.to_string()],
+        )
+    })
+}
+
 /// 规范化路径：移除冗余的 `.`、处理 `..`、统一分隔符
 /// 使用 Path::components() 解析，不需要路径实际存在
 pub fn normalize_path(path: &str) -> PathBuf {
@@ -81,6 +130,22 @@ pub fn get_logs_dir() -> PathBuf {
         .join("debug")
 }
 
+/// 后端日志文件名（与 lib.rs 中日志插件配置一致）
+pub const BACKEND_LOG_FILE: &str = "mxu-tauri.log";
+
+/// 读取后端日志末尾最多 max_bytes 字节（文件不存在时返回 None）
+pub fn read_backend_log_tail(max_bytes: u64) -> Option<String> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let mut file = std::fs::File::open(get_logs_dir().join(BACKEND_LOG_FILE)).ok()?;
+    let len = file.metadata().ok()?.len();
+    file.seek(SeekFrom::Start(len.saturating_sub(max_bytes)))
+        .ok()?;
+    let mut content = Vec::new();
+    file.read_to_end(&mut content).ok()?;
+    Some(String::from_utf8_lossy(&content).into_owned())
+}
+
 /// 获取 exe 所在目录路径（内部使用）
 pub fn get_exe_directory() -> Result<PathBuf, String> {
     let exe_path = std::env::current_exe().map_err(|e| format!("获取 exe 路径失败: {}", e))?;
@@ -90,9 +155,95 @@ pub fn get_exe_directory() -> Result<PathBuf, String> {
         .ok_or_else(|| "无法获取 exe 所在目录".to_string())
 }
 
-/// 获取可执行文件所在目录下的 maafw 子目录
+/// 获取 MaaFramework 库目录：默认为可执行文件所在目录下的 maafw 子目录，
+/// 选择了其他版本时为 maafw_versions/<版本名>
 pub fn get_maafw_dir() -> Result<PathBuf, String> {
-    Ok(get_exe_directory()?.join("maafw"))
+    let exe_dir = get_exe_directory()?;
+    if let Some(version) = super::maafw_versions::active_version(&exe_dir) {
+        let dir = exe_dir
+            .join(super::maafw_versions::MAAFW_VERSIONS_DIR)
+            .join(version);
+        if dir.is_dir() {
+            return Ok(dir);
+        }
+    }
+    Ok(exe_dir.join("maafw"))
+}
+
+/// 获取路径所在磁盘的可用空间（字节），路径不存在时向上查找已存在的父目录
+pub fn available_space(path: &std::path::Path) -> Option<u64> {
+    let existing = path.ancestors().find(|p| p.exists())?;
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::ffi::OsStrExt;
+        use windows::core::PCWSTR;
+        use windows::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+        let wide: Vec<u16> = existing.as_os_str().encode_wide().chain(Some(0)).collect();
+        let mut available = 0u64;
+        unsafe { GetDiskFreeSpaceExW(PCWSTR(wide.as_ptr()), Some(&mut available), None, None) }
+            .ok()?;
+        Some(available)
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+
+        let c_path = std::ffi::CString::new(existing.as_os_str().as_bytes()).ok()?;
+        let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+        if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+            return None;
+        }
+        Some(stat.f_bavail as u64 * stat.f_frsize as u64)
+    }
+}
+
+/// 文件或目录的总大小（字节）
+pub fn path_size(path: &std::path::Path) -> u64 {
+    if path.is_dir() {
+        std::fs::read_dir(path)
+            .map(|entries| {
+                entries
+                    .filter_map(|e| e.ok())
+                    .map(|e| path_size(&e.path()))
+                    .sum()
+            })
+            .unwrap_or(0)
+    } else {
+        std::fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / 1024.0 / 1024.0)
+}
+
+/// 写入前检查路径所在磁盘的可用空间，不足时返回 InsufficientDiskSpace
+/// （details 含 path / required / available 字节数）；无法获取可用空间时不阻止
+pub fn ensure_free_space(path: &std::path::Path, required: u64) -> Result<(), MxuError> {
+    let Some(available) = available_space(path) else {
+        return Ok(());
+    };
+    if available >= required {
+        return Ok(());
+    }
+    log::warn!(
+        "Insufficient disk space at {}: {} required, {} available",
+        path.display(),
+        required,
+        available
+    );
+    Err(MxuError::with_args(
+        ErrorCode::InsufficientDiskSpace,
+        [format_size(required), format_size(available)],
+    )
+    .with_details(serde_json::json!({
+        "path": path.to_string_lossy(),
+        "required": required,
+        "available": available,
+    })))
 }
 
 /// 构建 User-Agent 字符串
@@ -103,3 +254,43 @@ pub fn build_user_agent() -> String {
     let tauri_version = tauri::VERSION;
     format!("MXU/{} ({}; {}) Tauri/{}", version, os, arch, tauri_version)
 }
+
+/// 将 RGBA 图像编码为 PNG 并返回 data URL 字符串
+pub fn encode_png_data_url(image: &image::RgbaImage) -> Result<String, String> {
+    use base64::{engine::general_purpose::STANDARD, Engine as _};
+
+    let mut bytes = Vec::new();
+    image
+        .write_to(
+            &mut std::io::Cursor::new(&mut bytes),
+            image::ImageFormat::Png,
+        )
+        .map_err(|e| format!("PNG 编码失败: {}", e))?;
+    Ok(format!("data:image/png;base64,{}", STANDARD.encode(&bytes)))
+}
+
+/// 在 pipeline 覆盖末尾追加一项（统一转为数组形式，后追加的优先级更高）
+pub fn append_override(pipeline_override: &str, extra: serde_json::Value) -> String {
+    let mut overrides = match serde_json::from_str::<serde_json::Value>(pipeline_override) {
+        Ok(serde_json::Value::Array(items)) => items,
+        Ok(value) => vec![value],
+        Err(_) => Vec::new(),
+    };
+    overrides.push(extra);
+    serde_json::Value::Array(overrides).to_string()
+}
+
+/// 深度合并 JSON 对象，非对象值直接覆盖
+pub fn merge_json(target: &mut serde_json::Value, patch: &serde_json::Value) {
+    match (target, patch) {
+        (serde_json::Value::Object(target), serde_json::Value::Object(patch)) => {
+            for (key, value) in patch {
+                merge_json(
+                    target.entry(key.clone()).or_insert(serde_json::Value::Null),
+                    value,
+                );
+            }
+        }
+        (target, patch) => *target = patch.clone(),
+    }
+}
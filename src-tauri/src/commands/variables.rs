@@ -0,0 +1,96 @@
+//! 任务变量存储
+//!
+//! 供 MXU custom action 写入、任务队列条件读取的键值存储，按实例隔离。
+//! 前端通过 set_task_variable 预置的变量单独保存，不随运行清空；
+//! 实例以队列模式启动任务时只清空该实例上一次运行写入的变量，
+//! 读取时运行中写入的值优先于预置值
+
+use log::info;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{LazyLock, Mutex};
+
+use super::error::MxuError;
+
+/// 实例 ID -> 变量名 -> 值
+type VariableMap = HashMap<String, HashMap<String, serde_json::Value>>;
+
+/// 各实例运行中写入的变量
+static VARIABLES: LazyLock<Mutex<VariableMap>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 各实例由前端预置的变量
+static PRESETS: LazyLock<Mutex<VariableMap>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 设置实例的变量
+pub fn set_variable(instance_id: &str, name: &str, value: serde_json::Value) {
+    if let Ok(mut vars) = VARIABLES.lock() {
+        info!("[variables] {}: {} = {}", instance_id, name, value);
+        vars.entry(instance_id.to_string())
+            .or_default()
+            .insert(name.to_string(), value);
+    }
+}
+
+/// 读取实例的变量（运行中写入的值优先，其次为预置值）
+pub fn get_variable(instance_id: &str, name: &str) -> Option<serde_json::Value> {
+    let lookup = |map: &Mutex<VariableMap>| {
+        map.lock()
+            .ok()
+            .and_then(|v| v.get(instance_id)?.get(name).cloned())
+    };
+    lookup(&VARIABLES).or_else(|| lookup(&PRESETS))
+}
+
+/// 实例运行中写入的数值型变量（运行结束时写入运行历史）
+pub fn numeric_variables(instance_id: &str) -> BTreeMap<String, f64> {
+    VARIABLES
+        .lock()
+        .ok()
+        .and_then(|vars| {
+            let vars = vars.get(instance_id)?;
+            Some(
+                vars.iter()
+                    .filter_map(|(name, value)| Some((name.clone(), value.as_f64()?)))
+                    .collect(),
+            )
+        })
+        .unwrap_or_default()
+}
+
+/// 清空实例运行中写入的变量（预置变量保留）
+pub fn clear_variables(instance_id: &str) {
+    if let Ok(mut vars) = VARIABLES.lock() {
+        vars.remove(instance_id);
+    }
+}
+
+/// 获取实例的所有变量（预置值被运行中写入的同名变量覆盖）
+#[tauri::command]
+pub fn get_task_variables(
+    instance_id: String,
+) -> Result<HashMap<String, serde_json::Value>, MxuError> {
+    let mut result = PRESETS
+        .lock()
+        .map_err(|e| e.to_string())?
+        .get(&instance_id)
+        .cloned()
+        .unwrap_or_default();
+    let vars = VARIABLES.lock().map_err(|e| e.to_string())?;
+    if let Some(vars) = vars.get(&instance_id) {
+        result.extend(vars.iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
+    Ok(result)
+}
+
+/// 预置实例的变量（供前端预置条件变量，不随运行清空）
+#[tauri::command]
+pub fn set_task_variable(
+    instance_id: String,
+    name: String,
+    value: serde_json::Value,
+) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    let mut presets = PRESETS.lock().map_err(|e| e.to_string())?;
+    info!("[variables] {}: preset {} = {}", instance_id, name, value);
+    presets.entry(instance_id).or_default().insert(name, value);
+    Ok(())
+}
@@ -0,0 +1,220 @@
+//! VC++ 运行库自动安装
+//!
+//! 下载微软官方 vc_redist 安装程序，校验后以管理员权限静默安装，完成后自动重新加载 MaaFramework。
+//! aka.ms 地址始终指向最新版本，因此默认校验微软数字签名；调用方提供 sha256 时改为按固定哈希校验。
+//! 各阶段通过 vcredist-install-status 事件通知前端（downloading / installing / retrying）
+
+use log::{info, warn};
+use std::path::PathBuf;
+
+use tauri::{AppHandle, Emitter};
+
+use super::error::MxuError;
+use super::i18n::ErrorCode;
+use super::types::VcredistInstallResult;
+use super::utils::get_app_data_dir;
+
+/// 安装程序退出码：需要重启
+const EXIT_REBOOT_REQUIRED: u32 = 3010;
+/// 安装程序退出码：已安装相同或更新的版本
+const EXIT_ALREADY_INSTALLED: u32 = 1638;
+
+/// 当前架构对应的安装程序下载地址与文件名
+fn installer_source() -> (&'static str, &'static str) {
+    match std::env::consts::ARCH {
+        "x86" => (
+            "https://aka.ms/vs/17/release/vc_redist.x86.exe",
+            "vc_redist.x86.exe",
+        ),
+        "aarch64" => (
+            "https://aka.ms/vs/17/release/vc_redist.arm64.exe",
+            "vc_redist.arm64.exe",
+        ),
+        _ => (
+            "https://aka.ms/vs/17/release/vc_redist.x64.exe",
+            "vc_redist.x64.exe",
+        ),
+    }
+}
+
+fn emit_status(app: &AppHandle, stage: &str) {
+    let _ = app.emit("vcredist-install-status", stage);
+}
+
+/// 校验安装程序的微软数字签名（Get-AuthenticodeSignature）
+#[cfg(windows)]
+fn verify_signature(path: &std::path::Path) -> Result<(), MxuError> {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+    // 路径通过环境变量传入，避免拼接到脚本中
+    let output = std::process::Command::new("powershell")
+        .args([
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            "$s = Get-AuthenticodeSignature -LiteralPath $env:MXU_VERIFY_PATH; \
+             \"$($s.Status)|$($s.SignerCertificate.Subject)\"",
+        ])
+        .env("MXU_VERIFY_PATH", path)
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .map_err(|e| format!("无法校验数字签名: {}", e))?;
+    let result = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let (status, subject) = result.split_once('|').unwrap_or((&result, ""));
+    if status != "Valid" || !subject.contains("O=Microsoft Corporation") {
+        return Err(MxuError::with_args(
+            ErrorCode::VcredistSignatureInvalid,
+            [result],
+        ));
+    }
+    Ok(())
+}
+
+/// 以管理员权限静默运行安装程序并等待退出，返回退出码
+#[cfg(windows)]
+fn run_elevated(path: &std::path::Path, parameters: &str) -> Result<u32, String> {
+    use std::ffi::OsStr;
+    use std::os::windows::ffi::OsStrExt;
+    use windows::core::PCWSTR;
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Threading::{GetExitCodeProcess, WaitForSingleObject, INFINITE};
+    use windows::Win32::UI::Shell::{ShellExecuteExW, SEE_MASK_NOCLOSEPROCESS, SHELLEXECUTEINFOW};
+    use windows::Win32::UI::WindowsAndMessaging::SW_HIDE;
+
+    fn to_wide(s: &OsStr) -> Vec<u16> {
+        s.encode_wide().chain(Some(0)).collect()
+    }
+
+    let verb = to_wide(OsStr::new("runas"));
+    let file = to_wide(path.as_os_str());
+    let params = to_wide(OsStr::new(parameters));
+    let mut info = SHELLEXECUTEINFOW {
+        cbSize: std::mem::size_of::<SHELLEXECUTEINFOW>() as u32,
+        fMask: SEE_MASK_NOCLOSEPROCESS,
+        lpVerb: PCWSTR(verb.as_ptr()),
+        lpFile: PCWSTR(file.as_ptr()),
+        lpParameters: PCWSTR(params.as_ptr()),
+        nShow: SW_HIDE.0,
+        ..Default::default()
+    };
+
+    unsafe {
+        // 用户在 UAC 中拒绝时返回错误
+        ShellExecuteExW(&mut info).map_err(|e| format!("无法以管理员身份运行安装程序: {}", e))?;
+        WaitForSingleObject(info.hProcess, INFINITE);
+        let mut exit_code = 0u32;
+        let result = GetExitCodeProcess(info.hProcess, &mut exit_code);
+        let _ = CloseHandle(info.hProcess);
+        result.map_err(|e| format!("无法获取安装程序退出码: {}", e))?;
+        Ok(exit_code)
+    }
+}
+
+/// 一键安装 VC++ 运行库：下载、校验、静默安装后重新加载 MaaFramework
+/// sha256: 可选的固定哈希，提供时替代签名校验
+#[tauri::command]
+pub async fn vcredist_install(
+    app: AppHandle,
+    sha256: Option<String>,
+    proxy_url: Option<String>,
+) -> Result<VcredistInstallResult, MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    info!("vcredist_install called");
+
+    if !cfg!(windows) {
+        return Err(MxuError::new(ErrorCode::WindowsOnly));
+    }
+
+    // 1. 下载
+    emit_status(&app, "downloading");
+    let (url, file_name) = installer_source();
+    let save_path = get_app_data_dir()?.join("cache").join(file_name);
+    let download = super::download::download_file(
+        app.clone(),
+        url.to_string(),
+        save_path.to_string_lossy().to_string(),
+        None,
+        proxy_url,
+    )
+    .await?;
+    let installer = PathBuf::from(&download.actual_save_path);
+
+    // 2. 校验
+    let expected = sha256
+        .map(|s| s.trim().to_lowercase())
+        .filter(|s| !s.is_empty());
+    let verify_app = app.clone();
+    let verify_path = installer.clone();
+    tauri::async_runtime::spawn_blocking(move || -> Result<(), MxuError> {
+        match expected {
+            Some(expected) => {
+                let actual = super::file_ops::compute_file_hash(
+                    &verify_app,
+                    &verify_path.to_string_lossy(),
+                    "sha256",
+                )?;
+                if actual != expected {
+                    return Err(MxuError::with_args(
+                        ErrorCode::ChecksumMismatch,
+                        [expected, actual],
+                    ));
+                }
+                Ok(())
+            }
+            #[cfg(windows)]
+            None => verify_signature(&verify_path),
+            #[cfg(not(windows))]
+            None => Ok(()),
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .inspect_err(|_| {
+        let _ = std::fs::remove_file(&installer);
+    })?;
+
+    // 3. 静默安装
+    emit_status(&app, "installing");
+    info!("[vcredist] Installing {}", installer.display());
+    #[cfg(windows)]
+    let exit_code = {
+        let installer = installer.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            run_elevated(&installer, "/install /quiet /norestart")
+        })
+        .await
+        .map_err(|e| e.to_string())??
+    };
+    #[cfg(not(windows))]
+    let exit_code = 0u32;
+    info!("[vcredist] Installer exited with code {}", exit_code);
+    let _ = std::fs::remove_file(&installer);
+
+    if !matches!(exit_code, 0 | EXIT_REBOOT_REQUIRED | EXIT_ALREADY_INSTALLED) {
+        return Err(MxuError::with_args(
+            ErrorCode::VcredistInstallFailed,
+            [exit_code],
+        ));
+    }
+
+    // 4. 重新加载 MaaFramework
+    emit_status(&app, "retrying");
+    let (maafw_version, load_error) = match super::system::retry_load_maa_library().await {
+        Ok(version) => {
+            super::system::set_vcredist_missing(false);
+            (Some(version), None)
+        }
+        Err(e) => {
+            warn!("[vcredist] MaaFramework still failed to load: {}", e);
+            (None, Some(e.message))
+        }
+    };
+
+    Ok(VcredistInstallResult {
+        exit_code,
+        reboot_required: exit_code == EXIT_REBOOT_REQUIRED,
+        maafw_version,
+        load_error,
+    })
+}
@@ -0,0 +1,325 @@
+//! 定时唤醒
+//!
+//! 前端同步启用的定时策略后，计算下一次定时执行时间，在其前 lead_minutes 分钟设置可唤醒系统的计时器
+//! （Windows 可等待计时器，fResume = TRUE，需在电源选项中允许唤醒定时器）。
+//! 计时器触发后在运行开始前保持系统唤醒（由 power 模块持有防休眠请求），
+//! 开启 resleep 时，由唤醒触发的运行全部结束后重新进入睡眠
+
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, Local, Timelike};
+use log::{info, warn};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Emitter};
+
+use super::error::MxuError;
+use super::i18n::ErrorCode;
+use super::types::{MaaState, WakeScheduleSlot, WakeTimerSettings, WakeTimerStatus};
+use super::utils::{get_app_data_dir, save_json_config};
+
+/// 重新计算下次唤醒时间的间隔
+const REARM_INTERVAL: Duration = Duration::from_secs(60);
+/// 唤醒后等待定时运行开始的最长时间，超时后不再保持唤醒
+const WAIT_FOR_RUN: Duration = Duration::from_secs(30 * 60);
+
+/// 前端同步的定时执行时间点
+static SLOTS: LazyLock<Mutex<Vec<WakeScheduleSlot>>> = LazyLock::new(|| Mutex::new(Vec::new()));
+
+/// 当前已设置的唤醒时间与对应运行时间
+static ARMED: Mutex<Option<(DateTime<Local>, DateTime<Local>)>> = Mutex::new(None);
+
+/// 唤醒计时器触发的时间（运行结束或超时后清除）
+static WOKE_AT: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// 唤醒后定时运行是否已开始
+static RUN_STARTED: AtomicBool = AtomicBool::new(false);
+
+fn config_path() -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?.join("config").join("wake_timer.json"))
+}
+
+fn load_settings() -> Result<WakeTimerSettings, String> {
+    let path = config_path()?;
+    if !path.exists() {
+        return Ok(WakeTimerSettings::default());
+    }
+    let content =
+        std::fs::read_to_string(&path).map_err(|e| format!("无法读取定时唤醒设置: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("无法解析定时唤醒设置: {}", e))
+}
+
+/// 计算 after 之后最近的定时执行时间（整点）
+fn next_run_after(slots: &[WakeScheduleSlot], after: DateTime<Local>) -> Option<DateTime<Local>> {
+    let start = after.with_minute(0)?.with_second(0)?.with_nanosecond(0)?;
+    // 逐小时向后查找一周
+    (1..=7 * 24)
+        .map(|h| start + ChronoDuration::hours(h))
+        .find(|t| {
+            let weekday = t.weekday().num_days_from_sunday();
+            slots
+                .iter()
+                .any(|s| s.weekdays.contains(&weekday) && s.hours.contains(&t.hour()))
+        })
+}
+
+/// 计算下一次唤醒时间与运行时间（唤醒时间已过去的时间点跳过）
+fn next_wake(settings: &WakeTimerSettings) -> Option<(DateTime<Local>, DateTime<Local>)> {
+    let slots = SLOTS.lock().ok()?.clone();
+    if slots.is_empty() {
+        return None;
+    }
+    let lead = ChronoDuration::minutes(settings.lead_minutes as i64);
+    let now = Local::now();
+    let mut after = now;
+    for _ in 0..8 {
+        let run = next_run_after(&slots, after)?;
+        let wake = run - lead;
+        if wake > now {
+            return Some((wake, run));
+        }
+        after = run;
+    }
+    None
+}
+
+/// 是否处于唤醒后等待定时运行的阶段（power 模块据此保持系统唤醒）
+pub fn holding_awake() -> bool {
+    let Ok(mut woke_at) = WOKE_AT.lock() else {
+        return false;
+    };
+    match *woke_at {
+        Some(at) if !RUN_STARTED.load(Ordering::SeqCst) && at.elapsed() > WAIT_FOR_RUN => {
+            warn!("[wake_timer] No scheduled run started after wake, releasing");
+            *woke_at = None;
+            false
+        }
+        Some(_) => !RUN_STARTED.load(Ordering::SeqCst),
+        None => false,
+    }
+}
+
+/// 任务队列开始时调用：标记唤醒后的运行已开始
+pub fn on_queue_started() {
+    if WOKE_AT.lock().is_ok_and(|w| w.is_some()) {
+        RUN_STARTED.store(true, Ordering::SeqCst);
+    }
+}
+
+/// 任务队列结束时调用：由唤醒触发的运行全部结束后按设置重新睡眠
+pub fn on_queue_finished(state: &Arc<MaaState>) {
+    if !RUN_STARTED.load(Ordering::SeqCst) {
+        return;
+    }
+    let any_active = state
+        .task_queues
+        .lock()
+        .map(|queues| queues.values().any(|q| q.is_active()))
+        .unwrap_or(false);
+    if any_active {
+        return;
+    }
+
+    if let Ok(mut woke_at) = WOKE_AT.lock() {
+        *woke_at = None;
+    }
+    RUN_STARTED.store(false, Ordering::SeqCst);
+
+    if !load_settings().unwrap_or_default().resleep {
+        return;
+    }
+    // 已配置完成后操作时交由其处理，避免重复执行电源操作
+    if super::post_actions::post_action_get_policy().action != super::types::PostActionKind::None {
+        info!("[wake_timer] Post action configured, skipping re-sleep");
+        return;
+    }
+    info!("[wake_timer] Scheduled run after wake finished, returning to sleep");
+    std::thread::spawn(|| {
+        // 留出日志与配置写入的时间
+        std::thread::sleep(Duration::from_secs(10));
+        crate::mxu_actions::execute_power_sleep();
+    });
+}
+
+#[cfg(windows)]
+mod platform {
+    use chrono::{DateTime, Local};
+    use windows::core::PCWSTR;
+    use windows::Win32::Foundation::{HANDLE, WAIT_OBJECT_0};
+    use windows::Win32::System::Threading::{
+        CancelWaitableTimer, CreateWaitableTimerW, SetWaitableTimer, WaitForSingleObject, INFINITE,
+    };
+
+    /// 可等待计时器句柄（进程生命周期内有效）
+    pub struct WakeTimer(HANDLE);
+
+    unsafe impl Send for WakeTimer {}
+    unsafe impl Sync for WakeTimer {}
+
+    impl WakeTimer {
+        pub fn create() -> Result<Self, String> {
+            // 自动重置：每次触发只唤醒等待线程一次
+            let handle = unsafe { CreateWaitableTimerW(None, false, PCWSTR::null()) }
+                .map_err(|e| format!("无法创建唤醒计时器: {}", e))?;
+            Ok(Self(handle))
+        }
+
+        pub fn arm(&self, at: DateTime<Local>) -> Result<(), String> {
+            // 绝对时间，单位 100ns，自 1601-01-01 UTC 起
+            const EPOCH_DIFF_SECS: i64 = 11_644_473_600;
+            let due = (at.timestamp() + EPOCH_DIFF_SECS) * 10_000_000
+                + at.timestamp_subsec_nanos() as i64 / 100;
+            unsafe { SetWaitableTimer(self.0, &due, 0, None, None, true) }
+                .map_err(|e| format!("无法设置唤醒计时器: {}", e))
+        }
+
+        pub fn disarm(&self) {
+            let _ = unsafe { CancelWaitableTimer(self.0) };
+        }
+
+        /// 阻塞等待计时器触发
+        pub fn wait(&self) -> bool {
+            unsafe { WaitForSingleObject(self.0, INFINITE) == WAIT_OBJECT_0 }
+        }
+    }
+}
+
+#[cfg(not(windows))]
+mod platform {
+    use chrono::{DateTime, Local};
+
+    pub struct WakeTimer;
+
+    impl WakeTimer {
+        pub fn create() -> Result<Self, String> {
+            Err("定时唤醒仅支持 Windows".to_string())
+        }
+
+        pub fn arm(&self, _at: DateTime<Local>) -> Result<(), String> {
+            Ok(())
+        }
+
+        pub fn disarm(&self) {}
+
+        pub fn wait(&self) -> bool {
+            false
+        }
+    }
+}
+
+static TIMER: LazyLock<Option<platform::WakeTimer>> = LazyLock::new(|| {
+    platform::WakeTimer::create()
+        .map_err(|e| info!("[wake_timer] {}", e))
+        .ok()
+});
+
+/// 按当前设置与定时策略重新设置唤醒计时器
+fn rearm() {
+    let Some(timer) = TIMER.as_ref() else {
+        return;
+    };
+    let settings = load_settings().unwrap_or_default();
+    let target = if settings.enabled {
+        next_wake(&settings)
+    } else {
+        None
+    };
+
+    let Ok(mut armed) = ARMED.lock() else {
+        return;
+    };
+    if *armed == target {
+        return;
+    }
+    match target {
+        Some((wake, run)) => match timer.arm(wake) {
+            Ok(()) => {
+                info!(
+                    "[wake_timer] Wake timer set for {} (scheduled run at {})",
+                    wake.format("%Y-%m-%d %H:%M"),
+                    run.format("%Y-%m-%d %H:%M")
+                );
+                *armed = target;
+            }
+            Err(e) => {
+                warn!("[wake_timer] {}", e);
+                *armed = None;
+            }
+        },
+        None => {
+            timer.disarm();
+            if armed.is_some() {
+                info!("[wake_timer] Wake timer cleared");
+            }
+            *armed = None;
+        }
+    }
+}
+
+/// 启动唤醒计时器线程（非 Windows 平台不启动）
+pub fn spawn_wake_timer(app: AppHandle) {
+    let Some(timer) = TIMER.as_ref() else {
+        return;
+    };
+
+    std::thread::spawn(|| loop {
+        rearm();
+        std::thread::sleep(REARM_INTERVAL);
+    });
+
+    std::thread::spawn(move || loop {
+        if !timer.wait() {
+            std::thread::sleep(REARM_INTERVAL);
+            continue;
+        }
+        let run = ARMED.lock().ok().and_then(|mut a| a.take()).map(|(_, r)| r);
+        info!("[wake_timer] Wake timer fired");
+        if let Ok(mut woke_at) = WOKE_AT.lock() {
+            *woke_at = Some(Instant::now());
+        }
+        RUN_STARTED.store(false, Ordering::SeqCst);
+        let _ = app.emit("wake-timer-fired", run.map(|r| r.to_rfc3339()));
+        rearm();
+    });
+}
+
+/// 同步启用的定时策略时间点（前端策略变化时调用）
+#[tauri::command]
+pub fn wake_timer_sync_schedule(slots: Vec<WakeScheduleSlot>) {
+    if let Ok(mut current) = SLOTS.lock() {
+        *current = slots;
+    }
+    rearm();
+}
+
+/// 获取定时唤醒设置
+#[tauri::command]
+pub fn wake_timer_get_settings() -> Result<WakeTimerSettings, MxuError> {
+    Ok(load_settings()?)
+}
+
+/// 保存定时唤醒设置并立即重新设置计时器
+#[tauri::command]
+pub fn wake_timer_set_settings(settings: WakeTimerSettings) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    if settings.enabled && TIMER.is_none() {
+        return Err(MxuError::new(ErrorCode::WakeTimerUnsupported));
+    }
+    let path = config_path()?;
+    save_json_config(&path, &settings)?;
+    rearm();
+    Ok(())
+}
+
+/// 获取定时唤醒状态
+#[tauri::command]
+pub fn wake_timer_status() -> WakeTimerStatus {
+    let armed = ARMED.lock().ok().and_then(|a| *a);
+    WakeTimerStatus {
+        armed: armed.is_some(),
+        next_wake: armed.map(|(w, _)| w.to_rfc3339()),
+        next_run: armed.map(|(_, r)| r.to_rfc3339()),
+        woke_for_run: WOKE_AT.lock().is_ok_and(|w| w.is_some()),
+    }
+}
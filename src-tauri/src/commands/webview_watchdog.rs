@@ -0,0 +1,149 @@
+//! WebView 看门狗
+//!
+//! 基于 heartbeat 模块的前端心跳确认，后端线程检查主窗口可见期间的心跳间隔，
+//! 超过 config/webview_watchdog.json 中的超时时间时记录诊断信息，并可选重新加载窗口。
+//! 任务运行在后端，前端无响应期间自动化不受影响。
+//! 窗口隐藏或最小化时 WebView 定时器会被节流，此时不做判断
+
+use log::{info, warn};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Manager};
+
+use super::error::MxuError;
+use super::heartbeat::{last_ack, HEARTBEAT_INTERVAL};
+use super::types::{MaaState, WebviewWatchdogConfig};
+use super::utils::{get_app_data_dir, save_json_config};
+
+/// 本次运行期间的累计无响应次数
+static HANG_COUNT: Mutex<u32> = Mutex::new(0);
+
+fn config_path() -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?
+        .join("config")
+        .join("webview_watchdog.json"))
+}
+
+fn load_config() -> Result<WebviewWatchdogConfig, String> {
+    let path = config_path()?;
+    if !path.exists() {
+        return Ok(WebviewWatchdogConfig::default());
+    }
+    let content =
+        std::fs::read_to_string(&path).map_err(|e| format!("无法读取看门狗配置: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("无法解析看门狗配置: {}", e))
+}
+
+/// 统计正在运行任务的实例数（用于诊断日志）
+fn running_instances(app: &AppHandle) -> usize {
+    app.try_state::<Arc<MaaState>>()
+        .and_then(|state| {
+            state.instances.lock().ok().map(|instances| {
+                instances
+                    .values()
+                    .filter(|i| !i.task_ids.is_empty())
+                    .count()
+            })
+        })
+        .unwrap_or(0)
+}
+
+/// 启动看门狗线程
+pub fn spawn_watchdog(app: AppHandle) {
+    std::thread::spawn(move || {
+        // 是否已对本次无响应记录过（避免重复日志）
+        let mut reported = false;
+        // 计时起点：窗口重新可见或重新加载的时间，早于该时间的心跳不作为判断依据
+        let mut since: Option<Instant> = None;
+        // 重新加载后等待首次确认
+        let mut reloading = false;
+        loop {
+            std::thread::sleep(HEARTBEAT_INTERVAL);
+
+            let config = load_config().unwrap_or_default();
+            if !config.enabled {
+                continue;
+            }
+            let Some(window) = app.get_webview_window("main") else {
+                continue;
+            };
+            let visible = window.is_visible().unwrap_or(false);
+            let minimized = window.is_minimized().unwrap_or(false);
+            if !visible || minimized {
+                // 后台节流期间不判断，重新可见后从当前时间开始计时
+                since = Some(Instant::now());
+                continue;
+            }
+
+            // 页面尚未完成首次加载时不判断
+            let Some(ack) = last_ack() else {
+                continue;
+            };
+            if reloading {
+                if since.is_some_and(|s| ack < s) {
+                    continue;
+                }
+                reloading = false;
+                info!("[webview_watchdog] WebView heartbeat resumed after reload");
+            }
+            let reference = since.map_or(ack, |s| s.max(ack));
+            let elapsed = reference.elapsed();
+            if elapsed < Duration::from_secs(config.timeout_secs.max(1)) {
+                if reported {
+                    info!("[webview_watchdog] WebView heartbeat resumed");
+                }
+                reported = false;
+                continue;
+            }
+            if reported {
+                continue;
+            }
+            reported = true;
+
+            let hang_count = HANG_COUNT
+                .lock()
+                .map(|mut c| {
+                    *c += 1;
+                    *c
+                })
+                .unwrap_or(0);
+            warn!(
+                "[webview_watchdog] WebView unresponsive: no heartbeat ack for {:.1}s \
+                 (focused: {}, running instances: {}, hangs this session: {})",
+                elapsed.as_secs_f64(),
+                window.is_focused().unwrap_or(false),
+                running_instances(&app),
+                hang_count
+            );
+
+            if config.auto_restart {
+                match window.reload() {
+                    Ok(()) => {
+                        info!("[webview_watchdog] Reloading main window");
+                        // 等待重新加载后的首次心跳再恢复检查
+                        since = Some(Instant::now());
+                        reloading = true;
+                        reported = false;
+                    }
+                    Err(e) => warn!("[webview_watchdog] Failed to reload window: {}", e),
+                }
+            }
+        }
+    });
+}
+
+/// 获取看门狗配置
+#[tauri::command]
+pub fn webview_watchdog_get_config() -> Result<WebviewWatchdogConfig, MxuError> {
+    Ok(load_config()?)
+}
+
+/// 设置看门狗配置
+#[tauri::command]
+pub fn webview_watchdog_set_config(config: WebviewWatchdogConfig) -> Result<(), MxuError> {
+    super::guest_mode::ensure_not_guest()?;
+    let path = config_path()?;
+    save_json_config(&path, &config)
+}
@@ -0,0 +1,282 @@
+//! Win32 截图与输入方式
+//!
+//! UWP 应用与独占全屏游戏在默认截图方式下常出现黑屏或截图失败，部分游戏只响应特定的输入方式。
+//! 此模块对指定窗口逐一尝试 MaaFramework 支持的截图方式（GDI、FramePool(Windows.Graphics.Capture)、
+//! DXGI 桌面复制等）与输入方式（Seize、SendMessage、PostMessage 等）的组合，返回可用性与耗时，
+//! 前端据此将方式保存到配置档案的设备配置中；maa_connect_controller 的 Win32 配置也可直接使用方式名称
+
+use log::info;
+use serde_json::Value;
+
+use super::error::MxuError;
+use super::i18n::ErrorCode;
+use super::types::{
+    ControllerMethodOption, Win32MethodCombo, Win32MethodList, Win32MethodProbeReport,
+    Win32ScreencapProbe, Win32ScreencapReport,
+};
+
+/// 与 MaaFramework Win32ScreencapMethod 对应的名称与位标志
+pub const SCREENCAP_METHODS: &[(&str, u64)] = &[
+    ("GDI", 1),
+    ("FramePool", 1 << 1),
+    ("DXGI_DesktopDup", 1 << 2),
+    ("DXGI_DesktopDup_Window", 1 << 3),
+    ("PrintWindow", 1 << 4),
+    ("ScreenDC", 1 << 5),
+];
+
+/// 与 MaaFramework Win32InputMethod 对应的名称与位标志
+pub const INPUT_METHODS: &[(&str, u64)] = &[
+    ("Seize", 1),
+    ("SendMessage", 1 << 1),
+    ("PostMessage", 1 << 2),
+    ("LegacyEvent", 1 << 3),
+    ("PostThreadMessage", 1 << 4),
+    ("SendMessageWithCursorPos", 1 << 5),
+    ("PostMessageWithCursorPos", 1 << 6),
+    ("SendMessageWithWindowPos", 1 << 7),
+    ("PostMessageWithWindowPos", 1 << 8),
+];
+
+/// 将配置中的方式解析为位标志：数字、数字字符串、方式名称或名称数组（按位或）
+pub fn method_bits(table: &[(&str, u64)], value: &Value) -> Result<u64, MxuError> {
+    let unknown = |method: &dyn std::fmt::Display| {
+        MxuError::with_args(ErrorCode::UnknownControllerMethod, [method])
+    };
+    let by_name = |name: &str| {
+        table
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name.trim()))
+            .map(|(_, bits)| *bits)
+            .ok_or_else(|| unknown(&name))
+    };
+    match value {
+        Value::Number(n) => n.as_u64().ok_or_else(|| unknown(n)),
+        Value::String(s) => s.trim().parse::<u64>().or_else(|_| by_name(s)),
+        Value::Array(items) => items.iter().try_fold(0, |bits, item| match item {
+            Value::String(name) => Ok(bits | by_name(name)?),
+            other => Err(unknown(other)),
+        }),
+        other => Err(unknown(other)),
+    }
+}
+
+/// 单个方式的连接与截图超时
+#[cfg(windows)]
+const PROBE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// 判定为黑屏的通道最大值
+#[cfg(windows)]
+const BLACK_FRAME_LEVEL: u8 = 8;
+
+/// 使用指定截图与输入方式连接窗口并截图一次，返回 (耗时, 分辨率)
+#[cfg(windows)]
+fn probe_method(handle: u64, method: u64, input: u64) -> Result<(u64, [u32; 2]), String> {
+    use maa_framework::common::{Win32InputMethod, Win32ScreencapMethod};
+    use maa_framework::controller::Controller;
+    use std::time::{Duration, Instant};
+
+    let controller = Controller::new_win32(
+        handle as *mut std::ffi::c_void,
+        Win32ScreencapMethod::from_bits_truncate(method).bits(),
+        Win32InputMethod::from_bits_truncate(input).bits(),
+        Win32InputMethod::from_bits_truncate(input).bits(),
+    )
+    .map_err(|e| e.to_string())?;
+
+    controller.post_connection().map_err(|e| e.to_string())?;
+    let start = Instant::now();
+    while !controller.connected() {
+        if start.elapsed() > PROBE_TIMEOUT {
+            return Err("连接超时".to_string());
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+
+    let start = Instant::now();
+    controller.post_screencap().map_err(|e| e.to_string())?;
+    let data = loop {
+        let data = controller
+            .cached_image()
+            .ok()
+            .and_then(|buffer| buffer.to_vec())
+            .unwrap_or_default();
+        if !data.is_empty() {
+            break data;
+        }
+        if start.elapsed() > PROBE_TIMEOUT {
+            return Err("截图超时".to_string());
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    };
+    let elapsed = start.elapsed().as_millis() as u64;
+
+    let image = image::load_from_memory(&data)
+        .map_err(|e| format!("解析截图失败: {}", e))?
+        .to_rgb8();
+    if image
+        .pixels()
+        .all(|p| p.0.iter().all(|&c| c < BLACK_FRAME_LEVEL))
+    {
+        return Err("截图为黑屏".to_string());
+    }
+    Ok((elapsed, [image.width(), image.height()]))
+}
+
+#[cfg(not(windows))]
+fn probe_method(_handle: u64, _method: u64, _input: u64) -> Result<(u64, [u32; 2]), String> {
+    Err("此功能仅在 Windows 上可用".to_string())
+}
+
+/// 默认输入方式（Seize）
+const DEFAULT_INPUT_METHOD: u64 = 1;
+
+fn probe_screencap(handle: u64, name: &str, value: u64, input: u64) -> Win32ScreencapProbe {
+    let result = probe_method(handle, value, input);
+    info!("[win32_capture] {} -> {:?}", name, result);
+    Win32ScreencapProbe {
+        method: name.to_string(),
+        value,
+        supported: result.is_ok(),
+        elapsed_ms: result.as_ref().ok().map(|r| r.0),
+        resolution: result.as_ref().ok().map(|r| r.1),
+        error: result.err(),
+    }
+}
+
+/// 按名称筛选方式，names 为空时返回全部
+pub fn select_methods(
+    table: &'static [(&'static str, u64)],
+    names: Option<&Vec<String>>,
+) -> Vec<(&'static str, u64)> {
+    table
+        .iter()
+        .filter(|(name, _)| names.is_none_or(|m| m.iter().any(|n| n == name)))
+        .copied()
+        .collect()
+}
+
+/// 探测窗口可用的 Win32 截图方式
+///
+/// `methods` 为空时探测全部方式
+#[tauri::command]
+pub async fn maa_probe_win32_screencap(
+    handle: u64,
+    methods: Option<Vec<String>>,
+) -> Result<Win32ScreencapReport, MxuError> {
+    let candidates = select_methods(SCREENCAP_METHODS, methods.as_ref());
+    if candidates.is_empty() {
+        return Err(MxuError::new(ErrorCode::NoProbeMethods));
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let probes: Vec<Win32ScreencapProbe> = candidates
+            .into_iter()
+            .map(|(name, value)| {
+                // 探测截图时不发送输入，使用默认的 Seize 方式即可
+                probe_screencap(handle, name, value, DEFAULT_INPUT_METHOD)
+            })
+            .collect();
+        let recommended = probes
+            .iter()
+            .filter(|p| p.supported)
+            .min_by_key(|p| p.elapsed_ms)
+            .map(|p| p.method.clone());
+        Win32ScreencapReport {
+            probes,
+            recommended,
+        }
+    })
+    .await
+    .map_err(|e| e.to_string().into())
+}
+
+/// 列出 MaaFramework 支持的 Win32 截图与输入方式
+#[tauri::command]
+pub fn list_win32_methods() -> Win32MethodList {
+    let options = |table: &[(&str, u64)]| {
+        table
+            .iter()
+            .map(|(name, value)| ControllerMethodOption {
+                name: name.to_string(),
+                value: *value,
+            })
+            .collect()
+    };
+    Win32MethodList {
+        screencap: options(SCREENCAP_METHODS),
+        input: options(INPUT_METHODS),
+    }
+}
+
+/// 探测窗口可用的截图与输入方式组合
+///
+/// 先以默认输入方式探测各截图方式，再用可用方式中最快的截图方式逐一探测输入方式
+/// （连接并截图成功即视为可用；不会实际发送输入，游戏是否响应仍需在遥控面板中验证）。
+/// `screencap_methods` / `input_methods` 为空时探测全部方式
+#[tauri::command]
+pub async fn probe_win32_methods(
+    handle: u64,
+    screencap_methods: Option<Vec<String>>,
+    input_methods: Option<Vec<String>>,
+) -> Result<Win32MethodProbeReport, MxuError> {
+    let screencap_candidates = select_methods(SCREENCAP_METHODS, screencap_methods.as_ref());
+    let input_candidates = select_methods(INPUT_METHODS, input_methods.as_ref());
+    if screencap_candidates.is_empty() || input_candidates.is_empty() {
+        return Err(MxuError::new(ErrorCode::NoProbeMethods));
+    }
+    info!(
+        "probe_win32_methods: handle {}, {} screencap x {} input",
+        handle,
+        screencap_candidates.len(),
+        input_candidates.len()
+    );
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let screencap: Vec<Win32ScreencapProbe> = screencap_candidates
+            .into_iter()
+            .map(|(name, value)| probe_screencap(handle, name, value, DEFAULT_INPUT_METHOD))
+            .collect();
+        let best = screencap
+            .iter()
+            .filter(|p| p.supported)
+            .min_by_key(|p| p.elapsed_ms)
+            .cloned();
+
+        let combinations: Vec<Win32MethodCombo> = match &best {
+            Some(best) => input_candidates
+                .into_iter()
+                .map(|(input, input_value)| {
+                    let result = probe_method(handle, best.value, input_value);
+                    info!(
+                        "[win32_capture] {} + {} -> {:?}",
+                        best.method, input, result
+                    );
+                    Win32MethodCombo {
+                        screencap: best.method.clone(),
+                        screencap_value: best.value,
+                        input: input.to_string(),
+                        input_value,
+                        supported: result.is_ok(),
+                        elapsed_ms: result.as_ref().ok().map(|r| r.0),
+                        error: result.err(),
+                    }
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let recommended_input = combinations
+            .iter()
+            .find(|c| c.supported)
+            .map(|c| c.input.clone());
+        Win32MethodProbeReport {
+            screencap,
+            combinations,
+            recommended_screencap: best.map(|p| p.method),
+            recommended_input,
+        }
+    })
+    .await
+    .map_err(|e| e.to_string().into())
+}
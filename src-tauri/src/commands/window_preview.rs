@@ -0,0 +1,160 @@
+//! Win32 窗口缩略图
+//!
+//! 为窗口选择器截取各窗口的小尺寸预览图
+
+use log::{debug, info};
+use std::sync::Arc;
+
+use tauri::State;
+
+use super::error::MxuError;
+use super::types::{MaaState, Win32WindowThumbnail};
+
+/// 缩略图默认最大边长
+const DEFAULT_THUMBNAIL_SIZE: u32 = 240;
+
+/// 获取 Win32 窗口缩略图
+/// handles: 指定窗口句柄列表，为空时使用 maa_find_win32_windows 缓存的窗口列表
+/// max_size: 缩略图最大边长（像素），默认 240
+#[tauri::command]
+pub async fn maa_get_win32_thumbnails(
+    state: State<'_, Arc<MaaState>>,
+    handles: Option<Vec<u64>>,
+    max_size: Option<u32>,
+) -> Result<Vec<Win32WindowThumbnail>, MxuError> {
+    let handles = match handles {
+        Some(h) if !h.is_empty() => h,
+        _ => state
+            .cached_win32_windows
+            .lock()
+            .map_err(|e| e.to_string())?
+            .iter()
+            .map(|w| w.handle)
+            .collect(),
+    };
+    let max_size = max_size.unwrap_or(DEFAULT_THUMBNAIL_SIZE).clamp(16, 1024);
+
+    info!(
+        "maa_get_win32_thumbnails called, {} window(s), max_size: {}",
+        handles.len(),
+        max_size
+    );
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let thumbnails = handles
+            .into_iter()
+            .map(|handle| {
+                let image = capture_thumbnail(handle, max_size);
+                if image.is_none() {
+                    debug!("Failed to capture thumbnail for window {:#x}", handle);
+                }
+                Win32WindowThumbnail { handle, image }
+            })
+            .collect();
+        Ok(thumbnails)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 截取窗口并缩放为缩略图，返回 PNG data URL
+fn capture_thumbnail(handle: u64, max_size: u32) -> Option<String> {
+    let image = capture_window(handle)?;
+    // 按比例缩放，最长边不超过 max_size
+    let scale = (max_size as f64 / image.width().max(image.height()) as f64).min(1.0);
+    let width = ((image.width() as f64 * scale).round() as u32).max(1);
+    let height = ((image.height() as f64 * scale).round() as u32).max(1);
+    let thumbnail = image::imageops::thumbnail(&image, width, height);
+    super::utils::encode_png_data_url(&thumbnail).ok()
+}
+
+/// 通过 GDI 截取窗口客户区内容
+#[cfg(windows)]
+fn capture_window(handle: u64) -> Option<image::RgbaImage> {
+    use windows::Win32::Foundation::{HWND, RECT};
+    use windows::Win32::Graphics::Gdi::{
+        BitBlt, CreateCompatibleBitmap, CreateCompatibleDC, DeleteDC, DeleteObject, GetDC,
+        GetDIBits, ReleaseDC, SelectObject, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS,
+        SRCCOPY,
+    };
+    use windows::Win32::Storage::Xps::{PrintWindow, PRINT_WINDOW_FLAGS};
+    use windows::Win32::UI::WindowsAndMessaging::{GetClientRect, IsIconic, IsWindow};
+
+    // PW_CLIENTONLY | PW_RENDERFULLCONTENT，后者可截取 DirectX/硬件加速窗口
+    const PW_FLAGS: PRINT_WINDOW_FLAGS = PRINT_WINDOW_FLAGS(0x1 | 0x2);
+
+    let hwnd = HWND(handle as *mut std::ffi::c_void);
+
+    unsafe {
+        if !IsWindow(hwnd).as_bool() || IsIconic(hwnd).as_bool() {
+            return None;
+        }
+
+        let mut rect = RECT::default();
+        GetClientRect(hwnd, &mut rect).ok()?;
+        let width = rect.right - rect.left;
+        let height = rect.bottom - rect.top;
+        if width <= 0 || height <= 0 {
+            return None;
+        }
+
+        let window_dc = GetDC(hwnd);
+        if window_dc.is_invalid() {
+            return None;
+        }
+        let mem_dc = CreateCompatibleDC(window_dc);
+        let bitmap = CreateCompatibleBitmap(window_dc, width, height);
+        let old_object = SelectObject(mem_dc, bitmap);
+
+        // PrintWindow 失败时回退到 BitBlt（窗口被遮挡时内容可能不完整）
+        if !PrintWindow(hwnd, mem_dc, PW_FLAGS).as_bool() {
+            let _ = BitBlt(mem_dc, 0, 0, width, height, window_dc, 0, 0, SRCCOPY);
+        }
+
+        let mut info = BITMAPINFO {
+            bmiHeader: BITMAPINFOHEADER {
+                biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+                biWidth: width,
+                // 负高度表示自上而下的位图
+                biHeight: -height,
+                biPlanes: 1,
+                biBitCount: 32,
+                biCompression: BI_RGB.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut pixels = vec![0u8; (width * height * 4) as usize];
+        let lines = GetDIBits(
+            mem_dc,
+            bitmap,
+            0,
+            height as u32,
+            Some(pixels.as_mut_ptr() as *mut _),
+            &mut info,
+            DIB_RGB_COLORS,
+        );
+
+        SelectObject(mem_dc, old_object);
+        let _ = DeleteObject(bitmap);
+        let _ = DeleteDC(mem_dc);
+        ReleaseDC(hwnd, window_dc);
+
+        if lines == 0 {
+            return None;
+        }
+
+        // BGRA -> RGBA
+        for px in pixels.chunks_exact_mut(4) {
+            px.swap(0, 2);
+            px[3] = 255;
+        }
+
+        image::RgbaImage::from_raw(width as u32, height as u32, pixels)
+    }
+}
+
+#[cfg(not(windows))]
+fn capture_window(_handle: u64) -> Option<image::RgbaImage> {
+    None
+}
@@ -1,5 +1,8 @@
+pub mod cli;
 pub mod commands;
 mod mxu_actions;
+mod mxu_recognizers;
+mod taskbar;
 mod tray;
 
 use commands::MaaState;
@@ -9,6 +12,14 @@ use tauri_plugin_log::{Target, TargetKind, TimezoneStrategy};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    // 命令行导出/导入设置，不启动界面
+    if let Some(code) = cli::run_headless_command() {
+        std::process::exit(code);
+    }
+
+    // 更新 MXU 后重启的新进程需等待旧进程退出，否则开启单实例时会被当作重复启动
+    commands::app_update::wait_for_replaced_process();
+
     // 日志目录：exe 目录/debug/logs（与前端日志同目录）
     let logs_dir = commands::utils::get_logs_dir();
 
@@ -20,7 +31,28 @@ pub fn run() {
     #[cfg(windows)]
     commands::system::migrate_legacy_autostart();
 
-    tauri::Builder::default()
+    // 恢复只读访客模式状态
+    commands::guest_mode::init_guest_mode();
+
+    // 应用推理线程数（须在加载 MaaFramework 前设置）
+    commands::inference::init_inference_options();
+
+    // 加载日志级别与模块过滤配置（运行时可通过 set_log_config 调整）
+    commands::log_config::init_log_config();
+
+    // 安装 panic hook，崩溃时写入 debug/crashes
+    commands::crash_reporter::install_panic_hook();
+
+    let mut builder = tauri::Builder::default();
+    // 单实例（需在设置中开启）：再次启动（如点击 mxu:// 链接）时聚焦已运行的窗口，链接由 deep-link 插件转发
+    if commands::deep_link::single_instance_enabled() {
+        builder = builder.plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
+            commands::deep_link::focus_main_window(app);
+        }));
+    }
+
+    builder
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_dialog::init())
@@ -43,7 +75,8 @@ pub fn run() {
                     }),
                 ])
                 .timezone_strategy(TimezoneStrategy::UseLocal)
-                .level(log::LevelFilter::Debug)
+                .level(commands::log_config::max_level())
+                .filter(commands::log_config::is_enabled)
                 .build(),
         )
         .setup(|app| {
@@ -61,8 +94,9 @@ pub fn run() {
             }
 
             // 启动时异步清理 cache/old 目录（更新残留的旧文件），不阻塞应用启动
-            if let Ok(data_dir) = commands::get_data_dir() {
-                let old_dir = std::path::Path::new(&data_dir).join("cache").join("old");
+            // 与 update::move_to_old_folder 一致，位于 exe 目录而非数据目录
+            if let Ok(exe_dir) = commands::get_exe_dir() {
+                let old_dir = std::path::Path::new(&exe_dir).join("cache").join("old");
                 if old_dir.exists() {
                     std::thread::spawn(move || {
                         let (deleted, failed) = commands::cleanup_dir_contents(&old_dir);
@@ -91,8 +125,8 @@ pub fn run() {
                     #[cfg(target_os = "linux")]
                     let dll_path = maafw_dir.join("libMaaFramework.so");
 
-                    match maa_framework::load_library(&dll_path) {
-                        Ok(()) => log::info!("MaaFramework loaded from {:?}", dll_path),
+                    match commands::system::load_maa_library(&dll_path) {
+                        Ok(_) => log::info!("MaaFramework loaded from {:?}", dll_path),
                         Err(e) => {
                             log::error!("Failed to load MaaFramework: {}", e);
                             // 检查是否是 DLL 存在但加载失败的情况（可能是运行库缺失）
@@ -114,8 +148,69 @@ pub fn run() {
             // 初始化系统托盘
             if let Err(e) = tray::init_tray(app.handle()) {
                 log::error!("Failed to initialize system tray: {}", e);
+            } else {
+                // 托盘菜单显示运行状态与配置档案快速启动
+                tray::spawn_tray_menu_refresher(app.handle().clone());
             }
 
+            // 任务栏按钮显示下载与任务进度
+            taskbar::spawn_taskbar(app.handle().clone());
+
+            // 按用户配置顺序执行启动动作
+            commands::startup_actions::spawn_startup_actions(app.handle().clone());
+
+            // 上传未上传的崩溃报告（用户开启上传时）
+            commands::crash_reporter::spawn_pending_uploads();
+
+            // 前后端心跳与 WebView 无响应检测
+            commands::heartbeat::spawn_heartbeat(app.handle().clone());
+            commands::webview_watchdog::spawn_watchdog(app.handle().clone());
+
+            // MaaFramework 调用卡死检测
+            commands::ffi_guard::spawn_ffi_watchdog(app.handle().clone());
+
+            // Agent 子进程崩溃检测与自动重启
+            commands::maa_agent::spawn_agent_supervisor(app.handle().clone());
+
+            // 配置文件被外部修改后热重载
+            commands::config_reload::spawn_config_watcher(app.handle().clone());
+
+            // 运行结束后释放设备锁
+            commands::device_lock::spawn_lock_keeper(app.handle().clone());
+
+            // 按配置发送运行历史周报
+            commands::digest::spawn_digest_scheduler();
+
+            // 资源占用监控
+            commands::monitor::spawn_monitor(app.handle().clone());
+
+            // 任务运行期间防止系统休眠
+            commands::power::spawn_power_guard(app.handle().clone());
+
+            // 定时执行前唤醒系统（Windows）
+            commands::wake_timer::spawn_wake_timer(app.handle().clone());
+
+            // 隐藏到托盘时的低功耗后台模式
+            commands::background_mode::spawn_background_mode(app.handle().clone());
+
+            // 恢复全局快捷键
+            commands::hotkeys::restore_hotkeys(app.handle());
+
+            // 通知按钮回调需要访问应用
+            commands::notifications::init(app.handle());
+
+            // 脚本与二维码动作在动作线程中执行，需要访问实例状态
+            commands::scripting::init(app.handle());
+            commands::qrcode_scan::init(app.handle());
+            // MXU_POWER 特殊任务经完成后操作的倒计时执行
+            commands::post_actions::init(app.handle());
+
+            // 远程 API 服务（设置中开启时启动）
+            commands::api_server::init(app.handle());
+
+            // 监听 mxu:// 链接并处理启动参数中的链接
+            commands::deep_link::init(app.handle());
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -123,49 +218,244 @@ pub fn run() {
             commands::maa_core::maa_init,
             commands::maa_core::maa_set_resource_dir,
             commands::maa_core::maa_get_version,
+            commands::maafw_versions::maafw_version_list,
+            commands::maafw_versions::maafw_version_install,
+            commands::maafw_versions::maafw_version_switch,
+            commands::maafw_versions::maafw_version_use_profile,
             commands::maa_core::maa_check_version,
             commands::maa_core::maa_find_adb_devices,
+            commands::adb_manager::adb_locate,
+            commands::adb_manager::adb_download_platform_tools,
+            commands::adb_manager::adb_kill_server,
+            commands::adb_manager::adb_restart_server,
+            commands::adb_manager::adb_detect_conflicts,
+            commands::adb_tuning::list_adb_methods,
+            commands::adb_tuning::benchmark_adb_methods,
             commands::maa_core::maa_find_win32_windows,
+            commands::win32_capture::maa_probe_win32_screencap,
+            commands::win32_capture::list_win32_methods,
+            commands::win32_capture::probe_win32_methods,
             commands::maa_core::maa_create_instance,
             commands::maa_core::maa_destroy_instance,
             commands::maa_core::maa_connect_controller,
             commands::maa_core::maa_get_connection_status,
+            commands::resolution_check::check_device_resolution,
             commands::maa_core::maa_load_resource,
             commands::maa_core::maa_is_resource_loaded,
             commands::maa_core::maa_destroy_resource,
             commands::maa_core::maa_run_task,
             commands::maa_core::maa_get_task_status,
             commands::maa_core::maa_stop_task,
+            commands::stop_control::stop_get_settings,
+            commands::stop_control::stop_set_settings,
+            commands::pause_gate::maa_pause_tasks,
+            commands::pause_gate::maa_resume_tasks,
             commands::maa_core::maa_override_pipeline,
             commands::maa_core::maa_is_running,
             commands::maa_core::maa_post_screencap,
             commands::maa_core::maa_get_cached_image,
+            commands::controller_input::controller_click,
+            commands::controller_input::controller_swipe,
+            commands::controller_input::controller_key,
+            commands::controller_input::controller_input_text,
+            // 推理后端
+            commands::inference::maa_get_inference_options,
+            commands::inference::maa_set_inference_options,
+            commands::inference::maa_list_inference_devices,
+            // 识别结果检查
+            commands::recognition::maa_list_recent_recognitions,
+            commands::recognition::maa_clear_recognitions,
+            commands::recognition::maa_get_recognition_detail,
+            commands::recognition::maa_get_latest_recognition,
+            commands::recognition_feed::recognition_subscribe,
+            commands::recognition_feed::recognition_unsubscribe,
+            commands::recognition_feed::recognition_list_subscriptions,
+            commands::recognition_feed::recognition_poll,
+            // 自适应阈值
+            commands::adaptive_threshold::adaptive_threshold_get_config,
+            commands::adaptive_threshold::adaptive_threshold_set_config,
+            commands::adaptive_threshold::adaptive_threshold_stats,
+            commands::adaptive_threshold::adaptive_threshold_reset_stats,
+            // 截图工具
+            commands::screencap_tools::save_template_from_screencap,
+            commands::screencap_tools::sample_pixels,
+            // 颜色校准
+            commands::color_calibration::color_calibration_run,
+            commands::color_calibration::color_calibration_set_active,
+            commands::color_calibration::color_calibration_get_active,
             // Agent 命令
             commands::maa_agent::maa_start_tasks,
             commands::maa_agent::maa_stop_agent,
+            commands::agent_sandbox::agent_sandbox_get_settings,
+            commands::agent_sandbox::agent_sandbox_set_settings,
+            // 任务队列命令
+            commands::task_queue::queue_state,
+            commands::task_queue::queue_pause,
+            commands::task_queue::queue_resume,
+            commands::task_queue::stop_after_current,
+            commands::idle_policy::get_idle_policy,
+            commands::idle_policy::set_idle_policy,
+            commands::idle_policy::get_user_idle_secs,
+            commands::game_update_check::game_update_check_get_config,
+            commands::game_update_check::game_update_check_set_config,
+            commands::variables::get_task_variables,
+            commands::variables::set_task_variable,
+            commands::action_test::mxu_action_test,
+            commands::scripting::run_script,
+            commands::qrcode_scan::scan_qrcode,
+            // 覆盖预设命令
+            commands::override_presets::override_preset_list,
+            commands::override_presets::override_preset_save,
+            commands::override_presets::override_preset_delete,
+            commands::override_presets::override_preset_merge,
+            commands::override_presets::override_preset_select,
+            commands::override_presets::override_preset_get_selection,
+            commands::override_presets::override_preset_apply,
+            // 运行记录命令
+            commands::runs::get_run_pipeline,
+            commands::runs::list_runs,
+            commands::run_report::get_run_report_image,
+            // 完成后操作命令
+            commands::post_actions::post_action_get_policy,
+            commands::post_actions::post_action_set_policy,
+            commands::post_actions::post_action_cancel,
+            commands::post_actions::post_action_is_pending,
+            // 配置加载命令
+            commands::config_migration::load_user_config,
+            commands::config_migration::save_user_config,
+            commands::config_reload::config_reload_now,
+            // 设置备份命令
+            commands::backup::backup_create,
+            commands::backup::backup_list,
+            commands::backup::backup_restore,
+            commands::backup::backup_cancel,
+            // 配置档案命令
+            commands::profiles::profile_list,
+            commands::profiles::profile_get,
+            commands::profiles::profile_create,
+            commands::profiles::profile_save,
+            commands::profiles::profile_set_flag,
+            commands::profiles::profile_set_maafw_version,
+            commands::queue_templates::resolve_queue_template,
+            commands::profiles::profile_clone,
+            commands::profiles::profile_rename,
+            commands::profiles::profile_delete,
+            commands::profiles::profile_export,
+            commands::profiles::profile_import,
+            commands::config_import::import_preview,
+            commands::config_import::import_as_profile,
+            commands::hooks::hook_test,
+            commands::hooks::hook_list_unconfirmed,
+            commands::hooks::hook_confirm,
+            // 深度链接命令
+            commands::deep_link::deep_link_get_settings,
+            commands::deep_link::deep_link_set_settings,
+            // 集群命令
+            commands::cluster::cluster_list_nodes,
+            commands::cluster::cluster_add_node,
+            commands::cluster::cluster_remove_node,
+            commands::cluster::cluster_probe_nodes,
+            commands::cluster::cluster_dispatch,
+            commands::cluster::cluster_node_job_failed,
+            // 云同步命令
+            commands::sync::sync_get_config,
+            commands::sync::sync_set_config,
+            commands::sync::sync_push,
+            commands::sync::sync_pull,
+            // 访客模式命令
+            commands::guest_mode::guest_mode_get,
+            commands::guest_mode::guest_mode_enable,
+            commands::guest_mode::guest_mode_disable,
+            // 远程授权命令
+            commands::remote_auth::remote_token_list,
+            commands::remote_auth::remote_token_create,
+            commands::remote_auth::remote_token_rotate,
+            commands::remote_auth::remote_token_revoke,
+            commands::remote_auth::remote_get_allowlist,
+            commands::remote_auth::remote_set_allowlist,
+            commands::remote_auth::remote_audit_log,
+            commands::remote_auth::remote_pairing_qr,
+            commands::api_server::api_server_get_settings,
+            commands::api_server::api_server_set_settings,
+            commands::api_server::api_server_status,
+            // 资源包管理命令
+            commands::resource_manager::resource_pack_list,
+            commands::resource_manager::resource_pack_set_enabled,
+            commands::resource_manager::resource_pack_reorder,
+            commands::resource_manager::resource_pack_remove,
+            commands::resource_manager::resource_pack_install,
+            commands::resource_watcher::resource_watch_start,
+            commands::resource_watcher::resource_watch_stop,
+            commands::project_interface::interface_load,
+            commands::project_interface::interface_get_tasks,
+            commands::project_interface::interface_resolve_options,
             // 文件操作命令
             commands::file_ops::read_local_file,
             commands::file_ops::read_local_file_base64,
             commands::file_ops::local_file_exists,
             commands::file_ops::get_exe_dir,
             commands::file_ops::get_data_dir,
+            commands::data_dir::get_data_dir_info,
+            commands::data_dir::set_data_dir,
             commands::file_ops::get_cwd,
             commands::file_ops::check_exe_path,
             commands::file_ops::set_executable,
             commands::file_ops::export_logs,
+            commands::file_ops::cancel_export_logs,
+            commands::feedback::prepare_feedback,
+            commands::crash_reporter::crash_reports_list,
+            commands::crash_reporter::crash_report_read,
+            commands::crash_reporter::crash_reports_acknowledge,
+            commands::crash_reporter::crash_report_upload,
+            commands::crash_reporter::crash_reporter_get_config,
+            commands::crash_reporter::crash_reporter_set_config,
+            commands::file_ops::search_files,
+            commands::file_ops::hash_file,
             // 状态查询命令
             commands::state::maa_get_instance_state,
             commands::state::maa_get_all_states,
             commands::state::maa_get_cached_adb_devices,
             commands::state::maa_get_cached_win32_windows,
+            // 设备分组命令
+            commands::device_groups::device_groups_list,
+            commands::device_groups::device_group_save,
+            commands::device_groups::device_group_delete,
+            commands::device_groups::device_group_status,
+            commands::device_groups::device_group_connect,
+            commands::device_groups::device_group_stop,
+            commands::device_groups::device_group_run_profile,
+            commands::device_lock::device_lock_get_policy,
+            commands::device_lock::device_lock_set_policy,
+            commands::device_lock::device_lock_check,
+            // 周报命令
+            commands::digest::digest_list,
+            commands::digest::digest_save,
+            commands::digest::digest_delete,
+            commands::digest::digest_preview,
+            commands::digest::digest_send_now,
+            // 窗口预览命令
+            commands::window_preview::maa_get_win32_thumbnails,
+            // scrcpy 预览命令
+            commands::scrcpy::scrcpy_is_available,
+            commands::scrcpy::scrcpy_start,
+            commands::scrcpy::scrcpy_stop,
             // 更新安装命令
             commands::update::extract_zip,
             commands::update::check_changes_json,
             commands::update::apply_incremental_update,
             commands::update::apply_full_update,
             commands::update::cleanup_extract_dir,
+            commands::app_update::app_update_download,
+            commands::app_update::app_update_apply,
             commands::update::fallback_update,
             commands::update::move_file_to_old,
+            commands::update::update_staging_list,
+            commands::update::update_staging_preview,
+            commands::integrity::install_manifest_generate,
+            commands::integrity::verify_install,
+            commands::integrity::repair_install,
+            // 拖放安装命令
+            commands::package_install::install_dropped_package,
             // 下载命令
             commands::download::get_github_release_by_version,
             commands::download::download_file,
@@ -175,23 +465,80 @@ pub fn run() {
             commands::system::is_autostart,
             commands::system::restart_as_admin,
             commands::system::maa_set_save_draw,
+            commands::system::maa_list_global_options,
+            commands::system::maa_set_global_option,
             commands::system::open_file,
             commands::system::run_and_wait,
             commands::system::run_action,
             commands::system::is_process_running,
             commands::system::retry_load_maa_library,
+            commands::system::reload_maa_library,
             commands::system::check_vcredist_missing,
+            commands::vcredist::vcredist_install,
+            commands::diagnostics::run_diagnostics,
             commands::system::autostart_enable,
             commands::system::autostart_disable,
             commands::system::autostart_is_enabled,
+            commands::legacy_cleanup::cleanup_legacy_install,
             commands::system::get_arch,
             commands::system::get_os,
             commands::system::get_system_info,
+            commands::capabilities::get_capabilities,
+            commands::i18n::i18n_set_language,
+            commands::monitor::get_process_metrics,
+            commands::power::get_power_settings,
+            commands::power::set_power_settings,
+            commands::log_config::get_log_config,
+            commands::log_config::set_log_config,
+            // 剪贴板命令
+            commands::clipboard::clipboard_read_text,
+            commands::clipboard::clipboard_write_text,
+            commands::clipboard::clipboard_read_image,
+            // 语音播报命令
+            commands::tts::tts_set_config,
+            commands::tts::tts_get_config,
+            commands::tts::tts_speak,
+            commands::tts::tts_list_voices,
             // 托盘相关命令
             commands::tray::set_minimize_to_tray,
             commands::tray::get_minimize_to_tray,
             commands::tray::update_tray_icon,
             commands::tray::update_tray_tooltip,
+            commands::tray::set_tray_status,
+            commands::tray::set_tray_icon_style,
+            commands::tray::get_tray_icon_style,
+            // 启动动作
+            commands::startup_actions::startup_actions_get,
+            commands::startup_actions::startup_actions_set,
+            commands::startup_actions::startup_actions_results,
+            // 心跳与 WebView 看门狗
+            commands::heartbeat::heartbeat_ack,
+            commands::heartbeat::get_heartbeat_stats,
+            commands::webview_watchdog::webview_watchdog_get_config,
+            commands::webview_watchdog::webview_watchdog_set_config,
+            // MaaFramework 调用超时与实例恢复
+            commands::ffi_guard::get_instance_health,
+            commands::ffi_guard::recover_instance,
+            commands::ffi_guard::get_ffi_timeouts,
+            commands::ffi_guard::set_ffi_timeouts,
+            // 定时唤醒
+            commands::wake_timer::wake_timer_sync_schedule,
+            commands::wake_timer::wake_timer_get_settings,
+            commands::wake_timer::wake_timer_set_settings,
+            commands::wake_timer::wake_timer_status,
+            // 后台模式
+            commands::background_mode::background_mode_get_settings,
+            commands::background_mode::background_mode_set_settings,
+            commands::background_mode::background_mode_is_active,
+            // 截图打码
+            commands::screenshot_redaction::get_redaction_config,
+            commands::screenshot_redaction::set_redaction_config,
+            commands::screenshot_redaction::preview_redaction,
+            // 全局快捷键
+            commands::hotkeys::hotkeys_get,
+            commands::hotkeys::hotkeys_bind,
+            commands::hotkeys::hotkeys_unbind,
+            commands::hotkeys::hotkeys_set_enabled,
         ])
         .on_window_event(|window, event| {
             match event {
@@ -201,11 +548,25 @@ pub fn run() {
                         api.prevent_close();
                     }
                 }
+                // 文件拖放：识别安装包并通知前端确认
+                tauri::WindowEvent::DragDrop(tauri::DragDropEvent::Drop { paths, .. }) => {
+                    commands::package_install::handle_dropped_paths(window.app_handle(), paths);
+                }
+                // 系统主题变化：切换托盘图标明暗变体
+                tauri::WindowEvent::ThemeChanged(_) => {
+                    tray::refresh_tray_theme();
+                }
+                // 窗口获得焦点：用户已查看，清除任务栏错误状态
+                tauri::WindowEvent::Focused(true) => {
+                    taskbar::clear_errors();
+                }
                 // 窗口销毁时清理所有 agent 子进程
                 tauri::WindowEvent::Destroyed => {
                     if let Some(state) = window.try_state::<Arc<MaaState>>() {
                         state.cleanup_all_agent_children();
                     }
+                    commands::device_lock::release_all();
+                    commands::scrcpy::stop_all_sessions();
                 }
                 _ => {}
             }
@@ -5,8 +5,9 @@
 mod webview2;
 
 fn main() {
+    // 命令行导出/导入设置时不需要 WebView 与管理员权限
     #[cfg(target_os = "windows")]
-    {
+    if !mxu_lib::cli::is_headless_invocation() {
         // 设置 WebView2 数据目录为程序所在目录下的 webview_data 文件夹
         // 这样可以避免用户名包含特殊字符（如中文）导致 WebView2 无法创建数据目录的问题
         if let Ok(exe_path) = std::env::current_exe() {
@@ -25,6 +26,11 @@ fn main() {
         // 启动时自动请求管理员权限：如果当前不是管理员，则自提权重启并退出当前进程
         // 说明：用户取消 UAC 时 ShellExecuteW 会失败，此时继续以普通权限启动。
         // 调试模式下不请求管理员权限，方便开发调试
+        // 开启单实例时，通过 mxu:// 链接启动且已有实例确认收到链接后直接退出，不请求提权；
+        // 没有运行中的实例时本进程会成为主实例，照常提权
+        if mxu_lib::cli::forward_deep_link_to_running_instance() {
+            std::process::exit(0);
+        }
         if !cfg!(debug_assertions) && !mxu_lib::commands::system::is_elevated() {
             use std::ffi::OsStr;
             use std::os::windows::ffi::OsStrExt;
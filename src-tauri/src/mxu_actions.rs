@@ -1,11 +1,46 @@
 //! MXU 内置 Custom Actions
 //!
-//! 提供 MXU 特有的自定义动作实现，如 MXU_SLEEP 等
+//! 提供 MXU 特有的自定义动作实现，如 MXU_SLEEP 等。
+//! 动作通过 ActionEnv 获取执行环境，因此也可以脱离 pipeline 测试调用（见 test_action）
 
 use chrono::TimeZone;
 use log::{info, warn};
 use maa_framework::custom::FnAction;
 use maa_framework::resource::Resource;
+use std::cell::RefCell;
+
+use crate::commands::types::{ActionCapability, ActionParamSchema, ActionTestResult};
+
+/// 动作执行环境：pipeline 中由 Context 提供，测试调用时为模拟环境
+struct ActionEnv<'a> {
+    task_id: i64,
+    /// 是否已请求停止
+    stopping: &'a dyn Fn() -> bool,
+    /// 只校验参数并记录将要执行的操作，不产生副作用
+    dry_run: bool,
+    /// 测试调用时返回给前端的说明
+    notes: RefCell<Vec<String>>,
+}
+
+impl ActionEnv<'_> {
+    fn note(&self, message: String) {
+        info!("{}", message);
+        self.notes.borrow_mut().push(message);
+    }
+
+    fn warn(&self, message: String) {
+        warn!("{}", message);
+        self.notes.borrow_mut().push(message);
+    }
+
+    fn error(&self, message: String) {
+        log::error!("{}", message);
+        self.notes.borrow_mut().push(message);
+    }
+}
+
+/// 动作实现：参数为 custom_action_param
+type ActionFn = fn(&str, &ActionEnv) -> bool;
 
 // ============================================================================
 // MXU_SLEEP Custom Action
@@ -29,13 +64,13 @@ fn is_tasker_stopping(ctx: &maa_framework::context::Context) -> bool {
         .unwrap_or(false)
 }
 
-fn wait_with_stop_check(ctx: &maa_framework::context::Context, total_secs: u64) -> bool {
+fn wait_with_stop_check(env: &ActionEnv, total_secs: u64) -> bool {
     const STEP: std::time::Duration = std::time::Duration::from_millis(200);
     let total = std::time::Duration::from_secs(total_secs);
     let start = std::time::Instant::now();
 
     while start.elapsed() < total {
-        if is_tasker_stopping(ctx) {
+        if (env.stopping)() {
             info!("[MXU_WAIT] Stop requested, interrupting wait");
             return false;
         }
@@ -46,30 +81,34 @@ fn wait_with_stop_check(ctx: &maa_framework::context::Context, total_secs: u64)
     true
 }
 
-fn mxu_sleep_action_fn(
-    ctx: &maa_framework::context::Context,
-    args: &maa_framework::custom::ActionArgs,
-) -> bool {
-    let param_str = args.param;
+fn mxu_sleep_action_fn(param_str: &str, env: &ActionEnv) -> bool {
     info!("[MXU_SLEEP] Received param: {}", param_str);
 
     // 解析 JSON 获取 sleep_time
     let sleep_seconds: u64 = match serde_json::from_str::<serde_json::Value>(param_str) {
         Ok(json) => json.get("sleep_time").and_then(|v| v.as_u64()).unwrap_or(5),
         Err(e) => {
-            warn!(
+            env.warn(format!(
                 "[MXU_SLEEP] Failed to parse param JSON: {}, using default 5s",
                 e
-            );
+            ));
             5
         }
     };
 
+    if env.dry_run {
+        env.note(format!(
+            "[MXU_SLEEP] Would sleep for {} seconds",
+            sleep_seconds
+        ));
+        return true;
+    }
+
     info!("[MXU_SLEEP] Sleeping for {} seconds...", sleep_seconds);
 
     // 执行可中断睡眠（响应 stop）
-    if !wait_with_stop_check(ctx, sleep_seconds) {
-        warn!("[MXU_SLEEP] Interrupted by stop request");
+    if !wait_with_stop_check(env, sleep_seconds) {
+        env.warn("[MXU_SLEEP] Interrupted by stop request".to_string());
         return false;
     }
 
@@ -87,15 +126,11 @@ const MXU_WAITUNTIL_ACTION: &str = "MXU_WAITUNTIL_ACTION";
 /// MXU_WAITUNTIL custom action 回调函数
 /// 从 custom_action_param 中读取 target_time（HH:MM 格式），等待到该时间点
 /// 仅支持 24 小时内：若目标时间已过则等待到次日该时间
-fn mxu_waituntil_action_fn(
-    ctx: &maa_framework::context::Context,
-    args: &maa_framework::custom::ActionArgs,
-) -> bool {
-    let param_str = args.param;
+fn mxu_waituntil_action_fn(param_str: &str, env: &ActionEnv) -> bool {
     info!("[MXU_WAITUNTIL] Received param: {}", param_str);
 
     let Ok(json) = serde_json::from_str::<serde_json::Value>(param_str) else {
-        warn!("[MXU_WAITUNTIL] Failed to parse param JSON");
+        env.warn("[MXU_WAITUNTIL] Failed to parse param JSON".to_string());
         return false;
     };
 
@@ -104,7 +139,7 @@ fn mxu_waituntil_action_fn(
         .and_then(|v| v.as_str())
         .filter(|s| !s.trim().is_empty())
     else {
-        warn!("[MXU_WAITUNTIL] Missing or empty 'target_time' parameter");
+        env.warn("[MXU_WAITUNTIL] Missing or empty 'target_time' parameter".to_string());
         return false;
     };
     let target_time = target_time.to_string();
@@ -112,14 +147,17 @@ fn mxu_waituntil_action_fn(
     // 解析 HH:MM 格式
     let parts: Vec<&str> = target_time.split(':').collect();
     if parts.len() < 2 {
-        warn!("[MXU_WAITUNTIL] Invalid time format: {}", target_time);
+        env.warn(format!(
+            "[MXU_WAITUNTIL] Invalid time format: {}",
+            target_time
+        ));
         return false;
     }
 
     let target_hour: u32 = match parts[0].parse() {
         Ok(h) if h < 24 => h,
         _ => {
-            warn!("[MXU_WAITUNTIL] Invalid hour: {}", parts[0]);
+            env.warn(format!("[MXU_WAITUNTIL] Invalid hour: {}", parts[0]));
             return false;
         }
     };
@@ -127,7 +165,7 @@ fn mxu_waituntil_action_fn(
     let target_minute: u32 = match parts[1].parse() {
         Ok(m) if m < 60 => m,
         _ => {
-            warn!("[MXU_WAITUNTIL] Invalid minute: {}", parts[1]);
+            env.warn(format!("[MXU_WAITUNTIL] Invalid minute: {}", parts[1]));
             return false;
         }
     };
@@ -135,20 +173,20 @@ fn mxu_waituntil_action_fn(
     // 计算当前时间与目标时间的差值
     let now = chrono::Local::now();
     let Some(today_target) = now.date_naive().and_hms_opt(target_hour, target_minute, 0) else {
-        warn!(
+        env.warn(format!(
             "[MXU_WAITUNTIL] Invalid target time {:02}:{:02}",
             target_hour, target_minute
-        );
+        ));
         return false;
     };
 
     let today_target = match chrono::Local.from_local_datetime(&today_target).single() {
         Some(dt) => dt,
         None => {
-            warn!(
+            env.warn(format!(
                 "[MXU_WAITUNTIL] Ambiguous or invalid local time for target {:02}:{:02} (e.g. due to DST transition)",
                 target_hour, target_minute
-            );
+            ));
             return false;
         }
     };
@@ -162,13 +200,20 @@ fn mxu_waituntil_action_fn(
     };
 
     let wait_secs = wait_duration.num_seconds().max(0) as u64;
+    if env.dry_run {
+        env.note(format!(
+            "[MXU_WAITUNTIL] Would wait until {}:{:02} ({}s from now)",
+            target_hour, target_minute, wait_secs
+        ));
+        return true;
+    }
     info!(
         "[MXU_WAITUNTIL] Waiting until {}:{:02} ({}s from now)",
         target_hour, target_minute, wait_secs
     );
 
-    if !wait_with_stop_check(ctx, wait_secs) {
-        warn!("[MXU_WAITUNTIL] Interrupted by stop request");
+    if !wait_with_stop_check(env, wait_secs) {
+        env.warn("[MXU_WAITUNTIL] Interrupted by stop request".to_string());
         return false;
     }
 
@@ -185,17 +230,13 @@ const MXU_LAUNCH_ACTION: &str = "MXU_LAUNCH_ACTION";
 
 /// MXU_LAUNCH custom action 回调函数
 /// 从 custom_action_param 中读取 program, args, wait_for_exit，启动外部程序
-fn mxu_launch_action_fn(
-    _ctx: &maa_framework::context::Context,
-    args: &maa_framework::custom::ActionArgs,
-) -> bool {
-    let param_str = args.param;
+fn mxu_launch_action_fn(param_str: &str, env: &ActionEnv) -> bool {
     info!("[MXU_LAUNCH] Received param: {}", param_str);
 
     let json: serde_json::Value = match serde_json::from_str(param_str) {
         Ok(v) => v,
         Err(e) => {
-            warn!("[MXU_LAUNCH] Failed to parse param JSON: {}", e);
+            env.warn(format!("[MXU_LAUNCH] Failed to parse param JSON: {}", e));
             return false;
         }
     };
@@ -203,7 +244,7 @@ fn mxu_launch_action_fn(
     let program = match json.get("program").and_then(|v| v.as_str()) {
         Some(p) if !p.trim().is_empty() => p.to_string(),
         _ => {
-            warn!("[MXU_LAUNCH] Missing or empty 'program' parameter");
+            env.warn("[MXU_LAUNCH] Missing or empty 'program' parameter".to_string());
             return false;
         }
     };
@@ -225,7 +266,7 @@ fn mxu_launch_action_fn(
         .unwrap_or(false);
 
     // 如果启用了跳过检查且程序已在运行，直接返回成功
-    if skip_if_running {
+    if skip_if_running && !env.dry_run {
         if crate::commands::system::check_process_running(&program) {
             info!(
                 "[MXU_LAUNCH] Program '{}' is already running, skipping launch",
@@ -246,15 +287,28 @@ fn mxu_launch_action_fn(
         match shell_words::split(&args_str) {
             Ok(parsed) => parsed,
             Err(e) => {
-                warn!(
+                env.warn(format!(
                     "[MXU_LAUNCH] Failed to parse arguments with shell_words ({}); falling back to whitespace split: {}",
                     e, args_str
-                );
+                ));
                 args_str.split_whitespace().map(|s| s.to_string()).collect()
             }
         }
     };
 
+    if env.dry_run {
+        let path = std::path::Path::new(&program);
+        if path.is_absolute() && !path.exists() {
+            env.warn(format!("[MXU_LAUNCH] Program not found: {}", program));
+            return false;
+        }
+        env.note(format!(
+            "[MXU_LAUNCH] Would launch: program={}, args={:?}, wait_for_exit={}, skip_if_running={}",
+            program, args_vec, wait_for_exit, skip_if_running
+        ));
+        return true;
+    }
+
     let mut cmd = std::process::Command::new(&program);
 
     if !args_vec.is_empty() {
@@ -276,7 +330,7 @@ fn mxu_launch_action_fn(
                 true
             }
             Err(e) => {
-                log::error!("[MXU_LAUNCH] Failed to run program: {}", e);
+                env.error(format!("[MXU_LAUNCH] Failed to run program: {}", e));
                 false
             }
         }
@@ -287,7 +341,7 @@ fn mxu_launch_action_fn(
                 true
             }
             Err(e) => {
-                log::error!("[MXU_LAUNCH] Failed to spawn program: {}", e);
+                env.error(format!("[MXU_LAUNCH] Failed to spawn program: {}", e));
                 false
             }
         }
@@ -303,17 +357,13 @@ const MXU_WEBHOOK_ACTION: &str = "MXU_WEBHOOK_ACTION";
 
 /// MXU_WEBHOOK custom action 回调函数
 /// 从 custom_action_param 中读取 url，执行 HTTP GET 请求
-fn mxu_webhook_action_fn(
-    _ctx: &maa_framework::context::Context,
-    args: &maa_framework::custom::ActionArgs,
-) -> bool {
-    let param_str = args.param;
+fn mxu_webhook_action_fn(param_str: &str, env: &ActionEnv) -> bool {
     info!("[MXU_WEBHOOK] Received param: {}", param_str);
 
     let json: serde_json::Value = match serde_json::from_str(param_str) {
         Ok(v) => v,
         Err(e) => {
-            warn!("[MXU_WEBHOOK] Failed to parse param JSON: {}", e);
+            env.warn(format!("[MXU_WEBHOOK] Failed to parse param JSON: {}", e));
             return false;
         }
     };
@@ -321,11 +371,20 @@ fn mxu_webhook_action_fn(
     let url = match json.get("url").and_then(|v| v.as_str()) {
         Some(u) if !u.trim().is_empty() => u.to_string(),
         _ => {
-            warn!("[MXU_WEBHOOK] Missing or empty 'url' parameter");
+            env.warn("[MXU_WEBHOOK] Missing or empty 'url' parameter".to_string());
             return false;
         }
     };
 
+    if env.dry_run {
+        if let Err(e) = reqwest::Url::parse(&url) {
+            env.warn(format!("[MXU_WEBHOOK] Invalid url {}: {}", url, e));
+            return false;
+        }
+        env.note(format!("[MXU_WEBHOOK] Would send GET request to: {}", url));
+        return true;
+    }
+
     info!("[MXU_WEBHOOK] Sending GET request to: {}", url);
 
     let client = match reqwest::blocking::Client::builder()
@@ -334,7 +393,7 @@ fn mxu_webhook_action_fn(
     {
         Ok(c) => c,
         Err(e) => {
-            log::error!("[MXU_WEBHOOK] Failed to build HTTP client: {}", e);
+            env.error(format!("[MXU_WEBHOOK] Failed to build HTTP client: {}", e));
             return false;
         }
     };
@@ -346,12 +405,12 @@ fn mxu_webhook_action_fn(
             if status.is_success() {
                 true
             } else {
-                warn!("[MXU_WEBHOOK] Non-success status code: {}", status);
+                env.warn(format!("[MXU_WEBHOOK] Non-success status code: {}", status));
                 true // 仍然返回成功，只要请求发出去了
             }
         }
         Err(e) => {
-            log::error!("[MXU_WEBHOOK] Request failed: {}", e);
+            env.error(format!("[MXU_WEBHOOK] Request failed: {}", e));
             false
         }
     }
@@ -365,18 +424,19 @@ fn mxu_webhook_action_fn(
 const MXU_NOTIFY_ACTION: &str = "MXU_NOTIFY_ACTION";
 
 /// MXU_NOTIFY custom action 回调函数
-/// 从 custom_action_param 中读取 title, body，发送系统通知
-fn mxu_notify_action_fn(
-    _ctx: &maa_framework::context::Context,
-    args: &maa_framework::custom::ActionArgs,
-) -> bool {
-    let param_str = args.param;
+/// 从 custom_action_param 中读取 title, body, actions，发送系统通知
+/// actions 为通知按钮列表（open / stop_tasks / view_screenshot），默认只有「打开 MXU」；
+/// 包含 view_screenshot 时附带当前实例的最近一帧截图
+fn mxu_notify_action_fn(param_str: &str, env: &ActionEnv) -> bool {
+    use crate::commands::notifications;
+    use crate::commands::types::ToastAction;
+
     info!("[MXU_NOTIFY] Received param: {}", param_str);
 
     let json: serde_json::Value = match serde_json::from_str(param_str) {
         Ok(v) => v,
         Err(e) => {
-            warn!("[MXU_NOTIFY] Failed to parse param JSON: {}", e);
+            env.warn(format!("[MXU_NOTIFY] Failed to parse param JSON: {}", e));
             return false;
         }
     };
@@ -393,22 +453,43 @@ fn mxu_notify_action_fn(
         .unwrap_or("")
         .to_string();
 
+    let actions: Vec<ToastAction> = match json.get("actions") {
+        Some(v) => match serde_json::from_value(v.clone()) {
+            Ok(actions) => actions,
+            Err(e) => {
+                env.warn(format!("[MXU_NOTIFY] Invalid actions: {}", e));
+                return false;
+            }
+        },
+        None => vec![ToastAction::Open],
+    };
+
+    let screenshot = if actions.contains(&ToastAction::ViewScreenshot) {
+        notifications::save_task_screenshot(env.task_id)
+    } else {
+        None
+    };
+
+    if env.dry_run {
+        env.note(format!(
+            "[MXU_NOTIFY] Would send notification: title={}, body={}, actions={:?}",
+            title, body, actions
+        ));
+        return true;
+    }
+
     info!(
-        "[MXU_NOTIFY] Sending notification: title={}, body={}",
-        title, body
+        "[MXU_NOTIFY] Sending notification: title={}, body={}, actions={:?}",
+        title, body, actions
     );
 
-    match notify_rust::Notification::new()
-        .summary(&title)
-        .body(&body)
-        .show()
-    {
-        Ok(_) => {
+    match notifications::show(&title, &body, &actions, screenshot) {
+        Ok(()) => {
             info!("[MXU_NOTIFY] Notification sent successfully");
             true
         }
         Err(e) => {
-            log::error!("[MXU_NOTIFY] Failed to send notification: {}", e);
+            env.error(format!("[MXU_NOTIFY] Failed to send notification: {}", e));
             false
         }
     }
@@ -423,17 +504,13 @@ const MXU_KILLPROC_ACTION: &str = "MXU_KILLPROC_ACTION";
 
 /// MXU_KILLPROC custom action 回调函数
 /// 从 custom_action_param 中读取 kill_self, process_name，结束进程
-fn mxu_killproc_action_fn(
-    _ctx: &maa_framework::context::Context,
-    args: &maa_framework::custom::ActionArgs,
-) -> bool {
-    let param_str = args.param;
+fn mxu_killproc_action_fn(param_str: &str, env: &ActionEnv) -> bool {
     info!("[MXU_KILLPROC] Received param: {}", param_str);
 
     let json: serde_json::Value = match serde_json::from_str(param_str) {
         Ok(v) => v,
         Err(e) => {
-            warn!("[MXU_KILLPROC] Failed to parse param JSON: {}", e);
+            env.warn(format!("[MXU_KILLPROC] Failed to parse param JSON: {}", e));
             return false;
         }
     };
@@ -443,6 +520,24 @@ fn mxu_killproc_action_fn(
         .and_then(|v| v.as_bool())
         .unwrap_or(true);
 
+    if env.dry_run {
+        let target = if kill_self {
+            "MXU itself".to_string()
+        } else {
+            match json.get("process_name").and_then(|v| v.as_str()) {
+                Some(p) if !p.trim().is_empty() => p.to_string(),
+                _ => {
+                    env.warn(
+                        "[MXU_KILLPROC] Missing or empty 'process_name' parameter".to_string(),
+                    );
+                    return false;
+                }
+            }
+        };
+        env.note(format!("[MXU_KILLPROC] Would kill process: {}", target));
+        return true;
+    }
+
     if kill_self {
         info!("[MXU_KILLPROC] Killing self process");
         // 获取当前可执行文件名
@@ -454,14 +549,17 @@ fn mxu_killproc_action_fn(
             info!("[MXU_KILLPROC] Current exe: {}", name);
             kill_process_by_name(&name)
         } else {
-            warn!("[MXU_KILLPROC] Could not determine current exe name, using process::exit");
+            env.warn(
+                "[MXU_KILLPROC] Could not determine current exe name, using process::exit"
+                    .to_string(),
+            );
             std::process::exit(0);
         }
     } else {
         let process_name = match json.get("process_name").and_then(|v| v.as_str()) {
             Some(p) if !p.trim().is_empty() => p.to_string(),
             _ => {
-                warn!("[MXU_KILLPROC] Missing or empty 'process_name' parameter");
+                env.warn("[MXU_KILLPROC] Missing or empty 'process_name' parameter".to_string());
                 return false;
             }
         };
@@ -538,18 +636,17 @@ fn kill_process_by_name(name: &str) -> bool {
 const MXU_POWER_ACTION: &str = "MXU_POWER_ACTION";
 
 /// MXU_POWER custom action 回调函数
-/// 从 custom_action_param 中读取 power_action，执行关机/重启/息屏/睡眠操作
-fn mxu_power_action_fn(
-    _ctx: &maa_framework::context::Context,
-    args: &maa_framework::custom::ActionArgs,
-) -> bool {
-    let param_str = args.param;
+/// 从 custom_action_param 中读取 power_action，经完成后操作的可取消倒计时执行关机/重启/息屏/睡眠
+fn mxu_power_action_fn(param_str: &str, env: &ActionEnv) -> bool {
+    use crate::commands::post_actions;
+    use crate::commands::types::PostActionKind;
+
     info!("[MXU_POWER] Received param: {}", param_str);
 
     let json: serde_json::Value = match serde_json::from_str(param_str) {
         Ok(v) => v,
         Err(e) => {
-            warn!("[MXU_POWER] Failed to parse param JSON: {}", e);
+            env.warn(format!("[MXU_POWER] Failed to parse param JSON: {}", e));
             return false;
         }
     };
@@ -559,21 +656,37 @@ fn mxu_power_action_fn(
         .and_then(|v| v.as_str())
         .unwrap_or("shutdown");
 
-    info!("[MXU_POWER] Executing power action: {}", action);
-
-    match action {
-        "shutdown" => execute_power_shutdown(),
-        "restart" => execute_power_restart(),
-        "screenoff" => execute_power_screenoff(),
-        "sleep" => execute_power_sleep(),
+    let kind = match action {
+        "shutdown" => PostActionKind::Shutdown,
+        "restart" => PostActionKind::Restart,
+        "screenoff" => PostActionKind::ScreenOff,
+        "sleep" => PostActionKind::Sleep,
         _ => {
-            warn!("[MXU_POWER] Unknown power action: {}", action);
-            false
+            env.warn(format!("[MXU_POWER] Unknown power action: {}", action));
+            return false;
         }
+    };
+
+    if env.dry_run {
+        env.note(format!(
+            "[MXU_POWER] Would execute power action: {}",
+            action
+        ));
+        return true;
+    }
+
+    info!("[MXU_POWER] Requesting power action: {}", action);
+    if !post_actions::request_power_action(kind) {
+        env.warn(format!(
+            "[MXU_POWER] Failed to start countdown for power action: {}",
+            action
+        ));
+        return false;
     }
+    true
 }
 
-fn execute_power_shutdown() -> bool {
+pub(crate) fn execute_power_shutdown() -> bool {
     use std::process::Command;
 
     #[cfg(windows)]
@@ -625,7 +738,7 @@ fn execute_power_shutdown() -> bool {
     }
 }
 
-fn execute_power_restart() -> bool {
+pub(crate) fn execute_power_restart() -> bool {
     use std::process::Command;
 
     #[cfg(windows)]
@@ -677,7 +790,7 @@ fn execute_power_restart() -> bool {
     }
 }
 
-fn execute_power_screenoff() -> bool {
+pub(crate) fn execute_power_screenoff() -> bool {
     #[cfg(windows)]
     {
         use windows::Win32::Foundation::HWND;
@@ -730,7 +843,7 @@ fn execute_power_screenoff() -> bool {
     }
 }
 
-fn execute_power_sleep() -> bool {
+pub(crate) fn execute_power_sleep() -> bool {
     use std::process::Command;
 
     #[cfg(windows)]
@@ -779,10 +892,345 @@ fn execute_power_sleep() -> bool {
     }
 }
 
+// ============================================================================
+// MXU_SETVAR Custom Action
+// ============================================================================
+
+/// MXU_SETVAR 动作名称常量
+const MXU_SETVAR_ACTION: &str = "MXU_SETVAR_ACTION";
+
+/// MXU_SETVAR custom action 回调函数
+/// 从 custom_action_param 中读取 name, value，写入任务变量存储（供任务队列条件判断）
+fn mxu_setvar_action_fn(param_str: &str, env: &ActionEnv) -> bool {
+    info!("[MXU_SETVAR] Received param: {}", param_str);
+
+    let json: serde_json::Value = match serde_json::from_str(param_str) {
+        Ok(v) => v,
+        Err(e) => {
+            env.warn(format!("[MXU_SETVAR] Failed to parse param JSON: {}", e));
+            return false;
+        }
+    };
+
+    let name = match json.get("name").and_then(|v| v.as_str()) {
+        Some(n) if !n.trim().is_empty() => n.to_string(),
+        _ => {
+            env.warn("[MXU_SETVAR] Missing or empty 'name' parameter".to_string());
+            return false;
+        }
+    };
+
+    let value = json
+        .get("value")
+        .cloned()
+        .unwrap_or(serde_json::Value::Bool(true));
+
+    if env.dry_run {
+        env.note(format!(
+            "[MXU_SETVAR] Would set variable {} = {}",
+            name, value
+        ));
+        return true;
+    }
+
+    // 运行记录创建失败时任务不属于任何运行，无法确定写入哪个实例
+    let Some(instance_id) = crate::commands::runs::instance_for_task(env.task_id) else {
+        env.warn("[MXU_SETVAR] No instance associated with task".to_string());
+        return false;
+    };
+    crate::commands::variables::set_variable(&instance_id, &name, value);
+    true
+}
+
+// ============================================================================
+// MXU_SCRIPT Custom Action
+// ============================================================================
+
+/// MXU_SCRIPT 动作名称常量
+const MXU_SCRIPT_ACTION: &str = "MXU_SCRIPT_ACTION";
+
+/// MXU_SCRIPT custom action 回调函数
+/// 从 custom_action_param 中读取 script（内联源码）或 file（脚本文件）、param、timeout，
+/// 以任务所属实例执行 Rhai 脚本（见 commands::scripting），脚本返回 false 时动作失败
+fn mxu_script_action_fn(param_str: &str, env: &ActionEnv) -> bool {
+    use crate::commands::scripting;
+
+    info!("[MXU_SCRIPT] Received param: {}", param_str);
+
+    let json: serde_json::Value = match serde_json::from_str(param_str) {
+        Ok(v) => v,
+        Err(e) => {
+            env.warn(format!("[MXU_SCRIPT] Failed to parse param JSON: {}", e));
+            return false;
+        }
+    };
+
+    let source = match scripting::load_source(
+        json.get("script").and_then(|v| v.as_str()),
+        json.get("file").and_then(|v| v.as_str()),
+    ) {
+        Ok(source) => source,
+        Err(e) => {
+            env.warn(format!("[MXU_SCRIPT] {}", e));
+            return false;
+        }
+    };
+
+    if env.dry_run {
+        return match scripting::check(&source) {
+            Ok(()) => {
+                env.note(format!(
+                    "[MXU_SCRIPT] Script compiled, would run {} bytes of script",
+                    source.len()
+                ));
+                true
+            }
+            Err(e) => {
+                env.warn(format!("[MXU_SCRIPT] {}", e));
+                false
+            }
+        };
+    }
+
+    let timeout = scripting::timeout_from(json.get("timeout").and_then(|v| v.as_u64()));
+    let instance_id = crate::commands::runs::instance_for_task(env.task_id);
+    let outcome = scripting::execute(
+        &source,
+        instance_id,
+        json.get("param")
+            .cloned()
+            .unwrap_or(serde_json::Value::Null),
+        timeout,
+        env.stopping,
+    );
+    for line in &outcome.logs {
+        env.note(format!("[MXU_SCRIPT] {}", line));
+    }
+    if let Some(e) = &outcome.error {
+        env.error(format!("[MXU_SCRIPT] {}", e));
+    }
+    outcome.succeeded()
+}
+
+// ============================================================================
+// MXU_QRCODE Custom Action
+// ============================================================================
+
+/// MXU_QRCODE 动作名称常量
+const MXU_QRCODE_ACTION: &str = "MXU_QRCODE_ACTION";
+
+/// MXU_QRCODE custom action 回调函数
+/// 从 custom_action_param 中读取 roi（[x, y, w, h]，默认整张截图）、variable（默认 qrcode）与 expected（正则，可选），
+/// 识别最近一帧截图中的二维码，将内容写入任务变量并发送 qrcode-detected 事件；未识别到时动作失败
+fn mxu_qrcode_action_fn(param_str: &str, env: &ActionEnv) -> bool {
+    use crate::commands::qrcode_scan;
+
+    info!("[MXU_QRCODE] Received param: {}", param_str);
+
+    let json: serde_json::Value = match serde_json::from_str(param_str) {
+        Ok(v) => v,
+        Err(e) => {
+            env.warn(format!("[MXU_QRCODE] Failed to parse param JSON: {}", e));
+            return false;
+        }
+    };
+
+    let roi: [i32; 4] = match json.get("roi") {
+        Some(v) => match serde_json::from_value(v.clone()) {
+            Ok(roi) => roi,
+            Err(e) => {
+                env.warn(format!("[MXU_QRCODE] Invalid roi: {}", e));
+                return false;
+            }
+        },
+        None => [0, 0, 0, 0],
+    };
+    let variable = json
+        .get("variable")
+        .and_then(|v| v.as_str())
+        .unwrap_or("qrcode")
+        .to_string();
+    let expected = match json.get("expected").and_then(|v| v.as_str()) {
+        Some(pattern) => match regex::Regex::new(pattern) {
+            Ok(re) => Some(re),
+            Err(e) => {
+                env.warn(format!(
+                    "[MXU_QRCODE] Invalid expected pattern {}: {}",
+                    pattern, e
+                ));
+                return false;
+            }
+        },
+        None => None,
+    };
+
+    if env.dry_run {
+        env.note(format!(
+            "[MXU_QRCODE] Would scan QR code in roi {:?} and store it in variable {}",
+            roi, variable
+        ));
+        return true;
+    }
+
+    let Some(instance_id) = crate::commands::runs::instance_for_task(env.task_id) else {
+        env.warn("[MXU_QRCODE] No instance associated with task".to_string());
+        return false;
+    };
+    let codes = match qrcode_scan::scan_instance(&instance_id, roi) {
+        Ok(codes) => codes,
+        Err(e) => {
+            env.error(format!("[MXU_QRCODE] Failed to scan screenshot: {}", e));
+            return false;
+        }
+    };
+
+    match codes.iter().find(|code| {
+        expected
+            .as_ref()
+            .is_none_or(|re| re.is_match(&code.content))
+    }) {
+        Some(code) => {
+            info!("[MXU_QRCODE] Decoded QR code at {:?}", code.rect);
+            let variable = Some(variable.as_str()).filter(|v| !v.is_empty());
+            qrcode_scan::publish(&instance_id, variable, code);
+            true
+        }
+        None => {
+            env.warn(format!(
+                "[MXU_QRCODE] No matching QR code found ({} decoded)",
+                codes.len()
+            ));
+            false
+        }
+    }
+}
+
 // ============================================================================
 // 注册入口
 // ============================================================================
 
+/// 全部 MXU 内置动作
+const ACTIONS: &[(&str, ActionFn)] = &[
+    (MXU_SLEEP_ACTION, mxu_sleep_action_fn),
+    (MXU_WAITUNTIL_ACTION, mxu_waituntil_action_fn),
+    (MXU_LAUNCH_ACTION, mxu_launch_action_fn),
+    (MXU_WEBHOOK_ACTION, mxu_webhook_action_fn),
+    (MXU_NOTIFY_ACTION, mxu_notify_action_fn),
+    (MXU_KILLPROC_ACTION, mxu_killproc_action_fn),
+    (MXU_POWER_ACTION, mxu_power_action_fn),
+    (MXU_SETVAR_ACTION, mxu_setvar_action_fn),
+    (MXU_SCRIPT_ACTION, mxu_script_action_fn),
+    (MXU_QRCODE_ACTION, mxu_qrcode_action_fn),
+];
+
+/// 测试调用时默认只做 dry-run 的动作（有副作用或会长时间阻塞）
+const DRY_RUN_BY_DEFAULT: &[&str] = &[
+    MXU_SLEEP_ACTION,
+    MXU_WAITUNTIL_ACTION,
+    MXU_LAUNCH_ACTION,
+    MXU_KILLPROC_ACTION,
+    MXU_POWER_ACTION,
+    MXU_SCRIPT_ACTION,
+];
+
+/// 测试调用时始终只做 dry-run 的动作（结束进程、关机等无法撤销的操作）
+const ALWAYS_DRY_RUN: &[&str] = &[MXU_KILLPROC_ACTION, MXU_POWER_ACTION];
+
+/// 参数说明：(参数名, 类型, 是否必填, 默认值 JSON)
+type ParamSpec = (&'static str, &'static str, bool, Option<&'static str>);
+
+/// 各动作接受的参数（与各动作的参数解析保持一致）
+const ACTION_PARAMS: &[(&str, &[ParamSpec])] = &[
+    (
+        MXU_SLEEP_ACTION,
+        &[("sleep_time", "integer", false, Some("5"))],
+    ),
+    (
+        MXU_WAITUNTIL_ACTION,
+        &[("target_time", "string", true, None)],
+    ),
+    (
+        MXU_LAUNCH_ACTION,
+        &[
+            ("program", "string", true, None),
+            ("args", "string", false, Some("\"\"")),
+            ("wait_for_exit", "boolean", false, Some("false")),
+            ("skip_if_running", "boolean", false, Some("false")),
+        ],
+    ),
+    (MXU_WEBHOOK_ACTION, &[("url", "string", true, None)]),
+    (
+        MXU_NOTIFY_ACTION,
+        &[
+            ("title", "string", false, Some("\"MXU\"")),
+            ("body", "string", false, Some("\"\"")),
+            ("actions", "array", false, Some("[\"open\"]")),
+        ],
+    ),
+    (
+        MXU_KILLPROC_ACTION,
+        &[
+            ("kill_self", "boolean", false, Some("true")),
+            ("process_name", "string", false, None),
+        ],
+    ),
+    (
+        MXU_POWER_ACTION,
+        &[("power_action", "string", false, Some("\"shutdown\""))],
+    ),
+    (
+        MXU_SETVAR_ACTION,
+        &[
+            ("name", "string", true, None),
+            ("value", "any", false, Some("true")),
+        ],
+    ),
+    (
+        MXU_SCRIPT_ACTION,
+        &[
+            ("script", "string", false, None),
+            ("file", "string", false, None),
+            ("param", "any", false, None),
+            ("timeout", "integer", false, Some("60")),
+        ],
+    ),
+    (
+        MXU_QRCODE_ACTION,
+        &[
+            ("roi", "array", false, Some("[0, 0, 0, 0]")),
+            ("variable", "string", false, Some("\"qrcode\"")),
+            ("expected", "string", false, None),
+        ],
+    ),
+];
+
+/// 内置动作清单（供 get_capabilities 使用）
+pub fn action_manifest() -> Vec<ActionCapability> {
+    ACTIONS
+        .iter()
+        .map(|&(name, _)| {
+            let params = ACTION_PARAMS
+                .iter()
+                .find(|(n, _)| *n == name)
+                .map_or(&[][..], |(_, params)| *params);
+            ActionCapability {
+                name: name.to_string(),
+                params: params
+                    .iter()
+                    .map(|&(param, kind, required, default)| ActionParamSchema {
+                        name: param.to_string(),
+                        kind: kind.to_string(),
+                        required,
+                        default: default.and_then(|d| serde_json::from_str(d).ok()),
+                    })
+                    .collect(),
+                dry_run_by_default: DRY_RUN_BY_DEFAULT.contains(&name),
+                always_dry_run: ALWAYS_DRY_RUN.contains(&name),
+            }
+        })
+        .collect()
+}
+
 /// 为资源注册所有 MXU 内置 custom actions
 /// 在资源创建后调用此函数
 pub fn register_all_mxu_actions(resource: &Resource) -> Result<(), String> {
@@ -794,18 +1242,40 @@ pub fn register_all_mxu_actions(resource: &Resource) -> Result<(), String> {
             let wrapper = move |ctx: &maa_framework::context::Context,
                                 args: &maa_framework::custom::ActionArgs|
                   -> bool {
-                std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| $fn_name(ctx, args)))
-                    .unwrap_or_else(|e| {
-                        let msg = if let Some(s) = e.downcast_ref::<&str>() {
-                            s.to_string()
-                        } else if let Some(s) = e.downcast_ref::<String>() {
-                            s.clone()
-                        } else {
-                            "Unknown panic payload".to_string()
-                        };
-                        log::error!("[MXU] Custom action {} panicked: {}", $name, msg);
-                        false
-                    })
+                crate::commands::runs::run_log_for_task(
+                    args.task_id,
+                    &format!("[action] {} started, param: {}", $name, args.param),
+                );
+                let stopping = || is_tasker_stopping(ctx);
+                let env = ActionEnv {
+                    task_id: args.task_id,
+                    stopping: &stopping,
+                    dry_run: false,
+                    notes: RefCell::new(Vec::new()),
+                };
+                let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                    $fn_name(args.param, &env)
+                }))
+                .unwrap_or_else(|e| {
+                    let msg = if let Some(s) = e.downcast_ref::<&str>() {
+                        s.to_string()
+                    } else if let Some(s) = e.downcast_ref::<String>() {
+                        s.clone()
+                    } else {
+                        "Unknown panic payload".to_string()
+                    };
+                    log::error!("[MXU] Custom action {} panicked: {}", $name, msg);
+                    crate::commands::runs::run_log_for_task(
+                        args.task_id,
+                        &format!("[action] {} panicked: {}", $name, msg),
+                    );
+                    false
+                });
+                crate::commands::runs::run_log_for_task(
+                    args.task_id,
+                    &format!("[action] {} finished, success: {}", $name, result),
+                );
+                result
             };
 
             if let Err(e) = resource.register_custom_action($name, Box::new(FnAction::new(wrapper)))
@@ -818,13 +1288,9 @@ pub fn register_all_mxu_actions(resource: &Resource) -> Result<(), String> {
         };
     }
 
-    reg_action!(MXU_SLEEP_ACTION, mxu_sleep_action_fn);
-    reg_action!(MXU_WAITUNTIL_ACTION, mxu_waituntil_action_fn);
-    reg_action!(MXU_LAUNCH_ACTION, mxu_launch_action_fn);
-    reg_action!(MXU_WEBHOOK_ACTION, mxu_webhook_action_fn);
-    reg_action!(MXU_NOTIFY_ACTION, mxu_notify_action_fn);
-    reg_action!(MXU_KILLPROC_ACTION, mxu_killproc_action_fn);
-    reg_action!(MXU_POWER_ACTION, mxu_power_action_fn);
+    for &(name, action) in ACTIONS {
+        reg_action!(name, action);
+    }
 
     if failed_count > 0 {
         warn!(
@@ -835,3 +1301,44 @@ pub fn register_all_mxu_actions(resource: &Resource) -> Result<(), String> {
 
     Ok(())
 }
+
+/// 在 pipeline 之外调用内置动作（不需要已连接的实例）
+///
+/// name 可省略 _ACTION 后缀；dry_run 未指定时，有副作用或会长时间阻塞的动作只校验参数，
+/// 结束进程与电源操作始终只做 dry-run
+pub fn test_action(
+    name: &str,
+    param: &str,
+    dry_run: Option<bool>,
+) -> Result<ActionTestResult, String> {
+    let full_name = if name.ends_with("_ACTION") {
+        name.to_string()
+    } else {
+        format!("{}_ACTION", name)
+    };
+    let &(action_name, action) = ACTIONS
+        .iter()
+        .find(|(n, _)| *n == full_name)
+        .ok_or_else(|| format!("未知的 MXU 动作: {}", name))?;
+
+    let dry_run = ALWAYS_DRY_RUN.contains(&action_name)
+        || dry_run.unwrap_or(DRY_RUN_BY_DEFAULT.contains(&action_name));
+    info!(
+        "[MXU] Testing custom action {} (dry_run: {})",
+        action_name, dry_run
+    );
+    let env = ActionEnv {
+        task_id: 0,
+        stopping: &|| false,
+        dry_run,
+        notes: RefCell::new(Vec::new()),
+    };
+    let success = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| action(param, &env)))
+        .map_err(|_| format!("动作 {} 执行时发生 panic", action_name))?;
+    Ok(ActionTestResult {
+        action: action_name.to_string(),
+        success,
+        dry_run,
+        notes: env.notes.into_inner(),
+    })
+}
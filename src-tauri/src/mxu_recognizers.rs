@@ -0,0 +1,253 @@
+//! MXU 内置 Custom Recognitions
+//!
+//! 与 mxu_actions 中的内置动作一同注册到资源，为资源作者提供额外的识别原语：
+//! - MXU_PIXELDIFF_RECOGNITION：ROI 画面变化检测，与同一节点上次识别时的画面比较
+//! - MXU_QRCODE_RECOGNITION：识别 ROI 中的二维码，命中时 detail 中包含二维码内容
+
+use image::RgbaImage;
+use log::{info, warn};
+use maa_framework::common::Rect;
+use maa_framework::custom::FnRecognition;
+use maa_framework::resource::Resource;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+
+/// 识别区域 [x, y, width, height]
+pub(crate) type Roi = [i32; 4];
+
+/// 识别器实现：参数为 custom_recognition_param、节点名、截图与 ROI，命中时返回命中框与 detail
+type RecognizerFn = fn(&str, &str, &RgbaImage, Roi) -> Option<(Roi, Value)>;
+
+/// 将 ROI 限制在截图范围内，宽高为 0 时表示整张截图
+fn clamp_roi(image: &RgbaImage, roi: Roi) -> Roi {
+    let (width, height) = (image.width() as i32, image.height() as i32);
+    let [x, y, w, h] = roi;
+    if w <= 0 || h <= 0 {
+        return [0, 0, width, height];
+    }
+    let x = x.clamp(0, width);
+    let y = y.clamp(0, height);
+    [x, y, w.min(width - x), h.min(height - y)]
+}
+
+/// ROI 区域的灰度值
+fn gray_pixels(image: &RgbaImage, roi: Roi) -> Vec<u8> {
+    let [x, y, w, h] = roi;
+    let mut pixels = Vec::with_capacity((w.max(0) * h.max(0)) as usize);
+    for py in y..y + h {
+        for px in x..x + w {
+            let [r, g, b, _] = image.get_pixel(px as u32, py as u32).0;
+            pixels.push(((r as u32 * 299 + g as u32 * 587 + b as u32 * 114) / 1000) as u8);
+        }
+    }
+    pixels
+}
+
+// ============================================================================
+// MXU_PIXELDIFF Custom Recognition
+// ============================================================================
+
+/// MXU_PIXELDIFF 识别器名称常量
+const MXU_PIXELDIFF_RECOGNITION: &str = "MXU_PIXELDIFF_RECOGNITION";
+
+/// 最多保留的上一帧画面数
+const MAX_PIXELDIFF_FRAMES: usize = 64;
+
+/// 各节点（或 key）上次识别时的 ROI 画面：(ROI, 灰度值)
+static PIXELDIFF_FRAMES: LazyLock<Mutex<HashMap<String, (Roi, Vec<u8>)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// MXU_PIXELDIFF custom recognition 回调函数
+/// 从 custom_recognition_param 中读取 threshold（平均灰度差，默认 8）、expect（changed / stable，默认 changed）
+/// 与 key（比较基准的名称，默认为节点名）。首次识别只记录画面，不命中
+fn mxu_pixeldiff_recognition_fn(
+    param_str: &str,
+    node_name: &str,
+    image: &RgbaImage,
+    roi: Roi,
+) -> Option<(Roi, Value)> {
+    let json: Value = serde_json::from_str(param_str).unwrap_or(Value::Null);
+    let threshold = json
+        .get("threshold")
+        .and_then(|v| v.as_f64())
+        .unwrap_or(8.0);
+    let expect_stable = json.get("expect").and_then(|v| v.as_str()) == Some("stable");
+    let key = json
+        .get("key")
+        .and_then(|v| v.as_str())
+        .filter(|k| !k.is_empty())
+        .unwrap_or(node_name)
+        .to_string();
+
+    let roi = clamp_roi(image, roi);
+    let pixels = gray_pixels(image, roi);
+
+    let mut frames = PIXELDIFF_FRAMES.lock().ok()?;
+    if frames.len() >= MAX_PIXELDIFF_FRAMES && !frames.contains_key(&key) {
+        frames.clear();
+    }
+    let previous = frames.insert(key.clone(), (roi, pixels.clone()));
+    let Some((previous_roi, previous_pixels)) = previous else {
+        info!("[MXU_PIXELDIFF] First frame for {}, recorded", key);
+        return None;
+    };
+    if previous_roi != roi || pixels.is_empty() {
+        // ROI 变化后无法比较，以本次画面作为新的基准
+        return None;
+    }
+
+    let diff = pixels
+        .iter()
+        .zip(&previous_pixels)
+        .map(|(a, b)| a.abs_diff(*b) as u64)
+        .sum::<u64>() as f64
+        / pixels.len() as f64;
+    let changed = diff > threshold;
+    info!(
+        "[MXU_PIXELDIFF] {}: mean diff {:.2} (threshold {}), changed: {}",
+        key, diff, threshold, changed
+    );
+    (changed != expect_stable).then(|| (roi, json!({ "diff": diff, "changed": changed })))
+}
+
+// ============================================================================
+// MXU_QRCODE Custom Recognition
+// ============================================================================
+
+/// MXU_QRCODE 识别器名称常量
+const MXU_QRCODE_RECOGNITION: &str = "MXU_QRCODE_RECOGNITION";
+
+/// 识别 ROI 中的二维码，返回各二维码的内容与外接矩形（坐标为整张截图中的坐标）
+pub(crate) fn decode_qr_codes(image: &RgbaImage, roi: Roi) -> Vec<(String, Roi)> {
+    let roi = clamp_roi(image, roi);
+    let [x, y, w, h] = roi;
+    if w <= 0 || h <= 0 {
+        return Vec::new();
+    }
+    let pixels = gray_pixels(image, roi);
+    let mut prepared =
+        rqrr::PreparedImage::prepare_from_greyscale(w as usize, h as usize, |px, py| {
+            pixels[py * w as usize + px]
+        });
+    prepared
+        .detect_grids()
+        .into_iter()
+        .filter_map(|grid| {
+            let bounds = grid.bounds;
+            let (_, content) = grid
+                .decode()
+                .map_err(|e| warn!("[MXU_QRCODE] Failed to decode QR code: {:?}", e))
+                .ok()?;
+            let min_x = bounds.iter().map(|p| p.x).min()?;
+            let min_y = bounds.iter().map(|p| p.y).min()?;
+            let max_x = bounds.iter().map(|p| p.x).max()?;
+            let max_y = bounds.iter().map(|p| p.y).max()?;
+            Some((
+                content,
+                [x + min_x, y + min_y, max_x - min_x, max_y - min_y],
+            ))
+        })
+        .collect()
+}
+
+/// MXU_QRCODE custom recognition 回调函数
+/// 从 custom_recognition_param 中读取 expected（正则，可选），命中第一个内容匹配的二维码
+fn mxu_qrcode_recognition_fn(
+    param_str: &str,
+    _node_name: &str,
+    image: &RgbaImage,
+    roi: Roi,
+) -> Option<(Roi, Value)> {
+    let json: Value = serde_json::from_str(param_str).unwrap_or(Value::Null);
+    let expected = match json.get("expected").and_then(|v| v.as_str()) {
+        Some(pattern) => match regex::Regex::new(pattern) {
+            Ok(re) => Some(re),
+            Err(e) => {
+                warn!("[MXU_QRCODE] Invalid expected pattern {}: {}", pattern, e);
+                return None;
+            }
+        },
+        None => None,
+    };
+
+    let codes = decode_qr_codes(image, roi);
+    info!("[MXU_QRCODE] Found {} QR code(s)", codes.len());
+    codes
+        .into_iter()
+        .find(|(content, _)| expected.as_ref().is_none_or(|re| re.is_match(content)))
+        .map(|(content, rect)| (rect, json!({ "content": content })))
+}
+
+// ============================================================================
+// 注册入口
+// ============================================================================
+
+/// 全部 MXU 内置识别器
+const RECOGNIZERS: &[(&str, RecognizerFn)] = &[
+    (MXU_PIXELDIFF_RECOGNITION, mxu_pixeldiff_recognition_fn),
+    (MXU_QRCODE_RECOGNITION, mxu_qrcode_recognition_fn),
+];
+
+/// 内置识别器名称（供 get_capabilities 使用）
+pub fn recognizer_names() -> Vec<String> {
+    RECOGNIZERS
+        .iter()
+        .map(|&(name, _)| name.to_string())
+        .collect()
+}
+
+/// 为资源注册所有 MXU 内置 custom recognitions
+/// 在资源创建后调用此函数
+pub fn register_all_mxu_recognizers(resource: &Resource) -> Result<(), String> {
+    let mut failed_count = 0;
+
+    for &(name, recognizer) in RECOGNIZERS {
+        let wrapper = move |_ctx: &maa_framework::context::Context,
+                            args: &maa_framework::custom::RecognitionArgs|
+              -> Option<(Rect, String)> {
+            let image = args
+                .image
+                .to_vec()
+                .and_then(|data| image::load_from_memory(&data).ok())
+                .map(|img| img.to_rgba8())?;
+            let roi = [args.roi.x, args.roi.y, args.roi.width, args.roi.height];
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                recognizer(args.param, args.node_name, &image, roi)
+            }))
+            .unwrap_or_else(|_| {
+                log::error!("[MXU] Custom recognition {} panicked", name);
+                None
+            });
+            result.map(|([x, y, width, height], detail)| {
+                (
+                    Rect {
+                        x,
+                        y,
+                        width,
+                        height,
+                    },
+                    detail.to_string(),
+                )
+            })
+        };
+
+        if let Err(e) =
+            resource.register_custom_recognition(name, Box::new(FnRecognition::new(wrapper)))
+        {
+            warn!("[MXU] Failed to register {}: {:?}", name, e);
+            failed_count += 1;
+        } else {
+            info!("[MXU] Custom recognition {} registered successfully", name);
+        }
+    }
+
+    if failed_count > 0 {
+        warn!(
+            "[MXU] Failed to register {} custom recognitions, continuing anyway",
+            failed_count
+        );
+    }
+
+    Ok(())
+}
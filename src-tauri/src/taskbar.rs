@@ -0,0 +1,228 @@
+//! Windows 任务栏进度
+//!
+//! 通过 ITaskbarList3 在任务栏按钮上显示下载与任务运行进度，以及错误（红色）、暂停（黄色）状态。
+//! 下载、任务队列、Agent 等模块按来源设置各自的进度指示，多个来源合并显示：
+//! 状态取最严重者（错误 > 暂停 > 正常 > 不确定），进度取各来源的平均值。
+//! COM 调用在独立线程上执行，设置进度的调用方可能持有其他锁，因此只更新状态并通知该线程
+
+use std::collections::BTreeMap;
+use std::sync::{mpsc, Mutex, OnceLock};
+
+use tauri::AppHandle;
+
+/// 任务栏进度状态（按严重程度排序）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TaskbarState {
+    /// 不确定进度（滚动动画）
+    Indeterminate,
+    Normal,
+    /// 暂停（黄色）
+    Paused,
+    /// 错误（红色）
+    Error,
+}
+
+/// 单个来源的进度指示
+#[derive(Debug, Clone, Copy)]
+struct Indicator {
+    state: TaskbarState,
+    /// (已完成, 总量)
+    progress: Option<(u64, u64)>,
+}
+
+/// 各来源当前的进度指示
+static INDICATORS: Mutex<BTreeMap<String, Indicator>> = Mutex::new(BTreeMap::new());
+
+/// 通知任务栏线程刷新
+static NOTIFY: OnceLock<mpsc::Sender<()>> = OnceLock::new();
+
+/// 合并后每个来源的进度刻度
+const PROGRESS_SCALE: u64 = 1000;
+
+fn notify() {
+    if let Some(tx) = NOTIFY.get() {
+        let _ = tx.send(());
+    }
+}
+
+/// 设置来源的进度指示
+pub fn set(source: &str, state: TaskbarState, progress: Option<(u64, u64)>) {
+    if let Ok(mut indicators) = INDICATORS.lock() {
+        indicators.insert(source.to_string(), Indicator { state, progress });
+    }
+    notify();
+}
+
+/// 设置来源的确定进度
+pub fn set_progress(source: &str, completed: u64, total: u64) {
+    if total == 0 {
+        set(source, TaskbarState::Indeterminate, None);
+    } else {
+        set(
+            source,
+            TaskbarState::Normal,
+            Some((completed.min(total), total)),
+        );
+    }
+}
+
+/// 清除来源的进度指示
+pub fn clear(source: &str) {
+    let removed = INDICATORS
+        .lock()
+        .is_ok_and(|mut indicators| indicators.remove(source).is_some());
+    if removed {
+        notify();
+    }
+}
+
+/// 清除所有错误状态（用户已查看主窗口）
+pub fn clear_errors() {
+    let removed = INDICATORS.lock().is_ok_and(|mut indicators| {
+        let before = indicators.len();
+        indicators.retain(|_, i| i.state != TaskbarState::Error);
+        indicators.len() != before
+    });
+    if removed {
+        notify();
+    }
+}
+
+/// 作用域内的进度指示，离开作用域时清除（用于有多处提前返回的流程）
+pub struct ProgressGuard(String);
+
+impl ProgressGuard {
+    pub fn new(source: impl Into<String>) -> Self {
+        let source = source.into();
+        set(&source, TaskbarState::Indeterminate, None);
+        Self(source)
+    }
+
+    pub fn set_progress(&self, completed: u64, total: u64) {
+        set_progress(&self.0, completed, total);
+    }
+}
+
+impl Drop for ProgressGuard {
+    fn drop(&mut self) {
+        clear(&self.0);
+    }
+}
+
+/// 合并所有来源，返回要显示的状态与进度；无来源时返回 None
+fn combined() -> Option<(TaskbarState, Option<(u64, u64)>)> {
+    let indicators = INDICATORS.lock().ok()?;
+    let state = indicators.values().map(|i| i.state).max()?;
+
+    let (mut completed, mut total) = (0, 0);
+    for (done, all) in indicators.values().filter_map(|i| i.progress) {
+        completed += done * PROGRESS_SCALE / all.max(1);
+        total += PROGRESS_SCALE;
+    }
+    let progress = if total > 0 {
+        Some((completed, total))
+    } else if state == TaskbarState::Indeterminate {
+        None
+    } else {
+        // 错误与暂停状态需要进度值才能显示颜色，没有进度时显示满格
+        Some((1, 1))
+    };
+    Some((state, progress))
+}
+
+/// 启动任务栏刷新线程
+pub fn spawn_taskbar(app: AppHandle) {
+    #[cfg(windows)]
+    {
+        use tauri::Manager;
+
+        let (tx, rx) = mpsc::channel::<()>();
+        if NOTIFY.set(tx).is_err() {
+            return;
+        }
+        std::thread::spawn(move || {
+            let Some(taskbar) = platform::Taskbar::new() else {
+                log::warn!("[taskbar] ITaskbarList3 unavailable");
+                return;
+            };
+            while rx.recv().is_ok() {
+                // 合并短时间内的多次通知
+                while rx.try_recv().is_ok() {}
+                let Some(hwnd) = app
+                    .get_webview_window("main")
+                    .and_then(|w| w.hwnd().ok())
+                    .map(|h| h.0 as isize)
+                else {
+                    continue;
+                };
+                if let Err(e) = taskbar.apply(hwnd, combined()) {
+                    log::debug!("[taskbar] Failed to update taskbar progress: {}", e);
+                }
+            }
+        });
+    }
+
+    #[cfg(not(windows))]
+    {
+        let _ = app;
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::TaskbarState;
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CLSCTX_INPROC_SERVER, COINIT_APARTMENTTHREADED,
+    };
+    use windows::Win32::UI::Shell::{
+        ITaskbarList3, TaskbarList, TBPF_ERROR, TBPF_INDETERMINATE, TBPF_NOPROGRESS, TBPF_NORMAL,
+        TBPF_PAUSED,
+    };
+
+    pub struct Taskbar(ITaskbarList3);
+
+    impl Taskbar {
+        /// 在当前线程初始化 COM 并创建 ITaskbarList3
+        pub fn new() -> Option<Self> {
+            unsafe {
+                let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+                let list: ITaskbarList3 =
+                    CoCreateInstance(&TaskbarList, None, CLSCTX_INPROC_SERVER).ok()?;
+                list.HrInit().ok()?;
+                Some(Self(list))
+            }
+        }
+
+        pub fn apply(
+            &self,
+            hwnd: isize,
+            display: Option<(TaskbarState, Option<(u64, u64)>)>,
+        ) -> Result<(), String> {
+            let hwnd = HWND(hwnd as *mut core::ffi::c_void);
+            let (flag, progress) = match display {
+                None => (TBPF_NOPROGRESS, None),
+                Some((state, progress)) => (
+                    match state {
+                        TaskbarState::Indeterminate => TBPF_INDETERMINATE,
+                        TaskbarState::Normal => TBPF_NORMAL,
+                        TaskbarState::Paused => TBPF_PAUSED,
+                        TaskbarState::Error => TBPF_ERROR,
+                    },
+                    progress,
+                ),
+            };
+            unsafe {
+                // 设置进度值会把不确定状态切换为正常，因此先设置进度再设置状态
+                if let Some((completed, total)) = progress {
+                    self.0
+                        .SetProgressValue(hwnd, completed, total)
+                        .map_err(|e| e.to_string())?;
+                }
+                self.0
+                    .SetProgressState(hwnd, flag)
+                    .map_err(|e| e.to_string())
+            }
+        }
+    }
+}
@@ -1,10 +1,16 @@
+use crate::commands::task_queue;
+use crate::commands::types::{
+    MaaState, ProfileSummary, QueueItemStatus, QueueStatus, TrayIconStyle, TrayStatus,
+};
+use std::path::{Path, PathBuf};
 use std::sync::{
     atomic::{AtomicBool, Ordering},
-    Mutex, OnceLock,
+    Arc, Mutex, OnceLock,
 };
+use std::time::{Duration, Instant};
 use tauri::{
     image::Image,
-    menu::{Menu, MenuItem},
+    menu::{IsMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu},
     tray::{MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent},
     AppHandle, Emitter, Manager, Wry,
 };
@@ -15,6 +21,64 @@ static MINIMIZE_TO_TRAY: AtomicBool = AtomicBool::new(false);
 /// 全局托盘图标引用，用于动态更新图标
 static TRAY_ICON: OnceLock<Mutex<Option<TrayIcon>>> = OnceLock::new();
 
+/// 未加角标的基础图标 (RGBA, 宽, 高)
+static BASE_ICON: Mutex<Option<(Vec<u8>, u32, u32)>> = Mutex::new(None);
+
+/// 当前自定义托盘图标路径（用于按任务栏主题查找明暗变体）
+static ICON_SOURCE: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// 当前托盘状态与角标风格
+static ICON_STATE: Mutex<(TrayStatus, TrayIconStyle)> =
+    Mutex::new((TrayStatus::Idle, TrayIconStyle::Color));
+
+/// 需要动态更新的菜单项
+struct TrayMenuItems {
+    status: MenuItem<Wry>,
+    start: MenuItem<Wry>,
+    stop: MenuItem<Wry>,
+    stop_after: MenuItem<Wry>,
+    profiles: Submenu<Wry>,
+    /// 构建菜单时的配置档案列表（变化时重建菜单）
+    profile_list: Vec<(String, String)>,
+}
+
+/// 当前托盘菜单项
+static MENU_ITEMS: Mutex<Option<TrayMenuItems>> = Mutex::new(None);
+
+/// 配置档案列表是否有变化（由档案命令标记，刷新线程重建菜单）
+static PROFILES_DIRTY: AtomicBool = AtomicBool::new(true);
+
+/// 是否有待处理的菜单刷新请求（队列状态变化时设置）
+static MENU_REFRESH_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// 菜单状态定时刷新间隔与刷新请求的检查间隔
+const MENU_REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+const MENU_REQUEST_POLL: Duration = Duration::from_millis(250);
+
+/// 最近一次队列是否失败（打开主窗口或开始新队列后清除）
+static LAST_RUN_FAILED: AtomicBool = AtomicBool::new(false);
+
+/// 暂存区中是否有已下载的更新包
+static UPDATE_AVAILABLE: AtomicBool = AtomicBool::new(false);
+
+/// 上次根据后端状态推导出的托盘状态，变化时才更新图标，
+/// 因此前端通过 set_tray_status 设置的状态会保持到下一次状态变化
+static AUTO_STATUS: Mutex<Option<TrayStatus>> = Mutex::new(None);
+
+/// 运行中角标的动画帧（true 为缩小帧）
+static PULSE_FRAME: AtomicBool = AtomicBool::new(false);
+
+/// 运行中角标动画的帧间隔与更新包检查间隔
+const ANIMATION_FRAME_INTERVAL: Duration = Duration::from_millis(500);
+const UPDATE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 配置档案菜单项 ID 前缀
+const PROFILE_ITEM_PREFIX: &str = "profile:";
+
+/// “当前任务后停止”菜单项的默认标签与请求后的标签
+const STOP_AFTER_LABEL: &str = "当前任务后停止";
+const STOP_AFTER_PENDING_LABEL: &str = "将于当前任务后停止";
+
 /// 设置最小化到托盘选项
 pub fn set_minimize_to_tray(enabled: bool) {
     MINIMIZE_TO_TRAY.store(enabled, Ordering::SeqCst);
@@ -25,15 +89,342 @@ pub fn get_minimize_to_tray() -> bool {
     MINIMIZE_TO_TRAY.load(Ordering::SeqCst)
 }
 
-/// 初始化系统托盘
-pub fn init_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
-    // 创建托盘菜单项
+/// 构建托盘菜单
+fn build_menu(
+    app: &AppHandle,
+    profile_list: Vec<(String, String)>,
+) -> tauri::Result<(Menu<Wry>, TrayMenuItems)> {
+    let status_i = MenuItem::with_id(app, "status", "空闲", false, None::<&str>)?;
     let show_i = MenuItem::with_id(app, "show", "显示主窗口", true, None::<&str>)?;
     let start_i = MenuItem::with_id(app, "start", "开始任务", true, None::<&str>)?;
-    let stop_i = MenuItem::with_id(app, "stop", "停止任务", true, None::<&str>)?;
+    let stop_i = MenuItem::with_id(app, "stop", "停止任务", false, None::<&str>)?;
+    let stop_after_i = MenuItem::with_id(
+        app,
+        "stop_after_current",
+        STOP_AFTER_LABEL,
+        false,
+        None::<&str>,
+    )?;
+    let cancel_post_i = MenuItem::with_id(
+        app,
+        "cancel_post_action",
+        "取消完成后操作",
+        true,
+        None::<&str>,
+    )?;
     let quit_i = MenuItem::with_id(app, "quit", "退出", true, None::<&str>)?;
 
-    let menu = Menu::with_items(app, &[&show_i, &start_i, &stop_i, &quit_i])?;
+    // 配置档案快速启动子菜单
+    let profile_items = profile_list
+        .iter()
+        .map(|(id, name)| {
+            MenuItem::with_id(
+                app,
+                format!("{}{}", PROFILE_ITEM_PREFIX, id),
+                format!("启动 {}", name),
+                true,
+                None::<&str>,
+            )
+        })
+        .collect::<tauri::Result<Vec<_>>>()?;
+    let empty_i = MenuItem::with_id(app, "no_profiles", "暂无配置档案", false, None::<&str>)?;
+    let profile_refs: Vec<&dyn IsMenuItem<Wry>> = if profile_items.is_empty() {
+        vec![&empty_i]
+    } else {
+        profile_items
+            .iter()
+            .map(|i| i as &dyn IsMenuItem<Wry>)
+            .collect()
+    };
+    let profiles_i = Submenu::with_id_and_items(app, "profiles", "配置档案", true, &profile_refs)?;
+
+    let menu = Menu::with_items(
+        app,
+        &[
+            &status_i,
+            &PredefinedMenuItem::separator(app)?,
+            &show_i,
+            &start_i,
+            &profiles_i,
+            &stop_i,
+            &stop_after_i,
+            &cancel_post_i,
+            &PredefinedMenuItem::separator(app)?,
+            &quit_i,
+        ],
+    )?;
+
+    Ok((
+        menu,
+        TrayMenuItems {
+            status: status_i,
+            start: start_i,
+            stop: stop_i,
+            stop_after: stop_after_i,
+            profiles: profiles_i,
+            profile_list,
+        },
+    ))
+}
+
+/// 读取配置档案列表（ID 与名称）
+fn load_profile_list() -> Vec<(String, String)> {
+    crate::commands::profiles::profile_list()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|ProfileSummary { id, name, .. }| (id, name))
+        .collect()
+}
+
+/// 标记配置档案列表已变化，下次刷新时重建菜单
+pub fn mark_profiles_changed() {
+    PROFILES_DIRTY.store(true, Ordering::SeqCst);
+}
+
+/// 汇总的运行状态
+struct RunState {
+    /// 是否有任务在运行（含暂停中的队列）
+    running: bool,
+    /// 所有活动队列均处于暂停或等待空闲
+    paused: bool,
+    /// 状态栏文字（当前任务名与进度）
+    text: String,
+}
+
+/// 汇总运行状态
+fn running_status(state: &MaaState) -> RunState {
+    let snapshots: Vec<_> = state
+        .task_queues
+        .lock()
+        .map(|queues| {
+            queues
+                .values()
+                .filter(|q| q.is_active())
+                .filter_map(|q| q.snapshot())
+                .collect()
+        })
+        .unwrap_or_default();
+    let tasker_running = state.instances.lock().is_ok_and(|instances| {
+        instances
+            .values()
+            .any(|i| i.tasker.as_ref().is_some_and(|t| t.running()))
+    });
+
+    let Some(first) = snapshots.first() else {
+        let text = if tasker_running {
+            "运行中"
+        } else {
+            "空闲"
+        };
+        return RunState {
+            running: tasker_running,
+            paused: false,
+            text: text.to_string(),
+        };
+    };
+    let paused = snapshots
+        .iter()
+        .all(|s| matches!(s.status, QueueStatus::Paused | QueueStatus::WaitingForIdle));
+
+    let total = first.items.len().max(1);
+    let done = first
+        .items
+        .iter()
+        .filter(|i| {
+            !matches!(
+                i.status,
+                QueueItemStatus::Pending | QueueItemStatus::Running
+            )
+        })
+        .count();
+    let current = first
+        .current_index
+        .and_then(|i| first.items.get(i))
+        .map(|i| i.entry.as_str())
+        .unwrap_or("准备中");
+    let mut text = format!("运行中：{}（{}%）", current, done * 100 / total);
+    if snapshots.len() > 1 {
+        text.push_str(&format!(" 等 {} 个实例", snapshots.len()));
+    }
+    RunState {
+        running: true,
+        paused,
+        text,
+    }
+}
+
+/// 根据后端状态推导托盘状态：运行/暂停优先，其次是上次失败，再次是有可用更新
+fn derive_tray_status(run: &RunState) -> TrayStatus {
+    if run.running {
+        if run.paused {
+            TrayStatus::Paused
+        } else {
+            TrayStatus::Running
+        }
+    } else if LAST_RUN_FAILED.load(Ordering::SeqCst) {
+        TrayStatus::Error
+    } else if UPDATE_AVAILABLE.load(Ordering::SeqCst) {
+        TrayStatus::UpdateAvailable
+    } else {
+        TrayStatus::Idle
+    }
+}
+
+/// 推导出的状态变化时更新托盘图标
+fn refresh_tray_status(run: &RunState) {
+    let status = derive_tray_status(run);
+    let Ok(mut last) = AUTO_STATUS.lock() else {
+        return;
+    };
+    if *last == Some(status) {
+        return;
+    }
+    *last = Some(status);
+    drop(last);
+    PULSE_FRAME.store(false, Ordering::SeqCst);
+    if let Err(e) = set_tray_status(status) {
+        log::debug!("Failed to update tray status: {}", e);
+    }
+}
+
+/// 队列开始时清除上次失败标记
+pub fn on_queue_started() {
+    LAST_RUN_FAILED.store(false, Ordering::SeqCst);
+    request_menu_refresh();
+}
+
+/// 队列结束时记录是否失败（中止或有任务失败）
+pub fn on_queue_finished(final_status: QueueStatus, any_item_failed: bool) {
+    let failed = final_status == QueueStatus::Aborted || any_item_failed;
+    LAST_RUN_FAILED.store(failed, Ordering::SeqCst);
+    request_menu_refresh();
+}
+
+/// 刷新托盘菜单：更新状态文字与开始/停止可用状态，配置档案变化时重建菜单
+fn refresh_tray_menu(app: &AppHandle) {
+    let Some(state) = app.try_state::<Arc<MaaState>>() else {
+        return;
+    };
+    let run = running_status(&state);
+    refresh_tray_status(&run);
+    let (running, status_text) = (run.running, run.text);
+
+    if PROFILES_DIRTY.swap(false, Ordering::SeqCst) {
+        let profile_list = load_profile_list();
+        let changed = MENU_ITEMS
+            .lock()
+            .map(|items| {
+                items
+                    .as_ref()
+                    .is_none_or(|i| i.profile_list != profile_list)
+            })
+            .unwrap_or(false);
+        if changed {
+            rebuild_menu(app, profile_list);
+        }
+    }
+
+    let pending = task_queue::is_stop_after_current_pending(&state);
+    let Ok(items) = MENU_ITEMS.lock() else {
+        return;
+    };
+    let Some(items) = items.as_ref() else {
+        return;
+    };
+    let _ = items.status.set_text(&status_text);
+    let _ = items.start.set_enabled(!running);
+    let _ = items.profiles.set_enabled(!running);
+    let _ = items.stop.set_enabled(running);
+    let _ = items.stop_after.set_enabled(running);
+    let _ = items.stop_after.set_text(if pending {
+        STOP_AFTER_PENDING_LABEL
+    } else {
+        STOP_AFTER_LABEL
+    });
+}
+
+/// 重建托盘菜单并替换
+fn rebuild_menu(app: &AppHandle, profile_list: Vec<(String, String)>) {
+    let (menu, items) = match build_menu(app, profile_list) {
+        Ok(built) => built,
+        Err(e) => {
+            log::warn!("Failed to build tray menu: {}", e);
+            return;
+        }
+    };
+    let tray = TRAY_ICON
+        .get()
+        .and_then(|m| m.lock().ok())
+        .and_then(|t| t.clone());
+    if let Some(tray) = tray {
+        if let Err(e) = tray.set_menu(Some(menu)) {
+            log::warn!("Failed to update tray menu: {}", e);
+            return;
+        }
+    }
+    if let Ok(mut current) = MENU_ITEMS.lock() {
+        *current = Some(items);
+    }
+}
+
+/// 请求尽快刷新托盘菜单（调用方可能持有队列锁，由刷新线程异步处理）
+pub fn request_menu_refresh() {
+    MENU_REFRESH_REQUESTED.store(true, Ordering::SeqCst);
+}
+
+/// 运行中时切换角标动画帧
+fn advance_animation() {
+    let running = ICON_STATE.lock().is_ok_and(|s| s.0 == TrayStatus::Running);
+    if !running {
+        return;
+    }
+    PULSE_FRAME.fetch_xor(true, Ordering::SeqCst);
+    let _ = apply_tray_icon();
+}
+
+/// 检查暂存区中是否有已下载的更新包
+fn check_update_available() {
+    let available =
+        crate::commands::update::update_staging_list().is_ok_and(|packages| !packages.is_empty());
+    if UPDATE_AVAILABLE.swap(available, Ordering::SeqCst) != available {
+        request_menu_refresh();
+    }
+}
+
+/// 启动托盘菜单与图标状态刷新线程
+pub fn spawn_tray_menu_refresher(app: AppHandle) {
+    std::thread::spawn(move || {
+        let mut last_refresh = Instant::now();
+        let mut last_frame = Instant::now();
+        let mut last_update_check: Option<Instant> = None;
+        loop {
+            if last_update_check.is_none_or(|t| t.elapsed() >= UPDATE_CHECK_INTERVAL) {
+                check_update_available();
+                last_update_check = Some(Instant::now());
+            }
+            if MENU_REFRESH_REQUESTED.swap(false, Ordering::SeqCst)
+                || last_refresh.elapsed() >= MENU_REFRESH_INTERVAL
+            {
+                refresh_tray_menu(&app);
+                last_refresh = Instant::now();
+            }
+            if last_frame.elapsed() >= ANIMATION_FRAME_INTERVAL {
+                advance_animation();
+                last_frame = Instant::now();
+            }
+            std::thread::sleep(MENU_REQUEST_POLL);
+        }
+    });
+}
+
+/// 初始化系统托盘
+pub fn init_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    let profile_list = load_profile_list();
+    PROFILES_DIRTY.store(false, Ordering::SeqCst);
+    let (menu, items) = build_menu(app, profile_list)?;
+    if let Ok(mut current) = MENU_ITEMS.lock() {
+        *current = Some(items);
+    }
 
     // 获取图标
     let icon = app
@@ -41,6 +432,10 @@ pub fn init_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
         .cloned()
         .unwrap_or_else(|| Image::from_bytes(include_bytes!("../icons/icon.png")).unwrap());
 
+    if let Ok(mut base) = BASE_ICON.lock() {
+        *base = Some((icon.rgba().to_vec(), icon.width(), icon.height()));
+    }
+
     // 创建托盘图标
     let tray = TrayIconBuilder::<Wry>::new()
         .icon(icon)
@@ -53,6 +448,22 @@ pub fn init_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
                 "show" => {
                     show_main_window(app);
                 }
+                "start" | "stop" | "stop_after_current"
+                    if crate::commands::guest_mode::is_guest_mode() =>
+                {
+                    log::info!("Tray action '{}' ignored in guest mode", id);
+                }
+                _ if id.starts_with(PROFILE_ITEM_PREFIX) => {
+                    if crate::commands::guest_mode::is_guest_mode() {
+                        log::info!("Tray action '{}' ignored in guest mode", id);
+                        return;
+                    }
+                    // 发送启动配置档案事件到前端
+                    let profile_id = &id[PROFILE_ITEM_PREFIX.len()..];
+                    if let Some(window) = app.get_webview_window("main") {
+                        let _ = window.emit("tray-start-profile", profile_id);
+                    }
+                }
                 "start" => {
                     // 发送开始任务事件到前端
                     if let Some(window) = app.get_webview_window("main") {
@@ -65,6 +476,19 @@ pub fn init_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
                         let _ = window.emit("tray-stop-tasks", ());
                     }
                 }
+                "stop_after_current" => {
+                    // 再次点击时撤销请求
+                    let state = app.state::<Arc<MaaState>>();
+                    let enabled = !task_queue::is_stop_after_current_pending(&state);
+                    match task_queue::request_stop_after_current(app, &state, None, enabled) {
+                        Ok(0) => log::info!("No running task queue for stop-after-current"),
+                        Ok(_) => {}
+                        Err(e) => log::warn!("Failed to request stop after current: {}", e),
+                    }
+                }
+                "cancel_post_action" => {
+                    crate::commands::post_actions::cancel_countdown(app);
+                }
                 "quit" => {
                     // 真正退出应用
                     app.exit(0);
@@ -96,8 +520,28 @@ pub fn init_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-/// 显示主窗口
+/// 更新“当前任务后停止”菜单项标签
+pub fn set_stop_after_current_label(pending: bool) {
+    let Ok(items) = MENU_ITEMS.lock() else {
+        return;
+    };
+    if let Some(items) = items.as_ref() {
+        let label = if pending {
+            STOP_AFTER_PENDING_LABEL
+        } else {
+            STOP_AFTER_LABEL
+        };
+        if let Err(e) = items.stop_after.set_text(label) {
+            log::warn!("Failed to update tray menu label: {}", e);
+        }
+    }
+}
+
+/// 显示主窗口（用户已查看，清除失败角标）
 fn show_main_window(app: &AppHandle) {
+    if LAST_RUN_FAILED.swap(false, Ordering::SeqCst) {
+        request_menu_refresh();
+    }
     if let Some(window) = app.get_webview_window("main") {
         let _ = window.show();
         let _ = window.unminimize();
@@ -147,14 +591,132 @@ pub fn update_tray_icon(icon_path: &str) -> Result<(), String> {
         return Err("Invalid icon path: must be within application directory".to_string());
     }
 
+    if let Ok(mut source) = ICON_SOURCE.lock() {
+        *source = Some(canonical_path.clone());
+    }
+    load_themed_icon(&canonical_path)?;
+    log::info!("Tray icon updated: {}", icon_path);
+    Ok(())
+}
+
+/// 按当前任务栏主题加载图标（存在 xxx_dark / xxx_light 变体时优先使用），并设为基础图标
+/// xxx_dark 用于深色任务栏，xxx_light 用于浅色任务栏
+fn load_themed_icon(path: &Path) -> Result<(), String> {
+    let themed = is_taskbar_dark().and_then(|dark| themed_variant(path, dark));
+    let icon_path = themed.as_deref().unwrap_or(path);
+
     // 读取图标文件
-    let icon_data = std::fs::read(&canonical_path)
-        .map_err(|e| format!("Failed to read icon file {:?}: {}", canonical_path, e))?;
+    let icon_data = std::fs::read(icon_path)
+        .map_err(|e| format!("Failed to read icon file {:?}: {}", icon_path, e))?;
 
     // 创建图标
     let icon = Image::from_bytes(&icon_data).map_err(|e| format!("Failed to parse icon: {}", e))?;
 
-    // 更新托盘图标
+    // 保存为基础图标，再叠加当前状态角标
+    if let Ok(mut base) = BASE_ICON.lock() {
+        *base = Some((icon.rgba().to_vec(), icon.width(), icon.height()));
+    }
+    apply_tray_icon()
+}
+
+/// 查找图标的明暗变体文件
+fn themed_variant(path: &Path, dark: bool) -> Option<PathBuf> {
+    let stem = path.file_stem()?.to_string_lossy();
+    let suffix = if dark { "dark" } else { "light" };
+    let file_name = match path.extension() {
+        Some(ext) => format!("{}_{}.{}", stem, suffix, ext.to_string_lossy()),
+        None => format!("{}_{}", stem, suffix),
+    };
+    let variant = path.with_file_name(file_name);
+    variant.is_file().then_some(variant)
+}
+
+/// 系统主题变化时重新选择托盘图标变体
+pub fn refresh_tray_theme() {
+    let Some(path) = ICON_SOURCE.lock().ok().and_then(|s| s.clone()) else {
+        return;
+    };
+    if let Err(e) = load_themed_icon(&path) {
+        log::warn!("Failed to refresh tray icon for theme change: {}", e);
+    }
+}
+
+/// 检测任务栏/菜单栏是否为深色，无法检测时返回 None
+#[cfg(windows)]
+fn is_taskbar_dark() -> Option<bool> {
+    use windows::core::PCWSTR;
+    use windows::Win32::System::Registry::*;
+
+    let to_wide = |s: &str| -> Vec<u16> { s.encode_utf16().chain(Some(0)).collect() };
+    let subkey = to_wide(r"Software\Microsoft\Windows\CurrentVersion\Themes\Personalize");
+    let value = to_wide("SystemUsesLightTheme");
+    let mut data: u32 = 0;
+    let mut size = std::mem::size_of::<u32>() as u32;
+
+    let result = unsafe {
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            PCWSTR(subkey.as_ptr()),
+            PCWSTR(value.as_ptr()),
+            RRF_RT_REG_DWORD,
+            None,
+            Some(&mut data as *mut u32 as *mut std::ffi::c_void),
+            Some(&mut size),
+        )
+    };
+    result.is_ok().then_some(data == 0)
+}
+
+/// 检测任务栏/菜单栏是否为深色，无法检测时返回 None
+#[cfg(target_os = "macos")]
+fn is_taskbar_dark() -> Option<bool> {
+    // 浅色模式下该键不存在，命令返回非零
+    let output = std::process::Command::new("defaults")
+        .args(["read", "-g", "AppleInterfaceStyle"])
+        .output()
+        .ok()?;
+    Some(output.status.success() && String::from_utf8_lossy(&output.stdout).trim() == "Dark")
+}
+
+/// 检测任务栏/菜单栏是否为深色，无法检测时返回 None
+#[cfg(not(any(windows, target_os = "macos")))]
+fn is_taskbar_dark() -> Option<bool> {
+    None
+}
+
+/// 设置托盘状态并刷新角标
+pub fn set_tray_status(status: TrayStatus) -> Result<(), String> {
+    ICON_STATE.lock().map_err(|e| e.to_string())?.0 = status;
+    apply_tray_icon()
+}
+
+/// 设置角标风格并刷新图标
+pub fn set_tray_icon_style(style: TrayIconStyle) -> Result<(), String> {
+    ICON_STATE.lock().map_err(|e| e.to_string())?.1 = style;
+    apply_tray_icon()
+}
+
+/// 获取当前角标风格
+pub fn get_tray_icon_style() -> TrayIconStyle {
+    ICON_STATE
+        .lock()
+        .map(|s| s.1)
+        .unwrap_or(TrayIconStyle::Color)
+}
+
+/// 将基础图标与状态角标合成后设置到托盘
+fn apply_tray_icon() -> Result<(), String> {
+    let (mut rgba, width, height) = BASE_ICON
+        .lock()
+        .map_err(|e| e.to_string())?
+        .clone()
+        .ok_or("Tray base icon not initialized")?;
+    let (status, style) = *ICON_STATE.lock().map_err(|e| e.to_string())?;
+
+    let pulse = status == TrayStatus::Running && PULSE_FRAME.load(Ordering::SeqCst);
+    draw_status_badge(&mut rgba, width, height, status, style, pulse);
+    let icon = Image::new_owned(rgba, width, height);
+
     let tray_mutex = TRAY_ICON.get_or_init(|| Mutex::new(None));
     let guard = tray_mutex
         .lock()
@@ -162,14 +724,125 @@ pub fn update_tray_icon(icon_path: &str) -> Result<(), String> {
 
     if let Some(tray) = guard.as_ref() {
         tray.set_icon(Some(icon))
-            .map_err(|e| format!("Failed to set tray icon: {}", e))?;
-        log::info!("Tray icon updated: {}", icon_path);
-        Ok(())
+            .map_err(|e| format!("Failed to set tray icon: {}", e))
     } else {
         Err("Tray icon not initialized".to_string())
     }
 }
 
+/// 角标形状
+#[derive(Clone, Copy)]
+enum BadgeShape {
+    Circle,
+    /// 向右的三角形（运行中）
+    Triangle,
+    /// 方块（暂停）
+    Square,
+    /// 菱形（错误）
+    Diamond,
+}
+
+impl BadgeShape {
+    /// 判断归一化坐标 (x, y ∈ [-1, 1]) 是否在形状内，scale 用于绘制描边
+    fn contains(self, x: f32, y: f32, scale: f32) -> bool {
+        let (x, y) = (x / scale, y / scale);
+        match self {
+            BadgeShape::Circle => x * x + y * y <= 1.0,
+            BadgeShape::Triangle => x >= -0.6 && y.abs() <= 0.8 * (0.9 - x) / 1.5,
+            BadgeShape::Square => x.abs() <= 0.75 && y.abs() <= 0.75,
+            BadgeShape::Diamond => x.abs() + y.abs() <= 1.0,
+        }
+    }
+}
+
+/// 根据状态与风格返回角标的 (形状, 填充色, 描边色)
+fn badge_appearance(
+    status: TrayStatus,
+    style: TrayIconStyle,
+) -> Option<(BadgeShape, [u8; 3], [u8; 3])> {
+    const WHITE: [u8; 3] = [255, 255, 255];
+    const BLACK: [u8; 3] = [0, 0, 0];
+
+    let shape = match status {
+        TrayStatus::Idle => return None,
+        TrayStatus::Running => BadgeShape::Triangle,
+        TrayStatus::Paused => BadgeShape::Square,
+        TrayStatus::Success => BadgeShape::Circle,
+        TrayStatus::Error => BadgeShape::Diamond,
+        TrayStatus::UpdateAvailable => BadgeShape::Circle,
+    };
+
+    Some(match style {
+        TrayIconStyle::Color => {
+            let color = match status {
+                TrayStatus::Running => [33, 150, 243],
+                TrayStatus::Paused => [255, 193, 7],
+                TrayStatus::Success => [76, 175, 80],
+                TrayStatus::UpdateAvailable => [156, 39, 176],
+                _ => [244, 67, 54],
+            };
+            (BadgeShape::Circle, color, WHITE)
+        }
+        // Okabe-Ito 色盲友好配色
+        TrayIconStyle::Shape => {
+            let color = match status {
+                TrayStatus::Running => [0, 114, 178],
+                TrayStatus::Paused => [230, 159, 0],
+                TrayStatus::Success => [0, 158, 115],
+                TrayStatus::UpdateAvailable => [86, 180, 233],
+                _ => [213, 94, 0],
+            };
+            (shape, color, WHITE)
+        }
+        TrayIconStyle::Monochrome => (shape, BLACK, WHITE),
+    })
+}
+
+/// 在图标右下角绘制状态角标（带描边）
+///
+/// 有可用更新时绘制较小的圆点，pulse 为运行中动画的缩小帧
+fn draw_status_badge(
+    rgba: &mut [u8],
+    width: u32,
+    height: u32,
+    status: TrayStatus,
+    style: TrayIconStyle,
+    pulse: bool,
+) {
+    let Some((shape, fill, outline)) = badge_appearance(status, style) else {
+        return;
+    };
+
+    let mut size = 0.22;
+    if status == TrayStatus::UpdateAvailable {
+        size = 0.14;
+    }
+    if pulse {
+        size *= 0.8;
+    }
+    let radius = (width.min(height) as f32 * size).max(2.0);
+    let cx = width as f32 - radius * 1.25;
+    let cy = height as f32 - radius * 1.25;
+
+    for py in 0..height {
+        for px in 0..width {
+            let x = (px as f32 + 0.5 - cx) / radius;
+            let y = (py as f32 + 0.5 - cy) / radius;
+            let color = if shape.contains(x, y, 1.0) {
+                fill
+            } else if shape.contains(x, y, 1.25) {
+                outline
+            } else {
+                continue;
+            };
+            let idx = ((py * width + px) * 4) as usize;
+            if let Some(pixel) = rgba.get_mut(idx..idx + 4) {
+                pixel.copy_from_slice(&[color[0], color[1], color[2], 255]);
+            }
+        }
+    }
+}
+
 /// 更新托盘 tooltip
 pub fn update_tray_tooltip(tooltip: &str) -> Result<(), String> {
     let tray_mutex = TRAY_ICON.get_or_init(|| Mutex::new(None));
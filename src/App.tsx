@@ -18,6 +18,9 @@ import {
   BadPathModal,
 } from '@/components';
 import type { BadPathType } from '@/components';
+import type { DispatchedProfile, DroppedPackage, PackageInstallResult } from '@/types/config';
+import type { QueueStatus } from '@/types/maa';
+import { normalizeAgentConfigs } from '@/types/interface';
 import {
   autoLoadInterface,
   loadConfig,
@@ -38,8 +41,8 @@ import {
 } from '@/services/updateService';
 import { useTranslation } from 'react-i18next';
 import { invoke } from '@tauri-apps/api/core';
-import { register, unregisterAll } from '@tauri-apps/plugin-global-shortcut';
 import { loggers } from '@/utils/logger';
+import { errorMessage as describeError } from '@/utils/mxuError';
 import { useMaaCallbackLogger, useMaaAgentLogger } from '@/utils/useMaaCallbackLogger';
 import { getInterfaceLangKey } from '@/i18n';
 import { applyTheme, resolveThemeMode } from '@/themes';
@@ -62,6 +65,10 @@ type LoadingState = 'loading' | 'success' | 'error';
 
 // 页面过渡动画时长（ms）
 const PAGE_TRANSITION_DURATION = 120;
+// 任务队列的结束状态
+const QUEUE_FINAL_STATUSES: QueueStatus[] = ['completed', 'aborted', 'stopped', 'time_boxed'];
+// 集群分发的档案等待启动结果（含自动连接与资源加载）的最长时间
+const CLUSTER_START_TIMEOUT_MS = 5 * 60 * 1000;
 
 function App() {
   const [loadingState, setLoadingState] = useState<LoadingState>('loading');
@@ -118,6 +125,9 @@ function App() {
     rightPanelCollapsed,
     setRightPanelWidth: _setRightPanelWidth,
     setRightPanelCollapsed: _setRightPanelCollapsed,
+    instances,
+    guestMode,
+    setGuestMode,
   } = useAppStore();
 
   // 带退出动画的设置页面关闭
@@ -664,6 +674,45 @@ function App() {
     };
   }, [theme]);
 
+  // 同步启用的定时策略到后端，用于在定时执行前唤醒睡眠中的电脑
+  useEffect(() => {
+    if (!isTauri()) return;
+
+    const slots = instances.flatMap((inst) =>
+      (inst.schedulePolicies || [])
+        .filter((p) => p.enabled)
+        .map((p) => ({ weekdays: p.weekdays, hours: p.hours })),
+    );
+    invoke('wake_timer_sync_schedule', { slots }).catch((err) =>
+      log.warn('同步定时唤醒计划失败:', err),
+    );
+  }, [instances]);
+
+  // 确认后端心跳，供 WebView 看门狗检测页面无响应与 IPC 延迟统计
+  useEffect(() => {
+    if (!isTauri()) return;
+
+    let unlisten: (() => void) | null = null;
+    let disposed = false;
+
+    import('@tauri-apps/api/event')
+      .then(({ listen }) =>
+        listen<{ seq: number; revision: number }>('mxu://heartbeat', (event) => {
+          invoke('heartbeat_ack', { seq: event.payload.seq }).catch(() => {});
+        }),
+      )
+      .then((fn) => {
+        if (disposed) fn();
+        else unlisten = fn;
+      })
+      .catch((err) => log.warn('注册心跳监听失败:', err));
+
+    return () => {
+      disposed = true;
+      if (unlisten) unlisten();
+    };
+  }, []);
+
   // 回到主界面时，根据状态弹出相应的弹窗
   useEffect(() => {
     if (currentPage === 'main') {
@@ -908,52 +957,202 @@ function App() {
     return () => document.removeEventListener('keydown', handleKeyDown);
   }, [devMode]);
 
-  // 全局快捷键（窗口失焦时也生效）
+  // 全局快捷键（窗口失焦时也生效）：由后端注册，设置变化时同步绑定
   const hotkeys = useAppStore((state) => state.hotkeys);
   useEffect(() => {
-    if (!hotkeys?.globalEnabled) return;
-
-    const startKey = hotkeys.startTasks || 'F10';
-    const stopKey = hotkeys.stopTasks || 'F11';
+    if (!isTauri()) return;
 
-    // Ctrl -> CommandOrControl
-    const toTauriKey = (k: string) => k.replace(/^Ctrl\+/i, 'CommandOrControl+');
+    const startKey = hotkeys?.startTasks || 'F10';
+    const stopKey = hotkeys?.stopTasks || 'F11';
 
-    const GLOBAL_HOTKEY_THROTTLE_MS = 1000;
-    let lastStartTime = 0;
-    const registerKeys = async () => {
+    const syncHotkeys = async () => {
       try {
-        await register(toTauriKey(startKey), () => {
-          const now = Date.now();
-          if (now - lastStartTime < GLOBAL_HOTKEY_THROTTLE_MS) return;
-          lastStartTime = now;
-          document.dispatchEvent(
-            new CustomEvent('mxu-start-tasks', {
-              detail: { source: 'global-hotkey', combo: startKey },
-            }),
-          );
-        });
-        // 避免重复注册相同的键
+        await invoke('hotkeys_bind', { action: 'start_tasks', shortcut: startKey });
+        // 避免重复绑定相同的键
         if (stopKey !== startKey) {
-          await register(toTauriKey(stopKey), () => {
-            document.dispatchEvent(
-              new CustomEvent('mxu-stop-tasks', {
-                detail: { source: 'global-hotkey', combo: stopKey },
-              }),
-            );
-          });
+          await invoke('hotkeys_bind', { action: 'stop_tasks', shortcut: stopKey });
+        }
+        await invoke('hotkeys_set_enabled', { enabled: !!hotkeys?.globalEnabled });
+        if (hotkeys?.globalEnabled) {
+          log.info('全局快捷键已注册:', startKey, stopKey);
         }
-        log.info('全局快捷键已注册:', startKey, stopKey);
       } catch (err) {
         log.error('注册全局快捷键失败:', err);
       }
     };
 
-    registerKeys();
+    syncHotkeys();
+  }, [hotkeys?.globalEnabled, hotkeys?.startTasks, hotkeys?.stopTasks]);
+
+  // 同步只读访客模式状态（启动时读取，之后跟随后端 guest-mode-changed 事件）
+  useEffect(() => {
+    if (!isTauri()) return;
+
+    let unlisten: (() => void) | null = null;
+    let disposed = false;
+
+    invoke<boolean>('guest_mode_get')
+      .then((enabled) => setGuestMode(enabled))
+      .catch((err) => log.warn('读取访客模式状态失败:', err));
+
+    import('@tauri-apps/api/event')
+      .then(({ listen }) =>
+        listen<boolean>('guest-mode-changed', (event) => setGuestMode(event.payload)),
+      )
+      .then((fn) => {
+        if (disposed) fn();
+        else unlisten = fn;
+      })
+      .catch((err) => log.warn('注册访客模式事件监听失败:', err));
+
     return () => {
-      unregisterAll().catch(() => {});
+      disposed = true;
+      if (unlisten) unlisten();
     };
-  }, [hotkeys?.globalEnabled, hotkeys?.startTasks, hotkeys?.stopTasks]);
+  }, [setGuestMode]);
+
+  // mxu:// 链接处理失败时提示用户（链接常由网页触发，后端只记录日志用户看不到）
+  useEffect(() => {
+    if (!isTauri()) return;
+
+    let unlisten: (() => void) | null = null;
+    let disposed = false;
+
+    import('@tauri-apps/api/event')
+      .then(({ listen }) =>
+        listen<string>('deep-link-error', async (event) => {
+          log.warn('链接处理失败:', event.payload);
+          const { message } = await import('@tauri-apps/plugin-dialog');
+          await message(event.payload, { title: t('deepLink.errorTitle'), kind: 'error' });
+        }),
+      )
+      .then((fn) => {
+        if (disposed) fn();
+        else unlisten = fn;
+      })
+      .catch((err) => log.warn('注册链接错误事件监听失败:', err));
+
+    return () => {
+      disposed = true;
+      if (unlisten) unlisten();
+    };
+  }, [t]);
+
+  // 拖放到窗口（或经 mxu:// 链接下载）的安装包：用户确认后安装
+  useEffect(() => {
+    if (!isTauri()) return;
+
+    let unlisten: (() => void) | null = null;
+    let disposed = false;
+
+    import('@tauri-apps/api/event')
+      .then(({ listen }) =>
+        listen<DroppedPackage>('package-dropped', async (event) => {
+          const pkg = event.payload;
+          log.info('收到安装包:', pkg.file_name, pkg.kind);
+          const { ask, message } = await import('@tauri-apps/plugin-dialog');
+          const confirmed = await ask(
+            t('packageDrop.confirm', {
+              kind: t(`packageDrop.kinds.${pkg.kind}`),
+              name: pkg.file_name,
+            }),
+            { title: t('packageDrop.title'), kind: 'info' },
+          );
+          if (!confirmed) return;
+          try {
+            const result = await invoke<PackageInstallResult>('install_dropped_package', {
+              path: pkg.path,
+              kind: pkg.kind,
+            });
+            log.info('安装包已安装:', result);
+            await message(
+              t(result.requires_restart ? 'packageDrop.installedRestart' : 'packageDrop.installed'),
+              { title: t('packageDrop.title'), kind: 'info' },
+            );
+          } catch (err) {
+            log.error('安装包安装失败:', err);
+            await message(describeError(err), {
+              title: t('packageDrop.installFailed'),
+              kind: 'error',
+            });
+          }
+        }),
+      )
+      .then((fn) => {
+        if (disposed) fn();
+        else unlisten = fn;
+      })
+      .catch((err) => log.warn('注册安装包事件监听失败:', err));
+
+    return () => {
+      disposed = true;
+      if (unlisten) unlisten();
+    };
+  }, [t]);
+
+  // 任务以队列模式运行：按 task-queue-update 更新任务状态，队列结束时停止 Agent
+  useEffect(() => {
+    if (!isTauri()) return;
+
+    let unlisten: (() => void) | null = null;
+    let disposed = false;
+
+    maaService
+      .onQueueUpdate((snapshot) => {
+        const { applyQueueSnapshot, projectInterface } = useAppStore.getState();
+        applyQueueSnapshot(snapshot);
+        if (!QUEUE_FINAL_STATUSES.includes(snapshot.status)) return;
+        log.info('任务队列结束:', snapshot.instance_id, snapshot.status);
+        const agentConfigs = normalizeAgentConfigs(projectInterface?.agent);
+        if (agentConfigs && agentConfigs.length > 0) {
+          maaService.stopAgent(snapshot.instance_id).catch((err) => {
+            log.error('停止 Agent 失败:', err);
+          });
+        }
+      })
+      .then((fn) => {
+        if (disposed) fn();
+        else unlisten = fn;
+      })
+      .catch((err) => log.warn('注册任务队列事件监听失败:', err));
+
+    return () => {
+      disposed = true;
+      if (unlisten) unlisten();
+    };
+  }, []);
+
+  // 后端全局快捷键触发的开始/停止任务，复用 Toolbar 的启动/停止逻辑
+  useEffect(() => {
+    if (!isTauri()) return;
+
+    let unlisten: (() => void) | null = null;
+    let disposed = false;
+
+    import('@tauri-apps/api/event')
+      .then(({ listen }) =>
+        listen<string>('hotkey-action', (event) => {
+          const { hotkeys } = useAppStore.getState();
+          const isStart = event.payload === 'start_tasks';
+          const combo = isStart ? hotkeys?.startTasks || 'F10' : hotkeys?.stopTasks || 'F11';
+          document.dispatchEvent(
+            new CustomEvent(isStart ? 'mxu-start-tasks' : 'mxu-stop-tasks', {
+              detail: { source: 'global-hotkey', combo },
+            }),
+          );
+        }),
+      )
+      .then((fn) => {
+        if (disposed) fn();
+        else unlisten = fn;
+      })
+      .catch((err) => log.warn('注册快捷键事件监听失败:', err));
+
+    return () => {
+      disposed = true;
+      if (unlisten) unlisten();
+    };
+  }, []);
 
   // 监听托盘菜单事件（开始/停止任务）
   useEffect(() => {
@@ -961,6 +1160,9 @@ function App() {
 
     let unlistenStart: (() => void) | null = null;
     let unlistenStop: (() => void) | null = null;
+    let unlistenProfile: (() => void) | null = null;
+    let unlistenGroup: (() => void) | null = null;
+    let unlistenCluster: (() => void) | null = null;
 
     const setupTrayListeners = async () => {
       try {
@@ -978,6 +1180,83 @@ function App() {
           document.dispatchEvent(new CustomEvent('mxu-stop-tasks', { detail: { source: 'tray' } }));
         });
 
+        // 托盘配置档案快速启动：切换到与档案同名的实例后开始任务
+        unlistenProfile = await listen<string>('tray-start-profile', async (event) => {
+          try {
+            const profile = await invoke<{ id: string; name: string }>('profile_get', {
+              id: event.payload,
+            });
+            const { instances, setActiveInstance } = useAppStore.getState();
+            const target = instances.find((i) => i.name === profile.name);
+            if (!target) {
+              log.warn('未找到与配置档案同名的实例:', profile.name);
+              return;
+            }
+            log.info('收到托盘启动配置档案事件:', profile.name);
+            setActiveInstance(target.id);
+            document.dispatchEvent(
+              new CustomEvent('mxu-start-tasks', { detail: { source: 'tray' } }),
+            );
+          } catch (err) {
+            log.warn('启动配置档案失败:', err);
+          }
+        });
+
+        // 集群分发：按档案内容（设备、资源、任务）创建实例并开始任务，
+        // 无法启动时通知后端结束分发任务并向主实例回报失败
+        unlistenCluster = await listen<DispatchedProfile>('cluster-run-profile', async (event) => {
+          const profile = event.payload;
+          log.info('收到集群分发的配置档案:', profile.name);
+          let failure: string | null = null;
+          try {
+            useAppStore.getState().applyDispatchedProfile(profile);
+            const started = await new Promise<boolean>((resolve) => {
+              const timer = setTimeout(() => resolve(false), CLUSTER_START_TIMEOUT_MS);
+              document.dispatchEvent(
+                new CustomEvent('mxu-start-tasks', {
+                  detail: {
+                    source: 'cluster',
+                    onResult: (success: boolean) => {
+                      clearTimeout(timer);
+                      resolve(success);
+                    },
+                  },
+                }),
+              );
+            });
+            if (!started) failure = 'failed to start tasks';
+          } catch (err) {
+            failure = String(err);
+          }
+          if (failure) {
+            log.warn('集群分发的配置档案启动失败:', failure);
+            invoke('cluster_node_job_failed', { message: failure }).catch((err) =>
+              log.warn('回报集群任务失败出错:', err),
+            );
+          }
+        });
+
+        // 设备分组批量运行：依次切换到分组内的各实例并开始任务
+        unlistenGroup = await listen<{ profile_id: string; instance_ids: string[] }>(
+          'device-group-run-profile',
+          async (event) => {
+            log.info('收到设备分组运行事件:', event.payload);
+            for (const instanceId of event.payload.instance_ids) {
+              const { instances, setActiveInstance } = useAppStore.getState();
+              if (!instances.some((i) => i.id === instanceId)) {
+                log.warn('设备分组成员实例不存在:', instanceId);
+                continue;
+              }
+              setActiveInstance(instanceId);
+              document.dispatchEvent(
+                new CustomEvent('mxu-start-tasks', { detail: { source: 'device-group' } }),
+              );
+              // 等待当前实例完成启动流程后再切换下一个
+              await new Promise((resolve) => setTimeout(resolve, 1000));
+            }
+          },
+        );
+
         log.info('托盘事件监听已注册');
       } catch (err) {
         log.warn('注册托盘事件监听失败:', err);
@@ -989,6 +1268,9 @@ function App() {
     return () => {
       if (unlistenStart) unlistenStart();
       if (unlistenStop) unlistenStop();
+      if (unlistenProfile) unlistenProfile();
+      if (unlistenGroup) unlistenGroup();
+      if (unlistenCluster) unlistenCluster();
     };
   }, []);
 
@@ -1095,6 +1377,13 @@ function App() {
       {/* 顶部标签栏 */}
       <TabBar />
 
+      {/* 访客模式提示 */}
+      {guestMode && (
+        <div className="px-3 py-1 text-xs text-center bg-warning/10 text-warning">
+          {t('titleBar.guestMode')}
+        </div>
+      )}
+
       {/* 中控台视图 */}
       {dashboardView ? (
         <div
@@ -1106,10 +1395,11 @@ function App() {
       ) : (
         /* 主内容区 */
         <div key="main-view" className="flex-1 flex overflow-hidden">
-          {/* 左侧任务列表区 */}
+          {/* 左侧任务列表区（访客模式下不可编辑） */}
           <div
             className="flex-1 flex flex-col border-r border-border"
             style={{ minWidth: MIN_LEFT_PANEL_WIDTH }}
+            inert={guestMode}
           >
             {/* 任务列表 */}
             <TaskList />
@@ -1158,8 +1448,10 @@ function App() {
                 style={{ gridTemplateRows: sidePanelExpanded ? '1fr' : '0fr' }}
               >
                 <div className="overflow-hidden min-h-0 flex flex-col gap-3">
-                  {/* 连接设置（设备/资源选择） */}
-                  <ConnectionPanel />
+                  {/* 连接设置（设备/资源选择），访客模式下不可编辑 */}
+                  <div inert={guestMode}>
+                    <ConnectionPanel />
+                  </div>
 
                   {/* 实时截图 */}
                   <ScreenshotPanel />
@@ -232,6 +232,7 @@ function InstanceCard({ instanceId, instanceName, isActive, onSelect }: Instance
             taskConfigs.push({
               entry: taskDef.entry,
               pipeline_override: generateTaskPipelineOverride(selectedTask, projectInterface),
+              id: selectedTask.id,
             });
             // MXU 特殊任务的 label 是 MXU i18n key，需要用 t() 翻译
             const taskDisplayName =
@@ -263,6 +264,8 @@ function InstanceCard({ instanceId, instanceName, isActive, onSelect }: Instance
             agentConfigs,
             basePath,
             tcpCompatMode,
+            // 队列模式：支持任务重试、超时、执行条件、钩子与完成后操作
+            { delay_ms: 0 },
           );
 
           log.info(`[${instanceName}] 任务已提交, task_ids:`, taskIds);
@@ -299,7 +302,7 @@ function InstanceCard({ instanceId, instanceName, isActive, onSelect }: Instance
           runningInstanceIdRef.current = instanceId;
           setPendingTaskIds(instanceId, taskIds);
           setCurrentTaskIndex(instanceId, 0);
-          setInstanceCurrentTaskId(instanceId, taskIds[0]);
+          setInstanceCurrentTaskId(instanceId, taskIds[0] ?? null);
           setIsStarting(false);
         } catch (err) {
           log.error(`[${instanceName}] 任务启动异常:`, err);
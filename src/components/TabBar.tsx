@@ -71,6 +71,7 @@ export function TabBar() {
     removeAnimatingTabId,
     startTabCloseAnimation,
     confirmBeforeDelete,
+    guestMode,
   } = useAppStore();
 
   // 使用全局状态控制更新面板显示
@@ -523,7 +524,8 @@ export function TabBar() {
         </button>
         <button
           onClick={() => setCurrentPage('settings')}
-          className="p-2 rounded-md hover:bg-bg-hover transition-colors"
+          disabled={guestMode}
+          className="p-2 rounded-md hover:bg-bg-hover transition-colors disabled:opacity-50 disabled:cursor-not-allowed"
           title={t('titleBar.settings')}
         >
           <Settings className="w-4 h-4 text-text-secondary" />
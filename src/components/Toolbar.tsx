@@ -14,6 +14,7 @@ import { useAppStore } from '@/stores/appStore';
 import { maaService } from '@/services/maaService';
 import clsx from 'clsx';
 import { loggers, generateTaskPipelineOverride, computeResourcePaths } from '@/utils';
+import { errorMessage } from '@/utils/mxuError';
 import { getMxuSpecialTask } from '@/types/specialTasks';
 import type { TaskConfig, ControllerConfig } from '@/types/maa';
 import { normalizeAgentConfigs } from '@/types/interface';
@@ -510,7 +511,7 @@ export function Toolbar({ showAddPanel, onToggleAddPanel }: ToolbarProps) {
             log.error(`实例 ${targetInstance.name}: 前置动作执行失败:`, err);
             addLog(targetId, {
               type: 'error',
-              message: t('action.preActionFailed', { error: String(err) }),
+              message: t('action.preActionFailed', { error: errorMessage(err) }),
             });
             // 前置动作失败不阻止任务执行，继续
           }
@@ -816,6 +817,7 @@ export function Toolbar({ showAddPanel, onToggleAddPanel }: ToolbarProps) {
           taskConfigs.push({
             entry: taskDef.entry,
             pipeline_override: generateTaskPipelineOverride(selectedTask, projectInterface),
+            id: selectedTask.id,
           });
           // 预注册 entry -> taskName 映射，确保回调时能找到任务名
           // MXU 特殊任务的 label 是 MXU i18n key（如 'specialTask.sleep.label'），需要用 t() 翻译
@@ -855,6 +857,8 @@ export function Toolbar({ showAddPanel, onToggleAddPanel }: ToolbarProps) {
           agentConfigs,
           basePath,
           tcpCompatMode,
+          // 队列模式：支持任务重试、超时、执行条件、钩子与完成后操作
+          { delay_ms: 0 },
         );
 
         log.info(`实例 ${targetInstance.name}: 任务已提交, task_ids:`, taskIds);
@@ -895,7 +899,7 @@ export function Toolbar({ showAddPanel, onToggleAddPanel }: ToolbarProps) {
         runningInstanceIdRef.current = targetId;
         setPendingTaskIds(targetId, taskIds);
         setCurrentTaskIndexStore(targetId, 0);
-        setInstanceCurrentTaskId(targetId, taskIds[0]);
+        setInstanceCurrentTaskId(targetId, taskIds[0] ?? null);
 
         return true;
       } catch (err) {
@@ -1167,7 +1171,7 @@ export function Toolbar({ showAddPanel, onToggleAddPanel }: ToolbarProps) {
         }
       } catch (err) {
         log.error('任务启动异常:', err);
-        setAutoConnectError(err instanceof Error ? err.message : String(err));
+        setAutoConnectError(errorMessage(err));
         setAutoConnectPhase('idle');
       } finally {
         setIsStarting(false);
@@ -1180,13 +1184,20 @@ export function Toolbar({ showAddPanel, onToggleAddPanel }: ToolbarProps) {
   // 监听来自 App 的全局快捷键事件：F10 开始任务，F11 结束任务
   useEffect(() => {
     const handleStartTasks = async (evt: Event) => {
-      if (hotkeyStartingRef.current) return;
-      const currentInstance = useAppStore.getState().getActiveInstance();
-      if (!currentInstance) return;
-
+      // onResult：调用方（如集群分发）需要知道是否成功启动
       const detail = (evt as CustomEvent | undefined)?.detail as
-        | { source?: string; combo?: string }
+        | { source?: string; combo?: string; onResult?: (success: boolean) => void }
         | undefined;
+      if (hotkeyStartingRef.current) {
+        detail?.onResult?.(false);
+        return;
+      }
+      const currentInstance = useAppStore.getState().getActiveInstance();
+      if (!currentInstance) {
+        detail?.onResult?.(false);
+        return;
+      }
+
       const combo = detail?.combo || '';
       addLog(currentInstance.id, {
         type: 'info',
@@ -1201,13 +1212,15 @@ export function Toolbar({ showAddPanel, onToggleAddPanel }: ToolbarProps) {
           type: 'error',
           message: t('logs.messages.hotkeyStartFailed'),
         });
+        detail?.onResult?.(false);
         return;
       }
 
       // 直接使用从 store 获取的最新 instance，避免闭包捕获旧的 selectedTasks
       hotkeyStartingRef.current = true;
+      let success = false;
       try {
-        const success = await startTasksForInstance(currentInstance, {
+        success = await startTasksForInstance(currentInstance, {
           onPhaseChange: setAutoConnectPhase,
         });
         addLog(currentInstance.id, {
@@ -1218,6 +1231,7 @@ export function Toolbar({ showAddPanel, onToggleAddPanel }: ToolbarProps) {
         });
       } finally {
         hotkeyStartingRef.current = false;
+        detail?.onResult?.(success);
       }
     };
 
@@ -3,33 +3,11 @@ import { useTranslation } from 'react-i18next';
 import { X, AlertTriangle, Loader2, CheckCircle, XCircle } from 'lucide-react';
 import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
-import { getCacheDir, joinPath } from '@/utils/paths';
 import { loggers } from '@/utils/logger';
+import { errorMessage } from '@/utils/mxuError';
 
 const log = loggers.app;
 
-// 根据架构获取 VC++ 运行库下载地址和文件名
-function getVCRedistInfo(architecture: string): { url: string; filename: string } {
-  switch (architecture) {
-    case 'x86':
-      return {
-        url: 'https://aka.ms/vs/17/release/vc_redist.x86.exe',
-        filename: 'vc_redist.x86.exe',
-      };
-    case 'aarch64':
-      return {
-        url: 'https://aka.ms/vs/17/release/vc_redist.arm64.exe',
-        filename: 'vc_redist.arm64.exe',
-      };
-    case 'x86_64':
-    default:
-      return {
-        url: 'https://aka.ms/vs/17/release/vc_redist.x64.exe',
-        filename: 'vc_redist.x64.exe',
-      };
-  }
-}
-
 interface DownloadProgress {
   downloaded_size: number;
   total_size: number;
@@ -38,6 +16,13 @@ interface DownloadProgress {
   session_id: number;
 }
 
+interface VcredistInstallResult {
+  exit_code: number;
+  reboot_required: boolean;
+  maafw_version: string | null;
+  load_error: string | null;
+}
+
 type Status = 'downloading' | 'download_failed' | 'installing' | 'retrying' | 'success' | 'failed';
 
 interface VCRedistModalProps {
@@ -68,57 +53,39 @@ export function VCRedistModal({ show, onClose }: VCRedistModalProps) {
     return `${(bytesPerSecond / 1024 / 1024).toFixed(1)} MB/s`;
   };
 
-  // 自动执行流程
+  // 自动执行流程：下载、校验、静默安装与重新加载均由后端完成
   const runAutoProcess = useCallback(async () => {
+    let unlisten: (() => void) | null = null;
     try {
-      // 1. 下载
       setStatus('downloading');
       setDownloadProgress(null);
       setError(null);
 
-      // 获取系统架构并选择对应的下载地址
-      const architecture = await invoke<string>('get_arch');
-      const { url: vcredistUrl, filename: vcredistFilename } = getVCRedistInfo(architecture);
-      log.info(`系统架构: ${architecture}, 下载: ${vcredistFilename}`);
-
-      const cacheDir = await getCacheDir();
-      const downloadPath = joinPath(cacheDir, vcredistFilename);
-
-      log.info(`开始下载 VC++ 运行库: ${vcredistUrl} -> ${downloadPath}`);
-
-      const sessionId = await invoke<number>('download_file', {
-        url: vcredistUrl,
-        savePath: downloadPath,
-        totalSize: null,
+      unlisten = await listen<Status>('vcredist-install-status', (event) => {
+        setStatus(event.payload);
       });
 
-      currentSessionId.current = sessionId;
-      log.info('VC++ 运行库下载完成');
-
-      // 2. 运行安装程序并等待
-      setStatus('installing');
-      log.info(`运行安装程序: ${downloadPath}`);
-
-      const exitCode = await invoke<number>('run_and_wait', { filePath: downloadPath });
-      log.info(`安装程序退出，退出码: ${exitCode}`);
-
-      // 3. 重试加载 DLL
-      setStatus('retrying');
-      log.info('尝试重新加载 MaaFramework...');
+      log.info('开始安装 VC++ 运行库');
+      const result = await invoke<VcredistInstallResult>('vcredist_install', {
+        sha256: null,
+        proxyUrl: null,
+      });
+      log.info(`安装程序退出，退出码: ${result.exit_code}`);
 
-      try {
-        const version = await invoke<string>('retry_load_maa_library');
-        log.info(`MaaFramework 加载成功，版本: ${version}`);
+      if (result.maafw_version) {
+        log.info(`MaaFramework 加载成功，版本: ${result.maafw_version}`);
         setStatus('success');
-      } catch (retryErr) {
-        log.warn('重新加载 MaaFramework 失败:', retryErr);
+      } else {
+        log.warn('重新加载 MaaFramework 失败:', result.load_error);
         setStatus('failed');
         setError(t('vcredist.stillFailed', '安装完成，但加载仍然失败。请重启电脑后再试。'));
       }
     } catch (err) {
       log.error('VC++ 运行库安装流程失败:', err);
       setStatus('download_failed');
-      setError(err instanceof Error ? err.message : String(err));
+      setError(errorMessage(err));
+    } finally {
+      unlisten?.();
     }
   }, [t]);
 
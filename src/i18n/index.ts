@@ -1,4 +1,5 @@
 import i18n from 'i18next';
+import { invoke, isTauri } from '@tauri-apps/api/core';
 import { initReactI18next } from 'react-i18next';
 import zhCN from './locales/zh-CN';
 import zhTW from './locales/zh-TW';
@@ -99,10 +100,19 @@ i18n.use(initReactI18next).init({
   },
 });
 
+/** 同步语言到后端，使后端返回的错误信息与界面语言一致 */
+const syncBackendLanguage = (lang: SupportedLanguage) => {
+  if (!isTauri()) return;
+  invoke('i18n_set_language', { language: lang }).catch(() => {});
+};
+
+syncBackendLanguage(i18n.language as SupportedLanguage);
+
 export const setLanguage = (pref: LanguagePreference) => {
   const resolved = resolveLanguagePreference(pref);
   i18n.changeLanguage(resolved);
   localStorage.setItem('mxu-language', pref);
+  syncBackendLanguage(resolved);
 };
 
 export const getCurrentLanguage = (): SupportedLanguage => i18n.language as SupportedLanguage;
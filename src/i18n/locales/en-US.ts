@@ -31,6 +31,7 @@ export default {
     dragToReorder: 'Drag to reorder',
     closeTabConfirmTitle: 'Close Tab',
     closeTabConfirmMessage: 'Are you sure you want to close "{{name}}"?',
+    guestMode: 'Guest mode: view only, settings and tasks cannot be changed',
   },
 
   // Window controls
@@ -790,6 +791,26 @@ export default {
     hint: 'Tip: We recommend extracting to a dedicated folder like "D:\\MaaXXX". Avoid Desktop or Downloads for easier management.',
     exit: 'Exit',
   },
+
+  // mxu:// links
+  deepLink: {
+    errorTitle: 'Failed to Handle Link',
+  },
+
+  // Dropped packages
+  packageDrop: {
+    title: 'Package',
+    confirm: 'Detected {{kind}} "{{name}}". Install it?',
+    kinds: {
+      resource: 'resource package',
+      maa_framework: 'MaaFramework runtime',
+      profile: 'profile',
+    },
+    installed: 'Installation complete',
+    installedRestart: 'Installation complete. Restart MXU to apply.',
+    installFailed: 'Installation Failed',
+  },
+
   // Proxy Settings
   proxy: {
     title: 'Network Proxy',
@@ -30,6 +30,7 @@ export default {
     dragToReorder: 'ドラッグして並べ替え',
     closeTabConfirmTitle: '設定を閉じる',
     closeTabConfirmMessage: '「{{name}}」を閉じてもよろしいですか？',
+    guestMode: 'ゲストモード：閲覧のみ、設定やタスクの変更はできません',
   },
 
   // ウィンドウコントロール
@@ -795,6 +796,26 @@ export default {
     hint: 'ヒント：「D:\\MaaXXX」のような専用フォルダに解凍することをお勧めします。管理しやすくするため、デスクトップやダウンロードフォルダは避けてください。',
     exit: '終了',
   },
+
+  // mxu:// リンク
+  deepLink: {
+    errorTitle: 'リンクの処理に失敗しました',
+  },
+
+  // ドロップされたパッケージ
+  packageDrop: {
+    title: 'パッケージ',
+    confirm: '{{kind}}「{{name}}」を検出しました。インストールしますか？',
+    kinds: {
+      resource: 'リソースパッケージ',
+      maa_framework: 'MaaFramework ランタイム',
+      profile: 'プロファイル',
+    },
+    installed: 'インストールが完了しました',
+    installedRestart: 'インストールが完了しました。MXU を再起動すると反映されます。',
+    installFailed: 'インストールに失敗しました',
+  },
+
   // プロキシ設定
   proxy: {
     title: 'ネットワークプロキシ',
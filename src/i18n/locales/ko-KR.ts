@@ -30,6 +30,7 @@ export default {
     dragToReorder: '드래그하여 순서 변경',
     closeTabConfirmTitle: '탭 닫기',
     closeTabConfirmMessage: '"{{name}}"을(를) 닫으시겠습니까?',
+    guestMode: '게스트 모드: 보기 전용이며 설정이나 작업을 변경할 수 없습니다',
   },
 
   // 창 컨트롤
@@ -788,6 +789,26 @@ export default {
     hint: '팁: "D:\\MaaXXX"와 같은 전용 폴더에 압축을 푸는 것이 좋습니다. 관리하기 쉽도록 바탕화면이나 다운로드 폴더는 피하세요.',
     exit: '종료',
   },
+
+  // mxu:// 링크
+  deepLink: {
+    errorTitle: '링크 처리 실패',
+  },
+
+  // 드롭된 패키지
+  packageDrop: {
+    title: '패키지',
+    confirm: '{{kind}} "{{name}}"을(를) 감지했습니다. 설치하시겠습니까?',
+    kinds: {
+      resource: '리소스 패키지',
+      maa_framework: 'MaaFramework 런타임',
+      profile: '프로필',
+    },
+    installed: '설치 완료',
+    installedRestart: '설치 완료. MXU를 다시 시작하면 적용됩니다.',
+    installFailed: '설치 실패',
+  },
+
   // 프록시 설정
   proxy: {
     title: '네트워크 프록시',
@@ -31,6 +31,7 @@ export default {
     dragToReorder: '拖拽以重新排序',
     closeTabConfirmTitle: '关闭配置',
     closeTabConfirmMessage: '确定要关闭「{{name}}」吗？',
+    guestMode: '访客模式：仅可查看，不能修改设置或启停任务',
   },
 
   // 窗口控制按钮
@@ -786,4 +787,23 @@ export default {
     hint: '小提示：建议解压到一个专门的文件夹，比如「D:\\MaaXXX」，别放桌面或者下载文件夹，那样更方便管理。',
     exit: '退出程序',
   },
+
+  // mxu:// 链接
+  deepLink: {
+    errorTitle: '链接处理失败',
+  },
+
+  // 拖放安装包
+  packageDrop: {
+    title: '安装包',
+    confirm: '检测到{{kind}}「{{name}}」，是否安装？',
+    kinds: {
+      resource: '资源包',
+      maa_framework: 'MaaFramework 运行库',
+      profile: '配置档案',
+    },
+    installed: '安装完成',
+    installedRestart: '安装完成，重启 MXU 后生效',
+    installFailed: '安装失败',
+  },
 };
@@ -30,6 +30,7 @@ export default {
     dragToReorder: '拖動以重新排序',
     closeTabConfirmTitle: '關閉配置',
     closeTabConfirmMessage: '確定要關閉「{{name}}」嗎？',
+    guestMode: '訪客模式：僅可檢視，無法修改設定或啟停任務',
   },
 
   // 視窗控制按钮
@@ -775,6 +776,26 @@ export default {
     hint: '小提示：建議解壓到一個專門的資料夾，比如「D:\\MaaXXX」，别放桌面或者下載資料夾，那樣更方便管理。',
     exit: '退出程式',
   },
+
+  // mxu:// 連結
+  deepLink: {
+    errorTitle: '連結處理失敗',
+  },
+
+  // 拖放安裝包
+  packageDrop: {
+    title: '安裝包',
+    confirm: '偵測到{{kind}}「{{name}}」，是否安裝？',
+    kinds: {
+      resource: '資源包',
+      maa_framework: 'MaaFramework 執行庫',
+      profile: '設定檔',
+    },
+    installed: '安裝完成',
+    installedRestart: '安裝完成，重新啟動 MXU 後生效',
+    installFailed: '安裝失敗',
+  },
+
   // 代理設定
   proxy: {
     title: '網路代理',
@@ -9,16 +9,21 @@ const log = loggers.config;
 // 配置文件子目录
 const CONFIG_DIR = 'config';
 
+// 后端 load_user_config 返回结构
+interface ConfigLoadResult {
+  config: MxuConfig | null;
+  migrated: boolean;
+  from_version: number;
+  to_version: number;
+  backup_path: string | null;
+  changes: string[];
+}
+
 /** 生成配置文件名 */
 function getConfigFileName(projectName?: string): string {
   return projectName ? `mxu-${projectName}.json` : 'mxu.json';
 }
 
-/** 获取配置目录路径（同步版本，用于已知 dataPath 的场景） */
-function getConfigDirSync(dataPath: string): string {
-  return joinPath(dataPath || '.', CONFIG_DIR);
-}
-
 /** 获取配置文件完整路径（同步版本，用于已知 dataPath 的场景） */
 function getConfigPathSync(dataPath: string, projectName?: string): string {
   return joinPath(dataPath || '.', CONFIG_DIR, getConfigFileName(projectName));
@@ -35,6 +40,27 @@ export async function loadConfig(basePath: string, projectName?: string): Promis
 
     log.debug('加载配置, 路径:', configPath);
 
+    // 优先由后端加载并迁移配置，失败时回退到直接读取（兼容 JSONC）
+    try {
+      const { invoke } = await import('@tauri-apps/api/core');
+      const result = await invoke<ConfigLoadResult>('load_user_config', { projectName });
+      if (result.migrated) {
+        log.info(
+          `配置已迁移 v${result.from_version} -> v${result.to_version}`,
+          result.backup_path,
+          result.changes,
+        );
+      } else if (result.changes.length > 0) {
+        log.warn('配置加载提示:', result.changes);
+      }
+      if (result.config) {
+        log.info('配置加载成功');
+        return result.config;
+      }
+    } catch (err) {
+      log.warn('后端加载配置失败，尝试直接读取:', err);
+    }
+
     const { readTextFile, exists } = await import('@tauri-apps/plugin-fs');
 
     if (await exists(configPath)) {
@@ -97,22 +123,14 @@ export async function saveConfig(
     }
   }
 
-  const configDir = getConfigDirSync(basePath);
   const configPath = getConfigPathSync(basePath, projectName);
 
   log.debug('保存配置, 路径:', configPath);
 
+  // 由后端写入配置文件（访客模式下后端会拒绝修改）
   try {
-    const { writeTextFile, mkdir, exists } = await import('@tauri-apps/plugin-fs');
-
-    // 确保 config 目录存在
-    if (!(await exists(configDir))) {
-      log.debug('创建配置目录:', configDir);
-      await mkdir(configDir, { recursive: true });
-    }
-
-    const content = JSON.stringify(config, null, 2);
-    await writeTextFile(configPath, content);
+    const { invoke } = await import('@tauri-apps/api/core');
+    await invoke('save_user_config', { projectName, config });
     log.info('配置保存成功');
     return true;
   } catch (err) {
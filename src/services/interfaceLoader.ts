@@ -43,17 +43,22 @@ async function getDataDir(): Promise<string> {
 }
 
 // ============================================================================
-// Tauri 环境：通过 Rust 读取本地文件
+// Tauri 环境：由后端 project_interface 模块解析
 // ============================================================================
 
 /**
- * 通过 Tauri 命令读取本地文件
- * @param filename 相对于 exe 目录的文件路径
+ * 后端 interface_load 命令的返回值
  */
-async function readLocalFile(filename: string): Promise<string> {
-  return await invoke<string>('read_local_file', { filename });
+interface BackendLoadResult {
+  interface: ProjectInterface;
+  translations: Record<string, Record<string, string>>;
+  warnings: string[];
 }
 
+// ============================================================================
+// 浏览器环境：通过 HTTP 加载（用于纯前端开发）
+// ============================================================================
+
 /**
  * 拼接路径（处理空 basePath 的情况）
  */
@@ -62,51 +67,6 @@ function joinPath(basePath: string, relativePath: string): string {
   return `${basePath}/${relativePath}`;
 }
 
-/**
- * 从本地文件加载 interface.json（Tauri 环境）
- * @param interfacePath interface.json 的路径（相对于 exe 目录）
- */
-async function loadInterfaceFromLocal(interfacePath: string): Promise<ProjectInterface> {
-  const content = await readLocalFile(interfacePath);
-  const pi = parseJsonc<ProjectInterface>(content, interfacePath);
-
-  if (pi.interface_version !== 2) {
-    throw new Error(`不支持的 interface 版本: ${pi.interface_version}，仅支持 version 2`);
-  }
-
-  return pi;
-}
-
-/**
- * 从本地文件加载翻译文件（Tauri 环境）
- * @param pi ProjectInterface 对象
- * @param basePath interface.json 所在目录（相对于 exe 目录）
- */
-async function loadTranslationsFromLocal(
-  pi: ProjectInterface,
-  basePath: string,
-): Promise<Record<string, Record<string, string>>> {
-  const translations: Record<string, Record<string, string>> = {};
-
-  if (!pi.languages) return translations;
-
-  for (const [lang, relativePath] of Object.entries(pi.languages)) {
-    try {
-      const fullPath = joinPath(basePath, relativePath);
-      const langContent = await readLocalFile(fullPath);
-      translations[lang] = parseJsonc<Record<string, string>>(langContent, fullPath);
-    } catch (err) {
-      log.warn(`加载翻译文件失败 [${lang}]:`, err);
-    }
-  }
-
-  return translations;
-}
-
-// ============================================================================
-// 浏览器环境：通过 HTTP 加载（用于纯前端开发）
-// ============================================================================
-
 /**
  * 检查文件是否存在（HTTP 方式）
  */
@@ -169,20 +129,6 @@ async function loadTranslationsFromHttp(
 // Import 文件加载与合并
 // ============================================================================
 
-/**
- * 从本地文件加载可导入的 PI 文件（Tauri 环境）
- * @param importPath 导入文件的路径（相对于 exe 目录）
- */
-async function loadImportFromLocal(importPath: string): Promise<ImportableInterface> {
-  try {
-    const content = await readLocalFile(importPath);
-    return parseJsonc<ImportableInterface>(content, importPath);
-  } catch (err) {
-    log.warn(`加载导入文件失败 [${importPath}]:`, err);
-    return {};
-  }
-}
-
 /**
  * 从 HTTP 路径加载可导入的 PI 文件
  * @param importPath 导入文件的 HTTP 路径
@@ -221,16 +167,11 @@ function mergeImported(pi: ProjectInterface, imported: ImportableInterface): voi
 }
 
 /**
- * 处理 import 字段，加载并合并所有导入的文件
+ * 处理 import 字段，加载并合并所有导入的文件（浏览器环境）
  * @param pi 主 ProjectInterface
  * @param basePath interface.json 所在目录
- * @param useLocal 是否使用本地文件加载（Tauri 环境）
  */
-async function processImports(
-  pi: ProjectInterface,
-  basePath: string,
-  useLocal: boolean,
-): Promise<void> {
+async function processImports(pi: ProjectInterface, basePath: string): Promise<void> {
   if (!pi.import || pi.import.length === 0) {
     return;
   }
@@ -241,9 +182,7 @@ async function processImports(
     const fullPath = joinPath(basePath, importPath);
     log.info(`加载导入文件: ${fullPath}`);
 
-    const imported = useLocal
-      ? await loadImportFromLocal(fullPath)
-      : await loadImportFromHttp(fullPath);
+    const imported = await loadImportFromHttp(fullPath);
 
     mergeImported(pi, imported);
   }
@@ -334,16 +273,12 @@ export async function autoLoadInterface(): Promise<LoadResult> {
     const dataPath = await getDataDir();
     log.info('dataPath (数据目录):', dataPath);
 
-    const pi = await loadInterfaceFromLocal(interfacePath);
-
-    // 处理 import 字段
-    await processImports(pi, relativeBasePath, true);
-
-    // 过滤掉当前平台不支持的控制器
-    filterControllersByPlatform(pi);
-
-    const translations = await loadTranslationsFromLocal(pi, relativeBasePath);
-    return { interface: pi, translations, basePath, dataPath };
+    // 后端负责 JSONC 解析、import 合并、平台过滤、翻译加载与校验
+    const result = await invoke<BackendLoadResult>('interface_load', { path: interfacePath });
+    for (const warning of result.warnings) {
+      log.warn('interface.json 校验:', warning);
+    }
+    return { interface: result.interface, translations: result.translations, basePath, dataPath };
   }
 
   // 浏览器环境：通过 HTTP 加载
@@ -353,7 +288,7 @@ export async function autoLoadInterface(): Promise<LoadResult> {
 
     // 处理 import 字段
     const httpBasePath = relativeBasePath ? `/${relativeBasePath}` : '';
-    await processImports(pi, httpBasePath, false);
+    await processImports(pi, httpBasePath);
 
     // 过滤掉当前平台不支持的控制器
     filterControllersByPlatform(pi);
@@ -12,6 +12,8 @@ import type {
   AgentConfig,
   TaskConfig,
   InstanceRuntimeInfo,
+  QueueOptions,
+  QueueSnapshot,
 } from '@/types/maa';
 import { loggers } from '@/utils/logger';
 import { isTauri } from '@/utils/paths';
@@ -26,6 +28,28 @@ export interface MaaCallbackEvent {
   details: string;
 }
 
+/** 后台模式下后端合并发送的事件（mxu://event-batch） */
+interface BatchedEvent {
+  event: string;
+  payload: unknown;
+}
+
+/** 任务失败原因 */
+export type TaskFailureReason =
+  | { code: 'action_failed'; node: string }
+  | { code: 'next_list_failed'; node: string }
+  | { code: 'node_failed'; node: string }
+  | { code: 'unknown' };
+
+/** 结构化任务进度事件（task-progress，schema version 1） */
+export type TaskProgressEvent = { version: number; timestamp: number } & (
+  | { type: 'RunStarted'; task_id: number; entry: string }
+  | { type: 'NodeRecognized'; task_id: number; reco_id: number; node: string; hit: boolean }
+  | { type: 'NodeActionDone'; task_id: number; action_id: number; node: string; success: boolean }
+  | { type: 'RunCompleted'; task_id: number; entry: string }
+  | { type: 'RunFailed'; task_id: number; entry: string; reason: TaskFailureReason }
+);
+
 /** 回调消息详情（通用字段） */
 export interface MaaCallbackDetails {
   res_id?: number;
@@ -280,11 +304,12 @@ export const maaService = {
   /**
    * 停止任务
    * @param instanceId 实例 ID
+   * @param mode 停止方式：graceful 当前任务结束后停止并执行收尾任务，soft（默认）在下一个检查点中断，hard 立即结束 Agent
    */
-  async stopTask(instanceId: string): Promise<void> {
-    log.info('停止任务, 实例:', instanceId);
+  async stopTask(instanceId: string, mode?: 'graceful' | 'soft' | 'hard'): Promise<void> {
+    log.info('停止任务, 实例:', instanceId, '方式:', mode ?? 'soft');
     if (!isTauri()) return;
-    await invoke('maa_stop_task', { instanceId });
+    await invoke('maa_stop_task', { instanceId, mode });
     log.info('停止任务请求已发送');
   },
 
@@ -359,7 +384,8 @@ export const maaService = {
    * @param agentConfigs Agent 配置列表（可选，支持多个 Agent）
    * @param cwd 工作目录（Agent 子进程的 CWD）
    * @param tcpCompatMode 通信兼容模式（强制使用 TCP）
-   * @returns 任务 ID 列表
+   * @param queueOptions 队列选项，传入时以队列模式执行（任务 ID 通过 task-queue-update 事件获得）
+   * @returns 任务 ID 列表（队列模式下为空）
    */
   async startTasks(
     instanceId: string,
@@ -367,6 +393,7 @@ export const maaService = {
     agentConfigs?: AgentConfig[],
     cwd?: string,
     tcpCompatMode?: boolean,
+    queueOptions?: QueueOptions,
   ): Promise<number[]> {
     log.info('启动任务, 实例:', instanceId, ', 任务数:', tasks.length, ', cwd:', cwd || '.');
     tasks.forEach((task, i) => {
@@ -391,6 +418,7 @@ export const maaService = {
       agentConfigs: agentConfigs && agentConfigs.length > 0 ? agentConfigs : null,
       cwd: cwd || '.',
       tcpCompatMode: tcpCompatMode || false,
+      queueOptions: queueOptions ?? null,
     });
     log.info('任务已提交, taskIds:', taskIds);
     return taskIds;
@@ -427,8 +455,7 @@ export const maaService = {
       return () => {};
     }
 
-    return await listen<MaaCallbackEvent>('maa-callback', (event) => {
-      const { message, details } = event.payload;
+    const handle = ({ message, details }: MaaCallbackEvent) => {
       //   log.debug('MaaCallback:', message, details);
 
       try {
@@ -438,7 +465,56 @@ export const maaService = {
         log.warn('Failed to parse callback details:', details);
         callback(message, {});
       }
+    };
+
+    const unlistenSingle = await listen<MaaCallbackEvent>('maa-callback', (event) =>
+      handle(event.payload),
+    );
+    // 后台模式下后端合并发送的事件
+    const unlistenBatch = await listen<BatchedEvent[]>('mxu://event-batch', (event) => {
+      for (const item of event.payload) {
+        if (item.event === 'maa-callback') handle(item.payload as MaaCallbackEvent);
+      }
+    });
+    return () => {
+      unlistenSingle();
+      unlistenBatch();
+    };
+  },
+
+  /**
+   * 监听任务队列状态变化
+   * @param callback 回调函数，接收队列状态快照
+   * @returns 取消监听的函数
+   */
+  async onQueueUpdate(callback: (snapshot: QueueSnapshot) => void): Promise<UnlistenFn> {
+    if (!isTauri()) {
+      return () => {};
+    }
+    return listen<QueueSnapshot>('task-queue-update', (event) => callback(event.payload));
+  },
+
+  /**
+   * 监听结构化任务进度事件（无需解析原始回调 JSON）
+   * @param callback 回调函数，接收进度事件
+   * @returns 取消监听的函数
+   */
+  async onTaskProgress(callback: (event: TaskProgressEvent) => void): Promise<UnlistenFn> {
+    if (!isTauri()) {
+      return () => {};
+    }
+    const unlistenSingle = await listen<TaskProgressEvent>('task-progress', (event) =>
+      callback(event.payload),
+    );
+    const unlistenBatch = await listen<BatchedEvent[]>('mxu://event-batch', (event) => {
+      for (const item of event.payload) {
+        if (item.event === 'task-progress') callback(item.payload as TaskProgressEvent);
+      }
     });
+    return () => {
+      unlistenSingle();
+      unlistenBatch();
+    };
   },
 
   /**
@@ -645,6 +721,17 @@ export const maaService = {
     }
   },
 
+  /**
+   * 设置 MaaFramework 全局选项（通用透传，可用选项见 maa_list_global_options）
+   * @param key 选项名，如 debug_mode、stdout_level
+   * @param value 选项值
+   */
+  async setGlobalOption(key: string, value: boolean | number | string): Promise<void> {
+    if (!isTauri()) return;
+    log.info('设置全局选项:', key, value);
+    await invoke('maa_set_global_option', { key, value });
+  },
+
   /**
    * Run pre-action
    * @param program 程序路径
@@ -4,6 +4,7 @@
 import type { DownloadProgress, UpdateInfo } from '@/stores/appStore';
 import type { ProxySettings, UpdateChannel } from '@/types/config';
 import { loggers } from '@/utils/logger';
+import { hasErrorCode } from '@/utils/mxuError';
 import { getCacheDir, joinPath } from '@/utils/paths';
 import { invoke } from '@tauri-apps/api/core';
 import { dirname } from '@tauri-apps/api/path';
@@ -889,9 +890,14 @@ export async function getUpdateSavePath(filename?: string): Promise<string> {
 
 // changes.json 结构（增量包标识）
 interface ChangesJson {
+  schema_version: number;
   added: string[];
   deleted: string[];
   modified: string[];
+  // v2 扩展操作
+  renamed: { from: string; to: string }[];
+  deleted_dirs: string[];
+  permissions: { path: string; executable: boolean }[];
 }
 
 export interface InstallUpdateOptions {
@@ -977,6 +983,7 @@ export async function installUpdate(options: InstallUpdateOptions): Promise<bool
         extractDir,
         targetDir,
         deletedFiles: changesJson.deleted,
+        changes: changesJson,
       });
     } else {
       // 全量更新
@@ -1005,6 +1012,12 @@ export async function installUpdate(options: InstallUpdateOptions): Promise<bool
   } catch (error) {
     log.error('更新安装失败:', error);
 
+    // 有任务运行或磁盘空间不足时后端在改动文件前拒绝更新，无需兜底（兜底同样需要空间）
+    if (hasErrorCode(error, 'UpdateConflict') || hasErrorCode(error, 'InsufficientDiskSpace')) {
+      await invoke('cleanup_extract_dir', { extractDir }).catch(() => {});
+      throw error;
+    }
+
     // 兜底逻辑：尝试将新文件解压到 v版本号 文件夹
     try {
       log.info('尝试兜底更新...');
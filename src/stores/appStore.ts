@@ -10,7 +10,7 @@ import {
   resolveThemeMode,
   unregisterCustomAccent,
 } from '@/themes';
-import type { MxuConfig, RecentlyClosedInstance } from '@/types/config';
+import type { DispatchedProfile, MxuConfig, RecentlyClosedInstance } from '@/types/config';
 import {
   defaultMirrorChyanSettings,
   defaultScreenshotFrameRate,
@@ -23,7 +23,7 @@ import type {
   OptionValue,
   SelectedTask,
 } from '@/types/interface';
-import type { ConnectionStatus, TaskStatus } from '@/types/maa';
+import type { ConnectionStatus, QueueItemStatus, QueueStatus, TaskStatus } from '@/types/maa';
 import { getMxuSpecialTask, isMxuSpecialTask, MXU_SPECIAL_TASKS } from '@/types/specialTasks';
 import { loggers } from '@/utils/logger';
 import { findSwitchCase } from '@/utils/optionHelpers';
@@ -34,6 +34,24 @@ import { generateId, initializeAllOptionValues } from './helpers';
 // 从独立模块导入类型和辅助函数
 import type { AppState, LogEntry, TaskRunStatus } from './types';
 
+// 队列项状态对应的任务运行状态
+const QUEUE_ITEM_RUN_STATUS: Record<QueueItemStatus, TaskRunStatus> = {
+  pending: 'pending',
+  running: 'running',
+  succeeded: 'succeeded',
+  failed: 'failed',
+  timed_out: 'failed',
+  skipped: 'idle',
+};
+
+// 队列结束状态对应的实例任务状态（未结束的状态不在表中；手动停止时清空状态）
+const QUEUE_FINAL_TASK_STATUS: Partial<Record<QueueStatus, TaskStatus | null>> = {
+  completed: 'Succeeded',
+  time_boxed: 'Succeeded',
+  aborted: 'Failed',
+  stopped: null,
+};
+
 // 重新导出类型供外部使用
 export type {
   DownloadProgress,
@@ -149,7 +167,19 @@ export const useAppStore = create<AppState>()(
 
     // 当前页面
     currentPage: 'main',
-    setCurrentPage: (page) => set({ currentPage: page }),
+    setCurrentPage: (page) => {
+      // 访客模式下不能进入设置页
+      if (page === 'settings' && get().guestMode) return;
+      set({ currentPage: page });
+    },
+
+    // 只读访客模式
+    guestMode: false,
+    setGuestMode: (enabled) =>
+      set((state) => ({
+        guestMode: enabled,
+        currentPage: enabled && state.currentPage === 'settings' ? 'main' : state.currentPage,
+      })),
 
     // 调试选项（不落盘，每次启动默认关闭）
     saveDraw: false,
@@ -731,6 +761,53 @@ export const useAppStore = create<AppState>()(
       return newId;
     },
 
+    // 集群分发的配置档案：以档案 ID 作为实例 ID，重复分发时覆盖同一实例
+    applyDispatchedProfile: (profile: DispatchedProfile) => {
+      const state = get();
+      const id = profile.id;
+      const existing = state.instances.find((i) => i.id === id);
+      if (existing?.isRunning) return id;
+
+      const controllerName = profile.device?.controllerName;
+      const resourceName = profile.resource ?? undefined;
+      const instance: Instance = {
+        id,
+        name: profile.name,
+        controllerName,
+        resourceName,
+        savedDevice: profile.device?.savedDevice,
+        selectedTasks: (profile.tasks ?? []).map((t) => ({
+          id: generateId(),
+          taskName: t.taskName,
+          customName: t.customName,
+          enabled: t.enabled,
+          optionValues: { ...t.optionValues },
+          expanded: false,
+        })),
+        isRunning: false,
+      };
+
+      const newSelectedController = { ...state.selectedController };
+      const newSelectedResource = { ...state.selectedResource };
+      if (controllerName) {
+        newSelectedController[id] = controllerName;
+      }
+      if (resourceName) {
+        newSelectedResource[id] = resourceName;
+      }
+
+      set({
+        instances: existing
+          ? state.instances.map((i) => (i.id === id ? instance : i))
+          : [...state.instances, instance],
+        activeInstanceId: id,
+        selectedController: newSelectedController,
+        selectedResource: newSelectedResource,
+      });
+
+      return id;
+    },
+
     // 全局 UI 状态
     showAddTaskPanel: false,
     setShowAddTaskPanel: (show) => set({ showAddTaskPanel: show }),
@@ -1524,6 +1601,50 @@ export const useAppStore = create<AppState>()(
         },
       })),
 
+    applyQueueSnapshot: (snapshot) => {
+      const instanceId = snapshot.instance_id;
+      const state = get();
+      if (!state.instances.some((i) => i.id === instanceId)) return;
+
+      const runStatus = { ...state.instanceTaskRunStatus[instanceId] };
+      const mapping = { ...state.maaTaskIdMapping[instanceId] };
+      for (const item of snapshot.items) {
+        runStatus[item.id] = QUEUE_ITEM_RUN_STATUS[item.status];
+        if (item.task_id !== null) {
+          mapping[item.task_id] = item.id;
+        }
+      }
+      const current =
+        snapshot.current_index !== null ? snapshot.items[snapshot.current_index] : undefined;
+
+      const finalStatus = QUEUE_FINAL_TASK_STATUS[snapshot.status];
+      const finished = finalStatus !== undefined;
+      const anyFailed = snapshot.items.some(
+        (i) => i.status === 'failed' || i.status === 'timed_out',
+      );
+
+      set({
+        instanceTaskRunStatus: { ...state.instanceTaskRunStatus, [instanceId]: runStatus },
+        maaTaskIdMapping: { ...state.maaTaskIdMapping, [instanceId]: mapping },
+        instanceCurrentTaskId: {
+          ...state.instanceCurrentTaskId,
+          [instanceId]: finished ? null : (current?.task_id ?? null),
+        },
+        ...(finished && {
+          instances: state.instances.map((i) =>
+            i.id === instanceId ? { ...i, isRunning: false } : i,
+          ),
+          instanceTaskStatus: {
+            ...state.instanceTaskStatus,
+            [instanceId]:
+              finalStatus === 'Succeeded' && anyFailed ? 'Failed' : (finalStatus ?? null),
+          },
+          instancePendingTaskIds: { ...state.instancePendingTaskIds, [instanceId]: [] },
+          instanceCurrentTaskIndex: { ...state.instanceCurrentTaskIndex, [instanceId]: 0 },
+        }),
+      });
+    },
+
     // 定时执行状态
     scheduleExecutions: {},
 
@@ -1701,6 +1822,8 @@ function debouncedSaveConfig() {
   }
   saveTimeout = setTimeout(() => {
     const state = useAppStore.getState();
+    // 访客模式下配置只读
+    if (state.guestMode) return;
     const config = generateConfig();
     const projectName = state.projectInterface?.name;
     saveConfig(state.dataPath, config, projectName);
@@ -15,8 +15,15 @@ import type {
   RecentlyClosedInstance,
   ScreenshotFrameRate,
   HotkeySettings,
+  DispatchedProfile,
 } from '@/types/config';
-import type { ConnectionStatus, TaskStatus, AdbDevice, Win32Window } from '@/types/maa';
+import type {
+  ConnectionStatus,
+  TaskStatus,
+  AdbDevice,
+  Win32Window,
+  QueueSnapshot,
+} from '@/types/maa';
 import type { AccentColor, CustomAccent } from '@/themes';
 
 /** 单个任务的运行状态 */
@@ -107,6 +114,10 @@ export interface AppState {
   currentPage: PageView;
   setCurrentPage: (page: PageView) => void;
 
+  // 只读访客模式（由后端控制，不落盘到前端配置）
+  guestMode: boolean;
+  setGuestMode: (enabled: boolean) => void;
+
   // 调试选项（不落盘，每次启动默认关闭）
   saveDraw: boolean;
   setSaveDraw: (enabled: boolean) => void;
@@ -172,6 +183,9 @@ export interface AppState {
   // 实例右键菜单操作
   duplicateInstance: (instanceId: string) => string;
 
+  // 按集群分发的配置档案创建或覆盖对应实例，返回实例 ID
+  applyDispatchedProfile: (profile: DispatchedProfile) => string;
+
   // 全局 UI 状态
   showAddTaskPanel: boolean;
   setShowAddTaskPanel: (show: boolean) => void;
@@ -393,6 +407,8 @@ export interface AppState {
   setCurrentTaskIndex: (instanceId: string, index: number) => void;
   advanceCurrentTaskIndex: (instanceId: string) => void;
   clearPendingTasks: (instanceId: string) => void;
+  // 按后端任务队列状态（task-queue-update）更新任务运行状态，队列结束时结束运行
+  applyQueueSnapshot: (snapshot: QueueSnapshot) => void;
 
   // 定时执行状态
   scheduleExecutions: Record<string, ScheduleExecutionInfo>;
@@ -31,6 +31,36 @@ export interface SavedDeviceInfo {
   playcoverAddress?: string;
 }
 
+// 集群节点收到的配置档案（后端 Profile，内容字段与元数据平铺）
+export interface DispatchedProfile {
+  id: string;
+  name: string;
+  device?: {
+    controllerName?: string;
+    savedDevice?: SavedDeviceInfo;
+  };
+  resource?: string | null;
+  tasks?: SavedTask[];
+}
+
+// 拖放（或 mxu:// 链接下载）识别出的安装包类型
+export type PackageKind = 'resource' | 'maa_framework' | 'profile';
+
+// package-dropped 事件内容，确认后以相同的 path/kind 调用 install_dropped_package
+export interface DroppedPackage {
+  path: string;
+  file_name: string;
+  kind: PackageKind;
+  size: number;
+}
+
+// 安装包安装结果（配置档案的 target 为新档案 ID）
+export interface PackageInstallResult {
+  kind: PackageKind;
+  target: string;
+  requires_restart: boolean;
+}
+
 // 保存的实例配置
 export interface SavedInstance {
   id: string;
@@ -137,8 +167,13 @@ export interface AppSettings {
 }
 
 // MXU 配置文件完整结构
+// 配置 schema 版本（与后端 config_migration 保持一致）
+export const CONFIG_SCHEMA_VERSION = 1;
+
 export interface MxuConfig {
   version: string;
+  /** 配置 schema 版本，由后端迁移 */
+  schema_version?: number;
   instances: SavedInstance[];
   settings: AppSettings;
   recentlyClosed?: RecentlyClosedInstance[]; // 最近关闭的实例列表（最多30条）
@@ -177,6 +212,7 @@ export const defaultHotkeySettings: HotkeySettings = {
 // 默认配置
 export const defaultConfig: MxuConfig = {
   version: '1.0',
+  schema_version: CONFIG_SCHEMA_VERSION,
   instances: [],
   settings: {
     theme: 'system',
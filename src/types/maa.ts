@@ -49,12 +49,20 @@ export interface GamepadControllerConfig {
   handle: number;
 }
 
+/** 模拟控制器配置：回放目录中的截图，用于无设备时验证资源 */
+export interface MockControllerConfig {
+  type: 'Mock';
+  image_dir: string;
+  record_dir?: string;
+}
+
 /** 控制器配置 */
 export type ControllerConfig =
   | AdbControllerConfig
   | Win32ControllerConfig
   | PlayCoverControllerConfig
-  | GamepadControllerConfig;
+  | GamepadControllerConfig
+  | MockControllerConfig;
 
 /** 连接状态 */
 export type ConnectionStatus = 'Disconnected' | 'Connecting' | 'Connected' | { Failed: string };
@@ -160,4 +168,53 @@ export interface AgentConfig {
 export interface TaskConfig {
   entry: string;
   pipeline_override: string;
+  /** 队列项 ID（使用 SelectedTask.id，用于在队列状态中对应任务） */
+  id?: string;
+}
+
+/** 任务队列选项（传入时后端以队列模式逐个执行任务，支持重试、超时、执行条件与钩子） */
+export interface QueueOptions {
+  /** 任务之间的等待时间（毫秒） */
+  delay_ms?: number;
+  /** 整个运行的最长时间（秒） */
+  max_duration_secs?: number;
+}
+
+/** 队列项状态 */
+export type QueueItemStatus =
+  | 'pending'
+  | 'running'
+  | 'succeeded'
+  | 'failed'
+  | 'skipped'
+  | 'timed_out';
+
+/** 队列整体状态 */
+export type QueueStatus =
+  | 'running'
+  | 'paused'
+  | 'waiting_for_idle'
+  | 'stopping'
+  | 'completed'
+  | 'aborted'
+  | 'stopped'
+  | 'time_boxed';
+
+/** 队列项运行时信息 */
+export interface QueueItemState {
+  id: string;
+  entry: string;
+  status: QueueItemStatus;
+  attempts: number;
+  task_id: number | null;
+}
+
+/** 队列状态快照（task-queue-update 事件载荷） */
+export interface QueueSnapshot {
+  instance_id: string;
+  run_id: string | null;
+  status: QueueStatus;
+  current_index: number | null;
+  stop_after_current: boolean;
+  items: QueueItemState[];
 }
@@ -405,7 +405,7 @@ const MXU_KILLPROC_NAME_OPTION_DEF_INTERNAL: InputOption = {
   },
 };
 
-// MXU_POWER 任务定义
+// MXU_POWER 任务定义（后端经完成后操作的可取消倒计时执行）
 const MXU_POWER_TASK_DEF_INTERNAL: TaskItem = {
   name: MXU_POWER_TASK_NAME,
   label: 'specialTask.power.label',
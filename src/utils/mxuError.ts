@@ -0,0 +1,149 @@
+/**
+ * 后端命令错误
+ * 所有 Tauri 命令失败时抛出 { code, message, details }，按 code 区分错误类型，
+ * message 已按当前语言本地化，可直接展示
+ */
+
+export type MxuErrorCode =
+  | 'InstanceNotFound'
+  | 'ControllerNotConnected'
+  | 'ResourceNotLoaded'
+  | 'TaskerNotCreated'
+  | 'GuestModeReadOnly'
+  | 'ConfigDirCreateFailed'
+  | 'DeviceNotFound'
+  | 'VcredistMissing'
+  | 'UpdateConflict'
+  | 'InsufficientDiskSpace'
+  | 'ChecksumMismatch'
+  | 'WindowsOnly'
+  | 'FileNotInArchive'
+  | 'FileReadFailed'
+  | 'FileWriteFailed'
+  | 'HttpRequestFailed'
+  | 'InvalidExecutable'
+  | 'AppUpdateUnsupported'
+  | 'AppUpdateNotStaged'
+  | 'ProcessLaunchFailed'
+  | 'DataDirNotAbsolute'
+  | 'DataDirInsideCurrent'
+  | 'IntegrityManifestMissing'
+  | 'IntegrityManifestInvalid'
+  | 'IntegrityNoDownloadUrl'
+  | 'VcredistSignatureInvalid'
+  | 'VcredistInstallFailed'
+  | 'MaaFrameworkVersionInvalid'
+  | 'MaaFrameworkVersionInstalled'
+  | 'MaaFrameworkVersionNotFound'
+  | 'AdbNotRunnable'
+  | 'InvalidCoordinates'
+  | 'EmptyInputText'
+  | 'UnknownControllerMethod'
+  | 'NoProbeMethods'
+  | 'ScriptMissingSource'
+  | 'ScriptSyntaxError'
+  | 'ScriptStopped'
+  | 'ScriptTimedOut'
+  | 'ScriptFailed'
+  | 'ScriptingDisabled'
+  | 'Cancelled'
+  | 'InvalidUrl'
+  | 'InvalidPort'
+  | 'LanAddressUnavailable'
+  | 'OpenUrlFailed'
+  | 'EmptyName'
+  | 'InvalidJson'
+  | 'ValueOutOfRange'
+  | 'IntervalNotPositive'
+  | 'InvalidRegion'
+  | 'RegionOutOfBounds'
+  | 'DirectoryNotFound'
+  | 'DirectoryCreateFailed'
+  | 'DirectoryReadFailed'
+  | 'FileDeleteFailed'
+  | 'FileParseFailed'
+  | 'ConfigNotObject'
+  | 'NoRunningTasks'
+  | 'GuestModePinIncorrect'
+  | 'BackupNameInvalid'
+  | 'BackupNotFound'
+  | 'BackupReadFailed'
+  | 'BackupWriteFailed'
+  | 'BackupRestoreCancelled'
+  | 'BackupRestoreFailed'
+  | 'BackupRollbackFailed'
+  | 'SyncNotConfigured'
+  | 'SyncRequestFailed'
+  | 'SyncManifestInvalid'
+  | 'ClusterNodeNotFound'
+  | 'ClusterApiServerRequired'
+  | 'ClusterRoundActive'
+  | 'ClusterNoNodes'
+  | 'HotkeyConflict'
+  | 'PackageNotConfirmed'
+  | 'PackageInvalid'
+  | 'UpdatePackageNotStaged'
+  | 'UpdatePackageInvalid'
+  | 'CommandNotRemotable'
+  | 'InvalidEnvVarName'
+  | 'NothingToImport'
+  | 'CrashUploadDisabled'
+  | 'DeviceGroupNotFound'
+  | 'DuplicateGroupInstance'
+  | 'NoIdleGroupDevices'
+  | 'DigestNotFound'
+  | 'InvalidDeviceIndex'
+  | 'NoImagesInDirectory'
+  | 'OverrideNotObject'
+  | 'RunProgramPathRequired'
+  | 'ProfileNotFound'
+  | 'EmptyNodePatterns'
+  | 'ResourcePackNotFound'
+  | 'NoWatchableResourceDirs'
+  | 'ScrcpyServerNotFound'
+  | 'InvalidTemplateName'
+  | 'InvalidResourceDir'
+  | 'UnknownGlobalOption'
+  | 'GlobalOptionDeprecated'
+  | 'InvalidGlobalOptionValue'
+  | 'WakeTimerUnsupported'
+  | 'ClipboardTextTooLong'
+  | 'ClipboardReadFailed'
+  | 'MaaFrameworkNotInitialized'
+  | 'AgentStartFailed'
+  | 'NoImageData'
+  | 'ElevationFailed'
+  | 'TaskNotPaused'
+  | 'TaskQueueNotRunning'
+  | 'RemoteApiNotRunning'
+  | 'InvalidAccessToken'
+  | 'CrashUploadEndpointMissing'
+  | 'ExePathUnavailable'
+  | 'Internal';
+
+export interface MxuError {
+  code: MxuErrorCode;
+  message: string;
+  details?: unknown;
+}
+
+/** 判断是否为后端返回的结构化错误 */
+export function isMxuError(err: unknown): err is MxuError {
+  return (
+    typeof err === 'object' &&
+    err !== null &&
+    typeof (err as MxuError).code === 'string' &&
+    typeof (err as MxuError).message === 'string'
+  );
+}
+
+/** 判断错误是否为指定错误码 */
+export function hasErrorCode(err: unknown, code: MxuErrorCode): boolean {
+  return isMxuError(err) && err.code === code;
+}
+
+/** 提取可展示的错误信息 */
+export function errorMessage(err: unknown): string {
+  if (err instanceof Error || isMxuError(err)) return err.message;
+  return String(err);
+}
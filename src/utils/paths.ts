@@ -20,6 +20,7 @@ let cachedDataPath: string | null = null;
  * 获取应用数据目录
  * - macOS: ~/Library/Application Support/MXU/
  * - Windows/Linux: exe 所在目录
+ * - 通过 set_data_dir 修改后为重定向的目录（重启后生效）
  *
  * 结果会被缓存，多次调用不会重复请求
  */